@@ -27,7 +27,7 @@ use std::{
 
 use tari_common_types::types::{PrivateKey, PublicKey, Signature};
 use tari_crypto::tari_utilities::ByteArray;
-use tari_dan_common_types::ShardId;
+use tari_dan_common_types::{Epoch, ShardId};
 
 use crate::proto;
 
@@ -68,3 +68,16 @@ impl From<ShardId> for proto::common::ShardId {
         }
     }
 }
+
+// -------------------------------- Epoch -------------------------------- //
+impl From<proto::common::Epoch> for Epoch {
+    fn from(epoch: proto::common::Epoch) -> Self {
+        Epoch(epoch.epoch)
+    }
+}
+
+impl From<Epoch> for proto::common::Epoch {
+    fn from(epoch: Epoch) -> Self {
+        Self { epoch: epoch.as_u64() }
+    }
+}
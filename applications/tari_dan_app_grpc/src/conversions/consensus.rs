@@ -20,22 +20,32 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::convert::{TryFrom, TryInto};
+use std::{
+    convert::{TryFrom, TryInto},
+    time::Duration,
+};
 
 use anyhow::anyhow;
-use tari_common_types::types::PublicKey;
+use tari_common_types::types::{PrivateKey, PublicKey, Signature};
 use tari_comms::types::CommsPublicKey;
 use tari_crypto::tari_utilities::ByteArray;
 use tari_dan_common_types::{
+    AggregatedSignature,
     ObjectPledge,
     QuorumCertificate,
     QuorumDecision,
     ShardPledge,
+    SignerBitmap,
     SubstateState,
     TreeNodeHash,
     ValidatorMetadata,
 };
-use tari_dan_core::models::{vote_message::VoteMessage, HotStuffMessage, HotStuffTreeNode, Node, TariDanPayload};
+use tari_dan_core::{
+    consensus_constants::ConsensusConstants,
+    models::{vote_message::VoteMessage, HotStuffMessage, HotStuffTreeNode, Node, TariDanPayload},
+    services::leader_strategy::LeaderStrategyType,
+};
+use tari_dan_engine::fees::FeeTable;
 use tari_engine_types::substate::{Substate, SubstateAddress};
 
 use crate::proto;
@@ -107,7 +117,8 @@ impl TryFrom<proto::consensus::HotStuffTreeNode> for HotStuffTreeNode<CommsPubli
     type Error = anyhow::Error;
 
     fn try_from(value: proto::consensus::HotStuffTreeNode) -> Result<Self, Self::Error> {
-        Ok(Self::new(
+        let proposer_signature = value.proposer_signature.map(Signature::try_from).transpose()?;
+        let mut node = Self::new(
             value.parent.try_into()?,
             value.shard.try_into()?,
             value.height.into(),
@@ -123,7 +134,12 @@ impl TryFrom<proto::consensus::HotStuffTreeNode> for HotStuffTreeNode<CommsPubli
                 .map(|j| j.try_into())
                 .transpose()?
                 .ok_or_else(|| anyhow!("Justify is required"))?,
-        ))
+            value.state_root.as_slice().try_into()?,
+        );
+        if let Some(signature) = proposer_signature {
+            node.set_proposer_signature(signature);
+        }
+        Ok(node)
     }
 }
 
@@ -141,6 +157,8 @@ impl From<HotStuffTreeNode<CommsPublicKey, TariDanPayload>> for proto::consensus
             epoch: source.epoch().as_u64(),
             proposed_by: source.proposed_by().as_bytes().to_vec(),
             justify: Some(source.justify().clone().into()),
+            state_root: source.state_root().as_slice().to_vec(),
+            proposer_signature: source.proposer_signature().map(Into::into),
         }
     }
 }
@@ -161,7 +179,7 @@ impl From<QuorumCertificate> for proto::consensus::QuorumCertificate {
                 QuorumDecision::Reject(ref reason) => reason.as_u8().into(),
             },
             all_shard_pledges: source.all_shard_pledges().iter().map(|p| p.clone().into()).collect(),
-            validators_metadata: source.validators_metadata().iter().map(|p| p.clone().into()).collect(),
+            aggregated_signature: Some(source.aggregated_signature().clone().into()),
         }
     }
 }
@@ -184,10 +202,38 @@ impl TryFrom<proto::consensus::QuorumCertificate> for QuorumCertificate {
                 .map(|s| s.clone().try_into())
                 .collect::<Result<_, _>>()?,
             value
-                .validators_metadata
+                .aggregated_signature
+                .map(TryFrom::try_from)
+                .transpose()?
+                .ok_or_else(|| anyhow!("QuorumCertificate missing aggregated_signature"))?,
+        ))
+    }
+}
+
+// -------------------------------- AggregatedSignature -------------------------------- //
+
+impl From<AggregatedSignature> for proto::consensus::AggregatedSignature {
+    fn from(value: AggregatedSignature) -> Self {
+        Self {
+            signer_bitmap: value.signers().as_bytes().to_vec(),
+            public_nonces: value.public_nonces().iter().map(|n| n.to_vec()).collect(),
+            signature: value.signature().to_vec(),
+        }
+    }
+}
+
+impl TryFrom<proto::consensus::AggregatedSignature> for AggregatedSignature {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::consensus::AggregatedSignature) -> Result<Self, Self::Error> {
+        Ok(AggregatedSignature::new(
+            SignerBitmap::from_bytes(value.signer_bitmap),
+            value
+                .public_nonces
                 .iter()
-                .map(|v| v.clone().try_into())
+                .map(|n| PublicKey::from_bytes(n))
                 .collect::<Result<_, _>>()?,
+            PrivateKey::from_bytes(&value.signature)?,
         ))
     }
 }
@@ -333,20 +379,27 @@ impl TryFrom<proto::consensus::TariDanPayload> for TariDanPayload {
     type Error = anyhow::Error;
 
     fn try_from(value: proto::consensus::TariDanPayload) -> Result<Self, Self::Error> {
-        Ok(Self::new(
+        let mut transactions = Vec::with_capacity(value.batched_transactions.len() + 1);
+        transactions.push(
             value
                 .transaction
                 .map(|s| s.try_into())
                 .transpose()?
                 .ok_or_else(|| anyhow!("transaction is missing"))?,
-        ))
+        );
+        for transaction in value.batched_transactions {
+            transactions.push(transaction.try_into()?);
+        }
+        Ok(Self::new_batch(transactions))
     }
 }
 
 impl From<TariDanPayload> for proto::consensus::TariDanPayload {
     fn from(source: TariDanPayload) -> Self {
+        let mut transactions = source.into_transactions().into_iter();
         Self {
-            transaction: Some(source.transaction().clone().into()),
+            transaction: transactions.next().map(Into::into),
+            batched_transactions: transactions.map(Into::into).collect(),
         }
     }
 }
@@ -375,3 +428,67 @@ impl TryFrom<proto::transaction::Node> for Node {
         Ok(Self::new(hash, parent, height, is_committed))
     }
 }
+
+// -------------------------------- ConsensusConstants -------------------------------- //
+impl From<ConsensusConstants> for proto::consensus::ConsensusConstants {
+    fn from(value: ConsensusConstants) -> Self {
+        Self {
+            base_layer_confirmations: value.base_layer_confirmations,
+            committee_size: value.committee_size,
+            hotstuff_rounds: value.hotstuff_rounds,
+            pacemaker_timeout_ms: value.pacemaker_timeout.as_millis() as u64,
+            fee_per_runtime_call: value.fee_table.per_runtime_call_cost,
+            fee_per_wasm_point: value.fee_table.per_wasm_point_cost,
+            fee_per_byte_storage: value.fee_table.per_byte_storage_cost,
+            leader_strategy: proto::consensus::LeaderStrategy::from(value.leader_strategy) as i32,
+        }
+    }
+}
+
+impl TryFrom<proto::consensus::ConsensusConstants> for ConsensusConstants {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::consensus::ConsensusConstants) -> Result<Self, Self::Error> {
+        let leader_strategy = proto::consensus::LeaderStrategy::from_i32(value.leader_strategy)
+            .ok_or_else(|| anyhow!("Invalid leader strategy {}", value.leader_strategy))?
+            .try_into()?;
+        Ok(Self {
+            base_layer_confirmations: value.base_layer_confirmations,
+            committee_size: value.committee_size,
+            hotstuff_rounds: value.hotstuff_rounds,
+            pacemaker_timeout: Duration::from_millis(value.pacemaker_timeout_ms),
+            fee_table: FeeTable {
+                per_runtime_call_cost: value.fee_per_runtime_call,
+                per_wasm_point_cost: value.fee_per_wasm_point,
+                per_byte_storage_cost: value.fee_per_byte_storage,
+            },
+            leader_strategy,
+        })
+    }
+}
+
+// -------------------------------- LeaderStrategy -------------------------------- //
+impl From<LeaderStrategyType> for proto::consensus::LeaderStrategy {
+    fn from(value: LeaderStrategyType) -> Self {
+        match value {
+            LeaderStrategyType::AlwaysFirst => Self::AlwaysFirst,
+            LeaderStrategyType::Rotating => Self::Rotating,
+            LeaderStrategyType::PayloadSpecific => Self::PayloadSpecific,
+            LeaderStrategyType::Reputation => Self::Reputation,
+        }
+    }
+}
+
+impl TryFrom<proto::consensus::LeaderStrategy> for LeaderStrategyType {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::consensus::LeaderStrategy) -> Result<Self, Self::Error> {
+        match value {
+            proto::consensus::LeaderStrategy::Unknown => Err(anyhow!("Leader strategy is not set")),
+            proto::consensus::LeaderStrategy::AlwaysFirst => Ok(Self::AlwaysFirst),
+            proto::consensus::LeaderStrategy::Rotating => Ok(Self::Rotating),
+            proto::consensus::LeaderStrategy::PayloadSpecific => Ok(Self::PayloadSpecific),
+            proto::consensus::LeaderStrategy::Reputation => Ok(Self::Reputation),
+        }
+    }
+}
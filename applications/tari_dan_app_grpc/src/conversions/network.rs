@@ -58,6 +58,14 @@ impl From<DanMessage<TariDanPayload, CommsPublicKey>> for proto::network::DanMes
                 )),
                 message_tag,
             },
+            DanMessage::NewTransactionBatch(transactions) => Self {
+                message: Some(proto::network::dan_message::Message::NewTransactionBatch(
+                    proto::network::TransactionBatch {
+                        transactions: transactions.into_iter().map(Into::into).collect(),
+                    },
+                )),
+                message_tag,
+            },
             DanMessage::NetworkAnnounce(announce) => Self {
                 message: Some(proto::network::dan_message::Message::NetworkAnnounce(
                     (*announce).into(),
@@ -87,6 +95,12 @@ impl TryFrom<proto::network::DanMessage> for DanMessage<TariDanPayload, CommsPub
             proto::network::dan_message::Message::NewTransaction(msg) => {
                 Ok(DanMessage::NewTransaction(Box::new(msg.try_into()?)))
             },
+            proto::network::dan_message::Message::NewTransactionBatch(msg) => Ok(DanMessage::NewTransactionBatch(
+                msg.transactions
+                    .into_iter()
+                    .map(TryInto::try_into)
+                    .collect::<Result<_, _>>()?,
+            )),
             proto::network::dan_message::Message::NetworkAnnounce(msg) => {
                 Ok(DanMessage::NetworkAnnounce(Box::new(msg.try_into()?)))
             },
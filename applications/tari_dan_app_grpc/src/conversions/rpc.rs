@@ -3,8 +3,9 @@
 
 use std::convert::{TryFrom, TryInto};
 
-use anyhow;
-use tari_dan_core::models::SubstateShardData;
+use anyhow::anyhow;
+use tari_common_types::types::FixedHash;
+use tari_dan_core::models::{StateTreeProof, SubstateShardData};
 use tari_engine_types::substate::{Substate, SubstateAddress};
 
 use crate::proto;
@@ -40,7 +41,7 @@ impl TryFrom<proto::rpc::VnStateSyncResponse> for SubstateShardData {
                 .created_justify
                 .map(|v| v.try_into())
                 .transpose()?
-                .ok_or_else(|| anyhow::anyhow!("VnStateSyncResponse created_justify is required"))?,
+                .ok_or_else(|| anyhow!("VnStateSyncResponse created_justify is required"))?,
             value.destroyed_justify.map(|v| v.try_into()).transpose()?,
         ))
     }
@@ -73,6 +74,58 @@ impl TryFrom<SubstateShardData> for proto::rpc::VnStateSyncResponse {
                 .as_ref()
                 .map(|v| v.clone().try_into())
                 .transpose()?,
+            state_root: Vec::new(),
+            state_proof: None,
+            certified_node: None,
         })
     }
 }
+
+impl proto::rpc::VnStateSyncResponse {
+    /// Returns the state root that the responding validator node claims the substate is included in. The claimed root
+    /// is not certified, so a substate verified against it is only as trustworthy as the responding validator node.
+    pub fn claimed_state_root(&self) -> Result<FixedHash, anyhow::Error> {
+        Ok(FixedHash::try_from(self.state_root.as_slice())?)
+    }
+
+    /// Converts the response into substate data, after checking that the substate is included in the given state root
+    pub fn into_verified_substate(self, state_root: &FixedHash) -> Result<SubstateShardData, anyhow::Error> {
+        let proof: StateTreeProof = self
+            .state_proof
+            .clone()
+            .ok_or_else(|| anyhow!("VnStateSyncResponse state_proof is required"))?
+            .try_into()?;
+        let substate = SubstateShardData::try_from(self)?;
+        if !proof.verify_substate(state_root, &substate) {
+            return Err(anyhow!(
+                "Substate {} is not included in state root {}",
+                substate.shard_id(),
+                state_root
+            ));
+        }
+        Ok(substate)
+    }
+}
+
+// -------------------------------- StateTreeProof -------------------------------- //
+
+impl From<StateTreeProof> for proto::rpc::StateTreeProof {
+    fn from(value: StateTreeProof) -> Self {
+        Self {
+            siblings: value.siblings().iter().map(|s| s.as_slice().to_vec()).collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::rpc::StateTreeProof> for StateTreeProof {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::rpc::StateTreeProof) -> Result<Self, Self::Error> {
+        let siblings = value
+            .siblings
+            .iter()
+            .map(|s| FixedHash::try_from(s.as_slice()))
+            .collect::<Result<_, _>>()?;
+        Ok(Self::new(siblings))
+    }
+}
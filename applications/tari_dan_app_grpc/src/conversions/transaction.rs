@@ -31,7 +31,14 @@ use tari_crypto::{ristretto::RistrettoComSig, tari_utilities::ByteArray};
 use tari_dan_common_types::ShardId;
 use tari_engine_types::{confidential::ConfidentialClaim, instruction::Instruction};
 use tari_template_lib::{args::Arg, Hash};
-use tari_transaction::{ObjectClaim, SubstateChange, Transaction, TransactionMeta};
+use tari_transaction::{
+    ObjectClaim,
+    SubstateChange,
+    Transaction,
+    TransactionBody,
+    TransactionMeta,
+    TransactionSignature,
+};
 
 use crate::proto;
 
@@ -52,14 +59,20 @@ impl TryFrom<proto::transaction::Transaction> for Transaction {
         let instruction_signature = signature.try_into().map_err(|s| anyhow!("{}", s))?;
         let sender_public_key =
             PublicKey::from_bytes(&request.sender_public_key).map_err(|_| anyhow!("invalid sender_public_key"))?;
+        let co_signatures = request
+            .co_signatures
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, _>>()?;
         let meta = request.meta.map(TryInto::try_into).transpose()?;
-        let transaction = Transaction::new(
-            request.fee,
+        let body = TransactionBody {
+            fee: request.fee,
             instructions,
-            instruction_signature,
-            sender_public_key,
-            meta.ok_or_else(|| anyhow!("meta not provided"))?,
-        );
+            meta: meta.ok_or_else(|| anyhow!("meta not provided"))?,
+            min_epoch: request.min_epoch.map(Into::into),
+            expires_at_epoch: request.expires_at_epoch.map(Into::into),
+        };
+        let transaction = Transaction::new(body, instruction_signature, sender_public_key, co_signatures);
 
         Ok(transaction)
     }
@@ -67,20 +80,45 @@ impl TryFrom<proto::transaction::Transaction> for Transaction {
 
 impl From<Transaction> for proto::transaction::Transaction {
     fn from(transaction: Transaction) -> Self {
-        let fee = transaction.fee();
-        let meta = transaction.meta().clone();
-        let (instructions, signature, sender_public_key) = transaction.destruct();
+        let (body, signature, sender_public_key, co_signatures) = transaction.destruct();
 
         proto::transaction::Transaction {
             // TODO: Thaum inputs and outputs
             inputs: vec![],
             outputs: vec![],
-            instructions: instructions.into_iter().map(Into::into).collect(),
+            instructions: body.instructions.into_iter().map(Into::into).collect(),
             signature: Some(signature.signature().into()),
             sender_public_key: sender_public_key.to_vec(),
-            fee,
-            meta: Some(meta.into()),
+            fee: body.fee,
+            meta: Some(body.meta.into()),
             balance_proof: vec![],
+            co_signatures: co_signatures.into_iter().map(Into::into).collect(),
+            min_epoch: body.min_epoch.map(Into::into),
+            expires_at_epoch: body.expires_at_epoch.map(Into::into),
+        }
+    }
+}
+
+//---------------------------------- TransactionSignature --------------------------------------------//
+impl TryFrom<proto::transaction::TransactionSignature> for TransactionSignature {
+    type Error = anyhow::Error;
+
+    fn try_from(value: proto::transaction::TransactionSignature) -> Result<Self, Self::Error> {
+        let public_key = PublicKey::from_bytes(&value.public_key).map_err(|_| anyhow!("invalid public_key"))?;
+        let signature: Signature = value
+            .signature
+            .ok_or_else(|| anyhow!("invalid signature"))?
+            .try_into()?;
+        let signature = signature.try_into().map_err(|s| anyhow!("{}", s))?;
+        Ok(TransactionSignature::new(public_key, signature))
+    }
+}
+
+impl From<TransactionSignature> for proto::transaction::TransactionSignature {
+    fn from(value: TransactionSignature) -> Self {
+        Self {
+            public_key: value.public_key().to_vec(),
+            signature: Some(value.signature().signature().into()),
         }
     }
 }
@@ -137,6 +175,10 @@ impl TryFrom<proto::transaction::Instruction> for tari_engine_types::instruction
                         .map_err(|e| anyhow!("claim_burn_proof_of_knowledge: {}", e))?,
                 }),
             },
+            5 => Instruction::PayFee {
+                component_address: Hash::try_from(request.component_address)?.into(),
+                max_fee: request.max_fee.into(),
+            },
             _ => return Err(anyhow!("invalid instruction_type")),
         };
 
@@ -185,6 +227,14 @@ impl From<tari_engine_types::instruction::Instruction> for proto::transaction::I
                 result.claim_burn_proof_of_knowledge = Some(claim.proof_of_knowledge.into());
                 result.claim_burn_public_key = claim.public_key.to_vec();
             },
+            Instruction::PayFee {
+                component_address,
+                max_fee,
+            } => {
+                result.instruction_type = 5;
+                result.component_address = component_address.as_bytes().to_vec();
+                result.max_fee = max_fee.value();
+            },
         }
         result
     }
@@ -48,11 +48,7 @@ use tari_dan_core::{
     DigitalAssetError,
 };
 use tari_dan_storage::global::{GlobalDb, MetadataKey};
-use tari_dan_storage_sqlite::{
-    error::SqliteStorageError,
-    global::SqliteGlobalDbAdapter,
-    sqlite_shard_store_factory::SqliteShardStore,
-};
+use tari_dan_storage_sqlite::{error::SqliteStorageError, global::SqliteGlobalDbAdapter};
 use tari_engine_types::{
     confidential::UnclaimedConfidentialOutput,
     substate::{Substate, SubstateAddress, SubstateValue},
@@ -64,6 +60,7 @@ use tokio::{task, task::JoinHandle, time};
 use crate::{
     base_node_client::GrpcBaseNodeClient,
     epoch_manager::EpochManagerHandle,
+    shard_store::ShardStoreBackend,
     template_manager::{TemplateManagerError, TemplateManagerHandle, TemplateRegistration},
 };
 
@@ -76,7 +73,7 @@ pub fn spawn(
     template_manager: TemplateManagerHandle,
     shutdown: ShutdownSignal,
     consensus_constants: ConsensusConstants,
-    shard_store: SqliteShardStore,
+    shard_store: ShardStoreBackend,
     scan_base_layer: bool,
     base_layer_scanning_interval: Duration,
 ) -> JoinHandle<anyhow::Result<()>> {
@@ -109,7 +106,7 @@ pub struct BaseLayerScanner {
     template_manager: TemplateManagerHandle,
     shutdown: ShutdownSignal,
     consensus_constants: ConsensusConstants,
-    shard_store: SqliteShardStore,
+    shard_store: ShardStoreBackend,
     scan_base_layer: bool,
     base_layer_scanning_interval: Duration,
 }
@@ -122,7 +119,7 @@ impl BaseLayerScanner {
         template_manager: TemplateManagerHandle,
         shutdown: ShutdownSignal,
         consensus_constants: ConsensusConstants,
-        state_store: SqliteShardStore,
+        state_store: ShardStoreBackend,
         scan_base_layer: bool,
         base_layer_scanning_interval: Duration,
     ) -> Self {
@@ -33,7 +33,7 @@ use tari_crypto::tari_utilities::ByteArray;
 use tari_dan_common_types::ShardId;
 use tari_dan_core::{
     consensus_constants::BaseLayerConsensusConstants,
-    models::{BaseLayerMetadata, ValidatorNode},
+    models::{BaseLayerMetadata, SlashingEvidence, SlashingEvidenceSubmission, TariDanPayload, ValidatorNode},
     services::{base_node_error::BaseNodeError, BaseNodeClient, BlockInfo, SideChainUtxos},
 };
 
@@ -102,6 +102,33 @@ impl GrpcBaseNodeClient {
         }
         Ok(count)
     }
+
+    /// Packages slashing evidence for submission to the base layer, anchored to the current tip. The offender must be
+    /// registered as a validator node at the tip.
+    pub async fn build_slashing_evidence_submission(
+        &mut self,
+        evidence: &SlashingEvidence<CommsPublicKey, TariDanPayload>,
+    ) -> Result<SlashingEvidenceSubmission, BaseNodeError> {
+        let tip = self.get_tip_info().await?;
+        let height = tip.height_of_longest_chain;
+        let offender_shard_key = self.get_shard_key(height, evidence.offender()).await?.ok_or_else(|| {
+            BaseNodeError::ValidatorNodeNotRegistered {
+                public_key: evidence.offender().clone(),
+                height,
+            }
+        })?;
+        let encoded = serde_json::to_vec(evidence).map_err(|e| BaseNodeError::EncodingError(e.to_string()))?;
+
+        Ok(SlashingEvidenceSubmission {
+            evidence_hash: evidence.hash(),
+            offender: evidence.offender().clone(),
+            offender_shard_key,
+            epoch: evidence.epoch(),
+            base_layer_height: height,
+            base_layer_tip_hash: tip.tip_hash,
+            evidence: encoded,
+        })
+    }
 }
 
 #[async_trait]
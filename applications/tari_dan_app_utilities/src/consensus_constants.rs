@@ -0,0 +1,111 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tari_common::configuration::Network;
+use tari_dan_core::{consensus_constants::ConsensusConstants, services::leader_strategy::LeaderStrategyType};
+use tari_dan_engine::fees::FeeTable;
+use thiserror::Error;
+
+/// The number of rounds of HotStuff, one for each phase after genesis. Consensus maps node heights to phases with this
+/// number, so no other value is supported.
+const SUPPORTED_HOTSTUFF_ROUNDS: u64 = 4;
+
+/// Returns the consensus constants for the network, before any overrides from the config file are applied
+pub fn consensus_constants_for_network(network: Network) -> ConsensusConstants {
+    match network {
+        Network::MainNet | Network::StageNet | Network::NextNet => ConsensusConstants::mainnet(),
+        Network::LocalNet => ConsensusConstants::localnet(),
+        _ => ConsensusConstants::esmeralda(),
+    }
+}
+
+/// Overrides for the consensus constants of the network. Every node in the network must use the same overrides.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConsensusConstantsConfig {
+    pub base_layer_confirmations: Option<u64>,
+    pub committee_size: Option<u64>,
+    /// Must be 4, the number of phases of HotStuff
+    pub hotstuff_rounds: Option<u64>,
+    /// The pacemaker timeout in seconds
+    pub pacemaker_timeout: Option<u64>,
+    /// The fees charged for executing a transaction
+    pub fee_table: Option<FeeTable>,
+    /// The strategy used to select the consensus leader, one of "always_first", "rotating", "payload_specific" or
+    /// "reputation"
+    pub leader_strategy: Option<LeaderStrategyType>,
+}
+
+impl ConsensusConstantsConfig {
+    /// Returns the consensus constants for the network with the overrides applied
+    pub fn to_consensus_constants(
+        &self,
+        network: Network,
+    ) -> Result<ConsensusConstants, ConsensusConstantsConfigError> {
+        if let Some(hotstuff_rounds) = self.hotstuff_rounds {
+            if hotstuff_rounds != SUPPORTED_HOTSTUFF_ROUNDS {
+                return Err(ConsensusConstantsConfigError::UnsupportedHotstuffRounds { hotstuff_rounds });
+            }
+        }
+        let defaults = consensus_constants_for_network(network);
+        Ok(ConsensusConstants {
+            base_layer_confirmations: self
+                .base_layer_confirmations
+                .unwrap_or(defaults.base_layer_confirmations),
+            committee_size: self.committee_size.unwrap_or(defaults.committee_size),
+            hotstuff_rounds: self.hotstuff_rounds.unwrap_or(defaults.hotstuff_rounds),
+            pacemaker_timeout: self
+                .pacemaker_timeout
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.pacemaker_timeout),
+            fee_table: self.fee_table.clone().unwrap_or(defaults.fee_table),
+            leader_strategy: self.leader_strategy.unwrap_or(defaults.leader_strategy),
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ConsensusConstantsConfigError {
+    #[error("hotstuff_rounds must be {SUPPORTED_HOTSTUFF_ROUNDS}, got {hotstuff_rounds}")]
+    UnsupportedHotstuffRounds { hotstuff_rounds: u64 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_applies_overrides() {
+        let config = ConsensusConstantsConfig {
+            committee_size: Some(3),
+            hotstuff_rounds: Some(4),
+            leader_strategy: Some(LeaderStrategyType::Reputation),
+            ..Default::default()
+        };
+        let constants = config.to_consensus_constants(Network::LocalNet).unwrap();
+        assert_eq!(constants.committee_size, 3);
+        assert_eq!(constants.leader_strategy, LeaderStrategyType::Reputation);
+        assert_eq!(
+            constants.base_layer_confirmations,
+            ConsensusConstants::localnet().base_layer_confirmations
+        );
+    }
+
+    #[test]
+    fn it_rejects_unsupported_hotstuff_rounds() {
+        for hotstuff_rounds in [0, 3, 5] {
+            let config = ConsensusConstantsConfig {
+                hotstuff_rounds: Some(hotstuff_rounds),
+                ..Default::default()
+            };
+            let err = config.to_consensus_constants(Network::LocalNet).unwrap_err();
+            assert!(matches!(
+                err,
+                ConsensusConstantsConfigError::UnsupportedHotstuffRounds { hotstuff_rounds: rounds } if rounds == hotstuff_rounds
+            ));
+        }
+    }
+}
@@ -272,6 +272,26 @@ impl EpochManager<CommsPublicKey> for EpochManagerHandle {
         rx.await.map_err(|_| EpochManagerError::ReceiveError)?
     }
 
+    async fn get_epoch_timestamp(&self, epoch: Epoch) -> Result<u64, EpochManagerError> {
+        let (tx, rx) = oneshot::channel();
+        self.tx_request
+            .send(EpochManagerRequest::GetEpochTimestamp { epoch, reply: tx })
+            .await
+            .map_err(|_| EpochManagerError::SendError)?;
+
+        rx.await.map_err(|_| EpochManagerError::ReceiveError)?
+    }
+
+    async fn get_epoch_beacon(&self, epoch: Epoch) -> Result<[u8; 32], EpochManagerError> {
+        let (tx, rx) = oneshot::channel();
+        self.tx_request
+            .send(EpochManagerRequest::GetEpochBeacon { epoch, reply: tx })
+            .await
+            .map_err(|_| EpochManagerError::SendError)?;
+
+        rx.await.map_err(|_| EpochManagerError::ReceiveError)?
+    }
+
     /// Note: this awaits until scanning is complete.
     async fn notify_scanning_complete(&self) -> Result<(), EpochManagerError> {
         let (tx, rx) = oneshot::channel();
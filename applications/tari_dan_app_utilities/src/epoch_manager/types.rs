@@ -89,6 +89,14 @@ pub enum EpochManagerRequest {
         epoch: Epoch,
         reply: Reply<Vec<u8>>,
     },
+    GetEpochTimestamp {
+        epoch: Epoch,
+        reply: Reply<u64>,
+    },
+    GetEpochBeacon {
+        epoch: Epoch,
+        reply: Reply<[u8; 32]>,
+    },
     IsValidatorInCommitteeForCurrentEpoch {
         shard: ShardId,
         identity: CommsPublicKey,
@@ -22,5 +22,7 @@
 
 pub mod base_layer_scanner;
 pub mod base_node_client;
+pub mod consensus_constants;
 pub mod epoch_manager;
+pub mod shard_store;
 pub mod template_manager;
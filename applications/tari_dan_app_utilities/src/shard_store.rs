@@ -0,0 +1,532 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    fmt::{Display, Formatter},
+    ops::{Deref, DerefMut},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_dan_common_types::{
+    Epoch,
+    NodeHeight,
+    ObjectPledge,
+    ObjectPledgeInfo,
+    PayloadId,
+    QuorumCertificate,
+    ShardId,
+    SubstateState,
+    TreeNodeHash,
+};
+use tari_dan_core::{
+    models::{
+        vote_message::VoteMessage,
+        CurrentLeaderStates,
+        HotStuffTreeNode,
+        LeaderFailure,
+        LeafNode,
+        MempoolTransaction,
+        PayloadResult,
+        PrunedRecordCounts,
+        RecentTransaction,
+        SQLSubstate,
+        SQLTransaction,
+        SlashingEvidence,
+        StateTreeProof,
+        SubstateShardData,
+        TariDanPayload,
+    },
+    storage::{
+        shard_store::{ShardStore, ShardStoreReadTransaction, ShardStoreWriteTransaction},
+        StorageError,
+    },
+};
+use tari_dan_storage_lmdb::shard_store::{
+    LmdbShardStore,
+    LmdbShardStoreReadTransaction,
+    LmdbShardStoreWriteTransaction,
+};
+use tari_dan_storage_sqlite::sqlite_shard_store_factory::{
+    SqliteShardStore,
+    SqliteShardStoreReadTransaction,
+    SqliteShardStoreWriteTransaction,
+};
+use tari_engine_types::{
+    events::Event,
+    substate::{Substate, SubstateAddress},
+};
+
+/// The database used for the shard store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ShardStoreType {
+    #[default]
+    Sqlite,
+    Lmdb,
+}
+
+impl Display for ShardStoreType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShardStoreType::Sqlite => write!(f, "sqlite"),
+            ShardStoreType::Lmdb => write!(f, "lmdb"),
+        }
+    }
+}
+
+/// A shard store whose backend is chosen at runtime
+#[derive(Clone)]
+pub enum ShardStoreBackend {
+    Sqlite(SqliteShardStore),
+    Lmdb(LmdbShardStore),
+}
+
+impl ShardStoreBackend {
+    /// Opens the shard store at `path`. This is a file for SQLite and a directory for LMDB.
+    pub fn try_create<P: AsRef<Path>>(store_type: ShardStoreType, path: P) -> Result<Self, StorageError> {
+        match store_type {
+            ShardStoreType::Sqlite => Ok(Self::Sqlite(SqliteShardStore::try_create(path.as_ref().to_path_buf())?)),
+            ShardStoreType::Lmdb => Ok(Self::Lmdb(LmdbShardStore::try_create(path)?)),
+        }
+    }
+
+    pub fn store_type(&self) -> ShardStoreType {
+        match self {
+            ShardStoreBackend::Sqlite(_) => ShardStoreType::Sqlite,
+            ShardStoreBackend::Lmdb(_) => ShardStoreType::Lmdb,
+        }
+    }
+}
+
+impl From<SqliteShardStore> for ShardStoreBackend {
+    fn from(store: SqliteShardStore) -> Self {
+        Self::Sqlite(store)
+    }
+}
+
+impl From<LmdbShardStore> for ShardStoreBackend {
+    fn from(store: LmdbShardStore) -> Self {
+        Self::Lmdb(store)
+    }
+}
+
+impl ShardStore for ShardStoreBackend {
+    type Addr = PublicKey;
+    type Payload = TariDanPayload;
+    type ReadTransaction<'a> = ShardStoreBackendReadTransaction<'a>;
+    type WriteTransaction<'a> = ShardStoreBackendWriteTransaction<'a>;
+
+    fn create_read_tx(&self) -> Result<Self::ReadTransaction<'_>, StorageError> {
+        match self {
+            ShardStoreBackend::Sqlite(store) => {
+                Ok(ShardStoreBackendReadTransaction::SqliteRead(store.create_read_tx()?))
+            },
+            ShardStoreBackend::Lmdb(store) => Ok(ShardStoreBackendReadTransaction::LmdbRead(store.create_read_tx()?)),
+        }
+    }
+
+    fn create_write_tx(&self) -> Result<Self::WriteTransaction<'_>, StorageError> {
+        let tx = match self {
+            ShardStoreBackend::Sqlite(store) => ShardStoreBackendReadTransaction::SqliteWrite(store.create_write_tx()?),
+            ShardStoreBackend::Lmdb(store) => ShardStoreBackendReadTransaction::LmdbWrite(store.create_write_tx()?),
+        };
+        Ok(ShardStoreBackendWriteTransaction { transaction: Some(tx) })
+    }
+}
+
+/// A read transaction on either backend. A write transaction must deref to the read transaction, so this also holds
+/// the backend write transactions.
+pub enum ShardStoreBackendReadTransaction<'a> {
+    SqliteRead(SqliteShardStoreReadTransaction<'a>),
+    SqliteWrite(SqliteShardStoreWriteTransaction<'a>),
+    LmdbRead(LmdbShardStoreReadTransaction<'a>),
+    LmdbWrite(LmdbShardStoreWriteTransaction<'a>),
+}
+
+macro_rules! dispatch_read {
+    ($tx:expr, $inner:ident => $call:expr) => {
+        match $tx {
+            ShardStoreBackendReadTransaction::SqliteRead($inner) => $call,
+            ShardStoreBackendReadTransaction::SqliteWrite($inner) => $call,
+            ShardStoreBackendReadTransaction::LmdbRead($inner) => $call,
+            ShardStoreBackendReadTransaction::LmdbWrite($inner) => $call,
+        }
+    };
+}
+
+impl ShardStoreReadTransaction<PublicKey, TariDanPayload> for ShardStoreBackendReadTransaction<'_> {
+    fn get_high_qc_for(&mut self, payload_id: PayloadId, shard: ShardId) -> Result<QuorumCertificate, StorageError> {
+        dispatch_read!(self, tx => tx.get_high_qc_for(payload_id, shard))
+    }
+
+    fn get_high_qcs(&mut self, payload_id: PayloadId) -> Result<Vec<QuorumCertificate>, StorageError> {
+        dispatch_read!(self, tx => tx.get_high_qcs(payload_id))
+    }
+
+    fn get_leaf_node(&mut self, payload_id: &PayloadId, shard: &ShardId) -> Result<LeafNode, StorageError> {
+        dispatch_read!(self, tx => tx.get_leaf_node(payload_id, shard))
+    }
+
+    fn get_current_leaders_states(&mut self, payload: &PayloadId) -> Result<Vec<CurrentLeaderStates>, StorageError> {
+        dispatch_read!(self, tx => tx.get_current_leaders_states(payload))
+    }
+
+    fn get_payload(&mut self, payload_id: &PayloadId) -> Result<TariDanPayload, StorageError> {
+        dispatch_read!(self, tx => tx.get_payload(payload_id))
+    }
+
+    fn get_node(
+        &mut self,
+        node_hash: &TreeNodeHash,
+    ) -> Result<HotStuffTreeNode<PublicKey, TariDanPayload>, StorageError> {
+        dispatch_read!(self, tx => tx.get_node(node_hash))
+    }
+
+    fn get_locked_node_hash_and_height(
+        &mut self,
+        payload_id: PayloadId,
+        shard: ShardId,
+    ) -> Result<(TreeNodeHash, NodeHeight), StorageError> {
+        dispatch_read!(self, tx => tx.get_locked_node_hash_and_height(payload_id, shard))
+    }
+
+    fn get_last_executed_height(&mut self, shard: ShardId, payload_id: PayloadId) -> Result<NodeHeight, StorageError> {
+        dispatch_read!(self, tx => tx.get_last_executed_height(shard, payload_id))
+    }
+
+    fn get_state_inventory(&mut self) -> Result<Vec<ShardId>, StorageError> {
+        dispatch_read!(self, tx => tx.get_state_inventory())
+    }
+
+    fn get_substate_states(&mut self, shards: &[ShardId]) -> Result<Vec<SubstateShardData>, StorageError> {
+        dispatch_read!(self, tx => tx.get_substate_states(shards))
+    }
+
+    fn get_substate_states_by_range(
+        &mut self,
+        start_shard_id: ShardId,
+        end_shard_id: ShardId,
+        excluded_shards: &[ShardId],
+    ) -> Result<Vec<SubstateShardData>, StorageError> {
+        dispatch_read!(self, tx => tx.get_substate_states_by_range(start_shard_id, end_shard_id, excluded_shards))
+    }
+
+    fn get_substate_states_chunk(
+        &mut self,
+        start_shard_id: ShardId,
+        end_shard_id: ShardId,
+        limit: usize,
+    ) -> Result<Vec<SubstateShardData>, StorageError> {
+        dispatch_read!(self, tx => tx.get_substate_states_chunk(start_shard_id, end_shard_id, limit))
+    }
+
+    fn get_last_voted_height(
+        &mut self,
+        shard: ShardId,
+        payload_id: PayloadId,
+    ) -> Result<(NodeHeight, u32), StorageError> {
+        dispatch_read!(self, tx => tx.get_last_voted_height(shard, payload_id))
+    }
+
+    fn get_leader_proposals(
+        &mut self,
+        payload: PayloadId,
+        payload_height: NodeHeight,
+        shards: &[ShardId],
+    ) -> Result<Vec<HotStuffTreeNode<PublicKey, TariDanPayload>>, StorageError> {
+        dispatch_read!(self, tx => tx.get_leader_proposals(payload, payload_height, shards))
+    }
+
+    fn get_last_payload_height_for_leader_proposal(
+        &mut self,
+        payload: PayloadId,
+        shard: ShardId,
+    ) -> Result<NodeHeight, StorageError> {
+        dispatch_read!(self, tx => tx.get_last_payload_height_for_leader_proposal(payload, shard))
+    }
+
+    fn has_vote_for(&mut self, from: &PublicKey, node_hash: TreeNodeHash) -> Result<bool, StorageError> {
+        dispatch_read!(self, tx => tx.has_vote_for(from, node_hash))
+    }
+
+    fn get_received_votes_for(&mut self, node_hash: TreeNodeHash) -> Result<Vec<VoteMessage>, StorageError> {
+        dispatch_read!(self, tx => tx.get_received_votes_for(node_hash))
+    }
+
+    fn get_recent_transactions(&mut self) -> Result<Vec<RecentTransaction>, StorageError> {
+        dispatch_read!(self, tx => tx.get_recent_transactions())
+    }
+
+    fn get_transaction(&mut self, payload_id: Vec<u8>) -> Result<Vec<SQLTransaction>, StorageError> {
+        dispatch_read!(self, tx => tx.get_transaction(payload_id))
+    }
+
+    fn get_substates_for_payload(
+        &mut self,
+        payload_id: Vec<u8>,
+        shard_id: Vec<u8>,
+    ) -> Result<Vec<SQLSubstate>, StorageError> {
+        dispatch_read!(self, tx => tx.get_substates_for_payload(payload_id, shard_id))
+    }
+
+    fn get_payload_result(&mut self, payload_id: &PayloadId) -> Result<PayloadResult, StorageError> {
+        dispatch_read!(self, tx => tx.get_payload_result(payload_id))
+    }
+
+    fn get_resolved_pledges_for_payload(&mut self, payload: PayloadId) -> Result<Vec<ObjectPledgeInfo>, StorageError> {
+        dispatch_read!(self, tx => tx.get_resolved_pledges_for_payload(payload))
+    }
+
+    fn get_active_pledge(&mut self, shard: ShardId) -> Result<Option<ObjectPledgeInfo>, StorageError> {
+        dispatch_read!(self, tx => tx.get_active_pledge(shard))
+    }
+
+    fn get_events_for_payload(&mut self, payload_id: PayloadId) -> Result<Vec<Event>, StorageError> {
+        dispatch_read!(self, tx => tx.get_events_for_payload(payload_id))
+    }
+
+    fn get_events_by_topic(&mut self, topic: &str) -> Result<Vec<Event>, StorageError> {
+        dispatch_read!(self, tx => tx.get_events_by_topic(topic))
+    }
+
+    fn get_prunable_payloads(
+        &mut self,
+        before_epoch: Epoch,
+        decide_height: NodeHeight,
+        limit: usize,
+    ) -> Result<Vec<PayloadId>, StorageError> {
+        dispatch_read!(self, tx => tx.get_prunable_payloads(before_epoch, decide_height, limit))
+    }
+
+    fn get_state_root(&mut self) -> Result<FixedHash, StorageError> {
+        dispatch_read!(self, tx => tx.get_state_root())
+    }
+
+    fn get_state_proof(&mut self, shard: ShardId) -> Result<StateTreeProof, StorageError> {
+        dispatch_read!(self, tx => tx.get_state_proof(shard))
+    }
+
+    fn get_leader_failures(
+        &mut self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<LeaderFailure<PublicKey>>, StorageError> {
+        dispatch_read!(self, tx => tx.get_leader_failures(start_epoch, end_epoch))
+    }
+
+    fn get_slashing_evidence(
+        &mut self,
+        offender: Option<&PublicKey>,
+    ) -> Result<Vec<SlashingEvidence<PublicKey, TariDanPayload>>, StorageError> {
+        dispatch_read!(self, tx => tx.get_slashing_evidence(offender))
+    }
+
+    fn get_mempool_transactions(&mut self) -> Result<Vec<MempoolTransaction>, StorageError> {
+        dispatch_read!(self, tx => tx.get_mempool_transactions())
+    }
+}
+
+pub struct ShardStoreBackendWriteTransaction<'a> {
+    /// None indicates if the transaction has been explicitly committed/rolled back
+    transaction: Option<ShardStoreBackendReadTransaction<'a>>,
+}
+
+macro_rules! dispatch_write {
+    ($tx:expr, $inner:ident => $call:expr) => {
+        match $tx {
+            Some(ShardStoreBackendReadTransaction::SqliteWrite($inner)) => $call,
+            Some(ShardStoreBackendReadTransaction::LmdbWrite($inner)) => $call,
+            _ => unreachable!("ShardStoreBackendWriteTransaction always holds a write transaction"),
+        }
+    };
+}
+
+impl ShardStoreWriteTransaction<PublicKey, TariDanPayload> for ShardStoreBackendWriteTransaction<'_> {
+    fn commit(mut self) -> Result<(), StorageError> {
+        dispatch_write!(self.transaction.take(), tx => tx.commit())
+    }
+
+    fn rollback(mut self) -> Result<(), StorageError> {
+        dispatch_write!(self.transaction.take(), tx => tx.rollback())
+    }
+
+    fn insert_high_qc(&mut self, from: PublicKey, shard: ShardId, qc: QuorumCertificate) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.insert_high_qc(from, shard, qc))
+    }
+
+    fn save_payload(&mut self, payload: TariDanPayload) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.save_payload(payload))
+    }
+
+    fn save_current_leader_state(
+        &mut self,
+        payload: PayloadId,
+        shard_id: ShardId,
+        leader_round: u32,
+        leader: PublicKey,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.save_current_leader_state(payload, shard_id, leader_round, leader))
+    }
+
+    fn set_leaf_node(
+        &mut self,
+        payload_id: PayloadId,
+        shard: ShardId,
+        node: TreeNodeHash,
+        payload_height: NodeHeight,
+        height: NodeHeight,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.set_leaf_node(payload_id, shard, node, payload_height, height))
+    }
+
+    fn save_node(&mut self, node: HotStuffTreeNode<PublicKey, TariDanPayload>) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.save_node(node))
+    }
+
+    fn set_locked(
+        &mut self,
+        payload_id: PayloadId,
+        shard: ShardId,
+        node_hash: TreeNodeHash,
+        node_height: NodeHeight,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.set_locked(payload_id, shard, node_hash, node_height))
+    }
+
+    fn set_last_executed_height(
+        &mut self,
+        shard: ShardId,
+        payload_id: PayloadId,
+        height: NodeHeight,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.set_last_executed_height(shard, payload_id, height))
+    }
+
+    fn save_substate_changes(
+        &mut self,
+        node: HotStuffTreeNode<PublicKey, TariDanPayload>,
+        changes: &[SubstateState],
+    ) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.save_substate_changes(node, changes))
+    }
+
+    fn insert_substates(&mut self, substate_data: SubstateShardData) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.insert_substates(substate_data))
+    }
+
+    fn set_last_voted_height(
+        &mut self,
+        shard: ShardId,
+        payload_id: PayloadId,
+        height: NodeHeight,
+        leader_round: u32,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.set_last_voted_height(shard, payload_id, height, leader_round))
+    }
+
+    fn save_leader_proposals(
+        &mut self,
+        shard: ShardId,
+        payload: PayloadId,
+        payload_height: NodeHeight,
+        leader_round: u32,
+        node: HotStuffTreeNode<PublicKey, TariDanPayload>,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(
+            &mut self.transaction,
+            tx => tx.save_leader_proposals(shard, payload, payload_height, leader_round, node)
+        )
+    }
+
+    fn save_received_vote_for(
+        &mut self,
+        from: PublicKey,
+        node_hash: TreeNodeHash,
+        vote_message: VoteMessage,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.save_received_vote_for(from, node_hash, vote_message))
+    }
+
+    fn update_payload_result(&mut self, payload_id: &PayloadId, result: PayloadResult) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.update_payload_result(payload_id, result))
+    }
+
+    fn save_events(&mut self, payload_id: PayloadId, events: &[Event]) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.save_events(payload_id, events))
+    }
+
+    fn pledge_object(
+        &mut self,
+        shard: ShardId,
+        payload: PayloadId,
+        current_height: NodeHeight,
+    ) -> Result<ObjectPledge, StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.pledge_object(shard, payload, current_height))
+    }
+
+    fn complete_pledges(
+        &mut self,
+        shard: ShardId,
+        payload_id: PayloadId,
+        node_hash: &TreeNodeHash,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.complete_pledges(shard, payload_id, node_hash))
+    }
+
+    fn abandon_pledges(
+        &mut self,
+        shard: ShardId,
+        payload_id: PayloadId,
+        node_hash: &TreeNodeHash,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.abandon_pledges(shard, payload_id, node_hash))
+    }
+
+    fn save_burnt_utxo(
+        &mut self,
+        substate: &Substate,
+        commitment_address: SubstateAddress,
+        shard_id: ShardId,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.save_burnt_utxo(substate, commitment_address, shard_id))
+    }
+
+    fn prune_payload(&mut self, payload_id: PayloadId) -> Result<PrunedRecordCounts, StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.prune_payload(payload_id))
+    }
+
+    fn save_leader_failure(&mut self, failure: LeaderFailure<PublicKey>) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.save_leader_failure(failure))
+    }
+
+    fn save_slashing_evidence(
+        &mut self,
+        evidence: SlashingEvidence<PublicKey, TariDanPayload>,
+    ) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.save_slashing_evidence(evidence))
+    }
+
+    fn save_mempool_transaction(&mut self, transaction: MempoolTransaction) -> Result<(), StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.save_mempool_transaction(transaction))
+    }
+
+    fn remove_mempool_transaction(&mut self, transaction_hash: &FixedHash) -> Result<bool, StorageError> {
+        dispatch_write!(&mut self.transaction, tx => tx.remove_mempool_transaction(transaction_hash))
+    }
+}
+
+impl<'a> Deref for ShardStoreBackendWriteTransaction<'a> {
+    type Target = ShardStoreBackendReadTransaction<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.transaction.as_ref().unwrap()
+    }
+}
+
+impl<'a> DerefMut for ShardStoreBackendWriteTransaction<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.transaction.as_mut().unwrap()
+    }
+}
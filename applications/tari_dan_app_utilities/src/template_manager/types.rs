@@ -37,16 +37,23 @@ pub struct TemplateMetadata {
     pub binary_sha: Vec<u8>,
     /// Block height in which the template was published
     pub height: u64,
+    pub version: u16,
+    /// The address of the previous version of this template, if any
+    pub predecessor: Option<TemplateAddress>,
 }
 
-impl From<TemplateRegistration> for TemplateMetadata {
-    fn from(reg: TemplateRegistration) -> Self {
+impl TemplateMetadata {
+    /// Creates the metadata for a template registration. The predecessor is not part of the base layer registration,
+    /// it is determined by the template manager from the templates that were registered before this one.
+    pub fn from_registration(reg: TemplateRegistration, predecessor: Option<TemplateAddress>) -> Self {
         TemplateMetadata {
             name: reg.template_name,
             address: reg.template_address,
             url: reg.registration.binary_url.into_string(),
             binary_sha: reg.registration.binary_sha.into_vec(),
             height: reg.mined_height,
+            version: reg.registration.template_version,
+            predecessor,
         }
     }
 }
@@ -60,6 +67,8 @@ impl From<DbTemplate> for TemplateMetadata {
             url: record.url,
             binary_sha: vec![],
             height: record.height,
+            version: record.version,
+            predecessor: record.predecessor_address.map(|addr| (*addr).into()),
         }
     }
 }
@@ -82,6 +91,8 @@ impl From<DbTemplate> for Template {
                 // TODO: add field to db
                 binary_sha: vec![],
                 height: record.height,
+                version: record.version,
+                predecessor: record.predecessor_address.map(|addr| (*addr).into()),
             },
             compiled_code: record.compiled_code,
         }
@@ -32,10 +32,10 @@ use std::{
 use anyhow::anyhow;
 use clap::{Args, Subcommand};
 use tari_common_types::types::{FixedHash, PublicKey};
-use tari_dan_common_types::ShardId;
+use tari_dan_common_types::{Epoch, ShardId};
 use tari_dan_wallet_sdk::models::{ConfidentialProofId, VersionedSubstateAddress};
 use tari_engine_types::{
-    commit_result::{FinalizeResult, TransactionResult},
+    commit_result::FinalizeResult,
     execution_result::{ExecutionResult, Type},
     instruction::Instruction,
     substate::{SubstateAddress, SubstateValue},
@@ -113,6 +113,12 @@ pub struct CommonSubmitArgs {
     pub new_non_fungible_index_outputs: Vec<NewNonFungibleIndexOutput>,
     #[clap(long)]
     pub fee: Option<u64>,
+    /// The first epoch in which the transaction may be included
+    #[clap(long)]
+    pub min_epoch: Option<u64>,
+    /// The epoch from which the transaction is no longer valid
+    #[clap(long)]
+    pub expires_at_epoch: Option<u64>,
 }
 
 #[derive(Debug, Args, Clone)]
@@ -276,6 +282,8 @@ pub async fn submit_transaction(
             .collect(),
         is_dry_run: common.dry_run,
         proof_id,
+        min_epoch: common.min_epoch.map(Epoch),
+        expires_at_epoch: common.expires_at_epoch.map(Epoch),
     };
 
     let has_no_outputs = request.new_outputs == 0 &&
@@ -455,7 +463,7 @@ fn summarize(result: &TransactionWaitResultResponse, time_taken: Duration) {
     if let Some(qc) = result.qcs.first() {
         println!("Epoch: {}", qc.epoch());
         println!("Payload height: {}", qc.payload_height());
-        println!("Signed by: {} validator nodes", qc.validators_metadata().len());
+        println!("Signed by: {} validator nodes", qc.aggregated_signature().num_signers());
     } else {
         println!("No QC");
     }
@@ -483,48 +491,66 @@ fn summarize(result: &TransactionWaitResultResponse, time_taken: Duration) {
 
 pub fn summarize_finalize_result(finalize: &FinalizeResult) {
     println!("========= Substates =========");
-    match finalize.result {
-        TransactionResult::Accept(ref diff) => {
-            for (address, substate) in diff.up_iter() {
-                println!("️🌲 UP substate {} (v{})", address, substate.version(),);
-                println!("      🧩 Shard: {}", ShardId::from_address(address, substate.version()));
-                match substate.substate_value() {
-                    SubstateValue::Component(component) => {
-                        println!("      ▶ component ({}): {}", component.module_name, address,);
-                    },
-                    SubstateValue::Resource(_) => {
-                        println!("      ▶ resource: {}", address);
-                    },
-                    SubstateValue::Vault(vault) => {
-                        println!("      ▶ vault: {} {}", address, vault.resource_address());
-                    },
-                    SubstateValue::NonFungible(_) => {
-                        println!("      ▶ NFT: {}", address);
-                    },
-                    SubstateValue::UnclaimedConfidentialOutput(_) => {
-                        println!("      ▶ Layer 1 commitment: {}", address);
-                    },
-                    SubstateValue::NonFungibleIndex(index) => {
-                        let referenced_address = SubstateAddress::from(index.referenced_address().clone());
-                        println!("      ▶ NFT index {} referencing {}", address, referenced_address);
-                    },
-                }
-                println!();
-            }
-            for (address, version) in diff.down_iter() {
-                println!("🗑️ DOWN substate {} v{}", address, version,);
-                println!("      🧩 Shard: {}", ShardId::from_address(address, *version));
-                println!();
+    if let Some(diff) = finalize.result.accept() {
+        for (address, substate) in diff.up_iter() {
+            println!("️🌲 UP substate {} (v{})", address, substate.version(),);
+            println!("      🧩 Shard: {}", ShardId::from_address(address, substate.version()));
+            match substate.substate_value() {
+                SubstateValue::Component(component) => {
+                    println!("      ▶ component ({}): {}", component.module_name, address,);
+                },
+                SubstateValue::Resource(_) => {
+                    println!("      ▶ resource: {}", address);
+                },
+                SubstateValue::Vault(vault) => {
+                    println!("      ▶ vault: {} {}", address, vault.resource_address());
+                },
+                SubstateValue::NonFungible(_) => {
+                    println!("      ▶ NFT: {}", address);
+                },
+                SubstateValue::UnclaimedConfidentialOutput(_) => {
+                    println!("      ▶ Layer 1 commitment: {}", address);
+                },
+                SubstateValue::NonFungibleIndex(index) => {
+                    let referenced_address = SubstateAddress::from(index.referenced_address().clone());
+                    println!("      ▶ NFT index {} referencing {}", address, referenced_address);
+                },
+                SubstateValue::KeyValueEntry(_) => {
+                    println!("      ▶ key-value entry: {}", address);
+                },
             }
-        },
-        TransactionResult::Reject(ref reason) => {
-            println!("❌️ Transaction rejected: {}", reason);
-        },
+            println!();
+        }
+        for (address, version) in diff.down_iter() {
+            println!("🗑️ DOWN substate {} v{}", address, version,);
+            println!("      🧩 Shard: {}", ShardId::from_address(address, *version));
+            println!();
+        }
+    }
+    if let Some(reason) = finalize.result.reject() {
+        println!("❌️ Transaction rejected: {}", reason);
+    }
+
+    if let Some(ref fee_receipt) = finalize.fee_receipt {
+        println!("========= Fees =========");
+        for breakdown in &fee_receipt.cost_breakdown {
+            println!("{:?}: {}", breakdown.source, breakdown.amount);
+        }
+        println!("Total charged: {}", fee_receipt.total_fees_charged());
+        println!("Total paid: {}", fee_receipt.total_fees_paid);
+        println!("Refunded: {}", fee_receipt.total_refunded());
+        println!();
     }
 
     println!("========= Return Values =========");
     print_execution_results(&finalize.execution_results);
 
+    println!();
+    println!("========= EVENTS =========");
+    for event in &finalize.events {
+        println!("{} {:?}", event, event.payload);
+    }
+
     println!();
     println!("========= LOGS =========");
     for log in &finalize.logs {
@@ -379,7 +379,7 @@ async fn wait_for_result(
                     TransactionResult::Accept(_) => {
                         return Ok(finalized.result);
                     },
-                    TransactionResult::Reject(reject) => {
+                    TransactionResult::AcceptFeeRejectRest(_, reject) | TransactionResult::Reject(reject) => {
                         return Err(anyhow!("Transaction rejected: {}", reject));
                     },
                 }
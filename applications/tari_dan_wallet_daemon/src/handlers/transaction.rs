@@ -5,17 +5,28 @@ use std::time::Duration;
 use anyhow::anyhow;
 use futures::{future, future::Either};
 use log::*;
-use tari_common_types::types::FixedHash;
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_crypto::keys::PublicKey as _;
 use tari_dan_common_types::{optional::Optional, ShardId};
-use tari_dan_wallet_sdk::apis::key_manager;
+use tari_dan_wallet_sdk::{apis::key_manager, models::VersionedSubstateAddress, DanWalletSdk};
+use tari_dan_wallet_storage_sqlite::SqliteWalletStore;
 use tari_engine_types::{instruction::Instruction, substate::SubstateAddress};
-use tari_template_lib::prelude::NonFungibleAddress;
-use tari_transaction::Transaction;
+use tari_template_lib::{
+    models::{NonFungibleId, ResourceAddress},
+    prelude::NonFungibleAddress,
+};
+use tari_transaction::{SubstateChange, Transaction, TransactionBuilder};
 use tari_wallet_daemon_client::types::{
+    TransactionCoSignRequest,
+    TransactionCoSignResponse,
+    TransactionExportRequest,
+    TransactionExportResponse,
     TransactionGetRequest,
     TransactionGetResponse,
     TransactionGetResultRequest,
     TransactionGetResultResponse,
+    TransactionImportRequest,
+    TransactionImportResponse,
     TransactionSubmitRequest,
     TransactionSubmitResponse,
     TransactionWaitResultRequest,
@@ -41,68 +52,131 @@ pub async fn handle_submit(
     // TODO: Ideally the SDK should take care of signing the transaction internally
     let (_, key) = key_api.get_key_or_active(key_manager::TRANSACTION_BRANCH, req.signing_key_index)?;
 
-    // let transaction_api = sdk.transaction_api();
-    let inputs = if req.override_inputs {
-        req.inputs
-    } else {
-        // If we are not overring inputs, we will use the our own
-        // inputs, together with default inputs
-        // sdk.transaction_api().default_inputs().await?
-        let substates = get_referenced_component_addresses(&req.instructions);
-        let loaded_dependent_substates = sdk.substate_api().load_dependent_substates(&substates)?;
-        vec![req.inputs, loaded_dependent_substates].concat()
-    };
-
-    // TODO: we assume that all inputs will be consumed and produce a new output however this is only the case when the
-    //       object is mutated
-    let mut outputs = inputs
-        .iter()
-        .map(|versioned_addr| ShardId::from_address(&versioned_addr.address, versioned_addr.version + 1))
-        .collect::<Vec<_>>();
-
-    outputs.extend(req.specific_non_fungible_outputs.into_iter().map(|(resx_addr, id)| {
-        ShardId::from_address(&SubstateAddress::NonFungible(NonFungibleAddress::new(resx_addr, id)), 0)
-    }));
-
-    let inputs = inputs
-        .into_iter()
-        .map(|versioned_addr| ShardId::from_address(&versioned_addr.address, versioned_addr.version))
-        .collect::<Vec<_>>();
+    let (inputs, outputs) = get_inputs_and_outputs(
+        sdk,
+        &req.instructions,
+        req.inputs,
+        req.override_inputs,
+        req.specific_non_fungible_outputs,
+    )?;
 
     let mut builder = Transaction::builder();
     builder
         .with_fee(req.fee)
-        .with_inputs(inputs.clone())
-        .with_outputs(outputs.clone())
+        .with_inputs(inputs)
+        .with_outputs(outputs)
         .with_new_outputs(req.new_outputs)
         .with_new_non_fungible_outputs(req.new_non_fungible_outputs)
         .with_new_non_fungible_index_outputs(req.new_non_fungible_index_outputs)
-        .with_instructions(req.instructions)
-        .sign(&key.k);
+        .with_instructions(req.instructions);
+    if let Some(min_epoch) = req.min_epoch {
+        builder.with_min_epoch(min_epoch);
+    }
+    if let Some(expires_at_epoch) = req.expires_at_epoch {
+        builder.with_expires_at_epoch(expires_at_epoch);
+    }
+    // Unless the caller specified the inputs, the known inputs are narrowed down to the substates that the transaction
+    // actually requires and the outputs are discovered by dry-running it
+    if !req.override_inputs {
+        if let Err(err) = sdk
+            .transaction_api()
+            .fill_substate_requirements(&mut builder, PublicKey::from_secret_key(&key.k))
+            .await
+        {
+            warn!(
+                target: LOG_TARGET,
+                "Unable to discover the substates of the transaction, using the known inputs: {}", err
+            );
+        }
+    }
+    builder.sign(&key.k);
 
     let transaction = builder.build();
+    let (inputs, outputs) = get_involved_shards(&transaction);
     if let Some(proof_id) = req.proof_id {
         // update the proofs table with the corresponding transaction hash
         sdk.confidential_outputs_api()
             .proofs_set_transaction_hash(proof_id, FixedHash::from(transaction.hash().into_array()))?;
     }
 
-    info!(
-        target: LOG_TARGET,
-        "Submitted transaction with hash {}",
-        transaction.hash()
-    );
-    let hash = if req.is_dry_run {
-        sdk.transaction_api().submit_dry_run_to_vn(transaction).await?
-    } else {
-        sdk.transaction_api().submit_to_vn(transaction).await?
-    };
+    let hash = submit_transaction(context, transaction, req.is_dry_run).await?;
 
-    if !req.is_dry_run {
-        context.notifier().notify(TransactionSubmittedEvent { hash });
+    Ok(TransactionSubmitResponse { hash, inputs, outputs })
+}
+
+pub async fn handle_export(
+    context: &HandlerContext,
+    req: TransactionExportRequest,
+) -> Result<TransactionExportResponse, anyhow::Error> {
+    let sdk = context.wallet_sdk();
+    let key_api = sdk.key_manager_api();
+    let (_, key) = key_api.get_key_or_active(key_manager::TRANSACTION_BRANCH, req.signing_key_index)?;
+
+    let (inputs, outputs) = get_inputs_and_outputs(
+        sdk,
+        &req.instructions,
+        req.inputs,
+        req.override_inputs,
+        req.specific_non_fungible_outputs,
+    )?;
+
+    let mut builder = Transaction::builder();
+    builder
+        .with_fee(req.fee)
+        .with_inputs(inputs)
+        .with_outputs(outputs)
+        .with_new_outputs(req.new_outputs)
+        .with_new_non_fungible_outputs(req.new_non_fungible_outputs)
+        .with_new_non_fungible_index_outputs(req.new_non_fungible_index_outputs)
+        .with_instructions(req.instructions);
+    if let Some(min_epoch) = req.min_epoch {
+        builder.with_min_epoch(min_epoch);
     }
+    if let Some(expires_at_epoch) = req.expires_at_epoch {
+        builder.with_expires_at_epoch(expires_at_epoch);
+    }
+    builder.sign(&key.k);
 
-    Ok(TransactionSubmitResponse { hash, inputs, outputs })
+    Ok(TransactionExportResponse {
+        partial_transaction: builder,
+    })
+}
+
+pub async fn handle_co_sign(
+    context: &HandlerContext,
+    req: TransactionCoSignRequest,
+) -> Result<TransactionCoSignResponse, anyhow::Error> {
+    let partial_transaction = co_sign(context, req.partial_transaction, req.signing_key_index)?;
+    Ok(TransactionCoSignResponse { partial_transaction })
+}
+
+pub async fn handle_import(
+    context: &HandlerContext,
+    req: TransactionImportRequest,
+) -> Result<TransactionImportResponse, anyhow::Error> {
+    let builder = co_sign(context, req.partial_transaction, req.signing_key_index)?;
+    let hash = submit_transaction(context, builder.build(), req.is_dry_run).await?;
+
+    Ok(TransactionImportResponse { hash })
+}
+
+/// Co-signs a transaction that has been signed by its sender with the wallet key, unless the key already signed it
+fn co_sign(
+    context: &HandlerContext,
+    mut builder: TransactionBuilder,
+    signing_key_index: Option<u64>,
+) -> Result<TransactionBuilder, anyhow::Error> {
+    let key_api = context.wallet_sdk().key_manager_api();
+    let (_, key) = key_api.get_key_or_active(key_manager::TRANSACTION_BRANCH, signing_key_index)?;
+
+    if !builder.is_signed() {
+        return Err(anyhow!("Transaction has not been signed by the sender"));
+    }
+    let public_key = PublicKey::from_secret_key(&key.k);
+    if !builder.signer_public_keys().any(|signer| *signer == public_key) {
+        builder.co_sign(&key.k);
+    }
+    Ok(builder)
 }
 
 pub async fn handle_get(
@@ -207,6 +281,80 @@ pub async fn handle_wait_result(
     }
 }
 
+async fn submit_transaction(
+    context: &HandlerContext,
+    transaction: Transaction,
+    is_dry_run: bool,
+) -> Result<FixedHash, anyhow::Error> {
+    let sdk = context.wallet_sdk();
+    info!(
+        target: LOG_TARGET,
+        "Submitted transaction with hash {}",
+        transaction.hash()
+    );
+    let hash = if is_dry_run {
+        sdk.transaction_api().submit_dry_run_to_vn(transaction).await?
+    } else {
+        sdk.transaction_api().submit_to_vn(transaction).await?
+    };
+
+    if !is_dry_run {
+        context.notifier().notify(TransactionSubmittedEvent { hash });
+    }
+
+    Ok(hash)
+}
+
+/// Returns the input and output shards of a transaction with the given instructions
+fn get_inputs_and_outputs(
+    sdk: &DanWalletSdk<SqliteWalletStore>,
+    instructions: &[Instruction],
+    inputs: Vec<VersionedSubstateAddress>,
+    override_inputs: bool,
+    specific_non_fungible_outputs: Vec<(ResourceAddress, NonFungibleId)>,
+) -> Result<(Vec<ShardId>, Vec<ShardId>), anyhow::Error> {
+    let inputs = if override_inputs {
+        inputs
+    } else {
+        // If we are not overring inputs, we will use the our own
+        // inputs, together with default inputs
+        // sdk.transaction_api().default_inputs().await?
+        let substates = get_referenced_component_addresses(instructions);
+        let loaded_dependent_substates = sdk.substate_api().load_dependent_substates(&substates)?;
+        vec![inputs, loaded_dependent_substates].concat()
+    };
+
+    // TODO: we assume that all inputs will be consumed and produce a new output however this is only the case when the
+    //       object is mutated
+    let mut outputs = inputs
+        .iter()
+        .map(|versioned_addr| ShardId::from_address(&versioned_addr.address, versioned_addr.version + 1))
+        .collect::<Vec<_>>();
+
+    outputs.extend(specific_non_fungible_outputs.into_iter().map(|(resx_addr, id)| {
+        ShardId::from_address(&SubstateAddress::NonFungible(NonFungibleAddress::new(resx_addr, id)), 0)
+    }));
+
+    let inputs = inputs
+        .into_iter()
+        .map(|versioned_addr| ShardId::from_address(&versioned_addr.address, versioned_addr.version))
+        .collect::<Vec<_>>();
+
+    Ok((inputs, outputs))
+}
+
+/// Returns the input and output shards of a built transaction
+fn get_involved_shards(transaction: &Transaction) -> (Vec<ShardId>, Vec<ShardId>) {
+    let (outputs, inputs) = transaction
+        .meta()
+        .involved_objects_iter()
+        .partition::<Vec<_>, _>(|(_, (change, _))| matches!(change, SubstateChange::Create));
+    (
+        inputs.into_iter().map(|(shard, _)| *shard).collect(),
+        outputs.into_iter().map(|(shard, _)| *shard).collect(),
+    )
+}
+
 fn get_referenced_component_addresses(instructions: &[Instruction]) -> Vec<SubstateAddress> {
     let mut components = Vec::new();
     for instruction in instructions {
@@ -216,3 +364,101 @@ fn get_referenced_component_addresses(instructions: &[Instruction]) -> Vec<Subst
     }
     components
 }
+
+#[cfg(test)]
+mod tests {
+    use tari_dan_wallet_sdk::WalletSdkConfig;
+    use tari_template_lib::models::TemplateAddress;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::notify::Notify;
+
+    fn create_context() -> (HandlerContext, TempDir) {
+        let temp = tempfile::tempdir().unwrap();
+        let store = SqliteWalletStore::try_open(temp.path().join("wallet.sqlite")).unwrap();
+        store.run_migrations().unwrap();
+        let sdk = DanWalletSdk::initialize(store, WalletSdkConfig {
+            password: None,
+            validator_node_jrpc_endpoint: "".to_string(),
+        })
+        .unwrap();
+        (HandlerContext::new(sdk, Notify::new(10)), temp)
+    }
+
+    fn wallet_public_key(context: &HandlerContext) -> PublicKey {
+        context
+            .wallet_sdk()
+            .key_manager_api()
+            .get_public_key(key_manager::TRANSACTION_BRANCH, None)
+            .unwrap()
+    }
+
+    async fn export(context: &HandlerContext) -> TransactionBuilder {
+        let response = handle_export(context, TransactionExportRequest {
+            signing_key_index: None,
+            instructions: vec![Instruction::CallFunction {
+                template_address: TemplateAddress::from_array([1; 32]),
+                function: "new".to_string(),
+                args: vec![],
+            }],
+            fee: 1,
+            inputs: vec![],
+            override_inputs: true,
+            new_outputs: 1,
+            specific_non_fungible_outputs: vec![],
+            new_non_fungible_outputs: vec![],
+            new_non_fungible_index_outputs: vec![],
+            min_epoch: None,
+            expires_at_epoch: None,
+        })
+        .await
+        .unwrap();
+        response.partial_transaction
+    }
+
+    async fn co_sign_with(context: &HandlerContext, partial_transaction: TransactionBuilder) -> TransactionBuilder {
+        handle_co_sign(context, TransactionCoSignRequest {
+            signing_key_index: None,
+            partial_transaction,
+        })
+        .await
+        .unwrap()
+        .partial_transaction
+    }
+
+    #[tokio::test]
+    async fn it_exports_a_transaction_co_signed_by_several_wallets() {
+        let (sender, _sender_dir) = create_context();
+        let (co_signer1, _co_signer1_dir) = create_context();
+        let (co_signer2, _co_signer2_dir) = create_context();
+
+        let partial_transaction = export(&sender).await;
+        let partial_transaction = co_sign_with(&co_signer1, partial_transaction).await;
+        // Co-signing again with the same wallet does not add another signature
+        let partial_transaction = co_sign_with(&co_signer1, partial_transaction).await;
+        let partial_transaction = co_sign_with(&co_signer2, partial_transaction).await;
+
+        let signers = partial_transaction.signer_public_keys().cloned().collect::<Vec<_>>();
+        assert_eq!(signers, vec![
+            wallet_public_key(&sender),
+            wallet_public_key(&co_signer1),
+            wallet_public_key(&co_signer2),
+        ]);
+        let transaction = partial_transaction.build();
+        assert!(transaction.verify_all_signatures());
+    }
+
+    #[tokio::test]
+    async fn it_does_not_co_sign_a_transaction_without_a_sender_signature() {
+        let (co_signer, _co_signer_dir) = create_context();
+
+        let result = handle_co_sign(&co_signer, TransactionCoSignRequest {
+            signing_key_index: None,
+            partial_transaction: Transaction::builder(),
+        })
+        .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -57,6 +57,9 @@ async fn handler(Extension(context): Extension<Arc<HandlerContext>>, value: Json
         },
         Some(("transactions", method)) => match method {
             "submit" => call_handler(context, value, transaction::handle_submit).await,
+            "export" => call_handler(context, value, transaction::handle_export).await,
+            "co_sign" => call_handler(context, value, transaction::handle_co_sign).await,
+            "import" => call_handler(context, value, transaction::handle_import).await,
             "get" => call_handler(context, value, transaction::handle_get).await,
             "get_result" => call_handler(context, value, transaction::handle_get_result).await,
             "wait_result" => call_handler(context, value, transaction::handle_wait_result).await,
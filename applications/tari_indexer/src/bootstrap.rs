@@ -107,12 +107,12 @@ pub async fn spawn_services(
         shutdown.clone(),
         consensus_constants,
         // TODO: Remove coupling between scanner and shard store
-        SqliteShardStore::try_create(config.indexer.data_dir.join("unused-shard-store.sqlite"))?,
+        SqliteShardStore::try_create(config.indexer.data_dir.join("unused-shard-store.sqlite"))?.into(),
         true,
         config.indexer.base_layer_scanning_interval,
     );
 
-    let comms = setup_p2p_rpc(config, comms, peer_provider);
+    let comms = setup_p2p_rpc(config, comms, peer_provider, substate_store.clone());
     let comms = comms::spawn_comms_using_transport(comms, p2p_config.transport.clone())
         .await
         .map_err(|e| ExitError::new(ExitCode::ConfigError, format!("Could not spawn using transport: {}", e)))?;
@@ -139,11 +139,12 @@ fn setup_p2p_rpc(
     config: &ApplicationConfig,
     comms: UnspawnedCommsNode,
     peer_provider: CommsPeerProvider,
+    substate_store: SqliteSubstateStore,
 ) -> UnspawnedCommsNode {
     let rpc_server = RpcServer::builder()
         .with_maximum_simultaneous_sessions(config.indexer.p2p.rpc_max_simultaneous_sessions)
         .finish()
-        .add_service(create_validator_node_rpc_service(peer_provider));
+        .add_service(create_validator_node_rpc_service(peer_provider, substate_store));
 
     comms.add_protocol_extension(rpc_server)
 }
@@ -35,6 +35,7 @@ use tari_common::{
     SubConfigPath,
 };
 use tari_comms::multiaddr::Multiaddr;
+use tari_dan_app_utilities::consensus_constants::ConsensusConstantsConfig;
 use tari_engine_types::substate::SubstateAddress;
 use tari_p2p::{P2pConfig, PeerSeedsConfig};
 
@@ -88,6 +89,8 @@ pub struct IndexerConfig {
     /// How often do we want to scan the second layer for new versions
     #[serde(with = "serializers::seconds")]
     pub dan_layer_scanning_internal: Duration,
+    /// Overrides for the consensus constants of the network, which must match those of the validator nodes
+    pub consensus: ConsensusConstantsConfig,
 }
 
 impl IndexerConfig {
@@ -129,6 +132,7 @@ impl Default for IndexerConfig {
             http_ui_address: Some("127.0.0.1:15000".parse().unwrap()),
             address_watchlist: vec![],
             dan_layer_scanning_internal: Duration::from_secs(10),
+            consensus: ConsensusConstantsConfig::default(),
         }
     }
 }
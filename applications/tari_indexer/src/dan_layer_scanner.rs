@@ -24,11 +24,14 @@ use futures::StreamExt;
 use log::*;
 use serde::{Deserialize, Serialize};
 use tari_crypto::ristretto::RistrettoPublicKey;
-use tari_dan_app_grpc::proto::rpc::VnStateSyncResponse;
+use tari_dan_app_grpc::proto::rpc::{GetSubstateEventsRequest, VnStateSyncResponse};
 use tari_dan_app_utilities::epoch_manager::EpochManagerHandle;
-use tari_dan_common_types::{Epoch, ShardId};
+use tari_dan_common_types::{Epoch, PayloadId, ShardId};
 use tari_dan_core::services::{epoch_manager::EpochManager, ValidatorNodeClientFactory};
-use tari_engine_types::substate::{Substate, SubstateAddress};
+use tari_engine_types::{
+    events::Event,
+    substate::{Substate, SubstateAddress},
+};
 use tari_template_lib::{models::NonFungibleIndexAddress, prelude::ResourceAddress};
 
 use crate::p2p::services::rpc_client::TariCommsValidatorNodeClientFactory;
@@ -133,6 +136,51 @@ impl DanLayerScanner {
         }
     }
 
+    /// Returns the events emitted by the transaction that created the given version of the substate
+    pub async fn get_substate_events(
+        &self,
+        substate_address: &SubstateAddress,
+        version: u32,
+    ) -> Option<SubstateEvents> {
+        info!(
+            target: LOG_TARGET,
+            "get_substate_events: {}:{}", substate_address, version
+        );
+
+        let epoch = self.get_current_epoch().await?;
+        let shard_id = ShardId::from_address(substate_address, version);
+        let committee = match self.epoch_manager.get_committee(epoch, shard_id).await {
+            Ok(committee) => committee,
+            Err(e) => {
+                error!(
+                    target: LOG_TARGET,
+                    "Could not get commitee for substate {}:{} on epoch {}: {}", substate_address, version, epoch, e
+                );
+                return None;
+            },
+        };
+
+        for vn_public_key in &committee.members {
+            match self.get_substate_events_from_vn(vn_public_key, shard_id).await {
+                Ok(events) => return Some(events),
+                Err(e) => {
+                    // We ignore a single VN error and keep querying the rest of the committee
+                    error!(
+                        target: LOG_TARGET,
+                        "Could not get events for substate {}:{} from vn {} on epoch {}: {}",
+                        substate_address,
+                        version,
+                        vn_public_key,
+                        epoch,
+                        e
+                    );
+                },
+            }
+        }
+
+        None
+    }
+
     async fn get_current_epoch(&self) -> Option<Epoch> {
         match self.epoch_manager.current_epoch().await {
             Ok(epoch) => Some(epoch),
@@ -252,15 +300,42 @@ impl DanLayerScanner {
 
         Ok(SubstateResult::DoesNotExist)
     }
+
+    async fn get_substate_events_from_vn(
+        &self,
+        vn_public_key: &RistrettoPublicKey,
+        shard_id: ShardId,
+    ) -> Result<SubstateEvents, anyhow::Error> {
+        let mut vn_client = self.validator_node_client_factory.create_client(vn_public_key);
+        let mut vn_rpc_client = vn_client.create_connection().await?;
+
+        let request = GetSubstateEventsRequest {
+            shard_id: Some(shard_id.into()),
+        };
+        let response = vn_rpc_client.get_substate_events(request).await?;
+
+        let created_by_payload_id = PayloadId::try_from(response.created_payload_id)?;
+        let events = response
+            .events
+            .iter()
+            .map(|bytes| tari_bor::decode(bytes))
+            .collect::<Result<_, _>>()?;
+
+        Ok(SubstateEvents {
+            created_by_payload_id,
+            events,
+        })
+    }
 }
 
 fn extract_state_from_vn_response(msg: VnStateSyncResponse) -> Result<SubstateResult, anyhow::Error> {
-    let substate = Substate::from_bytes(&msg.substate)?;
+    let state_root = msg.claimed_state_root()?;
+    let substate_data = msg.into_verified_substate(&state_root)?;
 
-    let result = if msg.destroyed_payload_id.is_empty() {
-        SubstateResult::Up(substate)
+    let result = if substate_data.destroyed_payload_id().is_none() {
+        SubstateResult::Up(substate_data.into_substate())
     } else {
-        SubstateResult::Down(substate)
+        SubstateResult::Down(substate_data.into_substate())
     };
 
     Ok(result)
@@ -279,3 +354,9 @@ pub struct NonFungible {
     pub address: SubstateAddress,
     pub substate: Substate,
 }
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SubstateEvents {
+    pub created_by_payload_id: PayloadId,
+    pub events: Vec<Event>,
+}
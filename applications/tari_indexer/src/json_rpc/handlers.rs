@@ -290,6 +290,18 @@ impl JsonRpcHandlers {
         }
     }
 
+    /// Returns the events with the given topic that were emitted by the payloads that created the watched substates.
+    /// Events of other transactions are not indexed.
+    pub async fn get_watched_substate_events(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let request: GetWatchedSubstateEventsRequest = value.parse_params()?;
+
+        match self.substate_manager.get_events_by_topic(request.topic).await {
+            Ok(events) => Ok(JsonRpcResponse::success(answer_id, events)),
+            Err(_) => Err(Self::generic_error_response(answer_id)),
+        }
+    }
+
     fn parse_substate_address(address_str: &str, answer_id: i64) -> Result<SubstateAddress, JsonRpcResponse> {
         let address = SubstateAddress::from_str(address_str).map_err(|_| Self::generic_error_response(answer_id))?;
         Ok(address)
@@ -334,3 +346,8 @@ pub struct GetNonFungiblesRequest {
     pub start_index: u64,
     pub end_index: u64,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetWatchedSubstateEventsRequest {
+    pub topic: String,
+}
@@ -69,6 +69,7 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         "get_connections" => handlers.get_connections(value).await,
         "get_non_fungible_count" => handlers.get_non_fungible_count(value).await,
         "get_non_fungibles" => handlers.get_non_fungibles(value).await,
+        "get_watched_substate_events" => handlers.get_watched_substate_events(value).await,
         method => Ok(value.method_not_found(method)),
     }
 }
@@ -54,7 +54,7 @@ use tari_common::{
 };
 use tari_comms::peer_manager::PeerFeatures;
 use tari_dan_app_utilities::base_node_client::GrpcBaseNodeClient;
-use tari_dan_core::{consensus_constants::ConsensusConstants, services::BaseNodeClient, storage::DbFactory};
+use tari_dan_core::{services::BaseNodeClient, storage::DbFactory};
 use tari_dan_storage_sqlite::SqliteDbFactory;
 use tari_shutdown::ShutdownSignal;
 use tokio::{task, time};
@@ -84,13 +84,18 @@ pub async fn run_indexer(config: ApplicationConfig, mut shutdown_signal: Shutdow
         .get_or_create_global_db()
         .map_err(|e| ExitError::new(ExitCode::DatabaseError, e))?;
 
+    let consensus_constants = config
+        .indexer
+        .consensus
+        .to_consensus_constants(config.network)
+        .map_err(|e| ExitError::new(ExitCode::ConfigError, e))?;
     let base_node_client = create_base_layer_clients(&config).await?;
     let services: Services = spawn_services(
         &config,
         shutdown_signal.clone(),
         node_identity.clone(),
         global_db,
-        ConsensusConstants::devnet(), // TODO: change this eventually
+        consensus_constants,
     )
     .await?;
 
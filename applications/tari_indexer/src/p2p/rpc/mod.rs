@@ -30,6 +30,8 @@ use tari_comms_rpc_macros::tari_rpc;
 use tari_dan_app_grpc::proto;
 use tari_dan_core::services::PeerProvider;
 
+use crate::substate_storage_sqlite::sqlite_substate_store_factory::SqliteSubstateStore;
+
 #[tari_rpc(protocol_name = b"t/vn/1", server_struct = ValidatorNodeRpcServer, client_struct = ValidatorNodeRpcClient)]
 pub trait ValidatorNodeRpcService: Send + Sync + 'static {
     #[rpc(method = 1)]
@@ -49,11 +51,20 @@ pub trait ValidatorNodeRpcService: Send + Sync + 'static {
         &self,
         request: Request<proto::rpc::VnStateSyncRequest>,
     ) -> Result<Streaming<proto::rpc::VnStateSyncResponse>, RpcStatus>;
+
+    #[rpc(method = 4)]
+    async fn get_substate_events(
+        &self,
+        request: Request<proto::rpc::GetSubstateEventsRequest>,
+    ) -> Result<Response<proto::rpc::GetSubstateEventsResponse>, RpcStatus>;
 }
 
 pub fn create_validator_node_rpc_service<TPeerProvider>(
     peer_provider: TPeerProvider,
+    substate_store: SqliteSubstateStore,
 ) -> ValidatorNodeRpcServer<ValidatorNodeRpcServiceImpl<TPeerProvider>>
-where TPeerProvider: PeerProvider + Clone + Send + Sync + 'static {
-    ValidatorNodeRpcServer::new(ValidatorNodeRpcServiceImpl::new(peer_provider))
+where
+    TPeerProvider: PeerProvider + Clone + Send + Sync + 'static,
+{
+    ValidatorNodeRpcServer::new(ValidatorNodeRpcServiceImpl::new(peer_provider, substate_store))
 }
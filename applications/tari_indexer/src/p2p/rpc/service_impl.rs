@@ -23,25 +23,38 @@
 
 use log::*;
 use tari_comms::protocol::rpc::{Request, Response, RpcStatus, Streaming};
+use tari_crypto::tari_utilities::hex::from_hex;
 use tari_dan_app_grpc::{
     proto,
-    proto::rpc::{VnStateSyncRequest, VnStateSyncResponse},
+    proto::rpc::{GetSubstateEventsRequest, GetSubstateEventsResponse, VnStateSyncRequest, VnStateSyncResponse},
 };
-use tari_dan_common_types::NodeAddressable;
+use tari_dan_common_types::{NodeAddressable, ShardId};
 use tari_dan_core::services::PeerProvider;
+use tari_engine_types::events::Event;
 use tokio::{sync::mpsc, task};
 
 const LOG_TARGET: &str = "tari::dan::p2p::rpc";
 
-use crate::p2p::rpc::ValidatorNodeRpcService;
+use crate::{
+    p2p::rpc::ValidatorNodeRpcService,
+    substate_storage_sqlite::sqlite_substate_store_factory::{
+        SqliteSubstateStore,
+        SubstateStore,
+        SubstateStoreReadTransaction,
+    },
+};
 
 pub struct ValidatorNodeRpcServiceImpl<TPeerProvider> {
     peer_provider: TPeerProvider,
+    substate_store: SqliteSubstateStore,
 }
 
 impl<TPeerProvider: PeerProvider> ValidatorNodeRpcServiceImpl<TPeerProvider> {
-    pub fn new(peer_provider: TPeerProvider) -> Self {
-        Self { peer_provider }
+    pub fn new(peer_provider: TPeerProvider, substate_store: SqliteSubstateStore) -> Self {
+        Self {
+            peer_provider,
+            substate_store,
+        }
     }
 }
 
@@ -97,4 +110,47 @@ where TPeerProvider: PeerProvider + Clone + Send + Sync + 'static
     ) -> Result<Streaming<VnStateSyncResponse>, RpcStatus> {
         todo!()
     }
+
+    /// Returns the events of the payload that created the substate. Only the watched substates are indexed, so any
+    /// other substate is reported as not found.
+    async fn get_substate_events(
+        &self,
+        request: Request<GetSubstateEventsRequest>,
+    ) -> Result<Response<GetSubstateEventsResponse>, RpcStatus> {
+        let msg = request.into_message();
+        let shard_id = msg
+            .shard_id
+            .and_then(|s| ShardId::try_from(s).ok())
+            .ok_or_else(|| RpcStatus::bad_request("Invalid gRPC request: shard_id not provided"))?;
+
+        let (payload_id, rows) = {
+            let mut tx = self
+                .substate_store
+                .create_read_tx()
+                .map_err(|err| RpcStatus::general(&err))?;
+            let payload_id = tx
+                .get_substate_by_shard_id(shard_id.to_string())
+                .map_err(|err| RpcStatus::general(&err))?
+                .ok_or_else(|| RpcStatus::not_found(&format!("Substate {} is not watched", shard_id)))?
+                .created_by_payload_id
+                .ok_or_else(|| RpcStatus::not_found(&format!("The events of substate {} are not indexed", shard_id)))?;
+            let rows = tx
+                .get_events_by_payload_id(payload_id.clone())
+                .map_err(|err| RpcStatus::general(&err))?;
+            (payload_id, rows)
+        };
+
+        let events = rows
+            .iter()
+            .map(|row| {
+                let event: Event = serde_json::from_str(&row.data).map_err(|err| RpcStatus::general(&err))?;
+                tari_bor::encode(&event).map_err(|err| RpcStatus::general(&err))
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Response::new(GetSubstateEventsResponse {
+            created_payload_id: from_hex(&payload_id).map_err(|err| RpcStatus::general(&err))?,
+            events,
+        }))
+    }
 }
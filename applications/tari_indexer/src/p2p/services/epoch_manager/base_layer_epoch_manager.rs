@@ -169,6 +169,8 @@ impl BaseLayerEpochManager {
         let db_epoch = DbEpoch {
             epoch: epoch_height,
             validator_node_mr: header.validator_node_mr.to_vec(),
+            timestamp: header.timestamp.as_u64(),
+            block_hash: header.hash().to_vec(),
         };
 
         let mut tx = self.global_db.create_transaction()?;
@@ -139,6 +139,14 @@ impl EpochManagerService {
             EpochManagerRequest::GetValidatorShardKey { .. } => todo!(),
             EpochManagerRequest::GetValidatorNodeBMT { .. } => todo!(),
             EpochManagerRequest::GetValidatorNodeMerkleRoot { .. } => todo!(),
+            // The indexer does not track epoch timestamps
+            EpochManagerRequest::GetEpochTimestamp { reply, .. } => {
+                handle(reply, Err(EpochManagerError::UnexpectedRequest))
+            },
+            // The indexer does not track epoch beacons
+            EpochManagerRequest::GetEpochBeacon { reply, .. } => {
+                handle(reply, Err(EpochManagerError::UnexpectedRequest))
+            },
             EpochManagerRequest::IsValidatorInCommitteeForCurrentEpoch { .. } => todo!(),
             EpochManagerRequest::FilterToLocalShards { .. } => todo!(),
             EpochManagerRequest::Subscribe { .. } => todo!(),
@@ -24,12 +24,17 @@ use std::{str::FromStr, sync::Arc};
 
 use anyhow::anyhow;
 use log::info;
-use tari_engine_types::substate::{Substate, SubstateAddress};
+use tari_dan_common_types::{PayloadId, ShardId};
+use tari_engine_types::{
+    events::Event,
+    substate::{Substate, SubstateAddress},
+};
 
 use crate::{
-    dan_layer_scanner::{DanLayerScanner, NonFungible},
+    dan_layer_scanner::{DanLayerScanner, NonFungible, SubstateEvents},
     substate_storage_sqlite::{
         models::{
+            event::{Event as EventRow, NewEvent},
             non_fungible_index::{IndexedNftSubstate, NewNonFungibleIndex},
             substate::{NewSubstate, Substate as SubstateRow},
         },
@@ -74,9 +79,20 @@ impl SubstateManager {
             vec![]
         };
 
+        // get the events emitted by the transaction that created this version of the substate
+        let events = self
+            .dan_layer_scanner
+            .get_substate_events(substate_address, substate.version())
+            .await;
+
         // store the substate in the database
         let mut tx = self.substate_store.create_write_tx()?;
-        store_substate_in_db(&mut tx, substate_address, &substate)?;
+        store_substate_in_db(
+            &mut tx,
+            substate_address,
+            &substate,
+            events.as_ref().map(|e| &e.created_by_payload_id),
+        )?;
         info!(
             target: LOG_TARGET,
             "Added substate {} with version {} to the database",
@@ -84,10 +100,15 @@ impl SubstateManager {
             substate.version()
         );
 
+        // store the events of the substate in the database
+        if let Some(events) = events {
+            store_events_in_db(&mut tx, &events)?;
+        }
+
         // store the associated non fungibles in the database
         for nft in non_fungibles {
             // store the substate of the nft in the databas
-            store_substate_in_db(&mut tx, &nft.address, &nft.substate)?;
+            store_substate_in_db(&mut tx, &nft.address, &nft.substate, None)?;
 
             // store the index of the nft
             let nft_index_db_row = map_nft_index_to_db_row(substate_address, &nft)?;
@@ -197,6 +218,19 @@ impl SubstateManager {
         Ok(nfts)
     }
 
+    /// Returns the events with the given topic. The indexer does not scan every transaction: only the events emitted by
+    /// the payloads that created the watched substates (at the versions that were fetched) are indexed.
+    pub async fn get_events_by_topic(&self, topic: String) -> Result<Vec<Event>, anyhow::Error> {
+        let mut tx = self.substate_store.create_read_tx()?;
+        let rows = tx.get_events_by_topic(topic)?;
+
+        let mut events = vec![];
+        for row in rows {
+            events.push(map_db_row_to_event(&row)?);
+        }
+        Ok(events)
+    }
+
     pub async fn scan_and_update_substates(&self) -> Result<(), anyhow::Error> {
         let addresses = self.get_all_addresses_from_db().await?;
 
@@ -213,24 +247,43 @@ fn store_substate_in_db(
     tx: &mut SqliteSubstateStoreWriteTransaction,
     address: &SubstateAddress,
     substate: &Substate,
+    created_by_payload_id: Option<&PayloadId>,
 ) -> Result<(), anyhow::Error> {
-    let substate_row = map_substate_to_db_row(address, substate)?;
+    let substate_row = map_substate_to_db_row(address, substate, created_by_payload_id)?;
     tx.set_substate(substate_row)?;
 
     Ok(())
 }
 
+fn store_events_in_db(
+    tx: &mut SqliteSubstateStoreWriteTransaction,
+    substate_events: &SubstateEvents,
+) -> Result<(), anyhow::Error> {
+    for (idx, event) in substate_events.events.iter().enumerate() {
+        let event_row = map_event_to_db_row(&substate_events.created_by_payload_id.to_string(), idx, event)?;
+        tx.add_event(event_row)?;
+    }
+
+    Ok(())
+}
+
 fn map_db_row_to_substate(row: &SubstateRow) -> Result<Substate, anyhow::Error> {
     let substate: Substate = serde_json::from_str(&row.data)?;
     Ok(substate)
 }
 
-fn map_substate_to_db_row(address: &SubstateAddress, substate: &Substate) -> Result<NewSubstate, anyhow::Error> {
+fn map_substate_to_db_row(
+    address: &SubstateAddress,
+    substate: &Substate,
+    created_by_payload_id: Option<&PayloadId>,
+) -> Result<NewSubstate, anyhow::Error> {
     let pretty_data = serde_json::to_string_pretty(&substate)?;
     let row = NewSubstate {
         address: address.to_address_string(),
         version: i64::from(substate.version()),
         data: pretty_data,
+        shard_id: Some(ShardId::from_address(address, substate.version()).to_string()),
+        created_by_payload_id: created_by_payload_id.map(|id| id.to_string()),
     };
     Ok(row)
 }
@@ -255,3 +308,19 @@ fn map_nft_index_to_db_row(
         non_fungible_address: nft.address.to_address_string(),
     })
 }
+
+fn map_db_row_to_event(row: &EventRow) -> Result<Event, anyhow::Error> {
+    let event: Event = serde_json::from_str(&row.data)?;
+    Ok(event)
+}
+
+fn map_event_to_db_row(payload_id: &str, idx: usize, event: &Event) -> Result<NewEvent, anyhow::Error> {
+    Ok(NewEvent {
+        payload_id: payload_id.to_string(),
+        idx: idx as i32,
+        topic: event.topic.clone(),
+        template_address: event.template_address.to_string(),
+        component_address: event.component_address.map(|addr| addr.to_string()),
+        data: serde_json::to_string_pretty(event)?,
+    })
+}
@@ -0,0 +1,17 @@
+-- the events emitted by the transactions that created the watched substates
+create table events
+(
+    id                  integer   not NULL primary key AUTOINCREMENT,
+    payload_id          text      not NULL,
+    idx                 integer   not NULL,
+    topic               text      not NULL,
+    template_address    text      not NULL,
+    component_address   text      NULL,
+    data                text      not NULL
+);
+
+-- The same event may be fetched more than once when substates are rescanned
+create unique index uniq_events on events (payload_id, idx);
+
+-- DB index for faster queries by topic
+create index events_topic on events (topic);
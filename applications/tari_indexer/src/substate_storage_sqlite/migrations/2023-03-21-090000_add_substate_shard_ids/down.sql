@@ -0,0 +1,3 @@
+drop index substates_shard_id;
+alter table substates drop column created_by_payload_id;
+alter table substates drop column shard_id;
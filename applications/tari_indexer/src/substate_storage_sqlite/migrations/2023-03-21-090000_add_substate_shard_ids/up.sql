@@ -0,0 +1,6 @@
+-- the shard of the current version of each watched substate, and the payload that created it
+alter table substates add column shard_id text NULL;
+alter table substates add column created_by_payload_id text NULL;
+
+-- DB index for faster queries by shard
+create index substates_shard_id on substates (shard_id);
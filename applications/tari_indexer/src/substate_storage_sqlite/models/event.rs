@@ -0,0 +1,27 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use crate::substate_storage_sqlite::schema::*;
+
+#[derive(Debug, Identifiable, Queryable)]
+#[diesel(table_name = events)]
+pub struct Event {
+    pub id: i32,
+    pub payload_id: String,
+    pub idx: i32,
+    pub topic: String,
+    pub template_address: String,
+    pub component_address: Option<String>,
+    pub data: String,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = events)]
+pub struct NewEvent {
+    pub payload_id: String,
+    pub idx: i32,
+    pub topic: String,
+    pub template_address: String,
+    pub component_address: Option<String>,
+    pub data: String,
+}
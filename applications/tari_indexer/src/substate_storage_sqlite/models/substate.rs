@@ -28,12 +28,18 @@ pub struct Substate {
     pub address: String,
     pub version: i64,
     pub data: String,
+    pub shard_id: Option<String>,
+    pub created_by_payload_id: Option<String>,
 }
 
 #[derive(Debug, Insertable, AsChangeset)]
 #[diesel(table_name = substates)]
+#[diesel(treat_none_as_null = true)]
 pub struct NewSubstate {
     pub address: String,
     pub version: i64,
     pub data: String,
+    pub shard_id: Option<String>,
+    /// None if the events of the payload that created this version could not be fetched
+    pub created_by_payload_id: Option<String>,
 }
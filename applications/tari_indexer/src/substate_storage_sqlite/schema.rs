@@ -20,12 +20,26 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+diesel::table! {
+    events (id) {
+        id -> Integer,
+        payload_id -> Text,
+        idx -> Integer,
+        topic -> Text,
+        template_address -> Text,
+        component_address -> Nullable<Text>,
+        data -> Text,
+    }
+}
+
 diesel::table! {
     substates (id) {
         id -> Integer,
         address -> Text,
         version -> BigInt,
         data -> Text,
+        shard_id -> Nullable<Text>,
+        created_by_payload_id -> Nullable<Text>,
     }
 }
 
@@ -38,4 +52,4 @@ diesel::table! {
     }
 }
 
-diesel::allow_tables_to_appear_in_same_query!(substates, non_fungible_indexes);
+diesel::allow_tables_to_appear_in_same_query!(events, substates, non_fungible_indexes);
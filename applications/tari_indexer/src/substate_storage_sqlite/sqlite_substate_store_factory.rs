@@ -39,7 +39,10 @@ use tari_dan_core::storage::StorageError;
 use tari_dan_storage_sqlite::{error::SqliteStorageError, SqliteTransaction};
 use thiserror::Error;
 
-use super::models::non_fungible_index::{IndexedNftSubstate, NewNonFungibleIndex};
+use super::models::{
+    event::{Event, NewEvent},
+    non_fungible_index::{IndexedNftSubstate, NewNonFungibleIndex},
+};
 use crate::{
     diesel_migrations::MigrationHarness,
     substate_storage_sqlite::models::substate::{NewSubstate, Substate},
@@ -168,6 +171,7 @@ impl<'a> SqliteSubstateStoreReadTransaction<'a> {
 
 pub trait SubstateStoreReadTransaction {
     fn get_substate(&mut self, address: String) -> Result<Option<Substate>, StorageError>;
+    fn get_substate_by_shard_id(&mut self, shard_id: String) -> Result<Option<Substate>, StorageError>;
     fn get_all_addresses(&mut self) -> Result<Vec<(String, i64)>, StorageError>;
     fn get_all_substates(&mut self) -> Result<Vec<Substate>, StorageError>;
     fn get_non_fungible_count(&mut self, resource_address: String) -> Result<i64, StorageError>;
@@ -177,6 +181,8 @@ pub trait SubstateStoreReadTransaction {
         start_idx: i32,
         end_idx: i32,
     ) -> Result<Vec<IndexedNftSubstate>, StorageError>;
+    fn get_events_by_topic(&mut self, topic: String) -> Result<Vec<Event>, StorageError>;
+    fn get_events_by_payload_id(&mut self, payload_id: String) -> Result<Vec<Event>, StorageError>;
 }
 
 impl SubstateStoreReadTransaction for SqliteSubstateStoreReadTransaction<'_> {
@@ -194,6 +200,20 @@ impl SubstateStoreReadTransaction for SqliteSubstateStoreReadTransaction<'_> {
         Ok(substate)
     }
 
+    fn get_substate_by_shard_id(&mut self, shard_id: String) -> Result<Option<Substate>, StorageError> {
+        use crate::substate_storage_sqlite::schema::substates;
+
+        let substate = substates::table
+            .filter(substates::shard_id.eq(shard_id))
+            .first(self.connection())
+            .optional()
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("get_substate_by_shard_id: {}", e),
+            })?;
+
+        Ok(substate)
+    }
+
     fn get_all_addresses(&mut self) -> Result<Vec<(String, i64)>, StorageError> {
         use crate::substate_storage_sqlite::schema::substates;
 
@@ -262,6 +282,34 @@ impl SubstateStoreReadTransaction for SqliteSubstateStoreReadTransaction<'_> {
 
         Ok(res)
     }
+
+    fn get_events_by_topic(&mut self, topic: String) -> Result<Vec<Event>, StorageError> {
+        use crate::substate_storage_sqlite::schema::events;
+
+        let events = events::table
+            .filter(events::topic.eq(topic))
+            .order_by(events::id.asc())
+            .get_results(self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("get_events_by_topic: {}", e),
+            })?;
+
+        Ok(events)
+    }
+
+    fn get_events_by_payload_id(&mut self, payload_id: String) -> Result<Vec<Event>, StorageError> {
+        use crate::substate_storage_sqlite::schema::events;
+
+        let events = events::table
+            .filter(events::payload_id.eq(payload_id))
+            .order_by(events::idx.asc())
+            .get_results(self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("get_events_by_payload_id: {}", e),
+            })?;
+
+        Ok(events)
+    }
 }
 
 pub struct SqliteSubstateStoreWriteTransaction<'a> {
@@ -288,6 +336,7 @@ pub trait SubstateStoreWriteTransaction {
     fn delete_substate(&mut self, address: String) -> Result<(), StorageError>;
     fn clear_substates(&mut self) -> Result<(), StorageError>;
     fn add_non_fungible_index(&mut self, new_nft_index: NewNonFungibleIndex) -> Result<(), StorageError>;
+    fn add_event(&mut self, new_event: NewEvent) -> Result<(), StorageError>;
 }
 
 impl SubstateStoreWriteTransaction for SqliteSubstateStoreWriteTransaction<'_> {
@@ -357,7 +406,13 @@ impl SubstateStoreWriteTransaction for SqliteSubstateStoreWriteTransaction<'_> {
     }
 
     fn clear_substates(&mut self) -> Result<(), StorageError> {
-        use crate::substate_storage_sqlite::schema::substates;
+        use crate::substate_storage_sqlite::schema::{events, substates};
+
+        diesel::delete(events::table)
+            .execute(&mut *self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("clear_substates error: {}", e),
+            })?;
 
         diesel::delete(substates::table)
             .execute(&mut *self.connection())
@@ -387,6 +442,20 @@ impl SubstateStoreWriteTransaction for SqliteSubstateStoreWriteTransaction<'_> {
 
         Ok(())
     }
+
+    fn add_event(&mut self, new_event: NewEvent) -> Result<(), StorageError> {
+        use crate::substate_storage_sqlite::schema::events;
+
+        // the event may already be stored if the substate was scanned before
+        diesel::insert_or_ignore_into(events::table)
+            .values(&new_event)
+            .execute(&mut *self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("add_event error: {}", e),
+            })?;
+
+        Ok(())
+    }
 }
 
 impl<'a> Deref for SqliteSubstateStoreWriteTransaction<'a> {
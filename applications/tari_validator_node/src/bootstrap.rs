@@ -41,6 +41,7 @@ use tari_dan_app_utilities::{
     base_layer_scanner,
     base_node_client::GrpcBaseNodeClient,
     epoch_manager::EpochManagerHandle,
+    shard_store::ShardStoreBackend,
     template_manager::TemplateManagerHandle,
 };
 use tari_dan_common_types::{Epoch, NodeAddressable, NodeHeight, PayloadId, QuorumCertificate, ShardId, TreeNodeHash};
@@ -54,19 +55,20 @@ use tari_dan_core::{
     workers::events::{EventSubscription, HotStuffEvent},
 };
 use tari_dan_storage::global::GlobalDb;
-use tari_dan_storage_sqlite::{global::SqliteGlobalDbAdapter, sqlite_shard_store_factory::SqliteShardStore};
+use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
 use tari_engine_types::{
     resource::Resource,
     substate::{Substate, SubstateAddress},
 };
 use tari_shutdown::ShutdownSignal;
 use tari_template_lib::{
+    auth::{AccessRule, ResourceAccessRules},
     constants::{CONFIDENTIAL_TARI_RESOURCE_ADDRESS, PUBLIC_IDENTITY_RESOURCE_ADDRESS},
     models::Metadata,
     prelude::ResourceType,
     resource::TOKEN_SYMBOL,
 };
-use tokio::task::JoinHandle;
+use tokio::{sync::watch, task::JoinHandle};
 
 use crate::{
     comms,
@@ -76,6 +78,7 @@ use crate::{
         services::{
             comms_peer_provider::CommsPeerProvider,
             epoch_manager,
+            epoch_manager::StateSyncProgress,
             hotstuff,
             mempool,
             mempool::MempoolHandle,
@@ -83,6 +86,8 @@ use crate::{
             messaging::DanMessageReceivers,
             networking,
             networking::NetworkingHandle,
+            pruning,
+            pruning::PruningHandle,
             rpc_client::TariCommsValidatorNodeClientFactory,
             template_manager,
             template_manager::TemplateManager,
@@ -132,6 +137,7 @@ pub async fn spawn_services(
         rx_consensus_message,
         rx_vote_message,
         rx_new_transaction_message,
+        rx_new_transaction_batch_message,
         rx_network_announce,
         rx_recovery_message,
     } = message_receivers;
@@ -144,15 +150,26 @@ pub async fn spawn_services(
         outbound_messaging.clone(),
         peer_provider.clone(),
         comms.connectivity(),
+        consensus_constants.clone(),
     );
     handles.push(join_handle);
 
     // Connect to shard db
-    let shard_store = SqliteShardStore::try_create(config.validator_node.state_db_path())?;
+    let shard_store = ShardStoreBackend::try_create(
+        config.validator_node.shard_store_type,
+        config.validator_node.state_db_path(),
+    )?;
+    info!(
+        target: LOG_TARGET,
+        "🗄️ Using {} shard store at {}",
+        shard_store.store_type(),
+        config.validator_node.state_db_path().display()
+    );
     shard_store.with_write_tx(|tx| bootstrap_state(tx))?;
 
     // Epoch manager
     let validator_node_client_factory = TariCommsValidatorNodeClientFactory::new(comms.connectivity());
+    let (tx_state_sync_progress, state_sync_progress) = watch::channel(None);
     let (epoch_manager, join_handle) = epoch_manager::spawn(
         global_db.clone(),
         shard_store.clone(),
@@ -161,6 +178,7 @@ pub async fn spawn_services(
         shutdown.clone(),
         node_identity.clone(),
         validator_node_client_factory.clone(),
+        tx_state_sync_progress,
     );
     handles.push(join_handle);
 
@@ -169,13 +187,29 @@ pub async fn spawn_services(
     let (template_manager_service, join_handle) = template_manager::spawn(template_manager.clone(), shutdown.clone());
     handles.push(join_handle);
 
+    // Payload processor
+    let payload_processor =
+        TariDanPayloadProcessor::new(template_manager.clone(), consensus_constants.fee_table.clone());
+
+    let dry_run_transaction_processor = DryRunTransactionProcessor::new(
+        epoch_manager.clone(),
+        payload_processor.clone(),
+        shard_store.clone(),
+        validator_node_client_factory,
+        node_identity.clone(),
+    );
+
     // Mempool
     let (mempool, join_handle) = mempool::spawn(
+        config.validator_node.mempool.clone(),
         rx_new_transaction_message,
+        rx_new_transaction_batch_message,
         outbound_messaging.clone(),
         epoch_manager.clone(),
         node_identity.clone(),
-        template_manager.clone(),
+        template_manager,
+        shard_store.clone(),
+        dry_run_transaction_processor.clone(),
     );
     handles.push(join_handle);
 
@@ -186,16 +220,13 @@ pub async fn spawn_services(
         epoch_manager.clone(),
         template_manager_service.clone(),
         shutdown.clone(),
-        consensus_constants,
+        consensus_constants.clone(),
         shard_store.clone(),
         config.validator_node.scan_base_layer,
         config.validator_node.base_layer_scanning_interval,
     );
     handles.push(join_handle);
 
-    // Payload processor
-    let payload_processor = TariDanPayloadProcessor::new(template_manager);
-
     // Consensus
     let (hotstuff_events, waiter_join_handle, service_join_handle) = hotstuff::try_spawn(
         node_identity.clone(),
@@ -203,24 +234,34 @@ pub async fn spawn_services(
         outbound_messaging,
         epoch_manager.clone(),
         mempool.clone(),
-        payload_processor.clone(),
+        payload_processor,
         rx_consensus_message,
         rx_recovery_message,
         rx_vote_message,
+        consensus_constants.clone(),
         shutdown.clone(),
     );
     handles.push(waiter_join_handle);
     handles.push(service_join_handle);
 
-    let dry_run_transaction_processor = DryRunTransactionProcessor::new(
-        epoch_manager.clone(),
-        payload_processor,
+    // Pruning
+    let (pruning, join_handle) = pruning::spawn(
+        config.validator_node.pruning.clone(),
         shard_store.clone(),
-        validator_node_client_factory,
-        node_identity.clone(),
+        epoch_manager.clone(),
+        &consensus_constants,
+        shutdown.clone(),
     );
+    handles.push(join_handle);
 
-    let comms = setup_p2p_rpc(config, comms, peer_provider, shard_store.clone(), mempool.clone());
+    let comms = setup_p2p_rpc(
+        config,
+        comms,
+        peer_provider,
+        shard_store.clone(),
+        mempool.clone(),
+        consensus_constants,
+    );
     let comms = comms::spawn_comms_using_transport(comms, p2p_config.transport.clone())
         .await
         .map_err(|e| ExitError::new(ExitCode::ConfigError, format!("Could not spawn using transport: {}", e)))?;
@@ -246,6 +287,8 @@ pub async fn spawn_services(
         hotstuff_events,
         shard_store,
         dry_run_transaction_processor,
+        pruning,
+        state_sync_progress,
         handles,
     })
 }
@@ -274,8 +317,10 @@ pub struct Services {
     pub epoch_manager: EpochManagerHandle,
     pub template_manager: TemplateManagerHandle,
     pub hotstuff_events: EventSubscription<HotStuffEvent>,
-    pub shard_store: SqliteShardStore,
+    pub shard_store: ShardStoreBackend,
     pub dry_run_transaction_processor: DryRunTransactionProcessor,
+    pub pruning: PruningHandle,
+    pub state_sync_progress: watch::Receiver<Option<StateSyncProgress>>,
     pub handles: Vec<JoinHandle<Result<(), anyhow::Error>>>,
 }
 
@@ -295,8 +340,9 @@ fn setup_p2p_rpc(
     config: &ApplicationConfig,
     comms: UnspawnedCommsNode,
     peer_provider: CommsPeerProvider,
-    shard_store_store: SqliteShardStore,
+    shard_store_store: ShardStoreBackend,
     mempool: MempoolHandle,
+    consensus_constants: ConsensusConstants,
 ) -> UnspawnedCommsNode {
     let rpc_server = RpcServer::builder()
         .with_maximum_simultaneous_sessions(config.validator_node.p2p.rpc_max_simultaneous_sessions)
@@ -305,6 +351,7 @@ fn setup_p2p_rpc(
             peer_provider,
             shard_store_store,
             mempool,
+            consensus_constants,
         ));
 
     comms.add_protocol_extension(rpc_server)
@@ -323,12 +370,20 @@ where
     let address = SubstateAddress::Resource(PUBLIC_IDENTITY_RESOURCE_ADDRESS);
     let shard_id = ShardId::from_address(&address, 0);
     if tx.get_substate_states(&[shard_id])?.is_empty() {
-        // Create the resource for public identity
+        // Create the resource for public identity. Badges are never minted or burnt, proofs of them are created by the
+        // engine.
         tx.insert_substates(SubstateShardData::new(
             shard_id,
             address,
             0,
-            Substate::new(0, Resource::new(ResourceType::NonFungible, Default::default())),
+            Substate::new(
+                0,
+                Resource::new(
+                    ResourceType::NonFungible,
+                    ResourceAccessRules::deny_all(),
+                    Default::default(),
+                ),
+            ),
             NodeHeight(0),
             None,
             TreeNodeHash::zero(),
@@ -347,12 +402,14 @@ where
         let mut metadata = Metadata::new();
         // TODO: decide on symbol for L2 tari
         metadata.insert(TOKEN_SYMBOL, "tXTR2".to_string());
+        // Layer two tari can only be created by claiming a layer one burn
+        let access_rules = ResourceAccessRules::new().mintable(AccessRule::DenyAll);
 
         tx.insert_substates(SubstateShardData::new(
             shard_id,
             address,
             0,
-            Substate::new(0, Resource::new(ResourceType::Confidential, metadata)),
+            Substate::new(0, Resource::new(ResourceType::Confidential, access_rules, metadata)),
             NodeHeight(0),
             None,
             TreeNodeHash::zero(),
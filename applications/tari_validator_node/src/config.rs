@@ -35,9 +35,10 @@ use tari_common::{
     SubConfigPath,
 };
 use tari_comms::multiaddr::Multiaddr;
+use tari_dan_app_utilities::{consensus_constants::ConsensusConstantsConfig, shard_store::ShardStoreType};
 use tari_p2p::{P2pConfig, PeerSeedsConfig};
 
-use crate::p2p::services::template_manager::TemplateConfig;
+use crate::p2p::services::{mempool::MempoolConfig, pruning::PruningConfig, template_manager::TemplateConfig};
 
 #[derive(Debug, Clone)]
 pub struct ApplicationConfig {
@@ -95,11 +96,23 @@ pub struct ValidatorNodeConfig {
     pub auto_register: bool,
     /// Template config
     pub templates: TemplateConfig,
+    /// Pruning of consensus data for old committed payloads
+    pub pruning: PruningConfig,
+    /// Mempool settings, including batching of submitted transactions into a single consensus instance
+    pub mempool: MempoolConfig,
+    /// The database used for the shard store, either "sqlite" or "lmdb"
+    pub shard_store_type: ShardStoreType,
+    /// Overrides for the consensus constants of the network
+    pub consensus: ConsensusConstantsConfig,
 }
 
 impl ValidatorNodeConfig {
+    /// The path of the shard store. This is a file for SQLite and a directory for LMDB.
     pub fn state_db_path(&self) -> PathBuf {
-        self.data_dir.join("state.db")
+        match self.shard_store_type {
+            ShardStoreType::Sqlite => self.data_dir.join("state.db"),
+            ShardStoreType::Lmdb => self.data_dir.join("state_lmdb"),
+        }
     }
 
     pub fn set_base_path<P: AsRef<Path>>(&mut self, base_path: P) {
@@ -143,6 +156,10 @@ impl Default for ValidatorNodeConfig {
             http_ui_address: Some("127.0.0.1:5000".parse().unwrap()),
             auto_register: true,
             templates: TemplateConfig::default(),
+            pruning: PruningConfig::default(),
+            mempool: MempoolConfig::default(),
+            shard_store_type: ShardStoreType::default(),
+            consensus: ConsensusConstantsConfig::default(),
         }
     }
 }
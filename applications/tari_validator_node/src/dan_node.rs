@@ -27,7 +27,6 @@ use tari_dan_core::{
     workers::events::HotStuffEvent,
 };
 use tari_shutdown::ShutdownSignal;
-use tari_template_lib::Hash;
 
 use crate::{p2p::services::networking::NetworkingService, Services};
 
@@ -73,8 +72,9 @@ impl DanNode {
                 },
 
                 Ok(event) = hotstuff_events.recv() => {
-                    if let HotStuffEvent::OnFinalized(qc, _) = event {
-                        let transaction_hash = Hash::from(qc.payload_id().into_array());
+                    if let HotStuffEvent::OnFinalized(_, result) = event {
+                        // A batched payload is finalized per transaction, so the result identifies the transaction
+                        let transaction_hash = result.transaction_hash;
                         info!(target: LOG_TARGET, "🏁 Removing finalized transaction {} from mempool", transaction_hash);
                         if let Err(err) = self.services.mempool.remove_transaction(transaction_hash).await {
                             error!(target: LOG_TARGET, "Failed to remove transaction from mempool: {}", err);
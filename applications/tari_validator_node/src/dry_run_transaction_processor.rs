@@ -20,17 +20,13 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{
-    collections::HashMap,
-    convert::{TryFrom, TryInto},
-    sync::Arc,
-};
+use std::{collections::HashMap, convert::TryFrom, sync::Arc};
 
 use futures::StreamExt;
 use log::info;
 use tari_comms::{protocol::rpc::RpcStatus, NodeIdentity};
 use tari_dan_app_grpc::proto::rpc::VnStateSyncResponse;
-use tari_dan_app_utilities::epoch_manager::EpochManagerHandle;
+use tari_dan_app_utilities::{epoch_manager::EpochManagerHandle, shard_store::ShardStoreBackend};
 use tari_dan_common_types::{Epoch, ObjectPledge, PayloadId, ShardId, SubstateState};
 use tari_dan_core::{
     models::{Payload, TariDanPayload},
@@ -47,13 +43,14 @@ use tari_dan_core::{
     },
 };
 use tari_dan_engine::runtime::ConsensusContext;
-use tari_dan_storage_sqlite::sqlite_shard_store_factory::SqliteShardStore;
 use tari_engine_types::{
-    commit_result::FinalizeResult,
-    substate::{Substate, SubstateAddress},
+    commit_result::{FinalizeResult, RejectReason, TransactionResult},
+    substate::{SubstateAddress, SubstateDiff},
 };
-use tari_transaction::Transaction;
+use tari_template_lib::{models::NonFungibleId, Hash};
+use tari_transaction::{id_provider::IdProvider, SubstateRequirements, Transaction};
 use thiserror::Error;
+use tokio::task::{self, JoinError};
 
 use crate::{
     p2p::services::{rpc_client::TariCommsValidatorNodeClientFactory, template_manager::TemplateManager},
@@ -74,13 +71,17 @@ pub enum DryRunTransactionProcessorError {
     ValidatorNodeClient(#[from] ValidatorNodeClientError),
     #[error("Rpc error: {0}")]
     RpcRequestFailed(#[from] RpcStatus),
+    #[error("Transaction rejected: {0}")]
+    TransactionRejected(RejectReason),
+    #[error("Execution task failed: {0}")]
+    ExecutionTaskFailed(#[from] JoinError),
 }
 
 #[derive(Clone)]
 pub struct DryRunTransactionProcessor {
     epoch_manager: EpochManagerHandle,
     payload_processor: TariDanPayloadProcessor<TemplateManager>,
-    shard_store: SqliteShardStore,
+    shard_store: ShardStoreBackend,
     validator_node_client_factory: TariCommsValidatorNodeClientFactory,
     node_identity: Arc<NodeIdentity>,
 }
@@ -89,7 +90,7 @@ impl DryRunTransactionProcessor {
     pub fn new(
         epoch_manager: EpochManagerHandle,
         payload_processor: TariDanPayloadProcessor<TemplateManager>,
-        shard_store: SqliteShardStore,
+        shard_store: ShardStoreBackend,
         validator_node_client_factory: TariCommsValidatorNodeClientFactory,
         node_identity: Arc<NodeIdentity>,
     ) -> Self {
@@ -102,12 +103,75 @@ impl DryRunTransactionProcessor {
         }
     }
 
+    /// Aborts dry runs that consume more than the given fuel. A dry run that timed out keeps running on its blocking
+    /// thread, so this bounds the work done for transactions that never finish.
+    pub fn with_fuel_limit(mut self, fuel_limit: u64) -> Self {
+        self.payload_processor = self.payload_processor.with_fuel_limit(fuel_limit);
+        self
+    }
+
     pub async fn process_transaction(
         &self,
         transaction: Transaction,
     ) -> Result<FinalizeResult, DryRunTransactionProcessorError> {
+        let payload = TariDanPayload::new(transaction);
+        let shard_pledges = self.get_pledges(&payload).await?;
+
+        // execute the payload in the WASM engine and return the result. Execution is CPU bound, so it runs on a
+        // blocking thread to keep it from stalling the async runtime.
+        let consensus_context = self.get_consensus_context().await?;
+        let payload_processor = self.payload_processor.clone();
+        let result =
+            task::spawn_blocking(move || payload_processor.process_payload(payload, shard_pledges, consensus_context))
+                .await??;
+        Ok(result)
+    }
+
+    /// Executes an unsigned transaction against the substates of its involved shards and returns the substates that it
+    /// requires. The involved shards only need to include the inputs that the transaction could read, the exact inputs
+    /// and outputs are determined by executing it. Substates outside of the involved shards are not pledged on
+    /// demand, so discovery can only narrow the declared inputs and fails if the transaction needs any others.
+    pub async fn discover_substates(
+        &self,
+        transaction: Transaction,
+    ) -> Result<SubstateRequirements, DryRunTransactionProcessorError> {
+        let id_seed = transaction.id_seed();
+        let payload = TariDanPayload::new(transaction);
+        let shard_pledges = self.get_pledges(&payload).await?;
+        let pledged_shards = shard_pledges
+            .values()
+            .filter_map(|pledge| match &pledge.current_state {
+                SubstateState::Up { address, .. } => Some((address.clone(), pledge.shard_id)),
+                SubstateState::DoesNotExist | SubstateState::Down { .. } => None,
+            })
+            .collect::<HashMap<_, _>>();
+
+        let consensus_context = self.get_consensus_context().await?;
+        let payload_processor = self.payload_processor.clone();
+        let (result, reads) = task::spawn_blocking(move || {
+            payload_processor.execute_and_track_reads(payload.into_payload(), shard_pledges, consensus_context)
+        })
+        .await??;
+        let diff = match result.result {
+            TransactionResult::Accept(diff) => diff,
+            result => {
+                let reason = result
+                    .reject()
+                    .cloned()
+                    .unwrap_or_else(|| RejectReason::ExecutionFailure("Transaction was not accepted".to_string()));
+                return Err(DryRunTransactionProcessorError::TransactionRejected(reason));
+            },
+        };
+
+        Ok(get_substate_requirements(id_seed, &diff, &reads, &pledged_shards))
+    }
+
+    /// Returns the pledges for all involved shards of the payload
+    async fn get_pledges(
+        &self,
+        payload: &TariDanPayload,
+    ) -> Result<HashMap<ShardId, ObjectPledge>, DryRunTransactionProcessorError> {
         // get the list of involved shards for the transaction
-        let payload = TariDanPayload::new(transaction.clone());
         let involved_shards = payload.involved_shards();
 
         // get the pledges for all local shards
@@ -125,17 +189,18 @@ impl DryRunTransactionProcessor {
             shard_pledges.insert(shard_id, pledge);
         }
 
-        // execute the payload in the WASM engine and return the result
-        let consensus_context = self.get_consensus_context().await?;
-        let result = self
-            .payload_processor
-            .process_payload(payload, shard_pledges, consensus_context)?;
-        Ok(result)
+        Ok(shard_pledges)
     }
 
     async fn get_consensus_context(&self) -> Result<ConsensusContext, DryRunTransactionProcessorError> {
-        let current_epoch = self.epoch_manager.current_epoch().await?.as_u64();
-        let consensus_context = ConsensusContext { current_epoch };
+        let current_epoch = self.epoch_manager.current_epoch().await?;
+        let current_epoch_timestamp = self.epoch_manager.get_epoch_timestamp(current_epoch).await?;
+        let current_epoch_beacon = self.epoch_manager.get_epoch_beacon(current_epoch).await?;
+        let consensus_context = ConsensusContext {
+            current_epoch: current_epoch.as_u64(),
+            current_epoch_timestamp,
+            current_epoch_beacon,
+        };
         Ok(consensus_context)
     }
 
@@ -221,28 +286,204 @@ impl DryRunTransactionProcessor {
     }
 
     fn extract_pledge_from_vn_sync_response(msg: VnStateSyncResponse) -> Result<ObjectPledge, anyhow::Error> {
-        let shard_id = ShardId::try_from(msg.shard_id)?;
-        let pledged_to_payload = PayloadId::try_from(msg.created_payload_id.as_slice())?;
-
-        let current_state = if let Some(deleted_by) = Some(msg.destroyed_payload_id).filter(|p| !p.is_empty()) {
-            SubstateState::Down {
-                deleted_by: deleted_by.try_into()?,
-            }
-        } else {
-            let substate = Substate::from_bytes(&msg.substate)?;
-            SubstateState::Up {
-                created_by: msg.created_payload_id.try_into()?,
-                address: SubstateAddress::from_bytes(&msg.address)?,
-                data: substate,
-            }
-        };
-
+        let state_root = msg.claimed_state_root()?;
+        let substate = msg.into_verified_substate(&state_root)?;
         let pledge = ObjectPledge {
-            shard_id,
-            current_state,
-            pledged_to_payload,
+            shard_id: substate.shard_id(),
+            pledged_to_payload: substate.created_payload_id(),
+            current_state: substate.into_substate_state(),
         };
 
         Ok(pledge)
     }
 }
+
+/// Determines the substates that a transaction requires from the substate diff and reads of its dry run. The new
+/// substates whose addresses were derived from the ID seed are counted rather than listed, because the transaction
+/// derives them from a different seed once it is signed.
+fn get_substate_requirements(
+    id_seed: Hash,
+    diff: &SubstateDiff,
+    reads: &[SubstateAddress],
+    pledged_shards: &HashMap<SubstateAddress, ShardId>,
+) -> SubstateRequirements {
+    let mut requirements = SubstateRequirements::default();
+
+    for (address, version) in diff.down_iter() {
+        requirements.written.push(ShardId::from_address(address, *version));
+    }
+
+    requirements.read = reads
+        .iter()
+        .filter(|address| !diff.down_iter().any(|(down, _)| down == *address))
+        .filter_map(|address| pledged_shards.get(address).copied())
+        .collect();
+
+    let num_new = diff.up_iter().filter(|(_, substate)| substate.version() == 0).count();
+    let num_new = u32::try_from(num_new).unwrap_or(u32::MAX);
+    let id_provider = IdProvider::new(id_seed, num_new);
+    let new_ids = (0..num_new)
+        .filter_map(|_| id_provider.new_address_hash().ok())
+        .collect::<Vec<_>>();
+    let new_uuids = (0..num_new)
+        .filter_map(|_| id_provider.new_uuid().ok())
+        .collect::<Vec<_>>();
+
+    // The position of each randomly identified non-fungible in the UUID sequence, so that they can be grouped in the
+    // order that they were minted
+    let mut new_non_fungibles = Vec::new();
+    for (address, substate) in diff.up_iter() {
+        if substate.version() > 0 {
+            requirements
+                .outputs
+                .push(ShardId::from_address(address, substate.version()));
+            continue;
+        }
+
+        let canonical_hash = address.to_canonical_hash();
+        if let Some(pos) = new_ids.iter().position(|id| *id == canonical_hash) {
+            requirements.new_outputs = requirements.new_outputs.max(pos as u32 + 1);
+            continue;
+        }
+
+        let uuid_pos = address.as_non_fungible_address().and_then(|address| {
+            new_uuids
+                .iter()
+                .position(|uuid| *address.id() == NonFungibleId::from_u256(*uuid))
+                .map(|pos| (pos, *address.resource_address()))
+        });
+        match uuid_pos {
+            Some(pos_and_resource) => new_non_fungibles.push(pos_and_resource),
+            None => requirements.outputs.push(ShardId::from_address(address, 0)),
+        }
+    }
+
+    new_non_fungibles.sort_by_key(|(pos, _)| *pos);
+    for (_, resource_address) in new_non_fungibles {
+        match requirements.new_non_fungible_outputs.last_mut() {
+            Some((last, count)) if *last == resource_address && *count < u8::MAX => *count += 1,
+            _ => requirements.new_non_fungible_outputs.push((resource_address, 1)),
+        }
+    }
+
+    requirements
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_engine_types::{
+        resource::Resource,
+        substate::{Substate, SubstateValue},
+    };
+    use tari_template_lib::{
+        models::{ComponentAddress, NonFungibleAddress, ResourceAddress},
+        resource::ResourceType,
+    };
+
+    use super::*;
+
+    fn substate(version: u32) -> Substate {
+        Substate::new(
+            version,
+            SubstateValue::Resource(Resource::new(
+                ResourceType::Fungible,
+                Default::default(),
+                Default::default(),
+            )),
+        )
+    }
+
+    fn resource_address(n: u8) -> ResourceAddress {
+        Hash::from([n; 32]).into()
+    }
+
+    #[test]
+    fn it_separates_written_and_read_substates() {
+        let updated = SubstateAddress::Resource(resource_address(1));
+        let read = SubstateAddress::Resource(resource_address(2));
+        let unpledged = SubstateAddress::Resource(resource_address(3));
+
+        let mut diff = SubstateDiff::new();
+        diff.down(updated.clone(), 1);
+        diff.up(updated.clone(), substate(2));
+
+        let pledged_shards = [
+            (updated.clone(), ShardId::from_address(&updated, 1)),
+            (read.clone(), ShardId::from_address(&read, 4)),
+        ]
+        .into_iter()
+        .collect();
+
+        let requirements = get_substate_requirements(
+            Hash::default(),
+            &diff,
+            &[updated.clone(), read.clone(), unpledged],
+            &pledged_shards,
+        );
+
+        assert_eq!(requirements.written, vec![ShardId::from_address(&updated, 1)]);
+        assert_eq!(requirements.read, vec![ShardId::from_address(&read, 4)]);
+        assert_eq!(requirements.outputs, vec![ShardId::from_address(&updated, 2)]);
+        assert_eq!(requirements.new_outputs, 0);
+        assert!(requirements.new_non_fungible_outputs.is_empty());
+    }
+
+    #[test]
+    fn it_counts_new_outputs_derived_from_the_id_seed() {
+        let id_seed = Hash::from([9; 32]);
+        let id_provider = IdProvider::new(id_seed, 2);
+        let component: ComponentAddress = id_provider.new_address_hash().unwrap().into();
+        let resource: ResourceAddress = id_provider.new_address_hash().unwrap().into();
+
+        let mut diff = SubstateDiff::new();
+        diff.up(SubstateAddress::Resource(resource), substate(0));
+        diff.up(SubstateAddress::Component(component), substate(0));
+
+        let requirements = get_substate_requirements(id_seed, &diff, &[], &HashMap::new());
+
+        assert_eq!(requirements.new_outputs, 2);
+        assert!(requirements.outputs.is_empty());
+        assert!(requirements.written.is_empty());
+    }
+
+    #[test]
+    fn it_groups_random_non_fungibles_by_resource_in_mint_order() {
+        let id_seed = Hash::from([7; 32]);
+        let id_provider = IdProvider::new(id_seed, 3);
+        let uuids = (0..3).map(|_| id_provider.new_uuid().unwrap()).collect::<Vec<_>>();
+        let first = resource_address(1);
+        let second = resource_address(2);
+        let nft = |resource, uuid| {
+            SubstateAddress::NonFungible(NonFungibleAddress::new(resource, NonFungibleId::from_u256(uuid)))
+        };
+
+        // Inserted out of mint order
+        let mut diff = SubstateDiff::new();
+        diff.up(nft(second, uuids[2]), substate(0));
+        diff.up(nft(first, uuids[1]), substate(0));
+        diff.up(nft(first, uuids[0]), substate(0));
+
+        let requirements = get_substate_requirements(id_seed, &diff, &[], &HashMap::new());
+
+        assert_eq!(requirements.new_non_fungible_outputs, vec![(first, 2), (second, 1)]);
+        assert_eq!(requirements.new_outputs, 0);
+        assert!(requirements.outputs.is_empty());
+    }
+
+    #[test]
+    fn it_lists_new_outputs_with_addresses_that_are_not_derived_from_the_id_seed() {
+        let nft = SubstateAddress::NonFungible(NonFungibleAddress::new(
+            resource_address(1),
+            NonFungibleId::from_string("chosen"),
+        ));
+
+        let mut diff = SubstateDiff::new();
+        diff.up(nft.clone(), substate(0));
+
+        let requirements = get_substate_requirements(Hash::from([5; 32]), &diff, &[], &HashMap::new());
+
+        assert_eq!(requirements.outputs, vec![ShardId::from_address(&nft, 0)]);
+        assert_eq!(requirements.new_outputs, 0);
+        assert!(requirements.new_non_fungible_outputs.is_empty());
+    }
+}
@@ -43,6 +43,7 @@ use tari_crypto::tari_utilities::hex::Hex;
 use tari_dan_app_utilities::{
     base_node_client::GrpcBaseNodeClient,
     epoch_manager::EpochManagerHandle,
+    shard_store::ShardStoreBackend,
     template_manager::TemplateManagerHandle,
 };
 use tari_dan_common_types::{optional::Optional, PayloadId, QuorumCertificate, QuorumDecision, ShardId};
@@ -51,16 +52,22 @@ use tari_dan_core::{
     storage::shard_store::{ShardStore, ShardStoreReadTransaction},
     workers::events::{EventSubscription, HotStuffEvent},
 };
-use tari_dan_storage_sqlite::sqlite_shard_store_factory::SqliteShardStore;
 use tari_template_lib::Hash;
 use tari_validator_node_client::types::{
     AddPeerRequest,
     AddPeerResponse,
+    DiscoverTransactionSubstatesRequest,
+    DiscoverTransactionSubstatesResponse,
     GetCommitteeRequest,
     GetEpochManagerStatsResponse,
     GetIdentityResponse,
+    GetMempoolStatsResponse,
     GetRecentTransactionsResponse,
     GetShardKey,
+    GetSlashingEvidenceRequest,
+    GetSlashingEvidenceResponse,
+    GetSlashingEvidenceSubmissionRequest,
+    GetSlashingEvidenceSubmissionResponse,
     GetStateRequest,
     GetStateResponse,
     GetSubstateRequest,
@@ -73,6 +80,8 @@ use tari_validator_node_client::types::{
     GetTransactionQcsResponse,
     GetTransactionResultRequest,
     GetTransactionResultResponse,
+    ShardQueueDepth,
+    SlashingEvidenceInfo,
     SubmitTransactionRequest,
     SubmitTransactionResponse,
     SubstateStatus,
@@ -82,14 +91,19 @@ use tari_validator_node_client::types::{
     TemplateRegistrationResponse,
     TransactionFinalizeResult,
     TransactionRequest,
+    TRANSACTION_REJECTED_ERROR_CODE,
 };
-use tokio::sync::{broadcast, broadcast::error::RecvError};
+use tokio::sync::{broadcast, broadcast::error::RecvError, watch};
 
 use crate::{
     dry_run_transaction_processor::DryRunTransactionProcessor,
     grpc::services::wallet_client::GrpcWalletClient,
     json_rpc::jrpc_errors::internal_error,
-    p2p::services::mempool::MempoolHandle,
+    p2p::services::{
+        epoch_manager::StateSyncProgress,
+        mempool::{MempoolError, MempoolHandle},
+        pruning::PruningHandle,
+    },
     registration,
     Services,
     ValidatorNodeConfig,
@@ -106,8 +120,10 @@ pub struct JsonRpcHandlers {
     comms: CommsNode,
     hotstuff_events: EventSubscription<HotStuffEvent>,
     base_node_client: GrpcBaseNodeClient,
-    shard_store: SqliteShardStore,
+    shard_store: ShardStoreBackend,
     dry_run_transaction_processor: DryRunTransactionProcessor,
+    pruning: PruningHandle,
+    state_sync_progress: watch::Receiver<Option<StateSyncProgress>>,
     config: ValidatorNodeConfig,
 }
 
@@ -130,6 +146,8 @@ impl JsonRpcHandlers {
             base_node_client,
             shard_store: services.shard_store.clone(),
             dry_run_transaction_processor: services.dry_run_transaction_processor.clone(),
+            pruning: services.pruning.clone(),
+            state_sync_progress: services.state_sync_progress.clone(),
         }
     }
 
@@ -214,10 +232,20 @@ impl JsonRpcHandlers {
         } else {
             let subscription = self.hotstuff_events.subscribe();
             // Submit to mempool.
-            self.mempool
-                .submit_transaction(transaction)
-                .await
-                .map_err(internal_error(answer_id))?;
+            match self.mempool.submit_transaction(transaction).await {
+                Ok(()) => {},
+                Err(MempoolError::Rejected(reason)) => {
+                    return Err(JsonRpcResponse::error(
+                        answer_id,
+                        JsonRpcError::new(
+                            JsonRpcErrorReason::ApplicationError(TRANSACTION_REJECTED_ERROR_CODE),
+                            format!("Transaction rejected: {}", reason),
+                            json!(reason),
+                        ),
+                    ));
+                },
+                Err(e) => return Err(internal_error(answer_id)(e)),
+            }
 
             if wait_for_result {
                 return wait_for_transaction_result(
@@ -236,6 +264,32 @@ impl JsonRpcHandlers {
         }
     }
 
+    pub async fn discover_transaction_substates(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let DiscoverTransactionSubstatesRequest { transaction } = value.parse_params()?;
+        let hash = *transaction.hash();
+
+        match self.dry_run_transaction_processor.discover_substates(transaction).await {
+            Ok(requirements) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Transaction {} reads {} and writes {} substate(s)",
+                    hash,
+                    requirements.read.len(),
+                    requirements.written.len()
+                );
+                Ok(JsonRpcResponse::success(
+                    answer_id,
+                    DiscoverTransactionSubstatesResponse { requirements },
+                ))
+            },
+            Err(e) => Err(JsonRpcResponse::error(
+                answer_id,
+                JsonRpcError::new(JsonRpcErrorReason::ApplicationError(1), e.to_string(), json!(null)),
+            )),
+        }
+    }
+
     pub async fn get_state(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let request: GetStateRequest = value.parse_params()?;
@@ -430,6 +484,65 @@ impl JsonRpcHandlers {
         }
     }
 
+    pub async fn get_slashing_evidence(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let request: GetSlashingEvidenceRequest = value.parse_params()?;
+        let evidence = self
+            .shard_store
+            .with_read_tx(|tx| tx.get_slashing_evidence(request.offender.as_ref()))
+            .map_err(internal_error(answer_id))?;
+        let evidence = evidence
+            .into_iter()
+            .map(|evidence| SlashingEvidenceInfo {
+                hash: evidence.hash(),
+                evidence,
+            })
+            .collect();
+        Ok(JsonRpcResponse::success(answer_id, GetSlashingEvidenceResponse {
+            evidence,
+        }))
+    }
+
+    pub async fn get_slashing_evidence_submission(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let request: GetSlashingEvidenceSubmissionRequest = value.parse_params()?;
+        let evidence = self
+            .shard_store
+            .with_read_tx(|tx| tx.get_slashing_evidence(None))
+            .map_err(internal_error(answer_id))?
+            .into_iter()
+            .find(|evidence| evidence.hash() == request.evidence_hash)
+            .ok_or_else(|| {
+                JsonRpcResponse::error(
+                    answer_id,
+                    JsonRpcError::new(
+                        JsonRpcErrorReason::ApplicationError(404),
+                        format!("Slashing evidence with hash {} not found", request.evidence_hash),
+                        json::Value::Null,
+                    ),
+                )
+            })?;
+
+        let submission = self
+            .base_node_client()
+            .build_slashing_evidence_submission(&evidence)
+            .await
+            .map_err(|err| {
+                JsonRpcResponse::error(
+                    answer_id,
+                    JsonRpcError::new(
+                        JsonRpcErrorReason::ApplicationError(1),
+                        format!("Could not build slashing evidence submission: {}", err),
+                        json::Value::Null,
+                    ),
+                )
+            })?;
+        Ok(JsonRpcResponse::success(
+            answer_id,
+            GetSlashingEvidenceSubmissionResponse { submission },
+        ))
+    }
+
     pub async fn register_validator_node(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
 
@@ -489,6 +602,8 @@ impl JsonRpcHandlers {
                     url: t.url,
                     binary_sha: t.binary_sha,
                     height: t.height,
+                    version: t.version,
+                    predecessor: t.predecessor,
                 })
                 .collect(),
         }))
@@ -517,6 +632,8 @@ impl JsonRpcHandlers {
                 url: template.metadata.url,
                 binary_sha: template.metadata.binary_sha,
                 height: template.metadata.height,
+                version: template.metadata.version,
+                predecessor: template.metadata.predecessor,
             },
             abi,
         }))
@@ -560,8 +677,8 @@ impl JsonRpcHandlers {
 
     pub async fn get_mempool_stats(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
-        let size = self.mempool.get_mempool_size().await.map_err(|err| {
-            error!(target: LOG_TARGET, "Error getting mempool size: {}", err);
+        let stats = self.mempool.get_mempool_stats().await.map_err(|err| {
+            error!(target: LOG_TARGET, "Error getting mempool stats: {}", err);
             JsonRpcResponse::error(
                 answer_id,
                 JsonRpcError::new(
@@ -571,10 +688,42 @@ impl JsonRpcHandlers {
                 ),
             )
         })?;
-        let response = json!({ "size": size });
+        let mut shard_queue_depths = stats
+            .shard_queue_depths
+            .into_iter()
+            .map(|(shard_id, depth)| ShardQueueDepth { shard_id, depth })
+            .collect::<Vec<_>>();
+        shard_queue_depths.sort_by(|a, b| b.depth.cmp(&a.depth));
+        let response = GetMempoolStatsResponse {
+            size: stats.size,
+            max_size: stats.max_size,
+            shard_queue_depths,
+        };
         Ok(JsonRpcResponse::success(answer_id, response))
     }
 
+    pub async fn get_pruning_stats(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let stats = self.pruning.get_stats().await.map_err(|err| {
+            error!(target: LOG_TARGET, "Error getting pruning stats: {}", err);
+            JsonRpcResponse::error(
+                answer_id,
+                JsonRpcError::new(
+                    JsonRpcErrorReason::InternalError,
+                    format!("Could not get pruning stats: {}", err),
+                    json::Value::Null,
+                ),
+            )
+        })?;
+        Ok(JsonRpcResponse::success(answer_id, stats))
+    }
+
+    pub fn get_state_sync_progress(&self, value: JsonRpcExtractor) -> JrpcResult {
+        let answer_id = value.get_answer_id();
+        let progress = self.state_sync_progress.borrow().clone();
+        Ok(JsonRpcResponse::success(answer_id, progress))
+    }
+
     pub async fn get_epoch_manager_stats(&self, value: JsonRpcExtractor) -> JrpcResult {
         let answer_id = value.get_answer_id();
         let current_epoch = self.epoch_manager.current_epoch().await.map_err(|e| {
@@ -777,7 +926,8 @@ async fn wait_for_transaction_result(
         match tokio::time::timeout(timeout, subscription.recv()).await {
             Ok(res) => match res {
                 Ok(HotStuffEvent::OnFinalized(qc, result)) => {
-                    if qc.payload_id().as_bytes() != hash.as_ref() {
+                    // The QC is for the batch if the transaction was batched, so match on the result instead
+                    if result.transaction_hash != hash {
                         continue;
                     }
 
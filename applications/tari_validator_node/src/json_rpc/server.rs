@@ -60,6 +60,7 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         // Transaction
         // "get_transaction_status" => handlers.get_transaction_status(value).await,
         "submit_transaction" => handlers.submit_transaction(value).await,
+        "discover_transaction_substates" => handlers.discover_transaction_substates(value).await,
         "get_recent_transactions" => handlers.get_recent_transactions(value).await,
         "get_transaction" => handlers.get_transaction(value).await,
         "get_transaction_result" => handlers.get_transaction_result(value).await,
@@ -68,6 +69,8 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         "get_substate" => handlers.get_substate(value).await,
         "get_substates" => handlers.get_substates(value).await,
         "get_current_leader_state" => handlers.get_current_leader_state(value).await,
+        "get_slashing_evidence" => handlers.get_slashing_evidence(value).await,
+        "get_slashing_evidence_submission" => handlers.get_slashing_evidence_submission(value).await,
         // Template
         "get_template" => handlers.get_template(value).await,
         "get_templates" => handlers.get_templates(value).await,
@@ -77,6 +80,8 @@ async fn handler(Extension(handlers): Extension<Arc<JsonRpcHandlers>>, value: Js
         "register_validator_node" => handlers.register_validator_node(value).await,
         "get_mempool_stats" => handlers.get_mempool_stats(value).await,
         "get_epoch_manager_stats" => handlers.get_epoch_manager_stats(value).await,
+        "get_pruning_stats" => handlers.get_pruning_stats(value).await,
+        "get_state_sync_progress" => handlers.get_state_sync_progress(value),
         "get_shard_key" => handlers.get_shard_key(value).await,
         "get_committee" => handlers.get_committee(value).await,
         "get_all_vns" => handlers.get_all_vns(value).await,
@@ -50,7 +50,6 @@ use tari_common::{
 use tari_dan_app_utilities::base_node_client::GrpcBaseNodeClient;
 use tari_dan_common_types::ShardId;
 use tari_dan_core::{
-    consensus_constants::ConsensusConstants,
     services::{base_node_error::BaseNodeError, BaseNodeClient},
     storage::DbFactory,
     DigitalAssetError,
@@ -124,13 +123,23 @@ pub async fn run_validator_node(config: &ApplicationConfig, shutdown_signal: Shu
         //     .ok_or_else(|| ExitError::new(ExitCode::UnknownError, "public address not found for validator node"))?
     );
 
+    let consensus_constants = config
+        .validator_node
+        .consensus
+        .to_consensus_constants(config.network)
+        .map_err(|e| ExitError::new(ExitCode::ConfigError, e))?;
+    info!(
+        target: LOG_TARGET,
+        "Using consensus constants for {}: {:?}", config.network, consensus_constants
+    );
+
     let (base_node_client, wallet_client) = create_base_layer_clients(config).await?;
     let services = spawn_services(
         config,
         shutdown_signal.clone(),
         node_identity.clone(),
         global_db,
-        ConsensusConstants::devnet(), // TODO: change this eventually
+        consensus_constants,
     )
     .await?;
 
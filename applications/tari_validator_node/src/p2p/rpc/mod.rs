@@ -26,8 +26,8 @@ pub use service_impl::ValidatorNodeRpcServiceImpl;
 use tari_comms::protocol::rpc::{Request, Response, RpcStatus, Streaming};
 use tari_comms_rpc_macros::tari_rpc;
 use tari_dan_app_grpc::proto;
-use tari_dan_core::services::PeerProvider;
-use tari_dan_storage_sqlite::sqlite_shard_store_factory::SqliteShardStore;
+use tari_dan_app_utilities::shard_store::ShardStoreBackend;
+use tari_dan_core::{consensus_constants::ConsensusConstants, services::PeerProvider};
 
 use crate::p2p::services::mempool::MempoolHandle;
 
@@ -50,12 +50,31 @@ pub trait ValidatorNodeRpcService: Send + Sync + 'static {
         &self,
         request: Request<proto::rpc::VnStateSyncRequest>,
     ) -> Result<Streaming<proto::rpc::VnStateSyncResponse>, RpcStatus>;
+
+    #[rpc(method = 4)]
+    async fn get_substate_events(
+        &self,
+        request: Request<proto::rpc::GetSubstateEventsRequest>,
+    ) -> Result<Response<proto::rpc::GetSubstateEventsResponse>, RpcStatus>;
+
+    #[rpc(method = 5)]
+    async fn vn_state_sync_chunk(
+        &self,
+        request: Request<proto::rpc::VnStateSyncChunkRequest>,
+    ) -> Result<Response<proto::rpc::VnStateSyncChunkResponse>, RpcStatus>;
+
+    #[rpc(method = 6)]
+    async fn get_consensus_constants(
+        &self,
+        request: Request<proto::rpc::GetConsensusConstantsRequest>,
+    ) -> Result<Response<proto::rpc::GetConsensusConstantsResponse>, RpcStatus>;
 }
 
 pub fn create_validator_node_rpc_service<TPeerProvider>(
     peer_provider: TPeerProvider,
-    shard_store_store: SqliteShardStore,
+    shard_store_store: ShardStoreBackend,
     mempool: MempoolHandle,
+    consensus_constants: ConsensusConstants,
 ) -> ValidatorNodeRpcServer<ValidatorNodeRpcServiceImpl<TPeerProvider>>
 where
     TPeerProvider: PeerProvider + Clone + Send + Sync + 'static,
@@ -64,5 +83,6 @@ where
         peer_provider,
         shard_store_store,
         mempool,
+        consensus_constants,
     ))
 }
@@ -23,36 +23,66 @@
 use std::convert::{TryFrom, TryInto};
 
 use log::*;
-use tari_comms::protocol::rpc::{Request, Response, RpcStatus, Streaming};
+use tari_common_types::types::FixedHash;
+use tari_comms::{
+    protocol::rpc::{Request, Response, RpcStatus, Streaming},
+    types::CommsPublicKey,
+};
 use tari_dan_app_grpc::{
     proto,
-    proto::rpc::{VnStateSyncRequest, VnStateSyncResponse},
+    proto::rpc::{
+        GetConsensusConstantsRequest,
+        GetConsensusConstantsResponse,
+        GetSubstateEventsRequest,
+        GetSubstateEventsResponse,
+        VnStateSyncChunkRequest,
+        VnStateSyncChunkResponse,
+        VnStateSyncRequest,
+        VnStateSyncResponse,
+    },
 };
+use tari_dan_app_utilities::shard_store::ShardStoreBackend;
 use tari_dan_common_types::{NodeAddressable, ShardId};
 use tari_dan_core::{
+    consensus_constants::ConsensusConstants,
+    models::{HotStuffTreeNode, StateTreeProof, SubstateShardData, TariDanPayload},
     services::PeerProvider,
-    storage::shard_store::{ShardStore, ShardStoreReadTransaction},
+    storage::{
+        shard_store::{ShardStore, ShardStoreReadTransaction},
+        StorageError,
+    },
 };
-use tari_dan_storage_sqlite::sqlite_shard_store_factory::SqliteShardStore;
 use tari_transaction::Transaction;
 use tokio::{sync::mpsc, task};
 
 const LOG_TARGET: &str = "tari::dan::p2p::rpc";
+/// The maximum number of substates returned in a single state sync chunk
+const MAX_STATE_SYNC_CHUNK_SIZE: usize = 1000;
 
-use crate::p2p::{rpc::ValidatorNodeRpcService, services::mempool::MempoolHandle};
+use crate::p2p::{
+    rpc::ValidatorNodeRpcService,
+    services::mempool::{MempoolError, MempoolHandle},
+};
 
 pub struct ValidatorNodeRpcServiceImpl<TPeerProvider> {
     peer_provider: TPeerProvider,
-    shard_state_store: SqliteShardStore,
+    shard_state_store: ShardStoreBackend,
     mempool: MempoolHandle,
+    consensus_constants: ConsensusConstants,
 }
 
 impl<TPeerProvider: PeerProvider> ValidatorNodeRpcServiceImpl<TPeerProvider> {
-    pub fn new(peer_provider: TPeerProvider, shard_state_store: SqliteShardStore, mempool: MempoolHandle) -> Self {
+    pub fn new(
+        peer_provider: TPeerProvider,
+        shard_state_store: ShardStoreBackend,
+        mempool: MempoolHandle,
+        consensus_constants: ConsensusConstants,
+    ) -> Self {
         Self {
             peer_provider,
             shard_state_store,
             mempool,
+            consensus_constants,
         }
     }
 }
@@ -78,7 +108,6 @@ where TPeerProvider: PeerProvider + Clone + Send + Sync + 'static
             },
         };
 
-        // TODO: Implement a mempool handle that returns if the transaction was accepted or not
         match self.mempool.submit_transaction(transaction).await {
             Ok(_) => {
                 debug!(target: LOG_TARGET, "Accepted instruction into mempool");
@@ -87,11 +116,18 @@ where TPeerProvider: PeerProvider + Clone + Send + Sync + 'static
                     status: "Accepted".to_string(),
                 }));
             },
-            Err(_err) => {
-                // debug!(target: LOG_TARGET, "Mempool rejected instruction: {}", err);
+            Err(MempoolError::Rejected(reason)) => {
+                debug!(target: LOG_TARGET, "Mempool rejected instruction: {}", reason);
+                return Ok(Response::new(proto::rpc::SubmitTransactionResponse {
+                    result: vec![],
+                    status: format!("Rejected: {}", reason),
+                }));
+            },
+            Err(err) => {
+                debug!(target: LOG_TARGET, "Mempool failed to accept instruction: {}", err);
                 return Ok(Response::new(proto::rpc::SubmitTransactionResponse {
                     result: vec![],
-                    status: "Mempool has shut down".to_string(),
+                    status: format!("Mempool error: {}", err),
                 }));
             },
         }
@@ -156,10 +192,19 @@ where TPeerProvider: PeerProvider + Clone + Send + Sync + 'static
         let shard_db = self.shard_state_store.clone();
 
         task::spawn(async move {
+            // Read the substates and their proofs in one transaction so that every proof is against the same root
             let shards_substates_data = shard_db.with_read_tx(|tx| {
-                tx.get_substate_states_by_range(start_shard_id, end_shard_id, excluded_shards.as_slice())
+                let state_root = tx.get_state_root()?;
+                tx.get_substate_states_by_range(start_shard_id, end_shard_id, excluded_shards.as_slice())?
+                    .into_iter()
+                    .map(|substate| {
+                        let proof = tx.get_state_proof(substate.shard_id())?;
+                        Ok((substate, proof))
+                    })
+                    .collect::<Result<Vec<_>, StorageError>>()
+                    .map(|substates| (state_root, substates))
             });
-            let substates = match shards_substates_data {
+            let (state_root, substates) = match shards_substates_data {
                 Ok(s) => s,
                 Err(err) => {
                     error!(target: LOG_TARGET, "{}", err);
@@ -173,8 +218,8 @@ where TPeerProvider: PeerProvider + Clone + Send + Sync + 'static
             }
 
             // select data from db where shard_id <= end_shard_id and shard_id >= start_shard_id
-            for substate in substates {
-                match proto::rpc::VnStateSyncResponse::try_from(substate) {
+            for (substate, proof) in substates {
+                match state_sync_response(substate, &state_root, proof) {
                     Ok(r) => {
                         if tx.send(Ok(r)).await.is_err() {
                             debug!(
@@ -194,4 +239,141 @@ where TPeerProvider: PeerProvider + Clone + Send + Sync + 'static
         });
         Ok(Streaming::new(rx))
     }
+
+    async fn vn_state_sync_chunk(
+        &self,
+        request: Request<VnStateSyncChunkRequest>,
+    ) -> Result<Response<VnStateSyncChunkResponse>, RpcStatus> {
+        let msg = request.into_message();
+        let start_shard_id = msg
+            .start_shard_id
+            .and_then(|s| ShardId::try_from(s).ok())
+            .ok_or_else(|| RpcStatus::bad_request("Invalid gRPC request: start_shard_id not provided"))?;
+        let end_shard_id = msg
+            .end_shard_id
+            .and_then(|s| ShardId::try_from(s).ok())
+            .ok_or_else(|| RpcStatus::bad_request("Invalid gRPC request: end_shard_id not provided"))?;
+        let max_substates = (msg.max_substates as usize).min(MAX_STATE_SYNC_CHUNK_SIZE);
+        if max_substates == 0 {
+            return Err(RpcStatus::bad_request(
+                "Invalid gRPC request: max_substates must be greater than 0",
+            ));
+        }
+
+        let mut substates = self
+            .shard_state_store
+            .with_read_tx(|tx| {
+                // Fetch one extra substate to determine where the next chunk starts
+                tx.get_substate_states_chunk(start_shard_id, end_shard_id, max_substates + 1)?
+                    .into_iter()
+                    .map(|substate| {
+                        // The node certified by the justify of the last change to the substate commits to the
+                        // substate in its state root. Substates created when bootstrapping have no certified node.
+                        let justify = substate
+                            .destroyed_justify()
+                            .as_ref()
+                            .unwrap_or_else(|| substate.created_justify());
+                        let certified_node = if justify.node_hash().is_zero() {
+                            None
+                        } else {
+                            Some(tx.get_node(&justify.node_hash())?)
+                        };
+                        Ok((substate, certified_node))
+                    })
+                    .collect::<Result<Vec<_>, StorageError>>()
+            })
+            .map_err(|err| RpcStatus::general(&err))?;
+
+        let next_shard_id = if substates.len() > max_substates {
+            substates.pop().map(|(substate, _)| substate.shard_id().into())
+        } else {
+            None
+        };
+        let substates = substates
+            .into_iter()
+            .map(|(substate, certified_node)| certified_state_sync_response(substate, certified_node))
+            .collect::<Result<_, _>>()
+            .map_err(|err| RpcStatus::general(&err))?;
+
+        Ok(Response::new(VnStateSyncChunkResponse {
+            substates,
+            next_shard_id,
+        }))
+    }
+
+    async fn get_substate_events(
+        &self,
+        request: Request<GetSubstateEventsRequest>,
+    ) -> Result<Response<GetSubstateEventsResponse>, RpcStatus> {
+        let msg = request.into_message();
+        let shard_id = msg
+            .shard_id
+            .and_then(|s| ShardId::try_from(s).ok())
+            .ok_or_else(|| RpcStatus::bad_request("Invalid gRPC request: shard_id not provided"))?;
+
+        let (payload_id, events) = self
+            .shard_state_store
+            .with_read_tx(|tx| {
+                let substate = tx
+                    .get_substate_states(&[shard_id])?
+                    .pop()
+                    .ok_or_else(|| StorageError::NotFound {
+                        item: "substate".to_string(),
+                        key: shard_id.to_string(),
+                    })?;
+                let payload_id = substate.created_payload_id();
+                let events = tx.get_events_for_payload(payload_id)?;
+                Ok::<_, StorageError>((payload_id, events))
+            })
+            .map_err(|err| match err {
+                StorageError::NotFound { .. } => RpcStatus::not_found(&err.to_string()),
+                err => RpcStatus::general(&err),
+            })?;
+
+        let events = events
+            .iter()
+            .map(tari_bor::encode)
+            .collect::<Result<_, _>>()
+            .map_err(|err| RpcStatus::general(&err))?;
+
+        Ok(Response::new(GetSubstateEventsResponse {
+            created_payload_id: payload_id.as_bytes().to_vec(),
+            events,
+        }))
+    }
+
+    async fn get_consensus_constants(
+        &self,
+        _request: Request<GetConsensusConstantsRequest>,
+    ) -> Result<Response<GetConsensusConstantsResponse>, RpcStatus> {
+        Ok(Response::new(GetConsensusConstantsResponse {
+            consensus_constants: Some(self.consensus_constants.clone().into()),
+        }))
+    }
+}
+
+fn state_sync_response(
+    substate: SubstateShardData,
+    state_root: &FixedHash,
+    proof: StateTreeProof,
+) -> Result<VnStateSyncResponse, anyhow::Error> {
+    let mut response = VnStateSyncResponse::try_from(substate)?;
+    response.state_root = state_root.as_slice().to_vec();
+    response.state_proof = Some(proof.into());
+    Ok(response)
+}
+
+/// Returns the response for a substate in a state sync chunk. The substate is proven against the state root of the
+/// certified node, which only contains the substate's shard.
+fn certified_state_sync_response(
+    substate: SubstateShardData,
+    certified_node: Option<HotStuffTreeNode<CommsPublicKey, TariDanPayload>>,
+) -> Result<VnStateSyncResponse, anyhow::Error> {
+    let mut response = VnStateSyncResponse::try_from(substate)?;
+    if let Some(node) = certified_node {
+        response.state_root = node.state_root().as_slice().to_vec();
+        response.state_proof = Some(StateTreeProof::default().into());
+        response.certified_node = Some(node.into());
+    }
+    Ok(response)
 }
@@ -31,28 +31,38 @@ use tari_core::{
     ValidatorNodeBMT,
 };
 use tari_crypto::tari_utilities::ByteArray;
-use tari_dan_app_utilities::{base_node_client::GrpcBaseNodeClient, epoch_manager::EpochManagerEvent};
-use tari_dan_common_types::{optional::Optional, vn_bmt_node_hash, Epoch, ShardId};
+use tari_dan_app_utilities::{
+    base_node_client::GrpcBaseNodeClient,
+    epoch_manager::EpochManagerEvent,
+    shard_store::ShardStoreBackend,
+};
+use tari_dan_common_types::{optional::Optional, vn_bmt_node_hash, Epoch, QuorumCertificate, ShardId};
 use tari_dan_core::{
     consensus_constants::{BaseLayerConsensusConstants, ConsensusConstants},
     models::{Committee, ValidatorNode},
     services::{
         epoch_manager::{EpochManagerError, ShardCommitteeAllocation},
+        verify_quorum_certificate,
         BaseNodeClient,
+        NodeIdentitySigningService,
     },
 };
 use tari_dan_storage::global::{DbEpoch, DbValidatorNode, GlobalDb, MetadataKey};
-use tari_dan_storage_sqlite::{global::SqliteGlobalDbAdapter, sqlite_shard_store_factory::SqliteShardStore};
-use tokio::sync::broadcast;
+use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
+use tari_engine_types::hashing::{hasher, EngineHashDomainLabel};
+use tokio::sync::{broadcast, watch};
 
-use crate::p2p::services::{epoch_manager::PeerSyncManagerService, rpc_client::TariCommsValidatorNodeClientFactory};
+use crate::p2p::services::{
+    epoch_manager::{PeerSyncManagerService, StateSyncProgress},
+    rpc_client::TariCommsValidatorNodeClientFactory,
+};
 
 const LOG_TARGET: &str = "tari::validator_node::epoch_manager::base_layer_epoch_manager";
 
 #[derive(Clone)]
 pub struct BaseLayerEpochManager {
     global_db: GlobalDb<SqliteGlobalDbAdapter>,
-    shard_store: SqliteShardStore,
+    shard_store: ShardStoreBackend,
     pub base_node_client: GrpcBaseNodeClient,
     consensus_constants: ConsensusConstants,
     current_epoch: Epoch,
@@ -62,17 +72,19 @@ pub struct BaseLayerEpochManager {
     validator_node_client_factory: TariCommsValidatorNodeClientFactory,
     current_shard_key: Option<ShardId>,
     base_layer_consensus_constants: Option<BaseLayerConsensusConstants>,
+    tx_state_sync_progress: Arc<watch::Sender<Option<StateSyncProgress>>>,
 }
 
 impl BaseLayerEpochManager {
     pub fn new(
         global_db: GlobalDb<SqliteGlobalDbAdapter>,
-        shard_store: SqliteShardStore,
+        shard_store: ShardStoreBackend,
         base_node_client: GrpcBaseNodeClient,
         consensus_constants: ConsensusConstants,
         tx_events: broadcast::Sender<EpochManagerEvent>,
         node_identity: Arc<NodeIdentity>,
         validator_node_client_factory: TariCommsValidatorNodeClientFactory,
+        tx_state_sync_progress: Arc<watch::Sender<Option<StateSyncProgress>>>,
     ) -> Self {
         Self {
             global_db,
@@ -86,6 +98,7 @@ impl BaseLayerEpochManager {
             validator_node_client_factory,
             current_shard_key: None,
             base_layer_consensus_constants: None,
+            tx_state_sync_progress,
         }
     }
 
@@ -208,6 +221,8 @@ impl BaseLayerEpochManager {
         let db_epoch = DbEpoch {
             epoch: epoch_height,
             validator_node_mr: header.validator_node_mr.to_vec(),
+            timestamp: header.timestamp.as_u64(),
+            block_hash: header.hash().to_vec(),
         };
 
         let mut tx = self.global_db.create_transaction()?;
@@ -354,6 +369,19 @@ impl BaseLayerEpochManager {
         Ok(Committee::new(result.into_iter().map(|v| v.public_key).collect()))
     }
 
+    /// Checks that the QC is signed by a quorum of the committee for its shard in its epoch
+    pub fn verify_quorum_certificate(&self, qc: &QuorumCertificate) -> Result<(), EpochManagerError> {
+        let vns = self
+            .get_validator_nodes_per_epoch(qc.epoch())?
+            .into_iter()
+            .map(|vn| vn.public_key)
+            .collect::<Vec<_>>();
+        let committee = self.get_committee(qc.epoch(), qc.shard())?;
+        let signing_service = NodeIdentitySigningService::new(self.node_identity.clone());
+        verify_quorum_certificate(&signing_service, qc, &vns, &committee, committee.consensus_threshold())
+            .map_err(EpochManagerError::InvalidQuorumCertificate)
+    }
+
     pub fn is_validator_in_committee(
         &self,
         epoch: Epoch,
@@ -421,6 +449,41 @@ impl BaseLayerEpochManager {
         }
     }
 
+    pub fn get_epoch_timestamp(&self, epoch: Epoch) -> Result<u64, EpochManagerError> {
+        let mut tx = self.global_db.create_transaction()?;
+
+        let query_res = self.global_db.epochs(&mut tx).get_epoch_data(epoch.0)?;
+
+        match query_res {
+            Some(db_epoch) => Ok(db_epoch.timestamp),
+            None => Err(EpochManagerError::NoEpochFound(epoch)),
+        }
+    }
+
+    /// Returns the beacon of the epoch, derived from the base layer block that started it and the validator node merkle
+    /// root. The beacon cannot be predicted before the epoch starts, but is public from then on, so a sender can
+    /// compute the randomness that a transaction derives from it before submitting the transaction.
+    // TODO: derive the beacon from the committee (e.g. aggregated VRF outputs or a commit-reveal bound into the QC) so
+    //       that randomness derived from it cannot be computed by the sender before the transaction is sequenced.
+    //       Every committee involved in a payload executes it and must derive the same value, so a per-shard QC cannot
+    //       simply be mixed in.
+    pub fn get_epoch_beacon(&self, epoch: Epoch) -> Result<[u8; 32], EpochManagerError> {
+        let mut tx = self.global_db.create_transaction()?;
+
+        let db_epoch = self
+            .global_db
+            .epochs(&mut tx)
+            .get_epoch_data(epoch.0)?
+            .ok_or(EpochManagerError::NoEpochFound(epoch))?;
+
+        let beacon = hasher(EngineHashDomainLabel::EpochBeacon)
+            .chain(&epoch.0)
+            .chain(&db_epoch.block_hash)
+            .chain(&db_epoch.validator_node_mr)
+            .result();
+        Ok(beacon.into_array())
+    }
+
     pub fn get_validator_node_bmt(&self, epoch: Epoch) -> Result<ValidatorNodeBMT, EpochManagerError> {
         let vns = self.get_validator_nodes_per_epoch(epoch)?;
 
@@ -477,9 +540,21 @@ impl BaseLayerEpochManager {
             end_shard_id
         );
         // synchronize state with committee validator nodes
-        PeerSyncManagerService::new(self.validator_node_client_factory.clone(), self.shard_store.clone())
-            .sync_peers_state(committee_vns, start_shard_id, end_shard_id, vn_shard_key)
-            .await?;
+        PeerSyncManagerService::new(
+            self.validator_node_client_factory.clone(),
+            self.shard_store.clone(),
+            self.global_db.clone(),
+            self.tx_state_sync_progress.clone(),
+        )
+        .sync_peers_state(
+            &|qc| self.verify_quorum_certificate(qc),
+            self.current_epoch,
+            committee_vns,
+            start_shard_id,
+            end_shard_id,
+            vn_shard_key,
+        )
+        .await?;
 
         let mut tx = self.global_db.create_transaction()?;
         self.global_db
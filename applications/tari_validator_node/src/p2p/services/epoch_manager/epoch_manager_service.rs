@@ -27,18 +27,19 @@ use tari_comms::NodeIdentity;
 use tari_dan_app_utilities::{
     base_node_client::GrpcBaseNodeClient,
     epoch_manager::{EpochManagerEvent, EpochManagerRequest},
+    shard_store::ShardStoreBackend,
 };
 use tari_dan_core::{consensus_constants::ConsensusConstants, services::epoch_manager::EpochManagerError};
 use tari_dan_storage::global::GlobalDb;
-use tari_dan_storage_sqlite::{global::SqliteGlobalDbAdapter, sqlite_shard_store_factory::SqliteShardStore};
+use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
 use tari_shutdown::ShutdownSignal;
 use tokio::{
-    sync::{broadcast, mpsc::Receiver, oneshot},
+    sync::{broadcast, mpsc::Receiver, oneshot, watch},
     task::JoinHandle,
 };
 
 use crate::p2p::services::{
-    epoch_manager::base_layer_epoch_manager::BaseLayerEpochManager,
+    epoch_manager::{base_layer_epoch_manager::BaseLayerEpochManager, StateSyncProgress},
     rpc_client::TariCommsValidatorNodeClientFactory,
 };
 
@@ -58,11 +59,12 @@ impl EpochManagerService {
         rx_request: Receiver<EpochManagerRequest>,
         shutdown: ShutdownSignal,
         global_db: GlobalDb<SqliteGlobalDbAdapter>,
-        shard_store: SqliteShardStore,
+        shard_store: ShardStoreBackend,
         base_node_client: GrpcBaseNodeClient,
         consensus_constants: ConsensusConstants,
         node_identity: Arc<NodeIdentity>,
         validator_node_client_factory: TariCommsValidatorNodeClientFactory,
+        tx_state_sync_progress: Arc<watch::Sender<Option<StateSyncProgress>>>,
     ) -> JoinHandle<anyhow::Result<()>> {
         tokio::spawn(async move {
             let (tx, rx) = broadcast::channel(10);
@@ -76,6 +78,7 @@ impl EpochManagerService {
                     tx.clone(),
                     node_identity,
                     validator_node_client_factory,
+                    tx_state_sync_progress,
                 ),
                 events: (tx, rx),
             }
@@ -152,6 +155,10 @@ impl EpochManagerService {
             EpochManagerRequest::GetValidatorNodeMerkleRoot { epoch, reply } => {
                 handle(reply, self.inner.get_validator_node_merkle_root(epoch))
             },
+            EpochManagerRequest::GetEpochTimestamp { epoch, reply } => {
+                handle(reply, self.inner.get_epoch_timestamp(epoch))
+            },
+            EpochManagerRequest::GetEpochBeacon { epoch, reply } => handle(reply, self.inner.get_epoch_beacon(epoch)),
             EpochManagerRequest::GetValidatorNodesPerEpoch { epoch, reply } => {
                 handle(reply, self.inner.get_validator_nodes_per_epoch(epoch))
             },
@@ -23,26 +23,34 @@
 use std::sync::Arc;
 
 use tari_comms::NodeIdentity;
-use tari_dan_app_utilities::{base_node_client::GrpcBaseNodeClient, epoch_manager::EpochManagerHandle};
+use tari_dan_app_utilities::{
+    base_node_client::GrpcBaseNodeClient,
+    epoch_manager::EpochManagerHandle,
+    shard_store::ShardStoreBackend,
+};
 use tari_dan_core::consensus_constants::ConsensusConstants;
 use tari_dan_storage::global::GlobalDb;
-use tari_dan_storage_sqlite::{global::SqliteGlobalDbAdapter, sqlite_shard_store_factory::SqliteShardStore};
+use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
 use tari_shutdown::ShutdownSignal;
-use tokio::{sync::mpsc, task::JoinHandle};
+use tokio::{
+    sync::{mpsc, watch},
+    task::JoinHandle,
+};
 
 use crate::p2p::services::{
-    epoch_manager::epoch_manager_service::EpochManagerService,
+    epoch_manager::{epoch_manager_service::EpochManagerService, StateSyncProgress},
     rpc_client::TariCommsValidatorNodeClientFactory,
 };
 
 pub fn spawn(
     global_db: GlobalDb<SqliteGlobalDbAdapter>,
-    shard_store: SqliteShardStore,
+    shard_store: ShardStoreBackend,
     base_node_client: GrpcBaseNodeClient,
     consensus_constants: ConsensusConstants,
     shutdown: ShutdownSignal,
     node_identity: Arc<NodeIdentity>,
     validator_node_client_factory: TariCommsValidatorNodeClientFactory,
+    tx_state_sync_progress: watch::Sender<Option<StateSyncProgress>>,
 ) -> (EpochManagerHandle, JoinHandle<anyhow::Result<()>>) {
     let (tx_request, rx_request) = mpsc::channel(10);
     let epoch_manager = EpochManagerHandle::new(tx_request);
@@ -55,6 +63,7 @@ pub fn spawn(
         consensus_constants,
         node_identity,
         validator_node_client_factory,
+        Arc::new(tx_state_sync_progress),
     );
     (epoch_manager, handle)
 }
@@ -27,4 +27,4 @@ mod initializer;
 mod state_sync;
 
 pub use initializer::spawn;
-pub use state_sync::PeerSyncManagerService;
+pub use state_sync::{PeerSyncManagerService, StateSyncProgress};
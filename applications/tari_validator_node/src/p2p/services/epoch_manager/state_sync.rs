@@ -20,103 +20,707 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::convert::TryInto;
+use std::{collections::HashSet, future::Future, sync::Arc, time::Duration};
 
-use futures::StreamExt;
-use log::info;
+use anyhow::anyhow;
+use futures::future;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::FixedHash;
 use tari_comms::types::CommsPublicKey;
-use tari_dan_common_types::ShardId;
+use tari_dan_app_grpc::proto::rpc::{VnStateSyncChunkRequest, VnStateSyncResponse};
+use tari_dan_common_types::{Epoch, QuorumCertificate, ShardId};
 use tari_dan_core::{
-    models::{SubstateShardData, ValidatorNode},
+    models::{HotStuffTreeNode, SubstateShardData, TariDanPayload, ValidatorNode},
     services::{epoch_manager::EpochManagerError, ValidatorNodeClientFactory},
     storage::shard_store::{ShardStore, ShardStoreReadTransaction, ShardStoreWriteTransaction},
 };
+use tari_dan_storage::global::{GlobalDb, MetadataKey};
+use tari_dan_storage_sqlite::global::SqliteGlobalDbAdapter;
+use tokio::{sync::watch, time};
 
 use crate::p2p::services::rpc_client::TariCommsValidatorNodeClientFactory;
 
 const LOG_TARGET: &str = "tari::validator_node::state_sync";
 
+/// The number of substates requested from peers in each chunk
+const STATE_SYNC_CHUNK_SIZE: u32 = 100;
+/// The number of times peers are asked for a chunk before the sync fails
+const STATE_SYNC_MAX_ATTEMPTS: usize = 5;
+/// How long to wait before the first retry. The wait doubles after every attempt.
+const STATE_SYNC_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// The progress of a state sync. It is saved as a checkpoint after every chunk so that an interrupted sync resumes
+/// from the last synced chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateSyncProgress {
+    pub epoch: Epoch,
+    pub start_shard_id: ShardId,
+    pub end_shard_id: ShardId,
+    /// The shard id at which the next chunk starts, or None if the whole range has been synced
+    pub next_shard_id: Option<ShardId>,
+    pub chunks_synced: u64,
+    pub substates_synced: u64,
+}
+
+impl StateSyncProgress {
+    fn new(epoch: Epoch, start_shard_id: ShardId, end_shard_id: ShardId) -> Self {
+        Self {
+            epoch,
+            start_shard_id,
+            end_shard_id,
+            next_shard_id: Some(start_shard_id),
+            chunks_synced: 0,
+            substates_synced: 0,
+        }
+    }
+
+    /// Returns the checkpoint if it is for the same epoch and shard range, otherwise starts a new sync
+    fn resume_or_start(checkpoint: Option<Self>, epoch: Epoch, start_shard_id: ShardId, end_shard_id: ShardId) -> Self {
+        match checkpoint {
+            Some(checkpoint)
+                if checkpoint.epoch == epoch &&
+                    checkpoint.start_shard_id == start_shard_id &&
+                    checkpoint.end_shard_id == end_shard_id =>
+            {
+                info!(
+                    target: LOG_TARGET,
+                    "🌍 Resuming state sync from checkpoint: {} chunk(s) and {} substate(s) already synced",
+                    checkpoint.chunks_synced,
+                    checkpoint.substates_synced
+                );
+                checkpoint
+            },
+            _ => Self::new(epoch, start_shard_id, end_shard_id),
+        }
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.next_shard_id.is_none()
+    }
+}
+
+/// A chunk of verified substates received from a peer
+struct StateSyncChunk {
+    substates: Vec<SubstateShardData>,
+    value_hashes: Vec<FixedHash>,
+    /// The nodes that certify the substates, stored so that this node can serve the substates to other peers
+    certified_nodes: Vec<HotStuffTreeNode<CommsPublicKey, TariDanPayload>>,
+    next_shard_id: Option<ShardId>,
+}
+
+impl StateSyncChunk {
+    fn has_same_state(&self, other: &Self) -> bool {
+        self.next_shard_id == other.next_shard_id && self.value_hashes == other.value_hashes
+    }
+}
+
 pub struct PeerSyncManagerService<TShardStore> {
     validator_node_client_factory: TariCommsValidatorNodeClientFactory,
     shard_store: TShardStore,
+    global_db: GlobalDb<SqliteGlobalDbAdapter>,
+    tx_progress: Arc<watch::Sender<Option<StateSyncProgress>>>,
 }
 
 impl<TShardStore: ShardStore> PeerSyncManagerService<TShardStore> {
     pub(crate) fn new(
         validator_node_client_factory: TariCommsValidatorNodeClientFactory,
         shard_store: TShardStore,
+        global_db: GlobalDb<SqliteGlobalDbAdapter>,
+        tx_progress: Arc<watch::Sender<Option<StateSyncProgress>>>,
     ) -> Self {
         Self {
             validator_node_client_factory,
             shard_store,
+            global_db,
+            tx_progress,
         }
     }
 
-    pub(crate) async fn sync_peers_state(
+    /// Syncs the substates in the shard range from the other members of the committee. Every chunk is requested from
+    /// all peers in parallel and is only stored once enough peers agree on its contents that at least one of them is
+    /// honest. Each substate must be proven against the state root of a node certified by a QC, which is checked with
+    /// `verify_qc`.
+    pub(crate) async fn sync_peers_state<TVerifyQc>(
         &self,
+        verify_qc: &TVerifyQc,
+        epoch: Epoch,
         committee_vns: Vec<ValidatorNode<CommsPublicKey>>,
         start_shard_id: ShardId,
         end_shard_id: ShardId,
         vn_shard_key: ShardId,
-    ) -> Result<(), EpochManagerError> {
-        let inventory = {
-            let mut shard_db = self.shard_store.create_read_tx()?;
-            shard_db
-                .get_state_inventory()
-                .map_err(EpochManagerError::StorageError)?
-        };
-
-        let inventory = inventory
+    ) -> Result<(), EpochManagerError>
+    where
+        TVerifyQc: Fn(&QuorumCertificate) -> Result<(), EpochManagerError> + Sync,
+    {
+        let peers = committee_vns
             .into_iter()
-            .map(tari_dan_app_grpc::proto::common::ShardId::from)
+            .filter(|vn| vn.shard_key != vn_shard_key)
+            .map(|vn| vn.public_key)
             .collect::<Vec<_>>();
+        // With n = 3f + 1 committee members, f + 1 matching responses include at least one honest member
+        let quorum = peers.len() / 3 + 1;
 
-        // the validator node has to sync state with vn's in the committee
-        for sync_vn in committee_vns {
-            if sync_vn.shard_key == vn_shard_key {
-                continue;
-            }
-            info!(target: LOG_TARGET, "🌍 Connecting to sync peer: {}", sync_vn.public_key);
-            let mut sync_vn_client = self.validator_node_client_factory.create_client(&sync_vn.public_key);
-            let mut sync_vn_rpc_client = sync_vn_client
-                .create_connection()
-                .await
-                .map_err(EpochManagerError::ValidatorNodeClientError)?;
-            let request = tari_dan_app_grpc::proto::rpc::VnStateSyncRequest {
+        let mut progress =
+            StateSyncProgress::resume_or_start(self.load_checkpoint()?, epoch, start_shard_id, end_shard_id);
+        if peers.is_empty() {
+            info!(target: LOG_TARGET, "🌍 No committee peers to sync from");
+            progress.next_shard_id = None;
+        }
+        self.save_checkpoint(&progress)?;
+
+        while let Some(chunk_start) = progress.next_shard_id {
+            let chunk = fetch_agreed_chunk(&peers, quorum, chunk_start, STATE_SYNC_INITIAL_BACKOFF, |peer| {
+                self.fetch_chunk(verify_qc, peer, chunk_start, end_shard_id)
+            })
+            .await?;
+            let num_substates = chunk.substates.len();
+            self.shard_store.with_write_tx(|tx| {
+                // Substates may already exist if the node restarted before the checkpoint for this chunk was saved
+                let shards = chunk.substates.iter().map(|s| s.shard_id()).collect::<Vec<_>>();
+                let existing = tx
+                    .get_substate_states(&shards)?
+                    .into_iter()
+                    .map(|s| s.shard_id())
+                    .collect::<HashSet<_>>();
+                for substate in chunk.substates {
+                    if !existing.contains(&substate.shard_id()) {
+                        tx.insert_substates(substate)?;
+                    }
+                }
+                for node in chunk.certified_nodes {
+                    tx.save_node(node)?;
+                }
+                Ok::<_, EpochManagerError>(())
+            })?;
+
+            progress.next_shard_id = chunk.next_shard_id;
+            progress.chunks_synced += 1;
+            progress.substates_synced += num_substates as u64;
+            self.save_checkpoint(&progress)?;
+            info!(
+                target: LOG_TARGET,
+                "🌍 Synced chunk {} with {} substate(s)", progress.chunks_synced, num_substates
+            );
+        }
+
+        info!(
+            target: LOG_TARGET,
+            "🌍 Sync complete. {} substate(s) in {} chunk(s)", progress.substates_synced, progress.chunks_synced
+        );
+
+        Ok(())
+    }
+
+    async fn fetch_chunk<TVerifyQc>(
+        &self,
+        verify_qc: &TVerifyQc,
+        peer: &CommsPublicKey,
+        start_shard_id: ShardId,
+        end_shard_id: ShardId,
+    ) -> Result<StateSyncChunk, EpochManagerError>
+    where
+        TVerifyQc: Fn(&QuorumCertificate) -> Result<(), EpochManagerError> + Sync,
+    {
+        let mut client = self.validator_node_client_factory.create_client(peer);
+        let mut rpc_client = client.create_connection().await?;
+        let response = rpc_client
+            .vn_state_sync_chunk(VnStateSyncChunkRequest {
                 start_shard_id: Some(start_shard_id.into()),
                 end_shard_id: Some(end_shard_id.into()),
-                inventory: inventory.clone(),
+                max_substates: STATE_SYNC_CHUNK_SIZE,
+            })
+            .await?;
+
+        let next_shard_id = response.next_shard_id.map(ShardId::try_from).transpose()?;
+        let mut substates = Vec::with_capacity(response.substates.len());
+        let mut certified_nodes = Vec::new();
+        for msg in response.substates {
+            let (substate, certified_node) = verify_certified_substate(msg, verify_qc)?;
+            match certified_node {
+                Some(node) => certified_nodes.push(node),
+                None => self.verify_bootstrapped_substate(&substate)?,
+            }
+            substates.push(substate);
+        }
+
+        validate_chunk_bounds(&substates, next_shard_id, start_shard_id, end_shard_id)?;
+        let value_hashes = substates.iter().map(|s| s.state_tree_value_hash()).collect();
+        Ok(StateSyncChunk {
+            substates,
+            value_hashes,
+            certified_nodes,
+            next_shard_id,
+        })
+    }
+
+    /// Substates created when the validator node was bootstrapped are not certified by any node. Every validator node
+    /// creates them, so they are only accepted if this node holds exactly the same substate.
+    fn verify_bootstrapped_substate(&self, substate: &SubstateShardData) -> Result<(), EpochManagerError> {
+        let local = self
+            .shard_store
+            .with_read_tx(|tx| tx.get_substate_states(&[substate.shard_id()]))?
+            .pop();
+        if local.map(|s| s.state_tree_value_hash()) != Some(substate.state_tree_value_hash()) {
+            return Err(anyhow!(
+                "Peer provided substate {} without a certified node",
+                substate.shard_id()
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn load_checkpoint(&self) -> Result<Option<StateSyncProgress>, EpochManagerError> {
+        let mut tx = self.global_db.create_transaction()?;
+        let checkpoint = self
+            .global_db
+            .metadata(&mut tx)
+            .get_metadata(MetadataKey::StateSyncCheckpoint)?;
+        Ok(checkpoint)
+    }
+
+    fn save_checkpoint(&self, progress: &StateSyncProgress) -> Result<(), EpochManagerError> {
+        let mut tx = self.global_db.create_transaction()?;
+        self.global_db
+            .metadata(&mut tx)
+            .set_metadata(MetadataKey::StateSyncCheckpoint, progress)?;
+        tx.commit()?;
+        let _ignore = self.tx_progress.send(Some(progress.clone()));
+        Ok(())
+    }
+}
+
+/// Requests the chunk from the peers until `quorum` of them agree on its contents. Peers that fail to respond are
+/// asked again after a backoff. If every peer responded without reaching a quorum, e.g. because the committee was
+/// committing a payload in the range, all peers are asked again. The sync fails after
+/// [STATE_SYNC_MAX_ATTEMPTS] attempts.
+async fn fetch_agreed_chunk<'a, TFetch, TFut>(
+    peers: &'a [CommsPublicKey],
+    quorum: usize,
+    start_shard_id: ShardId,
+    initial_backoff: Duration,
+    fetch: TFetch,
+) -> Result<StateSyncChunk, EpochManagerError>
+where
+    TFetch: Fn(&'a CommsPublicKey) -> TFut,
+    TFut: Future<Output = Result<StateSyncChunk, EpochManagerError>>,
+{
+    let mut candidates = Vec::<(StateSyncChunk, usize)>::new();
+    let mut pending = peers.iter().collect::<Vec<_>>();
+    let mut backoff = initial_backoff;
+    for attempt in 1..=STATE_SYNC_MAX_ATTEMPTS {
+        if pending.is_empty() {
+            candidates.clear();
+            pending = peers.iter().collect();
+        }
+        let responses = future::join_all(pending.iter().copied().map(&fetch)).await;
+
+        let mut failed = Vec::new();
+        for (peer, response) in pending.into_iter().zip(responses) {
+            let chunk = match response {
+                Ok(chunk) => chunk,
+                Err(err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "🌍 Failed to fetch state chunk from peer {}: {}", peer, err
+                    );
+                    failed.push(peer);
+                    continue;
+                },
             };
-            let mut vn_state_stream = sync_vn_rpc_client
-                .vn_state_sync(request)
-                .await
-                .map_err(EpochManagerError::RpcError)?;
-            info!(target: LOG_TARGET, "🌍 Syncing...");
-            let mut substate_count = 0;
-            while let Some(resp) = vn_state_stream.next().await {
-                let msg = resp.map_err(EpochManagerError::RpcStatus)?;
-                let substate_shard_data: SubstateShardData =
-                    msg.try_into().map_err(EpochManagerError::InvalidStateSyncData)?;
-
-                // insert response state values in the shard db
-                self.shard_store.with_write_tx(|tx| {
-                    tx.insert_substates(substate_shard_data)
-                        .map_err(EpochManagerError::StorageError)
-                })?;
-
-                // increase node inventory
-                // inventory.push(sync_vn_shard.into());
-                substate_count += 1;
+            match candidates.iter_mut().find(|(c, _)| c.has_same_state(&chunk)) {
+                Some((_, votes)) => *votes += 1,
+                None => candidates.push((chunk, 1)),
             }
+        }
 
-            info!(
+        if let Some(index) = candidates.iter().position(|(_, votes)| *votes >= quorum) {
+            return Ok(candidates.swap_remove(index).0);
+        }
+        pending = failed;
+
+        if attempt < STATE_SYNC_MAX_ATTEMPTS {
+            warn!(
                 target: LOG_TARGET,
-                "🌍 Sync from peer {} complete. {} substate(s)", sync_vn.public_key, substate_count
+                "🌍 No quorum of peers agreed on the state chunk starting at {} (attempt {}/{}). Retrying in {:.0?}",
+                start_shard_id,
+                attempt,
+                STATE_SYNC_MAX_ATTEMPTS,
+                backoff
             );
+            time::sleep(backoff).await;
+            backoff *= 2;
         }
+    }
 
-        info!(target: LOG_TARGET, "🌍 Sync complete.");
+    Err(EpochManagerError::StateSyncNoQuorum { start_shard_id })
+}
 
-        Ok(())
+/// Verifies a substate in a state sync chunk against the node certified by the justify of the last change to the
+/// substate. That node's state root commits to the state that the change left the substate in. Returns the substate
+/// and the certified node, or no node if the substate was created when the validator node was bootstrapped.
+fn verify_certified_substate<TVerifyQc>(
+    msg: VnStateSyncResponse,
+    verify_qc: &TVerifyQc,
+) -> Result<
+    (
+        SubstateShardData,
+        Option<HotStuffTreeNode<CommsPublicKey, TariDanPayload>>,
+    ),
+    EpochManagerError,
+>
+where
+    TVerifyQc: Fn(&QuorumCertificate) -> Result<(), EpochManagerError>,
+{
+    let Some(certified_node) = msg.certified_node.clone() else {
+        let substate = SubstateShardData::try_from(msg)?;
+        let justify = substate.destroyed_justify().as_ref().unwrap_or_else(|| substate.created_justify());
+        if !justify.node_hash().is_zero() {
+            return Err(anyhow!("Peer did not provide a certified node for substate {}", substate.shard_id()).into());
+        }
+        return Ok((substate, None));
+    };
+    let certified_node: HotStuffTreeNode<CommsPublicKey, TariDanPayload> = certified_node.try_into()?;
+    let substate = msg.into_verified_substate(certified_node.state_root())?;
+    let justify = substate
+        .destroyed_justify()
+        .as_ref()
+        .unwrap_or_else(|| substate.created_justify());
+    if justify.node_hash() != *certified_node.hash() || certified_node.shard() != substate.shard_id() {
+        return Err(anyhow!(
+            "Peer provided a node that is not certified for substate {}",
+            substate.shard_id()
+        )
+        .into());
+    }
+    verify_qc(justify)?;
+    Ok((substate, Some(certified_node)))
+}
+
+/// Checks that the substates are ordered, lie within the requested range and that the chunk makes progress
+fn validate_chunk_bounds(
+    substates: &[SubstateShardData],
+    next_shard_id: Option<ShardId>,
+    start_shard_id: ShardId,
+    end_shard_id: ShardId,
+) -> Result<(), anyhow::Error> {
+    if substates.len() > STATE_SYNC_CHUNK_SIZE as usize {
+        return Err(anyhow!("Chunk contains more than {} substates", STATE_SYNC_CHUNK_SIZE));
+    }
+    let mut previous = None;
+    for shard_id in substates.iter().map(|s| s.shard_id()) {
+        if shard_id.0 < start_shard_id.0 || shard_id.0 > end_shard_id.0 {
+            return Err(anyhow!("Substate {} is outside of the requested range", shard_id));
+        }
+        if previous.map(|p: ShardId| p.0 >= shard_id.0).unwrap_or(false) {
+            return Err(anyhow!("Substates are not ordered by shard id"));
+        }
+        previous = Some(shard_id);
+    }
+    if let Some(next_shard_id) = next_shard_id {
+        let last = previous.ok_or_else(|| anyhow!("Chunk is empty but is not the last chunk"))?;
+        if next_shard_id.0 <= last.0 || next_shard_id.0 > end_shard_id.0 {
+            return Err(anyhow!("Invalid next shard id {}", next_shard_id));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use tari_dan_common_types::{crypto::create_key_pair, NodeHeight, PayloadId, TreeNodeHash};
+    use tari_engine_types::{
+        resource::Resource,
+        substate::{Substate, SubstateAddress},
+    };
+    use tari_template_lib::{models::ResourceAddress, prelude::ResourceType, Hash};
+
+    use super::*;
+
+    fn shard(n: u8) -> ShardId {
+        ShardId([n; 32])
+    }
+
+    fn create_substate(shard_id: ShardId) -> SubstateShardData {
+        let address = SubstateAddress::Resource(ResourceAddress::new(Hash::from(shard_id.0)));
+        let created_by = PayloadId::new([0u8; 32]);
+        SubstateShardData::new(
+            shard_id,
+            address,
+            0,
+            Substate::new(
+                0,
+                Resource::new(ResourceType::Fungible, Default::default(), Default::default()),
+            ),
+            NodeHeight(0),
+            None,
+            TreeNodeHash::zero(),
+            None,
+            created_by,
+            None,
+            QuorumCertificate::genesis(Epoch(0), created_by, shard_id),
+            None,
+        )
+    }
+
+    fn create_chunk(state: u8) -> StateSyncChunk {
+        StateSyncChunk {
+            substates: vec![],
+            value_hashes: vec![FixedHash::from([state; 32])],
+            certified_nodes: vec![],
+            next_shard_id: None,
+        }
+    }
+
+    fn create_peers(n: usize) -> Vec<CommsPublicKey> {
+        (0..n).map(|_| create_key_pair().1).collect()
+    }
+
+    mod validate_chunk_bounds {
+        use super::*;
+
+        fn validate(shards: &[u8], next_shard_id: Option<u8>) -> Result<(), anyhow::Error> {
+            let substates = shards.iter().map(|n| create_substate(shard(*n))).collect::<Vec<_>>();
+            validate_chunk_bounds(&substates, next_shard_id.map(shard), shard(10), shard(20))
+        }
+
+        #[test]
+        fn it_accepts_ordered_substates_in_range() {
+            validate(&[10, 15, 20], None).unwrap();
+            validate(&[10, 15], Some(16)).unwrap();
+            validate(&[], None).unwrap();
+        }
+
+        #[test]
+        fn it_rejects_substates_outside_of_the_range() {
+            validate(&[9, 15], None).unwrap_err();
+            validate(&[15, 21], None).unwrap_err();
+        }
+
+        #[test]
+        fn it_rejects_unordered_or_duplicate_substates() {
+            validate(&[15, 12], None).unwrap_err();
+            validate(&[12, 12], None).unwrap_err();
+        }
+
+        #[test]
+        fn it_rejects_chunks_that_do_not_make_progress() {
+            validate(&[], Some(15)).unwrap_err();
+            validate(&[12, 15], Some(15)).unwrap_err();
+            validate(&[12, 15], Some(11)).unwrap_err();
+            validate(&[12, 15], Some(21)).unwrap_err();
+        }
+
+        #[test]
+        fn it_rejects_oversized_chunks() {
+            let substates = (0..=STATE_SYNC_CHUNK_SIZE)
+                .map(|i| {
+                    let mut shard_id = shard(15);
+                    shard_id.0[28..].copy_from_slice(&i.to_be_bytes());
+                    create_substate(shard_id)
+                })
+                .collect::<Vec<_>>();
+            validate_chunk_bounds(&substates, None, shard(10), shard(20)).unwrap_err();
+            validate_chunk_bounds(&substates[1..], None, shard(10), shard(20)).unwrap();
+        }
+    }
+
+    mod verify_certified_substate {
+        use tari_dan_common_types::{AggregatedSignature, QuorumDecision, ShardPledgeCollection};
+        use tari_dan_core::models::StateTreeProof;
+
+        use super::*;
+
+        fn create_certified_substate(
+            shard_id: ShardId,
+        ) -> (VnStateSyncResponse, HotStuffTreeNode<CommsPublicKey, TariDanPayload>) {
+            let payload_id = PayloadId::new([1u8; 32]);
+            let uncertified = create_substate(shard_id);
+            let state_root = StateTreeProof::default().compute_root(&shard_id, &uncertified.state_tree_value_hash());
+            let node = HotStuffTreeNode::new(
+                TreeNodeHash::zero(),
+                shard_id,
+                NodeHeight(3),
+                payload_id,
+                None,
+                NodeHeight(3),
+                0,
+                None,
+                Epoch(0),
+                create_key_pair().1,
+                QuorumCertificate::genesis(Epoch(0), payload_id, shard_id),
+                state_root,
+            );
+            let justify = QuorumCertificate::new(
+                payload_id,
+                NodeHeight(3),
+                *node.hash(),
+                node.height(),
+                shard_id,
+                Epoch(0),
+                QuorumDecision::Accept,
+                ShardPledgeCollection::empty(),
+                AggregatedSignature::default(),
+            );
+            let substate = SubstateShardData::new(
+                shard_id,
+                uncertified.substate_address().clone(),
+                uncertified.version(),
+                uncertified.substate().clone(),
+                NodeHeight(4),
+                None,
+                TreeNodeHash::zero(),
+                None,
+                uncertified.created_payload_id(),
+                None,
+                justify,
+                None,
+            );
+            let mut msg = VnStateSyncResponse::try_from(substate).unwrap();
+            msg.state_proof = Some(StateTreeProof::default().into());
+            (msg, node)
+        }
+
+        fn accept_all(_: &QuorumCertificate) -> Result<(), EpochManagerError> {
+            Ok(())
+        }
+
+        #[test]
+        fn it_accepts_a_substate_proven_against_the_node_certified_by_its_justify() {
+            let (mut msg, node) = create_certified_substate(shard(1));
+            msg.certified_node = Some(node.clone().into());
+            let (substate, certified_node) = verify_certified_substate(msg, &accept_all).unwrap();
+            assert_eq!(substate.shard_id(), shard(1));
+            assert_eq!(certified_node.unwrap().hash(), node.hash());
+        }
+
+        #[test]
+        fn it_rejects_a_node_that_is_not_certified_by_the_justify() {
+            let (mut msg, _) = create_certified_substate(shard(1));
+            let (_, other_node) = create_certified_substate(shard(1));
+            msg.certified_node = Some(other_node.into());
+            verify_certified_substate(msg, &accept_all).unwrap_err();
+        }
+
+        #[test]
+        fn it_rejects_a_certified_substate_without_a_node() {
+            let (msg, _) = create_certified_substate(shard(1));
+            verify_certified_substate(msg, &accept_all).unwrap_err();
+        }
+
+        #[test]
+        fn it_rejects_a_substate_when_the_qc_is_invalid() {
+            let (mut msg, node) = create_certified_substate(shard(1));
+            msg.certified_node = Some(node.into());
+            verify_certified_substate(msg, &|_| Err(anyhow!("invalid QC").into())).unwrap_err();
+        }
+    }
+
+    mod resume_or_start {
+        use super::*;
+
+        #[test]
+        fn it_resumes_from_a_checkpoint_for_the_same_sync() {
+            let mut checkpoint = StateSyncProgress::new(Epoch(1), shard(10), shard(20));
+            checkpoint.next_shard_id = Some(shard(15));
+            checkpoint.chunks_synced = 3;
+            let progress = StateSyncProgress::resume_or_start(Some(checkpoint.clone()), Epoch(1), shard(10), shard(20));
+            assert_eq!(progress, checkpoint);
+        }
+
+        #[test]
+        fn it_restarts_when_the_checkpoint_is_for_another_sync() {
+            let mut checkpoint = StateSyncProgress::new(Epoch(1), shard(10), shard(20));
+            checkpoint.next_shard_id = Some(shard(15));
+            let fresh = StateSyncProgress::new(Epoch(2), shard(10), shard(20));
+            let progress = StateSyncProgress::resume_or_start(Some(checkpoint.clone()), Epoch(2), shard(10), shard(20));
+            assert_eq!(progress, fresh);
+            let progress = StateSyncProgress::resume_or_start(Some(checkpoint), Epoch(1), shard(11), shard(20));
+            assert_eq!(progress.next_shard_id, Some(shard(11)));
+            let progress = StateSyncProgress::resume_or_start(None, Epoch(2), shard(10), shard(20));
+            assert_eq!(progress, fresh);
+        }
+    }
+
+    mod fetch_agreed_chunk {
+        use super::*;
+
+        #[tokio::test]
+        async fn it_returns_the_chunk_that_a_quorum_agrees_on() {
+            let peers = create_peers(4);
+            let chunk = fetch_agreed_chunk(&peers, 2, shard(0), Duration::ZERO, |peer| {
+                let index = peers.iter().position(|p| p == peer).unwrap();
+                future::ready(match index {
+                    0 => Ok(create_chunk(2)),
+                    3 => Err(anyhow!("offline").into()),
+                    _ => Ok(create_chunk(1)),
+                })
+            })
+            .await
+            .unwrap();
+            assert!(chunk.has_same_state(&create_chunk(1)));
+        }
+
+        #[tokio::test]
+        async fn it_retries_peers_that_failed() {
+            let peers = create_peers(3);
+            let calls = peers.iter().map(|_| AtomicUsize::new(0)).collect::<Vec<_>>();
+            let chunk = fetch_agreed_chunk(&peers, 2, shard(0), Duration::ZERO, |peer| {
+                let index = peers.iter().position(|p| p == peer).unwrap();
+                let call = calls[index].fetch_add(1, Ordering::SeqCst);
+                future::ready(match index {
+                    0 => Ok(create_chunk(1)),
+                    // Fails twice before it responds
+                    1 if call < 2 => Err(anyhow!("timeout").into()),
+                    1 => Ok(create_chunk(1)),
+                    _ => Ok(create_chunk(2)),
+                })
+            })
+            .await
+            .unwrap();
+            assert!(chunk.has_same_state(&create_chunk(1)));
+            // Peers that responded are not asked again
+            assert_eq!(calls[0].load(Ordering::SeqCst), 1);
+            assert_eq!(calls[1].load(Ordering::SeqCst), 3);
+            assert_eq!(calls[2].load(Ordering::SeqCst), 1);
+        }
+
+        #[tokio::test]
+        async fn it_asks_every_peer_again_when_they_disagree() {
+            let peers = create_peers(3);
+            let num_calls = AtomicUsize::new(0);
+            let chunk = fetch_agreed_chunk(&peers, 2, shard(0), Duration::ZERO, |peer| {
+                let index = peers.iter().position(|p| p == peer).unwrap();
+                let call = num_calls.fetch_add(1, Ordering::SeqCst);
+                // Every peer has a different state in the first attempt
+                let state = if call < peers.len() { index as u8 } else { 1 };
+                future::ready(Ok(create_chunk(state)))
+            })
+            .await
+            .unwrap();
+            assert!(chunk.has_same_state(&create_chunk(1)));
+            assert_eq!(num_calls.load(Ordering::SeqCst), peers.len() * 2);
+        }
+
+        #[tokio::test]
+        async fn it_fails_when_peers_never_agree() {
+            let peers = create_peers(3);
+            let num_calls = AtomicUsize::new(0);
+            let err = fetch_agreed_chunk(&peers, 2, shard(7), Duration::ZERO, |peer| {
+                num_calls.fetch_add(1, Ordering::SeqCst);
+                let index = peers.iter().position(|p| p == peer).unwrap();
+                future::ready(Ok(create_chunk(index as u8)))
+            })
+            .await
+            .err()
+            .unwrap();
+            assert!(
+                matches!(err, EpochManagerError::StateSyncNoQuorum { start_shard_id } if start_shard_id == shard(7))
+            );
+            assert_eq!(num_calls.load(Ordering::SeqCst), peers.len() * STATE_SYNC_MAX_ATTEMPTS);
+        }
     }
 }
@@ -24,26 +24,24 @@ use std::{fmt::Display, sync::Arc};
 
 use log::*;
 use tari_comms::{types::CommsPublicKey, NodeIdentity};
-use tari_dan_app_utilities::epoch_manager::EpochManagerHandle;
+use tari_dan_app_utilities::{epoch_manager::EpochManagerHandle, shard_store::ShardStoreBackend};
 use tari_dan_common_types::ShardId;
 use tari_dan_core::{
     consensus_constants::ConsensusConstants,
     message::DanMessage,
-    models::{vote_message::VoteMessage, HotStuffMessage, TariDanPayload},
+    models::{vote_message::VoteMessage, HotStuffMessage, Payload, TariDanPayload},
     services::{
         infrastructure_services::OutboundService,
-        leader_strategy::PayloadSpecificLeaderStrategy,
+        leader_strategy::ConfiguredLeaderStrategy,
         NodeIdentitySigningService,
     },
     workers::{
         events::{EventSubscription, HotStuffEvent},
-        hotstuff_waiter::{HotStuffWaiter, RecoveryMessage, NETWORK_LATENCY},
+        hotstuff_waiter::{HotStuffWaiter, RecoveryMessage},
         pacemaker_worker::Pacemaker,
     },
 };
-use tari_dan_storage_sqlite::sqlite_shard_store_factory::SqliteShardStore;
 use tari_shutdown::ShutdownSignal;
-use tari_transaction::Transaction;
 use tokio::{
     sync::{
         broadcast,
@@ -85,10 +83,11 @@ impl HotstuffService {
         mempool: MempoolHandle,
         outbound: OutboundMessaging,
         payload_processor: TariDanPayloadProcessor<TemplateManager>,
-        shard_store_factory: SqliteShardStore,
+        shard_store_factory: ShardStoreBackend,
         rx_hotstuff_messages: Receiver<(CommsPublicKey, HotStuffMessage<TariDanPayload, CommsPublicKey>)>,
         rx_recovery_messages: Receiver<(CommsPublicKey, RecoveryMessage)>,
         rx_vote_messages: Receiver<(CommsPublicKey, VoteMessage)>,
+        consensus_constants: ConsensusConstants,
         shutdown: ShutdownSignal,
     ) -> (
         EventSubscription<HotStuffEvent>,
@@ -104,8 +103,12 @@ impl HotstuffService {
         let (tx_vote_message, rx_vote_message) = channel(100);
         let (tx_events, _) = broadcast::channel(100);
 
-        let leader_strategy = PayloadSpecificLeaderStrategy {};
-        let consensus_constants = ConsensusConstants::devnet();
+        info!(
+            target: LOG_TARGET,
+            "Using the {} leader strategy", consensus_constants.leader_strategy
+        );
+        let leader_strategy =
+            ConfiguredLeaderStrategy::new(consensus_constants.leader_strategy, shard_store_factory.clone());
         let node_public_key = node_identity.public_key().clone();
         let pacemaker = Pacemaker::spawn(shutdown.clone());
 
@@ -131,7 +134,6 @@ impl HotstuffService {
             shard_store_factory,
             shutdown.clone(),
             consensus_constants,
-            NETWORK_LATENCY,
         );
 
         let service_join_handle = tokio::spawn(
@@ -222,8 +224,8 @@ impl HotstuffService {
         Ok(())
     }
 
-    async fn handle_new_valid_transaction(&mut self, tx: Transaction, shard: ShardId) -> Result<(), anyhow::Error> {
-        self.tx_new.send((TariDanPayload::new(tx), shard)).await?;
+    async fn handle_new_valid_payload(&mut self, payload: TariDanPayload, shard: ShardId) -> Result<(), anyhow::Error> {
+        self.tx_new.send((payload, shard)).await?;
         Ok(())
     }
 
@@ -231,10 +233,10 @@ impl HotstuffService {
         loop {
             tokio::select! {
                 // Inbound
-                res = self.mempool.next_valid_transaction() => {
-                    if let Some((tx, shard_id)) = log(res, "new valid transaction") {
-                        debug!(target: LOG_TARGET, "Received new transaction {} for shard {}", tx.hash(), shard_id);
-                        log(self.handle_new_valid_transaction(tx, shard_id).await, "new valid transaction");
+                res = self.mempool.next_valid_payload() => {
+                    if let Some((payload, shard_id)) = log(res, "new valid payload") {
+                        debug!(target: LOG_TARGET, "Received new payload {} for shard {}", payload.to_id(), shard_id);
+                        log(self.handle_new_valid_payload(payload, shard_id).await, "new valid payload");
                     }
                 }
                 // Outbound
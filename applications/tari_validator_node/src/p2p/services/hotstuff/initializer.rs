@@ -23,15 +23,15 @@
 use std::sync::Arc;
 
 use tari_comms::{types::CommsPublicKey, NodeIdentity};
-use tari_dan_app_utilities::epoch_manager::EpochManagerHandle;
+use tari_dan_app_utilities::{epoch_manager::EpochManagerHandle, shard_store::ShardStoreBackend};
 use tari_dan_core::{
+    consensus_constants::ConsensusConstants,
     models::{vote_message::VoteMessage, HotStuffMessage, TariDanPayload},
     workers::{
         events::{EventSubscription, HotStuffEvent},
         hotstuff_waiter::RecoveryMessage,
     },
 };
-use tari_dan_storage_sqlite::sqlite_shard_store_factory::SqliteShardStore;
 use tari_shutdown::ShutdownSignal;
 use tokio::{sync::mpsc, task::JoinHandle};
 
@@ -47,7 +47,7 @@ use crate::{
 
 pub fn try_spawn(
     node_identity: Arc<NodeIdentity>,
-    shard_store: SqliteShardStore,
+    shard_store: ShardStoreBackend,
     outbound: OutboundMessaging,
     epoch_manager: EpochManagerHandle,
     mempool: MempoolHandle,
@@ -55,6 +55,7 @@ pub fn try_spawn(
     rx_consensus_message: mpsc::Receiver<(CommsPublicKey, HotStuffMessage<TariDanPayload, CommsPublicKey>)>,
     rx_recovery_message: mpsc::Receiver<(CommsPublicKey, RecoveryMessage)>,
     rx_vote_message: mpsc::Receiver<(CommsPublicKey, VoteMessage)>,
+    consensus_constants: ConsensusConstants,
     shutdown: ShutdownSignal,
 ) -> (
     EventSubscription<HotStuffEvent>,
@@ -71,6 +72,7 @@ pub fn try_spawn(
         rx_consensus_message,
         rx_recovery_message,
         rx_vote_message,
+        consensus_constants,
         shutdown,
     )
 }
@@ -20,7 +20,10 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::collections::HashMap;
+
 use tari_dan_common_types::ShardId;
+use tari_dan_core::models::TariDanPayload;
 use tari_template_lib::Hash;
 use tari_transaction::Transaction;
 use tokio::sync::{broadcast, broadcast::error::RecvError, mpsc, oneshot};
@@ -28,21 +31,38 @@ use tokio::sync::{broadcast, broadcast::error::RecvError, mpsc, oneshot};
 use crate::p2p::services::mempool::MempoolError;
 
 pub enum MempoolRequest {
-    SubmitTransaction(Box<Transaction>),
-    RemoveTransaction { transaction_hash: Hash },
-    GetMempoolSize { reply: oneshot::Sender<usize> },
+    SubmitTransaction {
+        transaction: Box<Transaction>,
+        reply: oneshot::Sender<Result<(), MempoolError>>,
+    },
+    RemoveTransaction {
+        transaction_hash: Hash,
+    },
+    GetMempoolStats {
+        reply: oneshot::Sender<MempoolStats>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct MempoolStats {
+    /// The number of transactions in the mempool
+    pub size: usize,
+    /// The maximum number of transactions that the mempool holds
+    pub max_size: usize,
+    /// The number of transactions in the mempool that involve each shard
+    pub shard_queue_depths: HashMap<ShardId, usize>,
 }
 
 #[derive(Debug)]
 pub struct MempoolHandle {
-    rx_valid_transactions: broadcast::Receiver<(Transaction, ShardId)>,
+    rx_valid_payloads: broadcast::Receiver<(TariDanPayload, ShardId)>,
     tx_mempool_request: mpsc::Sender<MempoolRequest>,
 }
 
 impl Clone for MempoolHandle {
     fn clone(&self) -> Self {
         MempoolHandle {
-            rx_valid_transactions: self.rx_valid_transactions.resubscribe(),
+            rx_valid_payloads: self.rx_valid_payloads.resubscribe(),
             tx_mempool_request: self.tx_mempool_request.clone(),
         }
     }
@@ -50,20 +70,25 @@ impl Clone for MempoolHandle {
 
 impl MempoolHandle {
     pub(super) fn new(
-        rx_valid_transactions: broadcast::Receiver<(Transaction, ShardId)>,
+        rx_valid_payloads: broadcast::Receiver<(TariDanPayload, ShardId)>,
         tx_mempool_request: mpsc::Sender<MempoolRequest>,
     ) -> Self {
         Self {
-            rx_valid_transactions,
+            rx_valid_payloads,
             tx_mempool_request,
         }
     }
 
+    /// Submits a transaction to the mempool, returning an error if the transaction was not admitted
     pub async fn submit_transaction(&self, transaction: Transaction) -> Result<(), MempoolError> {
+        let (tx, rx) = oneshot::channel();
         self.tx_mempool_request
-            .send(MempoolRequest::SubmitTransaction(Box::new(transaction)))
+            .send(MempoolRequest::SubmitTransaction {
+                transaction: Box::new(transaction),
+                reply: tx,
+            })
             .await?;
-        Ok(())
+        rx.await?
     }
 
     pub async fn remove_transaction(&self, transaction_hash: Hash) -> Result<(), MempoolError> {
@@ -73,14 +98,16 @@ impl MempoolHandle {
         Ok(())
     }
 
-    pub async fn next_valid_transaction(&mut self) -> Result<(Transaction, ShardId), RecvError> {
-        self.rx_valid_transactions.recv().await
+    /// Returns the next payload, a single transaction or a batch of transactions, that is ready for consensus on the
+    /// given shard
+    pub async fn next_valid_payload(&mut self) -> Result<(TariDanPayload, ShardId), RecvError> {
+        self.rx_valid_payloads.recv().await
     }
 
-    pub async fn get_mempool_size(&self) -> Result<usize, MempoolError> {
+    pub async fn get_mempool_stats(&self) -> Result<MempoolStats, MempoolError> {
         let (tx, rx) = oneshot::channel();
         self.tx_mempool_request
-            .send(MempoolRequest::GetMempoolSize { reply: tx })
+            .send(MempoolRequest::GetMempoolStats { reply: tx })
             .await?;
         rx.await.map_err(Into::into)
     }
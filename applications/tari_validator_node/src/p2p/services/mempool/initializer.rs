@@ -23,7 +23,7 @@
 use std::sync::Arc;
 
 use tari_comms::NodeIdentity;
-use tari_dan_app_utilities::epoch_manager::EpochManagerHandle;
+use tari_dan_app_utilities::{epoch_manager::EpochManagerHandle, shard_store::ShardStoreBackend};
 use tari_transaction::Transaction;
 use tokio::{
     sync::{broadcast, mpsc},
@@ -31,33 +31,76 @@ use tokio::{
     task::JoinHandle,
 };
 
-use crate::p2p::services::{
-    mempool::{handle::MempoolHandle, service::MempoolService, validator::MempoolTransactionValidator},
-    messaging::OutboundMessaging,
-    template_manager::TemplateManager,
+use crate::{
+    dry_run_transaction_processor::DryRunTransactionProcessor,
+    p2p::services::{
+        mempool::{
+            handle::MempoolHandle,
+            service::{MempoolChannels, MempoolService},
+            validators::{
+                DryRunValidator,
+                EpochRangeValidator,
+                InputsValidator,
+                SignatureValidator,
+                TemplateExistsValidator,
+                TransactionSizeValidator,
+            },
+            MempoolConfig,
+            Validator,
+        },
+        messaging::OutboundMessaging,
+        template_manager::TemplateManager,
+    },
 };
 
 pub fn spawn(
+    config: MempoolConfig,
     new_transactions: mpsc::Receiver<Transaction>,
+    new_transaction_batches: mpsc::Receiver<Vec<Transaction>>,
     outbound: OutboundMessaging,
     epoch_manager: EpochManagerHandle,
     node_identity: Arc<NodeIdentity>,
     template_manager: TemplateManager,
+    shard_store: ShardStoreBackend,
+    dry_run_transaction_processor: DryRunTransactionProcessor,
 ) -> (MempoolHandle, JoinHandle<anyhow::Result<()>>) {
-    let (tx_valid_transactions, rx_valid_transactions) = broadcast::channel(100);
+    let (tx_valid_payloads, rx_valid_payloads) = broadcast::channel(100);
     let (tx_mempool_request, rx_mempool_request) = mpsc::channel(1);
 
-    let validator = MempoolTransactionValidator::new(template_manager);
-    let mempool = MempoolService::new(
+    let dry_run_validator = if config.dry_run_enabled {
+        Some(DryRunValidator::new(
+            dry_run_transaction_processor.with_fuel_limit(config.dry_run_fuel_limit),
+            config.dry_run_timeout,
+        ))
+    } else {
+        None
+    };
+    let validator = TransactionSizeValidator::new(config.max_transaction_size)
+        .and_then(SignatureValidator)
+        .and_then(EpochRangeValidator::new(epoch_manager.clone()))
+        .and_then(TemplateExistsValidator::new(template_manager))
+        .and_then(InputsValidator::new(
+            shard_store.clone(),
+            epoch_manager.clone(),
+            node_identity.public_key().clone(),
+        ))
+        .and_then(dry_run_validator);
+    let channels = MempoolChannels {
         new_transactions,
-        rx_mempool_request,
+        new_transaction_batches,
+        mempool_requests: rx_mempool_request,
+        tx_valid_payloads,
+    };
+    let mempool = MempoolService::new(
+        config,
+        channels,
         outbound,
-        tx_valid_transactions,
         epoch_manager,
         node_identity,
+        shard_store,
         validator,
     );
-    let handle = MempoolHandle::new(rx_valid_transactions, tx_mempool_request);
+    let handle = MempoolHandle::new(rx_valid_payloads, tx_mempool_request);
 
     let join_handle = task::spawn(mempool.run());
 
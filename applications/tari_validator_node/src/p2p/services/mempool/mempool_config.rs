@@ -0,0 +1,56 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tari_common::configuration::serializers;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MempoolConfig {
+    /// The maximum number of submitted transactions that are decided together in a single consensus instance. Only
+    /// transactions that involve exactly the same shards are batched. A value of 1 disables batching.
+    pub max_batch_size: usize,
+    /// How long a submitted transaction waits for other compatible transactions before its batch is sent to
+    /// consensus
+    #[serde(with = "serializers::seconds")]
+    pub batch_timeout: Duration,
+    /// The maximum encoded size in bytes of a transaction that is admitted to the mempool
+    pub max_transaction_size: usize,
+    /// If true, transactions are executed against the current state before they are admitted to the mempool and are
+    /// rejected if execution fails. Submissions are handled one at a time, so this limits mempool throughput.
+    pub dry_run_enabled: bool,
+    /// How long the admission dry run may take before the transaction is rejected
+    #[serde(with = "serializers::seconds")]
+    pub dry_run_timeout: Duration,
+    /// The fuel, in WASM metering points and engine calls, that the admission dry run may consume before the
+    /// transaction is rejected. A dry run that times out is not cancelled, so this bounds the work it does.
+    pub dry_run_fuel_limit: u64,
+    /// The maximum number of transactions held in the mempool. When the mempool is full, the lowest priority
+    /// transactions are evicted to make room for higher priority transactions.
+    pub max_transactions: usize,
+    /// How long a transaction may remain in the mempool before it is expired
+    #[serde(with = "serializers::seconds")]
+    pub transaction_expiry: Duration,
+}
+
+impl MempoolConfig {
+    pub fn is_batching_enabled(&self) -> bool {
+        self.max_batch_size > 1
+    }
+}
+
+impl Default for MempoolConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 1,
+            batch_timeout: Duration::from_secs(1),
+            max_transaction_size: 256 * 1024,
+            dry_run_enabled: false,
+            dry_run_timeout: Duration::from_secs(5),
+            dry_run_fuel_limit: 100_000_000,
+            max_transactions: 10_000,
+            transaction_expiry: Duration::from_secs(60 * 60),
+        }
+    }
+}
@@ -26,16 +26,23 @@ pub use initializer::spawn;
 
 mod handle;
 use async_trait::async_trait;
-pub use handle::{MempoolHandle, MempoolRequest};
+pub use handle::{MempoolHandle, MempoolRequest, MempoolStats};
+
+mod mempool_config;
+pub use mempool_config::MempoolConfig;
 use tari_dan_app_utilities::template_manager::TemplateManagerError;
-use tari_dan_core::services::epoch_manager::EpochManagerError;
+use tari_dan_core::{services::epoch_manager::EpochManagerError, storage::StorageError};
+use tari_validator_node_client::types::TransactionRejectReason;
 use thiserror::Error;
 use tokio::sync::{mpsc::error::SendError, oneshot};
 
 use crate::p2p::services::messaging::MessagingError;
 
 mod service;
-mod validator;
+#[cfg(test)]
+mod test_utils;
+mod validators;
+pub use validators::AndThen;
 
 #[derive(Error, Debug)]
 pub enum MempoolError {
@@ -45,6 +52,10 @@ pub enum MempoolError {
     BroadcastFailed(#[from] MessagingError),
     #[error("Invalid template address: {0}")]
     InvalidTemplateAddress(#[from] TemplateManagerError),
+    #[error("Storage error: {0}")]
+    StorageError(#[from] StorageError),
+    #[error("Transaction rejected: {0}")]
+    Rejected(#[from] TransactionRejectReason),
     #[error("Internal service request cancelled")]
     RequestCancelled,
 }
@@ -66,4 +77,13 @@ pub trait Validator<T> {
     type Error;
 
     async fn validate(&self, input: &T) -> Result<(), Self::Error>;
+
+    /// Returns a validator that runs this validator and then, if it passes, the `next` validator
+    fn and_then<V>(self, next: V) -> AndThen<Self, V>
+    where
+        V: Validator<T, Error = Self::Error>,
+        Self: Sized,
+    {
+        AndThen::new(self, next)
+    }
 }
@@ -21,39 +21,89 @@
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    cmp::Reverse,
     collections::{HashMap, HashSet},
+    slice,
     sync::Arc,
+    time::Duration,
 };
 
+use chrono::Utc;
 use log::*;
+use tari_common_types::types::FixedHash;
 use tari_comms::NodeIdentity;
-use tari_dan_app_utilities::epoch_manager::EpochManagerHandle;
-use tari_dan_common_types::{ShardId, TreeNodeHash};
+use tari_dan_app_utilities::{epoch_manager::EpochManagerHandle, shard_store::ShardStoreBackend};
+use tari_dan_common_types::{optional::Optional, PayloadId, ShardId};
 use tari_dan_core::{
     message::DanMessage,
-    services::{epoch_manager::EpochManager, infrastructure_services::OutboundService},
+    models::{MempoolTransaction, Payload, TariDanPayload},
+    services::{
+        epoch_manager::EpochManager,
+        infrastructure_services::OutboundService,
+        leader_strategy::{LeaderStrategy, PayloadSpecificLeaderStrategy},
+    },
+    storage::{
+        shard_store::{ShardStore, ShardStoreReadTransaction, ShardStoreWriteTransaction},
+        StorageError,
+    },
 };
-use tari_template_lib::Hash;
+use tari_engine_types::hashing::{hasher, EngineHashDomainLabel};
+use tari_template_lib::{models::Amount, Hash};
 use tari_transaction::Transaction;
-use tokio::sync::{broadcast, mpsc};
+use tari_validator_node_client::types::TransactionRejectReason;
+use tokio::{
+    sync::{broadcast, mpsc},
+    time,
+};
 
 use super::MempoolError;
 use crate::p2p::services::{
-    mempool::{handle::MempoolRequest, Validator},
+    mempool::{handle::MempoolRequest, MempoolConfig, MempoolStats, Validator},
     messaging::OutboundMessaging,
 };
 
 const LOG_TARGET: &str = "tari::validator_node::mempool::service";
 
+/// How often the mempool is checked for expired transactions
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Transactions that lock a higher fee payment have a higher priority. The declared fee of a transaction is not
+/// charged, so it is not used. Transactions with the same fee payment are prioritised by age, and then by hash so that
+/// the order is total. A lower key is a higher priority.
+type PriorityKey = (Reverse<Amount>, u64, Hash);
+
+fn priority_key(transaction: &Transaction, received_at: u64) -> PriorityKey {
+    (Reverse(transaction.max_fee_payment()), received_at, *transaction.hash())
+}
+
+fn unix_timestamp() -> u64 {
+    Utc::now().timestamp() as u64
+}
+
+/// The channels that the mempool service receives transactions and requests on, and sends valid payloads to consensus
+/// on
+pub(super) struct MempoolChannels {
+    pub new_transactions: mpsc::Receiver<Transaction>,
+    pub new_transaction_batches: mpsc::Receiver<Vec<Transaction>>,
+    pub mempool_requests: mpsc::Receiver<MempoolRequest>,
+    pub tx_valid_payloads: broadcast::Sender<(TariDanPayload, ShardId)>,
+}
+
 #[derive(Debug)]
 pub struct MempoolService<V> {
-    transactions: HashMap<Hash, (Transaction, Option<TreeNodeHash>)>,
+    config: MempoolConfig,
+    /// Every transaction in the mempool, including transactions that are waiting to be batched
+    transactions: HashMap<Hash, MempoolTransaction>,
+    /// Hashes of submitted transactions waiting to be batched, keyed by their (sorted) involved shards
+    pending_batches: HashMap<Vec<ShardId>, Vec<Hash>>,
     new_transactions: mpsc::Receiver<Transaction>,
+    new_transaction_batches: mpsc::Receiver<Vec<Transaction>>,
     mempool_requests: mpsc::Receiver<MempoolRequest>,
     outbound: OutboundMessaging,
-    tx_valid_transactions: broadcast::Sender<(Transaction, ShardId)>,
+    tx_valid_payloads: broadcast::Sender<(TariDanPayload, ShardId)>,
     epoch_manager: EpochManagerHandle,
     node_identity: Arc<NodeIdentity>,
+    shard_store: ShardStoreBackend,
     validator: V,
 }
 
@@ -63,31 +113,44 @@ where
     MempoolError: From<V::Error>,
 {
     pub(super) fn new(
-        new_transactions: mpsc::Receiver<Transaction>,
-        mempool_requests: mpsc::Receiver<MempoolRequest>,
+        config: MempoolConfig,
+        channels: MempoolChannels,
         outbound: OutboundMessaging,
-        tx_valid_transactions: broadcast::Sender<(Transaction, ShardId)>,
         epoch_manager: EpochManagerHandle,
         node_identity: Arc<NodeIdentity>,
+        shard_store: ShardStoreBackend,
         validator: V,
     ) -> Self {
         Self {
+            config,
             transactions: Default::default(),
-            new_transactions,
-            mempool_requests,
+            pending_batches: Default::default(),
+            new_transactions: channels.new_transactions,
+            new_transaction_batches: channels.new_transaction_batches,
+            mempool_requests: channels.mempool_requests,
             outbound,
-            tx_valid_transactions,
+            tx_valid_payloads: channels.tx_valid_payloads,
             epoch_manager,
             node_identity,
+            shard_store,
             validator,
         }
     }
 
     pub async fn run(mut self) -> anyhow::Result<()> {
+        self.restore_transactions().await?;
+
+        let mut batch_timeout = time::interval(self.config.batch_timeout);
+        batch_timeout.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
+        let mut expiry_check = time::interval(EXPIRY_CHECK_INTERVAL);
+        expiry_check.set_missed_tick_behavior(time::MissedTickBehavior::Delay);
         loop {
             tokio::select! {
                 Some(req) = self.mempool_requests.recv() => self.handle_request(req).await,
                 Some(tx) = self.new_transactions.recv() => self.handle_new_transaction(tx).await,
+                Some(txs) = self.new_transaction_batches.recv() => self.handle_new_transaction_batch(txs).await,
+                _ = batch_timeout.tick(), if self.config.is_batching_enabled() => self.flush_pending_batches().await,
+                _ = expiry_check.tick() => self.expire_transactions().await,
 
                 else => {
                     info!(target: LOG_TARGET, "Mempool service shutting down");
@@ -98,18 +161,370 @@ where
         Ok(())
     }
 
+    /// Loads the transactions persisted before the node was restarted. Transactions that were sent to consensus are
+    /// sent to consensus again, in priority order, so that consensus resumes for payloads that were not finalized.
+    async fn restore_transactions(&mut self) -> Result<(), StorageError> {
+        let mut transactions = self.shard_store.with_read_tx(|tx| tx.get_mempool_transactions())?;
+        if transactions.is_empty() {
+            return Ok(());
+        }
+        info!(
+            target: LOG_TARGET,
+            "🎱 Restoring {} transaction(s) in mempool",
+            transactions.len()
+        );
+
+        transactions.sort_by_key(|t| priority_key(&t.transaction, t.received_at));
+        let mut restored_payloads = HashSet::new();
+        for transaction in transactions {
+            match transaction.payload_id {
+                Some(payload_id) => {
+                    if restored_payloads.insert(payload_id) {
+                        match self
+                            .shard_store
+                            .with_read_tx(|tx| tx.get_payload(&payload_id).optional())?
+                        {
+                            Some(payload) => {
+                                let committee_shards = self.get_committee_shards(&payload).await;
+                                self.send_to_consensus(&payload, committee_shards);
+                            },
+                            None => warn!(
+                                target: LOG_TARGET,
+                                "⚠ Payload {} for mempool transaction {} not found",
+                                payload_id,
+                                transaction.transaction.hash()
+                            ),
+                        }
+                    }
+                },
+                None => {
+                    let mut batch_key = transaction.transaction.meta().involved_shards();
+                    batch_key.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+                    self.pending_batches
+                        .entry(batch_key)
+                        .or_default()
+                        .push(*transaction.transaction.hash());
+                },
+            }
+            self.transactions.insert(*transaction.transaction.hash(), transaction);
+        }
+
+        // Pending batches are flushed on the first batch timeout tick
+        if !self.config.is_batching_enabled() {
+            self.flush_pending_batches().await;
+        }
+
+        Ok(())
+    }
+
     async fn handle_request(&mut self, request: MempoolRequest) {
         match request {
-            MempoolRequest::SubmitTransaction(transaction) => self.handle_new_transaction(*transaction).await,
+            MempoolRequest::SubmitTransaction { transaction, reply } => {
+                let result = self.handle_submitted_transaction(*transaction).await;
+                let _ignore = reply.send(result);
+            },
             MempoolRequest::RemoveTransaction { transaction_hash } => self.remove_transaction(&transaction_hash),
-            MempoolRequest::GetMempoolSize { reply } => {
-                let _ignore = reply.send(self.transactions.len());
+            MempoolRequest::GetMempoolStats { reply } => {
+                let _ignore = reply.send(self.get_stats());
             },
         }
     }
 
     fn remove_transaction(&mut self, hash: &Hash) {
-        self.transactions.remove(hash);
+        if self.transactions.remove(hash).is_some() {
+            for batch in self.pending_batches.values_mut() {
+                batch.retain(|h| h != hash);
+            }
+            self.pending_batches.retain(|_, batch| !batch.is_empty());
+            self.unpersist_transactions(&[*hash]);
+        }
+    }
+
+    fn get_stats(&self) -> MempoolStats {
+        let mut shard_queue_depths = HashMap::new();
+        for transaction in self.transactions.values() {
+            for shard in transaction.transaction.meta().involved_shards() {
+                *shard_queue_depths.entry(shard).or_insert(0) += 1;
+            }
+        }
+        MempoolStats {
+            size: self.transactions.len(),
+            max_size: self.config.max_transactions,
+            shard_queue_depths,
+        }
+    }
+
+    /// Removes transactions that have been in the mempool for longer than the configured expiry, or that expired at
+    /// the current epoch
+    async fn expire_transactions(&mut self) {
+        let expired_before = unix_timestamp().saturating_sub(self.config.transaction_expiry.as_secs());
+        let current_epoch = match self.epoch_manager.current_epoch().await {
+            Ok(epoch) => Some(epoch),
+            Err(e) => {
+                error!(target: LOG_TARGET, "Failed to get current epoch: {}", e);
+                None
+            },
+        };
+        let expired = self
+            .transactions
+            .values()
+            .filter(|t| {
+                t.received_at < expired_before ||
+                    current_epoch.map_or(false, |epoch| {
+                        t.transaction
+                            .expires_at_epoch()
+                            .map_or(false, |expires_at_epoch| epoch >= expires_at_epoch)
+                    })
+            })
+            .map(|t| *t.transaction.hash())
+            .collect::<Vec<_>>();
+        for hash in expired {
+            info!(target: LOG_TARGET, "⌛ Transaction {} expired from mempool", hash);
+            self.remove_transaction(&hash);
+        }
+    }
+
+    /// Ensures that there is room in the mempool for the transactions by evicting lower priority transactions. Only
+    /// transactions that have not been sent to consensus are evicted, consensus may already have decided on the
+    /// others. If there are not enough lower priority transactions to evict, the mempool is full and nothing is
+    /// evicted.
+    fn make_room_for(&mut self, transactions: &[Transaction], received_at: u64) -> Result<(), MempoolError> {
+        let num_required = (self.transactions.len() + transactions.len()).saturating_sub(self.config.max_transactions);
+        if num_required == 0 {
+            return Ok(());
+        }
+
+        let lowest_incoming = transactions
+            .iter()
+            .map(|t| priority_key(t, received_at))
+            .max()
+            .expect("transactions is not empty if room is required");
+        let mut evictable = self
+            .transactions
+            .values()
+            .filter(|t| t.payload_id.is_none())
+            .map(|t| priority_key(&t.transaction, t.received_at))
+            .filter(|key| *key > lowest_incoming)
+            .collect::<Vec<_>>();
+        if evictable.len() < num_required {
+            return Err(TransactionRejectReason::MempoolFull.into());
+        }
+
+        evictable.sort();
+        for (_, _, hash) in evictable.into_iter().rev().take(num_required) {
+            info!(
+                target: LOG_TARGET,
+                "🎱 Evicting lower priority transaction {} from full mempool", hash
+            );
+            self.remove_transaction(&hash);
+        }
+
+        Ok(())
+    }
+
+    /// Persists the mempool transactions, along with the payload that they were sent to consensus in so that the
+    /// payload can be restored after a restart
+    fn persist_transactions(&self, payload: Option<&TariDanPayload>, transactions: &[MempoolTransaction]) {
+        let result = self.shard_store.with_write_tx(|tx| {
+            if let Some(payload) = payload {
+                tx.save_payload(payload.clone())?;
+            }
+            for transaction in transactions {
+                tx.save_mempool_transaction(transaction.clone())?;
+            }
+            Ok::<_, StorageError>(())
+        });
+        if let Err(e) = result {
+            error!(target: LOG_TARGET, "Failed to persist mempool transactions: {}", e);
+        }
+    }
+
+    fn unpersist_transactions(&self, hashes: &[Hash]) {
+        let result = self.shard_store.with_write_tx(|tx| {
+            for hash in hashes {
+                tx.remove_mempool_transaction(&FixedHash::from(hash.into_array()))?;
+            }
+            Ok::<_, StorageError>(())
+        });
+        if let Err(e) = result {
+            error!(
+                target: LOG_TARGET,
+                "Failed to remove persisted mempool transactions: {}", e
+            );
+        }
+    }
+
+    fn is_pending(&self, hash: &Hash) -> bool {
+        self.transactions.contains_key(hash)
+    }
+
+    /// Checks that the transaction is not already known and is valid
+    async fn validate_new_transaction(&self, transaction: &Transaction) -> Result<(), MempoolError> {
+        if self.is_pending(transaction.hash()) {
+            info!(
+                target: LOG_TARGET,
+                "🎱 Transaction {} already in mempool",
+                transaction.hash()
+            );
+            return Err(TransactionRejectReason::AlreadyInMempool.into());
+        }
+
+        if let Err(e) = self.validator.validate(transaction).await {
+            let err = MempoolError::from(e);
+            warn!(
+                target: LOG_TARGET,
+                "⚠ Transaction {} failed validation: {}",
+                transaction.hash(),
+                err
+            );
+            return Err(err);
+        }
+
+        Ok(())
+    }
+
+    /// Transactions submitted to this node are propagated to the committees of their shards. If batching is enabled,
+    /// the transaction is batched by its batch proposer (see [Self::is_batch_proposer]), which may be this node.
+    async fn handle_submitted_transaction(&mut self, transaction: Transaction) -> Result<(), MempoolError> {
+        debug!(
+            target: LOG_TARGET,
+            "Received submitted transaction: {} {:?}",
+            transaction.hash(),
+            transaction
+        );
+
+        self.validate_new_transaction(&transaction).await?;
+        let received_at = unix_timestamp();
+        self.make_room_for(slice::from_ref(&transaction), received_at)?;
+
+        if !self.config.is_batching_enabled() {
+            self.handle_valid_payload(TariDanPayload::new(transaction)).await;
+            return Ok(());
+        }
+
+        let payload = TariDanPayload::new(transaction);
+        if let Err(e) = self.propagate_payload(&payload, &payload.involved_shards()).await {
+            error!(
+                target: LOG_TARGET,
+                "Unable to propagate transaction among peers: {}",
+                e.to_string()
+            )
+        }
+        self.add_to_pending_batch(payload.transaction().clone(), received_at)
+            .await;
+
+        Ok(())
+    }
+
+    /// Returns true if this node forms the batches of transactions that involve the shards in the batch key. Batches
+    /// are formed by a single member of the committee for the first shard, chosen for the shard set by the payload
+    /// specific leader strategy, so that each transaction is batched once and every committee member decides on the
+    /// same payload.
+    async fn is_batch_proposer(&self, batch_key: &[ShardId]) -> Result<bool, MempoolError> {
+        let shard = match batch_key.first() {
+            Some(shard) => *shard,
+            None => return Ok(false),
+        };
+        let epoch = self
+            .epoch_manager
+            .current_epoch()
+            .await
+            .map_err(|e| MempoolError::EpochManagerError(Box::new(e)))?;
+        let committee = self
+            .epoch_manager
+            .get_committee(epoch, shard)
+            .await
+            .map_err(|e| MempoolError::EpochManagerError(Box::new(e)))?;
+        if committee.is_empty() {
+            return Ok(false);
+        }
+        let batch_key_hash = batch_key
+            .iter()
+            .fold(hasher(EngineHashDomainLabel::MempoolBatch), |hasher, shard| {
+                hasher.chain(shard)
+            })
+            .result();
+        let is_proposer = PayloadSpecificLeaderStrategy {}.is_leader(
+            self.node_identity.public_key(),
+            epoch,
+            &committee,
+            PayloadId::new(batch_key_hash),
+            shard,
+            0,
+        )?;
+        Ok(is_proposer)
+    }
+
+    /// Adds a valid transaction to the pending batch for its shards if this node is the batch proposer for them.
+    /// Otherwise the transaction is left to the batch proposer, and arrives at this node as part of a batch.
+    async fn add_to_pending_batch(&mut self, transaction: Transaction, received_at: u64) {
+        let mut batch_key = transaction.meta().involved_shards();
+        batch_key.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+        match self.is_batch_proposer(&batch_key).await {
+            Ok(true) => {},
+            Ok(false) => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Not the batch proposer for transaction {}",
+                    transaction.hash()
+                );
+                return;
+            },
+            Err(e) => {
+                error!(
+                    target: LOG_TARGET,
+                    "Failed to determine the batch proposer for transaction {}: {}",
+                    transaction.hash(),
+                    e
+                );
+                return;
+            },
+        }
+
+        let hash = *transaction.hash();
+        let waiting = MempoolTransaction {
+            transaction,
+            received_at,
+            payload_id: None,
+        };
+        self.persist_transactions(None, slice::from_ref(&waiting));
+        self.transactions.insert(hash, waiting);
+
+        let batch = self.pending_batches.entry(batch_key.clone()).or_default();
+        batch.push(hash);
+        if batch.len() >= self.config.max_batch_size {
+            if let Some(batch) = self.pending_batches.remove(&batch_key) {
+                self.dispatch_batch(batch).await;
+            }
+        }
+    }
+
+    /// Dispatches the pending batches, highest priority batch first
+    async fn flush_pending_batches(&mut self) {
+        let mut batches = self.pending_batches.drain().map(|(_, batch)| batch).collect::<Vec<_>>();
+        batches.sort_by_key(|batch| {
+            batch
+                .iter()
+                .filter_map(|hash| self.transactions.get(hash))
+                .map(|t| priority_key(&t.transaction, t.received_at))
+                .min()
+        });
+        for batch in batches {
+            self.dispatch_batch(batch).await;
+        }
+    }
+
+    async fn dispatch_batch(&mut self, hashes: Vec<Hash>) {
+        let mut transactions = hashes
+            .iter()
+            .filter_map(|hash| self.transactions.get(hash))
+            .collect::<Vec<_>>();
+        if transactions.is_empty() {
+            return;
+        }
+        transactions.sort_by_key(|t| priority_key(&t.transaction, t.received_at));
+        let batch = transactions.into_iter().map(|t| t.transaction.clone()).collect();
+        self.handle_valid_payload(TariDanPayload::new_batch(batch)).await;
     }
 
     async fn handle_new_transaction(&mut self, transaction: Transaction) {
@@ -120,29 +535,118 @@ where
             transaction
         );
 
-        if self.transactions.contains_key(transaction.hash()) {
-            info!(
+        if self.validate_new_transaction(&transaction).await.is_err() {
+            return;
+        }
+        let received_at = unix_timestamp();
+        if let Err(e) = self.make_room_for(slice::from_ref(&transaction), received_at) {
+            warn!(
                 target: LOG_TARGET,
-                "🎱 Transaction {} already in mempool",
-                transaction.hash()
+                "⚠ Transaction {} not added to mempool: {}",
+                transaction.hash(),
+                e
             );
             return;
         }
 
-        if let Err(e) = self.validator.validate(&transaction).await {
-            error!(
+        if self.config.is_batching_enabled() {
+            self.add_to_pending_batch(transaction, received_at).await;
+            return;
+        }
+
+        self.handle_valid_payload(TariDanPayload::new(transaction)).await;
+    }
+
+    async fn handle_new_transaction_batch(&mut self, transactions: Vec<Transaction>) {
+        if transactions.is_empty() {
+            warn!(target: LOG_TARGET, "⚠ Received empty transaction batch");
+            return;
+        }
+
+        // The batch is decided as a unit, so a batch containing a known or invalid transaction is discarded in full
+        for transaction in &transactions {
+            if self.validate_new_transaction(transaction).await.is_err() {
+                debug!(
+                    target: LOG_TARGET,
+                    "Discarding batch of {} transaction(s) containing transaction {}",
+                    transactions.len(),
+                    transaction.hash()
+                );
+                return;
+            }
+        }
+        if let Err(e) = self.make_room_for(&transactions, unix_timestamp()) {
+            warn!(
                 target: LOG_TARGET,
-                "⚠ Invalid templates found for transaction: {}",
-                MempoolError::from(e)
+                "⚠ Batch of {} transaction(s) not added to mempool: {}",
+                transactions.len(),
+                e
             );
             return;
         }
 
-        let shards = transaction.meta().involved_shards();
+        self.handle_valid_payload(TariDanPayload::new_batch(transactions)).await;
+    }
+
+    async fn handle_valid_payload(&mut self, payload: TariDanPayload) {
+        let shards = payload.involved_shards();
         if shards.is_empty() {
             warn!(target: LOG_TARGET, "⚠ No involved shards for payload");
         }
 
+        let committee_shards = self.get_committee_shards(&payload).await;
+        let payload_id = payload.to_id();
+        if committee_shards.is_empty() {
+            info!(target: LOG_TARGET, "🙇 Not in committee for payload {}", payload_id);
+            // Submitted transactions that were waiting to be batched are no longer held by this node
+            let hashes = payload
+                .transactions()
+                .map(|t| *t.hash())
+                .filter(|hash| self.transactions.remove(hash).is_some())
+                .collect::<Vec<_>>();
+            if !hashes.is_empty() {
+                self.unpersist_transactions(&hashes);
+            }
+        } else {
+            info!(
+                target: LOG_TARGET,
+                "🎱 New payload {} with {} transaction(s) in mempool",
+                payload_id,
+                payload.transactions().count()
+            );
+            let now = unix_timestamp();
+            let dispatched = payload
+                .transactions()
+                .map(|transaction| MempoolTransaction {
+                    transaction: transaction.clone(),
+                    received_at: self
+                        .transactions
+                        .get(transaction.hash())
+                        .map(|t| t.received_at)
+                        .unwrap_or(now),
+                    payload_id: Some(payload_id),
+                })
+                .collect::<Vec<_>>();
+            self.persist_transactions(Some(&payload), &dispatched);
+            for transaction in dispatched {
+                self.transactions.insert(*transaction.transaction.hash(), transaction);
+            }
+        }
+
+        if let Err(e) = self.propagate_payload(&payload, &shards).await {
+            error!(
+                target: LOG_TARGET,
+                "Unable to propagate transaction among peers: {}",
+                e.to_string()
+            )
+        }
+
+        self.send_to_consensus(&payload, committee_shards);
+    }
+
+    /// Returns the involved shards of the payload that this node is a committee member for
+    async fn get_committee_shards(&self, payload: &TariDanPayload) -> Vec<ShardId> {
+        let shards = payload.involved_shards();
         let current_node_pubkey = self.node_identity.public_key();
 
         let mut committee_shards = Vec::with_capacity(shards.len());
@@ -162,35 +666,18 @@ where
                 ),
             }
         }
+        committee_shards
+    }
 
-        if committee_shards.is_empty() {
-            info!(
-                target: LOG_TARGET,
-                "🙇 Not in committee for transaction {}",
-                transaction.hash()
-            );
-        } else {
-            info!(target: LOG_TARGET, "🎱 New transaction in mempool");
-            self.transactions
-                .insert(*transaction.hash(), (transaction.clone(), None));
-        }
-
-        if let Err(e) = self.propagate_transaction(&transaction, &shards).await {
-            error!(
-                target: LOG_TARGET,
-                "Unable to propagate transaction among peers: {}",
-                e.to_string()
-            )
-        }
-
+    fn send_to_consensus(&self, payload: &TariDanPayload, committee_shards: Vec<ShardId>) {
         for shard_id in committee_shards {
             info!(
                 target: LOG_TARGET,
-                " 🚀 Sending transaction {} for shard {} to consensus",
-                transaction.hash(),
+                " 🚀 Sending payload {} for shard {} to consensus",
+                payload.to_id(),
                 shard_id
             );
-            if let Err(err) = self.tx_valid_transactions.send((transaction.clone(), shard_id)) {
+            if let Err(err) = self.tx_valid_payloads.send((payload.clone(), shard_id)) {
                 error!(
                     target: LOG_TARGET,
                     "Failed to send valid transaction to shard: {}: {}", shard_id, err
@@ -199,9 +686,9 @@ where
         }
     }
 
-    pub async fn propagate_transaction(
+    pub async fn propagate_payload(
         &mut self,
-        transaction: &Transaction,
+        payload: &TariDanPayload,
         shards: &[ShardId],
     ) -> Result<(), MempoolError> {
         let epoch = self
@@ -215,7 +702,11 @@ where
             .await
             .map_err(|e| MempoolError::EpochManagerError(Box::new(e)))?;
 
-        let msg = DanMessage::NewTransaction(Box::new(transaction.clone()));
+        let msg = if payload.is_batch() {
+            DanMessage::NewTransactionBatch(payload.transactions().cloned().collect())
+        } else {
+            DanMessage::NewTransaction(Box::new(payload.transaction().clone()))
+        };
 
         // propagate over the involved shard ids
         #[allow(clippy::mutable_key_type)]
@@ -233,3 +724,273 @@ where
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use tari_common_types::types::PublicKey;
+    use tari_comms::peer_manager::PeerFeatures;
+    use tari_dan_common_types::{crypto::create_key_pair, Epoch};
+    use tempfile::TempDir;
+    use tokio::sync::oneshot;
+
+    use super::*;
+    use crate::{
+        comms::Destination,
+        p2p::services::mempool::{
+            test_utils::{create_shard, create_shard_store, create_transaction_with_fee, spawn_epoch_manager},
+            validators::SignatureValidator,
+        },
+    };
+
+    type TestValidator = Option<SignatureValidator>;
+
+    struct TestMempool {
+        service: MempoolService<TestValidator>,
+        shard_store: ShardStoreBackend,
+        rx_valid_payloads: broadcast::Receiver<(TariDanPayload, ShardId)>,
+        _rx_outbound: mpsc::Receiver<(Destination<PublicKey>, DanMessage<TariDanPayload, PublicKey>)>,
+        _temp_dir: TempDir,
+    }
+
+    /// Creates a mempool whose node is the only committee member of the local shards
+    fn create_mempool(config: MempoolConfig, local_shards: Vec<ShardId>) -> TestMempool {
+        let (temp_dir, shard_store) = create_shard_store();
+        let node_identity = Arc::new(NodeIdentity::random(
+            &mut OsRng,
+            "/ip4/127.0.0.1/tcp/0".parse().unwrap(),
+            PeerFeatures::COMMUNICATION_NODE,
+        ));
+        let epoch_manager = spawn_epoch_manager(Epoch(1), local_shards, vec![node_identity.public_key().clone()]);
+        let (tx_outbound, rx_outbound) = mpsc::channel(100);
+        let (tx_loopback, _) = mpsc::channel(100);
+        let outbound = OutboundMessaging::new(node_identity.public_key().clone(), tx_outbound, tx_loopback);
+        let (tx_valid_payloads, rx_valid_payloads) = broadcast::channel(100);
+        let channels = MempoolChannels {
+            new_transactions: mpsc::channel(1).1,
+            new_transaction_batches: mpsc::channel(1).1,
+            mempool_requests: mpsc::channel(1).1,
+            tx_valid_payloads,
+        };
+        let service = MempoolService::new(
+            config,
+            channels,
+            outbound,
+            epoch_manager,
+            node_identity,
+            shard_store.clone(),
+            None,
+        );
+        TestMempool {
+            service,
+            shard_store,
+            rx_valid_payloads,
+            _rx_outbound: rx_outbound,
+            _temp_dir: temp_dir,
+        }
+    }
+
+    /// Batching is enabled and never dispatched by size, so admitted transactions wait in the mempool undispatched
+    fn batching_config(max_transactions: usize) -> MempoolConfig {
+        MempoolConfig {
+            max_batch_size: 100,
+            max_transactions,
+            ..Default::default()
+        }
+    }
+
+    fn persisted_hashes(shard_store: &ShardStoreBackend) -> HashSet<FixedHash> {
+        shard_store
+            .with_read_tx(|tx| tx.get_mempool_transactions())
+            .unwrap()
+            .into_iter()
+            .map(|t| FixedHash::from(t.transaction.hash().into_array()))
+            .collect()
+    }
+
+    async fn submit(mempool: &mut MempoolService<TestValidator>, transaction: Transaction) -> Result<(), MempoolError> {
+        let (reply, rx_reply) = oneshot::channel();
+        mempool
+            .handle_request(MempoolRequest::SubmitTransaction {
+                transaction: Box::new(transaction),
+                reply,
+            })
+            .await;
+        rx_reply.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn it_evicts_the_lowest_priority_transaction_when_full() {
+        let (_, shard) = create_shard(1);
+        let mut mempool = create_mempool(batching_config(2), vec![shard]);
+
+        let low = create_transaction_with_fee(Amount(10), vec![shard]);
+        let mid = create_transaction_with_fee(Amount(20), vec![shard]);
+        let high = create_transaction_with_fee(Amount(30), vec![shard]);
+        submit(&mut mempool.service, low.clone()).await.unwrap();
+        submit(&mut mempool.service, mid.clone()).await.unwrap();
+        submit(&mut mempool.service, high.clone()).await.unwrap();
+
+        assert!(!mempool.service.is_pending(low.hash()));
+        assert!(mempool.service.is_pending(mid.hash()));
+        assert!(mempool.service.is_pending(high.hash()));
+        assert!(!persisted_hashes(&mempool.shard_store).contains(&FixedHash::from(low.hash().into_array())));
+
+        // A transaction with a lower priority than every transaction in the full mempool is rejected
+        let lowest = create_transaction_with_fee(Amount(5), vec![shard]);
+        let err = submit(&mut mempool.service, lowest.clone()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::MempoolFull)
+        ));
+        assert!(!mempool.service.is_pending(lowest.hash()));
+    }
+
+    #[tokio::test]
+    async fn it_prioritises_by_the_fee_payment_rather_than_the_declared_fee() {
+        let (_, shard) = create_shard(1);
+        let mut mempool = create_mempool(batching_config(1), vec![shard]);
+
+        let paying = create_transaction_with_fee(Amount(10), vec![shard]);
+        submit(&mut mempool.service, paying.clone()).await.unwrap();
+
+        // The declared fee is never charged, so it does not outbid a transaction that pays a fee
+        let (secret_key, _) = create_key_pair();
+        let mut builder = Transaction::builder();
+        builder.with_fee(1_000_000).with_inputs(vec![shard]).sign(&secret_key);
+        let declared = builder.build();
+        let err = submit(&mut mempool.service, declared).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::MempoolFull)
+        ));
+        assert!(mempool.service.is_pending(paying.hash()));
+    }
+
+    #[tokio::test]
+    async fn it_does_not_evict_transactions_sent_to_consensus() {
+        let (_, shard) = create_shard(1);
+        let config = MempoolConfig {
+            max_transactions: 1,
+            ..Default::default()
+        };
+        let mut mempool = create_mempool(config, vec![shard]);
+
+        let low = create_transaction_with_fee(Amount(10), vec![shard]);
+        submit(&mut mempool.service, low.clone()).await.unwrap();
+        let (payload, _) = mempool.rx_valid_payloads.try_recv().unwrap();
+        assert_eq!(payload.transaction().hash(), low.hash());
+
+        let high = create_transaction_with_fee(Amount(30), vec![shard]);
+        let err = submit(&mut mempool.service, high.clone()).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::MempoolFull)
+        ));
+        assert!(mempool.service.is_pending(low.hash()));
+        assert!(!mempool.service.is_pending(high.hash()));
+    }
+
+    #[tokio::test]
+    async fn it_expires_old_transactions_and_transactions_past_their_expiry_epoch() {
+        let (_, shard) = create_shard(1);
+        let mut mempool = create_mempool(batching_config(10), vec![shard]);
+
+        let fresh = create_transaction_with_fee(Amount(10), vec![shard]);
+        let old = create_transaction_with_fee(Amount(10), vec![shard]);
+        let (secret_key, _) = create_key_pair();
+        let mut builder = Transaction::builder();
+        builder
+            .with_inputs(vec![shard])
+            .with_expires_at_epoch(Epoch(1))
+            .sign(&secret_key);
+        let expired_epoch = builder.build();
+        for transaction in [&fresh, &old, &expired_epoch] {
+            submit(&mut mempool.service, transaction.clone()).await.unwrap();
+        }
+        let expiry = mempool.service.config.transaction_expiry.as_secs();
+        mempool.service.transactions.get_mut(old.hash()).unwrap().received_at = unix_timestamp() - expiry - 1;
+
+        mempool.service.expire_transactions().await;
+
+        assert!(mempool.service.is_pending(fresh.hash()));
+        assert!(!mempool.service.is_pending(old.hash()));
+        assert!(!mempool.service.is_pending(expired_epoch.hash()));
+        assert_eq!(mempool.service.pending_batches.values().flatten().count(), 1);
+        assert_eq!(
+            persisted_hashes(&mempool.shard_store),
+            HashSet::from([FixedHash::from(fresh.hash().into_array())])
+        );
+    }
+
+    #[tokio::test]
+    async fn it_restores_persisted_transactions() {
+        let (_, shard) = create_shard(1);
+        let mut mempool = create_mempool(MempoolConfig::default(), vec![shard]);
+
+        let dispatched = create_transaction_with_fee(Amount(10), vec![shard]);
+        let waiting = create_transaction_with_fee(Amount(20), vec![shard]);
+        let payload = TariDanPayload::new(dispatched.clone());
+        let payload_id = payload.to_id();
+        mempool
+            .service
+            .persist_transactions(Some(&payload), &[MempoolTransaction {
+                transaction: dispatched.clone(),
+                received_at: 1,
+                payload_id: Some(payload_id),
+            }]);
+        mempool.service.persist_transactions(None, &[MempoolTransaction {
+            transaction: waiting.clone(),
+            received_at: 2,
+            payload_id: None,
+        }]);
+
+        mempool.service.restore_transactions().await.unwrap();
+
+        assert!(mempool.service.is_pending(dispatched.hash()));
+        assert!(mempool.service.is_pending(waiting.hash()));
+        // Both transactions are sent to consensus, the waiting transaction because batching is disabled
+        let mut sent = HashSet::new();
+        while let Ok((payload, sent_shard)) = mempool.rx_valid_payloads.try_recv() {
+            assert_eq!(sent_shard, shard);
+            sent.insert(*payload.transaction().hash());
+        }
+        assert_eq!(sent, HashSet::from([*dispatched.hash(), *waiting.hash()]));
+        assert_eq!(
+            mempool.service.transactions[waiting.hash()].payload_id,
+            Some(TariDanPayload::new(waiting.clone()).to_id())
+        );
+    }
+
+    #[tokio::test]
+    async fn it_reports_the_queue_depth_of_each_shard() {
+        let (_, shard1) = create_shard(1);
+        let (_, shard2) = create_shard(2);
+        let mut mempool = create_mempool(batching_config(10), vec![shard1, shard2]);
+
+        submit(
+            &mut mempool.service,
+            create_transaction_with_fee(Amount(10), vec![shard1, shard2]),
+        )
+        .await
+        .unwrap();
+        submit(
+            &mut mempool.service,
+            create_transaction_with_fee(Amount(10), vec![shard1]),
+        )
+        .await
+        .unwrap();
+
+        let (reply, rx_reply) = oneshot::channel();
+        mempool
+            .service
+            .handle_request(MempoolRequest::GetMempoolStats { reply })
+            .await;
+        let stats = rx_reply.await.unwrap();
+        assert_eq!(stats.size, 2);
+        assert_eq!(stats.max_size, 10);
+        assert_eq!(stats.shard_queue_depths.len(), 2);
+        assert_eq!(stats.shard_queue_depths[&shard1], 2);
+        assert_eq!(stats.shard_queue_depths[&shard2], 1);
+    }
+}
@@ -0,0 +1,167 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::HashSet;
+
+use tari_common_types::types::PublicKey;
+use tari_dan_app_utilities::{
+    epoch_manager::{EpochManagerHandle, EpochManagerRequest},
+    shard_store::{ShardStoreBackend, ShardStoreType},
+};
+use tari_dan_common_types::{
+    crypto::create_key_pair,
+    Epoch,
+    NodeHeight,
+    PayloadId,
+    QuorumCertificate,
+    ShardId,
+    TreeNodeHash,
+};
+use tari_dan_core::{
+    models::{Committee, SubstateShardData},
+    services::epoch_manager::ShardCommitteeAllocation,
+    storage::{
+        shard_store::{ShardStore, ShardStoreWriteTransaction},
+        DbFactory,
+        StorageError,
+    },
+};
+use tari_dan_storage_sqlite::SqliteDbFactory;
+use tari_engine_types::{
+    instruction::Instruction,
+    resource::Resource,
+    substate::{Substate, SubstateAddress},
+};
+use tari_template_lib::{
+    models::{Amount, ComponentAddress, ResourceAddress},
+    prelude::ResourceType,
+    Hash,
+};
+use tari_transaction::Transaction;
+use tempfile::TempDir;
+use tokio::{sync::mpsc, task};
+
+use crate::p2p::services::template_manager::{TemplateConfig, TemplateManager};
+
+/// Answers the epoch manager requests made by the mempool. The node is in the given committee for the local shards
+/// only.
+pub fn spawn_epoch_manager(
+    current_epoch: Epoch,
+    local_shards: Vec<ShardId>,
+    committee: Vec<PublicKey>,
+) -> EpochManagerHandle {
+    let (tx_request, mut rx_request) = mpsc::channel(10);
+    let local_shards = local_shards.into_iter().collect::<HashSet<_>>();
+    task::spawn(async move {
+        let committee_for = |shard: ShardId| {
+            if local_shards.contains(&shard) {
+                Committee::new(committee.clone())
+            } else {
+                Committee::empty()
+            }
+        };
+        while let Some(request) = rx_request.recv().await {
+            match request {
+                EpochManagerRequest::CurrentEpoch { reply } => {
+                    let _ignore = reply.send(Ok(current_epoch));
+                },
+                EpochManagerRequest::GetEpochTimestamp { reply, .. } => {
+                    let _ignore = reply.send(Ok(0));
+                },
+                EpochManagerRequest::GetEpochBeacon { reply, .. } => {
+                    let _ignore = reply.send(Ok([0u8; 32]));
+                },
+                EpochManagerRequest::IsValidatorInCommitteeForCurrentEpoch { shard, reply, .. } => {
+                    let _ignore = reply.send(Ok(local_shards.contains(&shard)));
+                },
+                EpochManagerRequest::GetCommittee { shard, reply, .. } => {
+                    let _ignore = reply.send(Ok(committee_for(shard)));
+                },
+                EpochManagerRequest::GetCommittees { shards, reply, .. } => {
+                    let committees = shards
+                        .into_iter()
+                        .map(|shard_id| ShardCommitteeAllocation {
+                            shard_id,
+                            committee: committee_for(shard_id),
+                        })
+                        .collect();
+                    let _ignore = reply.send(Ok(committees));
+                },
+                request => panic!("Unexpected epoch manager request: {:?}", request),
+            }
+        }
+    });
+    EpochManagerHandle::new(tx_request)
+}
+
+pub fn create_shard_store() -> (TempDir, ShardStoreBackend) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let shard_store = ShardStoreBackend::try_create(ShardStoreType::Sqlite, temp_dir.path().join("state.db")).unwrap();
+    (temp_dir, shard_store)
+}
+
+pub fn create_template_manager() -> (TempDir, TemplateManager) {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let db_factory = SqliteDbFactory::new(temp_dir.path().to_path_buf());
+    db_factory.migrate().unwrap();
+    let global_db = db_factory.get_or_create_global_db().unwrap();
+    (temp_dir, TemplateManager::new(global_db, TemplateConfig::default()))
+}
+
+/// Returns the shard of a resource substate that is unique to the given seed
+pub fn create_shard(seed: u8) -> (SubstateAddress, ShardId) {
+    let address = SubstateAddress::Resource(ResourceAddress::new(Hash::from([seed; 32])));
+    let shard_id = ShardId::from_address(&address, 0);
+    (address, shard_id)
+}
+
+/// Inserts a resource substate for the seed into the shard store, destroyed by the given payload if set
+pub fn insert_substate(shard_store: &ShardStoreBackend, seed: u8, destroyed_by: Option<PayloadId>) -> ShardId {
+    let (address, shard_id) = create_shard(seed);
+    let created_by = PayloadId::new([0u8; 32]);
+    shard_store
+        .with_write_tx(|tx| {
+            tx.insert_substates(SubstateShardData::new(
+                shard_id,
+                address,
+                0,
+                Substate::new(
+                    0,
+                    Resource::new(ResourceType::Fungible, Default::default(), Default::default()),
+                ),
+                NodeHeight(0),
+                destroyed_by.map(|_| NodeHeight(1)),
+                TreeNodeHash::zero(),
+                destroyed_by.map(|_| TreeNodeHash::zero()),
+                created_by,
+                destroyed_by,
+                QuorumCertificate::genesis(Epoch(0), created_by, shard_id),
+                destroyed_by.map(|payload_id| QuorumCertificate::genesis(Epoch(0), payload_id, shard_id)),
+            ))?;
+            Ok::<_, StorageError>(())
+        })
+        .unwrap();
+    shard_id
+}
+
+/// Builds a transaction signed by a random key with the given instructions and inputs
+pub fn create_transaction(instructions: Vec<Instruction>, inputs: Vec<ShardId>) -> Transaction {
+    let (secret_key, _) = create_key_pair();
+    let mut builder = Transaction::builder();
+    builder
+        .with_instructions(instructions)
+        .with_inputs(inputs)
+        .sign(&secret_key);
+    builder.build()
+}
+
+/// Builds a transaction with the given inputs that pays up to `max_fee` from an account
+pub fn create_transaction_with_fee(max_fee: Amount, inputs: Vec<ShardId>) -> Transaction {
+    let (secret_key, _) = create_key_pair();
+    let mut builder = Transaction::builder();
+    builder
+        .with_fee_account(ComponentAddress::new(Hash::from([0u8; 32])), max_fee)
+        .with_inputs(inputs)
+        .sign(&secret_key);
+    builder.build()
+}
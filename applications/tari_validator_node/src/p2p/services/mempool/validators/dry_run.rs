@@ -0,0 +1,169 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tari_transaction::Transaction;
+use tari_validator_node_client::types::TransactionRejectReason;
+use tokio::time;
+
+use crate::{
+    dry_run_transaction_processor::DryRunTransactionProcessor,
+    p2p::services::mempool::{MempoolError, Validator},
+};
+
+/// Rejects transactions that fail, or do not complete in time, when executed against the current state
+pub struct DryRunValidator {
+    dry_run_transaction_processor: DryRunTransactionProcessor,
+    timeout: Duration,
+}
+
+impl DryRunValidator {
+    pub fn new(dry_run_transaction_processor: DryRunTransactionProcessor, timeout: Duration) -> Self {
+        Self {
+            dry_run_transaction_processor,
+            timeout,
+        }
+    }
+}
+
+#[async_trait]
+impl Validator<Transaction> for DryRunValidator {
+    type Error = MempoolError;
+
+    async fn validate(&self, transaction: &Transaction) -> Result<(), MempoolError> {
+        let result = time::timeout(
+            self.timeout,
+            self.dry_run_transaction_processor
+                .process_transaction(transaction.clone()),
+        )
+        .await
+        .map_err(|_| TransactionRejectReason::DryRunTimedOut {
+            timeout_ms: self.timeout.as_millis() as u64,
+        })?
+        .map_err(|e| TransactionRejectReason::DryRunFailed { reason: e.to_string() })?;
+
+        if let Some(reason) = result.result.reject() {
+            return Err(TransactionRejectReason::DryRunFailed {
+                reason: reason.to_string(),
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rand::rngs::OsRng;
+    use tari_comms::{connectivity::ConnectivityRequester, peer_manager::PeerFeatures, NodeIdentity};
+    use tari_dan_common_types::Epoch;
+    use tari_dan_engine::fees::{FeeTable, RUNTIME_CALL_FUEL};
+    use tari_engine_types::instruction::Instruction;
+    use tari_template_builtin::ACCOUNT_TEMPLATE_ADDRESS;
+    use tari_template_lib::{args, models::TemplateAddress};
+    use tempfile::TempDir;
+    use tokio::sync::{broadcast, mpsc};
+
+    use super::*;
+    use crate::{
+        p2p::services::{
+            mempool::test_utils::{
+                create_shard_store,
+                create_template_manager,
+                create_transaction,
+                spawn_epoch_manager,
+            },
+            rpc_client::TariCommsValidatorNodeClientFactory,
+        },
+        payload_processor::TariDanPayloadProcessor,
+    };
+
+    fn create_processor() -> (Vec<TempDir>, DryRunTransactionProcessor) {
+        let (shard_store_dir, shard_store) = create_shard_store();
+        let (template_dir, template_manager) = create_template_manager();
+        let (tx_connectivity, _) = mpsc::channel(1);
+        let (tx_connectivity_events, _) = broadcast::channel(1);
+        let node_identity = NodeIdentity::random(
+            &mut OsRng,
+            "/ip4/127.0.0.1/tcp/0".parse().unwrap(),
+            PeerFeatures::COMMUNICATION_NODE,
+        );
+        let processor = DryRunTransactionProcessor::new(
+            spawn_epoch_manager(Epoch(1), vec![], vec![]),
+            TariDanPayloadProcessor::new(template_manager, FeeTable::zero_rated()),
+            shard_store,
+            TariCommsValidatorNodeClientFactory::new(ConnectivityRequester::new(
+                tx_connectivity,
+                tx_connectivity_events,
+            )),
+            Arc::new(node_identity),
+        );
+        (vec![shard_store_dir, template_dir], processor)
+    }
+
+    fn call_function(template_address: TemplateAddress) -> Instruction {
+        Instruction::CallFunction {
+            template_address,
+            function: "create".to_string(),
+            args: args![],
+        }
+    }
+
+    #[tokio::test]
+    async fn it_accepts_transactions_that_execute_successfully() {
+        let (_temp_dirs, processor) = create_processor();
+        let validator = DryRunValidator::new(processor, Duration::from_secs(10));
+
+        let transaction = create_transaction(vec![], vec![]);
+        validator.validate(&transaction).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_rejects_transactions_that_fail_to_execute() {
+        let (_temp_dirs, processor) = create_processor();
+        let validator = DryRunValidator::new(processor, Duration::from_secs(10));
+
+        let transaction = create_transaction(vec![call_function(TemplateAddress::from_array([1u8; 32]))], vec![]);
+        let err = validator.validate(&transaction).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::DryRunFailed { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_transactions_that_exceed_the_fuel_limit() {
+        let (_temp_dirs, processor) = create_processor();
+        let validator = DryRunValidator::new(
+            processor.with_fuel_limit(RUNTIME_CALL_FUEL - 1),
+            Duration::from_secs(10),
+        );
+
+        let transaction = create_transaction(vec![call_function(ACCOUNT_TEMPLATE_ADDRESS)], vec![]);
+        let err = validator.validate(&transaction).await.unwrap_err();
+        match err {
+            MempoolError::Rejected(TransactionRejectReason::DryRunFailed { reason }) => {
+                assert!(reason.contains("fuel limit"), "unexpected reason: {}", reason);
+            },
+            err => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[tokio::test]
+    async fn it_rejects_transactions_that_do_not_complete_in_time() {
+        let (_temp_dirs, processor) = create_processor();
+        let validator = DryRunValidator::new(processor, Duration::ZERO);
+
+        let transaction = create_transaction(vec![], vec![]);
+        let err = validator.validate(&transaction).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::DryRunTimedOut { timeout_ms: 0 })
+        ));
+    }
+}
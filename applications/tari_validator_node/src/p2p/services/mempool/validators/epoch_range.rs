@@ -0,0 +1,116 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use async_trait::async_trait;
+use tari_dan_app_utilities::epoch_manager::EpochManagerHandle;
+use tari_dan_core::services::epoch_manager::EpochManager;
+use tari_transaction::Transaction;
+use tari_validator_node_client::types::TransactionRejectReason;
+
+use crate::p2p::services::mempool::{MempoolError, Validator};
+
+/// Rejects transactions that are not valid in the current epoch
+pub struct EpochRangeValidator {
+    epoch_manager: EpochManagerHandle,
+}
+
+impl EpochRangeValidator {
+    pub fn new(epoch_manager: EpochManagerHandle) -> Self {
+        Self { epoch_manager }
+    }
+}
+
+#[async_trait]
+impl Validator<Transaction> for EpochRangeValidator {
+    type Error = MempoolError;
+
+    async fn validate(&self, transaction: &Transaction) -> Result<(), MempoolError> {
+        let current_epoch = self
+            .epoch_manager
+            .current_epoch()
+            .await
+            .map_err(|e| MempoolError::EpochManagerError(Box::new(e)))?;
+
+        if let Some(min_epoch) = transaction.min_epoch() {
+            if current_epoch < min_epoch {
+                return Err(TransactionRejectReason::NotYetValid {
+                    min_epoch,
+                    current_epoch,
+                }
+                .into());
+            }
+        }
+
+        if let Some(expires_at_epoch) = transaction.expires_at_epoch() {
+            if current_epoch >= expires_at_epoch {
+                return Err(TransactionRejectReason::Expired {
+                    expires_at_epoch,
+                    current_epoch,
+                }
+                .into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_dan_common_types::{crypto::create_key_pair, Epoch};
+
+    use super::*;
+    use crate::p2p::services::mempool::test_utils::{create_transaction, spawn_epoch_manager};
+
+    fn create_validator(current_epoch: Epoch) -> EpochRangeValidator {
+        EpochRangeValidator::new(spawn_epoch_manager(current_epoch, vec![], vec![]))
+    }
+
+    fn create_transaction_valid_in(min_epoch: Option<Epoch>, expires_at_epoch: Option<Epoch>) -> Transaction {
+        let (secret_key, _) = create_key_pair();
+        let mut builder = Transaction::builder();
+        if let Some(min_epoch) = min_epoch {
+            builder.with_min_epoch(min_epoch);
+        }
+        if let Some(expires_at_epoch) = expires_at_epoch {
+            builder.with_expires_at_epoch(expires_at_epoch);
+        }
+        builder.sign(&secret_key);
+        builder.build()
+    }
+
+    #[tokio::test]
+    async fn it_accepts_transactions_valid_in_the_current_epoch() {
+        let validator = create_validator(Epoch(5));
+
+        validator.validate(&create_transaction(vec![], vec![])).await.unwrap();
+        let transaction = create_transaction_valid_in(Some(Epoch(5)), Some(Epoch(6)));
+        validator.validate(&transaction).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_rejects_transactions_that_are_not_yet_valid() {
+        let validator = create_validator(Epoch(5));
+
+        let transaction = create_transaction_valid_in(Some(Epoch(6)), None);
+        let err = validator.validate(&transaction).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::NotYetValid { min_epoch, current_epoch })
+                if min_epoch == Epoch(6) && current_epoch == Epoch(5)
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_transactions_that_have_expired() {
+        let validator = create_validator(Epoch(5));
+
+        let transaction = create_transaction_valid_in(None, Some(Epoch(5)));
+        let err = validator.validate(&transaction).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::Expired { expires_at_epoch, current_epoch })
+                if expires_at_epoch == Epoch(5) && current_epoch == Epoch(5)
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use async_trait::async_trait;
+use tari_common_types::types::PublicKey;
+use tari_dan_app_utilities::{epoch_manager::EpochManagerHandle, shard_store::ShardStoreBackend};
+use tari_dan_common_types::ShardId;
+use tari_dan_core::{
+    models::{Payload, TariDanPayload},
+    services::epoch_manager::EpochManager,
+    storage::shard_store::{ShardStore, ShardStoreReadTransaction},
+};
+use tari_transaction::{SubstateChange, Transaction};
+use tari_validator_node_client::types::TransactionRejectReason;
+
+use crate::p2p::services::mempool::{MempoolError, Validator};
+
+/// Rejects transactions with inputs that do not exist, have been destroyed or are pledged to another payload. Only
+/// inputs in shards that this node is a committee member for are checked, the other inputs are checked by their own
+/// committees.
+pub struct InputsValidator {
+    shard_store: ShardStoreBackend,
+    epoch_manager: EpochManagerHandle,
+    node_public_key: PublicKey,
+}
+
+impl InputsValidator {
+    pub fn new(shard_store: ShardStoreBackend, epoch_manager: EpochManagerHandle, node_public_key: PublicKey) -> Self {
+        Self {
+            shard_store,
+            epoch_manager,
+            node_public_key,
+        }
+    }
+
+    async fn get_local_inputs(&self, transaction: &Transaction) -> Result<Vec<ShardId>, MempoolError> {
+        let mut local_inputs = Vec::new();
+        for (shard, (change, _)) in transaction.meta().involved_objects_iter() {
+            if matches!(change, SubstateChange::Create) {
+                continue;
+            }
+            let is_local = self
+                .epoch_manager
+                .is_validator_in_committee_for_current_epoch(*shard, self.node_public_key.clone())
+                .await
+                .map_err(|e| MempoolError::EpochManagerError(Box::new(e)))?;
+            if is_local {
+                local_inputs.push(*shard);
+            }
+        }
+        Ok(local_inputs)
+    }
+}
+
+#[async_trait]
+impl Validator<Transaction> for InputsValidator {
+    type Error = MempoolError;
+
+    async fn validate(&self, transaction: &Transaction) -> Result<(), MempoolError> {
+        let local_inputs = self.get_local_inputs(transaction).await?;
+        if local_inputs.is_empty() {
+            return Ok(());
+        }
+
+        let payload_id = TariDanPayload::new(transaction.clone()).to_id();
+        self.shard_store.with_read_tx(|tx| {
+            let substates = tx.get_substate_states(&local_inputs)?;
+            for shard in local_inputs {
+                let substate = substates
+                    .iter()
+                    .find(|s| s.shard_id() == shard)
+                    .ok_or(TransactionRejectReason::InputNotFound { shard })?;
+                if substate.destroyed_payload_id().is_some() {
+                    return Err(TransactionRejectReason::InputDestroyed { shard }.into());
+                }
+                // A pledge to this transaction's own payload is not a conflict, consensus may already have started
+                if let Some(pledge) = tx.get_active_pledge(shard)? {
+                    if pledge.pledged_to_payload_id != payload_id {
+                        return Err(TransactionRejectReason::InputPledged {
+                            shard,
+                            payload_id: pledge.pledged_to_payload_id,
+                        }
+                        .into());
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_dan_common_types::{Epoch, NodeHeight, PayloadId};
+    use tari_dan_core::{
+        services::epoch_manager::EpochManagerError,
+        storage::{shard_store::ShardStoreWriteTransaction, StorageError},
+    };
+
+    use super::*;
+    use crate::p2p::services::mempool::test_utils::{
+        create_shard,
+        create_shard_store,
+        create_transaction,
+        insert_substate,
+        spawn_epoch_manager,
+    };
+
+    fn create_validator(shard_store: &ShardStoreBackend, local_shards: Vec<ShardId>) -> InputsValidator {
+        let epoch_manager = spawn_epoch_manager(Epoch(1), local_shards, vec![]);
+        InputsValidator::new(shard_store.clone(), epoch_manager, PublicKey::default())
+    }
+
+    fn pledge(shard_store: &ShardStoreBackend, shard: ShardId, payload_id: PayloadId) {
+        shard_store
+            .with_write_tx(|tx| {
+                tx.pledge_object(shard, payload_id, NodeHeight(1))?;
+                Ok::<_, StorageError>(())
+            })
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_only_checks_local_inputs() {
+        let (_temp_dir, shard_store) = create_shard_store();
+        let (_, remote) = create_shard(1);
+        let validator = create_validator(&shard_store, vec![]);
+
+        let transaction = create_transaction(vec![], vec![remote]);
+        validator.validate(&transaction).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_accepts_existing_unpledged_inputs() {
+        let (_temp_dir, shard_store) = create_shard_store();
+        let input = insert_substate(&shard_store, 1, None);
+        let validator = create_validator(&shard_store, vec![input]);
+
+        let transaction = create_transaction(vec![], vec![input]);
+        validator.validate(&transaction).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_rejects_inputs_that_do_not_exist() {
+        let (_temp_dir, shard_store) = create_shard_store();
+        let (_, input) = create_shard(1);
+        let validator = create_validator(&shard_store, vec![input]);
+
+        let transaction = create_transaction(vec![], vec![input]);
+        let err = validator.validate(&transaction).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::InputNotFound { shard }) if shard == input
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_destroyed_inputs() {
+        let (_temp_dir, shard_store) = create_shard_store();
+        let input = insert_substate(&shard_store, 1, Some(PayloadId::new([1u8; 32])));
+        let validator = create_validator(&shard_store, vec![input]);
+
+        let transaction = create_transaction(vec![], vec![input]);
+        let err = validator.validate(&transaction).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::InputDestroyed { shard }) if shard == input
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_rejects_inputs_pledged_to_another_payload() {
+        let (_temp_dir, shard_store) = create_shard_store();
+        let input = insert_substate(&shard_store, 1, None);
+        let other_payload = PayloadId::new([1u8; 32]);
+        pledge(&shard_store, input, other_payload);
+        let validator = create_validator(&shard_store, vec![input]);
+
+        let transaction = create_transaction(vec![], vec![input]);
+        let err = validator.validate(&transaction).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::InputPledged { shard, payload_id })
+                if shard == input && payload_id == other_payload
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_accepts_inputs_pledged_to_the_transaction() {
+        let (_temp_dir, shard_store) = create_shard_store();
+        let input = insert_substate(&shard_store, 1, None);
+        let validator = create_validator(&shard_store, vec![input]);
+
+        let transaction = create_transaction(vec![], vec![input]);
+        pledge(&shard_store, input, TariDanPayload::new(transaction.clone()).to_id());
+        validator.validate(&transaction).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_fails_if_the_committee_cannot_be_determined() {
+        let (_temp_dir, shard_store) = create_shard_store();
+        let (_, input) = create_shard(1);
+        let (tx_request, rx_request) = tokio::sync::mpsc::channel(1);
+        // The epoch manager is not running, so every request is cancelled
+        drop(rx_request);
+        let validator = InputsValidator::new(
+            shard_store.clone(),
+            EpochManagerHandle::new(tx_request),
+            PublicKey::default(),
+        );
+
+        let transaction = create_transaction(vec![], vec![input]);
+        let err = validator.validate(&transaction).await.unwrap_err();
+        assert!(matches!(err, MempoolError::EpochManagerError(e) if matches!(*e, EpochManagerError::SendError)));
+    }
+}
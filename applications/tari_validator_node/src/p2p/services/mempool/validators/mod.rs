@@ -0,0 +1,140 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Mempool admission checks. Each validator performs a single check and they are chained together with
+//! [`Validator::and_then`], cheapest first, so that a transaction is rejected by the first check that fails.
+
+mod dry_run;
+pub use dry_run::DryRunValidator;
+
+mod epoch_range;
+pub use epoch_range::EpochRangeValidator;
+
+mod inputs;
+pub use inputs::InputsValidator;
+
+mod signature;
+pub use signature::SignatureValidator;
+
+mod size;
+pub use size::TransactionSizeValidator;
+
+mod template_exists;
+use async_trait::async_trait;
+pub use template_exists::TemplateExistsValidator;
+
+use crate::p2p::services::mempool::Validator;
+
+#[derive(Debug)]
+pub struct AndThen<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> AndThen<A, B> {
+    pub(super) fn new(first: A, second: B) -> Self {
+        Self { first, second }
+    }
+}
+
+#[async_trait]
+impl<T, A, B> Validator<T> for AndThen<A, B>
+where
+    T: Send + Sync,
+    A: Validator<T> + Send + Sync,
+    B: Validator<T, Error = A::Error> + Send + Sync,
+{
+    type Error = A::Error;
+
+    async fn validate(&self, input: &T) -> Result<(), Self::Error> {
+        self.first.validate(input).await?;
+        self.second.validate(input).await
+    }
+}
+
+/// An optional validator passes every input when it is not set
+#[async_trait]
+impl<T, V> Validator<T> for Option<V>
+where
+    T: Send + Sync,
+    V: Validator<T> + Send + Sync,
+{
+    type Error = V::Error;
+
+    async fn validate(&self, input: &T) -> Result<(), Self::Error> {
+        match self {
+            Some(validator) => validator.validate(input).await,
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Counts its calls and passes inputs that are at most the maximum
+    struct MaxValidator {
+        max: u32,
+        calls: AtomicUsize,
+    }
+
+    impl MaxValidator {
+        fn new(max: u32) -> Self {
+            Self {
+                max,
+                calls: AtomicUsize::new(0),
+            }
+        }
+
+        fn calls(&self) -> usize {
+            self.calls.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl<'a> Validator<u32> for &'a MaxValidator {
+        type Error = u32;
+
+        async fn validate(&self, input: &u32) -> Result<(), Self::Error> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if *input > self.max {
+                return Err(self.max);
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn and_then_runs_validators_in_order_until_one_fails() {
+        let first = MaxValidator::new(10);
+        let second = MaxValidator::new(5);
+        let validator = (&first).and_then(&second);
+
+        validator.validate(&5).await.unwrap();
+        assert_eq!(first.calls(), 1);
+        assert_eq!(second.calls(), 1);
+
+        assert_eq!(validator.validate(&7).await.unwrap_err(), 5);
+        assert_eq!(first.calls(), 2);
+        assert_eq!(second.calls(), 2);
+
+        // The second validator is not run when the first fails
+        assert_eq!(validator.validate(&11).await.unwrap_err(), 10);
+        assert_eq!(first.calls(), 3);
+        assert_eq!(second.calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn unset_optional_validators_pass_every_input() {
+        let first = MaxValidator::new(10);
+        let validator = (&first).and_then(None::<&MaxValidator>);
+        validator.validate(&10).await.unwrap();
+
+        let second = MaxValidator::new(5);
+        let validator = (&first).and_then(Some(&second));
+        assert_eq!(validator.validate(&10).await.unwrap_err(), 5);
+    }
+}
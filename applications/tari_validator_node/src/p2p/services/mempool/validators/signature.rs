@@ -0,0 +1,25 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use async_trait::async_trait;
+use tari_transaction::Transaction;
+use tari_validator_node_client::types::TransactionRejectReason;
+
+use crate::p2p::services::mempool::{MempoolError, Validator};
+
+/// Rejects transactions whose body is not signed by the sender and every co-signer
+#[derive(Debug, Default)]
+pub struct SignatureValidator;
+
+#[async_trait]
+impl Validator<Transaction> for SignatureValidator {
+    type Error = MempoolError;
+
+    async fn validate(&self, transaction: &Transaction) -> Result<(), MempoolError> {
+        if !transaction.verify_all_signatures() {
+            return Err(TransactionRejectReason::InvalidSignature.into());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use async_trait::async_trait;
+use prost::Message;
+use tari_dan_app_grpc::proto;
+use tari_transaction::Transaction;
+use tari_validator_node_client::types::TransactionRejectReason;
+
+use crate::p2p::services::mempool::{MempoolError, Validator};
+
+/// Rejects transactions whose encoded size, as propagated to other validator nodes, exceeds the maximum
+#[derive(Debug)]
+pub struct TransactionSizeValidator {
+    max_size: usize,
+}
+
+impl TransactionSizeValidator {
+    pub fn new(max_size: usize) -> Self {
+        Self { max_size }
+    }
+}
+
+#[async_trait]
+impl Validator<Transaction> for TransactionSizeValidator {
+    type Error = MempoolError;
+
+    async fn validate(&self, transaction: &Transaction) -> Result<(), MempoolError> {
+        let size = proto::transaction::Transaction::from(transaction.clone()).encoded_len();
+        if size > self.max_size {
+            return Err(TransactionRejectReason::TooLarge {
+                size,
+                max_size: self.max_size,
+            }
+            .into());
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::p2p::services::mempool::test_utils::create_transaction;
+
+    #[tokio::test]
+    async fn it_rejects_transactions_larger_than_the_maximum() {
+        let transaction = create_transaction(vec![], vec![]);
+        let size = proto::transaction::Transaction::from(transaction.clone()).encoded_len();
+
+        TransactionSizeValidator::new(size)
+            .validate(&transaction)
+            .await
+            .unwrap();
+
+        let err = TransactionSizeValidator::new(size - 1)
+            .validate(&transaction)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::TooLarge { size: s, max_size }) if s == size && max_size == size - 1
+        ));
+    }
+}
@@ -0,0 +1,87 @@
+//   Copyright 2022 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use async_trait::async_trait;
+use tari_engine_types::instruction::Instruction;
+use tari_transaction::Transaction;
+use tari_validator_node_client::types::TransactionRejectReason;
+
+use crate::p2p::services::{
+    mempool::{MempoolError, Validator},
+    template_manager::TemplateManager,
+};
+
+/// Rejects transactions that call functions on templates that this node does not know about
+#[derive(Debug)]
+pub struct TemplateExistsValidator {
+    template_manager: TemplateManager,
+}
+
+impl TemplateExistsValidator {
+    pub fn new(template_manager: TemplateManager) -> Self {
+        Self { template_manager }
+    }
+}
+
+#[async_trait]
+impl Validator<Transaction> for TemplateExistsValidator {
+    type Error = MempoolError;
+
+    async fn validate(&self, transaction: &Transaction) -> Result<(), MempoolError> {
+        for instruction in transaction.instructions() {
+            if let Instruction::CallFunction { template_address, .. } = instruction {
+                if !self.template_manager.template_exists(template_address)? {
+                    return Err(TransactionRejectReason::TemplateNotFound {
+                        template_address: *template_address,
+                    }
+                    .into());
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_template_builtin::ACCOUNT_TEMPLATE_ADDRESS;
+    use tari_template_lib::{args, models::TemplateAddress};
+
+    use super::*;
+    use crate::p2p::services::mempool::test_utils::{create_template_manager, create_transaction};
+
+    fn call_function(template_address: TemplateAddress) -> Instruction {
+        Instruction::CallFunction {
+            template_address,
+            function: "create".to_string(),
+            args: args![],
+        }
+    }
+
+    #[tokio::test]
+    async fn it_accepts_calls_to_known_templates() {
+        let (_temp_dir, template_manager) = create_template_manager();
+        let validator = TemplateExistsValidator::new(template_manager);
+
+        let transaction = create_transaction(vec![call_function(ACCOUNT_TEMPLATE_ADDRESS)], vec![]);
+        validator.validate(&transaction).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn it_rejects_calls_to_unknown_templates() {
+        let (_temp_dir, template_manager) = create_template_manager();
+        let validator = TemplateExistsValidator::new(template_manager);
+
+        let unknown = TemplateAddress::from_array([1u8; 32]);
+        let transaction = create_transaction(
+            vec![call_function(ACCOUNT_TEMPLATE_ADDRESS), call_function(unknown)],
+            vec![],
+        );
+        let err = validator.validate(&transaction).await.unwrap_err();
+        assert!(matches!(
+            err,
+            MempoolError::Rejected(TransactionRejectReason::TemplateNotFound { template_address }) if template_address == unknown
+        ));
+    }
+}
@@ -54,6 +54,9 @@ impl MessageDispatcher {
                 },
                 DanMessage::VoteMessage(msg) => self.message_senders.tx_vote_message.send((from, msg)).await?,
                 DanMessage::NewTransaction(msg) => self.message_senders.tx_new_transaction_message.send(*msg).await?,
+                DanMessage::NewTransactionBatch(msg) => {
+                    self.message_senders.tx_new_transaction_batch_message.send(msg).await?
+                },
                 DanMessage::NetworkAnnounce(announce) => {
                     self.message_senders.tx_network_announce.send((from, *announce)).await?
                 },
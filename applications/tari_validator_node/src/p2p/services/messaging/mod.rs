@@ -62,6 +62,7 @@ pub struct DanMessageSenders {
     pub tx_consensus_message: mpsc::Sender<(CommsPublicKey, HotStuffMessage<TariDanPayload, CommsPublicKey>)>,
     pub tx_vote_message: mpsc::Sender<(CommsPublicKey, VoteMessage)>,
     pub tx_new_transaction_message: mpsc::Sender<Transaction>,
+    pub tx_new_transaction_batch_message: mpsc::Sender<Vec<Transaction>>,
     pub tx_network_announce: mpsc::Sender<(CommsPublicKey, NetworkAnnounce<CommsPublicKey>)>,
     pub tx_recovery_message: mpsc::Sender<(CommsPublicKey, RecoveryMessage)>,
 }
@@ -71,6 +72,7 @@ pub struct DanMessageReceivers {
     pub rx_consensus_message: mpsc::Receiver<(CommsPublicKey, HotStuffMessage<TariDanPayload, CommsPublicKey>)>,
     pub rx_vote_message: mpsc::Receiver<(CommsPublicKey, VoteMessage)>,
     pub rx_new_transaction_message: mpsc::Receiver<Transaction>,
+    pub rx_new_transaction_batch_message: mpsc::Receiver<Vec<Transaction>>,
     pub rx_network_announce: mpsc::Receiver<(CommsPublicKey, NetworkAnnounce<CommsPublicKey>)>,
     pub rx_recovery_message: mpsc::Receiver<(CommsPublicKey, RecoveryMessage)>,
 }
@@ -79,12 +81,14 @@ pub fn new_messaging_channel(size: usize) -> (DanMessageSenders, DanMessageRecei
     let (tx_consensus_message, rx_consensus_message) = mpsc::channel(size);
     let (tx_vote_message, rx_vote_message) = mpsc::channel(size);
     let (tx_new_transaction_message, rx_new_transaction_message) = mpsc::channel(size);
+    let (tx_new_transaction_batch_message, rx_new_transaction_batch_message) = mpsc::channel(size);
     let (tx_network_announce, rx_network_announce) = mpsc::channel(size);
     let (tx_recovery_message, rx_recovery_message) = mpsc::channel(size);
     let senders = DanMessageSenders {
         tx_consensus_message,
         tx_vote_message,
         tx_new_transaction_message,
+        tx_new_transaction_batch_message,
         tx_network_announce,
         tx_recovery_message,
     };
@@ -92,6 +96,7 @@ pub fn new_messaging_channel(size: usize) -> (DanMessageSenders, DanMessageRecei
         rx_consensus_message,
         rx_vote_message,
         rx_new_transaction_message,
+        rx_new_transaction_batch_message,
         rx_network_announce,
         rx_recovery_message,
     };
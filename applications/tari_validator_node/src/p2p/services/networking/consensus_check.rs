@@ -0,0 +1,127 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::time::Duration;
+
+use anyhow::anyhow;
+use log::*;
+use tari_comms::{connectivity::ConnectivityRequester, PeerConnection};
+use tari_dan_app_grpc::proto;
+use tari_dan_core::consensus_constants::ConsensusConstants;
+
+use crate::p2p::rpc;
+
+const LOG_TARGET: &str = "tari::validator_node::networking::consensus_check";
+
+/// How long a peer with different consensus constants is banned for
+const CONSENSUS_MISMATCH_BAN_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// Checks that a newly connected peer uses the same consensus constants as this node, and bans the peer if it does not
+pub struct ConsensusCheckProtocol {
+    conn: PeerConnection,
+    consensus_constants: ConsensusConstants,
+    connectivity: ConnectivityRequester,
+}
+
+impl ConsensusCheckProtocol {
+    pub fn new(
+        conn: PeerConnection,
+        consensus_constants: ConsensusConstants,
+        connectivity: ConnectivityRequester,
+    ) -> Self {
+        Self {
+            conn,
+            consensus_constants,
+            connectivity,
+        }
+    }
+
+    /// Returns true if the peer agrees on the consensus constants
+    pub async fn run(mut self) -> Result<bool, anyhow::Error> {
+        let mut client = self.conn.connect_rpc::<rpc::ValidatorNodeRpcClient>().await?;
+        let peer_constants = client
+            .get_consensus_constants(proto::rpc::GetConsensusConstantsRequest {})
+            .await?
+            .consensus_constants
+            .ok_or_else(|| anyhow!("Peer {} did not send its consensus constants", self.conn.peer_node_id()))?;
+        let Some(peer_constants) = find_mismatch(&self.consensus_constants, peer_constants)? else {
+            debug!(
+                target: LOG_TARGET,
+                "🤝 Peer {} agrees on the consensus constants",
+                self.conn.peer_node_id()
+            );
+            return Ok(true);
+        };
+
+        warn!(
+            target: LOG_TARGET,
+            "🚫 Peer {} uses different consensus constants (ours: {:?}, theirs: {:?}). Banning peer for {:.0?}.",
+            self.conn.peer_node_id(),
+            self.consensus_constants,
+            peer_constants,
+            CONSENSUS_MISMATCH_BAN_DURATION
+        );
+        self.connectivity
+            .ban_peer_until(
+                self.conn.peer_node_id().clone(),
+                CONSENSUS_MISMATCH_BAN_DURATION,
+                "Peer uses different consensus constants".to_string(),
+            )
+            .await?;
+        Ok(false)
+    }
+}
+
+/// Returns the peer's consensus constants if they differ from ours, or None if they are the same. Constants that
+/// cannot be decoded, e.g. with an unknown leader strategy, are an error.
+fn find_mismatch(
+    ours: &ConsensusConstants,
+    theirs: proto::consensus::ConsensusConstants,
+) -> Result<Option<ConsensusConstants>, anyhow::Error> {
+    let theirs = ConsensusConstants::try_from(theirs)?;
+    if theirs == *ours {
+        Ok(None)
+    } else {
+        Ok(Some(theirs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_dan_core::services::leader_strategy::LeaderStrategyType;
+
+    use super::*;
+
+    #[test]
+    fn it_agrees_on_identical_constants() {
+        let ours = ConsensusConstants::localnet();
+        assert!(find_mismatch(&ours, ours.clone().into()).unwrap().is_none());
+    }
+
+    #[test]
+    fn it_disagrees_on_a_different_leader_strategy() {
+        let ours = ConsensusConstants::localnet();
+        for leader_strategy in [
+            LeaderStrategyType::AlwaysFirst,
+            LeaderStrategyType::Rotating,
+            LeaderStrategyType::Reputation,
+        ] {
+            let peer = ConsensusConstants {
+                leader_strategy,
+                ..ours.clone()
+            };
+            let theirs = find_mismatch(&ours, peer.into()).unwrap().unwrap();
+            assert_eq!(theirs.leader_strategy, leader_strategy);
+        }
+    }
+
+    #[test]
+    fn it_rejects_an_unknown_leader_strategy() {
+        let ours = ConsensusConstants::localnet();
+        let mut theirs = proto::consensus::ConsensusConstants::from(ours.clone());
+        theirs.leader_strategy = proto::consensus::LeaderStrategy::Unknown as i32;
+        find_mismatch(&ours, theirs.clone()).unwrap_err();
+        theirs.leader_strategy = 100;
+        find_mismatch(&ours, theirs).unwrap_err();
+    }
+}
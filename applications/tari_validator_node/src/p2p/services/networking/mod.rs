@@ -29,11 +29,12 @@ use tari_comms::{
     types::CommsPublicKey,
     NodeIdentity,
 };
-use tari_dan_core::message::NetworkAnnounce;
+use tari_dan_core::{consensus_constants::ConsensusConstants, message::NetworkAnnounce};
 use tokio::{sync::mpsc, task::JoinHandle};
 
 use crate::p2p::services::{comms_peer_provider::CommsPeerProvider, messaging::OutboundMessaging};
 
+mod consensus_check;
 mod service;
 use service::Networking;
 
@@ -53,6 +54,7 @@ pub fn spawn(
     outbound: OutboundMessaging,
     peer_provider: CommsPeerProvider,
     connectivity: ConnectivityRequester,
+    consensus_constants: ConsensusConstants,
 ) -> (NetworkingHandle, JoinHandle<anyhow::Result<()>>) {
     let (tx, rx) = mpsc::channel(1);
     let handle = tokio::spawn(
@@ -63,6 +65,7 @@ pub fn spawn(
             outbound,
             peer_provider,
             connectivity,
+            consensus_constants,
         )
         .run(),
     );
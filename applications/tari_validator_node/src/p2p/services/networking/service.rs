@@ -32,6 +32,7 @@ use tari_comms::{
 };
 use tari_dan_common_types::optional::Optional;
 use tari_dan_core::{
+    consensus_constants::ConsensusConstants,
     message::{DanMessage, NetworkAnnounce},
     services::{infrastructure_services::OutboundService, DanPeer, PeerProvider},
 };
@@ -43,7 +44,12 @@ use tokio::{
 use crate::p2p::services::{
     comms_peer_provider::CommsPeerProvider,
     messaging::OutboundMessaging,
-    networking::{handle::NetworkingRequest, peer_sync::PeerSyncProtocol, NetworkingError},
+    networking::{
+        consensus_check::ConsensusCheckProtocol,
+        handle::NetworkingRequest,
+        peer_sync::PeerSyncProtocol,
+        NetworkingError,
+    },
 };
 
 const LOG_TARGET: &str = "tari::validator_node::p2p::services::networking";
@@ -55,6 +61,7 @@ pub struct Networking {
     outbound: OutboundMessaging,
     peer_provider: CommsPeerProvider,
     connectivity: ConnectivityRequester,
+    consensus_constants: ConsensusConstants,
     peer_sync_permit: Arc<Semaphore>,
 }
 
@@ -66,6 +73,7 @@ impl Networking {
         outbound: OutboundMessaging,
         peer_provider: CommsPeerProvider,
         connectivity: ConnectivityRequester,
+        consensus_constants: ConsensusConstants,
     ) -> Self {
         Self {
             rx_network_announce,
@@ -74,6 +82,7 @@ impl Networking {
             outbound,
             peer_provider,
             connectivity,
+            consensus_constants,
             peer_sync_permit: Arc::new(Semaphore::new(1)),
         }
     }
@@ -226,11 +235,31 @@ impl Networking {
         Ok(())
     }
 
+    /// Checks that the peer agrees on the consensus constants and, if so, syncs peers from it
     fn initiate_sync_protocol(&self, conn: PeerConnection) {
         let permit = self.peer_sync_permit.clone();
         let peer_provider = self.peer_provider.clone();
         let our_identity = self.node_identity.public_key().clone();
+        let consensus_check = ConsensusCheckProtocol::new(
+            conn.clone(),
+            self.consensus_constants.clone(),
+            self.connectivity.clone(),
+        );
         task::spawn(async move {
+            match consensus_check.run().await {
+                Ok(true) => {},
+                Ok(false) => return,
+                Err(err) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "🤝 Failed to check consensus constants with {}, not syncing peers: {}",
+                        conn.peer_node_id(),
+                        err
+                    );
+                    return;
+                },
+            }
+
             let _permit = match permit.acquire().await {
                 Ok(permit) => permit,
                 Err(_) => {
@@ -0,0 +1,40 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::Serialize;
+use tari_dan_common_types::Epoch;
+use tari_dan_core::models::PrunedRecordCounts;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::p2p::services::pruning::PruningError;
+
+pub enum PruningRequest {
+    GetStats { reply: oneshot::Sender<PruningStats> },
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PruningStats {
+    pub enabled: bool,
+    pub num_epochs_to_keep: u64,
+    /// Committed payloads with nodes only from epochs before this epoch were pruned in the last run
+    pub last_pruned_before_epoch: Option<Epoch>,
+    pub total_payloads_pruned: u64,
+    pub total_records_pruned: PrunedRecordCounts,
+}
+
+#[derive(Debug, Clone)]
+pub struct PruningHandle {
+    tx_request: mpsc::Sender<PruningRequest>,
+}
+
+impl PruningHandle {
+    pub(super) fn new(tx_request: mpsc::Sender<PruningRequest>) -> Self {
+        Self { tx_request }
+    }
+
+    pub async fn get_stats(&self) -> Result<PruningStats, PruningError> {
+        let (tx, rx) = oneshot::channel();
+        self.tx_request.send(PruningRequest::GetStats { reply: tx }).await?;
+        rx.await.map_err(Into::into)
+    }
+}
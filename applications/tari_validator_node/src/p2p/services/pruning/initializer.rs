@@ -0,0 +1,30 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_dan_app_utilities::{epoch_manager::EpochManagerHandle, shard_store::ShardStoreBackend};
+use tari_dan_core::consensus_constants::ConsensusConstants;
+use tari_shutdown::ShutdownSignal;
+use tokio::{sync::mpsc, task, task::JoinHandle};
+
+use crate::p2p::services::pruning::{service::PruningService, PruningConfig, PruningHandle};
+
+pub fn spawn(
+    config: PruningConfig,
+    shard_store: ShardStoreBackend,
+    epoch_manager: EpochManagerHandle,
+    consensus_constants: &ConsensusConstants,
+    shutdown: ShutdownSignal,
+) -> (PruningHandle, JoinHandle<anyhow::Result<()>>) {
+    let (tx_request, rx_request) = mpsc::channel(1);
+    let service = PruningService::new(
+        config,
+        shard_store,
+        epoch_manager,
+        consensus_constants.max_payload_height(),
+        rx_request,
+        shutdown,
+    );
+    let join_handle = task::spawn(service.run());
+
+    (PruningHandle::new(tx_request), join_handle)
+}
@@ -0,0 +1,39 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+mod handle;
+pub use handle::{PruningHandle, PruningRequest, PruningStats};
+
+mod initializer;
+pub use initializer::spawn;
+
+mod pruning_config;
+pub use pruning_config::PruningConfig;
+
+mod service;
+
+use tari_dan_core::{services::epoch_manager::EpochManagerError, storage::StorageError};
+use thiserror::Error;
+use tokio::sync::{mpsc::error::SendError, oneshot};
+
+#[derive(Error, Debug)]
+pub enum PruningError {
+    #[error("Storage error: {0}")]
+    StorageError(#[from] StorageError),
+    #[error("Epoch Manager Error: {0}")]
+    EpochManagerError(#[from] EpochManagerError),
+    #[error("Internal service request cancelled")]
+    RequestCancelled,
+}
+
+impl From<SendError<PruningRequest>> for PruningError {
+    fn from(_: SendError<PruningRequest>) -> Self {
+        Self::RequestCancelled
+    }
+}
+
+impl From<oneshot::error::RecvError> for PruningError {
+    fn from(_: oneshot::error::RecvError) -> Self {
+        Self::RequestCancelled
+    }
+}
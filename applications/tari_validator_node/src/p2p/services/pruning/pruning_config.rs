@@ -0,0 +1,32 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{num::NonZeroUsize, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use tari_common::configuration::serializers;
+
+/// Configures the pruning of consensus data. Results of pruned payloads can no longer be queried.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PruningConfig {
+    /// If set to false, consensus data is never pruned
+    pub enabled: bool,
+    /// The number of epochs for which the consensus data of committed payloads is kept
+    pub num_epochs_to_keep: u64,
+    /// How often to check for prunable payloads
+    #[serde(with = "serializers::seconds")]
+    pub interval: Duration,
+    /// The maximum number of payloads that are pruned in a single database transaction
+    pub batch_size: NonZeroUsize,
+}
+
+impl Default for PruningConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            num_epochs_to_keep: 10,
+            interval: Duration::from_secs(10 * 60),
+            batch_size: NonZeroUsize::new(100).expect("100 is not zero"),
+        }
+    }
+}
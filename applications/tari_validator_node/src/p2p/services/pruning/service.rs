@@ -0,0 +1,138 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use log::*;
+use tari_dan_app_utilities::{epoch_manager::EpochManagerHandle, shard_store::ShardStoreBackend};
+use tari_dan_common_types::{Epoch, NodeHeight};
+use tari_dan_core::{
+    models::PrunedRecordCounts,
+    services::epoch_manager::EpochManager,
+    storage::shard_store::{ShardStore, ShardStoreReadTransaction, ShardStoreWriteTransaction},
+};
+use tari_shutdown::ShutdownSignal;
+use tokio::{sync::mpsc, task, time};
+
+use crate::p2p::services::pruning::{PruningConfig, PruningError, PruningRequest, PruningStats};
+
+const LOG_TARGET: &str = "tari::validator_node::pruning::service";
+
+/// Periodically removes the consensus data (tree nodes, votes, leader proposals, superseded high QCs, pledges,
+/// results and per-shard consensus state) of payloads that have been committed and are older than the configured
+/// number of epochs.
+pub struct PruningService {
+    config: PruningConfig,
+    shard_store: ShardStoreBackend,
+    epoch_manager: EpochManagerHandle,
+    /// The payload height of the DECIDE phase, at which a payload is committed
+    decide_height: NodeHeight,
+    requests: mpsc::Receiver<PruningRequest>,
+    shutdown: ShutdownSignal,
+    stats: PruningStats,
+}
+
+impl PruningService {
+    pub(super) fn new(
+        config: PruningConfig,
+        shard_store: ShardStoreBackend,
+        epoch_manager: EpochManagerHandle,
+        decide_height: NodeHeight,
+        requests: mpsc::Receiver<PruningRequest>,
+        shutdown: ShutdownSignal,
+    ) -> Self {
+        let stats = PruningStats {
+            enabled: config.enabled,
+            num_epochs_to_keep: config.num_epochs_to_keep,
+            ..Default::default()
+        };
+        Self {
+            config,
+            shard_store,
+            epoch_manager,
+            decide_height,
+            requests,
+            shutdown,
+            stats,
+        }
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        if !self.config.enabled {
+            info!(target: LOG_TARGET, "✂️ Pruning is disabled");
+        }
+        let mut interval = time::interval(self.config.interval);
+        loop {
+            tokio::select! {
+                Some(req) = self.requests.recv() => self.handle_request(req),
+                _ = interval.tick(), if self.config.enabled => {
+                    if let Err(err) = self.prune().await {
+                        error!(target: LOG_TARGET, "Error while pruning: {}", err);
+                    }
+                },
+                _ = self.shutdown.wait() => {
+                    info!(target: LOG_TARGET, "Pruning service shutting down");
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, request: PruningRequest) {
+        match request {
+            PruningRequest::GetStats { reply } => {
+                let _ignore = reply.send(self.stats.clone());
+            },
+        }
+    }
+
+    async fn prune(&mut self) -> Result<(), PruningError> {
+        let current_epoch = self.epoch_manager.current_epoch().await?;
+        if current_epoch.as_u64() < self.config.num_epochs_to_keep {
+            return Ok(());
+        }
+        let before_epoch = Epoch(current_epoch.as_u64() - self.config.num_epochs_to_keep);
+        let batch_size = self.config.batch_size.get();
+
+        let mut num_payloads = 0;
+        let mut counts = PrunedRecordCounts::default();
+        loop {
+            let (num_pruned, batch_counts) = self.shard_store.with_write_tx(|tx| {
+                let payloads = tx.get_prunable_payloads(before_epoch, self.decide_height, batch_size)?;
+                let mut counts = PrunedRecordCounts::default();
+                for payload_id in &payloads {
+                    counts += tx.prune_payload(*payload_id)?;
+                }
+                Ok::<_, PruningError>((payloads.len(), counts))
+            })?;
+            num_payloads += num_pruned as u64;
+            counts += batch_counts;
+
+            if num_pruned < batch_size {
+                break;
+            }
+            // Give consensus a chance to use the shard store between batches
+            task::yield_now().await;
+        }
+
+        if num_payloads > 0 {
+            info!(
+                target: LOG_TARGET,
+                "✂️ Pruned {} payload(s) from before epoch {}: {} node(s), {} vote(s), {} leader proposal(s), {} high \
+                 QC(s), {} pledge(s), {} consensus state(s)",
+                num_payloads,
+                before_epoch,
+                counts.nodes,
+                counts.received_votes,
+                counts.leader_proposals,
+                counts.high_qcs,
+                counts.pledges,
+                counts.consensus_states,
+            );
+        }
+
+        self.stats.last_pruned_before_epoch = Some(before_epoch);
+        self.stats.total_payloads_pruned += num_payloads;
+        self.stats.total_records_pruned += counts;
+        Ok(())
+    }
+}
@@ -23,6 +23,7 @@
 use std::{collections::HashMap, convert::TryFrom, fs};
 
 use log::*;
+use tari_crypto::tari_utilities::ByteArray;
 use tari_dan_app_utilities::template_manager::{
     Template,
     TemplateManagerError,
@@ -98,6 +99,8 @@ impl TemplateManager {
                 url: "".to_string(),
                 binary_sha: binary_sha.to_vec(),
                 height: 0,
+                version: 0,
+                predecessor: None,
             },
             compiled_code,
         }
@@ -155,6 +158,33 @@ impl TemplateManager {
     }
 
     pub(super) fn add_template(&self, template: TemplateRegistration) -> Result<(), TemplateManagerError> {
+        let mut tx = self.global_db.create_transaction()?;
+        let mut templates_db = self.global_db.templates(&mut tx);
+        if templates_db.get_template(template.template_address.as_ref())?.is_some() {
+            return Ok(());
+        }
+
+        // A template registered by the same author with the same name and a lower version is an upgrade of that
+        // template. Registrations that do not increase the version start a new lineage.
+        let author_public_key = template.registration.author_public_key.as_bytes().to_vec();
+        let version = template.registration.template_version;
+        let predecessor_address =
+            match templates_db.get_latest_template_version(&author_public_key, &template.template_name)? {
+                Some(latest) if latest.version < version => Some(latest.template_address),
+                Some(latest) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Template {} version {} is not greater than the latest registered version {}. It will not be \
+                         registered as an upgrade.",
+                        template.template_address,
+                        version,
+                        latest.version
+                    );
+                    None
+                },
+                None => None,
+            };
+
         let template = DbTemplate {
             template_name: template.template_name,
             template_address: template.template_address.into_array().into(),
@@ -163,13 +193,10 @@ impl TemplateManager {
             status: TemplateStatus::New,
             compiled_code: vec![],
             added_at: time::OffsetDateTime::now_utc(),
+            author_public_key,
+            version,
+            predecessor_address,
         };
-
-        let mut tx = self.global_db.create_transaction()?;
-        let mut templates_db = self.global_db.templates(&mut tx);
-        if templates_db.get_template(&*template.template_address)?.is_some() {
-            return Ok(());
-        }
         templates_db.insert_template(template)?;
         tx.commit()?;
 
@@ -208,4 +235,19 @@ impl TemplateProvider for TemplateManager {
 
         Ok(loaded)
     }
+
+    fn get_template_predecessor(&self, address: &TemplateAddress) -> Result<Option<TemplateAddress>, Self::Error> {
+        if self.builtin_templates.contains_key(address) {
+            return Ok(None);
+        }
+
+        let mut tx = self.global_db.create_transaction()?;
+        let template = self
+            .global_db
+            .templates(&mut tx)
+            .get_template(address)?
+            .ok_or(TemplateManagerError::TemplateNotFound { address: *address })?;
+
+        Ok(template.predecessor_address.map(|addr| (*addr).into()))
+    }
 }
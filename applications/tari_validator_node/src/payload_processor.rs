@@ -26,15 +26,16 @@ use std::{
 };
 
 use tari_crypto::tari_utilities::ByteArray;
-use tari_dan_common_types::{ObjectPledge, ShardId, SubstateState};
+use tari_dan_common_types::{Epoch, ObjectPledge, ShardId, SubstateState};
 use tari_dan_core::{
     models::TariDanPayload,
     services::{PayloadProcessor, PayloadProcessorError, TemplateProvider},
 };
 use tari_dan_engine::{
     bootstrap_state,
-    packager::{LoadedTemplate, Package},
-    runtime::{AuthParams, ConsensusContext},
+    fees::{FeeModule, FeeTable, FuelLimitModule},
+    packager::{LoadedTemplate, Package, PackageError, TemplateResolver},
+    runtime::{AuthParams, ConsensusContext, RuntimeModule},
     state_store::{memory::MemoryStateStore, AtomicDb, StateReader, StateStoreError, StateWriter},
     transaction::{TransactionError, TransactionProcessor},
     wasm::WasmExecutionError,
@@ -50,19 +51,31 @@ use tari_template_lib::{
 };
 use tari_transaction::Transaction;
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct TariDanPayloadProcessor<TTemplateProvider> {
     template_provider: TTemplateProvider,
+    fee_table: FeeTable,
+    fuel_limit: Option<u64>,
 }
 
 impl<TTemplateProvider> TariDanPayloadProcessor<TTemplateProvider> {
-    pub fn new(template_provider: TTemplateProvider) -> Self {
-        Self { template_provider }
+    pub fn new(template_provider: TTemplateProvider, fee_table: FeeTable) -> Self {
+        Self {
+            template_provider,
+            fee_table,
+            fuel_limit: None,
+        }
+    }
+
+    /// Aborts the execution of transactions that consume more than the given fuel
+    pub fn with_fuel_limit(mut self, fuel_limit: u64) -> Self {
+        self.fuel_limit = Some(fuel_limit);
+        self
     }
 }
 
 impl<TTemplateProvider> PayloadProcessor<TariDanPayload> for TariDanPayloadProcessor<TTemplateProvider>
-where TTemplateProvider: TemplateProvider<Template = LoadedTemplate>
+where TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + Clone + Send + Sync + 'static
 {
     fn process_payload(
         &self,
@@ -71,21 +84,75 @@ where TTemplateProvider: TemplateProvider<Template = LoadedTemplate>
         consensus: ConsensusContext,
     ) -> Result<FinalizeResult, PayloadProcessorError> {
         let transaction = payload.into_payload();
+        // The signers' badges are added to the auth scope, so a transaction is only executed if the sender and every
+        // co-signer signed its body
+        if !transaction.verify_all_signatures() {
+            return Ok(FinalizeResult::reject(
+                *transaction.hash(),
+                RejectReason::InvalidSignature("Transaction has an invalid signature".to_string()),
+            ));
+        }
+
+        // The validity window is checked against the epoch that consensus is running in, so that a transaction that
+        // was admitted to the mempool before it expired is still rejected
+        let current_epoch = Epoch(consensus.current_epoch);
+        if !transaction.is_valid_in_epoch(current_epoch) {
+            return Ok(FinalizeResult::reject(
+                *transaction.hash(),
+                RejectReason::InvalidEpoch(format!("Transaction is not valid in epoch {}", current_epoch)),
+            ));
+        }
+
+        let state_store = create_populated_state_store(pledges.into_values())?;
+        self.execute(transaction, state_store, consensus)
+    }
+}
+
+impl<TTemplateProvider> TariDanPayloadProcessor<TTemplateProvider>
+where TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + Clone + Send + Sync + 'static
+{
+    /// Executes the transaction against the pledged substates and returns the result together with the addresses of
+    /// the pledged substates that were read during execution
+    pub fn execute_and_track_reads(
+        &self,
+        transaction: Transaction,
+        pledges: HashMap<ShardId, ObjectPledge>,
+        consensus: ConsensusContext,
+    ) -> Result<(FinalizeResult, Vec<SubstateAddress>), PayloadProcessorError> {
+        let mut state_store = create_populated_state_store(pledges.into_values())?;
+        state_store.track_reads();
+        let result = self.execute(transaction, state_store.clone(), consensus)?;
+        let reads = state_store
+            .read_keys()
+            .iter()
+            .filter_map(|key| tari_bor::decode_exact::<SubstateAddress>(key).ok())
+            .collect();
+        Ok((result, reads))
+    }
+
+    fn execute(
+        &self,
+        transaction: Transaction,
+        state_store: MemoryStateStore,
+        consensus: ConsensusContext,
+    ) -> Result<FinalizeResult, PayloadProcessorError> {
         let mut template_addresses = HashSet::<_, RandomState>::from_iter(transaction.required_templates());
         let components = transaction.required_components();
 
-        let state_store = create_populated_state_store(pledges.into_values())?;
         template_addresses.extend(load_template_addresses_for_components(&state_store, &components)?);
 
         let package = build_package(&self.template_provider, template_addresses)?;
 
         // Include ownership token for the signers of this in the auth scope
-        let owner_token = get_auth_token(&transaction);
         let auth_params = AuthParams {
-            initial_ownership_proofs: vec![owner_token],
+            initial_ownership_proofs: get_auth_tokens(&transaction),
         };
 
-        let modules = vec![]; // No modules for now, currently used in tests. Also will be useful for more advanced use-cases like fees, etc.
+        // Transactions that do not pay the fees charged by the fee module are rejected with FeesNotPaid
+        let mut modules: Vec<Box<dyn RuntimeModule>> = vec![Box::new(FeeModule::new(self.fee_table.clone()))];
+        if let Some(fuel_limit) = self.fuel_limit {
+            modules.push(Box::new(FuelLimitModule::new(fuel_limit)));
+        }
 
         let processor = TransactionProcessor::new(package, state_store, auth_params, consensus, modules);
         let tx_hash = *transaction.hash();
@@ -102,10 +169,13 @@ where TTemplateProvider: TemplateProvider<Template = LoadedTemplate>
     }
 }
 
-fn build_package<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>>(
+fn build_package<TTemplateProvider>(
     template_provider: &TTemplateProvider,
     template_addresses: HashSet<TemplateAddress>,
-) -> Result<Package, PayloadProcessorError> {
+) -> Result<Package, PayloadProcessorError>
+where
+    TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + Clone + Send + Sync + 'static,
+{
     let mut builder = Package::builder();
 
     for addr in template_addresses {
@@ -115,13 +185,56 @@ fn build_package<TTemplateProvider: TemplateProvider<Template = LoadedTemplate>>
         builder.add_template(addr, template);
     }
 
+    // Templates of components that are called by other components, upgrade targets and template lineages are only
+    // known once the transaction executes, so they are loaded on demand
+    builder.with_resolver(TemplateProviderResolver {
+        template_provider: template_provider.clone(),
+    });
+
     Ok(builder.build())
 }
 
-fn get_auth_token(transaction: &Transaction) -> NonFungibleAddress {
-    let public_key = RistrettoPublicKeyBytes::from_bytes(transaction.sender_public_key().as_bytes())
-        .expect("Expected public key to be 32 bytes");
-    NonFungibleAddress::from_public_key(public_key)
+/// Loads templates from the template provider when they are first called during execution
+struct TemplateProviderResolver<TTemplateProvider> {
+    template_provider: TTemplateProvider,
+}
+
+impl<TTemplateProvider> TemplateResolver for TemplateProviderResolver<TTemplateProvider>
+where TTemplateProvider: TemplateProvider<Template = LoadedTemplate> + Send + Sync
+{
+    fn resolve_template(&self, address: &TemplateAddress) -> Result<Option<LoadedTemplate>, PackageError> {
+        self.template_provider
+            .get_template_module(address)
+            .map(Some)
+            .map_err(|err| PackageError::TemplateLoadFailed {
+                address: *address,
+                details: err.to_string(),
+            })
+    }
+
+    fn resolve_template_predecessor(&self, address: &TemplateAddress) -> Result<Option<TemplateAddress>, PackageError> {
+        self.template_provider
+            .get_template_predecessor(address)
+            .map_err(|err| PackageError::TemplateLoadFailed {
+                address: *address,
+                details: err.to_string(),
+            })
+    }
+}
+
+/// Returns the public key badge of each signer of the transaction. A signer's badge is only included once, even if
+/// they signed more than once.
+fn get_auth_tokens(transaction: &Transaction) -> Vec<NonFungibleAddress> {
+    let mut tokens = Vec::new();
+    for public_key in transaction.signer_public_keys() {
+        let public_key =
+            RistrettoPublicKeyBytes::from_bytes(public_key.as_bytes()).expect("Expected public key to be 32 bytes");
+        let token = NonFungibleAddress::from_public_key(public_key);
+        if !tokens.contains(&token) {
+            tokens.push(token);
+        }
+    }
+    tokens
 }
 
 fn load_template_addresses_for_components(
@@ -170,3 +283,318 @@ fn create_populated_state_store<I: IntoIterator<Item = ObjectPledge>>(
 
     Ok(state_db)
 }
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+    use tari_common_types::types::{PrivateKey, PublicKey};
+    use tari_crypto::keys::PublicKey as PublicKeyT;
+    use tari_dan_core::models::Payload;
+    use tari_dan_engine::{packager::TemplateModuleLoader, wasm::compile::compile_template};
+    use tari_engine_types::instruction::Instruction;
+    use tari_template_lib::{args, models::Amount};
+    use tari_transaction::TransactionSignature;
+
+    use super::*;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("Template {0} not found")]
+    struct TemplateNotFound(TemplateAddress);
+
+    #[derive(Debug, Clone, Default)]
+    struct TestTemplateProvider {
+        templates: HashMap<TemplateAddress, LoadedTemplate>,
+        predecessors: HashMap<TemplateAddress, TemplateAddress>,
+    }
+
+    impl TemplateProvider for TestTemplateProvider {
+        type Error = TemplateNotFound;
+        type Template = LoadedTemplate;
+
+        fn get_template_module(&self, id: &TemplateAddress) -> Result<Self::Template, Self::Error> {
+            self.templates.get(id).cloned().ok_or(TemplateNotFound(*id))
+        }
+
+        fn get_template_predecessor(&self, id: &TemplateAddress) -> Result<Option<TemplateAddress>, Self::Error> {
+            Ok(self.predecessors.get(id).copied())
+        }
+    }
+
+    fn create_template_provider(templates: &[(&str, TemplateAddress)]) -> TestTemplateProvider {
+        let templates = templates
+            .iter()
+            .map(|(name, address)| {
+                let template = compile_template(format!("../../dan_layer/engine/tests/templates/{}", name), &[])
+                    .unwrap()
+                    .load_template()
+                    .unwrap();
+                (*address, template)
+            })
+            .collect();
+        TestTemplateProvider {
+            templates,
+            predecessors: HashMap::new(),
+        }
+    }
+
+    fn create_processor(
+        templates: &[(&str, TemplateAddress)],
+        fee_table: FeeTable,
+    ) -> TariDanPayloadProcessor<TestTemplateProvider> {
+        TariDanPayloadProcessor::new(create_template_provider(templates), fee_table)
+    }
+
+    /// Processes a transaction with the given instruction against the state and applies the resulting substate changes
+    /// to the state
+    fn process_and_apply(
+        processor: &TariDanPayloadProcessor<TestTemplateProvider>,
+        state: &mut HashMap<ShardId, ObjectPledge>,
+        secret_key: &PrivateKey,
+        instruction: Instruction,
+    ) -> FinalizeResult {
+        let payload = create_payload(secret_key, instruction);
+        let payload_id = payload.to_id();
+        let result = processor
+            .process_payload(payload, state.clone(), consensus_context())
+            .unwrap();
+        let diff = result
+            .result
+            .accept()
+            .unwrap_or_else(|| panic!("Transaction was rejected: {}", result.result.reject().unwrap()));
+        for (address, version) in diff.down_iter() {
+            state.remove(&ShardId::from_address(address, *version));
+        }
+        for (address, substate) in diff.up_iter() {
+            let shard_id = ShardId::from_address(address, substate.version());
+            state.insert(shard_id, ObjectPledge {
+                shard_id,
+                current_state: SubstateState::Up {
+                    created_by: payload_id,
+                    address: address.clone(),
+                    data: substate.clone(),
+                },
+                pledged_to_payload: payload_id,
+            });
+        }
+        result
+    }
+
+    fn create_payload(secret_key: &PrivateKey, instruction: Instruction) -> TariDanPayload {
+        let transaction = Transaction::builder()
+            .add_instruction(instruction)
+            .sign(secret_key)
+            .clone()
+            .build();
+        TariDanPayload::new(transaction)
+    }
+
+    fn consensus_context() -> ConsensusContext {
+        ConsensusContext {
+            current_epoch: 0,
+            current_epoch_timestamp: 0,
+            current_epoch_beacon: [0; 32],
+        }
+    }
+
+    #[test]
+    fn it_rejects_transactions_that_do_not_pay_fees() {
+        let faucet_template = TemplateAddress::from_array([2; 32]);
+        let processor = create_processor(&[("faucet", faucet_template)], FeeTable {
+            per_runtime_call_cost: 1,
+            per_wasm_point_cost: 1,
+            per_byte_storage_cost: 1,
+        });
+
+        let (secret_key, _) = PublicKey::random_keypair(&mut OsRng);
+        let payload = create_payload(&secret_key, Instruction::CallFunction {
+            template_address: faucet_template,
+            function: "mint".to_string(),
+            args: args![Amount(1_000_000)],
+        });
+        let result = processor
+            .process_payload(payload, HashMap::new(), consensus_context())
+            .unwrap();
+
+        assert!(matches!(result.result.reject(), Some(RejectReason::FeesNotPaid(_))));
+        assert!(result.result.accept().is_none());
+    }
+
+    #[test]
+    fn it_rejects_transactions_outside_of_their_validity_window() {
+        let faucet_template = TemplateAddress::from_array([2; 32]);
+        let processor = create_processor(&[("faucet", faucet_template)], FeeTable::zero_rated());
+        let (secret_key, _) = PublicKey::random_keypair(&mut OsRng);
+        let transaction = Transaction::builder()
+            .add_instruction(Instruction::CallFunction {
+                template_address: faucet_template,
+                function: "mint".to_string(),
+                args: args![Amount(1_000_000)],
+            })
+            .with_min_epoch(Epoch(2))
+            .with_expires_at_epoch(Epoch(4))
+            .sign(&secret_key)
+            .clone()
+            .build();
+
+        let process_in_epoch = |epoch| {
+            processor
+                .process_payload(
+                    TariDanPayload::new(transaction.clone()),
+                    HashMap::new(),
+                    ConsensusContext {
+                        current_epoch: epoch,
+                        ..consensus_context()
+                    },
+                )
+                .unwrap()
+        };
+
+        for epoch in [1, 4] {
+            let result = process_in_epoch(epoch);
+            assert!(matches!(result.result.reject(), Some(RejectReason::InvalidEpoch(_))));
+        }
+        let result = process_in_epoch(3);
+        assert!(result.result.accept().is_some());
+    }
+
+    #[test]
+    fn it_passes_buckets_between_components_of_different_templates() {
+        let state_template = TemplateAddress::from_array([1; 32]);
+        let faucet_template = TemplateAddress::from_array([2; 32]);
+        let composability_template = TemplateAddress::from_array([3; 32]);
+        let processor = create_processor(
+            &[
+                ("state", state_template),
+                ("faucet", faucet_template),
+                ("composability", composability_template),
+            ],
+            FeeTable::zero_rated(),
+        );
+        let mut state = HashMap::new();
+        let (secret_key, _) = PublicKey::random_keypair(&mut OsRng);
+
+        let result = process_and_apply(&processor, &mut state, &secret_key, Instruction::CallFunction {
+            template_address: faucet_template,
+            function: "mint".to_string(),
+            args: args![Amount(1_000_000)],
+        });
+        let faucet: ComponentAddress = result.execution_results[0].decode().unwrap();
+
+        let result = process_and_apply(&processor, &mut state, &secret_key, Instruction::CallFunction {
+            template_address: composability_template,
+            function: "new".to_string(),
+            args: args![state_template],
+        });
+        let composability: ComponentAddress = result.execution_results[0].decode().unwrap();
+
+        // Only the composability component is called by the transaction, so the faucet template is loaded when the
+        // composability component calls the faucet
+        let result = process_and_apply(&processor, &mut state, &secret_key, Instruction::CallMethod {
+            component_address: composability,
+            method: "borrow_coins".to_string(),
+            args: args![faucet],
+        });
+        let amount: Amount = result.execution_results[0].decode().unwrap();
+        assert_eq!(amount, Amount(1000));
+        assert_eq!(result.nested_calls.len(), 2);
+        assert!(result
+            .nested_calls
+            .iter()
+            .all(|call| call.template_address == faucet_template));
+    }
+
+    #[test]
+    fn it_upgrades_a_component_to_a_successor_from_the_template_provider() {
+        let v1_template = TemplateAddress::from_array([1; 32]);
+        let v2_template = TemplateAddress::from_array([2; 32]);
+        let mut template_provider =
+            create_template_provider(&[("upgrade_v1", v1_template), ("upgrade_v2", v2_template)]);
+        template_provider.predecessors.insert(v2_template, v1_template);
+        let processor = TariDanPayloadProcessor::new(template_provider, FeeTable::zero_rated());
+        let mut state = HashMap::new();
+        let (secret_key, public_key) = PublicKey::random_keypair(&mut OsRng);
+        let owner =
+            NonFungibleAddress::from_public_key(RistrettoPublicKeyBytes::from_bytes(public_key.as_bytes()).unwrap());
+
+        let result = process_and_apply(&processor, &mut state, &secret_key, Instruction::CallFunction {
+            template_address: v1_template,
+            function: "new".to_string(),
+            args: args![owner],
+        });
+        let component: ComponentAddress = result.execution_results[0].decode().unwrap();
+        process_and_apply(&processor, &mut state, &secret_key, Instruction::CallMethod {
+            component_address: component,
+            method: "increase".to_string(),
+            args: args![],
+        });
+
+        process_and_apply(&processor, &mut state, &secret_key, Instruction::CallFunction {
+            template_address: v2_template,
+            function: "upgrade".to_string(),
+            args: args![component, v2_template],
+        });
+
+        let result = process_and_apply(&processor, &mut state, &secret_key, Instruction::CallMethod {
+            component_address: component,
+            method: "value".to_string(),
+            args: args![],
+        });
+        let value: u64 = result.execution_results[0].decode().unwrap();
+        assert_eq!(value, 1);
+    }
+
+    #[test]
+    fn it_does_not_give_a_badge_for_a_forged_co_signature() {
+        let v1_template = TemplateAddress::from_array([1; 32]);
+        let v2_template = TemplateAddress::from_array([2; 32]);
+        let mut template_provider =
+            create_template_provider(&[("upgrade_v1", v1_template), ("upgrade_v2", v2_template)]);
+        template_provider.predecessors.insert(v2_template, v1_template);
+        let processor = TariDanPayloadProcessor::new(template_provider, FeeTable::zero_rated());
+        let mut state = HashMap::new();
+        let (owner_secret_key, owner_public_key) = PublicKey::random_keypair(&mut OsRng);
+        let owner = NonFungibleAddress::from_public_key(
+            RistrettoPublicKeyBytes::from_bytes(owner_public_key.as_bytes()).unwrap(),
+        );
+        let result = process_and_apply(&processor, &mut state, &owner_secret_key, Instruction::CallFunction {
+            template_address: v1_template,
+            function: "new".to_string(),
+            args: args![owner],
+        });
+        let component: ComponentAddress = result.execution_results[0].decode().unwrap();
+
+        // The attacker replays a signature that the owner made over a different transaction as a co-signature, to
+        // get the owner's badge for the upgrade
+        let other_transaction = Transaction::builder()
+            .add_instruction(Instruction::CallMethod {
+                component_address: component,
+                method: "value".to_string(),
+                args: args![],
+            })
+            .sign(&owner_secret_key)
+            .clone()
+            .build();
+        let forged_co_signature = TransactionSignature::new(owner_public_key, other_transaction.signature().clone());
+        let (attacker_secret_key, _) = PublicKey::random_keypair(&mut OsRng);
+        let transaction = Transaction::builder()
+            .add_instruction(Instruction::CallFunction {
+                template_address: v2_template,
+                function: "upgrade".to_string(),
+                args: args![component, v2_template],
+            })
+            .sign(&attacker_secret_key)
+            .with_co_signature(forged_co_signature)
+            .clone()
+            .build();
+
+        let result = processor
+            .process_payload(TariDanPayload::new(transaction), state, consensus_context())
+            .unwrap();
+
+        assert!(matches!(
+            result.result.reject(),
+            Some(RejectReason::InvalidSignature(_))
+        ));
+        assert!(result.execution_results.is_empty());
+    }
+}
@@ -194,6 +194,13 @@ pub(crate) fn add_substate_addresses(world: &mut TariWorld, outputs_name: String
                 });
                 counters[5] += 1;
             },
+            SubstateAddress::KeyValueEntry(_) => {
+                outputs.insert(format!("key_value_entries/{}", counters[6]), VersionedSubstateAddress {
+                    address: addr.clone(),
+                    version: data.version(),
+                });
+                counters[6] += 1;
+            },
         }
     }
 }
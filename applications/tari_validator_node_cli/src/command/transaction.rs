@@ -32,7 +32,7 @@ use clap::{Args, Subcommand};
 use tari_common_types::types::FixedHash;
 use tari_dan_common_types::ShardId;
 use tari_engine_types::{
-    commit_result::{FinalizeResult, TransactionResult},
+    commit_result::FinalizeResult,
     execution_result::{ExecutionResult, Type},
     instruction::Instruction,
     substate::{SubstateAddress, SubstateValue},
@@ -44,11 +44,12 @@ use tari_template_lib::{
     models::{Amount, NonFungibleAddress, NonFungibleId},
     prelude::ResourceAddress,
 };
-use tari_transaction::Transaction;
+use tari_transaction::{SubstateChange, Transaction};
 use tari_transaction_manifest::parse_manifest;
 use tari_utilities::hex::to_hex;
 use tari_validator_node_client::{
     types::{
+        DiscoverTransactionSubstatesRequest,
         GetTransactionResultRequest,
         SubmitTransactionRequest,
         SubmitTransactionResponse,
@@ -229,7 +230,8 @@ pub async fn submit_transaction(
         .get_active_key()
         .ok_or_else(|| anyhow::anyhow!("No active key. Use `keys use [public key hex]` to set one."))?;
 
-    let inputs = if common.inputs.is_empty() {
+    let discover_substates = common.inputs.is_empty();
+    let inputs = if discover_substates {
         load_inputs(&instructions, &component_manager)?
     } else {
         common.inputs
@@ -255,9 +257,6 @@ pub async fn submit_transaction(
         .map(|versioned_addr| ShardId::from_address(&versioned_addr.address, versioned_addr.version))
         .collect::<Vec<_>>();
 
-    summarize_request(&instructions, &inputs, &outputs, 1, common.dry_run);
-    println!();
-
     let mut builder = Transaction::builder();
 
     builder
@@ -279,10 +278,33 @@ pub async fn submit_transaction(
                 .map(|i| (i.parent_address, i.index))
                 .collect(),
         )
-        .with_fee(1)
-        .sign(&key.secret_key);
+        .with_fee(1);
+
+    if discover_substates {
+        // The loaded inputs are narrowed down to the substates that the transaction requires by dry-running it
+        let mut unsigned = builder.clone();
+        unsigned.with_sender_public_key(key.public_key.clone());
+        let request = DiscoverTransactionSubstatesRequest {
+            transaction: unsigned.build_unsigned(),
+        };
+        match client.discover_transaction_substates(request).await {
+            Ok(resp) => {
+                builder.with_substate_requirements(&resp.requirements);
+            },
+            Err(err) => {
+                println!(
+                    "⚠️ Unable to discover the substates of the transaction, using the loaded inputs: {}",
+                    err
+                );
+                println!();
+            },
+        }
+    }
+    builder.sign(&key.secret_key);
 
     let transaction = builder.build();
+    summarize_request(&transaction, common.dry_run);
+    println!();
 
     if transaction.meta().involved_shards().is_empty() {
         return Err(anyhow::anyhow!(
@@ -317,23 +339,22 @@ pub async fn submit_transaction(
     Ok(resp)
 }
 
-fn summarize_request(
-    instructions: &[Instruction],
-    inputs: &[ShardId],
-    outputs: &[ShardId],
-    fee: u64,
-    is_dry_run: bool,
-) {
+fn summarize_request(transaction: &Transaction, is_dry_run: bool) {
+    let (outputs, inputs) = transaction
+        .meta()
+        .involved_objects_iter()
+        .partition::<Vec<_>, _>(|(_, (change, _))| matches!(change, SubstateChange::Create));
+
     if is_dry_run {
         println!("NOTE: Dry run is enabled. This transaction will not be processed by the network.");
         println!();
     }
-    println!("Fee: {}", fee);
+    println!("Fee: {}", transaction.fee());
     println!("Inputs:");
     if inputs.is_empty() {
         println!("  None");
     } else {
-        for shard_id in inputs {
+        for (shard_id, _) in inputs {
             println!("- {}", shard_id);
         }
     }
@@ -342,13 +363,13 @@ fn summarize_request(
     if outputs.is_empty() {
         println!("  None");
     } else {
-        for shard_id in outputs {
+        for (shard_id, _) in outputs {
             println!("- {}", shard_id);
         }
     }
     println!();
     println!("🌟 Submitting instructions:");
-    for instruction in instructions {
+    for instruction in transaction.instructions() {
         println!("- {}", instruction);
     }
     println!();
@@ -360,7 +381,10 @@ fn summarize(result: &TransactionFinalizeResult, time_taken: Duration) {
     println!();
     println!("Epoch: {}", result.qc.epoch());
     println!("Payload height: {}", result.qc.payload_height());
-    println!("Signed by: {} validator nodes", result.qc.validators_metadata().len());
+    println!(
+        "Signed by: {} validator nodes",
+        result.qc.aggregated_signature().num_signers()
+    );
     println!();
 
     summarize_finalize_result(&result.finalize);
@@ -380,43 +404,55 @@ fn summarize(result: &TransactionFinalizeResult, time_taken: Duration) {
 #[allow(clippy::too_many_lines)]
 fn summarize_finalize_result(finalize: &FinalizeResult) {
     println!("========= Substates =========");
-    match finalize.result {
-        TransactionResult::Accept(ref diff) => {
-            for (address, substate) in diff.up_iter() {
-                println!("️🌲 UP substate {} (v{})", address, substate.version(),);
-                println!("      🧩 Shard: {}", ShardId::from_address(address, substate.version()));
-                match substate.substate_value() {
-                    SubstateValue::Component(component) => {
-                        println!("      ▶ component ({}): {}", component.module_name, address,);
-                    },
-                    SubstateValue::Resource(_) => {
-                        println!("      ▶ resource: {}", address);
-                    },
-                    SubstateValue::Vault(vault) => {
-                        println!("      ▶ vault: {} {}", address, vault.resource_address());
-                    },
-                    SubstateValue::NonFungible(_) => {
-                        println!("      ▶ NFT: {}", address);
-                    },
-                    SubstateValue::UnclaimedConfidentialOutput(_hash) => {
-                        println!("     ! layer one commitment: Should never happen");
-                    },
-                    SubstateValue::NonFungibleIndex(index) => {
-                        let referenced_address = SubstateAddress::from(index.referenced_address().clone());
-                        println!("      ▶ NFT index {} referencing {}", address, referenced_address);
-                    },
-                }
-                println!();
-            }
-            for (address, version) in diff.down_iter() {
-                println!("🗑️ DOWN substate {} v{}", address, version,);
-                println!("      🧩 Shard: {}", ShardId::from_address(address, *version));
-                println!();
+    if let Some(diff) = finalize.result.accept() {
+        for (address, substate) in diff.up_iter() {
+            println!("️🌲 UP substate {} (v{})", address, substate.version(),);
+            println!("      🧩 Shard: {}", ShardId::from_address(address, substate.version()));
+            match substate.substate_value() {
+                SubstateValue::Component(component) => {
+                    println!("      ▶ component ({}): {}", component.module_name, address,);
+                },
+                SubstateValue::Resource(_) => {
+                    println!("      ▶ resource: {}", address);
+                },
+                SubstateValue::Vault(vault) => {
+                    println!("      ▶ vault: {} {}", address, vault.resource_address());
+                },
+                SubstateValue::NonFungible(_) => {
+                    println!("      ▶ NFT: {}", address);
+                },
+                SubstateValue::UnclaimedConfidentialOutput(_hash) => {
+                    println!("     ! layer one commitment: Should never happen");
+                },
+                SubstateValue::NonFungibleIndex(index) => {
+                    let referenced_address = SubstateAddress::from(index.referenced_address().clone());
+                    println!("      ▶ NFT index {} referencing {}", address, referenced_address);
+                },
+                SubstateValue::KeyValueEntry(_) => {
+                    println!("      ▶ key-value entry: {}", address);
+                },
             }
-        },
-        TransactionResult::Reject(ref reason) => {
-            println!("❌️ Transaction rejected: {}", reason);
-        },
+            println!();
+        }
+        for (address, version) in diff.down_iter() {
+            println!("🗑️ DOWN substate {} v{}", address, version,);
+            println!("      🧩 Shard: {}", ShardId::from_address(address, *version));
+            println!();
+        }
+    }
+    if let Some(reason) = finalize.result.reject() {
+        println!("❌️ Transaction rejected: {}", reason);
+    }
+
+    if let Some(ref fee_receipt) = finalize.fee_receipt {
+        println!("========= Fees =========");
+        for breakdown in &fee_receipt.cost_breakdown {
+            println!("{:?}: {}", breakdown.source, breakdown.amount);
+        }
+        println!("Total charged: {}", fee_receipt.total_fees_charged());
+        println!("Total paid: {}", fee_receipt.total_fees_paid);
+        println!("Refunded: {}", fee_receipt.total_refunded());
+        println!();
     }
 
     println!("========= Return Values =========");
@@ -480,6 +516,12 @@ fn summarize_finalize_result(finalize: &FinalizeResult) {
         }
     }
 
+    println!();
+    println!("========= EVENTS =========");
+    for event in &finalize.events {
+        println!("{} {:?}", event, event.payload);
+    }
+
     println!();
     println!("========= LOGS =========");
     for log in &finalize.logs {
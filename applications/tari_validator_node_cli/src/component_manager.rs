@@ -88,7 +88,8 @@ impl ComponentManager {
                 addr @ SubstateAddress::Resource(_) |
                 addr @ SubstateAddress::Vault(_) |
                 addr @ SubstateAddress::NonFungible(_) |
-                addr @ SubstateAddress::NonFungibleIndex(_) => {
+                addr @ SubstateAddress::NonFungibleIndex(_) |
+                addr @ SubstateAddress::KeyValueEntry(_) => {
                     children.push(VersionedSubstateAddress {
                         address: addr.clone(),
                         version: substate.version(),
@@ -127,7 +127,7 @@ const [payloadId, substates, outputs, current_leader_states] = useLoaderData() a
       } ${
         justify.local_node_height === 0
           ? ''
-          : ' w ' + justify.validators_metadata.length + ' votes'
+          : ' w ' + justify.aggregated_signature.public_nonces.length + ' votes'
       }] ${mapHeight(node.height)}  :done, s${shardNo}h${node.height}, ${
         node.timestamp
       } , 1s`;
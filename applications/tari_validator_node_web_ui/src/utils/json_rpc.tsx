@@ -62,6 +62,15 @@ async function getCommsStats() {
 async function getMempoolStats() {
   return await jsonRpc('get_mempool_stats');
 }
+async function getPruningStats() {
+  return await jsonRpc('get_pruning_stats');
+}
+async function getStateSyncProgress() {
+  return await jsonRpc('get_state_sync_progress');
+}
+async function getSlashingEvidence(offender?: string) {
+  return await jsonRpc('get_slashing_evidence', { offender });
+}
 async function getShardKey(height: number, public_key: string) {
   return await jsonRpc('get_shard_key', [height, public_key]);
 }
@@ -113,6 +122,9 @@ export {
   getEpochManagerStats,
   getIdentity,
   getMempoolStats,
+  getPruningStats,
+  getStateSyncProgress,
+  getSlashingEvidence,
   getRecentTransactions,
   getShardKey,
   getTemplate,
@@ -3,6 +3,8 @@
 
 use tari_dan_common_types::optional::IsNotFoundError;
 
+use crate::types::TransactionRejectReason;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ValidatorNodeClientError {
     #[error("Failed to deserialize response for method {method}: {source}")]
@@ -18,6 +20,8 @@ pub enum ValidatorNodeClientError {
     RequestFailedWithStatus { code: i64, message: String },
     #[error("Invalid response: {message}")]
     InvalidResponse { message: String },
+    #[error("Transaction rejected: {reason}")]
+    TransactionRejected { reason: TransactionRejectReason },
 }
 
 impl IsNotFoundError for ValidatorNodeClientError {
@@ -32,10 +32,17 @@ use tari_comms_logging::LoggedMessage;
 use crate::types::{
     AddPeerRequest,
     AddPeerResponse,
+    DiscoverTransactionSubstatesRequest,
+    DiscoverTransactionSubstatesResponse,
     GetEpochManagerStatsResponse,
     GetIdentityResponse,
+    GetMempoolStatsResponse,
     GetRecentTransactionsRequest,
     GetRecentTransactionsResponse,
+    GetSlashingEvidenceRequest,
+    GetSlashingEvidenceResponse,
+    GetSlashingEvidenceSubmissionRequest,
+    GetSlashingEvidenceSubmissionResponse,
     GetStateRequest,
     GetStateResponse,
     GetSubstateRequest,
@@ -52,6 +59,7 @@ use crate::types::{
     SubmitTransactionResponse,
     TemplateRegistrationRequest,
     TemplateRegistrationResponse,
+    TRANSACTION_REJECTED_ERROR_CODE,
 };
 
 #[derive(Debug, Clone)]
@@ -87,6 +95,10 @@ impl ValidatorNodeClient {
         self.send_request("get_epoch_manager_stats", json!({})).await
     }
 
+    pub async fn get_mempool_stats(&mut self) -> Result<GetMempoolStatsResponse, ValidatorNodeClientError> {
+        self.send_request("get_mempool_stats", json!({})).await
+    }
+
     pub async fn register_validator_node(&mut self) -> Result<u64, ValidatorNodeClientError> {
         let val: json::Value = self.send_request("register_validator_node", json!({})).await?;
         let tx_id = val["transaction_id"]
@@ -115,6 +127,13 @@ impl ValidatorNodeClient {
         self.send_request("get_state", request).await
     }
 
+    pub async fn discover_transaction_substates(
+        &mut self,
+        request: DiscoverTransactionSubstatesRequest,
+    ) -> Result<DiscoverTransactionSubstatesResponse, ValidatorNodeClientError> {
+        self.send_request("discover_transaction_substates", request).await
+    }
+
     pub async fn get_substate(
         &mut self,
         request: GetSubstateRequest,
@@ -165,6 +184,20 @@ impl ValidatorNodeClient {
         self.send_request("submit_transaction", request).await
     }
 
+    pub async fn get_slashing_evidence(
+        &mut self,
+        request: GetSlashingEvidenceRequest,
+    ) -> Result<GetSlashingEvidenceResponse, ValidatorNodeClientError> {
+        self.send_request("get_slashing_evidence", request).await
+    }
+
+    pub async fn get_slashing_evidence_submission(
+        &mut self,
+        request: GetSlashingEvidenceSubmissionRequest,
+    ) -> Result<GetSlashingEvidenceSubmissionResponse, ValidatorNodeClientError> {
+        self.send_request("get_slashing_evidence_submission", request).await
+    }
+
     pub async fn add_peer(&mut self, request: AddPeerRequest) -> Result<AddPeerResponse, ValidatorNodeClientError> {
         self.send_request("add_peer", request).await
     }
@@ -232,6 +265,11 @@ impl ValidatorNodeClient {
 fn jsonrpc_result(val: json::Value) -> Result<json::Value, ValidatorNodeClientError> {
     if let Some(err) = val.get("error") {
         let code = err.get("code").and_then(|c| c.as_i64()).unwrap_or(-1);
+        if code == i64::from(TRANSACTION_REJECTED_ERROR_CODE) {
+            if let Some(reason) = err.get("data").and_then(|d| json::from_value(d.clone()).ok()) {
+                return Err(ValidatorNodeClientError::TransactionRejected { reason });
+            }
+        }
         let message = err.get("message").and_then(|m| m.as_str()).unwrap_or("Unknown error");
         return Err(ValidatorNodeClientError::RequestFailedWithStatus {
             code,
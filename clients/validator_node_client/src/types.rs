@@ -27,15 +27,16 @@ use tari_dan_common_types::{
     quorum_certificate::{QuorumCertificate, QuorumDecision},
     serde_with,
     Epoch,
+    PayloadId,
     ShardId,
 };
-use tari_dan_core::models::RecentTransaction;
+use tari_dan_core::models::{RecentTransaction, SlashingEvidence, SlashingEvidenceSubmission, TariDanPayload};
 use tari_engine_types::{
     commit_result::FinalizeResult,
     substate::{SubstateAddress, SubstateValue},
     TemplateAddress,
 };
-use tari_transaction::Transaction;
+use tari_transaction::{SubstateRequirements, Transaction};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetIdentityResponse {
@@ -107,6 +108,9 @@ pub struct TemplateMetadata {
     pub binary_sha: Vec<u8>,
     /// Block height in which the template was published
     pub height: u64,
+    pub version: u16,
+    /// The address of the previous version of this template, if any
+    pub predecessor: Option<TemplateAddress>,
 }
 
 /// A request to submit a transaction
@@ -147,6 +151,55 @@ pub struct SubmitTransactionResponse {
     pub result: Option<TransactionFinalizeResult>,
 }
 
+/// The JSON-RPC application error code returned when a submitted transaction is not admitted to the mempool. The
+/// error data contains the [`TransactionRejectReason`].
+pub const TRANSACTION_REJECTED_ERROR_CODE: i32 = 400;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, thiserror::Error)]
+pub enum TransactionRejectReason {
+    #[error("Transaction is already in the mempool")]
+    AlreadyInMempool,
+    #[error("Transaction size {size} exceeds the maximum of {max_size} bytes")]
+    TooLarge { size: usize, max_size: usize },
+    #[error("Transaction signature is invalid")]
+    InvalidSignature,
+    #[error("Template {template_address} not found")]
+    TemplateNotFound { template_address: TemplateAddress },
+    #[error("Input {shard} does not exist")]
+    InputNotFound { shard: ShardId },
+    #[error("Input {shard} has been destroyed")]
+    InputDestroyed { shard: ShardId },
+    #[error("Input {shard} is pledged to payload {payload_id}")]
+    InputPledged { shard: ShardId, payload_id: PayloadId },
+    #[error("Dry run failed: {reason}")]
+    DryRunFailed { reason: String },
+    #[error("Dry run did not complete within {timeout_ms}ms")]
+    DryRunTimedOut { timeout_ms: u64 },
+    #[error("Mempool is full")]
+    MempoolFull,
+    #[error("Transaction is not valid before epoch {min_epoch} (current epoch {current_epoch})")]
+    NotYetValid { min_epoch: Epoch, current_epoch: Epoch },
+    #[error("Transaction expired at epoch {expires_at_epoch} (current epoch {current_epoch})")]
+    Expired {
+        expires_at_epoch: Epoch,
+        current_epoch: Epoch,
+    },
+}
+
+/// Requests the substates that a transaction requires. The transaction does not have to be signed (see
+/// `TransactionBuilder::build_unsigned`), but its involved shards must include every input that it could read.
+/// Discovery only narrows the involved shards down to the substates that the transaction uses: substates outside of
+/// them are not pledged, so they cannot be discovered and the dry run fails if the transaction needs them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoverTransactionSubstatesRequest {
+    pub transaction: Transaction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoverTransactionSubstatesResponse {
+    pub requirements: SubstateRequirements,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionFinalizeResult {
     // TODO: we should not return the whole state but only the addresses and perhaps a hash of the state
@@ -262,9 +315,52 @@ pub struct AddPeerRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AddPeerResponse {}
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetMempoolStatsResponse {
+    pub size: usize,
+    pub max_size: usize,
+    /// The number of transactions in the mempool per involved shard, deepest queue first
+    pub shard_queue_depths: Vec<ShardQueueDepth>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardQueueDepth {
+    pub shard_id: ShardId,
+    pub depth: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GetEpochManagerStatsResponse {
     pub current_epoch: Epoch,
     pub current_block_height: u64,
     pub is_valid: bool,
 }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GetSlashingEvidenceRequest {
+    /// Only return the evidence against this validator node
+    pub offender: Option<PublicKey>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetSlashingEvidenceResponse {
+    pub evidence: Vec<SlashingEvidenceInfo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashingEvidenceInfo {
+    #[serde(with = "serde_with::hex")]
+    pub hash: FixedHash,
+    pub evidence: SlashingEvidence<PublicKey, TariDanPayload>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetSlashingEvidenceSubmissionRequest {
+    #[serde(with = "serde_with::hex")]
+    pub evidence_hash: FixedHash,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetSlashingEvidenceSubmissionResponse {
+    pub submission: SlashingEvidenceSubmission,
+}
@@ -60,10 +60,16 @@ use crate::{
         KeysListResponse,
         KeysSetActiveRequest,
         KeysSetActiveResponse,
+        TransactionCoSignRequest,
+        TransactionCoSignResponse,
+        TransactionExportRequest,
+        TransactionExportResponse,
         TransactionGetRequest,
         TransactionGetResponse,
         TransactionGetResultRequest,
         TransactionGetResultResponse,
+        TransactionImportRequest,
+        TransactionImportResponse,
         TransactionSubmitRequest,
         TransactionSubmitResponse,
         TransactionWaitResultRequest,
@@ -154,6 +160,27 @@ impl WalletDaemonClient {
         self.send_request("transactions.submit", request.borrow()).await
     }
 
+    pub async fn export_transaction<T: Borrow<TransactionExportRequest>>(
+        &mut self,
+        request: T,
+    ) -> Result<TransactionExportResponse, WalletDaemonClientError> {
+        self.send_request("transactions.export", request.borrow()).await
+    }
+
+    pub async fn co_sign_transaction<T: Borrow<TransactionCoSignRequest>>(
+        &mut self,
+        request: T,
+    ) -> Result<TransactionCoSignResponse, WalletDaemonClientError> {
+        self.send_request("transactions.co_sign", request.borrow()).await
+    }
+
+    pub async fn import_transaction<T: Borrow<TransactionImportRequest>>(
+        &mut self,
+        request: T,
+    ) -> Result<TransactionImportResponse, WalletDaemonClientError> {
+        self.send_request("transactions.import", request.borrow()).await
+    }
+
     pub async fn create_account<T: Borrow<AccountsCreateRequest>>(
         &mut self,
         request: T,
@@ -22,7 +22,7 @@
 
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::{FixedHash, PublicKey};
-use tari_dan_common_types::{serde_with, QuorumCertificate, ShardId};
+use tari_dan_common_types::{serde_with, Epoch, QuorumCertificate, ShardId};
 use tari_dan_wallet_sdk::models::{Account, ConfidentialProofId, TransactionStatus, VersionedSubstateAddress};
 use tari_engine_types::{
     commit_result::FinalizeResult,
@@ -36,7 +36,7 @@ use tari_template_lib::{
     models::{Amount, ComponentAddress, ConfidentialOutputProof, NonFungibleId, ResourceAddress},
     prelude::{ConfidentialWithdrawProof, ResourceType},
 };
-use tari_transaction::Transaction;
+use tari_transaction::{Transaction, TransactionBuilder};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TransactionSubmitRequest {
@@ -51,6 +51,10 @@ pub struct TransactionSubmitRequest {
     pub new_non_fungible_index_outputs: Vec<(ResourceAddress, u64)>,
     pub is_dry_run: bool,
     pub proof_id: Option<ConfidentialProofId>,
+    #[serde(default)]
+    pub min_epoch: Option<Epoch>,
+    #[serde(default)]
+    pub expires_at_epoch: Option<Epoch>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -61,6 +65,57 @@ pub struct TransactionSubmitResponse {
     pub outputs: Vec<ShardId>,
 }
 
+/// Builds a transaction that is signed by the wallet as the sender and returns it without submitting it, so that it
+/// can be co-signed by other signers
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TransactionExportRequest {
+    pub signing_key_index: Option<u64>,
+    pub instructions: Vec<Instruction>,
+    pub fee: u64,
+    pub inputs: Vec<VersionedSubstateAddress>,
+    pub override_inputs: bool,
+    pub new_outputs: u8,
+    pub specific_non_fungible_outputs: Vec<(ResourceAddress, NonFungibleId)>,
+    pub new_non_fungible_outputs: Vec<(ResourceAddress, u8)>,
+    pub new_non_fungible_index_outputs: Vec<(ResourceAddress, u64)>,
+    #[serde(default)]
+    pub min_epoch: Option<Epoch>,
+    #[serde(default)]
+    pub expires_at_epoch: Option<Epoch>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TransactionExportResponse {
+    pub partial_transaction: TransactionBuilder,
+}
+
+/// Co-signs a partially signed transaction with the wallet key and returns it without submitting it, so that it can
+/// be co-signed by further signers
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TransactionCoSignRequest {
+    pub signing_key_index: Option<u64>,
+    pub partial_transaction: TransactionBuilder,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TransactionCoSignResponse {
+    pub partial_transaction: TransactionBuilder,
+}
+
+/// Co-signs a partially signed transaction with the wallet key and submits it
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TransactionImportRequest {
+    pub signing_key_index: Option<u64>,
+    pub partial_transaction: TransactionBuilder,
+    pub is_dry_run: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TransactionImportResponse {
+    #[serde(with = "serde_with::hex")]
+    pub hash: FixedHash,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct TransactionGetRequest {
     #[serde(with = "serde_with::hex")]
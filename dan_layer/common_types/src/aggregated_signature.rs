@@ -0,0 +1,104 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use borsh::BorshSerialize;
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{PrivateKey, PublicKey};
+
+use crate::serde_with;
+
+/// A bitmap over the leaves of an epoch's validator node BMT. Bit `i` is set if the validator at leaf index `i` signed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize, BorshSerialize)]
+#[serde(transparent)]
+pub struct SignerBitmap {
+    #[serde(with = "serde_with::hex")]
+    bits: Vec<u8>,
+}
+
+impl SignerBitmap {
+    pub fn from_bytes(bits: Vec<u8>) -> Self {
+        Self { bits }
+    }
+
+    pub fn from_indices<I: IntoIterator<Item = u64>>(indices: I) -> Self {
+        let mut bits = Vec::new();
+        for index in indices {
+            let byte = (index / 8) as usize;
+            if bits.len() <= byte {
+                bits.resize(byte + 1, 0u8);
+            }
+            bits[byte] |= 1 << (index % 8);
+        }
+        Self { bits }
+    }
+
+    pub fn contains(&self, index: u64) -> bool {
+        self.bits
+            .get((index / 8) as usize)
+            .map(|b| b & (1 << (index % 8)) != 0)
+            .unwrap_or(false)
+    }
+
+    /// Returns the set leaf indices in ascending order
+    pub fn indices(&self) -> impl Iterator<Item = u64> + '_ {
+        self.bits.iter().enumerate().flat_map(|(byte, b)| {
+            (0..8u64)
+                .filter(move |bit| b & (1 << bit) != 0)
+                .map(move |bit| byte as u64 * 8 + bit)
+        })
+    }
+
+    pub fn count(&self) -> usize {
+        self.bits.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bits
+    }
+}
+
+/// The combined signature of the validators that voted for a QC.
+///
+/// Each vote is an independent Schnorr signature, so the partial signatures are half-aggregated: the scalars are
+/// combined into one, weighted by per-signer coefficients, while the public nonces are kept. This replaces a signature,
+/// public key and merkle proof per signer with a nonce per signer and the signer bitmap. Signer public keys are
+/// resolved from the bitmap using the epoch's validator node list, which is committed to by the BMT root.
+///
+/// The aggregate is not constant size: it still grows by one nonce per signer. Votes are signed independently, so a
+/// single shared nonce (MuSig) would need an extra round in which the leader collects nonces before the committee
+/// signs.
+// TODO: replace with a constant-size aggregate (BLS, or MuSig2 with nonces exchanged in the preceding vote) so that a
+//       QC carries one signature and the signer bitmap.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, BorshSerialize)]
+pub struct AggregatedSignature {
+    signers: SignerBitmap,
+    /// The public nonces of each signer, ordered by leaf index
+    public_nonces: Vec<PublicKey>,
+    signature: PrivateKey,
+}
+
+impl AggregatedSignature {
+    pub fn new(signers: SignerBitmap, public_nonces: Vec<PublicKey>, signature: PrivateKey) -> Self {
+        Self {
+            signers,
+            public_nonces,
+            signature,
+        }
+    }
+
+    pub fn signers(&self) -> &SignerBitmap {
+        &self.signers
+    }
+
+    pub fn num_signers(&self) -> usize {
+        self.signers.count()
+    }
+
+    pub fn public_nonces(&self) -> &[PublicKey] {
+        &self.public_nonces
+    }
+
+    pub fn signature(&self) -> &PrivateKey {
+        &self.signature
+    }
+}
@@ -12,6 +12,9 @@ use tari_common_types::types::{FixedHash, FixedHashSizeError};
 use tari_engine_types::substate::{Substate, SubstateAddress};
 use tari_utilities::hex::Hex;
 
+mod aggregated_signature;
+pub use aggregated_signature::{AggregatedSignature, SignerBitmap};
+
 pub mod crypto;
 pub mod proto;
 
@@ -10,7 +10,7 @@ use tari_common_types::types::FixedHash;
 use tari_crypto::hash::blake2::Blake256;
 use tari_engine_types::commit_result::RejectReason;
 
-use crate::{Epoch, NodeHeight, PayloadId, ShardId, ShardPledgeCollection, TreeNodeHash, ValidatorMetadata};
+use crate::{AggregatedSignature, Epoch, NodeHeight, PayloadId, ShardId, ShardPledgeCollection, TreeNodeHash};
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, BorshSerialize)]
 pub enum QuorumDecision {
@@ -31,6 +31,9 @@ pub enum QuorumRejectReason {
     PreviousQcRejection,
     ShardPledgedToAnotherPayload,
     ShardRejected,
+    FeesNotPaid,
+    InvalidEpoch,
+    InvalidSignature,
 }
 
 impl QuorumRejectReason {
@@ -41,6 +44,9 @@ impl QuorumRejectReason {
             QuorumRejectReason::PreviousQcRejection => 3,
             QuorumRejectReason::ShardPledgedToAnotherPayload => 4,
             QuorumRejectReason::ShardRejected => 5,
+            QuorumRejectReason::FeesNotPaid => 6,
+            QuorumRejectReason::InvalidEpoch => 7,
+            QuorumRejectReason::InvalidSignature => 8,
         }
     }
 }
@@ -61,6 +67,9 @@ impl QuorumDecision {
             3 => Ok(QuorumDecision::Reject(QuorumRejectReason::PreviousQcRejection)),
             4 => Ok(QuorumDecision::Reject(QuorumRejectReason::ShardPledgedToAnotherPayload)),
             5 => Ok(QuorumDecision::Reject(QuorumRejectReason::ShardRejected)),
+            6 => Ok(QuorumDecision::Reject(QuorumRejectReason::FeesNotPaid)),
+            7 => Ok(QuorumDecision::Reject(QuorumRejectReason::InvalidEpoch)),
+            8 => Ok(QuorumDecision::Reject(QuorumRejectReason::InvalidSignature)),
             // TODO: Add error type
             _ => Err(anyhow::anyhow!("Invalid QuorumDecision")),
         }
@@ -75,6 +84,9 @@ impl<T: Borrow<RejectReason>> From<T> for QuorumRejectReason {
             RejectReason::PreviousQcRejection => QuorumRejectReason::PreviousQcRejection,
             RejectReason::ShardPledgedToAnotherPayload(_) => QuorumRejectReason::ShardPledgedToAnotherPayload,
             RejectReason::ShardRejected(_) => QuorumRejectReason::ShardRejected,
+            RejectReason::FeesNotPaid(_) => QuorumRejectReason::FeesNotPaid,
+            RejectReason::InvalidEpoch(_) => QuorumRejectReason::InvalidEpoch,
+            RejectReason::InvalidSignature(_) => QuorumRejectReason::InvalidSignature,
         }
     }
 }
@@ -91,7 +103,7 @@ pub struct QuorumCertificate {
     epoch: Epoch,
     decision: QuorumDecision,
     all_shard_pledges: ShardPledgeCollection,
-    validators_metadata: Vec<ValidatorMetadata>,
+    aggregated_signature: AggregatedSignature,
 }
 
 impl QuorumCertificate {
@@ -112,7 +124,7 @@ impl QuorumCertificate {
         epoch: Epoch,
         decision: QuorumDecision,
         all_shard_pledges: ShardPledgeCollection,
-        validators_metadata: Vec<ValidatorMetadata>,
+        aggregated_signature: AggregatedSignature,
     ) -> Self {
         Self {
             payload_id: payload,
@@ -123,7 +135,7 @@ impl QuorumCertificate {
             epoch,
             decision,
             all_shard_pledges,
-            validators_metadata,
+            aggregated_signature,
         }
     }
 
@@ -137,7 +149,7 @@ impl QuorumCertificate {
             epoch,
             decision: QuorumDecision::Accept,
             all_shard_pledges: ShardPledgeCollection::empty(),
-            validators_metadata: vec![],
+            aggregated_signature: AggregatedSignature::default(),
         }
     }
 
@@ -153,8 +165,8 @@ impl QuorumCertificate {
         self.epoch
     }
 
-    pub fn validators_metadata(&self) -> &[ValidatorMetadata] {
-        self.validators_metadata.as_slice()
+    pub fn aggregated_signature(&self) -> &AggregatedSignature {
+        &self.aggregated_signature
     }
 
     pub fn set_payload_id(&mut self, payload_id: PayloadId) {
@@ -162,16 +174,13 @@ impl QuorumCertificate {
     }
 
     pub fn to_hash(&self) -> FixedHash {
-        let mut result = Blake256::new()
+        let result = Blake256::new()
             .chain(self.local_node_hash.as_bytes())
             .chain(self.local_node_height.to_le_bytes())
             .chain(self.shard.as_bytes())
-            .chain((self.validators_metadata.len() as u64).to_le_bytes());
+            .chain(self.aggregated_signature.signers().as_bytes());
         // TODO: add all fields
 
-        for vm in &self.validators_metadata {
-            result = result.chain(vm.encode_merkle_proof());
-        }
         // result = result.chain((self.involved_shards.len() as u32).to_le_bytes());
         // for shard in &self.involved_shards {
         //     result = result.chain((*shard).to_le_bytes());
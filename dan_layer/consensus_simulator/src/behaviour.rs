@@ -0,0 +1,16 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+/// The way a Byzantine node deviates from the protocol. The node runs an honest [HotStuffWaiter], and the
+/// behaviour is applied to the messages it sends.
+///
+/// [HotStuffWaiter]: tari_dan_core::workers::hotstuff_waiter::HotStuffWaiter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByzantineBehaviour {
+    /// The node does not send any messages
+    Silent,
+    /// When the node proposes, half of the recipients receive a conflicting proposal for the same height
+    Equivocate,
+    /// The node signs its votes over pledges to a payload that does not exist
+    InvalidPledges,
+}
@@ -0,0 +1,45 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::time::Duration;
+
+use tari_dan_core::consensus_constants::ConsensusConstants;
+
+use crate::{behaviour::ByzantineBehaviour, network::NetworkRule};
+
+#[derive(Debug, Clone)]
+pub struct SimulatorConfig {
+    /// Seeds the key generation and every random choice made by the simulated network
+    pub seed: u64,
+    /// The amount of virtual time that passes in a step, used to convert pacemaker timeouts into steps
+    pub step_duration: Duration,
+    pub consensus_constants: ConsensusConstants,
+    pub network_rules: Vec<NetworkRule>,
+    /// Nodes that deviate from the protocol, by node index
+    pub byzantine_nodes: Vec<(usize, ByzantineBehaviour)>,
+}
+
+impl SimulatorConfig {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            step_duration: Duration::from_millis(100),
+            consensus_constants: ConsensusConstants {
+                pacemaker_timeout: Duration::from_secs(2),
+                ..ConsensusConstants::devnet()
+            },
+            network_rules: Vec::new(),
+            byzantine_nodes: Vec::new(),
+        }
+    }
+
+    pub fn with_network_rule(mut self, rule: NetworkRule) -> Self {
+        self.network_rules.push(rule);
+        self
+    }
+
+    pub fn with_byzantine_node(mut self, node: usize, behaviour: ByzantineBehaviour) -> Self {
+        self.byzantine_nodes.push((node, behaviour));
+        self
+    }
+}
@@ -0,0 +1,26 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_dan_common_types::PayloadId;
+use tari_dan_core::{services::epoch_manager::EpochManagerError, workers::hotstuff_error::HotStuffError};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SimulatorError {
+    #[error("Invariant '{invariant}' violated at step {step}: {details}")]
+    InvariantViolated {
+        invariant: &'static str,
+        step: u64,
+        details: String,
+    },
+    #[error("Step limit of {max_steps} reached before the simulation completed")]
+    StepLimitReached { max_steps: u64 },
+    #[error("Payload {payload_id} does not involve any shard of the simulated committees")]
+    PayloadNotInvolved { payload_id: PayloadId },
+    #[error("Node {node} does not exist in the simulation")]
+    UnknownNode { node: usize },
+    #[error("HotStuff error: {0}")]
+    HotStuffError(#[from] HotStuffError),
+    #[error("Epoch manager error: {0}")]
+    EpochManagerError(#[from] EpochManagerError),
+}
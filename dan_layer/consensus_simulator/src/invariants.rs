@@ -0,0 +1,111 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::HashMap;
+
+use tari_dan_common_types::{PayloadId, ShardId, TreeNodeHash};
+use tari_dan_core::workers::events::HotStuffEvent;
+
+use crate::trace::{ObservedEvent, Outcome};
+
+/// A property that must hold for the honest nodes of a simulation. Invariants are checked after every step with the
+/// events that honest nodes published during that step.
+pub trait Invariant {
+    fn name(&self) -> &'static str;
+
+    /// Checks the invariant, returning a description of the violation if it does not hold
+    fn check(&mut self, events: &[ObservedEvent]) -> Result<(), String>;
+}
+
+/// Honest nodes never commit different nodes for the same payload and shard
+#[derive(Debug, Default)]
+pub struct NoConflictingCommits {
+    commits: HashMap<(PayloadId, ShardId), (usize, TreeNodeHash)>,
+}
+
+impl Invariant for NoConflictingCommits {
+    fn name(&self) -> &'static str {
+        "no conflicting commits"
+    }
+
+    fn check(&mut self, events: &[ObservedEvent]) -> Result<(), String> {
+        for observed in events {
+            if let HotStuffEvent::OnFinalized(qc, _) = &observed.event {
+                let key = (qc.payload_id(), qc.shard());
+                match self.commits.get(&key) {
+                    Some((node, node_hash)) if *node_hash != qc.node_hash() => {
+                        return Err(format!(
+                            "node {} committed {} for payload {} on shard {}, but node {} committed {}",
+                            observed.node,
+                            qc.node_hash(),
+                            key.0,
+                            key.1,
+                            node,
+                            node_hash
+                        ));
+                    },
+                    Some(_) => {},
+                    None => {
+                        self.commits.insert(key, (observed.node, qc.node_hash()));
+                    },
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Honest nodes agree on whether a payload was committed, and if so, whether it was accepted
+#[derive(Debug, Default)]
+pub struct Agreement {
+    decisions: HashMap<PayloadId, (usize, Option<bool>)>,
+}
+
+impl Invariant for Agreement {
+    fn name(&self) -> &'static str {
+        "agreement"
+    }
+
+    fn check(&mut self, events: &[ObservedEvent]) -> Result<(), String> {
+        for observed in events {
+            let payload_id = observed.payload_id();
+            let decision = match observed.outcome() {
+                Outcome::Finalized { accepted, .. } => Some(accepted),
+                Outcome::Failed => None,
+            };
+            match self.decisions.get(&payload_id) {
+                Some((node, existing)) if *existing != decision => {
+                    return Err(format!(
+                        "node {} decided {} for payload {}, but node {} decided {}",
+                        observed.node,
+                        describe(decision),
+                        payload_id,
+                        node,
+                        describe(*existing)
+                    ));
+                },
+                Some(_) => {},
+                None => {
+                    self.decisions.insert(payload_id, (observed.node, decision));
+                },
+            }
+        }
+        Ok(())
+    }
+}
+
+fn describe(decision: Option<bool>) -> &'static str {
+    match decision {
+        Some(true) => "accept",
+        Some(false) => "reject",
+        None => "failed",
+    }
+}
+
+/// The invariants that every simulation checks unless others are given
+pub fn default_invariants() -> Vec<Box<dyn Invariant + Send>> {
+    vec![
+        Box::new(NoConflictingCommits::default()),
+        Box::new(Agreement::default()),
+    ]
+}
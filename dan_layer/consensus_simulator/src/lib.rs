@@ -0,0 +1,30 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! A deterministic simulator for HotStuff consensus. The simulator steps a [HotStuffWaiter] for every member of
+//! a number of committees and routes their messages through a simulated network, which can delay, drop, reorder and
+//! partition messages. All randomness comes from a single seed and pacemaker timers run on virtual time, so a
+//! failing run can be replayed exactly.
+//!
+//! [HotStuffWaiter]: tari_dan_core::workers::hotstuff_waiter::HotStuffWaiter
+
+#![allow(clippy::too_many_arguments)]
+
+mod behaviour;
+mod config;
+mod error;
+mod invariants;
+mod message;
+mod network;
+mod node;
+mod simulator;
+mod trace;
+
+pub use behaviour::ByzantineBehaviour;
+pub use config::SimulatorConfig;
+pub use error::SimulatorError;
+pub use invariants::{default_invariants, Agreement, Invariant, NoConflictingCommits};
+pub use message::SimMessage;
+pub use network::NetworkRule;
+pub use simulator::Simulator;
+pub use trace::{DropReason, MessageKind, ObservedEvent, Outcome, TraceEvent};
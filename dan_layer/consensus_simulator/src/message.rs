@@ -0,0 +1,37 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_common_types::types::PublicKey;
+use tari_dan_core::{
+    models::{vote_message::VoteMessage, HotStuffMessage, TariDanPayload},
+    workers::hotstuff_waiter::{HotStuffInput, RecoveryMessage},
+};
+
+/// A message sent from one simulated node to another
+#[derive(Debug, Clone)]
+pub enum SimMessage {
+    HotStuff(HotStuffMessage<TariDanPayload, PublicKey>),
+    Vote(VoteMessage),
+    Recovery(RecoveryMessage),
+}
+
+impl SimMessage {
+    pub(crate) fn into_input(self, from: PublicKey) -> HotStuffInput<TariDanPayload, PublicKey> {
+        match self {
+            SimMessage::HotStuff(msg) => HotStuffInput::HotStuffMessage(from, msg),
+            SimMessage::Vote(msg) => HotStuffInput::Vote(from, msg),
+            SimMessage::Recovery(msg) => HotStuffInput::RecoveryMessage(from, msg),
+        }
+    }
+}
+
+/// A message in flight between two nodes. Messages that are due in the same step are delivered in the order they
+/// were sent, unless the network reorders them.
+#[derive(Debug, Clone)]
+pub(crate) struct Envelope {
+    pub from: usize,
+    pub to: usize,
+    /// The step at or after which the message may be delivered
+    pub deliver_at: u64,
+    pub message: SimMessage,
+}
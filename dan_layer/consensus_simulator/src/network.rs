@@ -0,0 +1,71 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::ops::Range;
+
+use rand::{rngs::StdRng, Rng};
+
+/// A rule applied by the simulated network to messages sent between two different nodes. Messages that a node sends
+/// to itself are always delivered in the next step.
+#[derive(Debug, Clone)]
+pub enum NetworkRule {
+    /// Delays each message by a number of additional steps, chosen uniformly from `min..=max`
+    Delay { min: u64, max: u64 },
+    /// Drops each message with the given probability
+    Drop { probability: f64 },
+    /// Delivers the messages that are ready in a step in a random order, instead of the order they were sent in
+    Reorder,
+    /// Drops messages sent during `steps` between nodes in different groups. Nodes that are not in any group are not
+    /// affected by the partition.
+    Partition { groups: Vec<Vec<usize>>, steps: Range<u64> },
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Network {
+    rules: Vec<NetworkRule>,
+}
+
+impl Network {
+    pub fn new(rules: Vec<NetworkRule>) -> Self {
+        Self { rules }
+    }
+
+    pub fn is_reordering(&self) -> bool {
+        self.rules.iter().any(|rule| matches!(rule, NetworkRule::Reorder))
+    }
+
+    pub fn is_partitioned(&self, step: u64, from: usize, to: usize) -> bool {
+        self.rules.iter().any(|rule| match rule {
+            NetworkRule::Partition { groups, steps } if steps.contains(&step) => {
+                let from_group = groups.iter().position(|g| g.contains(&from));
+                let to_group = groups.iter().position(|g| g.contains(&to));
+                matches!((from_group, to_group), (Some(a), Some(b)) if a != b)
+            },
+            _ => false,
+        })
+    }
+
+    /// Rolls the drop rules for a message. The rng is advanced once per drop rule, whether or not the message is
+    /// dropped, so that a run is replayable.
+    pub fn should_drop(&self, rng: &mut StdRng) -> bool {
+        let mut dropped = false;
+        for rule in &self.rules {
+            if let NetworkRule::Drop { probability } = rule {
+                dropped |= rng.gen_bool(probability.clamp(0.0, 1.0));
+            }
+        }
+        dropped
+    }
+
+    /// Returns the number of additional steps a message is delayed by
+    pub fn delay(&self, rng: &mut StdRng) -> u64 {
+        self.rules
+            .iter()
+            .map(|rule| match rule {
+                NetworkRule::Delay { min, max } if max > min => rng.gen_range(*min, *max + 1),
+                NetworkRule::Delay { min, .. } => *min,
+                _ => 0,
+            })
+            .sum()
+    }
+}
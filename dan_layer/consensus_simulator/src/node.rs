@@ -0,0 +1,237 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{convert::TryFrom, sync::Arc, time::Duration};
+
+use tari_common_types::types::{PrivateKey, PublicKey};
+use tari_comms::{multiaddr::Multiaddr, peer_manager::PeerFeatures, NodeIdentity};
+use tari_dan_core::{
+    consensus_constants::ConsensusConstants,
+    models::{vote_message::VoteMessage, HotStuffMessage, TariDanPayload},
+    services::{
+        epoch_manager::RangeEpochManager,
+        leader_strategy::LeaderStrategy,
+        NodeIdentitySigningService,
+        PayloadProcessor,
+    },
+    storage::shard_store::ShardStore,
+    workers::{
+        events::HotStuffEvent,
+        hotstuff_error::HotStuffError,
+        hotstuff_waiter::{HotStuffInput, HotStuffWaiter, PacemakerEvents, RecoveryMessage},
+        pacemaker_worker::{PacemakerHandle, PacemakerTimer},
+    },
+};
+use tokio::sync::{
+    broadcast,
+    mpsc::{channel, Receiver},
+};
+
+use crate::{behaviour::ByzantineBehaviour, message::SimMessage};
+
+/// Large enough that a waiter never blocks on a send while handling a single input
+const CHANNEL_CAPACITY: usize = 1000;
+
+type SimWaiter<TLeaderStrategy, TPayloadProcessor, TShardStore> = HotStuffWaiter<
+    TariDanPayload,
+    PublicKey,
+    TLeaderStrategy,
+    RangeEpochManager<PublicKey>,
+    TPayloadProcessor,
+    TShardStore,
+    NodeIdentitySigningService,
+>;
+
+/// A message produced by a node, before it is routed by the simulated network
+pub(crate) struct Outgoing {
+    pub message: SimMessage,
+    pub recipients: Vec<PublicKey>,
+    /// The waiter chose the recipients at random from a committee. The simulator picks its own recipients using the
+    /// seeded rng so that runs can be replayed.
+    pub is_random_subset: bool,
+}
+
+/// A pacemaker timer, measured in steps instead of wall-clock time
+struct Timer {
+    deadline: u64,
+    event: PacemakerEvents,
+}
+
+pub(crate) struct SimNode<TLeaderStrategy, TPayloadProcessor, TShardStore> {
+    pub public_key: PublicKey,
+    pub signing_service: NodeIdentitySigningService,
+    pub behaviour: Option<ByzantineBehaviour>,
+    waiter: SimWaiter<TLeaderStrategy, TPayloadProcessor, TShardStore>,
+    rx_leader: Receiver<(PublicKey, HotStuffMessage<TariDanPayload, PublicKey>)>,
+    rx_broadcast: Receiver<(HotStuffMessage<TariDanPayload, PublicKey>, Vec<PublicKey>)>,
+    rx_recovery: Receiver<(RecoveryMessage, PublicKey)>,
+    rx_recovery_broadcast: Receiver<(RecoveryMessage, Vec<PublicKey>)>,
+    rx_vote_message: Receiver<(VoteMessage, PublicKey)>,
+    rx_pacemaker: Receiver<PacemakerTimer<PacemakerEvents>>,
+    rx_events: broadcast::Receiver<HotStuffEvent>,
+    timers: Vec<Timer>,
+}
+
+impl<TLeaderStrategy, TPayloadProcessor, TShardStore> SimNode<TLeaderStrategy, TPayloadProcessor, TShardStore>
+where
+    TLeaderStrategy: LeaderStrategy<PublicKey> + Send + Sync + 'static,
+    TPayloadProcessor: PayloadProcessor<TariDanPayload> + Send + Sync + 'static,
+    TShardStore: ShardStore<Addr = PublicKey, Payload = TariDanPayload> + Send + Sync + 'static,
+{
+    pub fn new(
+        secret_key: PrivateKey,
+        public_key: PublicKey,
+        behaviour: Option<ByzantineBehaviour>,
+        epoch_manager: RangeEpochManager<PublicKey>,
+        leader_strategy: TLeaderStrategy,
+        payload_processor: TPayloadProcessor,
+        shard_store: TShardStore,
+        consensus_constants: ConsensusConstants,
+    ) -> Self {
+        let (_, rx_new) = channel(1);
+        let (_, rx_hs_message) = channel(1);
+        let (_, rx_recovery_message) = channel(1);
+        let (_, rx_votes) = channel(1);
+        let (tx_leader, rx_leader) = channel(CHANNEL_CAPACITY);
+        let (tx_broadcast, rx_broadcast) = channel(CHANNEL_CAPACITY);
+        let (tx_recovery, rx_recovery) = channel(CHANNEL_CAPACITY);
+        let (tx_recovery_broadcast, rx_recovery_broadcast) = channel(CHANNEL_CAPACITY);
+        let (tx_vote_message, rx_vote_message) = channel(CHANNEL_CAPACITY);
+        let (tx_events, rx_events) = broadcast::channel(CHANNEL_CAPACITY);
+        let (tx_pacemaker, rx_pacemaker) = channel(CHANNEL_CAPACITY);
+        // Timeouts are fed to the waiter directly, so nothing is ever sent on this channel
+        let (_, rx_timeout) = channel(1);
+
+        let node_identity = NodeIdentity::new(secret_key, vec![Multiaddr::empty()], PeerFeatures::COMMUNICATION_NODE);
+        let signing_service = NodeIdentitySigningService::new(Arc::new(node_identity));
+
+        let waiter = HotStuffWaiter::new(
+            signing_service.clone(),
+            public_key.clone(),
+            epoch_manager,
+            leader_strategy,
+            rx_new,
+            rx_hs_message,
+            rx_recovery_message,
+            rx_votes,
+            tx_leader,
+            tx_broadcast,
+            tx_recovery,
+            tx_recovery_broadcast,
+            tx_vote_message,
+            tx_events,
+            PacemakerHandle::new(tx_pacemaker, rx_timeout),
+            payload_processor,
+            shard_store,
+            consensus_constants,
+        );
+
+        Self {
+            public_key,
+            signing_service,
+            behaviour,
+            waiter,
+            rx_leader,
+            rx_broadcast,
+            rx_recovery,
+            rx_recovery_broadcast,
+            rx_vote_message,
+            rx_pacemaker,
+            rx_events,
+            timers: Vec::new(),
+        }
+    }
+
+    pub fn is_honest(&self) -> bool {
+        self.behaviour.is_none()
+    }
+
+    pub async fn handle(&mut self, input: HotStuffInput<TariDanPayload, PublicKey>) -> Result<(), HotStuffError> {
+        self.waiter.handle_input(input).await
+    }
+
+    /// Applies the timer signals sent by the waiter. A timer that is started at `step` with a timeout of `d` fires
+    /// `d / step_duration` steps later (rounded up).
+    pub fn update_timers(&mut self, step: u64, step_duration: Duration) {
+        while let Ok(signal) = self.rx_pacemaker.try_recv() {
+            match signal {
+                PacemakerTimer::Start(event, timeout) => {
+                    // As with the pacemaker, a timer that is already running is not restarted
+                    if self.timers.iter().all(|t| t.event != event) {
+                        let steps = (timeout.as_nanos() + step_duration.as_nanos() - 1) / step_duration.as_nanos();
+                        let steps = u64::try_from(steps).unwrap_or(u64::MAX).max(1);
+                        self.timers.push(Timer {
+                            deadline: step.saturating_add(steps),
+                            event,
+                        });
+                    }
+                },
+                PacemakerTimer::Stop(event) => {
+                    self.timers.retain(|t| t.event != event);
+                },
+            }
+        }
+    }
+
+    /// Removes and returns the timers that have expired by `step`, in the order they were started
+    pub fn take_expired_timers(&mut self, step: u64) -> Vec<PacemakerEvents> {
+        let mut expired = Vec::new();
+        self.timers.retain(|t| {
+            if t.deadline <= step {
+                expired.push(t.event.clone());
+                false
+            } else {
+                true
+            }
+        });
+        expired
+    }
+
+    pub fn drain_outgoing(&mut self) -> Vec<Outgoing> {
+        let mut outgoing = Vec::new();
+        while let Ok((to, msg)) = self.rx_leader.try_recv() {
+            outgoing.push(Outgoing {
+                message: SimMessage::HotStuff(msg),
+                recipients: vec![to],
+                is_random_subset: false,
+            });
+        }
+        while let Ok((msg, recipients)) = self.rx_broadcast.try_recv() {
+            outgoing.push(Outgoing {
+                message: SimMessage::HotStuff(msg),
+                recipients,
+                is_random_subset: false,
+            });
+        }
+        while let Ok((msg, to)) = self.rx_recovery.try_recv() {
+            outgoing.push(Outgoing {
+                message: SimMessage::Recovery(msg),
+                recipients: vec![to],
+                is_random_subset: false,
+            });
+        }
+        while let Ok((msg, recipients)) = self.rx_recovery_broadcast.try_recv() {
+            outgoing.push(Outgoing {
+                message: SimMessage::Recovery(msg),
+                recipients,
+                is_random_subset: true,
+            });
+        }
+        while let Ok((msg, to)) = self.rx_vote_message.try_recv() {
+            outgoing.push(Outgoing {
+                message: SimMessage::Vote(msg),
+                recipients: vec![to],
+                is_random_subset: false,
+            });
+        }
+        outgoing
+    }
+
+    pub fn drain_events(&mut self) -> Vec<HotStuffEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = self.rx_events.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+}
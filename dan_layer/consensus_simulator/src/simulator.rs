@@ -0,0 +1,553 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+    ops::Range,
+};
+
+use log::*;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_crypto::keys::PublicKey as PublicKeyT;
+use tari_dan_common_types::{Epoch, PayloadId, ShardId, ShardPledge};
+use tari_dan_core::{
+    models::{vote_message::VoteMessage, HotStuffMessage, HotStuffTreeNode, Payload, TariDanPayload},
+    services::{
+        epoch_manager::{EpochManager, RangeEpochManager},
+        leader_strategy::LeaderStrategy,
+        NodeIdentitySigningService,
+        PayloadProcessor,
+        SigningService,
+    },
+    storage::shard_store::ShardStore,
+    workers::hotstuff_waiter::{HotStuffInput, RecoveryMessage},
+};
+
+use crate::{
+    behaviour::ByzantineBehaviour,
+    config::SimulatorConfig,
+    error::SimulatorError,
+    invariants::{default_invariants, Invariant},
+    message::{Envelope, SimMessage},
+    network::Network,
+    node::SimNode,
+    trace::{DropReason, MessageKind, ObservedEvent, Outcome, TraceEvent},
+};
+
+const LOG_TARGET: &str = "tari::dan::consensus_simulator";
+
+/// Drives the members of a number of committees through consensus, one step at a time.
+///
+/// In each step, the pacemaker timers that have expired fire, and then every message that is due is delivered.
+/// Messages sent during a step are delivered in the next step at the earliest. The invariants are checked at the end
+/// of every step against the events published by honest nodes.
+pub struct Simulator<TLeaderStrategy, TPayloadProcessor, TShardStore> {
+    config: SimulatorConfig,
+    rng: StdRng,
+    step: u64,
+    nodes: Vec<SimNode<TLeaderStrategy, TPayloadProcessor, TShardStore>>,
+    committees: Vec<(Range<ShardId>, Vec<usize>)>,
+    epoch_manager: RangeEpochManager<PublicKey>,
+    network: Network,
+    in_flight: Vec<Envelope>,
+    invariants: Vec<Box<dyn Invariant + Send>>,
+    /// The nodes that each submitted payload was given to
+    submitted: HashMap<PayloadId, Vec<usize>>,
+    events: Vec<ObservedEvent>,
+    unchecked_events: Vec<ObservedEvent>,
+    trace: Vec<TraceEvent>,
+}
+
+impl<TLeaderStrategy, TPayloadProcessor, TShardStore> Simulator<TLeaderStrategy, TPayloadProcessor, TShardStore>
+where
+    TLeaderStrategy: LeaderStrategy<PublicKey> + Send + Sync + 'static,
+    TPayloadProcessor: PayloadProcessor<TariDanPayload> + Send + Sync + 'static,
+    TShardStore: ShardStore<Addr = PublicKey, Payload = TariDanPayload> + Send + Sync + 'static,
+{
+    /// Creates a simulation with a committee of each of the given sizes. Committee `i` is responsible for the shards
+    /// in `[i; 32]..[i + 1; 32]`, and nodes are numbered in committee order. `create_node` is called for each node to
+    /// create its leader strategy, payload processor and shard store.
+    pub fn new<F>(config: SimulatorConfig, committee_sizes: &[usize], mut create_node: F) -> Self
+    where F: FnMut(&PublicKey) -> (TLeaderStrategy, TPayloadProcessor, TShardStore) {
+        assert!(
+            committee_sizes.len() < usize::from(u8::MAX),
+            "Too many committees for the simulated shard ranges"
+        );
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let keys = committee_sizes
+            .iter()
+            .map(|size| {
+                (0..*size)
+                    .map(|_| PublicKey::random_keypair(&mut rng))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let mut committees = Vec::with_capacity(keys.len());
+        let mut next_node = 0;
+        for (i, committee_keys) in keys.iter().enumerate() {
+            let start = ShardId([i as u8; 32]);
+            let end = ShardId([i as u8 + 1; 32]);
+            committees.push((start..end, (next_node..next_node + committee_keys.len()).collect()));
+            next_node += committee_keys.len();
+        }
+
+        let registered_vn_keys = keys.iter().flatten().map(|(_, pk)| pk.clone()).collect();
+        let epoch_manager = RangeEpochManager::new_with_multiple(
+            registered_vn_keys,
+            &committees
+                .iter()
+                .zip(&keys)
+                .map(|((range, _), committee_keys)| {
+                    (range.clone(), committee_keys.iter().map(|(_, pk)| pk.clone()).collect())
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        let nodes = keys
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .map(|(index, (secret_key, public_key))| {
+                let behaviour = config
+                    .byzantine_nodes
+                    .iter()
+                    .find(|(node, _)| *node == index)
+                    .map(|(_, behaviour)| *behaviour);
+                let (leader_strategy, payload_processor, shard_store) = create_node(&public_key);
+                SimNode::new(
+                    secret_key,
+                    public_key,
+                    behaviour,
+                    epoch_manager.clone(),
+                    leader_strategy,
+                    payload_processor,
+                    shard_store,
+                    config.consensus_constants.clone(),
+                )
+            })
+            .collect();
+
+        Self {
+            network: Network::new(config.network_rules.clone()),
+            config,
+            rng,
+            step: 0,
+            nodes,
+            committees,
+            epoch_manager,
+            in_flight: Vec::new(),
+            invariants: default_invariants(),
+            submitted: HashMap::new(),
+            events: Vec::new(),
+            unchecked_events: Vec::new(),
+            trace: Vec::new(),
+        }
+    }
+
+    /// Adds an invariant to be checked in addition to the default invariants
+    pub fn with_invariant<I: Invariant + Send + 'static>(mut self, invariant: I) -> Self {
+        self.invariants.push(Box::new(invariant));
+        self
+    }
+
+    pub fn current_step(&self) -> u64 {
+        self.step
+    }
+
+    pub fn num_nodes(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn public_key(&self, node: usize) -> Option<&PublicKey> {
+        self.nodes.get(node).map(|n| &n.public_key)
+    }
+
+    /// The node indexes of the members of a committee, in committee order
+    pub fn committee_nodes(&self, committee: usize) -> &[usize] {
+        &self.committees[committee].1
+    }
+
+    /// A shard that the committee is responsible for
+    pub fn committee_shard(&self, committee: usize) -> ShardId {
+        self.committees[committee].0.start
+    }
+
+    pub fn is_honest(&self, node: usize) -> bool {
+        matches!(self.nodes.get(node), Some(n) if n.is_honest())
+    }
+
+    /// The events published by all nodes, including Byzantine nodes, in the order they were observed
+    pub fn events(&self) -> &[ObservedEvent] {
+        &self.events
+    }
+
+    pub fn trace(&self) -> &[TraceEvent] {
+        &self.trace
+    }
+
+    /// Returns the outcomes of a payload at each node that has published one
+    pub fn outcomes(&self, payload_id: &PayloadId) -> Vec<(usize, Outcome)> {
+        self.events
+            .iter()
+            .filter(|e| e.payload_id() == *payload_id)
+            .map(|e| (e.node, e.outcome()))
+            .collect()
+    }
+
+    /// Returns true if every honest node that was given the payload has published an outcome for it
+    pub fn is_decided(&self, payload_id: &PayloadId) -> bool {
+        let decided = self
+            .events
+            .iter()
+            .filter(|e| e.payload_id() == *payload_id)
+            .map(|e| e.node)
+            .collect::<HashSet<_>>();
+        self.submitted.get(payload_id).map_or(false, |nodes| {
+            nodes
+                .iter()
+                .filter(|node| self.nodes[**node].is_honest())
+                .all(|node| decided.contains(node))
+        })
+    }
+
+    /// Gives the payload to every member of the committees of the shards it involves
+    pub async fn submit(&mut self, payload: TariDanPayload) -> Result<(), SimulatorError> {
+        let payload_id = payload.to_id();
+        let mut recipients = Vec::new();
+        for shard in payload.involved_shards() {
+            if let Some(committee) = self.committee_for_shard(shard) {
+                for node in self.committees[committee].1.clone() {
+                    recipients.push((node, shard));
+                }
+            }
+        }
+        if recipients.is_empty() {
+            return Err(SimulatorError::PayloadNotInvolved { payload_id });
+        }
+
+        for (node, shard) in recipients {
+            self.trace.push(TraceEvent::PayloadSubmitted {
+                step: self.step,
+                node,
+                payload_id,
+                shard,
+            });
+            self.submitted.entry(payload_id).or_default().push(node);
+            self.handle_input(node, HotStuffInput::NewPayload(payload.clone(), shard))
+                .await?;
+        }
+        self.check_invariants()
+    }
+
+    /// Advances the simulation by a single step
+    pub async fn step(&mut self) -> Result<(), SimulatorError> {
+        self.step += 1;
+
+        for node in 0..self.nodes.len() {
+            for event in self.nodes[node].take_expired_timers(self.step) {
+                self.trace.push(TraceEvent::Timeout {
+                    step: self.step,
+                    node,
+                    event: event.clone(),
+                });
+                self.handle_input(node, HotStuffInput::PacemakerTimeout(event)).await?;
+            }
+        }
+
+        let step = self.step;
+        let (mut ready, pending): (Vec<_>, Vec<_>) = mem::take(&mut self.in_flight)
+            .into_iter()
+            .partition(|e| e.deliver_at <= step);
+        self.in_flight = pending;
+        if self.network.is_reordering() {
+            ready.shuffle(&mut self.rng);
+        }
+
+        for envelope in ready {
+            self.trace.push(TraceEvent::Delivered {
+                step: self.step,
+                from: envelope.from,
+                to: envelope.to,
+                kind: MessageKind::from_message(&envelope.message),
+            });
+            let from = self.nodes[envelope.from].public_key.clone();
+            self.handle_input(envelope.to, envelope.message.into_input(from))
+                .await?;
+        }
+
+        self.check_invariants()
+    }
+
+    /// Steps the simulation until `is_done` returns true, failing if that takes more than `max_steps` steps
+    pub async fn run_until<F>(&mut self, max_steps: u64, mut is_done: F) -> Result<(), SimulatorError>
+    where F: FnMut(&Self) -> bool {
+        let start = self.step;
+        while !is_done(self) {
+            if self.step - start >= max_steps {
+                return Err(SimulatorError::StepLimitReached { max_steps });
+            }
+            self.step().await?;
+        }
+        Ok(())
+    }
+
+    /// Steps the simulation until every honest node given the payload has published an outcome for it
+    pub async fn run_until_decided(&mut self, payload_id: PayloadId, max_steps: u64) -> Result<(), SimulatorError> {
+        self.run_until(max_steps, |sim| sim.is_decided(&payload_id)).await
+    }
+
+    /// Steps the simulation `steps` times
+    pub async fn run_for(&mut self, steps: u64) -> Result<(), SimulatorError> {
+        for _ in 0..steps {
+            self.step().await?;
+        }
+        Ok(())
+    }
+
+    async fn handle_input(
+        &mut self,
+        node: usize,
+        input: HotStuffInput<TariDanPayload, PublicKey>,
+    ) -> Result<(), SimulatorError> {
+        let sim_node = self.nodes.get_mut(node).ok_or(SimulatorError::UnknownNode { node })?;
+        if let Err(err) = sim_node.handle(input).await {
+            // As in the waiter's run loop, errors are not fatal to the node
+            debug!(target: LOG_TARGET, "Node {} failed to handle input: {}", node, err);
+            self.trace.push(TraceEvent::InputFailed { step: self.step, node });
+        }
+        self.collect_events(node);
+        self.collect_outgoing(node).await
+    }
+
+    fn collect_events(&mut self, node: usize) {
+        let public_key = self.nodes[node].public_key.clone();
+        let is_honest = self.nodes[node].is_honest();
+        for event in self.nodes[node].drain_events() {
+            let observed = ObservedEvent {
+                step: self.step,
+                node,
+                public_key: public_key.clone(),
+                event,
+            };
+            self.trace.push(TraceEvent::Outcome {
+                step: self.step,
+                node,
+                payload_id: observed.payload_id(),
+                outcome: observed.outcome(),
+            });
+            if is_honest {
+                self.unchecked_events.push(observed.clone());
+            }
+            self.events.push(observed);
+        }
+    }
+
+    async fn collect_outgoing(&mut self, from: usize) -> Result<(), SimulatorError> {
+        self.nodes[from].update_timers(self.step, self.config.step_duration);
+
+        let mut messages = Vec::new();
+        for outgoing in self.nodes[from].drain_outgoing() {
+            let recipients = if outgoing.is_random_subset {
+                self.choose_recipients(&outgoing.message, outgoing.recipients.len())
+            } else {
+                outgoing
+                    .recipients
+                    .iter()
+                    .filter_map(|pk| self.node_index(pk))
+                    .collect()
+            };
+            for to in recipients {
+                messages.push((to, outgoing.message.clone()));
+            }
+        }
+        // The waiter may produce messages in an order that depends on hash map iteration, so they are sorted
+        messages.sort_by_key(|(to, msg)| (*to, MessageKind::from_message(msg).sort_key()));
+
+        let messages = match self.nodes[from].behaviour {
+            Some(behaviour) => self.apply_behaviour(from, behaviour, messages).await?,
+            None => messages,
+        };
+        for (to, message) in messages {
+            self.send(from, to, message);
+        }
+        Ok(())
+    }
+
+    fn send(&mut self, from: usize, to: usize, message: SimMessage) {
+        let kind = MessageKind::from_message(&message);
+        let mut deliver_at = self.step + 1;
+        if from != to {
+            if self.network.is_partitioned(self.step, from, to) {
+                self.trace_dropped(from, to, kind, DropReason::Partitioned);
+                return;
+            }
+            if self.network.should_drop(&mut self.rng) {
+                self.trace_dropped(from, to, kind, DropReason::Dropped);
+                return;
+            }
+            deliver_at += self.network.delay(&mut self.rng);
+        }
+
+        self.trace.push(TraceEvent::Sent {
+            step: self.step,
+            from,
+            to,
+            kind,
+            deliver_at,
+        });
+        self.in_flight.push(Envelope {
+            from,
+            to,
+            deliver_at,
+            message,
+        });
+    }
+
+    fn trace_dropped(&mut self, from: usize, to: usize, kind: MessageKind, reason: DropReason) {
+        self.trace.push(TraceEvent::Dropped {
+            step: self.step,
+            from,
+            to,
+            kind,
+            reason,
+        });
+    }
+
+    async fn apply_behaviour(
+        &mut self,
+        from: usize,
+        behaviour: ByzantineBehaviour,
+        messages: Vec<(usize, SimMessage)>,
+    ) -> Result<Vec<(usize, SimMessage)>, SimulatorError> {
+        match behaviour {
+            ByzantineBehaviour::Silent => {
+                for (to, message) in messages {
+                    self.trace_dropped(from, to, MessageKind::from_message(&message), DropReason::Silent);
+                }
+                Ok(Vec::new())
+            },
+            ByzantineBehaviour::Equivocate => {
+                let signing_service = self.nodes[from].signing_service.clone();
+                let mut num_proposals = 0usize;
+                let messages = messages
+                    .into_iter()
+                    .map(|(to, message)| match message {
+                        SimMessage::HotStuff(msg) if msg.node().is_some() => {
+                            num_proposals += 1;
+                            if num_proposals % 2 == 0 {
+                                (to, SimMessage::HotStuff(conflicting_proposal(&msg, &signing_service)))
+                            } else {
+                                (to, SimMessage::HotStuff(msg))
+                            }
+                        },
+                        message => (to, message),
+                    })
+                    .collect();
+                Ok(messages)
+            },
+            ByzantineBehaviour::InvalidPledges => {
+                let vn_bmt = self.epoch_manager.get_validator_node_bmt(Epoch(0)).await?;
+                let mut tampered = Vec::with_capacity(messages.len());
+                for (to, message) in messages {
+                    let message = match message {
+                        SimMessage::Vote(vote) => {
+                            let pledges = vote
+                                .all_shard_pledges()
+                                .iter()
+                                .cloned()
+                                .map(|mut pledge: ShardPledge| {
+                                    pledge.pledge.pledged_to_payload = PayloadId::new([0xffu8; 32]);
+                                    pledge
+                                })
+                                .collect();
+                            let mut invalid_vote = VoteMessage::new(vote.local_node_hash(), vote.decision(), pledges);
+                            invalid_vote.sign_vote(
+                                &self.nodes[from].signing_service,
+                                vote.validator_metadata().vn_shard_key,
+                                &vn_bmt,
+                            )?;
+                            SimMessage::Vote(invalid_vote)
+                        },
+                        message => message,
+                    };
+                    tampered.push((to, message));
+                }
+                Ok(tampered)
+            },
+        }
+    }
+
+    /// Chooses the recipients of a recovery message from the committee of the shard it is about
+    fn choose_recipients(&mut self, message: &SimMessage, amount: usize) -> Vec<usize> {
+        let shard = match message {
+            SimMessage::Recovery(RecoveryMessage::MissingProposal(_, shard, _, _)) |
+            SimMessage::Recovery(RecoveryMessage::ElectionInProgress(_, shard, _)) => *shard,
+            SimMessage::HotStuff(msg) => msg.shard(),
+            SimMessage::Vote(_) => return Vec::new(),
+        };
+        match self.committee_for_shard(shard) {
+            Some(committee) => self.committees[committee]
+                .1
+                .choose_multiple(&mut self.rng, amount)
+                .copied()
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn check_invariants(&mut self) -> Result<(), SimulatorError> {
+        let events = mem::take(&mut self.unchecked_events);
+        for invariant in &mut self.invariants {
+            if let Err(details) = invariant.check(&events) {
+                return Err(SimulatorError::InvariantViolated {
+                    invariant: invariant.name(),
+                    step: self.step,
+                    details,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn committee_for_shard(&self, shard: ShardId) -> Option<usize> {
+        self.committees.iter().position(|(range, _)| range.contains(&shard))
+    }
+
+    fn node_index(&self, public_key: &PublicKey) -> Option<usize> {
+        self.nodes.iter().position(|n| n.public_key == *public_key)
+    }
+}
+
+/// Returns a proposal for the same parent and height as the given proposal, but with a different state root, signed by
+/// the equivocating leader
+fn conflicting_proposal(
+    msg: &HotStuffMessage<TariDanPayload, PublicKey>,
+    signing_service: &NodeIdentitySigningService,
+) -> HotStuffMessage<TariDanPayload, PublicKey> {
+    let node = msg.node().expect("conflicting_proposal called without a node");
+    let mut state_root = [0u8; 32];
+    state_root.copy_from_slice(node.state_root().as_slice());
+    state_root[0] ^= 0xff;
+    let mut conflicting = HotStuffTreeNode::new(
+        *node.parent(),
+        node.shard(),
+        node.height(),
+        node.payload_id(),
+        node.payload().cloned(),
+        node.payload_height(),
+        node.leader_round(),
+        node.local_pledge().cloned(),
+        node.epoch(),
+        node.proposed_by().clone(),
+        node.justify().clone(),
+        FixedHash::from(state_root),
+    );
+    let signature = signing_service
+        .sign(conflicting.hash().as_bytes())
+        .expect("signing with a node identity does not fail");
+    conflicting.set_proposer_signature(signature);
+    HotStuffMessage::new_proposal(conflicting, msg.shard())
+}
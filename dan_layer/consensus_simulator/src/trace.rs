@@ -0,0 +1,170 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_common_types::types::PublicKey;
+use tari_dan_common_types::{PayloadId, QuorumDecision, ShardId};
+use tari_dan_core::{
+    models::HotStuffMessageType,
+    workers::{
+        events::HotStuffEvent,
+        hotstuff_waiter::{PacemakerEvents, RecoveryMessage},
+    },
+};
+
+use crate::message::SimMessage;
+
+/// A summary of a message that is independent of signatures and hashes, which differ between runs even when the
+/// simulation is seeded identically.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MessageKind {
+    NewView {
+        shard: ShardId,
+    },
+    Proposal {
+        shard: ShardId,
+        height: u64,
+        payload_height: u64,
+    },
+    Vote {
+        decision: QuorumDecision,
+    },
+    MissingProposal {
+        shard: ShardId,
+        height: u64,
+    },
+    ElectionInProgress {
+        shard: ShardId,
+    },
+}
+
+impl MessageKind {
+    pub(crate) fn from_message(message: &SimMessage) -> Self {
+        match message {
+            SimMessage::HotStuff(msg) => match (msg.message_type(), msg.node()) {
+                (HotStuffMessageType::Proposal, Some(node)) => MessageKind::Proposal {
+                    shard: msg.shard(),
+                    height: node.height().as_u64(),
+                    payload_height: node.payload_height().as_u64(),
+                },
+                _ => MessageKind::NewView { shard: msg.shard() },
+            },
+            SimMessage::Vote(vote) => MessageKind::Vote {
+                decision: vote.decision(),
+            },
+            SimMessage::Recovery(RecoveryMessage::MissingProposal(_, shard, _, height)) => {
+                MessageKind::MissingProposal {
+                    shard: *shard,
+                    height: height.as_u64(),
+                }
+            },
+            SimMessage::Recovery(RecoveryMessage::ElectionInProgress(_, shard, _)) => {
+                MessageKind::ElectionInProgress { shard: *shard }
+            },
+        }
+    }
+
+    /// Used to order the messages that a node sends in a single step, so that the order does not depend on the
+    /// order in which the node happened to produce them.
+    pub(crate) fn sort_key(&self) -> (u8, ShardId, u64) {
+        match self {
+            MessageKind::NewView { shard } => (0, *shard, 0),
+            MessageKind::Proposal { shard, height, .. } => (1, *shard, *height),
+            MessageKind::Vote { decision } => (2, ShardId::zero(), u64::from(decision.as_u8())),
+            MessageKind::MissingProposal { shard, height } => (3, *shard, *height),
+            MessageKind::ElectionInProgress { shard } => (4, *shard, 0),
+        }
+    }
+}
+
+/// Why a message was not delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropReason {
+    /// The sender is silent
+    Silent,
+    /// A drop rule matched the message
+    Dropped,
+    /// The sender and recipient were in different partitions
+    Partitioned,
+}
+
+/// The outcome of a payload at a node, as published in its [HotStuffEvent]s
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Finalized { shard: ShardId, accepted: bool },
+    Failed,
+}
+
+/// A single entry in the trace of a simulation. Two simulations with the same seed, committees, rules and payloads
+/// produce the same trace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceEvent {
+    PayloadSubmitted {
+        step: u64,
+        node: usize,
+        payload_id: PayloadId,
+        shard: ShardId,
+    },
+    Sent {
+        step: u64,
+        from: usize,
+        to: usize,
+        kind: MessageKind,
+        deliver_at: u64,
+    },
+    Dropped {
+        step: u64,
+        from: usize,
+        to: usize,
+        kind: MessageKind,
+        reason: DropReason,
+    },
+    Delivered {
+        step: u64,
+        from: usize,
+        to: usize,
+        kind: MessageKind,
+    },
+    Timeout {
+        step: u64,
+        node: usize,
+        event: PacemakerEvents,
+    },
+    InputFailed {
+        step: u64,
+        node: usize,
+    },
+    Outcome {
+        step: u64,
+        node: usize,
+        payload_id: PayloadId,
+        outcome: Outcome,
+    },
+}
+
+/// A [HotStuffEvent] published by a node, together with where and when it was observed
+#[derive(Debug, Clone)]
+pub struct ObservedEvent {
+    pub step: u64,
+    pub node: usize,
+    pub public_key: PublicKey,
+    pub event: HotStuffEvent,
+}
+
+impl ObservedEvent {
+    pub fn payload_id(&self) -> PayloadId {
+        match &self.event {
+            HotStuffEvent::OnFinalized(_, result) => PayloadId::new(result.transaction_hash),
+            HotStuffEvent::Failed(payload_id, _) => *payload_id,
+        }
+    }
+
+    pub fn outcome(&self) -> Outcome {
+        match &self.event {
+            HotStuffEvent::OnFinalized(qc, result) => Outcome::Finalized {
+                shard: qc.shard(),
+                accepted: !qc.decision().is_reject() && result.is_accept(),
+            },
+            HotStuffEvent::Failed(..) => Outcome::Failed,
+        }
+    }
+}
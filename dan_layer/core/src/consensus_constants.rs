@@ -20,15 +20,30 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use tari_core::transactions::tari_amount::MicroTari;
 use tari_dan_common_types::{Epoch, NodeHeight};
+use tari_dan_engine::fees::FeeTable;
+
+use crate::services::leader_strategy::LeaderStrategyType;
 
-#[derive(Clone)]
+/// Constants that every validator node in a network must agree on. Nodes compare their constants when they connect
+/// and refuse to talk to a node with different constants.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ConsensusConstants {
+    /// The number of blocks on top of a base layer block before its validator node registrations are used
     pub base_layer_confirmations: u64,
     pub committee_size: u64,
     pub hotstuff_rounds: u64,
+    /// How long a replica waits for the leader before it triggers a new leader. Later rounds wait exponentially
+    /// longer.
+    pub pacemaker_timeout: Duration,
+    /// The fees charged for executing a transaction. Transactions that do not pay these fees in full are rejected.
+    pub fee_table: FeeTable,
+    /// The strategy used to select the consensus leader
+    pub leader_strategy: LeaderStrategyType,
 }
 
 impl ConsensusConstants {
@@ -37,6 +52,50 @@ impl ConsensusConstants {
             base_layer_confirmations: 3,
             committee_size: 7,
             hotstuff_rounds: 4,
+            pacemaker_timeout: Duration::from_secs(10),
+            fee_table: FeeTable::zero_rated(),
+            leader_strategy: LeaderStrategyType::PayloadSpecific,
+        }
+    }
+
+    pub const fn localnet() -> Self {
+        Self {
+            base_layer_confirmations: 3,
+            committee_size: 7,
+            hotstuff_rounds: 4,
+            pacemaker_timeout: Duration::from_secs(10),
+            fee_table: FeeTable::zero_rated(),
+            leader_strategy: LeaderStrategyType::PayloadSpecific,
+        }
+    }
+
+    pub const fn esmeralda() -> Self {
+        Self {
+            base_layer_confirmations: 3,
+            committee_size: 7,
+            hotstuff_rounds: 4,
+            pacemaker_timeout: Duration::from_secs(10),
+            fee_table: FeeTable {
+                per_runtime_call_cost: 1,
+                per_wasm_point_cost: 1,
+                per_byte_storage_cost: 1,
+            },
+            leader_strategy: LeaderStrategyType::PayloadSpecific,
+        }
+    }
+
+    pub const fn mainnet() -> Self {
+        Self {
+            base_layer_confirmations: 10,
+            committee_size: 21,
+            hotstuff_rounds: 4,
+            pacemaker_timeout: Duration::from_secs(20),
+            fee_table: FeeTable {
+                per_runtime_call_cost: 1,
+                per_wasm_point_cost: 1,
+                per_byte_storage_cost: 1,
+            },
+            leader_strategy: LeaderStrategyType::PayloadSpecific,
         }
     }
 
@@ -37,6 +37,8 @@ pub enum DanMessage<TPayload, TAddr> {
     VoteMessage(VoteMessage),
     // Mempool
     NewTransaction(Box<Transaction>),
+    /// A batch of transactions that is decided in a single consensus instance
+    NewTransactionBatch(Vec<Transaction>),
     // Network
     NetworkAnnounce(Box<NetworkAnnounce<TAddr>>),
     // Recovery
@@ -49,6 +51,7 @@ impl<TPayload, TAddr: NodeAddressable> DanMessage<TPayload, TAddr> {
             Self::HotStuffMessage(_) => "HotStuffMessage",
             Self::VoteMessage(_) => "VoteMessage",
             Self::NewTransaction(_) => "NewTransaction",
+            Self::NewTransactionBatch(_) => "NewTransactionBatch",
             Self::NetworkAnnounce(_) => "NetworkAnnounce",
             Self::RecoveryMessage(_) => "RecoveryMessage",
         }
@@ -59,6 +62,10 @@ impl<TPayload, TAddr: NodeAddressable> DanMessage<TPayload, TAddr> {
             Self::HotStuffMessage(msg) => format!("shard_{}", msg.shard()),
             Self::VoteMessage(msg) => format!("node_{}", msg.local_node_hash()),
             Self::NewTransaction(tx) => format!("hash_{}", tx.hash()),
+            Self::NewTransactionBatch(txs) => format!(
+                "batch_{}",
+                txs.first().map(|tx| tx.hash().to_string()).unwrap_or_default()
+            ),
             Self::NetworkAnnounce(msg) => format!("pk_{}", msg.identity),
             Self::RecoveryMessage(msg) => format!("recovery_{:?}", msg),
         }
@@ -20,7 +20,10 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use digest::Digest;
 use serde::Serialize;
+use tari_common_types::types::FixedHash;
+use tari_crypto::hash::blake2::Blake256;
 use tari_dan_common_types::NodeAddressable;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Default, Hash)]
@@ -59,6 +62,17 @@ impl<TAddr: NodeAddressable> Committee<TAddr> {
     pub fn contains(&self, member: &TAddr) -> bool {
         self.members.contains(member)
     }
+
+    /// Returns a hash of the committee members that does not depend on the order of the members
+    pub fn members_hash(&self) -> FixedHash {
+        let mut members = self.members.iter().map(|m| m.as_bytes()).collect::<Vec<_>>();
+        members.sort_unstable();
+        members
+            .into_iter()
+            .fold(Blake256::new(), |hasher, member| hasher.chain(member))
+            .finalize()
+            .into()
+    }
 }
 
 impl<TAddr: NodeAddressable> IntoIterator for Committee<TAddr> {
@@ -21,6 +21,7 @@
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use serde::{Deserialize, Serialize};
+use tari_common_types::types::{FixedHash, Signature};
 use tari_dan_common_types::{
     Epoch,
     NodeAddressable,
@@ -53,6 +54,12 @@ pub struct HotStuffTreeNode<TAddr, TPayload> {
     justify: QuorumCertificate,
     // Mostly used for debugging
     proposed_by: TAddr,
+    /// The root of a state tree containing the state of the node's shard as of the parent node
+    #[serde(default)]
+    state_root: FixedHash,
+    /// The proposer's signature over the node hash
+    #[serde(default)]
+    proposer_signature: Option<Signature>,
 }
 
 impl<TAddr: NodeAddressable, TPayload: Payload> HotStuffTreeNode<TAddr, TPayload> {
@@ -68,6 +75,7 @@ impl<TAddr: NodeAddressable, TPayload: Payload> HotStuffTreeNode<TAddr, TPayload
         epoch: Epoch,
         proposed_by: TAddr,
         justify: QuorumCertificate,
+        state_root: FixedHash,
     ) -> Self {
         let mut s = HotStuffTreeNode {
             hash: TreeNodeHash::zero(),
@@ -82,6 +90,8 @@ impl<TAddr: NodeAddressable, TPayload: Payload> HotStuffTreeNode<TAddr, TPayload
             payload_height,
             local_pledge,
             proposed_by,
+            state_root,
+            proposer_signature: None,
         };
         s.hash = s.calculate_hash();
         s
@@ -108,6 +118,8 @@ impl<TAddr: NodeAddressable, TPayload: Payload> HotStuffTreeNode<TAddr, TPayload
             epoch,
             justify: QuorumCertificate::genesis(epoch, payload_id, shard_id),
             proposed_by,
+            state_root: FixedHash::zero(),
+            proposer_signature: None,
         }
     }
 
@@ -120,12 +132,14 @@ impl<TAddr: NodeAddressable, TPayload: Payload> HotStuffTreeNode<TAddr, TPayload
             .chain(&self.parent)
             .chain(&self.epoch)
             .chain(&self.height)
+            .chain(&self.leader_round)
             .chain(&self.justify)
             .chain(&self.shard)
             .chain(&self.payload_id)
             .chain(&self.payload_height)
             .chain(&self.proposed_by.as_bytes())
             .chain(&self.local_pledge)
+            .chain(self.state_root.as_slice())
             .result()
             .into_array()
             .into()
@@ -141,6 +155,14 @@ impl<TAddr, TPayload> HotStuffTreeNode<TAddr, TPayload> {
         &self.proposed_by
     }
 
+    pub fn proposer_signature(&self) -> Option<&Signature> {
+        self.proposer_signature.as_ref()
+    }
+
+    pub fn set_proposer_signature(&mut self, signature: Signature) {
+        self.proposer_signature = Some(signature);
+    }
+
     pub fn parent(&self) -> &TreeNodeHash {
         &self.parent
     }
@@ -190,6 +212,16 @@ impl<TAddr, TPayload> HotStuffTreeNode<TAddr, TPayload> {
     pub fn local_pledge(&self) -> Option<&ObjectPledge> {
         self.local_pledge.as_ref()
     }
+
+    pub fn state_root(&self) -> &FixedHash {
+        &self.state_root
+    }
+
+    /// Returns the node without its payload. The payload is not included in the node hash.
+    pub fn without_payload(mut self) -> Self {
+        self.payload = None;
+        self
+    }
 }
 
 impl<TAddr: NodeAddressable, TPayload: Payload> PartialEq for HotStuffTreeNode<TAddr, TPayload> {
@@ -207,6 +239,8 @@ pub enum HotstuffPhase {
     Decide,
 }
 
+/// Maps a payload height to its phase. There is one height per phase, so this relies on
+/// [ConsensusConstants::hotstuff_rounds](crate::consensus_constants::ConsensusConstants::hotstuff_rounds) being 4.
 impl From<NodeHeight> for HotstuffPhase {
     fn from(value: NodeHeight) -> Self {
         match value.as_u64() % 5 {
@@ -0,0 +1,24 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::PayloadId;
+use tari_transaction::Transaction;
+
+/// A transaction held in the mempool of this node. Mempool transactions are persisted so that they are not lost when
+/// the node restarts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MempoolTransaction {
+    pub transaction: Transaction,
+    /// Unix timestamp in seconds of when the transaction was admitted to the mempool
+    pub received_at: u64,
+    /// The payload that the transaction was sent to consensus in, or None if it is waiting to be batched
+    pub payload_id: Option<PayloadId>,
+}
+
+impl MempoolTransaction {
+    pub fn hash(&self) -> FixedHash {
+        self.transaction.hash().into_array().into()
+    }
+}
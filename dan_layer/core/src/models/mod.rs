@@ -20,7 +20,7 @@
 // WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 // USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{convert::TryFrom, fmt::Debug};
+use std::{convert::TryFrom, fmt::Debug, ops::AddAssign};
 
 use anyhow::anyhow;
 use chrono::NaiveDateTime;
@@ -35,9 +35,12 @@ mod error;
 mod hot_stuff_message;
 mod hot_stuff_tree_node;
 mod leaf_node;
+mod mempool_transaction;
 mod node;
 mod payload;
 mod sidechain_metadata;
+mod slashing_evidence;
+mod state_tree;
 mod substate_shard_data;
 mod tari_dan_payload;
 mod validator_node;
@@ -50,11 +53,14 @@ pub use error::ModelError;
 pub use hot_stuff_message::HotStuffMessage;
 pub use hot_stuff_tree_node::{HotStuffTreeNode, HotstuffPhase};
 pub use leaf_node::LeafNode;
+pub use mempool_transaction::MempoolTransaction;
 pub use node::Node;
 pub use payload::{Payload, PayloadResult};
 pub use sidechain_metadata::SidechainMetadata;
-pub use substate_shard_data::SubstateShardData;
-use tari_dan_common_types::{NodeHeight, TreeNodeHash};
+pub use slashing_evidence::{SlashingEvidence, SlashingEvidenceKind, SlashingEvidenceSubmission};
+pub use state_tree::{is_right_at_depth, StateTreeNode, StateTreeProof, STATE_TREE_MAX_DEPTH};
+pub use substate_shard_data::{state_tree_value_hash, SubstateShardData};
+use tari_dan_common_types::{Epoch, NodeHeight, PayloadId, ShardId, TreeNodeHash};
 pub use tari_dan_payload::{CheckpointData, TariDanPayload};
 pub use validator_node::ValidatorNode;
 
@@ -165,6 +171,29 @@ impl From<u64> for ChainHeight {
     }
 }
 
+/// The number of consensus records removed by pruning
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrunedRecordCounts {
+    pub nodes: u64,
+    pub received_votes: u64,
+    pub leader_proposals: u64,
+    pub high_qcs: u64,
+    pub pledges: u64,
+    /// Leaf nodes, locked nodes, last executed and voted heights and current leader states
+    pub consensus_states: u64,
+}
+
+impl AddAssign for PrunedRecordCounts {
+    fn add_assign(&mut self, rhs: Self) {
+        self.nodes += rhs.nodes;
+        self.received_votes += rhs.received_votes;
+        self.leader_proposals += rhs.leader_proposals;
+        self.high_qcs += rhs.high_qcs;
+        self.pledges += rhs.pledges;
+        self.consensus_states += rhs.consensus_states;
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RecentTransaction {
     pub payload_id: Vec<u8>,
@@ -199,6 +228,19 @@ pub struct SQLSubstate {
     pub destroyed_justify: Option<String>,
 }
 
+/// A leader that failed to propose in a round of a committed node. The leader of every round before the round in which
+/// the node was proposed is considered to have failed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LeaderFailure<TAddr> {
+    pub epoch: Epoch,
+    /// The [Committee::members_hash] of the committee that committed the node
+    pub committee_hash: FixedHash,
+    pub shard_id: ShardId,
+    pub payload_id: PayloadId,
+    pub leader_round: u32,
+    pub leader: TAddr,
+}
+
 #[derive(Debug, Serialize)]
 pub struct CurrentLeaderStates {
     pub payload_id: Vec<u8>,
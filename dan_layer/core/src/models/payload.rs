@@ -37,6 +37,11 @@ pub trait Payload: Debug + Clone + Send + Sync + ConsensusHash {
     }
     fn objects_for_shard(&self, shard: ShardId) -> Option<(SubstateChange, ObjectClaim)>;
     fn max_outputs(&self) -> u32;
+    /// The individual payloads that are decided together in this payload, in execution order. Empty if the payload
+    /// is not a batch.
+    fn batched_payloads(&self) -> Vec<Self> {
+        Vec::new()
+    }
 }
 
 impl ConsensusHash for (String, Vec<ShardId>) {
@@ -0,0 +1,373 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt::{Display, Formatter};
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_comms::types::Signature;
+use tari_dan_common_types::{hashing::tari_hasher, Epoch, NodeAddressable, NodeHeight, PayloadId, ShardId};
+use tari_utilities::ByteArray;
+
+use crate::{
+    models::{vote_message::VoteMessage, HotStuffTreeNode, Payload},
+    services::SigningService,
+    workers::hotstuff_error::HotStuffError,
+    TariDanCoreHashDomain,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SlashingEvidenceKind {
+    /// The offender proposed two different nodes for the same payload, shard, height and leader round
+    DoubleProposal,
+    /// The offender signed votes for two different nodes with the same payload, shard, height and leader round
+    DoubleVote,
+}
+
+impl SlashingEvidenceKind {
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            SlashingEvidenceKind::DoubleProposal => 0,
+            SlashingEvidenceKind::DoubleVote => 1,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SlashingEvidenceKind::DoubleProposal => "double_proposal",
+            SlashingEvidenceKind::DoubleVote => "double_vote",
+        }
+    }
+}
+
+impl Display for SlashingEvidenceKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Evidence that a validator node equivocated, i.e. proposed or voted for two different nodes in the same position of
+/// a shard's chain. Proposing or voting again in a later leader round is part of the protocol, so only conflicting
+/// messages in the same leader round are evidence.
+///
+/// Proposals and votes are signed by the offender and the leader round is part of the node hash that they sign, so the
+/// evidence can be checked by anyone. The reporter signs the evidence to show who found it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashingEvidence<TAddr, TPayload> {
+    kind: SlashingEvidenceKind,
+    offender: TAddr,
+    first_node: HotStuffTreeNode<TAddr, TPayload>,
+    second_node: HotStuffTreeNode<TAddr, TPayload>,
+    first_vote: Option<VoteMessage>,
+    second_vote: Option<VoteMessage>,
+    reported_by: TAddr,
+    reporter_signature: Option<Signature>,
+}
+
+impl<TAddr: NodeAddressable, TPayload: Payload> SlashingEvidence<TAddr, TPayload> {
+    pub fn double_proposal(
+        first_node: HotStuffTreeNode<TAddr, TPayload>,
+        second_node: HotStuffTreeNode<TAddr, TPayload>,
+        reported_by: TAddr,
+    ) -> Self {
+        let offender = first_node.proposed_by().clone();
+        Self::new(
+            SlashingEvidenceKind::DoubleProposal,
+            offender,
+            (first_node, None),
+            (second_node, None),
+            reported_by,
+        )
+    }
+
+    pub fn double_vote(
+        offender: TAddr,
+        (first_node, first_vote): (HotStuffTreeNode<TAddr, TPayload>, VoteMessage),
+        (second_node, second_vote): (HotStuffTreeNode<TAddr, TPayload>, VoteMessage),
+        reported_by: TAddr,
+    ) -> Self {
+        Self::new(
+            SlashingEvidenceKind::DoubleVote,
+            offender,
+            (first_node, Some(first_vote)),
+            (second_node, Some(second_vote)),
+            reported_by,
+        )
+    }
+
+    fn new(
+        kind: SlashingEvidenceKind,
+        offender: TAddr,
+        first: (HotStuffTreeNode<TAddr, TPayload>, Option<VoteMessage>),
+        second: (HotStuffTreeNode<TAddr, TPayload>, Option<VoteMessage>),
+        reported_by: TAddr,
+    ) -> Self {
+        // The nodes are ordered by hash, so that the evidence hash does not depend on the order the messages arrived in
+        let (first, second) = if first.0.hash().as_bytes() <= second.0.hash().as_bytes() {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        Self {
+            kind,
+            offender,
+            first_node: first.0.without_payload(),
+            second_node: second.0.without_payload(),
+            first_vote: first.1,
+            second_vote: second.1,
+            reported_by,
+            reporter_signature: None,
+        }
+    }
+
+    /// The hash that identifies the evidence and is signed by the reporter
+    pub fn hash(&self) -> FixedHash {
+        let hasher = tari_hasher::<TariDanCoreHashDomain>("slashing_evidence")
+            .chain(&[self.kind.as_u8()])
+            .chain(self.offender.as_bytes())
+            .chain(self.first_node.hash().as_bytes())
+            .chain(self.second_node.hash().as_bytes());
+        self.first_vote
+            .iter()
+            .chain(self.second_vote.iter())
+            .fold(hasher, |hasher, vote| {
+                hasher.chain(vote.construct_challenge().as_slice())
+            })
+            .result()
+    }
+
+    pub fn sign<TSigningService: SigningService>(
+        &mut self,
+        signing_service: &TSigningService,
+    ) -> Result<(), HotStuffError> {
+        let signature = signing_service
+            .sign(self.hash().as_slice())
+            .ok_or(HotStuffError::FailedToSignSlashingEvidence)?;
+        self.reporter_signature = Some(signature);
+        Ok(())
+    }
+
+    /// Checks that the evidence shows two different nodes in the same position that were both proposed, or voted for,
+    /// by the offender, that the offender signed both messages and that the evidence is signed by the reporter.
+    pub fn verify<TSigningService: SigningService>(
+        &self,
+        signing_service: &TSigningService,
+    ) -> Result<(), HotStuffError> {
+        for node in [&self.first_node, &self.second_node] {
+            if node.calculate_hash() != *node.hash() {
+                return Err(invalid(format!("node {} does not match its hash", node.hash())));
+            }
+        }
+        let (first, second) = (&self.first_node, &self.second_node);
+        if first.hash() == second.hash() {
+            return Err(invalid("the nodes are the same".to_string()));
+        }
+        if first.epoch() != second.epoch() ||
+            first.payload_id() != second.payload_id() ||
+            first.shard() != second.shard() ||
+            first.height() != second.height() ||
+            first.leader_round() != second.leader_round()
+        {
+            return Err(invalid("the nodes are not in the same position".to_string()));
+        }
+
+        match self.kind {
+            SlashingEvidenceKind::DoubleProposal => {
+                if *first.proposed_by() != self.offender || *second.proposed_by() != self.offender {
+                    return Err(invalid("the nodes were not both proposed by the offender".to_string()));
+                }
+                let offender_public_key = PublicKey::from_bytes(self.offender.as_bytes())
+                    .map_err(|_| invalid("offender is not a valid public key".to_string()))?;
+                for node in [first, second] {
+                    let is_signed_by_offender = node.proposer_signature().map_or(false, |signature| {
+                        signing_service.verify_for_public_key(&offender_public_key, signature, node.hash().as_bytes())
+                    });
+                    if !is_signed_by_offender {
+                        return Err(invalid(format!("node {} is not signed by the offender", node.hash())));
+                    }
+                }
+            },
+            SlashingEvidenceKind::DoubleVote => {
+                for (node, vote) in [(first, &self.first_vote), (second, &self.second_vote)] {
+                    let vote = vote
+                        .as_ref()
+                        .ok_or_else(|| invalid(format!("missing vote for node {}", node.hash())))?;
+                    if vote.local_node_hash() != *node.hash() {
+                        return Err(invalid(format!("vote is not for node {}", node.hash())));
+                    }
+                    let md = vote
+                        .try_validator_metadata()
+                        .ok_or_else(|| invalid(format!("vote for node {} is not signed", node.hash())))?;
+                    if NodeAddressable::as_bytes(&md.public_key) != self.offender.as_bytes() ||
+                        !signing_service.verify_for_public_key(
+                            &md.public_key,
+                            &md.signature,
+                            vote.construct_challenge().as_slice(),
+                        )
+                    {
+                        return Err(invalid(format!(
+                            "vote for node {} is not signed by the offender",
+                            node.hash()
+                        )));
+                    }
+                }
+            },
+        }
+
+        let reporter_public_key = PublicKey::from_bytes(self.reported_by.as_bytes())
+            .map_err(|_| invalid("reporter is not a valid public key".to_string()))?;
+        let is_signed_by_reporter = self.reporter_signature.as_ref().map_or(false, |signature| {
+            signing_service.verify_for_public_key(&reporter_public_key, signature, self.hash().as_slice())
+        });
+        if !is_signed_by_reporter {
+            return Err(invalid("evidence is not signed by the reporter".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+impl<TAddr, TPayload> SlashingEvidence<TAddr, TPayload> {
+    pub fn kind(&self) -> SlashingEvidenceKind {
+        self.kind
+    }
+
+    pub fn offender(&self) -> &TAddr {
+        &self.offender
+    }
+
+    pub fn nodes(&self) -> [&HotStuffTreeNode<TAddr, TPayload>; 2] {
+        [&self.first_node, &self.second_node]
+    }
+
+    pub fn votes(&self) -> Option<[&VoteMessage; 2]> {
+        Some([self.first_vote.as_ref()?, self.second_vote.as_ref()?])
+    }
+
+    pub fn reported_by(&self) -> &TAddr {
+        &self.reported_by
+    }
+
+    pub fn reporter_signature(&self) -> Option<&Signature> {
+        self.reporter_signature.as_ref()
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        self.first_node.epoch()
+    }
+
+    pub fn payload_id(&self) -> PayloadId {
+        self.first_node.payload_id()
+    }
+
+    pub fn shard(&self) -> ShardId {
+        self.first_node.shard()
+    }
+
+    pub fn height(&self) -> NodeHeight {
+        self.first_node.height()
+    }
+
+    pub fn leader_round(&self) -> u32 {
+        self.first_node.leader_round()
+    }
+}
+
+fn invalid(details: String) -> HotStuffError {
+    HotStuffError::InvalidSlashingEvidence(details)
+}
+
+/// Slashing evidence packaged for submission to the base layer, anchored to the base layer tip it was built at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashingEvidenceSubmission {
+    pub evidence_hash: FixedHash,
+    pub offender: PublicKey,
+    pub offender_shard_key: ShardId,
+    pub epoch: Epoch,
+    pub base_layer_height: u64,
+    pub base_layer_tip_hash: FixedHash,
+    /// The JSON encoded [SlashingEvidence]
+    pub evidence: Vec<u8>,
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_comms::{peer_manager::PeerFeatures, test_utils::node_identity::build_node_identity};
+    use tari_dan_common_types::{QuorumCertificate, TreeNodeHash};
+
+    use super::*;
+    use crate::{models::TariDanPayload, services::NodeIdentitySigningService};
+
+    fn create_node(
+        parent: u8,
+        leader_round: u32,
+        proposed_by: &PublicKey,
+        signer: &NodeIdentitySigningService,
+    ) -> HotStuffTreeNode<PublicKey, TariDanPayload> {
+        let payload_id = PayloadId::new([1u8; 32]);
+        let shard = ShardId([2u8; 32]);
+        let mut node = HotStuffTreeNode::new(
+            TreeNodeHash::from([parent; 32]),
+            shard,
+            NodeHeight(1),
+            payload_id,
+            None,
+            NodeHeight(1),
+            leader_round,
+            None,
+            Epoch(1),
+            proposed_by.clone(),
+            QuorumCertificate::genesis(Epoch(1), payload_id, shard),
+            FixedHash::zero(),
+        );
+        node.set_proposer_signature(signer.sign(node.hash().as_bytes()).unwrap());
+        node
+    }
+
+    #[test]
+    fn double_proposal() {
+        let leader = NodeIdentitySigningService::new(build_node_identity(PeerFeatures::COMMUNICATION_NODE));
+        let reporter = NodeIdentitySigningService::new(build_node_identity(PeerFeatures::COMMUNICATION_NODE));
+        let first = create_node(1, 0, leader.public_key(), &leader);
+        let second = create_node(2, 0, leader.public_key(), &leader);
+
+        let mut evidence =
+            SlashingEvidence::double_proposal(first.clone(), second.clone(), reporter.public_key().clone());
+        assert_eq!(evidence.offender(), leader.public_key());
+        // The evidence is the same regardless of the order the proposals were received in
+        let reversed = SlashingEvidence::double_proposal(second.clone(), first.clone(), reporter.public_key().clone());
+        assert_eq!(evidence.hash(), reversed.hash());
+
+        assert!(evidence.verify(&reporter).is_err());
+        evidence.sign(&reporter).unwrap();
+        evidence.verify(&reporter).unwrap();
+
+        // Proposals by different leaders are not evidence
+        let other_leader = create_node(2, 0, reporter.public_key(), &reporter);
+        let mut evidence =
+            SlashingEvidence::double_proposal(first.clone(), other_leader, reporter.public_key().clone());
+        evidence.sign(&reporter).unwrap();
+        assert!(evidence.verify(&reporter).is_err());
+
+        // The same proposal twice is not evidence
+        let mut evidence =
+            SlashingEvidence::double_proposal(first.clone(), first.clone(), reporter.public_key().clone());
+        evidence.sign(&reporter).unwrap();
+        assert!(evidence.verify(&reporter).is_err());
+
+        // Proposals in different leader rounds are not evidence, and the leader round cannot be changed without
+        // invalidating the proposer's signature
+        let next_round = create_node(2, 1, leader.public_key(), &leader);
+        assert_ne!(next_round.hash(), second.hash());
+        let mut evidence = SlashingEvidence::double_proposal(first.clone(), next_round, reporter.public_key().clone());
+        evidence.sign(&reporter).unwrap();
+        assert!(evidence.verify(&reporter).is_err());
+
+        // A proposal that was not signed by the leader is not evidence, even if it is signed by the reporter
+        let forged = create_node(2, 0, leader.public_key(), &reporter);
+        let mut evidence = SlashingEvidence::double_proposal(first, forged, reporter.public_key().clone());
+        evidence.sign(&reporter).unwrap();
+        assert!(evidence.verify(&reporter).is_err());
+    }
+}
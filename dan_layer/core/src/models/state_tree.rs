@@ -0,0 +1,95 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::ShardId;
+use tari_engine_types::hashing::{hasher, EngineHashDomainLabel};
+
+use crate::models::SubstateShardData;
+
+/// The maximum depth of the state tree, one level for every bit of a shard id
+pub const STATE_TREE_MAX_DEPTH: usize = 256;
+
+/// A node in the sparse Merkle tree that commits to the substates held by a validator node. Nodes are addressed by
+/// their hash. A subtree that contains a single substate is collapsed into a leaf, and empty subtrees are represented
+/// by the zero hash and never stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StateTreeNode {
+    Leaf { shard_id: ShardId, value_hash: FixedHash },
+    Branch { left: FixedHash, right: FixedHash },
+}
+
+impl StateTreeNode {
+    pub fn hash(&self) -> FixedHash {
+        match self {
+            Self::Leaf { shard_id, value_hash } => leaf_hash(shard_id, value_hash),
+            Self::Branch { left, right } => branch_hash(left, right),
+        }
+    }
+}
+
+/// Returns true if the bit of the shard id at the given depth is set, i.e. the shard id lies in the right subtree at
+/// that depth.
+pub fn is_right_at_depth(shard_id: &ShardId, depth: usize) -> bool {
+    shard_id.0[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+fn leaf_hash(shard_id: &ShardId, value_hash: &FixedHash) -> FixedHash {
+    hasher(EngineHashDomainLabel::StateTreeLeaf)
+        .chain(shard_id)
+        .chain(value_hash.as_slice())
+        .result()
+        .into_array()
+        .into()
+}
+
+fn branch_hash(left: &FixedHash, right: &FixedHash) -> FixedHash {
+    hasher(EngineHashDomainLabel::StateTreeBranch)
+        .chain(left.as_slice())
+        .chain(right.as_slice())
+        .result()
+        .into_array()
+        .into()
+}
+
+/// An inclusion proof for a substate in the state tree. The sibling hashes are ordered from the root down to the
+/// leaf.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateTreeProof {
+    siblings: Vec<FixedHash>,
+}
+
+impl StateTreeProof {
+    pub fn new(siblings: Vec<FixedHash>) -> Self {
+        Self { siblings }
+    }
+
+    pub fn siblings(&self) -> &[FixedHash] {
+        &self.siblings
+    }
+
+    /// Computes the root of a tree that contains the leaf for `shard_id` at the position described by this proof
+    pub fn compute_root(&self, shard_id: &ShardId, value_hash: &FixedHash) -> FixedHash {
+        self.siblings
+            .iter()
+            .enumerate()
+            .rev()
+            .fold(leaf_hash(shard_id, value_hash), |hash, (depth, sibling)| {
+                if is_right_at_depth(shard_id, depth) {
+                    branch_hash(sibling, &hash)
+                } else {
+                    branch_hash(&hash, sibling)
+                }
+            })
+    }
+
+    pub fn verify(&self, root: &FixedHash, shard_id: &ShardId, value_hash: &FixedHash) -> bool {
+        self.siblings.len() <= STATE_TREE_MAX_DEPTH && self.compute_root(shard_id, value_hash) == *root
+    }
+
+    /// Returns true if the substate is included, exactly as given, in the state tree with the given root
+    pub fn verify_substate(&self, root: &FixedHash, substate: &SubstateShardData) -> bool {
+        self.verify(root, &substate.shard_id(), &substate.state_tree_value_hash())
+    }
+}
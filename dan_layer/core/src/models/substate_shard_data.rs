@@ -2,6 +2,7 @@
 //   SPDX-License-Identifier: BSD-3-Clause
 
 use serde::{Deserialize, Serialize};
+use tari_common_types::types::FixedHash;
 use tari_dan_common_types::{
     quorum_certificate::QuorumCertificate,
     NodeHeight,
@@ -10,7 +11,10 @@ use tari_dan_common_types::{
     SubstateState,
     TreeNodeHash,
 };
-use tari_engine_types::substate::{Substate, SubstateAddress};
+use tari_engine_types::{
+    hashing::{hasher, EngineHashDomainLabel},
+    substate::{Substate, SubstateAddress},
+};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct SubstateShardData {
@@ -111,6 +115,19 @@ impl SubstateShardData {
         &self.destroyed_justify
     }
 
+    /// The hash committed to in the state tree. Only fields that are identical on every validator that holds the
+    /// substate are included, so the justifying QCs and node hashes are left out.
+    pub fn state_tree_value_hash(&self) -> FixedHash {
+        state_tree_value_hash(
+            &self.shard_id,
+            &self.address,
+            self.version,
+            &self.substate,
+            &self.created_payload_id,
+            &self.destroyed_payload_id,
+        )
+    }
+
     pub fn into_substate_state(self) -> SubstateState {
         if let Some(payload_id) = self.destroyed_payload_id() {
             SubstateState::Down { deleted_by: payload_id }
@@ -123,3 +140,25 @@ impl SubstateShardData {
         }
     }
 }
+
+/// Returns the hash committed to in the state tree for a substate with the given contents. This allows the value hash
+/// of a substate to be calculated before the substate is stored, e.g. for the state that a payload will leave it in.
+pub fn state_tree_value_hash(
+    shard_id: &ShardId,
+    address: &SubstateAddress,
+    version: u32,
+    substate: &Substate,
+    created_payload_id: &PayloadId,
+    destroyed_payload_id: &Option<PayloadId>,
+) -> FixedHash {
+    hasher(EngineHashDomainLabel::SubstateShardData)
+        .chain(shard_id)
+        .chain(address)
+        .chain(&version)
+        .chain(substate)
+        .chain(created_payload_id)
+        .chain(destroyed_payload_id)
+        .result()
+        .into_array()
+        .into()
+}
@@ -25,15 +25,22 @@ use std::fmt::Debug;
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::FixedHash;
-use tari_dan_common_types::ShardId;
+use tari_dan_common_types::{hashing::tari_hasher, ShardId};
 use tari_engine_types::commit_result::FinalizeResult;
 use tari_transaction::{ObjectClaim, SubstateChange, Transaction};
 
-use crate::models::{ConsensusHash, Payload};
+use crate::{
+    models::{ConsensusHash, Payload},
+    TariDanCoreHashDomain,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TariDanPayload {
     transaction: Transaction,
+    /// Further transactions that are decided together with `transaction` in a single consensus instance. Empty
+    /// unless the payload is a batch.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    batched_transactions: Vec<Transaction>,
     timestamp: i64,
     result: Option<FinalizeResult>,
 }
@@ -42,15 +49,57 @@ impl TariDanPayload {
     pub fn new(transaction: Transaction) -> Self {
         Self {
             transaction,
+            batched_transactions: Vec::new(),
             timestamp: Utc::now().timestamp(),
             result: None,
         }
     }
 
+    /// Creates a payload that batches the given transactions into a single consensus instance. A batch of one
+    /// transaction is identical to a payload created with [TariDanPayload::new].
+    ///
+    /// # Panics
+    /// Panics if `transactions` is empty
+    pub fn new_batch(mut transactions: Vec<Transaction>) -> Self {
+        assert!(
+            !transactions.is_empty(),
+            "A payload batch must contain at least one transaction"
+        );
+        let transaction = transactions.remove(0);
+        Self {
+            transaction,
+            batched_transactions: transactions,
+            timestamp: Utc::now().timestamp(),
+            result: None,
+        }
+    }
+
+    /// The first (or only) transaction of the payload
     pub fn transaction(&self) -> &Transaction {
         &self.transaction
     }
 
+    /// The transactions batched after the first transaction
+    pub fn batched_transactions(&self) -> &[Transaction] {
+        &self.batched_transactions
+    }
+
+    /// All transactions in the payload, in execution order
+    pub fn transactions(&self) -> impl Iterator<Item = &Transaction> + '_ {
+        Some(&self.transaction).into_iter().chain(&self.batched_transactions)
+    }
+
+    pub fn into_transactions(self) -> Vec<Transaction> {
+        let mut transactions = Vec::with_capacity(self.batched_transactions.len() + 1);
+        transactions.push(self.transaction);
+        transactions.extend(self.batched_transactions);
+        transactions
+    }
+
+    pub fn is_batch(&self) -> bool {
+        !self.batched_transactions.is_empty()
+    }
+
     pub fn into_payload(self) -> Transaction {
         self.transaction
     }
@@ -69,22 +118,58 @@ impl TariDanPayload {
 }
 
 impl ConsensusHash for TariDanPayload {
+    /// A single transaction payload is identified by the transaction hash, a batch by the hash of all transaction
+    /// hashes in the batch.
     fn consensus_hash(&self) -> FixedHash {
-        self.transaction.hash().into_array().into()
+        if !self.is_batch() {
+            return self.transaction.hash().into_array().into();
+        }
+
+        self.transactions()
+            .fold(
+                tari_hasher::<TariDanCoreHashDomain>("payload_batch"),
+                |hasher, transaction| hasher.chain(transaction.hash()),
+            )
+            .result()
     }
 }
 
 impl Payload for TariDanPayload {
     fn involved_shards(&self) -> Vec<ShardId> {
-        self.transaction.meta().involved_shards()
+        let mut shards = self.transaction.meta().involved_shards();
+        for transaction in &self.batched_transactions {
+            for shard in transaction.meta().involved_shards() {
+                if !shards.contains(&shard) {
+                    shards.push(shard);
+                }
+            }
+        }
+        shards
     }
 
     fn objects_for_shard(&self, shard: ShardId) -> Option<(SubstateChange, ObjectClaim)> {
-        self.transaction.meta().objects_for_shard(shard)
+        self.transactions()
+            .find_map(|transaction| transaction.meta().objects_for_shard(shard))
     }
 
     fn max_outputs(&self) -> u32 {
-        self.transaction.meta().max_outputs()
+        self.transactions()
+            .map(|transaction| transaction.meta().max_outputs())
+            .sum()
+    }
+
+    fn batched_payloads(&self) -> Vec<Self> {
+        if !self.is_batch() {
+            return Vec::new();
+        }
+        self.transactions()
+            .map(|transaction| Self {
+                transaction: transaction.clone(),
+                batched_transactions: Vec::new(),
+                timestamp: self.timestamp,
+                result: None,
+            })
+            .collect()
     }
 }
 
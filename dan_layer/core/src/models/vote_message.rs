@@ -148,6 +148,10 @@ impl VoteMessage {
         self.validator_metadata.as_ref().unwrap()
     }
 
+    pub fn try_validator_metadata(&self) -> Option<&ValidatorMetadata> {
+        self.validator_metadata.as_ref()
+    }
+
     pub fn local_node_hash(&self) -> TreeNodeHash {
         self.local_node_hash
     }
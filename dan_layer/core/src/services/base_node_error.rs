@@ -20,7 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use tari_common_types::types::FixedHashSizeError;
+use tari_common_types::types::{FixedHashSizeError, PublicKey};
 use tari_dan_common_types::optional::IsNotFoundError;
 use thiserror::Error;
 
@@ -37,6 +37,10 @@ pub enum BaseNodeError {
     InvalidPeerMessage(String),
     #[error("Hash size error: {0}")]
     HashSizeError(#[from] FixedHashSizeError),
+    #[error("Validator node {public_key} is not registered at height {height}")]
+    ValidatorNodeNotRegistered { public_key: PublicKey, height: u64 },
+    #[error("Failed to encode submission: {0}")]
+    EncodingError(String),
 }
 
 impl IsNotFoundError for BaseNodeError {
@@ -31,7 +31,7 @@ use thiserror::Error;
 
 use crate::{
     models::{Committee, ValidatorNode},
-    services::{base_node_error::BaseNodeError, ValidatorNodeClientError},
+    services::{base_node_error::BaseNodeError, QuorumCertificateError, ValidatorNodeClientError},
     storage::StorageError,
 };
 
@@ -77,6 +77,10 @@ pub enum EpochManagerError {
     ShardKeyNotFound { public_key: PublicKey, block_height: u64 },
     #[error("Received invalid state sync data from peer:{0}")]
     InvalidStateSyncData(#[from] anyhow::Error),
+    #[error("Not enough committee members agreed on the state sync chunk starting at shard {start_shard_id}")]
+    StateSyncNoQuorum { start_shard_id: ShardId },
+    #[error("Invalid quorum certificate: {0}")]
+    InvalidQuorumCertificate(QuorumCertificateError),
 }
 
 impl<T: Into<StorageError>> From<T> for EpochManagerError {
@@ -117,6 +121,12 @@ pub trait EpochManager<TAddr: NodeAddressable>: Clone {
         -> Result<Vec<ValidatorNode<TAddr>>, EpochManagerError>;
     async fn get_validator_node_bmt(&self, epoch: Epoch) -> Result<ValidatorNodeBMT, EpochManagerError>;
     async fn get_validator_node_merkle_root(&self, epoch: Epoch) -> Result<Vec<u8>, EpochManagerError>;
+    /// Returns the timestamp of the base layer block that started the given epoch
+    async fn get_epoch_timestamp(&self, epoch: Epoch) -> Result<u64, EpochManagerError>;
+    /// Returns the randomness beacon for the given epoch. The beacon is derived from the hash of the base layer block
+    /// that started the epoch and the validator node merkle root. It cannot be predicted before the epoch begins, but
+    /// anyone can compute it once the epoch has started, so it is not a committee-derived (VRF) beacon.
+    async fn get_epoch_beacon(&self, epoch: Epoch) -> Result<[u8; 32], EpochManagerError>;
 
     // TODO: Should be part of VN state machine
     async fn notify_scanning_complete(&self) -> Result<(), EpochManagerError>;
@@ -309,6 +319,14 @@ impl<TAddr: NodeAddressable> EpochManager<TAddr> for RangeEpochManager<TAddr> {
         Ok(vn_mmr.get_merkle_root())
     }
 
+    async fn get_epoch_timestamp(&self, _epoch: Epoch) -> Result<u64, EpochManagerError> {
+        Ok(0)
+    }
+
+    async fn get_epoch_beacon(&self, _epoch: Epoch) -> Result<[u8; 32], EpochManagerError> {
+        Ok([0u8; 32])
+    }
+
     async fn notify_scanning_complete(&self) -> Result<(), EpochManagerError> {
         Ok(())
     }
@@ -20,40 +20,65 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+    sync::Mutex,
+};
+
 use digest::Digest;
+use serde::{Deserialize, Serialize};
 use tari_common_types::types::FixedHash;
 use tari_crypto::hash::blake2::Blake256;
-use tari_dan_common_types::{NodeAddressable, PayloadId, ShardId};
+use tari_dan_common_types::{Epoch, NodeAddressable, PayloadId, ShardId};
+
+use crate::{
+    models::Committee,
+    storage::{
+        shard_store::{ShardStore, ShardStoreReadTransaction},
+        StorageError,
+    },
+};
 
-use crate::models::Committee;
+/// The number of previous epochs whose leader failures are counted by the [ReputationLeaderStrategy]
+pub const LEADER_REPUTATION_WINDOW_EPOCHS: u64 = 10;
 
 pub trait LeaderStrategy<TAddr: NodeAddressable> {
-    fn calculate_leader(&self, committee: &Committee<TAddr>, payload: PayloadId, shard: ShardId, round: u32) -> u32;
+    fn calculate_leader(
+        &self,
+        epoch: Epoch,
+        committee: &Committee<TAddr>,
+        payload: PayloadId,
+        shard: ShardId,
+        round: u32,
+    ) -> Result<u32, StorageError>;
     fn is_leader(
         &self,
         node: &TAddr,
+        epoch: Epoch,
         committee: &Committee<TAddr>,
         payload: PayloadId,
         shard: ShardId,
         round: u32,
-    ) -> bool {
-        let position = self.calculate_leader(committee, payload, shard, round);
+    ) -> Result<bool, StorageError> {
+        let position = self.calculate_leader(epoch, committee, payload, shard, round)?;
         if let Some(index) = committee.members.iter().position(|m| m == node) {
-            position == index as u32
+            Ok(position == index as u32)
         } else {
-            false
+            Ok(false)
         }
     }
 
     fn get_leader<'a, 'b>(
         &'a self,
+        epoch: Epoch,
         committee: &'b Committee<TAddr>,
         payload: PayloadId,
         shard: ShardId,
         round: u32,
-    ) -> &'b TAddr {
-        let index = self.calculate_leader(committee, payload, shard, round);
-        committee.members.get(index as usize).unwrap()
+    ) -> Result<&'b TAddr, StorageError> {
+        let index = self.calculate_leader(epoch, committee, payload, shard, round)?;
+        Ok(committee.members.get(index as usize).unwrap())
     }
 }
 
@@ -62,37 +87,189 @@ pub struct AlwaysFirstLeader {}
 impl<TAddr: NodeAddressable> LeaderStrategy<TAddr> for AlwaysFirstLeader {
     fn calculate_leader(
         &self,
+        _epoch: Epoch,
         _committee: &Committee<TAddr>,
         _payload: PayloadId,
         _shard: ShardId,
         _round: u32,
-    ) -> u32 {
-        0
+    ) -> Result<u32, StorageError> {
+        Ok(0)
     }
 }
 
 pub struct RotatingLeader {}
 
 impl<TAddr: NodeAddressable> LeaderStrategy<TAddr> for RotatingLeader {
-    fn calculate_leader(&self, committee: &Committee<TAddr>, _payload: PayloadId, _shard: ShardId, round: u32) -> u32 {
-        round % (committee.len() as u32)
+    fn calculate_leader(
+        &self,
+        _epoch: Epoch,
+        committee: &Committee<TAddr>,
+        _payload: PayloadId,
+        _shard: ShardId,
+        round: u32,
+    ) -> Result<u32, StorageError> {
+        Ok(round % (committee.len() as u32))
     }
 }
 
 pub struct PayloadSpecificLeaderStrategy {}
 
 impl<TAddr: NodeAddressable> LeaderStrategy<TAddr> for PayloadSpecificLeaderStrategy {
-    fn calculate_leader(&self, committee: &Committee<TAddr>, payload: PayloadId, shard: ShardId, round: u32) -> u32 {
+    fn calculate_leader(
+        &self,
+        _epoch: Epoch,
+        committee: &Committee<TAddr>,
+        payload: PayloadId,
+        shard: ShardId,
+        round: u32,
+    ) -> Result<u32, StorageError> {
         // TODO: Maybe Committee should not be able to be constructed with an empty committee
         assert!(!committee.is_empty(), "Committee was empty in calculate_leader");
-        // Perhaps a less heavy hasher in future?
-        let hash: FixedHash = Blake256::new()
-            .chain(payload.as_bytes())
-            .chain(shard.as_bytes())
-            .finalize()
-            .into();
-        let hash = u32::from_le_bytes([hash[0], hash[1], hash[2], hash[3]]);
-        let first = hash % committee.members.len() as u32;
-        (first + round) % committee.members.len() as u32
+        let first = payload_specific_first_leader(payload, shard, committee.members.len() as u32);
+        Ok((first + round) % committee.members.len() as u32)
+    }
+}
+
+fn payload_specific_first_leader(payload: PayloadId, shard: ShardId, committee_size: u32) -> u32 {
+    // Perhaps a less heavy hasher in future?
+    let hash: FixedHash = Blake256::new()
+        .chain(payload.as_bytes())
+        .chain(shard.as_bytes())
+        .finalize()
+        .into();
+    let hash = u32::from_le_bytes([hash[0], hash[1], hash[2], hash[3]]);
+    hash % committee_size
+}
+
+type FailureCounts<TAddr> = HashMap<FixedHash, HashMap<TAddr, u64>>;
+
+/// Orders the committee by the number of times each member failed to propose as leader in the committed nodes of
+/// the previous [LEADER_REPUTATION_WINDOW_EPOCHS] epochs, so that members that recently failed are only chosen once
+/// every other member has had a turn. Members with the same number of failures keep the order of the
+/// [PayloadSpecificLeaderStrategy].
+///
+/// Only failures committed by a committee with the same members are counted, and failures in the current epoch are
+/// ignored, so that all members of a committee calculate the same leaders.
+pub struct ReputationLeaderStrategy<TShardStore: ShardStore> {
+    shard_store: TShardStore,
+    failure_counts: Mutex<Option<(Epoch, FailureCounts<TShardStore::Addr>)>>,
+}
+
+impl<TShardStore: ShardStore> ReputationLeaderStrategy<TShardStore> {
+    pub fn new(shard_store: TShardStore) -> Self {
+        Self {
+            shard_store,
+            failure_counts: Mutex::new(None),
+        }
+    }
+
+    fn load_failure_counts(&self, epoch: Epoch) -> Result<FailureCounts<TShardStore::Addr>, StorageError> {
+        let mut counts = FailureCounts::new();
+        if epoch.as_u64() == 0 {
+            return Ok(counts);
+        }
+        let start_epoch = Epoch(epoch.as_u64().saturating_sub(LEADER_REPUTATION_WINDOW_EPOCHS));
+        let end_epoch = Epoch(epoch.as_u64() - 1);
+        let failures = self
+            .shard_store
+            .with_read_tx(|tx| tx.get_leader_failures(start_epoch, end_epoch))?;
+        for failure in failures {
+            *counts
+                .entry(failure.committee_hash)
+                .or_default()
+                .entry(failure.leader)
+                .or_default() += 1;
+        }
+        Ok(counts)
+    }
+}
+
+impl<TShardStore: ShardStore> LeaderStrategy<TShardStore::Addr> for ReputationLeaderStrategy<TShardStore> {
+    fn calculate_leader(
+        &self,
+        epoch: Epoch,
+        committee: &Committee<TShardStore::Addr>,
+        payload: PayloadId,
+        shard: ShardId,
+        round: u32,
+    ) -> Result<u32, StorageError> {
+        assert!(!committee.is_empty(), "Committee was empty in calculate_leader");
+        let committee_size = committee.members.len() as u32;
+        let first = payload_specific_first_leader(payload, shard, committee_size);
+
+        let mut failure_counts = self.failure_counts.lock().expect("failure_counts mutex poisoned");
+        // Failure counts that could not be loaded are not cached, so that loading is retried on the next call
+        if !matches!(&*failure_counts, Some((cached_epoch, _)) if *cached_epoch == epoch) {
+            *failure_counts = Some((epoch, self.load_failure_counts(epoch)?));
+        }
+        let (_, counts) = failure_counts.as_ref().expect("failure counts were just loaded");
+
+        let mut order = (0..committee_size)
+            .map(|i| (first + i) % committee_size)
+            .collect::<Vec<_>>();
+        if let Some(counts) = counts.get(&committee.members_hash()) {
+            // The sort is stable, so members with the same number of failures keep the payload specific order
+            order.sort_by_key(|index| counts.get(&committee.members[*index as usize]).copied().unwrap_or(0));
+        }
+        Ok(order[(round % committee_size) as usize])
+    }
+}
+
+/// The leader strategy used by consensus
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderStrategyType {
+    AlwaysFirst,
+    Rotating,
+    #[default]
+    PayloadSpecific,
+    Reputation,
+}
+
+impl Display for LeaderStrategyType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LeaderStrategyType::AlwaysFirst => write!(f, "always_first"),
+            LeaderStrategyType::Rotating => write!(f, "rotating"),
+            LeaderStrategyType::PayloadSpecific => write!(f, "payload_specific"),
+            LeaderStrategyType::Reputation => write!(f, "reputation"),
+        }
+    }
+}
+
+/// A leader strategy that is chosen at runtime
+pub enum ConfiguredLeaderStrategy<TShardStore: ShardStore> {
+    AlwaysFirst(AlwaysFirstLeader),
+    Rotating(RotatingLeader),
+    PayloadSpecific(PayloadSpecificLeaderStrategy),
+    Reputation(ReputationLeaderStrategy<TShardStore>),
+}
+
+impl<TShardStore: ShardStore> ConfiguredLeaderStrategy<TShardStore> {
+    pub fn new(strategy_type: LeaderStrategyType, shard_store: TShardStore) -> Self {
+        match strategy_type {
+            LeaderStrategyType::AlwaysFirst => Self::AlwaysFirst(AlwaysFirstLeader {}),
+            LeaderStrategyType::Rotating => Self::Rotating(RotatingLeader {}),
+            LeaderStrategyType::PayloadSpecific => Self::PayloadSpecific(PayloadSpecificLeaderStrategy {}),
+            LeaderStrategyType::Reputation => Self::Reputation(ReputationLeaderStrategy::new(shard_store)),
+        }
+    }
+}
+
+impl<TShardStore: ShardStore> LeaderStrategy<TShardStore::Addr> for ConfiguredLeaderStrategy<TShardStore> {
+    fn calculate_leader(
+        &self,
+        epoch: Epoch,
+        committee: &Committee<TShardStore::Addr>,
+        payload: PayloadId,
+        shard: ShardId,
+        round: u32,
+    ) -> Result<u32, StorageError> {
+        match self {
+            Self::AlwaysFirst(strategy) => strategy.calculate_leader(epoch, committee, payload, shard, round),
+            Self::Rotating(strategy) => strategy.calculate_leader(epoch, committee, payload, shard, round),
+            Self::PayloadSpecific(strategy) => strategy.calculate_leader(epoch, committee, payload, shard, round),
+            Self::Reputation(strategy) => strategy.calculate_leader(epoch, committee, payload, shard, round),
+        }
     }
 }
@@ -32,7 +32,12 @@ pub use base_node_client::{BaseNodeClient, BlockInfo, SideChainUtxos};
 pub use events_publisher::{EventsPublisher, LoggingEventsPublisher};
 pub use payload_processor::{PayloadProcessor, PayloadProcessorError};
 pub use peer_service::{DanPeer, PeerProvider};
-pub use signing_service::{NodeIdentitySigningService, SigningService};
+pub use signing_service::{
+    verify_quorum_certificate,
+    NodeIdentitySigningService,
+    QuorumCertificateError,
+    SigningService,
+};
 pub use template_provider::TemplateProvider;
 pub mod base_node_error;
 pub mod epoch_manager;
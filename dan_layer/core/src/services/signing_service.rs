@@ -22,20 +22,37 @@
 
 use std::sync::Arc;
 
-use tari_common_types::types::{FixedHash, PublicKey};
+use tari_common_types::types::{FixedHash, PrivateKey, PublicKey};
 use tari_comms::{
     types::{CommsPublicKey, Signature},
     NodeIdentity,
 };
-use tari_dan_common_types::{crypto::create_key_pair, hashing::tari_hasher};
+use tari_crypto::keys::PublicKey as PublicKeyT;
+use tari_dan_common_types::{
+    crypto::create_key_pair,
+    hashing::tari_hasher,
+    AggregatedSignature,
+    NodeAddressable,
+    QuorumCertificate,
+    SignerBitmap,
+};
 use tari_utilities::ByteArray;
+use thiserror::Error;
 
-use crate::TariDanCoreHashDomain;
+use crate::{
+    models::{vote_message::VoteMessage, Committee},
+    TariDanCoreHashDomain,
+};
 
 pub trait SigningService {
     fn sign(&self, challenge: &[u8]) -> Option<Signature>;
     fn verify(&self, signature: &Signature, challenge: &[u8]) -> bool;
     fn verify_for_public_key(&self, public_key: &PublicKey, signature: &Signature, challenge: &[u8]) -> bool;
+    /// Combines signatures over the same challenge, given as (leaf index, public key, signature), into a single
+    /// aggregated signature. Returns None if the same leaf index is given more than once.
+    fn aggregate(&self, signatures: Vec<(u64, PublicKey, Signature)>, challenge: &[u8]) -> Option<AggregatedSignature>;
+    /// Verifies an aggregated signature. The public keys must be given in the order of the signer leaf indices.
+    fn verify_aggregate(&self, public_keys: &[PublicKey], signature: &AggregatedSignature, challenge: &[u8]) -> bool;
     fn public_key(&self) -> &CommsPublicKey;
 }
 
@@ -56,6 +73,26 @@ impl NodeIdentitySigningService {
             .chain(challenge)
             .result()
     }
+
+    /// Each partial signature is weighted by a coefficient that commits to every signer and nonce, so that a signer
+    /// cannot choose their key or nonce to cancel out the contributions of the others.
+    fn aggregation_coefficients(
+        public_keys: &[PublicKey],
+        public_nonces: &[PublicKey],
+        challenge: &[u8],
+    ) -> Option<Vec<PrivateKey>> {
+        let mut hasher = tari_hasher::<TariDanCoreHashDomain>("signing_service_aggregate")
+            .chain(challenge)
+            .chain(&(public_keys.len() as u64));
+        for (public_key, public_nonce) in public_keys.iter().zip(public_nonces) {
+            hasher.update(&key_to_fixed_bytes(public_key));
+            hasher.update(&key_to_fixed_bytes(public_nonce));
+        }
+
+        (0..public_keys.len() as u64)
+            .map(|i| hash_to_scalar(hasher.clone().chain(&i).result()))
+            .collect()
+    }
 }
 
 impl SigningService for NodeIdentitySigningService {
@@ -75,11 +112,133 @@ impl SigningService for NodeIdentitySigningService {
         signature.verify_challenge(public_key, &*challenge)
     }
 
+    fn aggregate(
+        &self,
+        mut signatures: Vec<(u64, PublicKey, Signature)>,
+        challenge: &[u8],
+    ) -> Option<AggregatedSignature> {
+        signatures.sort_by_key(|(index, _, _)| *index);
+        let signers = SignerBitmap::from_indices(signatures.iter().map(|(index, _, _)| *index));
+        if signers.count() != signatures.len() {
+            return None;
+        }
+
+        let (public_keys, public_nonces): (Vec<_>, Vec<_>) = signatures
+            .iter()
+            .map(|(_, public_key, signature)| (public_key.clone(), signature.get_public_nonce().clone()))
+            .unzip();
+        let coefficients = Self::aggregation_coefficients(&public_keys, &public_nonces, challenge)?;
+        let signature = signatures
+            .iter()
+            .zip(coefficients)
+            .fold(PrivateKey::default(), |acc, ((_, _, signature), z)| {
+                acc + &z * signature.get_signature()
+            });
+
+        Some(AggregatedSignature::new(signers, public_nonces, signature))
+    }
+
+    fn verify_aggregate(&self, public_keys: &[PublicKey], signature: &AggregatedSignature, challenge: &[u8]) -> bool {
+        let public_nonces = signature.public_nonces();
+        if public_keys.is_empty() ||
+            public_keys.len() != public_nonces.len() ||
+            public_keys.len() != signature.num_signers()
+        {
+            return false;
+        }
+        let coefficients = match Self::aggregation_coefficients(public_keys, public_nonces, challenge) {
+            Some(c) => c,
+            None => return false,
+        };
+
+        // s*G == sum(z_i*R_i + z_i*e_i*P_i), where e_i is the challenge each signer signed
+        let mut expected = None;
+        for ((public_key, public_nonce), z) in public_keys.iter().zip(public_nonces).zip(coefficients) {
+            let e = match hash_to_scalar(Self::create_challenge(public_nonce, public_key, challenge)) {
+                Some(e) => e,
+                None => return false,
+            };
+            let term = &z * public_nonce + &(&z * &e) * public_key;
+            expected = Some(match expected {
+                Some(acc) => acc + term,
+                None => term,
+            });
+        }
+
+        expected == Some(PublicKey::from_secret_key(signature.signature()))
+    }
+
     fn public_key(&self) -> &CommsPublicKey {
         self.node_identity.public_key()
     }
 }
 
+#[derive(Debug, Error)]
+pub enum QuorumCertificateError {
+    #[error("insufficient quorum")]
+    InsufficientQuorum,
+    #[error("signer leaf index {0} is out of range")]
+    SignerIndexOutOfRange(u64),
+    #[error("some signers are not in committee")]
+    SignerNotInCommittee,
+    #[error("invalid signer public key")]
+    InvalidSignerPublicKey,
+    #[error("invalid signature")]
+    InvalidSignature,
+}
+
+/// Checks that a QC is signed by at least `min_signers` members of the committee. The signers are identified by their
+/// leaf index in the epoch's validator node BMT, which is built from `epoch_validator_nodes` in order, so no merkle
+/// proofs are needed.
+pub fn verify_quorum_certificate<TAddr: NodeAddressable, TSigningService: SigningService + ?Sized>(
+    signing_service: &TSigningService,
+    qc: &QuorumCertificate,
+    epoch_validator_nodes: &[TAddr],
+    committee: &Committee<TAddr>,
+    min_signers: usize,
+) -> Result<(), QuorumCertificateError> {
+    let aggregated_signature = qc.aggregated_signature();
+
+    // the bitmap cannot contain duplicate signers
+    let num_signers = aggregated_signature.num_signers();
+    if num_signers < min_signers {
+        return Err(QuorumCertificateError::InsufficientQuorum);
+    }
+    if num_signers == 0 {
+        return Ok(());
+    }
+
+    let signers = aggregated_signature
+        .signers()
+        .indices()
+        .map(|i| {
+            epoch_validator_nodes
+                .get(i as usize)
+                .ok_or(QuorumCertificateError::SignerIndexOutOfRange(i))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !signers.iter().all(|s| committee.contains(s)) {
+        return Err(QuorumCertificateError::SignerNotInCommittee);
+    }
+
+    let public_keys = signers
+        .iter()
+        .map(|s| PublicKey::from_bytes(s.as_bytes()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| QuorumCertificateError::InvalidSignerPublicKey)?;
+    let vote = VoteMessage::new(qc.node_hash(), *qc.decision(), qc.all_shard_pledges().clone());
+    if !signing_service.verify_aggregate(&public_keys, aggregated_signature, &*vote.construct_challenge()) {
+        return Err(QuorumCertificateError::InvalidSignature);
+    }
+
+    Ok(())
+}
+
+fn hash_to_scalar(hash: FixedHash) -> Option<PrivateKey> {
+    PrivateKey::from_bytes(hash.as_slice()).ok()
+}
+
 // TODO: this wont be required when borsh support is added to tari crypto
 fn key_to_fixed_bytes(key: &CommsPublicKey) -> [u8; 32] {
     let mut buf = [0u8; 32];
@@ -90,6 +249,15 @@ fn key_to_fixed_bytes(key: &CommsPublicKey) -> [u8; 32] {
 #[cfg(test)]
 mod tests {
     use tari_comms::{peer_manager::PeerFeatures, test_utils::node_identity::build_node_identity};
+    use tari_dan_common_types::{
+        Epoch,
+        NodeHeight,
+        PayloadId,
+        QuorumDecision,
+        ShardId,
+        ShardPledgeCollection,
+        TreeNodeHash,
+    };
 
     use super::*;
 
@@ -101,4 +269,106 @@ mod tests {
         let signature = signing_service.sign(challenge).unwrap();
         assert!(signing_service.verify(&signature, challenge));
     }
+
+    #[test]
+    fn aggregate() {
+        let signing_services = (0..4)
+            .map(|_| NodeIdentitySigningService::new(build_node_identity(PeerFeatures::COMMUNICATION_NODE)))
+            .collect::<Vec<_>>();
+        let challenge = b"challenge";
+        // Leaf indices given out of order
+        let signatures = signing_services
+            .iter()
+            .zip([5u64, 1, 9, 2])
+            .map(|(s, i)| (i, s.public_key().clone(), s.sign(challenge).unwrap()))
+            .collect::<Vec<_>>();
+
+        let aggregated = signing_services[0].aggregate(signatures.clone(), challenge).unwrap();
+        assert_eq!(aggregated.signers().indices().collect::<Vec<_>>(), vec![1, 2, 5, 9]);
+        let public_keys = [1, 3, 0, 2]
+            .iter()
+            .map(|i| signing_services[*i].public_key().clone())
+            .collect::<Vec<_>>();
+        assert!(signing_services[0].verify_aggregate(&public_keys, &aggregated, challenge));
+
+        assert!(!signing_services[0].verify_aggregate(&public_keys, &aggregated, b"other"));
+        let mut wrong_order = public_keys.clone();
+        wrong_order.swap(0, 1);
+        assert!(!signing_services[0].verify_aggregate(&wrong_order, &aggregated, challenge));
+        // A signature from a non-signer does not verify
+        let other = NodeIdentitySigningService::new(build_node_identity(PeerFeatures::COMMUNICATION_NODE));
+        let mut with_other = public_keys;
+        with_other[3] = other.public_key().clone();
+        assert!(!signing_services[0].verify_aggregate(&with_other, &aggregated, challenge));
+
+        // Duplicate signers are rejected
+        let mut duplicated = signatures;
+        duplicated[1].0 = 5;
+        assert!(signing_services[0].aggregate(duplicated, challenge).is_none());
+    }
+
+    #[test]
+    fn it_verifies_quorum_certificates_against_the_committee() {
+        let signing_services = (0..4)
+            .map(|_| NodeIdentitySigningService::new(build_node_identity(PeerFeatures::COMMUNICATION_NODE)))
+            .collect::<Vec<_>>();
+        let epoch_vns = signing_services
+            .iter()
+            .map(|s| s.public_key().clone())
+            .collect::<Vec<_>>();
+        let create_qc = |signers: &[usize]| {
+            let vote = VoteMessage::new(
+                TreeNodeHash::zero(),
+                QuorumDecision::Accept,
+                ShardPledgeCollection::empty(),
+            );
+            let challenge = vote.construct_challenge();
+            let signatures = signers
+                .iter()
+                .map(|i| {
+                    let s = &signing_services[*i];
+                    (*i as u64, s.public_key().clone(), s.sign(&*challenge).unwrap())
+                })
+                .collect();
+            QuorumCertificate::new(
+                PayloadId::new([0u8; 32]),
+                NodeHeight(0),
+                TreeNodeHash::zero(),
+                NodeHeight(1),
+                ShardId::zero(),
+                Epoch(0),
+                QuorumDecision::Accept,
+                ShardPledgeCollection::empty(),
+                signing_services[0].aggregate(signatures, &*challenge).unwrap(),
+            )
+        };
+        let committee = Committee::new(epoch_vns[..3].to_vec());
+
+        let qc = create_qc(&[0, 1]);
+        verify_quorum_certificate(&signing_services[0], &qc, &epoch_vns, &committee, 2).unwrap();
+        assert!(matches!(
+            verify_quorum_certificate(&signing_services[0], &qc, &epoch_vns, &committee, 3),
+            Err(QuorumCertificateError::InsufficientQuorum)
+        ));
+        assert!(matches!(
+            verify_quorum_certificate(&signing_services[0], &qc, &epoch_vns[..1], &committee, 2),
+            Err(QuorumCertificateError::SignerIndexOutOfRange(1))
+        ));
+
+        // The last validator node is not a member of the committee
+        let qc = create_qc(&[0, 3]);
+        assert!(matches!(
+            verify_quorum_certificate(&signing_services[0], &qc, &epoch_vns, &committee, 2),
+            Err(QuorumCertificateError::SignerNotInCommittee)
+        ));
+
+        // Signers mapped to the wrong keys do not verify
+        let mut swapped = epoch_vns.clone();
+        swapped.swap(0, 2);
+        let qc = create_qc(&[0, 1]);
+        assert!(matches!(
+            verify_quorum_certificate(&signing_services[0], &qc, &swapped, &committee, 2),
+            Err(QuorumCertificateError::InvalidSignature)
+        ));
+    }
 }
@@ -27,4 +27,6 @@ pub trait TemplateProvider {
     type Error: std::error::Error + Sync + Send + 'static;
 
     fn get_template_module(&self, id: &TemplateAddress) -> Result<Self::Template, Self::Error>;
+    /// Returns the address of the template that the given template is registered as an upgrade of, if any
+    fn get_template_predecessor(&self, id: &TemplateAddress) -> Result<Option<TemplateAddress>, Self::Error>;
 }
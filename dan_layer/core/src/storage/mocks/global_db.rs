@@ -70,6 +70,15 @@ impl GlobalDbAdapter for MockGlobalDbBackupAdapter {
         todo!()
     }
 
+    fn get_latest_template_version(
+        &self,
+        _tx: &mut Self::DbTransaction<'_>,
+        _author_public_key: &[u8],
+        _template_name: &str,
+    ) -> Result<Option<DbTemplate>, Self::Error> {
+        todo!()
+    }
+
     fn insert_template(&self, _tx: &mut Self::DbTransaction<'_>, _template: DbTemplate) -> Result<(), Self::Error> {
         todo!()
     }
@@ -28,3 +28,4 @@ pub use db_factory::DbFactory;
 
 pub mod mocks;
 pub mod shard_store;
+pub mod state_tree;
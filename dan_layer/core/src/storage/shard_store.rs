@@ -22,7 +22,9 @@
 
 use std::ops::{Deref, DerefMut};
 
+use tari_common_types::types::FixedHash;
 use tari_dan_common_types::{
+    Epoch,
     NodeAddressable,
     NodeHeight,
     ObjectPledge,
@@ -33,7 +35,10 @@ use tari_dan_common_types::{
     SubstateState,
     TreeNodeHash,
 };
-use tari_engine_types::substate::{Substate, SubstateAddress};
+use tari_engine_types::{
+    events::Event,
+    substate::{Substate, SubstateAddress},
+};
 use thiserror::Error;
 
 use crate::{
@@ -41,12 +46,17 @@ use crate::{
         vote_message::VoteMessage,
         CurrentLeaderStates,
         HotStuffTreeNode,
+        LeaderFailure,
         LeafNode,
+        MempoolTransaction,
         Payload,
         PayloadResult,
+        PrunedRecordCounts,
         RecentTransaction,
         SQLSubstate,
         SQLTransaction,
+        SlashingEvidence,
+        StateTreeProof,
         SubstateShardData,
     },
     storage::StorageError,
@@ -135,6 +145,13 @@ pub trait ShardStoreReadTransaction<TAddr: NodeAddressable, TPayload: Payload> {
         end_shard_id: ShardId,
         excluded_shards: &[ShardId],
     ) -> Result<Vec<SubstateShardData>, StorageError>;
+    /// Returns up to `limit` substates with shard ids in the inclusive range, ordered by shard id
+    fn get_substate_states_chunk(
+        &mut self,
+        start_shard_id: ShardId,
+        end_shard_id: ShardId,
+        limit: usize,
+    ) -> Result<Vec<SubstateShardData>, StorageError>;
     /// Returns the last voted height. A height of 0 means that no previous vote height has been recorded for the
     /// <shard, payload> pair.
     fn get_last_voted_height(
@@ -164,6 +181,37 @@ pub trait ShardStoreReadTransaction<TAddr: NodeAddressable, TPayload: Payload> {
     ) -> Result<Vec<SQLSubstate>, StorageError>;
     fn get_payload_result(&mut self, payload_id: &PayloadId) -> Result<PayloadResult, StorageError>;
     fn get_resolved_pledges_for_payload(&mut self, payload: PayloadId) -> Result<Vec<ObjectPledgeInfo>, StorageError>;
+    /// Returns the most recent active pledge on the shard, if the shard is currently pledged to a payload
+    fn get_active_pledge(&mut self, shard: ShardId) -> Result<Option<ObjectPledgeInfo>, StorageError>;
+    fn get_events_for_payload(&mut self, payload_id: PayloadId) -> Result<Vec<Event>, StorageError>;
+    fn get_events_by_topic(&mut self, topic: &str) -> Result<Vec<Event>, StorageError>;
+    /// Returns up to `limit` payloads that have been committed on every shard they were proposed for and whose nodes
+    /// are all from epochs before `before_epoch`. A payload is committed on a shard once its node at `decide_height`
+    /// for that shard has been executed.
+    fn get_prunable_payloads(
+        &mut self,
+        before_epoch: Epoch,
+        decide_height: NodeHeight,
+        limit: usize,
+    ) -> Result<Vec<PayloadId>, StorageError>;
+    /// Returns the root of the state tree that commits to every substate held by this node
+    fn get_state_root(&mut self) -> Result<FixedHash, StorageError>;
+    /// Returns a proof that the substate in `shard` is included in the current state root
+    fn get_state_proof(&mut self, shard: ShardId) -> Result<StateTreeProof, StorageError>;
+    /// Returns the leader failures recorded for committed nodes in the inclusive epoch range
+    fn get_leader_failures(
+        &mut self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<LeaderFailure<TAddr>>, StorageError>;
+    /// Returns the slashing evidence recorded against the offender, or against all validator nodes if no offender is
+    /// given
+    fn get_slashing_evidence(
+        &mut self,
+        offender: Option<&TAddr>,
+    ) -> Result<Vec<SlashingEvidence<TAddr, TPayload>>, StorageError>;
+    /// Returns every transaction in the mempool, in no particular order
+    fn get_mempool_transactions(&mut self) -> Result<Vec<MempoolTransaction>, StorageError>;
 }
 
 pub trait ShardStoreWriteTransaction<TAddr: NodeAddressable, TPayload: Payload> {
@@ -202,11 +250,13 @@ pub trait ShardStoreWriteTransaction<TAddr: NodeAddressable, TPayload: Payload>
         payload_id: PayloadId,
         height: NodeHeight,
     ) -> Result<(), StorageError>;
+    /// Applies the substate changes of a committed node and updates the state tree accordingly
     fn save_substate_changes(
         &mut self,
         node: HotStuffTreeNode<TAddr, TPayload>,
         changes: &[SubstateState],
     ) -> Result<(), StorageError>;
+    /// Inserts a substate received from a peer and updates the state tree accordingly
     fn insert_substates(&mut self, substate_data: SubstateShardData) -> Result<(), StorageError>;
     fn set_last_voted_height(
         &mut self,
@@ -234,6 +284,8 @@ pub trait ShardStoreWriteTransaction<TAddr: NodeAddressable, TPayload: Payload>
 
     /// Updates the result for an existing payload
     fn update_payload_result(&mut self, payload_id: &PayloadId, result: PayloadResult) -> Result<(), StorageError>;
+    /// Saves the events emitted by a finalized payload
+    fn save_events(&mut self, payload_id: PayloadId, events: &[Event]) -> Result<(), StorageError>;
 
     // -------------------------------- Pledges -------------------------------- //
     fn pledge_object(
@@ -261,4 +313,23 @@ pub trait ShardStoreWriteTransaction<TAddr: NodeAddressable, TPayload: Payload>
         commitment_address: SubstateAddress,
         shard_id: ShardId,
     ) -> Result<(), StorageError>;
+
+    /// Removes the consensus data of the payload: tree nodes, leader proposals, the votes received for either, all
+    /// high QCs except the committing QC for each shard, resolved pledges and the per-shard consensus state (leaf and
+    /// locked nodes, last executed and voted heights and leader state). The committing QC of a shard is the justify of
+    /// the node at the last executed height, so that node and the node certified by its justify are kept, the latter
+    /// being the certificate that state sync serves for the substates. Substates, events, the payload and its result
+    /// are kept.
+    fn prune_payload(&mut self, payload_id: PayloadId) -> Result<PrunedRecordCounts, StorageError>;
+
+    /// Records a leader failure. Saving the same failure more than once has no effect.
+    fn save_leader_failure(&mut self, failure: LeaderFailure<TAddr>) -> Result<(), StorageError>;
+
+    /// Records slashing evidence. Saving evidence with the same hash more than once has no effect.
+    fn save_slashing_evidence(&mut self, evidence: SlashingEvidence<TAddr, TPayload>) -> Result<(), StorageError>;
+
+    /// Saves the mempool transaction, replacing the existing entry for the same transaction if there is one
+    fn save_mempool_transaction(&mut self, transaction: MempoolTransaction) -> Result<(), StorageError>;
+    /// Removes the transaction from the mempool, returning true if it was in the mempool
+    fn remove_mempool_transaction(&mut self, transaction_hash: &FixedHash) -> Result<bool, StorageError>;
 }
@@ -0,0 +1,247 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_common_types::types::FixedHash;
+use tari_dan_common_types::ShardId;
+
+use crate::{
+    models::{is_right_at_depth, StateTreeNode, StateTreeProof, STATE_TREE_MAX_DEPTH},
+    storage::StorageError,
+};
+
+pub trait StateTreeStoreReader {
+    /// Returns the root of the state tree, or the zero hash if the tree is empty
+    fn get_state_tree_root(&mut self) -> Result<FixedHash, StorageError>;
+    fn get_state_tree_node(&mut self, hash: &FixedHash) -> Result<StateTreeNode, StorageError>;
+}
+
+pub trait StateTreeStoreWriter: StateTreeStoreReader {
+    fn set_state_tree_root(&mut self, root: FixedHash) -> Result<(), StorageError>;
+    fn insert_state_tree_node(&mut self, hash: FixedHash, node: StateTreeNode) -> Result<(), StorageError>;
+    fn delete_state_tree_node(&mut self, hash: &FixedHash) -> Result<(), StorageError>;
+}
+
+/// A sparse Merkle tree keyed by shard id that commits to every substate held by a validator node
+pub struct StateTree<'a, TStore> {
+    store: &'a mut TStore,
+}
+
+impl<'a, TStore: StateTreeStoreReader> StateTree<'a, TStore> {
+    pub fn new(store: &'a mut TStore) -> Self {
+        Self { store }
+    }
+
+    pub fn root(&mut self) -> Result<FixedHash, StorageError> {
+        self.store.get_state_tree_root()
+    }
+
+    /// Returns the inclusion proof for the substate in `shard_id` against the current root
+    pub fn get_proof(&mut self, shard_id: &ShardId) -> Result<StateTreeProof, StorageError> {
+        let mut hash = self.store.get_state_tree_root()?;
+        let mut siblings = Vec::new();
+        for depth in 0..STATE_TREE_MAX_DEPTH {
+            if hash == FixedHash::zero() {
+                break;
+            }
+            match self.store.get_state_tree_node(&hash)? {
+                StateTreeNode::Leaf {
+                    shard_id: leaf_shard, ..
+                } if leaf_shard == *shard_id => {
+                    return Ok(StateTreeProof::new(siblings));
+                },
+                StateTreeNode::Leaf { .. } => break,
+                StateTreeNode::Branch { left, right } => {
+                    if is_right_at_depth(shard_id, depth) {
+                        siblings.push(left);
+                        hash = right;
+                    } else {
+                        siblings.push(right);
+                        hash = left;
+                    }
+                },
+            }
+        }
+
+        Err(StorageError::NotFound {
+            item: "state tree leaf".to_string(),
+            key: shard_id.to_string(),
+        })
+    }
+}
+
+impl<'a, TStore: StateTreeStoreWriter> StateTree<'a, TStore> {
+    /// Inserts or replaces the leaf for `shard_id` and returns the new root
+    pub fn put(&mut self, shard_id: ShardId, value_hash: FixedHash) -> Result<FixedHash, StorageError> {
+        let root = self.store.get_state_tree_root()?;
+        let new_root = self.insert_at(root, 0, shard_id, value_hash)?;
+        self.store.set_state_tree_root(new_root)?;
+        Ok(new_root)
+    }
+
+    fn insert_at(
+        &mut self,
+        hash: FixedHash,
+        depth: usize,
+        shard_id: ShardId,
+        value_hash: FixedHash,
+    ) -> Result<FixedHash, StorageError> {
+        if hash == FixedHash::zero() {
+            return self.insert_node(StateTreeNode::Leaf { shard_id, value_hash });
+        }
+
+        match self.store.get_state_tree_node(&hash)? {
+            StateTreeNode::Leaf {
+                shard_id: leaf_shard, ..
+            } if leaf_shard == shard_id => {
+                self.store.delete_state_tree_node(&hash)?;
+                self.insert_node(StateTreeNode::Leaf { shard_id, value_hash })
+            },
+            StateTreeNode::Leaf {
+                shard_id: leaf_shard, ..
+            } => {
+                // The existing leaf moves down unchanged, so its hash and stored node remain valid
+                let new_leaf = self.insert_node(StateTreeNode::Leaf { shard_id, value_hash })?;
+                self.split(depth, (leaf_shard, hash), (shard_id, new_leaf))
+            },
+            StateTreeNode::Branch { left, right } => {
+                self.store.delete_state_tree_node(&hash)?;
+                let (left, right) = if is_right_at_depth(&shard_id, depth) {
+                    (left, self.insert_at(right, depth + 1, shard_id, value_hash)?)
+                } else {
+                    (self.insert_at(left, depth + 1, shard_id, value_hash)?, right)
+                };
+                self.insert_node(StateTreeNode::Branch { left, right })
+            },
+        }
+    }
+
+    /// Creates the branches that separate two leaves sharing the same path down to `depth`
+    fn split(
+        &mut self,
+        depth: usize,
+        (a_shard, a_hash): (ShardId, FixedHash),
+        (b_shard, b_hash): (ShardId, FixedHash),
+    ) -> Result<FixedHash, StorageError> {
+        if depth >= STATE_TREE_MAX_DEPTH {
+            return Err(StorageError::General {
+                details: format!("State tree leaves {} and {} cannot be separated", a_shard, b_shard),
+            });
+        }
+
+        let a_is_right = is_right_at_depth(&a_shard, depth);
+        let (left, right) = if a_is_right == is_right_at_depth(&b_shard, depth) {
+            let child = self.split(depth + 1, (a_shard, a_hash), (b_shard, b_hash))?;
+            if a_is_right {
+                (FixedHash::zero(), child)
+            } else {
+                (child, FixedHash::zero())
+            }
+        } else if a_is_right {
+            (b_hash, a_hash)
+        } else {
+            (a_hash, b_hash)
+        };
+        self.insert_node(StateTreeNode::Branch { left, right })
+    }
+
+    fn insert_node(&mut self, node: StateTreeNode) -> Result<FixedHash, StorageError> {
+        let hash = node.hash();
+        self.store.insert_state_tree_node(hash, node)?;
+        Ok(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryStore {
+        root: FixedHash,
+        nodes: HashMap<FixedHash, StateTreeNode>,
+    }
+
+    impl StateTreeStoreReader for MemoryStore {
+        fn get_state_tree_root(&mut self) -> Result<FixedHash, StorageError> {
+            Ok(self.root)
+        }
+
+        fn get_state_tree_node(&mut self, hash: &FixedHash) -> Result<StateTreeNode, StorageError> {
+            self.nodes.get(hash).cloned().ok_or_else(|| StorageError::NotFound {
+                item: "state tree node".to_string(),
+                key: hash.to_string(),
+            })
+        }
+    }
+
+    impl StateTreeStoreWriter for MemoryStore {
+        fn set_state_tree_root(&mut self, root: FixedHash) -> Result<(), StorageError> {
+            self.root = root;
+            Ok(())
+        }
+
+        fn insert_state_tree_node(&mut self, hash: FixedHash, node: StateTreeNode) -> Result<(), StorageError> {
+            self.nodes.insert(hash, node);
+            Ok(())
+        }
+
+        fn delete_state_tree_node(&mut self, hash: &FixedHash) -> Result<(), StorageError> {
+            self.nodes.remove(hash);
+            Ok(())
+        }
+    }
+
+    fn shard(first_byte: u8) -> ShardId {
+        let mut bytes = [0u8; 32];
+        bytes[0] = first_byte;
+        ShardId(bytes)
+    }
+
+    fn value(n: u8) -> FixedHash {
+        FixedHash::from([n; 32])
+    }
+
+    #[test]
+    fn it_proves_inclusion_of_every_leaf() {
+        let mut store = MemoryStore::default();
+        // 0b0000_0000, 0b0000_0001 and 0b1000_0000 share various prefix lengths
+        let leaves = [
+            (shard(0x00), value(1)),
+            (shard(0x01), value(2)),
+            (shard(0x80), value(3)),
+        ];
+        let mut tree = StateTree::new(&mut store);
+        for (shard_id, value_hash) in leaves {
+            tree.put(shard_id, value_hash).unwrap();
+        }
+
+        let root = tree.root().unwrap();
+        for (shard_id, value_hash) in leaves {
+            let proof = tree.get_proof(&shard_id).unwrap();
+            assert!(proof.verify(&root, &shard_id, &value_hash));
+            assert!(!proof.verify(&root, &shard_id, &value(9)));
+        }
+        assert!(tree.get_proof(&shard(0x02)).is_err());
+    }
+
+    #[test]
+    fn it_is_independent_of_insertion_order_and_cleans_up_replaced_nodes() {
+        let mut store_a = MemoryStore::default();
+        let mut tree = StateTree::new(&mut store_a);
+        tree.put(shard(0x00), value(1)).unwrap();
+        tree.put(shard(0x01), value(2)).unwrap();
+        tree.put(shard(0x80), value(3)).unwrap();
+        let root_a = tree.put(shard(0x01), value(4)).unwrap();
+
+        let mut store_b = MemoryStore::default();
+        let mut tree = StateTree::new(&mut store_b);
+        tree.put(shard(0x80), value(3)).unwrap();
+        tree.put(shard(0x01), value(4)).unwrap();
+        let root_b = tree.put(shard(0x00), value(1)).unwrap();
+
+        assert_eq!(root_a, root_b);
+        assert_eq!(store_a.nodes.len(), store_b.nodes.len());
+    }
+}
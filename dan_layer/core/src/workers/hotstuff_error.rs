@@ -20,6 +20,7 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use tari_common_types::types::FixedHash;
 use tari_dan_common_types::{Epoch, NodeHeight, PayloadId, ShardId};
 use tari_engine_types::{commit_result::RejectReason, substate::SubstateAddress};
 use tari_transaction::SubstateChange;
@@ -61,6 +62,12 @@ pub enum HotStuffError {
     InvalidQuorumCertificate(String),
     #[error("Failed to sign QC")]
     FailedToSignQc,
+    #[error("Failed to sign slashing evidence")]
+    FailedToSignSlashingEvidence,
+    #[error("Failed to sign proposal")]
+    FailedToSignProposal,
+    #[error("Invalid slashing evidence: {0}")]
+    InvalidSlashingEvidence(String),
     #[error("This validator node is not included in the BMT")]
     ValidatorNodeNotIncludedInBMT,
     #[error("Failed to generate Merkle proof for validator node")]
@@ -124,4 +131,12 @@ pub enum ProposalValidationError {
     LocalPledgeIsNone,
     #[error("Received proposal pledge for a different payload {pledged_payload} for shard {shard}")]
     PledgePayloadMismatch { shard: ShardId, pledged_payload: PayloadId },
+    #[error("Proposed state root {proposed} for shard {shard} does not match the local state root {local}")]
+    StateRootMismatch {
+        shard: ShardId,
+        proposed: FixedHash,
+        local: FixedHash,
+    },
+    #[error("Proposal is not signed by its proposer {proposed_by}")]
+    InvalidProposerSignature { proposed_by: String },
 }
@@ -20,15 +20,12 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::{
-    collections::{HashMap, HashSet},
-    time::Duration,
-};
+use std::collections::{HashMap, HashSet};
 
 use log::*;
 use rand::seq::SliceRandom;
 use serde::Serialize;
-use tari_common_types::types::{FixedHash, PublicKey, Signature};
+use tari_common_types::types::{FixedHash, PublicKey};
 use tari_core::ValidatorNodeBMT;
 use tari_dan_common_types::{
     optional::Optional,
@@ -47,10 +44,11 @@ use tari_dan_common_types::{
 use tari_dan_engine::runtime::ConsensusContext;
 use tari_engine_types::{
     commit_result::{FinalizeResult, RejectReason, TransactionResult},
-    substate::SubstateDiff,
+    substate::{Substate, SubstateAddress, SubstateDiff},
 };
 use tari_shutdown::ShutdownSignal;
 use tari_transaction::SubstateChange;
+use tari_utilities::ByteArray;
 use tokio::{
     sync::{
         broadcast,
@@ -64,17 +62,30 @@ use super::pacemaker_worker::PacemakerHandle;
 use crate::{
     consensus_constants::ConsensusConstants,
     models::{
+        state_tree_value_hash,
         vote_message::VoteMessage,
         Committee,
         HotStuffMessage,
         HotStuffMessageType,
         HotStuffTreeNode,
         HotstuffPhase,
+        LeaderFailure,
         Payload,
         PayloadResult,
+        SlashingEvidence,
+        StateTreeProof,
+    },
+    services::{
+        epoch_manager::EpochManager,
+        leader_strategy::LeaderStrategy,
+        verify_quorum_certificate,
+        PayloadProcessor,
+        SigningService,
+    },
+    storage::{
+        shard_store::{ShardStore, ShardStoreReadTransaction, ShardStoreWriteTransaction},
+        StorageError,
     },
-    services::{epoch_manager::EpochManager, leader_strategy::LeaderStrategy, PayloadProcessor, SigningService},
-    storage::shard_store::{ShardStore, ShardStoreReadTransaction, ShardStoreWriteTransaction},
     workers::{
         events::HotStuffEvent,
         hotstuff_error::{HotStuffError, ProposalValidationError},
@@ -82,7 +93,6 @@ use crate::{
 };
 
 const LOG_TARGET: &str = "tari::dan::hotstuff_waiter";
-pub const NETWORK_LATENCY: Duration = Duration::from_secs(10);
 
 // This is the value that we wait over in the pacemaker. So when it trigger we know what triggered it.
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -98,6 +108,28 @@ pub enum RecoveryMessage {
     ElectionInProgress(Epoch, ShardId, PayloadId),
 }
 
+/// A single input to the [HotStuffWaiter]. Each variant corresponds to one of the channels the waiter receives on.
+#[derive(Debug)]
+pub enum HotStuffInput<TPayload, TAddr> {
+    NewPayload(TPayload, ShardId),
+    HotStuffMessage(TAddr, HotStuffMessage<TPayload, TAddr>),
+    Vote(TAddr, VoteMessage),
+    RecoveryMessage(TAddr, RecoveryMessage),
+    PacemakerTimeout(PacemakerEvents),
+}
+
+impl<TPayload, TAddr> HotStuffInput<TPayload, TAddr> {
+    fn context(&self) -> &'static str {
+        match self {
+            HotStuffInput::NewPayload(..) => "new payload (on_next_sync_view)",
+            HotStuffInput::HotStuffMessage(..) => "new hotstuff message (on_new_hs_message)",
+            HotStuffInput::Vote(..) => "vote (on_receive_vote)",
+            HotStuffInput::RecoveryMessage(..) => "recovery message (on_receive_recovery_message)",
+            HotStuffInput::PacemakerTimeout(..) => "timeout message (on_timeout)",
+        }
+    }
+}
+
 pub struct HotStuffWaiter<
     TPayload,
     TAddr,
@@ -141,13 +173,9 @@ pub struct HotStuffWaiter<
     /// Store used to persist consensus state.
     shard_store: TShardStore,
     /// Network-wide constants
-    // TODO: remove if not needed
     consensus_constants: ConsensusConstants,
     /// NEWVIEW message counts - TODO: this will bloat memory maybe moving to the db is better
     newview_message_counts: HashMap<(ShardId, PayloadId), HashSet<TAddr>>,
-    /// Network latency
-    #[allow(dead_code)]
-    network_latency: Duration,
     /// We store what round is for next leader selection, default is 0.
     current_leader_round: HashMap<(ShardId, PayloadId), u32>,
     /// We have to store if we are in the middle of an election, so that when we receive a recovery message
@@ -186,7 +214,6 @@ where
         shard_store: TShardStore,
         shutdown: ShutdownSignal,
         consensus_constants: ConsensusConstants,
-        network_latency: Duration,
     ) -> JoinHandle<anyhow::Result<()>> {
         let waiter = HotStuffWaiter::new(
             signing_service,
@@ -207,7 +234,6 @@ where
             payload_processor,
             shard_store,
             consensus_constants,
-            network_latency,
         );
         tokio::spawn(async move {
             waiter.run(shutdown).await?;
@@ -234,7 +260,6 @@ where
         payload_processor: TPayloadProcessor,
         shard_store: TShardStore,
         consensus_constants: ConsensusConstants,
-        network_latency: Duration,
     ) -> Self {
         Self {
             signing_service,
@@ -256,7 +281,6 @@ where
             shard_store,
             consensus_constants,
             newview_message_counts: HashMap::new(),
-            network_latency,
             current_leader_round: HashMap::new(),
             election_in_progress: HashSet::new(),
         }
@@ -279,7 +303,7 @@ where
         let current_leader = *self.current_leader_round.entry((shard, payload_id)).or_default();
         let leader = self
             .leader_strategy
-            .get_leader(&committee, payload_id, shard, current_leader);
+            .get_leader(epoch, &committee, payload_id, shard, current_leader)?;
 
         let new_view = self.shard_store.with_write_tx(|tx| {
             let high_qc = tx
@@ -320,7 +344,7 @@ where
         self.pacemaker
             .start_timer(
                 PacemakerEvents::LocalCommittee(epoch, shard, payload_id),
-                self.network_latency,
+                self.consensus_constants.pacemaker_timeout,
             )
             .await
             .unwrap();
@@ -335,7 +359,7 @@ where
                 self.pacemaker
                     .start_timer(
                         PacemakerEvents::ForeignCommittee(epoch, shard, payload_id),
-                        self.network_latency * 2,
+                        self.consensus_constants.pacemaker_timeout * 2,
                     )
                     .await
                     .unwrap();
@@ -419,8 +443,10 @@ where
                 let node = tx.get_node(current_leaf_node.hash())?;
                 (*node.hash(), node.height(), node.payload_height(), None)
             };
+            let state_root =
+                Self::shard_state_root(&mut *tx, shard, payload_id, parent_payload_height + NodeHeight(1))?;
 
-            leaf_node = HotStuffTreeNode::new(
+            let mut node = HotStuffTreeNode::new(
                 parent_hash,
                 shard,
                 parent_height + NodeHeight(1),
@@ -434,7 +460,14 @@ where
                 epoch,
                 self.public_key.clone(),
                 high_qc,
+                state_root,
             );
+            let signature = self
+                .signing_service
+                .sign(node.hash().as_bytes())
+                .ok_or(HotStuffError::FailedToSignProposal)?;
+            node.set_proposer_signature(signature);
+            leaf_node = node;
 
             info!(
                 target: LOG_TARGET,
@@ -482,6 +515,12 @@ where
 
         self.validate_proposal(&node)?;
 
+        if let Some(conflicting_node) = self.find_conflicting_proposal(&node)? {
+            let evidence = SlashingEvidence::double_proposal(conflicting_node, node, self.public_key.clone());
+            self.save_slashing_evidence(evidence)?;
+            return Ok(());
+        }
+
         // We remove the shard from pacemaker, so it will not trigger. We don't have to check if it's local shard or
         // not, we just remove them both, one of them will not exists, but pacemaker will take care of that.
         self.pacemaker
@@ -500,6 +539,10 @@ where
         self.election_in_progress.remove(&(node.shard(), payload_id));
 
         let shard = node.shard();
+        // Determined before opening the write transaction, because the leader strategy may read from the shard store
+        let committee = self.epoch_manager.get_committee(node.epoch(), shard).await?;
+        self.validate_state_root(&node, &committee)?;
+        let leader_failures = self.get_leader_failures(&node, &committee)?;
         let payload;
         let last_vote_height;
         let last_leader_round;
@@ -568,7 +611,7 @@ where
                     node.payload_height()
                 );
 
-                self.update_nodes(&node)?;
+                self.update_nodes(&node, &leader_failures)?;
                 return Ok(());
             }
 
@@ -593,7 +636,7 @@ where
                 locked_height
             );
         }
-        self.update_nodes(&node)?;
+        self.update_nodes(&node, &leader_failures)?;
         // If all pledges for all shards and complete, then we can persist the payload changes
         self.finalize_payload(&involved_shards, &node).await?;
 
@@ -627,7 +670,7 @@ where
         let committee = self.epoch_manager.get_committee(epoch, shard_id).await?;
         let leader = self
             .leader_strategy
-            .get_leader(&committee, payload_id, shard_id, current_leader);
+            .get_leader(epoch, &committee, payload_id, shard_id, current_leader)?;
         self.shard_store
             .with_write_tx(|tx| tx.save_current_leader_state(payload_id, shard_id, current_leader, leader.clone()))?;
         self.tx_leader
@@ -637,7 +680,7 @@ where
         self.pacemaker
             .start_timer(
                 PacemakerEvents::LocalCommittee(epoch, shard_id, payload_id),
-                self.network_latency * (2 << current_leader),
+                self.consensus_constants.pacemaker_timeout * (2 << current_leader),
             )
             .await
             .unwrap();
@@ -688,7 +731,7 @@ where
         self.pacemaker
             .start_timer(
                 PacemakerEvents::ForeignCommittee(epoch, shard_id, payload_id),
-                self.network_latency * 2,
+                self.consensus_constants.pacemaker_timeout * 2,
             )
             .await
             .unwrap();
@@ -792,7 +835,7 @@ where
         self.pacemaker
             .start_timer(
                 PacemakerEvents::ForeignCommittee(epoch, shard_id, payload_id),
-                self.network_latency * 2,
+                self.consensus_constants.pacemaker_timeout * 2,
             )
             .await?;
         Ok(())
@@ -848,7 +891,8 @@ where
         if resolved_pledges.len() == involved_shards.len() {
             let payload_result = tx.get_payload_result(&node.payload_id())?;
             match &payload_result.finalize_result.result {
-                TransactionResult::Accept(diff) => {
+                // If only the fee payment was accepted, the fee changes are committed and the payload is finalized
+                TransactionResult::Accept(diff) | TransactionResult::AcceptFeeRejectRest(diff, _) => {
                     if resolved_pledges
                         .iter()
                         .any(|pledge| pledge.abandoned_by_tree_node_hash.is_some())
@@ -872,14 +916,44 @@ where
                             tx.save_substate_changes(node, changes)?;
                         }
                     }
+                    let batched_payloads = tx.get_payload(&node.payload_id())?.batched_payloads();
+                    if batched_payloads.is_empty() {
+                        tx.save_events(node.payload_id(), &payload_result.finalize_result.events)?;
 
-                    self.publish_event(HotStuffEvent::OnFinalized(
-                        Box::new(node.justify().clone()),
-                        payload_result.finalize_result,
-                    ));
+                        self.publish_event(HotStuffEvent::OnFinalized(
+                            Box::new(node.justify().clone()),
+                            payload_result.finalize_result,
+                        ));
+                    } else {
+                        // Each payload in the batch is finalized with its own result
+                        for payload in batched_payloads {
+                            let payload_id = payload.to_id();
+                            let result = tx.get_payload_result(&payload_id)?.finalize_result;
+                            if let TransactionResult::Reject(reason) = &result.result {
+                                self.publish_event(HotStuffEvent::Failed(payload_id, reason.to_string()));
+                                continue;
+                            }
+                            tx.save_events(payload_id, &result.events)?;
+                            self.publish_event(HotStuffEvent::OnFinalized(Box::new(node.justify().clone()), result));
+                        }
+                    }
                 },
                 TransactionResult::Reject(reason) => {
-                    self.publish_event(HotStuffEvent::Failed(node.payload_id(), reason.to_string()));
+                    let batched_payloads = tx.get_payload(&node.payload_id())?.batched_payloads();
+                    if batched_payloads.is_empty() {
+                        self.publish_event(HotStuffEvent::Failed(node.payload_id(), reason.to_string()));
+                    } else {
+                        // None of the payloads in a rejected batch are committed, so their recorded results are
+                        // replaced with the rejection of the batch
+                        for payload in batched_payloads {
+                            let payload_id = payload.to_id();
+                            tx.update_payload_result(&payload_id, PayloadResult {
+                                finalize_result: FinalizeResult::reject(payload_id.into_array().into(), reason.clone()),
+                                pledge_hash: payload_result.pledge_hash,
+                            })?;
+                            self.publish_event(HotStuffEvent::Failed(payload_id, reason.to_string()));
+                        }
+                    }
                 },
             }
         }
@@ -932,6 +1006,19 @@ where
 
             None => return Err(ProposalValidationError::LocalPledgeIsNone),
         }
+
+        // Proposals are signed by the proposer, so that conflicting proposals can be proven to anyone
+        let is_signed_by_proposer = node.proposer_signature().map_or(false, |signature| {
+            PublicKey::from_bytes(node.proposed_by().as_bytes()).map_or(false, |public_key| {
+                self.signing_service
+                    .verify_for_public_key(&public_key, signature, node.hash().as_bytes())
+            })
+        });
+        if !is_signed_by_proposer {
+            return Err(ProposalValidationError::InvalidProposerSignature {
+                proposed_by: node.proposed_by().to_string(),
+            });
+        }
         // if node.payload_height() > NodeHeight(0) && node.justify().decision() != &QuorumDecision::Accept {
         //     return Err(HotStuffError::JustifyIsNotAccepted);
         // }
@@ -939,6 +1026,87 @@ where
         Ok(())
     }
 
+    /// Members of the committee for the proposal's shard only vote for a proposal if its state root matches the root
+    /// they calculate for the shard as of the parent node (see [Self::shard_state_root]). The QCs for a proposal
+    /// therefore certify the state of its shard, which allows state sync to verify substates against a certified root.
+    fn validate_state_root(
+        &self,
+        node: &HotStuffTreeNode<TAddr, TPayload>,
+        committee: &Committee<TAddr>,
+    ) -> Result<(), HotStuffError> {
+        if node.is_genesis() || !committee.contains(&self.public_key) {
+            return Ok(());
+        }
+        let local_state_root = self
+            .shard_store
+            .with_read_tx(|tx| Self::shard_state_root(tx, node.shard(), node.payload_id(), node.payload_height()))?;
+        if *node.state_root() != local_state_root {
+            return Err(ProposalValidationError::StateRootMismatch {
+                shard: node.shard(),
+                proposed: *node.state_root(),
+                local: local_state_root,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Returns the state root that a proposal for the shard at the given payload height commits to. The root is taken
+    /// over the state of the shard as of the parent node: the state pledged to the payload for the first proposal, and
+    /// the state that the result of the payload leaves the shard in once the payload has been executed.
+    ///
+    /// The root does not cover the other substates in the committee's shard range. Chains for different payloads
+    /// commit concurrently and in no particular order, so members routinely hold different states for the rest of the
+    /// range. A shard only changes through the payload it is pledged to, so every member of the committee calculates
+    /// the same root for it.
+    fn shard_state_root(
+        tx: &mut TShardStore::ReadTransaction<'_>,
+        shard: ShardId,
+        payload_id: PayloadId,
+        payload_height: NodeHeight,
+    ) -> Result<FixedHash, HotStuffError> {
+        let current = tx.get_substate_states(&[shard])?.pop();
+        let mut value_hash = current.as_ref().map(|s| s.state_tree_value_hash());
+        if payload_height > NodeHeight(1) {
+            let payload_result = tx.get_payload_result(&payload_id).optional()?;
+            if let Some(diff) = payload_result.as_ref().and_then(|r| r.finalize_result.result.accept()) {
+                let changes = extract_changes_for_shards(&[shard], payload_id, diff)?;
+                for change in changes.get(&shard).into_iter().flatten() {
+                    value_hash = match change {
+                        SubstateState::Up {
+                            address,
+                            created_by,
+                            data,
+                        } => Some(state_tree_value_hash(
+                            &shard,
+                            address,
+                            data.version(),
+                            data,
+                            created_by,
+                            &None,
+                        )),
+                        SubstateState::Down { deleted_by } => current.as_ref().map(|s| {
+                            state_tree_value_hash(
+                                &shard,
+                                s.substate_address(),
+                                s.version(),
+                                s.substate(),
+                                &s.created_payload_id(),
+                                &Some(*deleted_by),
+                            )
+                        }),
+                        SubstateState::DoesNotExist => value_hash,
+                    };
+                }
+            }
+        }
+
+        // The tree contains a single leaf, so its root is the hash of the leaf
+        Ok(value_hash
+            .map(|value_hash| StateTreeProof::default().compute_root(&shard, &value_hash))
+            .unwrap_or_else(FixedHash::zero))
+    }
+
     async fn decide_and_vote_on_all_nodes(
         &self,
         payload: TPayload,
@@ -991,7 +1159,7 @@ where
                     self.pacemaker
                         .start_timer(
                             PacemakerEvents::ForeignCommittee(epoch, node.shard(), payload_id),
-                            self.network_latency * 2,
+                            self.consensus_constants.pacemaker_timeout * 2,
                         )
                         .await?;
                 }
@@ -1046,7 +1214,7 @@ where
             self.pacemaker
                 .start_timer(
                     PacemakerEvents::LocalCommittee(epoch, node.shard(), payload_id),
-                    self.network_latency,
+                    self.consensus_constants.pacemaker_timeout,
                 )
                 .await?;
         }
@@ -1180,16 +1348,21 @@ where
 
                     return Ok(finalize_result);
                 }
-                let finalize_result = match task::block_in_place(|| self.execute(payload, shard_pledges, node.epoch()))
-                {
-                    Ok(finalize_result) => finalize_result,
-                    Err(err) => FinalizeResult::reject(
-                        payload_id.into_array().into(),
-                        RejectReason::ExecutionFailure(err.to_string()),
-                    ),
+                let consensus_context = ConsensusContext {
+                    current_epoch: node.epoch().as_u64(),
+                    current_epoch_timestamp: self.epoch_manager.get_epoch_timestamp(node.epoch()).await?,
+                    current_epoch_beacon: self.epoch_manager.get_epoch_beacon(node.epoch()).await?,
                 };
-
-                if let TransactionResult::Accept(ref diff) = finalize_result.result {
+                let finalize_result =
+                    match task::block_in_place(|| self.execute(payload, shard_pledges, consensus_context)) {
+                        Ok(finalize_result) => finalize_result,
+                        Err(err) => FinalizeResult::reject(
+                            payload_id.into_array().into(),
+                            RejectReason::ExecutionFailure(err.to_string()),
+                        ),
+                    };
+
+                if let Some(diff) = finalize_result.result.accept() {
                     match Self::validate_pledges(shard_pledges, diff) {
                         Ok(_) => {
                             self.shard_store.with_write_tx(|tx| {
@@ -1347,18 +1520,40 @@ where
             if !valid_committee.contains(&from) {
                 return Err(HotStuffError::ReceivedMessageFromNonCommitteeMember);
             }
+        }
+        self.validate_vote_message(&from, node.epoch(), &msg).await?;
+        if let Some(conflicting) = self.find_conflicting_vote(&from, &node)? {
+            let evidence = SlashingEvidence::double_vote(from, conflicting, (node, msg), self.public_key.clone());
+            self.save_slashing_evidence(evidence)?;
+            return Ok(());
+        }
+        {
             let mut tx = self.shard_store.create_write_tx()?;
 
             // Collect votes
             tx.save_received_vote_for(from, msg.local_node_hash(), msg.clone())?;
 
-            let votes = tx.get_received_votes_for(msg.local_node_hash())?;
+            // Votes with a different decision or pledges sign a different challenge and cannot be aggregated, so votes
+            // are counted per challenge and the QC is formed by the first of them to reach the threshold
+            let challenge = msg.construct_challenge();
+            let votes = tx
+                .get_received_votes_for(msg.local_node_hash())?
+                .into_iter()
+                .filter(|v| v.construct_challenge() == challenge)
+                .collect::<Vec<_>>();
 
             if votes.len() == valid_committee.consensus_threshold() {
-                let validator_metadata = votes.iter().map(|v| v.validator_metadata().clone()).collect();
-
-                // TODO: Check all votes
-                let main_vote = votes.get(0).unwrap();
+                let signatures = votes
+                    .iter()
+                    .map(|v| {
+                        let md = v.validator_metadata();
+                        (md.merkle_leaf_index, md.public_key.clone(), md.signature.clone())
+                    })
+                    .collect();
+                let aggregated_signature = self
+                    .signing_service
+                    .aggregate(signatures, &*challenge)
+                    .ok_or_else(|| HotStuffError::InvalidVote("duplicate signer in votes".to_string()))?;
 
                 let qc = QuorumCertificate::new(
                     node.payload_id(),
@@ -1367,9 +1562,9 @@ where
                     node.height(),
                     node.shard(),
                     node.epoch(),
-                    main_vote.decision(),
-                    main_vote.all_shard_pledges().clone(),
-                    validator_metadata,
+                    msg.decision(),
+                    msg.all_shard_pledges().clone(),
+                    aggregated_signature,
                 );
                 self.update_high_qc(&mut tx, node.proposed_by().clone(), qc)?;
 
@@ -1388,7 +1583,7 @@ where
             if committee.is_empty() {
                 return Err(HotStuffError::NoCommitteeForShard { shard: shard_id, epoch });
             }
-            if self.is_leader(payload_id, shard_id, &committee)? {
+            if self.is_leader(epoch, payload_id, shard_id, &committee)? {
                 self.leader_on_propose(shard_id, payload_id).await?;
             }
         }
@@ -1404,6 +1599,7 @@ where
 
     fn is_leader(
         &self,
+        epoch: Epoch,
         payload: PayloadId,
         shard: ShardId,
         committee: &Committee<TAddr>,
@@ -1411,13 +1607,15 @@ where
         // TODO: What if the leader doesn't know that he is the leader? e.g. didn't get the message. The leader (index
         // 0) failed. Now index 1 should be leader, but he doesn't know, so he ignores the newviews (should we ignore
         // the newviews?)
-        Ok(self.leader_strategy.is_leader(
+        let is_leader = self.leader_strategy.is_leader(
             &self.public_key,
+            epoch,
             committee,
             payload,
             shard,
             *self.current_leader_round.get(&(shard, payload)).unwrap_or(&0),
-        ))
+        )?;
+        Ok(is_leader)
     }
 
     async fn validate_from_committee(&self, from: &TAddr, epoch: Epoch, shard: ShardId) -> Result<(), HotStuffError> {
@@ -1429,79 +1627,139 @@ where
     }
 
     async fn validate_qc(&self, qc: &QuorumCertificate, min_signers: usize) -> Result<(), HotStuffError> {
-        // extract all the pairs of signer-signature present in the QC
-        let signer_signatures = Self::extract_signer_signatures_from_qc(qc);
-
-        // the QC should not have repeated signers
-        let signers_set = signer_signatures
-            .iter()
-            .map(|s| NodeAddressable::as_bytes(&s.0))
-            .collect::<HashSet<_>>();
-        if signer_signatures.len() != signers_set.len() {
-            return Err(HotStuffError::InvalidQuorumCertificate(
-                "duplicated signers".to_string(),
-            ));
+        if qc.aggregated_signature().num_signers() == 0 && min_signers == 0 {
+            return Ok(());
         }
+        let vns = self
+            .epoch_manager
+            .get_validator_nodes_per_epoch(qc.epoch())
+            .await?
+            .into_iter()
+            .map(|vn| vn.public_key)
+            .collect::<Vec<_>>();
+        let committee = self.epoch_manager.get_committee(qc.epoch(), qc.shard()).await?;
+        verify_quorum_certificate(&self.signing_service, qc, &vns, &committee, min_signers)
+            .map_err(|e| HotStuffError::InvalidQuorumCertificate(e.to_string()))
+    }
 
-        // check that the minimum quorum has been reached
-        if signers_set.len() < min_signers {
-            return Err(HotStuffError::InvalidQuorumCertificate(
-                "insufficient quorum".to_string(),
+    /// Checks that a vote is signed by the sender and that the leaf index it gives, which is used to aggregate its
+    /// signature into the QC, refers to the sender in the epoch's validator node BMT.
+    async fn validate_vote_message(&self, from: &TAddr, epoch: Epoch, msg: &VoteMessage) -> Result<(), HotStuffError> {
+        let md = msg.validator_metadata();
+        if NodeAddressable::as_bytes(&md.public_key) != from.as_bytes() {
+            return Err(HotStuffError::InvalidVote(
+                "vote was not signed by the sender".to_string(),
             ));
         }
 
-        // all merkle proofs for the signers must be valid
-        let validator_node_root = self.epoch_manager.get_validator_node_merkle_root(qc.epoch()).await?;
-        // TODO: Combine all validator merkle proofs before sending them
-        let mut verify_all = true;
-        for md in qc.validators_metadata() {
-            verify_all &= md
-                .merkle_proof
-                .verify(&validator_node_root, md.get_node_hash().to_vec());
-            if !verify_all {
-                return Err(HotStuffError::InvalidQuorumCertificate(
-                    "invalid merkle proof".to_string(),
-                ));
-            }
-        }
-
-        // all signers must be included in the epoch committee for the shard
-        let committee = self.epoch_manager.get_committee(qc.epoch(), qc.shard()).await?;
-        let committee_set = committee.members.iter().map(|m| m.as_bytes()).collect::<HashSet<_>>();
-        let all_signers_are_in_committee = signers_set.iter().all(|s| committee_set.contains(s));
-        if !all_signers_are_in_committee {
-            return Err(HotStuffError::InvalidQuorumCertificate(
-                "some signers are not in committee".to_string(),
-            ));
+        let challenge = msg.construct_challenge();
+        if !self
+            .signing_service
+            .verify_for_public_key(&md.public_key, &md.signature, &*challenge)
+        {
+            return Err(HotStuffError::InvalidVote("invalid signature".to_string()));
         }
 
-        // all signatures must be valid
-        let all_signatures_are_valid = signer_signatures
-            .iter()
-            .all(|(public_key, signature)| self.validate_vote(qc, public_key, signature));
-        if !all_signatures_are_valid {
-            return Err(HotStuffError::InvalidQuorumCertificate("invalid signature".to_string()));
+        let vns = self.epoch_manager.get_validator_nodes_per_epoch(epoch).await?;
+        let is_leaf_of_sender = vns
+            .get(md.merkle_leaf_index as usize)
+            .map(|vn| vn.public_key.as_bytes() == from.as_bytes())
+            .unwrap_or(false);
+        if !is_leaf_of_sender {
+            return Err(HotStuffError::InvalidVote(format!(
+                "leaf index {} does not belong to the sender",
+                md.merkle_leaf_index
+            )));
         }
 
         Ok(())
     }
 
-    fn validate_vote(&self, qc: &QuorumCertificate, public_key: &PublicKey, signature: &Signature) -> bool {
-        let vote = VoteMessage::new(qc.node_hash(), *qc.decision(), qc.all_shard_pledges().clone());
-        let challenge = vote.construct_challenge();
-        self.signing_service
-            .verify_for_public_key(public_key, signature, &*challenge)
+    /// Returns a stored proposal by the same leader that conflicts with the node
+    fn find_conflicting_proposal(
+        &self,
+        node: &HotStuffTreeNode<TAddr, TPayload>,
+    ) -> Result<Option<HotStuffTreeNode<TAddr, TPayload>>, HotStuffError> {
+        let proposals = self
+            .shard_store
+            .with_read_tx(|tx| tx.get_leader_proposals(node.payload_id(), node.payload_height(), &[node.shard()]))?;
+        Ok(proposals
+            .into_iter()
+            .find(|proposal| proposal.proposed_by() == node.proposed_by() && is_conflicting(proposal, node)))
     }
 
-    fn extract_signer_signatures_from_qc(qc: &QuorumCertificate) -> Vec<(PublicKey, Signature)> {
-        qc.validators_metadata()
-            .iter()
-            .map(|md| (md.public_key.clone(), md.signature.clone()))
+    /// Returns a stored proposal that conflicts with the node, together with the vote for it received from the voter
+    fn find_conflicting_vote(
+        &self,
+        from: &TAddr,
+        node: &HotStuffTreeNode<TAddr, TPayload>,
+    ) -> Result<Option<(HotStuffTreeNode<TAddr, TPayload>, VoteMessage)>, HotStuffError> {
+        let conflicting = self.shard_store.with_read_tx(|tx| {
+            let proposals = tx.get_leader_proposals(node.payload_id(), node.payload_height(), &[node.shard()])?;
+            for proposal in proposals.into_iter().filter(|proposal| is_conflicting(proposal, node)) {
+                let vote = tx.get_received_votes_for(*proposal.hash())?.into_iter().find(|vote| {
+                    vote.try_validator_metadata()
+                        .map_or(false, |md| NodeAddressable::as_bytes(&md.public_key) == from.as_bytes())
+                });
+                if let Some(vote) = vote {
+                    return Ok(Some((proposal, vote)));
+                }
+            }
+            Ok::<_, StorageError>(None)
+        })?;
+        Ok(conflicting)
+    }
+
+    /// Signs and stores evidence of equivocation. The conflicting message is not processed any further. Evidence is
+    /// only stored if it verifies, i.e. it is made of two validly signed conflicting messages.
+    fn save_slashing_evidence(&self, mut evidence: SlashingEvidence<TAddr, TPayload>) -> Result<(), HotStuffError> {
+        evidence.sign(&self.signing_service)?;
+        evidence.verify(&self.signing_service)?;
+        warn!(
+            target: LOG_TARGET,
+            "🔥 Recording {} evidence against {} for payload {}, shard {}, height {}, leader round {}",
+            evidence.kind(),
+            evidence.offender(),
+            evidence.payload_id(),
+            evidence.shard(),
+            evidence.height(),
+            evidence.leader_round(),
+        );
+        self.shard_store
+            .with_write_tx(|tx| tx.save_slashing_evidence(evidence))?;
+        Ok(())
+    }
+
+    /// Returns the leaders that failed to propose before the round in which the node was proposed
+    fn get_leader_failures(
+        &self,
+        node: &HotStuffTreeNode<TAddr, TPayload>,
+        committee: &Committee<TAddr>,
+    ) -> Result<Vec<LeaderFailure<TAddr>>, HotStuffError> {
+        let committee_hash = committee.members_hash();
+        (0..node.leader_round())
+            .map(|round| {
+                let leader =
+                    self.leader_strategy
+                        .get_leader(node.epoch(), committee, node.payload_id(), node.shard(), round)?;
+                Ok(LeaderFailure {
+                    epoch: node.epoch(),
+                    committee_hash,
+                    shard_id: node.shard(),
+                    payload_id: node.payload_id(),
+                    leader_round: round,
+                    leader: leader.clone(),
+                })
+            })
             .collect()
     }
 
     /// See section 6, algorithm 4 in https://arxiv.org/pdf/1803.05069.pdf
-    fn update_nodes(&self, node: &HotStuffTreeNode<TAddr, TPayload>) -> Result<(), HotStuffError> {
+    fn update_nodes(
+        &self,
+        node: &HotStuffTreeNode<TAddr, TPayload>,
+        leader_failures: &[LeaderFailure<TAddr>],
+    ) -> Result<(), HotStuffError> {
         let mut tx = self.shard_store.create_write_tx()?;
         // commit_node is at PRE-COMMIT phase
         self.update_high_qc(&mut tx, node.proposed_by().clone(), node.justify().clone())?;
@@ -1549,7 +1807,7 @@ where
                 prepare_node,
             );
 
-            self.on_commit(&mut tx, node)?;
+            self.on_commit(&mut tx, node, leader_failures)?;
             tx.set_last_executed_height(node.shard(), node.payload_id(), node.height())?;
         } else {
             debug!(
@@ -1606,9 +1864,13 @@ where
         &self,
         tx: &mut TShardStore::WriteTransaction<'_>,
         node: &HotStuffTreeNode<TAddr, TPayload>,
+        leader_failures: &[LeaderFailure<TAddr>],
     ) -> Result<(), HotStuffError> {
         let last_exec_height = tx.get_last_executed_height(node.shard(), node.payload_id())?;
         if last_exec_height < node.height() {
+            for failure in leader_failures {
+                tx.save_leader_failure(failure.clone())?;
+            }
             match node.payload_phase() {
                 HotstuffPhase::Decide => {
                     info!(
@@ -1618,7 +1880,7 @@ where
                     );
                     let payload_result = tx.get_payload_result(&node.payload_id())?;
                     match payload_result.finalize_result.result {
-                        TransactionResult::Accept(_) => {
+                        TransactionResult::Accept(_) | TransactionResult::AcceptFeeRejectRest(_, _) => {
                             tx.complete_pledges(node.shard(), node.payload_id(), node.hash())?;
                         },
                         TransactionResult::Reject(_) => {
@@ -1655,7 +1917,7 @@ where
         &self,
         payload: TPayload,
         shard_pledges: &ShardPledgeCollection,
-        epoch: Epoch,
+        consensus_context: ConsensusContext,
     ) -> Result<FinalizeResult, HotStuffError> {
         let maybe_payload_result = self
             .shard_store
@@ -1688,19 +1950,83 @@ where
             );
         }
 
-        let consensus_context = ConsensusContext {
-            current_epoch: epoch.as_u64(),
-        };
+        let batched_payloads = payload.batched_payloads();
+        if !batched_payloads.is_empty() {
+            return self.execute_batch(
+                payload.to_id(),
+                batched_payloads,
+                &pledges,
+                &consensus_context,
+                shard_pledges.pledge_hash(),
+            );
+        }
+
         let finalize_result = self
             .payload_processor
             .process_payload(payload, pledges, consensus_context)?;
         Ok(finalize_result)
     }
 
+    /// Executes the payloads of a batch in order. Each payload is executed against the state left by the payloads
+    /// before it, so a payload that spends a substate already spent earlier in the batch fails as it would if the
+    /// payloads were executed one after another. The result of each payload is recorded under its own payload id, and
+    /// the combined result of the batch is returned.
+    fn execute_batch(
+        &self,
+        batch_id: PayloadId,
+        payloads: Vec<TPayload>,
+        pledges: &HashMap<ShardId, ObjectPledge>,
+        consensus_context: &ConsensusContext,
+        pledge_hash: FixedHash,
+    ) -> Result<FinalizeResult, HotStuffError> {
+        info!(
+            target: LOG_TARGET,
+            "[execute] Executing batch {} of {} payload(s)",
+            batch_id,
+            payloads.len()
+        );
+
+        let mut state = pledges.clone();
+        let mut results = Vec::with_capacity(payloads.len());
+        for payload in &payloads {
+            let payload_id = payload.to_id();
+            let result =
+                match self
+                    .payload_processor
+                    .process_payload(payload.clone(), state.clone(), consensus_context.clone())
+                {
+                    Ok(result) => result,
+                    Err(err) => FinalizeResult::reject(
+                        payload_id.into_array().into(),
+                        RejectReason::ExecutionFailure(err.to_string()),
+                    ),
+                };
+            if let Some(diff) = result.result.accept() {
+                apply_batch_member_diff(&mut state, batch_id, payload_id, diff);
+            }
+            results.push(result);
+        }
+
+        self.shard_store.with_write_tx(|tx| {
+            for (payload, result) in payloads.into_iter().zip(&results) {
+                let payload_id = payload.to_id();
+                tx.save_payload(payload)?;
+                tx.update_payload_result(&payload_id, PayloadResult {
+                    finalize_result: result.clone(),
+                    pledge_hash,
+                })?;
+            }
+            Ok::<_, StorageError>(())
+        })?;
+
+        Ok(merge_batch_results(batch_id, &results))
+    }
+
     async fn get_leader(&self, node: &HotStuffTreeNode<TAddr, TPayload>) -> Result<TAddr, HotStuffError> {
         let epoch = self.epoch_manager.current_epoch().await?;
         let committee = self.epoch_manager.get_committee(epoch, node.shard()).await?;
         let leader = self.leader_strategy.get_leader(
+            epoch,
             &committee,
             node.payload_id(),
             node.shard(),
@@ -1708,7 +2034,7 @@ where
                 .current_leader_round
                 .get(&(node.shard(), node.payload_id()))
                 .unwrap_or(&0),
-        );
+        )?;
         Ok(leader.clone())
     }
 
@@ -1721,7 +2047,7 @@ where
         vn_bmt: &ValidatorNodeBMT,
     ) -> Result<VoteMessage, HotStuffError> {
         let mut vote_msg = match payload_result {
-            TransactionResult::Accept(ref accept) => {
+            TransactionResult::Accept(ref accept) | TransactionResult::AcceptFeeRejectRest(ref accept, _) => {
                 info!(
                     target: LOG_TARGET,
                     "💚 Vote to ACCEPT for node {}. Up substate(s): {}, down substate(s): {}",
@@ -1753,7 +2079,7 @@ where
         if committee.is_empty() {
             return Err(HotStuffError::NoCommitteeForShard { shard, epoch });
         }
-        if self.is_leader(payload_id, shard, &committee)? {
+        if self.is_leader(epoch, payload_id, shard, &committee)? {
             let min_required_new_views = committee.consensus_threshold();
             let num_new_views = self.get_newview_count_for(shard, payload_id);
             if num_new_views >= min_required_new_views {
@@ -1801,55 +2127,135 @@ where
         let _ignore = self.tx_events.send(event);
     }
 
+    /// Handles a single input, as received on one of the waiter's channels. This is what [HotStuffWaiter::run] does
+    /// for each message it receives, and allows the waiter to be stepped without spawning it.
+    pub async fn handle_input(&mut self, input: HotStuffInput<TPayload, TAddr>) -> Result<(), HotStuffError> {
+        match input {
+            HotStuffInput::NewPayload(payload, shard) => self.on_next_sync_view(payload, shard).await,
+            HotStuffInput::HotStuffMessage(from, msg) => self.on_new_hs_message(from, msg).await,
+            HotStuffInput::Vote(from, msg) => {
+                debug!(target: LOG_TARGET, "Received vote from {}", from);
+                self.leader_on_receive_vote(from, msg).await
+            },
+            HotStuffInput::RecoveryMessage(from, msg) => {
+                debug!(target: LOG_TARGET, "Received recovery message from {}", from);
+                self.on_receive_recovery_message(from, msg).await
+            },
+            HotStuffInput::PacemakerTimeout(timeout) => {
+                debug!(target: LOG_TARGET, "Received timeout message for {:?}", timeout);
+                self.on_pacemaker_trigger(timeout).await
+            },
+        }
+    }
+
     pub async fn run(mut self, mut shutdown: ShutdownSignal) -> Result<(), HotStuffError> {
         loop {
-            tokio::select! {
+            let input = tokio::select! {
                 msg = self.rx_new.recv() => {
                     if let Some((payload, shard)) = msg {
-                        if let Err(e) = self.on_next_sync_view(payload, shard).await {
-                           error!(target: LOG_TARGET, "Error while processing new payload (on_next_sync_view): {}", e);
-                        }
-                        // self.on_beat(0, msg);
-                        // TODO: Start timer for receiving proposal
+                        HotStuffInput::NewPayload(payload, shard)
                     } else {
                         dbg!("All senders have dropped");
                         break;
                     }
                 },
-                Some((from, msg)) = self.rx_hs_message.recv() => {
-                    if let Err(e) = self.on_new_hs_message(from, msg).await {
-                        // self.publish_event(HotStuffEvent::Failed(e.to_string()));
-                        error!(target: LOG_TARGET, "Error while processing new hotstuff message (on_new_hs_message): {}", e);
-                    }
-                },
-                Some((from, msg)) = self.rx_votes.recv() => {
-                    debug!(target: LOG_TARGET, "Received vote from {}", from);
-                    if let Err(e) = self.leader_on_receive_vote(from, msg).await {
-                        error!(target: LOG_TARGET, "Error while processing vote (on_receive_vote): {}", e);
-                    }
-                },
-                Some((from,msg)) = self.rx_recovery_message.recv() => {
-                    debug!(target:LOG_TARGET, "Received recovery message from {}", from);
-                    if let Err(e) = self.on_receive_recovery_message(from, msg).await {
-                        error!(target: LOG_TARGET, "Error while processing recovery message (on_receive_recovery_message): {}", e);
-                    }
-                },
-                Some(timeout) = self.pacemaker.on_timeout() => {
-                    debug!(target:LOG_TARGET, "Received timeout message for {:?}", timeout);
-                    if let Err(e) = self.on_pacemaker_trigger(timeout).await {
-                        error!(target: LOG_TARGET, "Error while processing timeout message (on_timeout): {}", e);
-                    }
-                }
+                Some((from, msg)) = self.rx_hs_message.recv() => HotStuffInput::HotStuffMessage(from, msg),
+                Some((from, msg)) = self.rx_votes.recv() => HotStuffInput::Vote(from, msg),
+                Some((from, msg)) = self.rx_recovery_message.recv() => HotStuffInput::RecoveryMessage(from, msg),
+                Some(timeout) = self.pacemaker.on_timeout() => HotStuffInput::PacemakerTimeout(timeout),
                 _ = shutdown.wait() => {
                     info!(target: LOG_TARGET, "💤 Shutting down");
                     break;
                 }
+            };
+            let context = input.context();
+            if let Err(e) = self.handle_input(input).await {
+                error!(target: LOG_TARGET, "Error while processing {}: {}", context, e);
             }
         }
         Ok(())
     }
 }
 
+/// Applies the changes of an accepted payload in a batch to the state that the next payload in the batch is executed
+/// against
+fn apply_batch_member_diff(
+    state: &mut HashMap<ShardId, ObjectPledge>,
+    batch_id: PayloadId,
+    payload_id: PayloadId,
+    diff: &SubstateDiff,
+) {
+    for (address, version) in diff.down_iter() {
+        let shard_id = ShardId::from_address(address, *version);
+        if let Some(pledge) = state.get_mut(&shard_id) {
+            pledge.current_state = SubstateState::Down { deleted_by: payload_id };
+        }
+    }
+    for (address, substate) in diff.up_iter() {
+        let shard_id = ShardId::from_address(address, substate.version());
+        state.insert(shard_id, ObjectPledge {
+            shard_id,
+            pledged_to_payload: batch_id,
+            current_state: SubstateState::Up {
+                created_by: payload_id,
+                address: address.clone(),
+                data: substate.clone(),
+            },
+        });
+    }
+}
+
+/// Combines the results of the payloads in a batch into the result of the batch. The batch is accepted with the
+/// combined changes of all payloads whose changes were accepted, or rejected if there are none. A substate that is
+/// created and spent within the batch does not appear in the combined changes.
+fn merge_batch_results(batch_id: PayloadId, results: &[FinalizeResult]) -> FinalizeResult {
+    let mut downs = Vec::new();
+    let mut ups = Vec::<(SubstateAddress, Substate)>::new();
+    let mut logs = Vec::new();
+    let mut events = Vec::new();
+    let mut num_accepted = 0;
+    for result in results {
+        logs.extend(result.logs.iter().cloned());
+        if let Some(accepted_diff) = result.result.accept() {
+            num_accepted += 1;
+            for (address, version) in accepted_diff.down_iter() {
+                match ups
+                    .iter()
+                    .position(|(a, substate)| a == address && substate.version() == *version)
+                {
+                    Some(pos) => {
+                        ups.remove(pos);
+                    },
+                    None => downs.push((address.clone(), *version)),
+                }
+            }
+            ups.extend(
+                accepted_diff
+                    .up_iter()
+                    .map(|(address, substate)| (address.clone(), substate.clone())),
+            );
+            events.extend(result.events.iter().cloned());
+        }
+    }
+
+    let transaction_hash = batch_id.into_array().into();
+    if num_accepted == 0 {
+        return FinalizeResult::reject(
+            transaction_hash,
+            RejectReason::ExecutionFailure(format!("All {} payload(s) in the batch were rejected", results.len())),
+        );
+    }
+
+    let mut diff = SubstateDiff::new();
+    for (address, version) in downs {
+        diff.down(address, version);
+    }
+    for (address, substate) in ups {
+        diff.up(address, substate);
+    }
+    FinalizeResult::new(transaction_hash, logs, events, TransactionResult::Accept(diff))
+}
+
 fn extract_changes_for_shards(
     shard_ids: &[ShardId],
     payload_id: PayloadId,
@@ -1879,3 +2285,90 @@ fn extract_changes_for_shards(
 
     Ok(changes)
 }
+
+/// Two different nodes conflict if they are for the same position in the same leader round. The nodes are expected to
+/// be for the same payload, shard and payload height.
+fn is_conflicting<TAddr: NodeAddressable, TPayload: Payload>(
+    a: &HotStuffTreeNode<TAddr, TPayload>,
+    b: &HotStuffTreeNode<TAddr, TPayload>,
+) -> bool {
+    a.hash() != b.hash() && a.epoch() == b.epoch() && a.height() == b.height() && a.leader_round() == b.leader_round()
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_engine_types::key_value_store::KeyValueEntry;
+    use tari_template_lib::{
+        models::{KeyValueEntryAddress, KeyValueStoreId},
+        Hash,
+    };
+
+    use super::*;
+
+    fn create_substate(seed: u8, version: u32) -> (SubstateAddress, Substate) {
+        let address = SubstateAddress::KeyValueEntry(KeyValueEntryAddress::new(
+            KeyValueStoreId::new(Hash::from_array([seed; 32])),
+            vec![seed],
+        ));
+        (address, Substate::new(version, KeyValueEntry::new(vec![seed])))
+    }
+
+    fn accept(payload_id: PayloadId, diff: SubstateDiff) -> FinalizeResult {
+        FinalizeResult::new(
+            payload_id.into_array().into(),
+            vec![],
+            vec![],
+            TransactionResult::Accept(diff),
+        )
+    }
+
+    #[test]
+    fn batch_members_are_executed_against_the_state_left_by_earlier_members() {
+        let batch_id = PayloadId::new([1u8; 32]);
+        let first_id = PayloadId::new([2u8; 32]);
+        let second_id = PayloadId::new([3u8; 32]);
+        let (address, v0) = create_substate(1, 0);
+        let (_, v1) = create_substate(1, 1);
+        let v0_shard = ShardId::from_address(&address, 0);
+        let v1_shard = ShardId::from_address(&address, 1);
+        let mut state = HashMap::from([(v0_shard, ObjectPledge {
+            shard_id: v0_shard,
+            pledged_to_payload: batch_id,
+            current_state: SubstateState::Up {
+                created_by: PayloadId::new([0u8; 32]),
+                address: address.clone(),
+                data: v0,
+            },
+        })]);
+
+        // The first payload spends v0 and creates v1
+        let mut first = SubstateDiff::new();
+        first.down(address.clone(), 0);
+        first.up(address.clone(), v1.clone());
+        apply_batch_member_diff(&mut state, batch_id, first_id, &first);
+        assert!(
+            matches!(&state[&v0_shard].current_state, SubstateState::Down { deleted_by } if *deleted_by == first_id)
+        );
+        assert!(
+            matches!(&state[&v1_shard].current_state, SubstateState::Up { created_by, .. } if *created_by == first_id)
+        );
+
+        // The second payload spends v1, so v1 is created and spent within the batch
+        let mut second = SubstateDiff::new();
+        second.down(address.clone(), 1);
+        let (_, v2) = create_substate(1, 2);
+        second.up(address.clone(), v2);
+
+        let result = merge_batch_results(batch_id, &[accept(first_id, first), accept(second_id, second)]);
+        let diff = result.result.accept().unwrap();
+        assert_eq!(diff.down_iter().map(|(_, version)| *version).collect::<Vec<_>>(), vec![
+            0
+        ]);
+        assert_eq!(
+            diff.up_iter()
+                .map(|(_, substate)| substate.version())
+                .collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+}
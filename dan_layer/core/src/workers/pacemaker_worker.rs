@@ -174,6 +174,15 @@ pub struct PacemakerHandle<T> {
 }
 
 impl<T: Debug> PacemakerHandle<T> {
+    /// Creates a handle from its raw channels. [Pacemaker::spawn] should be used unless the caller drives the timers
+    /// itself, in which case it receives the start/stop signals and sends the timeouts.
+    pub fn new(tx_signal: Sender<PacemakerTimer<T>>, rx_timeout_status: Receiver<T>) -> Self {
+        Self {
+            tx_signal,
+            rx_timeout_status,
+        }
+    }
+
     pub async fn start_timer(&self, wait_over: T, duration_timeout: Duration) -> Result<(), HotStuffError> {
         info!(
             target: LOG_TARGET,
@@ -6,6 +6,7 @@ use tari_engine_types::{
     substate::{Substate, SubstateAddress},
 };
 use tari_template_lib::{
+    auth::{AccessRule, ResourceAccessRules},
     constants::{CONFIDENTIAL_TARI_RESOURCE_ADDRESS, PUBLIC_IDENTITY_RESOURCE_ADDRESS},
     models::Metadata,
     prelude::ResourceType,
@@ -16,10 +17,17 @@ use crate::state_store::{StateStoreError, StateWriter};
 
 pub fn bootstrap_state<T: StateWriter>(state_db: &mut T) -> Result<(), StateStoreError> {
     let address = SubstateAddress::Resource(PUBLIC_IDENTITY_RESOURCE_ADDRESS);
-    // Create the resource for badges
+    // Create the resource for badges. Badges are never minted or burnt, proofs of them are created by the engine.
     state_db.set_state(
         &address,
-        Substate::new(0, Resource::new(ResourceType::NonFungible, Default::default())),
+        Substate::new(
+            0,
+            Resource::new(
+                ResourceType::NonFungible,
+                ResourceAccessRules::deny_all(),
+                Default::default(),
+            ),
+        ),
     )?;
 
     // Create the second layer tari resource
@@ -27,9 +35,11 @@ pub fn bootstrap_state<T: StateWriter>(state_db: &mut T) -> Result<(), StateStor
     let mut metadata = Metadata::new();
     // TODO: decide on symbol for L2 tari
     metadata.insert(TOKEN_SYMBOL, "tXTR2".to_string());
+    // Layer two tari can only be created by claiming a layer one burn
+    let access_rules = ResourceAccessRules::new().mintable(AccessRule::DenyAll);
     state_db.set_state(
         &address,
-        Substate::new(0, Resource::new(ResourceType::Confidential, metadata)),
+        Substate::new(0, Resource::new(ResourceType::Confidential, access_rules, metadata)),
     )?;
 
     Ok(())
@@ -0,0 +1,60 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_engine_types::{fees::FeeSource, substate::SubstateDiff};
+use tari_template_lib::models::Amount;
+
+use crate::{
+    fees::FeeTable,
+    runtime::{RuntimeModule, RuntimeModuleError, StateTracker},
+};
+
+/// Charges transaction fees according to the given fee table. Transactions executed without this module are not
+/// charged any fees.
+pub struct FeeModule {
+    fee_table: FeeTable,
+}
+
+impl FeeModule {
+    pub fn new(fee_table: FeeTable) -> Self {
+        Self { fee_table }
+    }
+
+    fn charge(track: &StateTracker, source: FeeSource, units: u64, cost_per_unit: u64) {
+        let amount = Amount::try_from(units.saturating_mul(cost_per_unit)).unwrap_or_else(|_| Amount::new(i64::MAX));
+        track.add_fee_charge(source, amount);
+    }
+}
+
+impl RuntimeModule for FeeModule {
+    fn on_runtime_call(&self, track: &StateTracker, _call: &'static str) -> Result<(), RuntimeModuleError> {
+        Self::charge(track, FeeSource::RuntimeCall, 1, self.fee_table.per_runtime_call_cost);
+        Ok(())
+    }
+
+    fn on_wasm_executed(&self, track: &StateTracker, points_consumed: u64) -> Result<(), RuntimeModuleError> {
+        Self::charge(
+            track,
+            FeeSource::WasmExecution,
+            points_consumed,
+            self.fee_table.per_wasm_point_cost,
+        );
+        Ok(())
+    }
+
+    fn on_before_finalize(&self, track: &StateTracker, changes: &SubstateDiff) -> Result<(), RuntimeModuleError> {
+        let mut total_bytes = 0u64;
+        for (_, substate) in changes.up_iter() {
+            let encoded = tari_bor::encode(substate)
+                .map_err(|e| RuntimeModuleError::SubstateEncodingFailed { details: e.to_string() })?;
+            total_bytes = total_bytes.saturating_add(encoded.len() as u64);
+        }
+        Self::charge(
+            track,
+            FeeSource::Storage,
+            total_bytes,
+            self.fee_table.per_byte_storage_cost,
+        );
+        Ok(())
+    }
+}
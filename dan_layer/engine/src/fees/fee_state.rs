@@ -0,0 +1,40 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::BTreeMap;
+
+use tari_engine_types::{
+    fees::{FeeBreakdown, FeeSource},
+    resource_container::ResourceContainer,
+};
+use tari_template_lib::models::{Amount, VaultId};
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FeeState {
+    /// Funds locked for fee payment, along with the vault that any refund is returned to
+    pub fee_payments: Vec<(ResourceContainer, VaultId)>,
+    pub fee_charges: BTreeMap<FeeSource, Amount>,
+}
+
+impl FeeState {
+    pub fn add_charge(&mut self, source: FeeSource, amount: Amount) {
+        let charge = self.fee_charges.entry(source).or_insert_with(Amount::zero);
+        *charge = charge.saturating_add(&amount);
+    }
+
+    pub fn total_charges(&self) -> Amount {
+        self.fee_charges
+            .values()
+            .fold(Amount::zero(), |total, amount| total.saturating_add(amount))
+    }
+
+    pub fn cost_breakdown(&self) -> Vec<FeeBreakdown> {
+        self.fee_charges
+            .iter()
+            .map(|(source, amount)| FeeBreakdown {
+                source: *source,
+                amount: *amount,
+            })
+            .collect()
+    }
+}
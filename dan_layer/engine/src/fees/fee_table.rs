@@ -0,0 +1,26 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeeTable {
+    /// Charged for every call the template makes to the engine
+    pub per_runtime_call_cost: u64,
+    /// Charged per WASM metering point consumed
+    pub per_wasm_point_cost: u64,
+    /// Charged per byte of substate written by the transaction
+    pub per_byte_storage_cost: u64,
+}
+
+impl FeeTable {
+    /// A fee table that charges nothing, so that every transaction is considered paid in full
+    pub const fn zero_rated() -> Self {
+        Self {
+            per_runtime_call_cost: 0,
+            per_wasm_point_cost: 0,
+            per_byte_storage_cost: 0,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::runtime::{RuntimeModule, RuntimeModuleError, StateTracker};
+
+/// The fuel consumed by each call the template makes to the engine
+pub const RUNTIME_CALL_FUEL: u64 = 1_000;
+
+/// Aborts the transaction once the WASM metering points and engine calls that it consumed exceed the fuel limit. This
+/// bounds the work done for a transaction independently of whether it pays any fees.
+pub struct FuelLimitModule {
+    limit: u64,
+    consumed: AtomicU64,
+}
+
+impl FuelLimitModule {
+    pub fn new(limit: u64) -> Self {
+        Self {
+            limit,
+            consumed: AtomicU64::new(0),
+        }
+    }
+
+    fn consume(&self, fuel: u64) -> Result<(), RuntimeModuleError> {
+        let consumed = self.consumed.fetch_add(fuel, Ordering::Relaxed).saturating_add(fuel);
+        if consumed > self.limit {
+            return Err(RuntimeModuleError::FuelExhausted { limit: self.limit });
+        }
+        Ok(())
+    }
+}
+
+impl RuntimeModule for FuelLimitModule {
+    fn on_runtime_call(&self, _track: &StateTracker, _call: &'static str) -> Result<(), RuntimeModuleError> {
+        self.consume(RUNTIME_CALL_FUEL)
+    }
+
+    fn on_wasm_executed(&self, _track: &StateTracker, points_consumed: u64) -> Result<(), RuntimeModuleError> {
+        self.consume(points_consumed)
+    }
+}
@@ -0,0 +1,14 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+mod fee_module;
+pub use fee_module::FeeModule;
+
+mod fee_state;
+pub(crate) use fee_state::FeeState;
+
+mod fee_table;
+pub use fee_table::FeeTable;
+
+mod fuel_limit_module;
+pub use fuel_limit_module::{FuelLimitModule, RUNTIME_CALL_FUEL};
@@ -3,6 +3,7 @@
 
 // pub mod crypto;
 // pub mod flow;
+pub mod fees;
 pub mod function_definitions;
 pub mod packager;
 pub mod runtime;
@@ -20,6 +20,8 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
+use tari_template_lib::models::TemplateAddress;
+
 use crate::wasm::WasmExecutionError;
 
 #[derive(Debug, thiserror::Error)]
@@ -36,4 +38,6 @@ pub enum PackageError {
     HostEnvInitError(#[from] wasmer::HostEnvInitError),
     #[error("Runtime error: {0}")]
     RuntimeError(#[from] wasmer::RuntimeError),
+    #[error("Failed to load template {address}: {details}")]
+    TemplateLoadFailed { address: TemplateAddress, details: String },
 }
@@ -24,7 +24,7 @@ mod error;
 pub use error::PackageError;
 
 mod package;
-pub use package::{Package, PackageBuilder};
+pub use package::{Package, PackageBuilder, TemplateResolver, MAX_TEMPLATE_LINEAGE_DEPTH};
 
 mod template;
 pub use template::LoadedTemplate;
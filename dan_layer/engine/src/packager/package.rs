@@ -20,16 +20,33 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
 
-use tari_template_abi::TemplateDef;
 use tari_template_lib::models::TemplateAddress;
 
-use crate::packager::template::LoadedTemplate;
+use crate::packager::{template::LoadedTemplate, PackageError};
 
-#[derive(Debug, Clone)]
+/// The maximum number of predecessors that are followed when checking whether one template is an upgrade of another
+pub const MAX_TEMPLATE_LINEAGE_DEPTH: usize = 32;
+
+/// Loads templates that were not added to a package when it was built. This allows a template to call templates and
+/// components that are only known once it executes.
+pub trait TemplateResolver: Send + Sync {
+    /// Returns the template at the given address, or None if no template is registered at that address
+    fn resolve_template(&self, address: &TemplateAddress) -> Result<Option<LoadedTemplate>, PackageError>;
+    /// Returns the address of the template that the given template is registered as an upgrade of, if any
+    fn resolve_template_predecessor(&self, address: &TemplateAddress) -> Result<Option<TemplateAddress>, PackageError>;
+}
+
+#[derive(Clone)]
 pub struct Package {
-    templates: HashMap<TemplateAddress, LoadedTemplate>,
+    templates: Arc<RwLock<HashMap<TemplateAddress, LoadedTemplate>>>,
+    predecessors: Arc<RwLock<HashMap<TemplateAddress, TemplateAddress>>>,
+    resolver: Option<Arc<dyn TemplateResolver>>,
 }
 
 impl Package {
@@ -37,27 +54,82 @@ impl Package {
         PackageBuilder::new()
     }
 
-    pub fn get_template_by_address(&self, addr: &TemplateAddress) -> Option<&LoadedTemplate> {
-        self.templates.get(addr)
+    /// Returns the template at the given address. Templates that are not in the package are loaded from the resolver,
+    /// if one is set.
+    pub fn get_template_by_address(&self, addr: &TemplateAddress) -> Result<Option<LoadedTemplate>, PackageError> {
+        if let Some(template) = self.templates.read().unwrap().get(addr) {
+            return Ok(Some(template.clone()));
+        }
+        let Some(resolver) = &self.resolver else {
+            return Ok(None);
+        };
+        let maybe_template = resolver.resolve_template(addr)?;
+        if let Some(template) = &maybe_template {
+            self.templates.write().unwrap().insert(*addr, template.clone());
+        }
+        Ok(maybe_template)
+    }
+
+    /// Records that `template` is registered as an upgrade of `predecessor`
+    pub fn set_template_predecessor(&mut self, template: TemplateAddress, predecessor: TemplateAddress) {
+        self.predecessors.write().unwrap().insert(template, predecessor);
+    }
+
+    /// Returns true if `template` is a (possibly indirect) upgrade of `predecessor`
+    pub fn is_template_upgrade_of(
+        &self,
+        template: &TemplateAddress,
+        predecessor: &TemplateAddress,
+    ) -> Result<bool, PackageError> {
+        let mut current = *template;
+        for _ in 0..MAX_TEMPLATE_LINEAGE_DEPTH {
+            match self.get_template_predecessor(&current)? {
+                Some(addr) if addr == *predecessor => return Ok(true),
+                Some(addr) => current = addr,
+                None => return Ok(false),
+            }
+        }
+        Ok(false)
+    }
+
+    fn get_template_predecessor(&self, addr: &TemplateAddress) -> Result<Option<TemplateAddress>, PackageError> {
+        if let Some(predecessor) = self.predecessors.read().unwrap().get(addr) {
+            return Ok(Some(*predecessor));
+        }
+        let Some(resolver) = &self.resolver else {
+            return Ok(None);
+        };
+        let maybe_predecessor = resolver.resolve_template_predecessor(addr)?;
+        if let Some(predecessor) = maybe_predecessor {
+            self.predecessors.write().unwrap().insert(*addr, predecessor);
+        }
+        Ok(maybe_predecessor)
     }
+}
 
-    pub fn get_template_defs(&self) -> HashMap<TemplateAddress, TemplateDef> {
-        self.templates
-            .iter()
-            .map(|(addr, template)| (*addr, template.template_def().clone()))
-            .collect()
+impl fmt::Debug for Package {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Package")
+            .field("templates", &self.templates)
+            .field("predecessors", &self.predecessors)
+            .field("has_resolver", &self.resolver.is_some())
+            .finish()
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Clone, Default)]
 pub struct PackageBuilder {
     templates: HashMap<TemplateAddress, LoadedTemplate>,
+    predecessors: HashMap<TemplateAddress, TemplateAddress>,
+    resolver: Option<Arc<dyn TemplateResolver>>,
 }
 
 impl PackageBuilder {
     pub fn new() -> Self {
         Self {
             templates: HashMap::new(),
+            predecessors: HashMap::new(),
+            resolver: None,
         }
     }
 
@@ -67,9 +139,23 @@ impl PackageBuilder {
         self
     }
 
+    /// Records that the template at `address` is registered as an upgrade of the template at `predecessor`.
+    pub fn add_template_predecessor(&mut self, address: TemplateAddress, predecessor: TemplateAddress) -> &mut Self {
+        self.predecessors.insert(address, predecessor);
+        self
+    }
+
+    /// Sets the resolver that loads templates and predecessors that were not added to the package
+    pub fn with_resolver<T: TemplateResolver + 'static>(&mut self, resolver: T) -> &mut Self {
+        self.resolver = Some(Arc::new(resolver));
+        self
+    }
+
     pub fn build(&mut self) -> Package {
         Package {
-            templates: self.templates.drain().collect(),
+            templates: Arc::new(RwLock::new(self.templates.drain().collect())),
+            predecessors: Arc::new(RwLock::new(self.predecessors.drain().collect())),
+            resolver: self.resolver.take(),
         }
     }
 }
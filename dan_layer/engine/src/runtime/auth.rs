@@ -1,7 +1,14 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use tari_template_lib::{auth::AccessRules, models::NonFungibleAddress};
+use std::collections::BTreeSet;
+
+use tari_engine_types::proof::Proof;
+use tari_template_lib::{
+    auth::{AccessRule, AccessRules, RestrictedAccessRule},
+    models::{Amount, NonFungibleAddress},
+    resource::ResourceType,
+};
 
 use crate::runtime::{FunctionIdent, RuntimeError};
 
@@ -14,38 +21,92 @@ pub struct AuthorizationScope<'a> {
     /// Virtual proofs are system-issued non-fungibles that exist for no longer than the execution e.g. derived from
     /// the transaction sender public key
     virtual_proofs: &'a [NonFungibleAddress],
+    /// Proofs of resources held in vaults or buckets that were created during the transaction
+    proofs: &'a [Proof],
 }
 
 impl<'a> AuthorizationScope<'a> {
-    pub fn new(virtual_proofs: &'a [NonFungibleAddress]) -> Self {
-        Self { virtual_proofs }
+    pub fn new(virtual_proofs: &'a [NonFungibleAddress], proofs: &'a [Proof]) -> Self {
+        Self { virtual_proofs, proofs }
     }
 
     pub fn check_access_rules(&self, fn_ident: &FunctionIdent, access_rules: &AccessRules) -> Result<(), RuntimeError> {
         match fn_ident {
             FunctionIdent::Native(native_fn) => {
-                if access_rules
-                    .get_native_access_rule(native_fn)
-                    .is_access_allowed(self.virtual_proofs)
-                {
-                    Ok(())
-                } else {
-                    Err(RuntimeError::AccessDenied {
-                        fn_ident: fn_ident.clone(),
-                    })
-                }
+                self.check_access_rule(fn_ident, access_rules.get_native_access_rule(native_fn))
             },
             FunctionIdent::Template { function, .. } => {
-                if access_rules
-                    .get_method_access_rule(function)
-                    .is_access_allowed(self.virtual_proofs)
-                {
-                    Ok(())
-                } else {
-                    Err(RuntimeError::AccessDenied {
-                        fn_ident: fn_ident.clone(),
+                self.check_access_rule(fn_ident, access_rules.get_method_access_rule(function))
+            },
+        }
+    }
+
+    pub fn check_access_rule(&self, fn_ident: &FunctionIdent, rule: &AccessRule) -> Result<(), RuntimeError> {
+        if self.is_access_allowed(rule) {
+            Ok(())
+        } else {
+            Err(RuntimeError::AccessDenied {
+                fn_ident: fn_ident.clone(),
+            })
+        }
+    }
+
+    fn is_access_allowed(&self, rule: &AccessRule) -> bool {
+        match rule {
+            AccessRule::AllowAll => true,
+            AccessRule::DenyAll => false,
+            AccessRule::Restricted(rule) => self.is_restricted_access_allowed(rule),
+        }
+    }
+
+    fn is_restricted_access_allowed(&self, rule: &RestrictedAccessRule) -> bool {
+        match rule {
+            RestrictedAccessRule::Require(address) => {
+                self.virtual_proofs.contains(address) ||
+                    self.proofs.iter().any(|proof| {
+                        proof.resource_address() == address.resource_address() &&
+                            proof.non_fungible_ids().contains(address.id())
                     })
+            },
+            RestrictedAccessRule::RequireResource(resource_address) => {
+                self.virtual_proofs
+                    .iter()
+                    .any(|proof| proof.resource_address() == resource_address) ||
+                    self.proofs
+                        .iter()
+                        .any(|proof| proof.resource_address() == resource_address && proof.amount().is_positive())
+            },
+            RestrictedAccessRule::RequireAmount(resource_address, amount) => {
+                let num_virtual_proofs = self
+                    .virtual_proofs
+                    .iter()
+                    .filter(|proof| proof.resource_address() == resource_address)
+                    .count();
+                // Proofs never cover the same resources of a container, but a non-fungible is only counted once in
+                // case it is proven more than once
+                let mut proven_ids = BTreeSet::new();
+                let mut proven_amount = Amount::from(num_virtual_proofs);
+                for proof in self
+                    .proofs
+                    .iter()
+                    .filter(|proof| proof.resource_address() == resource_address)
+                {
+                    if proof.resource_type() == ResourceType::NonFungible {
+                        proven_ids.extend(proof.non_fungible_ids());
+                    } else {
+                        proven_amount = proven_amount.saturating_add(&proof.amount());
+                    }
                 }
+                proven_amount.saturating_add(&Amount::from(proven_ids.len())) >= *amount
+            },
+            RestrictedAccessRule::AnyOf(rules) => rules.iter().any(|rule| self.is_restricted_access_allowed(rule)),
+            RestrictedAccessRule::AllOf(rules) => rules.iter().all(|rule| self.is_restricted_access_allowed(rule)),
+            RestrictedAccessRule::MinOf(n, rules) => {
+                let num_satisfied = rules
+                    .iter()
+                    .filter(|rule| self.is_restricted_access_allowed(rule))
+                    .count();
+                num_satisfied >= *n as usize
             },
         }
     }
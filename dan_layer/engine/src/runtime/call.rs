@@ -0,0 +1,22 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_engine_types::TemplateAddress;
+use tari_template_lib::{args::Arg, models::ComponentAddress};
+
+use crate::packager::LoadedTemplate;
+
+/// The maximum depth of nested template calls in a single instruction
+pub const MAX_CALL_DEPTH: usize = 10;
+
+/// A call from the currently executing template to another template function or component method that has passed all
+/// checks and is ready to be invoked.
+#[derive(Debug, Clone)]
+pub struct PreparedCall {
+    pub template: LoadedTemplate,
+    pub template_address: TemplateAddress,
+    pub component_address: Option<ComponentAddress>,
+    pub function: String,
+    pub args: Vec<Arg>,
+    pub depth: usize,
+}
@@ -23,4 +23,9 @@
 #[derive(Debug, Clone)]
 pub struct ConsensusContext {
     pub current_epoch: u64,
+    /// The timestamp (seconds since the unix epoch) of the base layer block that started the current epoch
+    pub current_epoch_timestamp: u64,
+    /// The beacon of the current epoch, which every validator derives identically from the base layer. It is public
+    /// once the epoch starts, so values derived from it are predictable by the transaction sender.
+    pub current_epoch_beacon: [u8; 32],
 }
@@ -29,16 +29,22 @@ use tari_template_lib::models::{
     Amount,
     BucketId,
     ComponentAddress,
+    KeyValueStoreId,
     NonFungibleId,
+    ProofId,
     ResourceAddress,
+    TemplateAddress,
     UnclaimedConfidentialOutputAddress,
     VaultId,
+    VaultLock,
 };
 use tari_transaction::id_provider::MaxIdsExceeded;
 
 use crate::{
+    packager::PackageError,
     runtime::{FunctionIdent, RuntimeModuleError},
     state_store::StateStoreError,
+    transaction::TransactionError,
 };
 
 #[derive(Debug, thiserror::Error)]
@@ -78,6 +84,10 @@ pub enum RuntimeError {
     },
     #[error("Bucket not found with id {bucket_id}")]
     BucketNotFound { bucket_id: BucketId },
+    #[error("Vault {vault_id} is {lock}")]
+    VaultLocked { vault_id: VaultId, lock: VaultLock },
+    #[error("Proof not found with id {proof_id}")]
+    ProofNotFound { proof_id: ProofId },
     #[error("Resource not found with address {resource_address}")]
     ResourceNotFound { resource_address: ResourceAddress },
     #[error(transparent)]
@@ -110,6 +120,34 @@ pub enum RuntimeError {
     ConfidentialOutputAlreadyClaimed {
         address: UnclaimedConfidentialOutputAddress,
     },
+    #[error("Template not found at address {address}")]
+    TemplateNotFound { address: TemplateAddress },
+    #[error("Package error: {0}")]
+    PackageError(#[from] PackageError),
+    #[error("Maximum call depth of {max_depth} exceeded")]
+    MaxCallDepthExceeded { max_depth: usize },
+    #[error("Re-entrant call to component {address} is not permitted")]
+    ReentrantCall { address: ComponentAddress },
+    #[error("Template {to} is not registered as an upgrade of template {from}")]
+    InvalidTemplateUpgrade { from: TemplateAddress, to: TemplateAddress },
+    #[error("Component {address} cannot be upgraded while it is executing")]
+    UpgradeOfExecutingComponent { address: ComponentAddress },
+    #[error("Call to {function} failed: {source}")]
+    NestedCallFailed {
+        function: String,
+        source: Box<TransactionError>,
+    },
+    #[error("Fees can only be paid in {expected}, but got {resource_address}")]
+    InvalidFeeResource {
+        expected: ResourceAddress,
+        resource_address: ResourceAddress,
+    },
+    #[error("No fee checkpoint has been set")]
+    NoFeeCheckpoint,
+    #[error("Bucket {bucket_id} cannot be moved while it has proofs")]
+    BucketLockedByProof { bucket_id: BucketId },
+    #[error("Key-value store {store_id} can only be written by the component that owns it")]
+    KeyValueStoreAccessDenied { store_id: KeyValueStoreId },
 }
 
 impl RuntimeError {
@@ -125,6 +163,7 @@ impl IsNotFoundError for RuntimeError {
             RuntimeError::ComponentNotFound { .. } |
                 RuntimeError::VaultNotFound { .. } |
                 RuntimeError::BucketNotFound { .. } |
+                RuntimeError::ProofNotFound { .. } |
                 RuntimeError::ResourceNotFound { .. } |
                 RuntimeError::NonFungibleNotFound { .. }
         )
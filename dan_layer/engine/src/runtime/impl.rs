@@ -31,15 +31,24 @@ use tari_crypto::{
 use tari_engine_types::{
     commit_result::{FinalizeResult, RejectReason, TransactionResult},
     confidential::{get_commitment_factory, get_range_proof_service, ConfidentialClaim, ConfidentialOutput},
+    execution_result::{ExecutionResult, NestedCall, Type},
+    fees::FeeReceipt,
     hashing::ownership_proof_hasher,
+    key_value_store::KeyValueEntry,
     logs::LogEntry,
+    proof::ProofContainer,
     resource_container::ResourceContainer,
 };
 use tari_template_abi::TemplateDef;
 use tari_template_lib::{
+    arg,
     args::{
+        Arg,
         BucketAction,
         BucketRef,
+        CallAction,
+        CallFunctionArg,
+        CallMethodArg,
         ComponentAction,
         ComponentRef,
         ConfidentialRevealArg,
@@ -47,9 +56,12 @@ use tari_template_lib::{
         CreateComponentArg,
         CreateResourceArg,
         InvokeResult,
+        KeyValueStoreAction,
+        KeyValueStoreRef,
         LogLevel,
         MintResourceArg,
         NonFungibleAction,
+        ProofAction,
         ResourceAction,
         ResourceGetNonFungibleArg,
         ResourceRef,
@@ -58,29 +70,53 @@ use tari_template_lib::{
         VaultWithdrawArg,
         WorkspaceAction,
     },
-    auth::AccessRules,
-    constants::CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
-    models::{Amount, BucketId, ComponentAddress, ComponentHeader, NonFungibleAddress, VaultRef},
+    auth::{AccessRule, AccessRules, NativeFunctionCall},
+    constants::{CONFIDENTIAL_TARI_RESOURCE_ADDRESS, TEMPLATE_MIGRATION_FUNCTION},
+    models::{
+        Amount,
+        BucketId,
+        ComponentAddress,
+        ComponentHeader,
+        KeyValueEntryAddress,
+        Metadata,
+        NonFungibleAddress,
+        NonFungibleId,
+        ProofId,
+        TemplateAddress,
+        VaultId,
+        VaultLock,
+        VaultRef,
+    },
+    Hash,
 };
 use tari_utilities::ByteArray;
 
-use crate::runtime::{
-    engine_args::EngineArgs,
-    tracker::StateTracker,
-    AuthParams,
-    ConsensusContext,
-    RuntimeError,
-    RuntimeInterface,
-    RuntimeModule,
-    RuntimeState,
+use crate::{
+    packager::{LoadedTemplate, Package},
+    runtime::{
+        engine_args::EngineArgs,
+        tracker::StateTracker,
+        AuthParams,
+        AuthorizationScope,
+        ConsensusContext,
+        FunctionIdent,
+        PreparedCall,
+        RuntimeError,
+        RuntimeInterface,
+        RuntimeModule,
+        RuntimeState,
+        MAX_CALL_DEPTH,
+    },
 };
 
 const LOG_TARGET: &str = "tari::dan::engine::runtime::impl";
 
 pub struct RuntimeInterfaceImpl {
     tracker: StateTracker,
-    _auth_params: AuthParams,
+    package: Package,
+    auth_params: AuthParams,
     consensus: ConsensusContext,
+    transaction_hash: Hash,
     sender_public_key: RistrettoPublicKey,
     modules: Vec<Box<dyn RuntimeModule>>,
 }
@@ -88,27 +124,62 @@ pub struct RuntimeInterfaceImpl {
 impl RuntimeInterfaceImpl {
     pub fn new(
         tracker: StateTracker,
+        package: Package,
         auth_params: AuthParams,
         consensus: ConsensusContext,
+        transaction_hash: Hash,
         sender_public_key: RistrettoPublicKey,
         modules: Vec<Box<dyn RuntimeModule>>,
     ) -> Self {
         Self {
             tracker,
-            _auth_params: auth_params,
+            package,
+            auth_params,
             consensus,
+            transaction_hash,
             sender_public_key,
             modules,
         }
     }
 
-    // TODO: this will be needed when we restrict Resources
-    // fn check_access_rules(&self, function: FunctionIdent, access_rules: &AccessRules) -> Result<(), RuntimeError> {
-    //     // TODO: In this very basic auth system, you can only call on owned objects (because initial_ownership_proofs
-    // is     //       usually set to include the owner token).
-    //     let auth_zone = AuthorizationScope::new(&self.auth_params.initial_ownership_proofs);
-    //     auth_zone.check_access_rules(&function, access_rules)
-    // }
+    fn check_access_rules(&self, function: FunctionIdent, access_rules: &AccessRules) -> Result<(), RuntimeError> {
+        // TODO: In this very basic auth system, you can only call on owned objects (because initial_ownership_proofs
+        //       is usually set to include the owner token).
+        let proofs = self.tracker.list_proofs();
+        let auth_zone = AuthorizationScope::new(&self.auth_params.initial_ownership_proofs, &proofs);
+        auth_zone.check_access_rules(&function, access_rules)
+    }
+
+    fn check_access_rule(&self, function: FunctionIdent, access_rule: &AccessRule) -> Result<(), RuntimeError> {
+        let proofs = self.tracker.list_proofs();
+        let auth_zone = AuthorizationScope::new(&self.auth_params.initial_ownership_proofs, &proofs);
+        auth_zone.check_access_rule(&function, access_rule)
+    }
+
+    fn check_vault_unlocked(&self, vault_id: &VaultId) -> Result<(), RuntimeError> {
+        let current_epoch = self.consensus.current_epoch;
+        let current_epoch_timestamp = self.consensus.current_epoch_timestamp;
+        let lock = self.tracker.borrow_vault(vault_id, |vault| {
+            if vault.is_unlocked(current_epoch, current_epoch_timestamp) {
+                None
+            } else {
+                vault.lock().copied()
+            }
+        })?;
+        match lock {
+            Some(lock) => Err(RuntimeError::VaultLocked {
+                vault_id: *vault_id,
+                lock,
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn get_template(&self, address: &TemplateAddress) -> Result<LoadedTemplate, RuntimeError> {
+        self.package
+            .get_template_by_address(address)?
+            .ok_or(RuntimeError::TemplateNotFound { address: *address })
+    }
 
     fn invoke_on_runtime_call_modules(&self, function: &'static str) -> Result<(), RuntimeError> {
         for module in &self.modules {
@@ -116,6 +187,36 @@ impl RuntimeInterfaceImpl {
         }
         Ok(())
     }
+
+    /// Runs the finalization hooks of the runtime modules against the changes made by the transaction
+    fn finalize_modules(&self) -> Result<(), RuntimeError> {
+        self.invoke_on_runtime_call_modules("finalize")?;
+        let substate_diff = self.tracker.generate_substate_diff()?;
+        for module in &self.modules {
+            module.on_before_finalize(&self.tracker, &substate_diff)?;
+        }
+        Ok(())
+    }
+
+    /// Discards all changes except for the fee payment, which is charged in full.
+    fn finalize_fee_payment_only(&self, reason: RejectReason) -> Result<(TransactionResult, FeeReceipt), RuntimeError> {
+        if self.tracker.reset_to_fee_checkpoint().is_err() {
+            // No fee instructions were executed, so there is nothing to commit
+            let fee_receipt = self.tracker.finalize_fees()?;
+            return Ok((TransactionResult::Reject(reason), fee_receipt));
+        }
+
+        let fee_receipt = self.tracker.finalize_fees()?;
+        if fee_receipt.total_fee_payment.is_zero() {
+            return Ok((TransactionResult::Reject(reason), fee_receipt));
+        }
+
+        let result = match self.tracker.finalize() {
+            Ok(substate_diff) => TransactionResult::AcceptFeeRejectRest(substate_diff, reason),
+            Err(err) => TransactionResult::Reject(RejectReason::ExecutionFailure(err.to_string())),
+        };
+        Ok((result, fee_receipt))
+    }
 }
 
 impl RuntimeInterface for RuntimeInterfaceImpl {
@@ -141,11 +242,26 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
         Ok(())
     }
 
+    fn emit_event(&self, topic: String, payload: Metadata) -> Result<(), RuntimeError> {
+        self.invoke_on_runtime_call_modules("emit_event")?;
+        self.tracker.add_event(topic, payload)
+    }
+
     fn get_component(&self, address: &ComponentAddress) -> Result<ComponentHeader, RuntimeError> {
         self.invoke_on_runtime_call_modules("get_component")?;
         self.tracker.get_component(address)
     }
 
+    fn check_component_access_rules(&self, method: &str, component: &ComponentHeader) -> Result<(), RuntimeError> {
+        self.check_access_rules(
+            FunctionIdent::Template {
+                module_name: component.module_name.clone(),
+                function: method.to_string(),
+            },
+            &component.access_rules,
+        )
+    }
+
     fn component_invoke(
         &self,
         component_ref: ComponentRef,
@@ -157,8 +273,8 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
         match action {
             ComponentAction::Create => {
                 let arg: CreateComponentArg = args.get(0)?;
-                let template_def = self.tracker.get_template_def()?;
-                validate_access_rules(&arg.access_rules, template_def)?;
+                let template = self.get_template(&self.tracker.current_template_address()?)?;
+                validate_access_rules(&arg.access_rules, template.template_def())?;
                 let component_address =
                     self.tracker
                         .new_component(arg.module_name, arg.encoded_state, arg.access_rules)?;
@@ -204,6 +320,48 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
                 self.tracker.set_component(address, component)?;
                 Ok(InvokeResult::unit())
             },
+            ComponentAction::UpgradeTemplate => {
+                let address = component_ref
+                    .as_component_address()
+                    .ok_or_else(|| RuntimeError::InvalidArgument {
+                        argument: "component_ref",
+                        reason: "UpgradeTemplate component action requires a component address".to_string(),
+                    })?;
+                let template_address: TemplateAddress = args.get(0)?;
+                let mut component = self.tracker.get_component(&address)?;
+
+                self.check_access_rule(
+                    FunctionIdent::Native(NativeFunctionCall::Component(ComponentAction::UpgradeTemplate)),
+                    component.access_rules.get_upgrade_access_rule(),
+                )?;
+
+                // The calling method would overwrite the migrated state with its own when it returns
+                if self.tracker.is_component_executing(&address) {
+                    return Err(RuntimeError::UpgradeOfExecutingComponent { address });
+                }
+
+                if !self
+                    .package
+                    .is_template_upgrade_of(&template_address, &component.template_address)?
+                {
+                    return Err(RuntimeError::InvalidTemplateUpgrade {
+                        from: component.template_address,
+                        to: template_address,
+                    });
+                }
+                let template = self.get_template(&template_address)?;
+
+                let requires_migration = template
+                    .template_def()
+                    .get_function(TEMPLATE_MIGRATION_FUNCTION)
+                    .is_some();
+
+                component.template_address = template_address;
+                component.module_name = template.template_name().to_string();
+                self.tracker.set_component(address, component)?;
+                // The caller is responsible for running the migration function on the new template
+                Ok(InvokeResult::encode(&requires_migration)?)
+            },
         }
     }
 
@@ -219,7 +377,9 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
             ResourceAction::Create => {
                 let arg: CreateResourceArg = args.get(0)?;
 
-                let resource_address = self.tracker.new_resource(arg.resource_type, arg.metadata)?;
+                let resource_address = self
+                    .tracker
+                    .new_resource(arg.resource_type, arg.access_rules, arg.metadata)?;
 
                 let mut output_bucket = None;
                 if let Some(mint_arg) = arg.mint_arg {
@@ -269,7 +429,11 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
                             reason: "Mint resource action requires a resource address".to_string(),
                         })?;
                 let mint_resource: MintResourceArg = args.get(0)?;
-                // TODO: access check
+                let resource = self.tracker.get_resource(&resource_address)?;
+                self.check_access_rule(
+                    FunctionIdent::Native(NativeFunctionCall::Resource(ResourceAction::Mint)),
+                    resource.access_rules().get_mintable_rule(),
+                )?;
                 let bucket_id = self.tracker.mint_resource(resource_address, mint_resource.mint_arg)?;
                 let bucket = tari_template_lib::models::Bucket::from_id(bucket_id);
                 Ok(InvokeResult::encode(&bucket)?)
@@ -307,7 +471,11 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
                             reason: "UpdateNonFungibleData resource action requires a resource address".to_string(),
                         })?;
                 let arg: ResourceUpdateNonFungibleDataArg = args.get(0)?;
-                // TODO: access check
+                let resource = self.tracker.get_resource(&resource_address)?;
+                self.check_access_rule(
+                    FunctionIdent::Native(NativeFunctionCall::Resource(ResourceAction::UpdateNonFungibleData)),
+                    resource.access_rules().get_update_non_fungible_data_rule(),
+                )?;
                 self.tracker
                     .set_non_fungible_data(&NonFungibleAddress::new(resource_address, arg.id), arg.data)?;
 
@@ -335,9 +503,35 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
                     })?;
                 let resource = self.tracker.get_resource(resource_address)?;
 
-                let vault_id = self.tracker.new_vault(*resource_address, resource.resource_type())?;
+                let vault_id = self
+                    .tracker
+                    .new_vault(*resource_address, resource.resource_type(), None)?;
+                Ok(InvokeResult::encode(&vault_id)?)
+            },
+            VaultAction::CreateLocked => {
+                let resource_address = vault_ref
+                    .resource_address()
+                    .ok_or_else(|| RuntimeError::InvalidArgument {
+                        argument: "vault_ref",
+                        reason: "CreateLocked vault action requires a resource address".to_string(),
+                    })?;
+                let lock: VaultLock = args.get(0)?;
+                let resource = self.tracker.get_resource(resource_address)?;
+
+                let vault_id = self
+                    .tracker
+                    .new_vault(*resource_address, resource.resource_type(), Some(lock))?;
                 Ok(InvokeResult::encode(&vault_id)?)
             },
+            VaultAction::GetLock => {
+                let vault_id = vault_ref.vault_id().ok_or_else(|| RuntimeError::InvalidArgument {
+                    argument: "vault_ref",
+                    reason: "GetLock vault action requires a vault id".to_string(),
+                })?;
+
+                let lock = self.tracker.borrow_vault(&vault_id, |vault| vault.lock().copied())?;
+                Ok(InvokeResult::encode(&lock)?)
+            },
             VaultAction::Deposit => {
                 let vault_id = vault_ref.vault_id().ok_or_else(|| RuntimeError::InvalidArgument {
                     argument: "vault_ref",
@@ -356,6 +550,7 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
                     argument: "vault_ref",
                     reason: "WithdrawFungible vault action requires a vault id".to_string(),
                 })?;
+                self.check_vault_unlocked(&vault_id)?;
                 let arg: VaultWithdrawArg = args.get(0)?;
 
                 // TODO: access check
@@ -372,6 +567,7 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
                     argument: "vault_ref",
                     reason: "WithdrawAll vault action requires a vault id".to_string(),
                 })?;
+                self.check_vault_unlocked(&vault_id)?;
 
                 // TODO: access check
                 let resource = self
@@ -436,6 +632,7 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
                 })?;
 
                 let arg: ConfidentialRevealArg = args.get(0)?;
+                self.check_vault_unlocked(&vault_id)?;
 
                 // TODO: access check
                 let resource = self
@@ -445,6 +642,80 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
                 let bucket_id = self.tracker.new_bucket(resource)?;
                 Ok(InvokeResult::encode(&bucket_id)?)
             },
+            VaultAction::PayFee => {
+                let vault_id = vault_ref.vault_id().ok_or_else(|| RuntimeError::InvalidArgument {
+                    argument: "vault_ref",
+                    reason: "PayFee vault action requires a vault id".to_string(),
+                })?;
+                self.check_vault_unlocked(&vault_id)?;
+                let max_fee: Amount = args.get(0)?;
+
+                // TODO: access check
+                let resource = self.tracker.borrow_vault_mut(&vault_id, |vault| {
+                    if *vault.resource_address() != CONFIDENTIAL_TARI_RESOURCE_ADDRESS {
+                        return Err(RuntimeError::InvalidFeeResource {
+                            expected: CONFIDENTIAL_TARI_RESOURCE_ADDRESS,
+                            resource_address: *vault.resource_address(),
+                        });
+                    }
+                    Ok(vault.withdraw(max_fee)?)
+                })??;
+                self.tracker.pay_fee(resource, vault_id);
+                Ok(InvokeResult::unit())
+            },
+            VaultAction::CreateProofByResource => {
+                let vault_id = vault_ref.vault_id().ok_or_else(|| RuntimeError::InvalidArgument {
+                    argument: "vault_ref",
+                    reason: "CreateProofByResource vault action requires a vault id".to_string(),
+                })?;
+
+                // TODO: access check
+                let container = ProofContainer::Vault(vault_id);
+                let locked = self.tracker.locked_by_proofs(container);
+                let proof = self
+                    .tracker
+                    .borrow_vault(&vault_id, |vault| vault.create_proof(&locked))??;
+                let proof_id = self.tracker.new_proof(container, proof);
+                Ok(InvokeResult::encode(&tari_template_lib::models::Proof::from_id(
+                    proof_id,
+                ))?)
+            },
+            VaultAction::CreateProofByAmount => {
+                let vault_id = vault_ref.vault_id().ok_or_else(|| RuntimeError::InvalidArgument {
+                    argument: "vault_ref",
+                    reason: "CreateProofByAmount vault action requires a vault id".to_string(),
+                })?;
+                let amount: Amount = args.get(0)?;
+
+                // TODO: access check
+                let container = ProofContainer::Vault(vault_id);
+                let locked = self.tracker.locked_by_proofs(container);
+                let proof = self
+                    .tracker
+                    .borrow_vault(&vault_id, |vault| vault.create_proof_of_amount(&locked, amount))??;
+                let proof_id = self.tracker.new_proof(container, proof);
+                Ok(InvokeResult::encode(&tari_template_lib::models::Proof::from_id(
+                    proof_id,
+                ))?)
+            },
+            VaultAction::CreateProofByNonFungibles => {
+                let vault_id = vault_ref.vault_id().ok_or_else(|| RuntimeError::InvalidArgument {
+                    argument: "vault_ref",
+                    reason: "CreateProofByNonFungibles vault action requires a vault id".to_string(),
+                })?;
+                let ids: BTreeSet<NonFungibleId> = args.get(0)?;
+
+                // TODO: access check
+                let container = ProofContainer::Vault(vault_id);
+                let locked = self.tracker.locked_by_proofs(container);
+                let proof = self
+                    .tracker
+                    .borrow_vault(&vault_id, |vault| vault.create_proof_of_non_fungibles(&locked, ids))??;
+                let proof_id = self.tracker.new_proof(container, proof);
+                Ok(InvokeResult::encode(&tari_template_lib::models::Proof::from_id(
+                    proof_id,
+                ))?)
+            },
         }
     }
 
@@ -535,9 +806,59 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
                     argument: "bucket_ref",
                     reason: "Burn bucket action requires a bucket id".to_string(),
                 })?;
+                let bucket = self.tracker.get_bucket(bucket_id)?;
+                let resource = self.tracker.get_resource(bucket.resource_address())?;
+                self.check_access_rule(
+                    FunctionIdent::Native(NativeFunctionCall::Bucket(BucketAction::Burn)),
+                    resource.access_rules().get_burnable_rule(),
+                )?;
                 self.tracker.burn_bucket(bucket_id)?;
                 Ok(InvokeResult::unit())
             },
+            BucketAction::CreateProof => {
+                let bucket_id = bucket_ref.bucket_id().ok_or_else(|| RuntimeError::InvalidArgument {
+                    argument: "bucket_ref",
+                    reason: "CreateProof bucket action requires a bucket id".to_string(),
+                })?;
+                let container = ProofContainer::Bucket(bucket_id);
+                let locked = self.tracker.locked_by_proofs(container);
+                let proof = self.tracker.get_bucket(bucket_id)?.create_proof(&locked)?;
+                let proof_id = self.tracker.new_proof(container, proof);
+                Ok(InvokeResult::encode(&tari_template_lib::models::Proof::from_id(
+                    proof_id,
+                ))?)
+            },
+        }
+    }
+
+    fn proof_invoke(
+        &self,
+        proof_id: ProofId,
+        action: ProofAction,
+        _args: EngineArgs,
+    ) -> Result<InvokeResult, RuntimeError> {
+        self.invoke_on_runtime_call_modules("proof_invoke")?;
+
+        match action {
+            ProofAction::GetResourceAddress => {
+                let proof = self.tracker.get_proof(proof_id)?;
+                Ok(InvokeResult::encode(proof.resource_address())?)
+            },
+            ProofAction::GetAmount => {
+                let proof = self.tracker.get_proof(proof_id)?;
+                Ok(InvokeResult::encode(&proof.amount())?)
+            },
+            ProofAction::GetNonFungibles => {
+                let proof = self.tracker.get_proof(proof_id)?;
+                // NOTE: A BTreeSet does not decode when received in the WASM
+                Ok(InvokeResult::encode(
+                    &proof.non_fungible_ids().iter().collect::<Vec<_>>(),
+                )?)
+            },
+            ProofAction::Drop => {
+                self.tracker.drop_proof(proof_id)?;
+                Ok(InvokeResult::unit())
+            },
         }
     }
 
@@ -603,11 +924,123 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
         }
     }
 
+    fn key_value_store_invoke(
+        &self,
+        store_ref: KeyValueStoreRef,
+        action: KeyValueStoreAction,
+        args: EngineArgs,
+    ) -> Result<InvokeResult, RuntimeError> {
+        self.invoke_on_runtime_call_modules("key_value_store_invoke")?;
+
+        let value = match action {
+            KeyValueStoreAction::Create => {
+                let store_id = self.tracker.new_key_value_store()?;
+                return Ok(InvokeResult::encode(&store_id)?);
+            },
+            KeyValueStoreAction::Get => {
+                let address = get_key_value_entry_address(store_ref, &args)?;
+                self.tracker.get_key_value_entry(&address)?
+            },
+            KeyValueStoreAction::Insert => {
+                let address = get_key_value_entry_address(store_ref, &args)?;
+                self.tracker.check_key_value_store_access(address.store_id())?;
+                let value: Vec<u8> = args.get(1)?;
+                self.tracker
+                    .set_key_value_entry(address, Some(KeyValueEntry::new(value)))?
+            },
+            KeyValueStoreAction::Remove => {
+                let address = get_key_value_entry_address(store_ref, &args)?;
+                self.tracker.check_key_value_store_access(address.store_id())?;
+                self.tracker.set_key_value_entry(address, None)?
+            },
+        };
+
+        Ok(InvokeResult::encode(&value.and_then(KeyValueEntry::into_value))?)
+    }
+
     fn consensus_invoke(&self, action: ConsensusAction) -> Result<InvokeResult, RuntimeError> {
         self.invoke_on_runtime_call_modules("consensus_invoke")?;
         match action {
             ConsensusAction::GetCurrentEpoch => Ok(InvokeResult::encode(&self.consensus.current_epoch)?),
+            ConsensusAction::GetEpochTimestamp => Ok(InvokeResult::encode(&self.consensus.current_epoch_timestamp)?),
+            ConsensusAction::GetEpochBeacon => Ok(InvokeResult::encode(&self.consensus.current_epoch_beacon)?),
+            ConsensusAction::GenerateRandomBytes => {
+                let bytes = self
+                    .tracker
+                    .id_provider()
+                    .new_random_bytes(&self.consensus.current_epoch_beacon);
+                Ok(InvokeResult::encode(&bytes)?)
+            },
+        }
+    }
+
+    fn begin_call(&self, action: CallAction, args: EngineArgs) -> Result<PreparedCall, RuntimeError> {
+        self.invoke_on_runtime_call_modules("begin_call")?;
+
+        let (template_address, component_address, function, args) = match action {
+            CallAction::CallFunction => {
+                let arg: CallFunctionArg = args.get(0)?;
+                let args = arg.args.into_iter().map(Arg::Literal).collect();
+                (arg.template_address, None, arg.function, args)
+            },
+            CallAction::CallMethod => {
+                let arg: CallMethodArg = args.get(0)?;
+                let component = self.tracker.get_component(&arg.component_address)?;
+                // The callee is executed under its own access rules, regardless of the caller
+                self.check_access_rules(
+                    FunctionIdent::Template {
+                        module_name: component.module_name.clone(),
+                        function: arg.method.clone(),
+                    },
+                    &component.access_rules,
+                )?;
+
+                let mut final_args = Vec::with_capacity(arg.args.len() + 1);
+                final_args.push(arg![arg.component_address]);
+                final_args.extend(arg.args.into_iter().map(Arg::Literal));
+                (
+                    component.template_address,
+                    Some(arg.component_address),
+                    arg.method,
+                    final_args,
+                )
+            },
+        };
+
+        let template = self.get_template(&template_address)?;
+
+        let depth = self.tracker.push_call_frame(
+            RuntimeState {
+                template_address,
+                component_address,
+            },
+            MAX_CALL_DEPTH,
+        )?;
+        // Proofs passed to the callee are moved to its frame, so that they satisfy the access rules checked there
+        self.tracker
+            .move_proofs_to_current_frame(&argument_proof_ids(&template, &function, &args));
+
+        Ok(PreparedCall {
+            template,
+            template_address,
+            component_address,
+            function,
+            args,
+            depth,
+        })
+    }
+
+    fn end_call(&self, completed_call: Option<NestedCall>) -> Result<(), RuntimeError> {
+        self.invoke_on_runtime_call_modules("end_call")?;
+        let returned_proofs = completed_call
+            .as_ref()
+            .map(|call| returned_proof_ids(&call.result))
+            .unwrap_or_default();
+        self.tracker.pop_call_frame(&returned_proofs)?;
+        if let Some(call) = completed_call {
+            self.tracker.add_nested_call(call);
         }
+        Ok(())
     }
 
     fn generate_uuid(&self) -> Result<[u8; 32], RuntimeError> {
@@ -622,6 +1055,11 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
         Ok(())
     }
 
+    fn end_instruction(&self, result: &ExecutionResult) -> Result<(), RuntimeError> {
+        self.tracker.drop_unreturned_proofs(&returned_proof_ids(result));
+        Ok(())
+    }
+
     fn claim_burn(&self, claim: ConfidentialClaim) -> Result<(), RuntimeError> {
         let ConfidentialClaim {
             public_key: diffie_hellman_public_key,
@@ -674,14 +1112,50 @@ impl RuntimeInterface for RuntimeInterfaceImpl {
         Ok(())
     }
 
+    fn charge_wasm_execution(&self, points_consumed: u64) -> Result<(), RuntimeError> {
+        for module in &self.modules {
+            module.on_wasm_executed(&self.tracker, points_consumed)?;
+        }
+        Ok(())
+    }
+
+    fn fee_checkpoint(&self) -> Result<(), RuntimeError> {
+        self.tracker.fee_checkpoint();
+        Ok(())
+    }
+
+    fn reset_to_fee_checkpoint(&self) -> Result<(), RuntimeError> {
+        self.tracker.reset_to_fee_checkpoint()
+    }
+
     fn finalize(&self) -> Result<FinalizeResult, RuntimeError> {
-        self.invoke_on_runtime_call_modules("finalize")?;
-        let result = match self.tracker.finalize() {
-            Ok(substate_diff) => TransactionResult::Accept(substate_diff),
-            Err(err) => TransactionResult::Reject(RejectReason::ExecutionFailure(err.to_string())),
+        // A module may fail the transaction at this point (e.g. once the fuel limit is exceeded), in which case the fee
+        // payment is still committed
+        let (result, fee_receipt) = match self.finalize_modules() {
+            Ok(()) => {
+                let fee_receipt = self.tracker.finalize_fees()?;
+                if fee_receipt.is_paid_in_full() {
+                    match self.tracker.finalize() {
+                        Ok(substate_diff) => (TransactionResult::Accept(substate_diff), fee_receipt),
+                        Err(err) => self.finalize_fee_payment_only(RejectReason::ExecutionFailure(err.to_string()))?,
+                    }
+                } else {
+                    let reason = RejectReason::FeesNotPaid(format!(
+                        "Required {} but only {} was paid",
+                        fee_receipt.total_fees_charged(),
+                        fee_receipt.total_fee_payment
+                    ));
+                    self.finalize_fee_payment_only(reason)?
+                }
+            },
+            Err(err) => self.finalize_fee_payment_only(RejectReason::ExecutionFailure(err.to_string()))?,
         };
+
         let logs = self.tracker.take_logs();
-        let commit = FinalizeResult::new(self.tracker.transaction_hash(), logs, result);
+        let events = self.tracker.take_events();
+        let mut commit = FinalizeResult::new(self.transaction_hash, logs, events, result);
+        commit.nested_calls = self.tracker.take_nested_calls();
+        commit.fee_receipt = Some(fee_receipt);
 
         Ok(commit)
     }
@@ -698,3 +1172,49 @@ fn validate_access_rules(access_rules: &AccessRules, template_def: &TemplateDef)
     }
     Ok(())
 }
+
+fn get_key_value_entry_address(
+    store_ref: KeyValueStoreRef,
+    args: &EngineArgs,
+) -> Result<KeyValueEntryAddress, RuntimeError> {
+    let store_id = store_ref.store_id().ok_or_else(|| RuntimeError::InvalidArgument {
+        argument: "store_ref",
+        reason: "Key-value store entry actions require a store id".to_string(),
+    })?;
+    let key: Vec<u8> = args.get(0)?;
+    Ok(KeyValueEntryAddress::new(store_id, key))
+}
+
+/// Returns the ids of the proofs returned by a call, so that they can be moved to the caller's frame
+fn returned_proof_ids(result: &ExecutionResult) -> Vec<ProofId> {
+    proof_ids_in_value(&result.return_type, &result.raw)
+}
+
+/// Returns the ids of the proofs passed as arguments to a call, so that they can be moved to the callee's frame
+fn argument_proof_ids(template: &LoadedTemplate, function: &str, args: &[Arg]) -> Vec<ProofId> {
+    let Some(function_def) = template.template_def().get_function(function) else {
+        return Vec::new();
+    };
+    function_def
+        .arguments
+        .iter()
+        .zip(args)
+        .flat_map(|(ty, arg)| match arg {
+            Arg::Literal(value) => proof_ids_in_value(ty, value),
+            Arg::Variable(_) => Vec::new(),
+        })
+        .collect()
+}
+
+fn proof_ids_in_value(ty: &Type, value: &[u8]) -> Vec<ProofId> {
+    let is_proof = |ty: &Type| matches!(ty, Type::Other { name } if name == "Proof");
+    match ty {
+        ty if is_proof(ty) => tari_bor::decode::<tari_template_lib::models::Proof>(value)
+            .map(|proof| vec![proof.id()])
+            .unwrap_or_default(),
+        Type::Vec(ty) if is_proof(ty) => tari_bor::decode::<Vec<tari_template_lib::models::Proof>>(value)
+            .map(|proofs| proofs.iter().map(|proof| proof.id()).collect())
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
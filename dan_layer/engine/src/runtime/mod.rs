@@ -23,6 +23,9 @@
 mod auth;
 pub use auth::{AuthParams, AuthorizationScope};
 
+mod call;
+pub use call::{PreparedCall, MAX_CALL_DEPTH};
+
 mod r#impl;
 pub use r#impl::RuntimeInterfaceImpl;
 
@@ -49,35 +52,53 @@ mod tests;
 
 use std::{fmt::Debug, sync::Arc};
 
-use tari_engine_types::{commit_result::FinalizeResult, confidential::ConfidentialClaim};
+use tari_engine_types::{
+    commit_result::FinalizeResult,
+    confidential::ConfidentialClaim,
+    execution_result::{ExecutionResult, NestedCall},
+};
 use tari_template_lib::{
     args::{
         Arg,
         BucketAction,
         BucketRef,
+        CallAction,
+        CallFunctionArg,
         ComponentAction,
         ComponentRef,
         ConsensusAction,
         InvokeResult,
+        KeyValueStoreAction,
+        KeyValueStoreRef,
         LogLevel,
         NonFungibleAction,
+        ProofAction,
         ResourceAction,
         ResourceRef,
         VaultAction,
         WorkspaceAction,
     },
+    constants::TEMPLATE_MIGRATION_FUNCTION,
     invoke_args,
-    models::{ComponentAddress, ComponentHeader, NonFungibleAddress, VaultRef},
+    models::{ComponentAddress, ComponentHeader, Metadata, NonFungibleAddress, ProofId, VaultRef},
 };
 pub use tracker::{RuntimeState, StateTracker};
 
+use crate::transaction::TransactionProcessor;
+
 pub trait RuntimeInterface: Send + Sync {
     fn set_current_runtime_state(&self, state: RuntimeState) -> Result<(), RuntimeError>;
 
     fn emit_log(&self, level: LogLevel, message: String) -> Result<(), RuntimeError>;
 
+    fn emit_event(&self, topic: String, payload: Metadata) -> Result<(), RuntimeError>;
+
     fn get_component(&self, address: &ComponentAddress) -> Result<ComponentHeader, RuntimeError>;
 
+    /// Checks that the method may be called on the component given the virtual proofs and the proofs created so far in
+    /// the transaction
+    fn check_component_access_rules(&self, method: &str, component: &ComponentHeader) -> Result<(), RuntimeError>;
+
     fn component_invoke(
         &self,
         component_ref: ComponentRef,
@@ -106,6 +127,13 @@ pub trait RuntimeInterface: Send + Sync {
         args: EngineArgs,
     ) -> Result<InvokeResult, RuntimeError>;
 
+    fn proof_invoke(
+        &self,
+        proof_id: ProofId,
+        action: ProofAction,
+        args: EngineArgs,
+    ) -> Result<InvokeResult, RuntimeError>;
+
     fn workspace_invoke(&self, action: WorkspaceAction, args: EngineArgs) -> Result<InvokeResult, RuntimeError>;
 
     fn non_fungible_invoke(
@@ -115,14 +143,41 @@ pub trait RuntimeInterface: Send + Sync {
         args: EngineArgs,
     ) -> Result<InvokeResult, RuntimeError>;
 
+    fn key_value_store_invoke(
+        &self,
+        store_ref: KeyValueStoreRef,
+        action: KeyValueStoreAction,
+        args: EngineArgs,
+    ) -> Result<InvokeResult, RuntimeError>;
+
     fn consensus_invoke(&self, action: ConsensusAction) -> Result<InvokeResult, RuntimeError>;
 
+    /// Checks and prepares a call to another template function or component method, suspending the runtime state of
+    /// the caller. Every successful call to `begin_call` must be followed by a call to `end_call`.
+    fn begin_call(&self, action: CallAction, args: EngineArgs) -> Result<PreparedCall, RuntimeError>;
+
+    /// Restores the runtime state of the caller. The call is recorded if it completed successfully.
+    fn end_call(&self, completed_call: Option<NestedCall>) -> Result<(), RuntimeError>;
+
     fn generate_uuid(&self) -> Result<[u8; 32], RuntimeError>;
 
     fn set_last_instruction_output(&self, value: Option<Vec<u8>>) -> Result<(), RuntimeError>;
 
+    /// Drops the proofs created by a top-level call instruction that the call did not return. Returned proofs remain
+    /// available to the instructions that follow.
+    fn end_instruction(&self, result: &ExecutionResult) -> Result<(), RuntimeError>;
+
     fn claim_burn(&self, claim: ConfidentialClaim) -> Result<(), RuntimeError>;
 
+    /// Records the metering points consumed by a WASM call so that the execution can be charged for.
+    fn charge_wasm_execution(&self, points_consumed: u64) -> Result<(), RuntimeError>;
+
+    /// Saves the state after the fee instructions have been executed.
+    fn fee_checkpoint(&self) -> Result<(), RuntimeError>;
+
+    /// Discards all changes made after the fee checkpoint, keeping the fees charged so far.
+    fn reset_to_fee_checkpoint(&self) -> Result<(), RuntimeError>;
+
     fn finalize(&self) -> Result<FinalizeResult, RuntimeError>;
 }
 
@@ -147,6 +202,74 @@ impl Runtime {
         }
         Ok(resolved)
     }
+
+    /// Invokes a component action. After a component's template is upgraded, the migration function of the new
+    /// template (if defined) is called with the previous component state and its result becomes the new state.
+    pub(crate) fn component_invoke(
+        &self,
+        component_ref: ComponentRef,
+        action: ComponentAction,
+        args: EngineArgs,
+    ) -> Result<InvokeResult, RuntimeError> {
+        if action != ComponentAction::UpgradeTemplate {
+            return self.interface.component_invoke(component_ref, action, args);
+        }
+
+        let address = component_ref
+            .as_component_address()
+            .ok_or_else(|| RuntimeError::InvalidArgument {
+                argument: "component_ref",
+                reason: "UpgradeTemplate component action requires a component address".to_string(),
+            })?;
+        let previous = self.interface.get_component(&address)?;
+        let result = self.interface.component_invoke(component_ref, action, args)?;
+        let requires_migration: bool = result.decode()?;
+        if requires_migration {
+            let upgraded = self.interface.get_component(&address)?;
+            let migrated = self.call_invoke(
+                CallAction::CallFunction,
+                invoke_args![CallFunctionArg {
+                    template_address: upgraded.template_address,
+                    function: TEMPLATE_MIGRATION_FUNCTION.to_string(),
+                    args: invoke_args![previous.template_address, previous.state().to_vec()],
+                }]
+                .into(),
+            )?;
+            let state: Vec<u8> = migrated.decode()?;
+            self.interface.component_invoke(
+                ComponentRef::Ref(address),
+                ComponentAction::SetState,
+                invoke_args![state].into(),
+            )?;
+        }
+
+        Ok(InvokeResult::unit())
+    }
+
+    pub(crate) fn call_invoke(&self, action: CallAction, args: EngineArgs) -> Result<InvokeResult, RuntimeError> {
+        let call = self.interface.begin_call(action, args)?;
+        let result = TransactionProcessor::invoke_template(call.template, self.clone(), &call.function, call.args);
+        match result {
+            Ok(result) => {
+                let raw = result.raw.clone();
+                self.interface.end_call(Some(NestedCall {
+                    depth: call.depth,
+                    template_address: call.template_address,
+                    component_address: call.component_address,
+                    function: call.function,
+                    result,
+                }))?;
+                Ok(InvokeResult::raw(raw))
+            },
+            Err(err) => {
+                self.interface.end_call(None)?;
+                Err(RuntimeError::NestedCallFailed {
+                    function: call.function,
+                    source: Box::new(err),
+                })
+            },
+        }
+    }
 }
 
 impl Runtime {
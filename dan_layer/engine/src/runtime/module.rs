@@ -1,12 +1,24 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
+use tari_engine_types::substate::SubstateDiff;
+
 use crate::runtime::StateTracker;
 
 pub trait RuntimeModule: Send + Sync {
     fn on_runtime_call(&self, _track: &StateTracker, _call: &'static str) -> Result<(), RuntimeModuleError> {
         Ok(())
     }
+
+    /// Called after a WASM function returns (successfully or not) with the number of metering points it consumed.
+    fn on_wasm_executed(&self, _track: &StateTracker, _points_consumed: u64) -> Result<(), RuntimeModuleError> {
+        Ok(())
+    }
+
+    /// Called with the substate changes of the transaction before fees are settled and the transaction is finalized.
+    fn on_before_finalize(&self, _track: &StateTracker, _changes: &SubstateDiff) -> Result<(), RuntimeModuleError> {
+        Ok(())
+    }
     // Add more runtime "hooks"
 }
 
@@ -14,4 +26,8 @@ pub trait RuntimeModule: Send + Sync {
 pub enum RuntimeModuleError {
     #[error("Todo")]
     Todo,
+    #[error("Failed to encode substate: {details}")]
+    SubstateEncodingFailed { details: String },
+    #[error("Transaction exceeded the fuel limit of {limit}")]
+    FuelExhausted { limit: u64 },
 }
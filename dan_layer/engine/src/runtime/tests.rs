@@ -1,8 +1,6 @@
 //   Copyright 2022 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::collections::HashMap;
-
 use tari_template_lib::{auth::AccessRules, Hash};
 use tari_transaction::id_provider::IdProvider;
 
@@ -18,11 +16,12 @@ mod tracker {
     #[test]
     fn it_creates_a_new_component() {
         let store = MemoryStateStore::default();
-        let tx_hash = Hash::default();
-        let id_provider = IdProvider::new(tx_hash, 1);
-        let tracker = StateTracker::new(store, id_provider, HashMap::default());
+        let id_seed = Hash::default();
+        let id_provider = IdProvider::new(id_seed, 1);
+        let tracker = StateTracker::new(store, id_provider);
         tracker.set_current_runtime_state(RuntimeState {
             template_address: Default::default(),
+            component_address: None,
         });
         let addr = tracker
             .new_component("test".to_string(), vec![1, 2, 3], AccessRules::new())
@@ -32,3 +31,72 @@ mod tracker {
         assert_eq!(component.state.state, vec![1, 2, 3]);
     }
 }
+
+mod auth {
+    use tari_template_lib::{
+        args::ResourceAction,
+        auth::{AccessRule, NativeFunctionCall, RestrictedAccessRule::*},
+        models::{Amount, NonFungibleAddress, NonFungibleId, ResourceAddress},
+        Hash,
+    };
+
+    use crate::runtime::{AuthorizationScope, FunctionIdent};
+
+    fn resource(n: u8) -> ResourceAddress {
+        ResourceAddress::new(Hash::from_array([n; 32]))
+    }
+
+    fn badge(resource_num: u8, id: u32) -> NonFungibleAddress {
+        NonFungibleAddress::new(resource(resource_num), NonFungibleId::from_u32(id))
+    }
+
+    fn is_allowed(proofs: &[NonFungibleAddress], rule: AccessRule) -> bool {
+        let fn_ident = FunctionIdent::Native(NativeFunctionCall::Resource(ResourceAction::Mint));
+        AuthorizationScope::new(proofs, &[])
+            .check_access_rule(&fn_ident, &rule)
+            .is_ok()
+    }
+
+    #[test]
+    fn it_checks_resource_requirements() {
+        let proofs = [badge(1, 1), badge(1, 2), badge(2, 1)];
+        assert!(is_allowed(&proofs, AccessRule::Restricted(Require(badge(1, 2)))));
+        assert!(!is_allowed(&proofs, AccessRule::Restricted(Require(badge(2, 2)))));
+        assert!(is_allowed(
+            &proofs,
+            AccessRule::Restricted(RequireResource(resource(2)))
+        ));
+        assert!(!is_allowed(
+            &proofs,
+            AccessRule::Restricted(RequireResource(resource(3)))
+        ));
+        assert!(is_allowed(
+            &proofs,
+            AccessRule::Restricted(RequireAmount(resource(1), Amount(2)))
+        ));
+        assert!(!is_allowed(
+            &proofs,
+            AccessRule::Restricted(RequireAmount(resource(2), Amount(2)))
+        ));
+    }
+
+    #[test]
+    fn it_checks_composite_rules() {
+        let proofs = [badge(1, 1), badge(2, 1)];
+        let signers = vec![Require(badge(1, 1)), Require(badge(2, 1)), Require(badge(3, 1))];
+
+        assert!(is_allowed(&proofs, AccessRule::Restricted(AnyOf(signers.clone()))));
+        assert!(!is_allowed(&proofs, AccessRule::Restricted(AllOf(signers.clone()))));
+        assert!(is_allowed(&proofs, AccessRule::Restricted(MinOf(2, signers.clone()))));
+        assert!(!is_allowed(&proofs, AccessRule::Restricted(MinOf(3, signers))));
+        assert!(is_allowed(
+            &proofs,
+            AccessRule::Restricted(AllOf(vec![
+                RequireResource(resource(1)),
+                AnyOf(vec![Require(badge(3, 1)), Require(badge(2, 1))]),
+            ]))
+        ));
+        assert!(!is_allowed(&proofs, AccessRule::Restricted(AnyOf(vec![]))));
+        assert!(is_allowed(&[], AccessRule::Restricted(MinOf(0, vec![]))));
+    }
+}
@@ -21,9 +21,9 @@
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
-    collections::{BTreeSet, HashMap},
+    collections::BTreeSet,
     mem,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
 };
 
 use log::debug;
@@ -31,40 +31,51 @@ use tari_dan_common_types::optional::Optional;
 use tari_engine_types::{
     bucket::Bucket,
     confidential::UnclaimedConfidentialOutput,
+    events::Event,
+    execution_result::NestedCall,
+    fees::{FeeReceipt, FeeSource},
+    key_value_store::KeyValueEntry,
     logs::LogEntry,
     non_fungible::NonFungibleContainer,
     non_fungible_index::NonFungibleIndex,
+    proof::{LockedResource, Proof, ProofContainer},
     resource::Resource,
     resource_container::ResourceContainer,
-    substate::{Substate, SubstateAddress, SubstateDiff},
+    substate::SubstateDiff,
     vault::Vault,
     TemplateAddress,
 };
-use tari_template_abi::TemplateDef;
 use tari_template_lib::{
     args::MintArg,
-    auth::AccessRules,
+    auth::{AccessRules, ResourceAccessRules},
     models::{
         Amount,
         BucketId,
         ComponentAddress,
         ComponentBody,
         ComponentHeader,
+        KeyValueEntryAddress,
+        KeyValueStoreId,
         Metadata,
         NonFungibleAddress,
         NonFungibleIndexAddress,
+        ProofId,
         ResourceAddress,
         UnclaimedConfidentialOutputAddress,
         VaultId,
+        VaultLock,
     },
     resource::ResourceType,
-    Hash,
 };
 use tari_transaction::id_provider::IdProvider;
 
 use crate::{
-    runtime::{working_state::WorkingState, RuntimeError, TransactionCommitError},
-    state_store::{memory::MemoryStateStore, AtomicDb, StateReader},
+    runtime::{
+        working_state::{ProofEntry, WorkingState},
+        RuntimeError,
+        TransactionCommitError,
+    },
+    state_store::memory::MemoryStateStore,
 };
 
 const LOG_TARGET: &str = "tari::dan::engine::runtime::state_tracker";
@@ -72,8 +83,9 @@ const LOG_TARGET: &str = "tari::dan::engine::runtime::state_tracker";
 #[derive(Debug, Clone)]
 pub struct StateTracker {
     working_state: Arc<RwLock<WorkingState>>,
+    /// The working state after the fee instructions have been executed
+    fee_checkpoint: Arc<Mutex<Option<WorkingState>>>,
     id_provider: IdProvider,
-    template_defs: HashMap<TemplateAddress, TemplateDef>,
 }
 
 impl StateTracker {}
@@ -81,18 +93,16 @@ impl StateTracker {}
 #[derive(Debug, Clone)]
 pub struct RuntimeState {
     pub template_address: TemplateAddress,
+    /// The component whose method is being executed, or None if a template function is being executed
+    pub component_address: Option<ComponentAddress>,
 }
 
 impl StateTracker {
-    pub fn new(
-        state_store: MemoryStateStore,
-        id_provider: IdProvider,
-        template_defs: HashMap<TemplateAddress, TemplateDef>,
-    ) -> Self {
+    pub fn new(state_store: MemoryStateStore, id_provider: IdProvider) -> Self {
         Self {
             working_state: Arc::new(RwLock::new(WorkingState::new(state_store))),
+            fee_checkpoint: Arc::new(Mutex::new(None)),
             id_provider,
-            template_defs,
         }
     }
 
@@ -104,12 +114,98 @@ impl StateTracker {
         self.write_with(|state| mem::take(&mut state.logs))
     }
 
-    pub fn get_template_def(&self) -> Result<&TemplateDef, RuntimeError> {
+    /// Records an event emitted by the currently executing template or component
+    pub fn add_event(&self, topic: String, payload: Metadata) -> Result<(), RuntimeError> {
         let runtime_state = self.runtime_state()?;
-        Ok(self
-            .template_defs
-            .get(&runtime_state.template_address)
-            .expect("Template def not found for current template"))
+        let event = Event::new(
+            topic,
+            runtime_state.template_address,
+            runtime_state.component_address,
+            payload,
+        );
+        self.write_with(|state| state.events.push(event));
+        Ok(())
+    }
+
+    pub fn take_events(&self) -> Vec<Event> {
+        self.write_with(|state| mem::take(&mut state.events))
+    }
+
+    pub fn add_nested_call(&self, call: NestedCall) {
+        self.write_with(|state| state.nested_calls.push(call));
+    }
+
+    pub fn take_nested_calls(&self) -> Vec<NestedCall> {
+        self.write_with(|state| mem::take(&mut state.nested_calls))
+    }
+
+    pub fn add_fee_charge(&self, source: FeeSource, amount: Amount) {
+        self.write_with(|state| state.fee_state.add_charge(source, amount));
+    }
+
+    pub fn pay_fee(&self, resource: ResourceContainer, return_vault: VaultId) {
+        self.write_with(|state| state.fee_state.fee_payments.push((resource, return_vault)));
+    }
+
+    /// Saves the current working state so that all changes made after the fee instructions can be discarded.
+    pub(crate) fn fee_checkpoint(&self) {
+        let checkpoint = self.read_with(|state| state.clone());
+        *self.fee_checkpoint.lock().unwrap() = Some(checkpoint);
+    }
+
+    /// Discards all changes made since the fee checkpoint. Fee charges and logs are kept so that the transaction is
+    /// still charged for the work that was done.
+    pub(crate) fn reset_to_fee_checkpoint(&self) -> Result<(), RuntimeError> {
+        let checkpoint = self
+            .fee_checkpoint
+            .lock()
+            .unwrap()
+            .clone()
+            .ok_or(RuntimeError::NoFeeCheckpoint)?;
+        self.write_with(|state| {
+            let fee_charges = mem::take(&mut state.fee_state.fee_charges);
+            let logs = mem::take(&mut state.logs);
+            *state = checkpoint;
+            state.fee_state.fee_charges = fee_charges;
+            state.logs = logs;
+        });
+        Ok(())
+    }
+
+    /// Takes the charged fees from the fee payments and refunds the remainder to the paying vaults.
+    ///
+    /// The fees taken are burnt: they are not deposited anywhere. The total supply of the Tari resource is not reduced,
+    /// because that would write the resource substate, which the transaction has not pledged.
+    pub(crate) fn finalize_fees(&self) -> Result<FeeReceipt, RuntimeError> {
+        self.write_with(|state| {
+            let total_fees_charged = state.fee_state.total_charges();
+            let mut total_fee_payment = Amount::zero();
+            let mut total_fees_paid = Amount::zero();
+
+            for (mut payment, vault_id) in mem::take(&mut state.fee_state.fee_payments) {
+                total_fee_payment += payment.amount();
+                let fee = (total_fees_charged - total_fees_paid).min(payment.amount());
+                if fee.is_positive() {
+                    // TODO: distribute the fees to the committee that processed the transaction instead of burning them
+                    payment.withdraw(fee)?;
+                    total_fees_paid += fee;
+                }
+                if payment.amount().is_positive() {
+                    state.borrow_vault_mut(&vault_id, |vault| vault.deposit(Bucket::new(payment)))??;
+                }
+            }
+
+            Ok(FeeReceipt {
+                total_fee_payment,
+                total_fees_paid,
+                cost_breakdown: state.fee_state.cost_breakdown(),
+            })
+        })
+    }
+
+    /// Returns the address of the template that is currently executing
+    pub fn current_template_address(&self) -> Result<TemplateAddress, RuntimeError> {
+        Ok(self.runtime_state()?.template_address)
     }
 
     fn check_amount(&self, amount: Amount) -> Result<(), RuntimeError> {
@@ -125,10 +221,11 @@ impl StateTracker {
     pub fn new_resource(
         &self,
         resource_type: ResourceType,
+        access_rules: ResourceAccessRules,
         metadata: Metadata,
     ) -> Result<ResourceAddress, RuntimeError> {
         let resource_address = self.id_provider.new_resource_address()?;
-        let resource = Resource::new(resource_type, metadata);
+        let resource = Resource::new(resource_type, access_rules, metadata);
         self.write_with(|state| {
             state.new_resources.insert(resource_address, resource);
         });
@@ -277,17 +374,74 @@ impl StateTracker {
         })
     }
 
+    /// Adds a proof of resources held in the container. The proven resources are locked in the container until the
+    /// proof is dropped or the call frame that owns it returns.
+    pub fn new_proof(&self, container: ProofContainer, proof: Proof) -> ProofId {
+        self.write_with(|state| {
+            let proof_id = self.id_provider.new_proof_id();
+            debug!(target: LOG_TARGET, "New proof: {}", proof_id);
+            let call_depth = state.call_frames.len();
+            state.proofs.insert(proof_id, ProofEntry {
+                proof,
+                container,
+                call_depth,
+            });
+            proof_id
+        })
+    }
+
+    pub fn locked_by_proofs(&self, container: ProofContainer) -> LockedResource {
+        self.read_with(|state| state.locked_by_proofs(container))
+    }
+
+    pub fn get_proof(&self, proof_id: ProofId) -> Result<Proof, RuntimeError> {
+        self.read_with(|state| {
+            state
+                .proofs
+                .get(&proof_id)
+                .map(|entry| &entry.proof)
+                .cloned()
+                .ok_or(RuntimeError::ProofNotFound { proof_id })
+        })
+    }
+
+    pub fn drop_proof(&self, proof_id: ProofId) -> Result<(), RuntimeError> {
+        self.write_with(|state| {
+            state
+                .proofs
+                .remove(&proof_id)
+                .map(|_| ())
+                .ok_or(RuntimeError::ProofNotFound { proof_id })
+        })
+    }
+
+    /// Returns the proofs owned by the current call frame, i.e. the proofs it created or was passed
+    pub fn list_proofs(&self) -> Vec<Proof> {
+        self.read_with(|state| {
+            let call_depth = state.call_frames.len();
+            state
+                .proofs
+                .values()
+                .filter(|entry| entry.call_depth == call_depth)
+                .map(|entry| entry.proof.clone())
+                .collect()
+        })
+    }
+
     pub fn with_bucket_mut<R, F: FnOnce(&mut Bucket) -> R>(
         &self,
         bucket_id: BucketId,
         callback: F,
     ) -> Result<R, RuntimeError> {
         self.write_with(|state| {
+            let locked = state.locked_by_proofs(ProofContainer::Bucket(bucket_id));
             let bucket = state
                 .buckets
                 .get_mut(&bucket_id)
                 .ok_or(RuntimeError::BucketNotFound { bucket_id })?;
-            Ok(callback(bucket))
+            let ret = callback(bucket);
+            bucket.check_locked(&locked)?;
+            Ok(ret)
         })
     }
 
@@ -343,14 +497,27 @@ impl StateTracker {
         let component = ComponentBody { state };
         let component_address = self.id_provider().new_component_address()?;
         debug!(target: LOG_TARGET, "New component created: {}", component_address);
-        let component = ComponentHeader {
+        let mut component = ComponentHeader {
             template_address: runtime_state.template_address,
             module_name,
             access_rules,
             state: component,
+            key_value_stores: Vec::new(),
         };
 
         self.write_with(|state| {
+            // Stores created by this function are owned by the component that it creates
+            let depth = state.call_frames.len();
+            let stores = state
+                .unbound_key_value_stores
+                .iter()
+                .filter(|(_, created_at)| **created_at == depth)
+                .map(|(store_id, _)| *store_id)
+                .collect::<Vec<_>>();
+            for store_id in stores {
+                state.unbound_key_value_stores.remove(&store_id);
+                component.key_value_stores.push(store_id);
+            }
             // New root component
             state.new_components.insert(component_address, component);
         });
@@ -375,13 +542,104 @@ impl StateTracker {
     }
 
     pub(crate) fn set_current_runtime_state(&self, state: RuntimeState) {
-        self.write_with(|s| s.runtime_state = Some(state));
+        self.write_with(|s| {
+            // Stores that were not bound to a component by the previous instruction can never be written
+            s.unbound_key_value_stores.clear();
+            s.runtime_state = Some(state);
+        });
+    }
+
+    /// Suspends the current runtime state and replaces it with the given state for the duration of a nested call.
+    /// Returns the new call depth.
+    pub(crate) fn push_call_frame(&self, state: RuntimeState, max_depth: usize) -> Result<usize, RuntimeError> {
+        self.write_with(|s| {
+            let current = s.runtime_state.take().ok_or(RuntimeError::IllegalRuntimeState)?;
+            if s.call_frames.len() >= max_depth {
+                s.runtime_state = Some(current);
+                return Err(RuntimeError::MaxCallDepthExceeded { max_depth });
+            }
+
+            if let Some(address) = state.component_address {
+                let is_reentrant = s
+                    .call_frames
+                    .iter()
+                    .chain(Some(&current))
+                    .any(|frame| frame.component_address == Some(address));
+                if is_reentrant {
+                    s.runtime_state = Some(current);
+                    return Err(RuntimeError::ReentrantCall { address });
+                }
+            }
+
+            s.call_frames.push(current);
+            s.runtime_state = Some(state);
+            Ok(s.call_frames.len())
+        })
+    }
+
+    /// Returns true if a method of the component is currently executing, including suspended callers of a nested call.
+    pub(crate) fn is_component_executing(&self, address: &ComponentAddress) -> bool {
+        self.read_with(|s| {
+            s.call_frames
+                .iter()
+                .chain(s.runtime_state.as_ref())
+                .any(|frame| frame.component_address.as_ref() == Some(address))
+        })
+    }
+
+    /// Moves the proofs from the caller's frame to the current call frame. The proofs are dropped when the frame
+    /// returns, unless they are returned to the caller.
+    pub(crate) fn move_proofs_to_current_frame(&self, proof_ids: &[ProofId]) {
+        self.write_with(|s| {
+            let depth = s.call_frames.len();
+            for proof_id in proof_ids {
+                if let Some(entry) = s.proofs.get_mut(proof_id) {
+                    if entry.call_depth + 1 == depth {
+                        entry.call_depth = depth;
+                    }
+                }
+            }
+        })
+    }
+
+    /// Drops the proofs created by a top-level instruction unless the instruction returned them. Proofs returned by
+    /// this or an earlier instruction remain available to the instructions that follow.
+    pub(crate) fn drop_unreturned_proofs(&self, returned_proofs: &[ProofId]) {
+        self.write_with(|s| {
+            s.returned_proofs.extend(returned_proofs);
+            let returned_proofs = &s.returned_proofs;
+            s.proofs.retain(|proof_id, _| returned_proofs.contains(proof_id));
+        })
+    }
+
+    /// Restores the runtime state that was suspended by the matching `push_call_frame`. Proofs owned by the returning
+    /// frame are dropped, unless they were returned to the caller.
+    pub(crate) fn pop_call_frame(&self, returned_proofs: &[ProofId]) -> Result<(), RuntimeError> {
+        self.write_with(|s| {
+            let previous = s.call_frames.pop().ok_or(RuntimeError::IllegalRuntimeState)?;
+            s.runtime_state = Some(previous);
+            let depth = s.call_frames.len();
+            s.unbound_key_value_stores.retain(|_, created_at| *created_at <= depth);
+            s.proofs.retain(|proof_id, entry| {
+                if entry.call_depth <= depth {
+                    return true;
+                }
+                if returned_proofs.contains(proof_id) {
+                    entry.call_depth = depth;
+                    return true;
+                }
+                // Dropping the proof releases the resources that it locks
+                false
+            });
+            Ok(())
+        })
     }
 
     pub fn new_vault(
         &self,
         resource_address: ResourceAddress,
         resource_type: ResourceType,
+        lock: Option<VaultLock>,
     ) -> Result<VaultId, RuntimeError> {
         let vault_id = self.id_provider.new_vault_id()?;
         debug!(target: LOG_TARGET, "New vault id: {}", vault_id);
@@ -390,7 +648,10 @@ impl StateTracker {
             ResourceType::NonFungible => ResourceContainer::non_fungible(resource_address, BTreeSet::new()),
             ResourceType::Confidential => ResourceContainer::confidential(resource_address, None, Amount::zero()),
         };
-        let vault = Vault::new(vault_id, resource);
+        let vault = match lock {
+            Some(lock) => Vault::new_locked(vault_id, resource, lock),
+            None => Vault::new(vault_id, resource),
+        };
 
         self.write_with(|state| {
             state.new_vaults.insert(vault_id, vault);
@@ -407,6 +668,65 @@ impl StateTracker {
         self.write_with(|state| state.borrow_vault_mut(vault_id, f))
     }
 
+    /// Creates a new key-value store. A store created by a component method is owned by that component, a store created
+    /// by a template function is owned by the first component that the function creates.
+    pub fn new_key_value_store(&self) -> Result<KeyValueStoreId, RuntimeError> {
+        let store_id = self.id_provider.new_key_value_store_id();
+        debug!(target: LOG_TARGET, "New key-value store id: {}", store_id);
+        self.write_with(|state| {
+            let component_address = state
+                .runtime_state
+                .as_ref()
+                .ok_or(RuntimeError::IllegalRuntimeState)?
+                .component_address;
+            match component_address {
+                Some(address) => {
+                    let mut component = state.get_component(&address)?;
+                    component.key_value_stores.push(store_id);
+                    state.new_components.insert(address, component);
+                },
+                None => {
+                    let depth = state.call_frames.len();
+                    state.unbound_key_value_stores.insert(store_id, depth);
+                },
+            }
+            Ok(store_id)
+        })
+    }
+
+    /// Checks that entries of the store may be written by the current call frame. Writes are permitted by a method of
+    /// the component that owns the store, or by the template function that created the store until it is bound to a
+    /// component.
+    pub fn check_key_value_store_access(&self, store_id: &KeyValueStoreId) -> Result<(), RuntimeError> {
+        self.read_with(|state| {
+            let component_address = state
+                .runtime_state
+                .as_ref()
+                .ok_or(RuntimeError::IllegalRuntimeState)?
+                .component_address;
+            let is_permitted = match component_address {
+                Some(address) => state.get_component(&address)?.key_value_stores.contains(store_id),
+                None => state.unbound_key_value_stores.get(store_id) == Some(&state.call_frames.len()),
+            };
+            if !is_permitted {
+                return Err(RuntimeError::KeyValueStoreAccessDenied { store_id: *store_id });
+            }
+            Ok(())
+        })
+    }
+
+    pub fn get_key_value_entry(&self, address: &KeyValueEntryAddress) -> Result<Option<KeyValueEntry>, RuntimeError> {
+        self.read_with(|state| state.get_key_value_entry(address))
+    }
+
+    pub fn set_key_value_entry(
+        &self,
+        address: KeyValueEntryAddress,
+        entry: Option<KeyValueEntry>,
+    ) -> Result<Option<KeyValueEntry>, RuntimeError> {
+        self.write_with(|state| state.set_key_value_entry(address, entry))
+    }
+
     fn runtime_state(&self) -> Result<RuntimeState, RuntimeError> {
         self.read_with(|state| state.runtime_state.clone().ok_or(RuntimeError::IllegalRuntimeState))
     }
@@ -440,89 +760,18 @@ impl StateTracker {
         })
     }
 
+    /// Generates the substate diff for the current working state without finalizing the transaction.
+    pub(crate) fn generate_substate_diff(&self) -> Result<SubstateDiff, TransactionCommitError> {
+        self.read_with(|state| state.generate_substate_diff())
+    }
+
     pub fn finalize(&self) -> Result<SubstateDiff, TransactionCommitError> {
-        let substates = self.write_with(|current_state| {
+        self.write_with(|current_state| {
             // Finalise will always reset the state
             let state = mem::replace(current_state, WorkingState::new(current_state.state_store.clone()));
             state.validate_finalized()?;
-
-            let tx = state
-                .state_store
-                .read_access()
-                .map_err(TransactionCommitError::StateStoreTransactionError)?;
-            let mut substate_diff = SubstateDiff::new();
-
-            for (component_addr, substate) in state.new_components {
-                let addr = SubstateAddress::Component(component_addr);
-                let new_substate = match tx.get_state::<_, Substate>(&addr).optional()? {
-                    Some(existing_state) => {
-                        substate_diff.down(addr.clone(), existing_state.version());
-                        Substate::new(existing_state.version() + 1, substate)
-                    },
-                    None => Substate::new(0, substate),
-                };
-                substate_diff.up(addr, new_substate);
-            }
-
-            for (vault_id, substate) in state.new_vaults {
-                let addr = SubstateAddress::Vault(vault_id);
-                let new_substate = match tx.get_state::<_, Substate>(&addr).optional()? {
-                    Some(existing_state) => {
-                        substate_diff.down(addr.clone(), existing_state.version());
-                        Substate::new(existing_state.version() + 1, substate)
-                    },
-                    None => Substate::new(0, substate),
-                };
-                substate_diff.up(addr, new_substate);
-            }
-
-            for (resource_addr, substate) in state.new_resources {
-                let addr = SubstateAddress::Resource(resource_addr);
-                let new_substate = match tx.get_state::<_, Substate>(&addr).optional()? {
-                    Some(existing_state) => {
-                        substate_diff.down(addr.clone(), existing_state.version());
-                        Substate::new(existing_state.version() + 1, substate)
-                    },
-                    None => Substate::new(0, substate),
-                };
-                substate_diff.up(addr, new_substate);
-            }
-
-            for (address, substate) in state.new_non_fungibles {
-                let addr = SubstateAddress::NonFungible(address);
-                let new_substate = match tx.get_state::<_, Substate>(&addr).optional()? {
-                    Some(existing_state) => {
-                        substate_diff.down(addr.clone(), existing_state.version());
-                        Substate::new(existing_state.version() + 1, substate)
-                    },
-                    None => Substate::new(0, substate),
-                };
-                substate_diff.up(addr, new_substate);
-            }
-
-            for (address, substate) in state.new_non_fungible_indexes {
-                let addr = SubstateAddress::NonFungibleIndex(address.clone());
-                let new_substate = match tx.get_state::<_, Substate>(&addr).optional()? {
-                    Some(_) => {
-                        // nft indexes are immutable
-                        return Err(TransactionCommitError::NonFungibleIndexMutation {
-                            resource_address: *address.resource_address(),
-                            index: address.index(),
-                        });
-                    },
-                    None => Substate::new(0, substate),
-                };
-                substate_diff.up(addr, new_substate);
-            }
-
-            for claimed in state.claimed_confidential_outputs {
-                substate_diff.down(SubstateAddress::UnclaimedConfidentialOutput(claimed), 0);
-            }
-
-            Result::<_, TransactionCommitError>::Ok(substate_diff)
-        })?;
-
-        Ok(substates)
+            state.generate_substate_diff()
+        })
     }
 
     fn read_with<R, F: FnOnce(&WorkingState) -> R>(&self, f: F) -> R {
@@ -533,10 +782,6 @@ impl StateTracker {
         f(&mut self.working_state.write().unwrap())
     }
 
-    pub fn transaction_hash(&self) -> Hash {
-        self.id_provider.transaction_hash()
-    }
-
     pub(crate) fn id_provider(&self) -> &IdProvider {
         &self.id_provider
     }
@@ -1,31 +1,39 @@
 //   Copyright 2023 The Tari Project
 //   SPDX-License-Identifier: BSD-3-Clause
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use tari_dan_common_types::optional::Optional;
 use tari_engine_types::{
     bucket::Bucket,
     confidential::UnclaimedConfidentialOutput,
+    events::Event,
+    execution_result::NestedCall,
+    key_value_store::KeyValueEntry,
     logs::LogEntry,
     non_fungible::NonFungibleContainer,
     non_fungible_index::NonFungibleIndex,
+    proof::{LockedResource, Proof, ProofContainer},
     resource::Resource,
-    substate::{Substate, SubstateAddress},
+    substate::{Substate, SubstateAddress, SubstateDiff},
     vault::Vault,
 };
 use tari_template_lib::models::{
     BucketId,
     ComponentAddress,
     ComponentHeader,
+    KeyValueEntryAddress,
+    KeyValueStoreId,
     NonFungibleAddress,
     NonFungibleIndexAddress,
+    ProofId,
     ResourceAddress,
     UnclaimedConfidentialOutputAddress,
     VaultId,
 };
 
 use crate::{
+    fees::FeeState,
     runtime::{RuntimeError, RuntimeState, TransactionCommitError},
     state_store::{memory::MemoryStateStore, AtomicDb, StateReader},
 };
@@ -33,33 +41,66 @@ use crate::{
 #[derive(Debug, Clone)]
 pub(super) struct WorkingState {
     pub logs: Vec<LogEntry>,
+    pub events: Vec<Event>,
+    pub nested_calls: Vec<NestedCall>,
     pub buckets: HashMap<BucketId, Bucket>,
+    /// Proofs created in this transaction. These are used to satisfy access rules until they are dropped.
+    pub proofs: HashMap<ProofId, ProofEntry>,
+    /// Proofs returned by a top-level instruction, which remain available to the instructions that follow
+    pub returned_proofs: HashSet<ProofId>,
     // These could be "new_substates"
     pub new_resources: HashMap<ResourceAddress, Resource>,
     pub new_components: HashMap<ComponentAddress, ComponentHeader>,
     pub new_vaults: HashMap<VaultId, Vault>,
     pub new_non_fungibles: HashMap<NonFungibleAddress, NonFungibleContainer>,
     pub new_non_fungible_indexes: HashMap<NonFungibleIndexAddress, NonFungibleIndex>,
+    /// Key-value entries written in this transaction. A `None` value means the entry was removed.
+    pub new_key_value_entries: HashMap<KeyValueEntryAddress, Option<KeyValueEntry>>,
+    /// Key-value stores created by a template function in this transaction, with the call depth of the function. A
+    /// store is bound to the first component that the function creates.
+    pub unbound_key_value_stores: HashMap<KeyValueStoreId, usize>,
     pub claimed_confidential_outputs: Vec<UnclaimedConfidentialOutputAddress>,
+    pub fee_state: FeeState,
 
     pub runtime_state: Option<RuntimeState>,
+    /// Runtime states of the callers that are suspended while a nested call executes
+    pub call_frames: Vec<RuntimeState>,
     pub last_instruction_output: Option<Vec<u8>>,
     pub workspace: HashMap<Vec<u8>, Vec<u8>>,
     pub state_store: MemoryStateStore,
 }
 
+/// A proof created in this transaction. The proven resources are locked in their container while the proof exists.
+#[derive(Debug, Clone)]
+pub(super) struct ProofEntry {
+    pub proof: Proof,
+    pub container: ProofContainer,
+    /// The depth of the call frame that owns the proof. The proof only satisfies access rules that are checked in
+    /// that frame, and is dropped when the frame returns. Proofs are owned by the frame that created them, and move to
+    /// another frame when they are passed to or returned from a call.
+    pub call_depth: usize,
+}
+
 impl WorkingState {
     pub fn new(state_store: MemoryStateStore) -> Self {
         Self {
             logs: Vec::new(),
+            events: Vec::new(),
+            nested_calls: Vec::new(),
             buckets: HashMap::new(),
+            proofs: HashMap::new(),
+            returned_proofs: HashSet::new(),
             new_resources: HashMap::new(),
             new_components: HashMap::new(),
             new_vaults: HashMap::new(),
             new_non_fungibles: HashMap::new(),
             claimed_confidential_outputs: Vec::new(),
             new_non_fungible_indexes: HashMap::new(),
+            new_key_value_entries: HashMap::new(),
+            unbound_key_value_stores: HashMap::new(),
+            fee_state: FeeState::default(),
             runtime_state: None,
+            call_frames: Vec::new(),
             last_instruction_output: None,
             workspace: HashMap::new(),
             state_store,
@@ -123,6 +164,37 @@ impl WorkingState {
         }
     }
 
+    pub fn get_key_value_entry(&self, address: &KeyValueEntryAddress) -> Result<Option<KeyValueEntry>, RuntimeError> {
+        match self.new_key_value_entries.get(address) {
+            Some(entry) => Ok(entry.clone()),
+            None => {
+                let tx = self.state_store.read_access()?;
+                let entry = tx
+                    .get_state::<_, Substate>(&SubstateAddress::KeyValueEntry(address.clone()))
+                    .optional()?
+                    .map(|substate| {
+                        substate
+                            .into_substate_value()
+                            .into_key_value_entry()
+                            .expect("Substate was not a key-value entry at key-value entry address")
+                    })
+                    .filter(|entry| !entry.is_tombstone());
+                Ok(entry)
+            },
+        }
+    }
+
+    /// Sets (or removes if `None`) the key-value entry, returning the previous entry
+    pub fn set_key_value_entry(
+        &mut self,
+        address: KeyValueEntryAddress,
+        entry: Option<KeyValueEntry>,
+    ) -> Result<Option<KeyValueEntry>, RuntimeError> {
+        let previous = self.get_key_value_entry(&address)?;
+        self.new_key_value_entries.insert(address, entry);
+        Ok(previous)
+    }
+
     pub fn get_unclaimed_confidential_commitment(
         &self,
         addr: &UnclaimedConfidentialOutputAddress,
@@ -206,9 +278,14 @@ impl WorkingState {
         vault_id: &VaultId,
         f: F,
     ) -> Result<R, RuntimeError> {
+        let locked = self.locked_by_proofs(ProofContainer::Vault(*vault_id));
         let vault_mut = self.new_vaults.get_mut(vault_id);
         match vault_mut {
-            Some(vault_mut) => Ok(f(vault_mut)),
+            Some(vault_mut) => {
+                let ret = f(vault_mut);
+                vault_mut.check_locked(&locked)?;
+                Ok(ret)
+            },
             None => {
                 let substate = self
                     .state_store
@@ -223,6 +300,7 @@ impl WorkingState {
                     .into_vault()
                     .expect("Substate was not a vault type at vault address");
                 let ret = f(&mut vault);
+                vault.check_locked(&locked)?;
                 self.new_vaults.insert(*vault_id, vault);
                 Ok(ret)
             },
@@ -260,11 +338,123 @@ impl WorkingState {
     }
 
     pub fn take_bucket(&mut self, bucket_id: BucketId) -> Result<Bucket, RuntimeError> {
+        if !self.locked_by_proofs(ProofContainer::Bucket(bucket_id)).is_empty() {
+            return Err(RuntimeError::BucketLockedByProof { bucket_id });
+        }
         self.buckets
             .remove(&bucket_id)
             .ok_or(RuntimeError::BucketNotFound { bucket_id })
     }
 
+    /// Returns the resources of the vault or bucket that are locked by the proofs created from it
+    pub fn locked_by_proofs(&self, container: ProofContainer) -> LockedResource {
+        self.proofs.values().filter(|entry| entry.container == container).fold(
+            LockedResource::default(),
+            |mut locked, entry| {
+                locked.add(&entry.proof);
+                locked
+            },
+        )
+    }
+
+    pub fn generate_substate_diff(&self) -> Result<SubstateDiff, TransactionCommitError> {
+        let tx = self
+            .state_store
+            .read_access()
+            .map_err(TransactionCommitError::StateStoreTransactionError)?;
+        let mut substate_diff = SubstateDiff::new();
+
+        for (component_addr, substate) in &self.new_components {
+            let addr = SubstateAddress::Component(*component_addr);
+            let new_substate = match tx.get_state::<_, Substate>(&addr).optional()? {
+                Some(existing_state) => {
+                    substate_diff.down(addr.clone(), existing_state.version());
+                    Substate::new(existing_state.version() + 1, substate.clone())
+                },
+                None => Substate::new(0, substate.clone()),
+            };
+            substate_diff.up(addr, new_substate);
+        }
+
+        for (vault_id, substate) in &self.new_vaults {
+            let addr = SubstateAddress::Vault(*vault_id);
+            let new_substate = match tx.get_state::<_, Substate>(&addr).optional()? {
+                Some(existing_state) => {
+                    substate_diff.down(addr.clone(), existing_state.version());
+                    Substate::new(existing_state.version() + 1, substate.clone())
+                },
+                None => Substate::new(0, substate.clone()),
+            };
+            substate_diff.up(addr, new_substate);
+        }
+
+        for (resource_addr, substate) in &self.new_resources {
+            let addr = SubstateAddress::Resource(*resource_addr);
+            let new_substate = match tx.get_state::<_, Substate>(&addr).optional()? {
+                Some(existing_state) => {
+                    substate_diff.down(addr.clone(), existing_state.version());
+                    Substate::new(existing_state.version() + 1, substate.clone())
+                },
+                None => Substate::new(0, substate.clone()),
+            };
+            substate_diff.up(addr, new_substate);
+        }
+
+        for (address, substate) in &self.new_non_fungibles {
+            let addr = SubstateAddress::NonFungible(address.clone());
+            let new_substate = match tx.get_state::<_, Substate>(&addr).optional()? {
+                Some(existing_state) => {
+                    substate_diff.down(addr.clone(), existing_state.version());
+                    Substate::new(existing_state.version() + 1, substate.clone())
+                },
+                None => Substate::new(0, substate.clone()),
+            };
+            substate_diff.up(addr, new_substate);
+        }
+
+        for (address, substate) in &self.new_non_fungible_indexes {
+            let addr = SubstateAddress::NonFungibleIndex(address.clone());
+            let new_substate = match tx.get_state::<_, Substate>(&addr).optional()? {
+                Some(_) => {
+                    // nft indexes are immutable
+                    return Err(TransactionCommitError::NonFungibleIndexMutation {
+                        resource_address: *address.resource_address(),
+                        index: address.index(),
+                    });
+                },
+                None => Substate::new(0, substate.clone()),
+            };
+            substate_diff.up(addr, new_substate);
+        }
+
+        for (address, entry) in &self.new_key_value_entries {
+            let addr = SubstateAddress::KeyValueEntry(address.clone());
+            let existing_state = tx.get_state::<_, Substate>(&addr).optional()?;
+            match (existing_state, entry) {
+                // Removing an entry that was already removed
+                (Some(existing_state), None) if is_tombstone(&existing_state) => {},
+                (Some(existing_state), entry) => {
+                    substate_diff.down(addr.clone(), existing_state.version());
+                    // A removed entry is replaced with a tombstone, so that the next version of the entry is never
+                    // one that was already used
+                    let entry = entry.clone().unwrap_or_else(KeyValueEntry::tombstone);
+                    substate_diff.up(addr, Substate::new(existing_state.version() + 1, entry));
+                },
+                (None, Some(entry)) => {
+                    substate_diff.up(addr, Substate::new(0, entry.clone()));
+                },
+                // Inserted and removed in this transaction
+                (None, None) => {},
+            }
+        }
+
+        for claimed in &self.claimed_confidential_outputs {
+            substate_diff.down(SubstateAddress::UnclaimedConfidentialOutput(*claimed), 0);
+        }
+
+        Ok(substate_diff)
+    }
+
     pub(super) fn validate_finalized(&self) -> Result<(), TransactionCommitError> {
         if !self.buckets.is_empty() {
             return Err(TransactionCommitError::DanglingBuckets {
@@ -282,3 +472,10 @@ impl WorkingState {
         Ok(())
     }
 }
+
+fn is_tombstone(substate: &Substate) -> bool {
+    substate
+        .substate_value()
+        .key_value_entry()
+        .map_or(false, KeyValueEntry::is_tombstone)
+}
@@ -20,8 +20,8 @@
 //  WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 use std::{
-    collections::HashMap,
-    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard},
 };
 
 use anyhow::anyhow;
@@ -32,11 +32,13 @@ use tari_utilities::hex::to_hex;
 use crate::state_store::{AtomicDb, StateReader, StateStoreError, StateWriter};
 
 type InnerKvMap = HashMap<Vec<u8>, Vec<u8>>;
+type ReadKeys = Arc<Mutex<HashSet<Vec<u8>>>>;
 
 #[derive(Debug, Clone)]
 pub struct MemoryStateStore {
     pub allow_creation_of_non_existent_shards: bool,
     state: Arc<RwLock<InnerKvMap>>,
+    read_keys: Option<ReadKeys>,
 }
 
 impl Default for MemoryStateStore {
@@ -44,6 +46,7 @@ impl Default for MemoryStateStore {
         Self {
             allow_creation_of_non_existent_shards: true,
             state: Arc::new(RwLock::new(HashMap::new())),
+            read_keys: None,
         }
     }
 }
@@ -57,17 +60,40 @@ impl MemoryStateStore {
         Self {
             allow_creation_of_non_existent_shards: false,
             state: Arc::new(RwLock::new(state)),
+            read_keys: None,
         }
     }
+
+    /// Records the keys of the committed values that are read from this store and its clones from now on
+    pub fn track_reads(&mut self) {
+        self.read_keys = Some(Arc::new(Mutex::new(HashSet::new())));
+    }
+
+    /// Returns the keys of the committed values that were read since [`MemoryStateStore::track_reads`] was called
+    pub fn read_keys(&self) -> Vec<Vec<u8>> {
+        self.read_keys
+            .as_ref()
+            .map(|keys| keys.lock().unwrap().iter().cloned().collect())
+            .unwrap_or_default()
+    }
 }
 
 pub struct MemoryTransaction<T> {
     pending: InnerKvMap,
     // TODO: this is copied from the state store, there's probably a better way
     allow_creation_of_non_existent_shards: bool,
+    read_keys: Option<ReadKeys>,
     guard: T,
 }
 
+impl<T> MemoryTransaction<T> {
+    fn record_read(&self, key: &[u8]) {
+        if let Some(read_keys) = &self.read_keys {
+            read_keys.lock().unwrap().insert(key.to_vec());
+        }
+    }
+}
+
 impl<'a> AtomicDb<'a> for MemoryStateStore {
     type Error = anyhow::Error;
     type ReadAccess = MemoryTransaction<RwLockReadGuard<'a, InnerKvMap>>;
@@ -79,6 +105,7 @@ impl<'a> AtomicDb<'a> for MemoryStateStore {
         Ok(MemoryTransaction {
             pending: HashMap::default(),
             allow_creation_of_non_existent_shards: self.allow_creation_of_non_existent_shards,
+            read_keys: self.read_keys.clone(),
             guard,
         })
     }
@@ -89,6 +116,7 @@ impl<'a> AtomicDb<'a> for MemoryStateStore {
         Ok(MemoryTransaction {
             pending: HashMap::default(),
             allow_creation_of_non_existent_shards: self.allow_creation_of_non_existent_shards,
+            read_keys: self.read_keys.clone(),
             guard,
         })
     }
@@ -96,35 +124,51 @@ impl<'a> AtomicDb<'a> for MemoryStateStore {
 
 impl<'a> StateReader for MemoryTransaction<RwLockReadGuard<'a, InnerKvMap>> {
     fn get_state_raw(&self, key: &[u8]) -> Result<Vec<u8>, StateStoreError> {
-        self.pending
-            .get(key)
-            .cloned()
-            .or_else(|| self.guard.get(key).cloned())
-            .ok_or_else(|| StateStoreError::NotFound {
-                kind: "state",
-                key: to_hex(key),
-            })
+        if let Some(value) = self.pending.get(key) {
+            return Ok(value.clone());
+        }
+        let value = self.guard.get(key).cloned().ok_or_else(|| StateStoreError::NotFound {
+            kind: "state",
+            key: to_hex(key),
+        })?;
+        self.record_read(key);
+        Ok(value)
     }
 
     fn exists(&self, key: &[u8]) -> Result<bool, StateStoreError> {
-        Ok(self.pending.contains_key(key) || self.guard.contains_key(key))
+        if self.pending.contains_key(key) {
+            return Ok(true);
+        }
+        let exists = self.guard.contains_key(key);
+        if exists {
+            self.record_read(key);
+        }
+        Ok(exists)
     }
 }
 
 impl<'a> StateReader for MemoryTransaction<RwLockWriteGuard<'a, InnerKvMap>> {
     fn get_state_raw(&self, key: &[u8]) -> Result<Vec<u8>, StateStoreError> {
-        self.pending
-            .get(key)
-            .cloned()
-            .or_else(|| self.guard.get(key).cloned())
-            .ok_or_else(|| StateStoreError::NotFound {
-                kind: "state",
-                key: to_hex(key),
-            })
+        if let Some(value) = self.pending.get(key) {
+            return Ok(value.clone());
+        }
+        let value = self.guard.get(key).cloned().ok_or_else(|| StateStoreError::NotFound {
+            kind: "state",
+            key: to_hex(key),
+        })?;
+        self.record_read(key);
+        Ok(value)
     }
 
     fn exists(&self, key: &[u8]) -> Result<bool, StateStoreError> {
-        Ok(self.pending.contains_key(key) || self.guard.contains_key(key))
+        if self.pending.contains_key(key) {
+            return Ok(true);
+        }
+        let exists = self.guard.contains_key(key);
+        if exists {
+            self.record_read(key);
+        }
+        Ok(exists)
     }
 }
 
@@ -215,4 +259,27 @@ mod tests {
         let res: UserData = access.get_state(b"abc").unwrap();
         assert_eq!(res, user_data);
     }
+
+    #[test]
+    fn it_tracks_reads_of_committed_values() {
+        let mut store = MemoryStateStore::default();
+        {
+            let mut access = store.write_access().unwrap();
+            access.set_state_raw(b"abc", vec![1]).unwrap();
+            access.set_state_raw(b"def", vec![2]).unwrap();
+            access.commit().unwrap();
+        }
+        store.track_reads();
+
+        // Clones share the tracked reads
+        let cloned = store.clone();
+        let mut access = cloned.write_access().unwrap();
+        access.set_state_raw(b"ghi", vec![3]).unwrap();
+        access.get_state_raw(b"abc").unwrap();
+        access.get_state_raw(b"ghi").unwrap();
+        access.get_state_raw(b"xyz").optional().unwrap();
+        drop(access);
+
+        assert_eq!(store.read_keys(), vec![b"abc".to_vec()]);
+    }
 }
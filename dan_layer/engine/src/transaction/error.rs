@@ -22,7 +22,7 @@
 
 use tari_template_lib::models::TemplateAddress;
 
-use crate::{runtime::RuntimeError, wasm::WasmExecutionError};
+use crate::{packager::PackageError, runtime::RuntimeError, wasm::WasmExecutionError};
 
 #[derive(Debug, thiserror::Error)]
 pub enum TransactionError {
@@ -32,4 +32,6 @@ pub enum TransactionError {
     TemplateNotFound { address: TemplateAddress },
     #[error(transparent)]
     RuntimeError(#[from] RuntimeError),
+    #[error(transparent)]
+    PackageError(#[from] PackageError),
 }
@@ -23,7 +23,11 @@
 use std::sync::Arc;
 
 use log::*;
-use tari_engine_types::{commit_result::FinalizeResult, execution_result::ExecutionResult, instruction::Instruction};
+use tari_engine_types::{
+    commit_result::{FinalizeResult, RejectReason, TransactionResult},
+    execution_result::ExecutionResult,
+    instruction::Instruction,
+};
 use tari_template_lib::{
     arg,
     args::{Arg, WorkspaceAction},
@@ -33,17 +37,7 @@ use tari_transaction::{id_provider::IdProvider, Transaction};
 
 use crate::{
     packager::{LoadedTemplate, Package},
-    runtime::{
-        AuthParams,
-        AuthorizationScope,
-        ConsensusContext,
-        FunctionIdent,
-        Runtime,
-        RuntimeInterfaceImpl,
-        RuntimeModule,
-        RuntimeState,
-        StateTracker,
-    },
+    runtime::{AuthParams, ConsensusContext, Runtime, RuntimeInterfaceImpl, RuntimeModule, RuntimeState, StateTracker},
     state_store::memory::MemoryStateStore,
     traits::Invokable,
     transaction::TransactionError,
@@ -78,37 +72,74 @@ impl TransactionProcessor {
     }
 
     pub fn execute(self, transaction: Transaction) -> Result<FinalizeResult, TransactionError> {
-        let id_provider = IdProvider::new(*transaction.hash(), 1000);
-        // TODO: We can avoid this for each execution with improved design
-        let template_defs = self.package.get_template_defs();
-        let tracker = StateTracker::new(self.state_db.clone(), id_provider, template_defs);
-        let initial_proofs = self.auth_params.initial_ownership_proofs.clone();
+        let id_provider = IdProvider::new(transaction.id_seed(), 1000);
+        let tracker = StateTracker::new(self.state_db.clone(), id_provider);
         let runtime_interface = RuntimeInterfaceImpl::new(
             tracker,
+            self.package.clone(),
             self.auth_params,
             self.consensus,
+            *transaction.hash(),
             transaction.sender_public_key().clone(),
             self.modules,
         );
         let package = self.package;
 
-        let auth_scope = AuthorizationScope::new(&initial_proofs);
         let runtime = Runtime::new(Arc::new(runtime_interface));
-        let exec_results = transaction
+
+        // Fee instructions are always executed first. If they fail, the transaction is invalid and nothing is charged.
+        let (fee_instructions, instructions) = transaction
             .into_instructions()
             .into_iter()
-            .map(|instruction| Self::process_instruction(&package, &runtime, &auth_scope, instruction))
+            .partition::<Vec<_>, _>(|instruction| matches!(instruction, Instruction::PayFee { .. }));
+        let mut exec_results = fee_instructions
+            .into_iter()
+            .map(|instruction| Self::process_instruction(&package, &runtime, instruction))
             .collect::<Result<Vec<_>, _>>()?;
+        let has_paid_fees = !exec_results.is_empty();
+        runtime.interface().fee_checkpoint()?;
 
-        let mut finalize_result = runtime.interface().finalize()?;
-        finalize_result.execution_results = exec_results;
-        Ok(finalize_result)
+        let instruction_results = instructions
+            .into_iter()
+            .map(|instruction| Self::process_instruction(&package, &runtime, instruction))
+            .collect::<Result<Vec<_>, _>>();
+
+        match instruction_results {
+            Ok(results) => {
+                exec_results.extend(results);
+                let mut finalize_result = runtime.interface().finalize()?;
+                finalize_result.execution_results = exec_results;
+                Ok(finalize_result)
+            },
+            Err(err) if has_paid_fees => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Transaction failed after fees were paid. Only the fee payment will be committed: {}", err
+                );
+                runtime.interface().reset_to_fee_checkpoint()?;
+                let mut finalize_result = runtime.interface().finalize()?;
+                finalize_result.execution_results = exec_results;
+                // Finalization may itself fail the transaction (e.g. the fuel limit is still exceeded), but the failed
+                // instruction is the reason that it was rejected
+                finalize_result.result = match finalize_result.result {
+                    TransactionResult::Accept(substate_diff) |
+                    TransactionResult::AcceptFeeRejectRest(substate_diff, RejectReason::ExecutionFailure(_)) => {
+                        TransactionResult::AcceptFeeRejectRest(
+                            substate_diff,
+                            RejectReason::ExecutionFailure(err.to_string()),
+                        )
+                    },
+                    result => result,
+                };
+                Ok(finalize_result)
+            },
+            Err(err) => Err(err),
+        }
     }
 
     fn process_instruction(
         package: &Package,
         runtime: &Runtime,
-        auth_scope: &AuthorizationScope<'_>,
         instruction: Instruction,
     ) -> Result<ExecutionResult, TransactionError> {
         debug!(target: LOG_TARGET, "instruction = {:?}", instruction);
@@ -118,18 +149,20 @@ impl TransactionProcessor {
                 function,
                 args,
             } => {
-                runtime
-                    .interface()
-                    .set_current_runtime_state(RuntimeState { template_address })?;
+                runtime.interface().set_current_runtime_state(RuntimeState {
+                    template_address,
+                    component_address: None,
+                })?;
 
                 let template =
                     package
-                        .get_template_by_address(&template_address)
+                        .get_template_by_address(&template_address)?
                         .ok_or(TransactionError::TemplateNotFound {
                             address: template_address,
                         })?;
 
-                let result = Self::invoke_template(template.clone(), runtime.clone(), &function, args)?;
+                let result = Self::invoke_template(template, runtime.clone(), &function, args)?;
+                runtime.interface().end_instruction(&result)?;
                 Ok(result)
             },
             Instruction::CallMethod {
@@ -138,17 +171,9 @@ impl TransactionProcessor {
                 args,
             } => {
                 let component = runtime.interface().get_component(&component_address)?;
-                // TODO: In this very basic auth system, you can only call on owned objects (because
-                // initial_ownership_proofs is       usually set to include the owner token).
-                auth_scope.check_access_rules(
-                    &FunctionIdent::Template {
-                        module_name: component.module_name.clone(),
-                        function: method.clone(),
-                    },
-                    &component.access_rules,
-                )?;
+                runtime.interface().check_component_access_rules(&method, &component)?;
 
-                let template = package.get_template_by_address(&component.template_address).ok_or(
+                let template = package.get_template_by_address(&component.template_address)?.ok_or(
                     TransactionError::TemplateNotFound {
                         address: component.template_address,
                     },
@@ -156,13 +181,15 @@ impl TransactionProcessor {
 
                 runtime.interface().set_current_runtime_state(RuntimeState {
                     template_address: component.template_address,
+                    component_address: Some(component_address),
                 })?;
 
                 let mut final_args = Vec::with_capacity(args.len() + 1);
                 final_args.push(arg![component_address]);
                 final_args.extend(args);
 
-                let result = Self::invoke_template(template.clone(), runtime.clone(), &method, final_args)?;
+                let result = Self::invoke_template(template, runtime.clone(), &method, final_args)?;
+                runtime.interface().end_instruction(&result)?;
                 Ok(result)
             },
             Instruction::PutLastInstructionOutputOnWorkspace { key } => {
@@ -180,10 +207,18 @@ impl TransactionProcessor {
                 runtime.interface().claim_burn(*claim)?;
                 Ok(ExecutionResult::empty())
             },
+            Instruction::PayFee {
+                component_address,
+                max_fee,
+            } => Self::process_instruction(package, runtime, Instruction::CallMethod {
+                component_address,
+                method: "pay_fee".to_string(),
+                args: vec![arg![max_fee]],
+            }),
         }
     }
 
-    fn invoke_template(
+    pub(crate) fn invoke_template(
         module: LoadedTemplate,
         runtime: Runtime,
         function: &str,
@@ -27,11 +27,15 @@ use tari_template_lib::{
     args::{
         Arg,
         BucketInvokeArg,
+        CallInvokeArg,
         ComponentInvokeArg,
         ConsensusInvokeArg,
+        EmitEventArg,
         EmitLogArg,
+        KeyValueStoreInvokeArg,
         LogLevel,
         NonFungibleInvokeArg,
+        ProofInvokeArg,
         ResourceInvokeArg,
         VaultInvokeArg,
         WorkspaceInvokeArg,
@@ -46,6 +50,7 @@ use crate::{
     wasm::{
         environment::{AllocPtr, WasmEnv},
         error::WasmExecutionError,
+        metering,
         LoadedWasmTemplate,
     },
 };
@@ -104,9 +109,11 @@ impl WasmProcess {
             EngineOp::EmitLog => Self::handle(env, arg, |env, arg: EmitLogArg| {
                 env.state().interface().emit_log(arg.level, arg.message)
             }),
+            EngineOp::EmitEvent => Self::handle(env, arg, |env, arg: EmitEventArg| {
+                env.state().interface().emit_event(arg.topic, arg.payload)
+            }),
             EngineOp::ComponentInvoke => Self::handle(env, arg, |env, arg: ComponentInvokeArg| {
                 env.state()
-                    .interface()
                     .component_invoke(arg.component_ref, arg.action, arg.args.into())
             }),
             EngineOp::ResourceInvoke => Self::handle(env, arg, |env, arg: ResourceInvokeArg| {
@@ -124,6 +131,11 @@ impl WasmProcess {
                     .interface()
                     .bucket_invoke(arg.bucket_ref, arg.action, arg.args.into())
             }),
+            EngineOp::ProofInvoke => Self::handle(env, arg, |env, arg: ProofInvokeArg| {
+                env.state()
+                    .interface()
+                    .proof_invoke(arg.proof_id, arg.action, arg.args.into())
+            }),
             EngineOp::WorkspaceInvoke => Self::handle(env, arg, |env, arg: WorkspaceInvokeArg| {
                 env.state().interface().workspace_invoke(arg.action, arg.args.into())
             }),
@@ -132,12 +144,20 @@ impl WasmProcess {
                     .interface()
                     .non_fungible_invoke(arg.address, arg.action, arg.args.into())
             }),
+            EngineOp::KeyValueStoreInvoke => Self::handle(env, arg, |env, arg: KeyValueStoreInvokeArg| {
+                env.state()
+                    .interface()
+                    .key_value_store_invoke(arg.store_ref, arg.action, arg.args.into())
+            }),
             EngineOp::GenerateUniqueId => {
                 Self::handle(env, arg, |env, _arg: ()| env.state().interface().generate_uuid())
             },
             EngineOp::ConsensusInvoke => Self::handle(env, arg, |env, arg: ConsensusInvokeArg| {
                 env.state().interface().consensus_invoke(arg.action)
             }),
+            EngineOp::CallInvoke => Self::handle(env, arg, |env, arg: CallInvokeArg| {
+                env.state().call_invoke(arg.action, arg.args.into())
+            }),
         };
 
         result.unwrap_or_else(|err| {
@@ -200,7 +220,10 @@ impl Invokable for WasmProcess {
         let func = self.instance.exports.get_function(&main_name)?;
 
         let call_info_ptr = self.alloc_and_write(&call_info)?;
+        let points_before = metering::get_remaining_points(&self.instance);
         let res = func.call(&[Val::I32(call_info_ptr.as_i32()), Val::I32(call_info_ptr.len() as i32)]);
+        let points_consumed = points_before.saturating_sub(metering::get_remaining_points(&self.instance));
+        self.env.state().interface().charge_wasm_execution(points_consumed)?;
         self.env.free(call_info_ptr)?;
         let val = match res {
             Ok(res) => res,
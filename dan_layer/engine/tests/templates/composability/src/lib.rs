@@ -0,0 +1,56 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::{invoke_args, models::TemplateAddress, prelude::*};
+
+#[template]
+mod composability_template {
+    use super::*;
+
+    pub struct Composability {
+        state_component: ComponentAddress,
+    }
+
+    impl Composability {
+        pub fn new(state_template: TemplateAddress) -> Self {
+            let state_component =
+                TemplateManager::get(state_template).call_function::<ComponentAddress>("new", invoke_args![]);
+            Self { state_component }
+        }
+
+        pub fn state_component(&self) -> ComponentAddress {
+            self.state_component
+        }
+
+        pub fn set_inner_value(&self, value: u32) {
+            engine()
+                .component_manager(self.state_component)
+                .call::<()>("set", invoke_args![value]);
+        }
+
+        pub fn get_inner_value(&self) -> u32 {
+            engine()
+                .component_manager(self.state_component)
+                .call::<u32>("get", invoke_args![])
+        }
+
+        /// Takes coins from a faucet component and returns them to it, passing a bucket across the call boundary in
+        /// both directions
+        pub fn borrow_coins(&self, faucet: ComponentAddress) -> Amount {
+            let coins = engine()
+                .component_manager(faucet)
+                .call::<Bucket>("take_free_coins", invoke_args![]);
+            let amount = coins.amount();
+            engine()
+                .component_manager(faucet)
+                .call::<()>("return_coins", invoke_args![coins]);
+            amount
+        }
+
+        pub fn call_self(&self, own_address: ComponentAddress) -> ComponentAddress {
+            engine()
+                .component_manager(own_address)
+                .call::<ComponentAddress>("state_component", invoke_args![])
+        }
+    }
+}
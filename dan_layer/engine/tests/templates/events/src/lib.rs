@@ -0,0 +1,32 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::prelude::*;
+
+#[template]
+mod event_emitter_template {
+    use super::*;
+
+    pub struct EventEmitter {
+        count: u32,
+    }
+
+    impl EventEmitter {
+        pub fn new() -> Self {
+            Self { count: 0 }
+        }
+
+        pub fn emit_from_function(message: String) {
+            let mut payload = Metadata::new();
+            payload.insert("message", message);
+            engine().emit_event("Function", payload);
+        }
+
+        pub fn increase(&mut self) {
+            self.count += 1;
+            let mut payload = Metadata::new();
+            payload.insert("count", self.count.to_string());
+            engine().emit_event("Increased", payload);
+        }
+    }
+}
@@ -47,6 +47,10 @@ mod faucet_template {
             self.vault.withdraw(Amount(1000))
         }
 
+        pub fn return_coins(&mut self, bucket: Bucket) {
+            self.vault.deposit(bucket);
+        }
+
         // TODO: we can make a fungible utility template with these common operations
         pub fn burn_coins(&mut self, amount: Amount) {
             let mut bucket = self.vault.withdraw(amount);
@@ -0,0 +1,25 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::{constants::CONFIDENTIAL_TARI_RESOURCE_ADDRESS, prelude::*};
+
+#[template]
+mod fee_faucet_template {
+    use super::*;
+
+    pub struct FeeFaucet {
+        vault: Vault,
+    }
+
+    impl FeeFaucet {
+        pub fn new() -> Self {
+            Self {
+                vault: Vault::new_empty(CONFIDENTIAL_TARI_RESOURCE_ADDRESS),
+            }
+        }
+
+        pub fn take(&mut self, amount: Amount) -> Bucket {
+            self.vault.withdraw(amount)
+        }
+    }
+}
@@ -0,0 +1,67 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::prelude::*;
+
+#[template]
+mod key_value_store_template {
+    use super::*;
+
+    pub struct Registry {
+        entries: KeyValueStore<String, u64>,
+        count: u32,
+    }
+
+    impl Registry {
+        pub fn new() -> RegistryComponent {
+            Self {
+                entries: KeyValueStore::new(),
+                count: 0,
+            }
+            .create()
+        }
+
+        pub fn set(&mut self, name: String, value: u64) -> Option<u64> {
+            let previous = self.entries.insert(name, value);
+            if previous.is_none() {
+                self.count += 1;
+            }
+            previous
+        }
+
+        pub fn set_many(&mut self, count: u32, value: u64) {
+            for i in 0..count {
+                self.set(format!("entry-{}", i), value);
+            }
+        }
+
+        pub fn get(&self, name: String) -> Option<u64> {
+            self.entries.get(&name)
+        }
+
+        pub fn remove(&mut self, name: String) -> Option<u64> {
+            let removed = self.entries.remove(&name);
+            if removed.is_some() {
+                self.count -= 1;
+            }
+            removed
+        }
+
+        pub fn count(&self) -> u32 {
+            self.count
+        }
+
+        pub fn store_id(&self) -> KeyValueStoreId {
+            self.entries.id()
+        }
+
+        /// Attempts to write to a store that may be owned by another component
+        pub fn set_in_store(&mut self, store_id: KeyValueStoreId, name: String, value: u64) -> Option<u64> {
+            KeyValueStore::<String, u64>::from_id(store_id).insert(name, value)
+        }
+
+        pub fn set_in_store_from_function(store_id: KeyValueStoreId, name: String, value: u64) -> Option<u64> {
+            KeyValueStore::<String, u64>::from_id(store_id).insert(name, value)
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::{invoke_args, prelude::*};
+
+#[template]
+mod proofs_template {
+    use super::*;
+
+    pub struct BadgeGated {
+        badge_resource: ResourceAddress,
+        counter: u32,
+    }
+
+    impl BadgeGated {
+        pub fn create_badges() -> Bucket {
+            ResourceBuilder::non_fungible()
+                .with_token_symbol("BADGE")
+                .mint_many_with(1..=2, |n: u32| (NonFungibleId::from_u32(n), (Vec::new(), Vec::new())))
+                .build_bucket()
+        }
+
+        pub fn new(badge_resource: ResourceAddress) -> BadgeGatedComponent {
+            let rules = AccessRules::with_default_allow()
+                .add_method_rule(
+                    "increase",
+                    AccessRule::Restricted(Require(NonFungibleAddress::new(
+                        badge_resource,
+                        NonFungibleId::from_u32(1),
+                    ))),
+                )
+                .add_method_rule(
+                    "increase_with_two_badges",
+                    AccessRule::Restricted(RequireAmount(badge_resource, Amount(2))),
+                );
+
+            Self {
+                badge_resource,
+                counter: 0,
+            }
+            .create_with_access_rules(rules)
+        }
+
+        pub fn increase(&mut self) {
+            self.counter += 1;
+        }
+
+        pub fn increase_with_two_badges(&mut self) {
+            self.counter += 1;
+        }
+
+        pub fn increase_with_proof(&mut self, proof: Proof) {
+            assert_eq!(proof.resource_address(), self.badge_resource, "Invalid proof resource");
+            assert!(
+                proof.get_non_fungibles().contains(&NonFungibleId::from_u32(1)),
+                "Proof does not contain badge 1"
+            );
+            proof.drop();
+            self.counter += 1;
+        }
+
+        /// Calls `increase` on the other component, which requires the badge that the proof passed to this method
+        /// proves
+        pub fn increase_other(&self, other: ComponentAddress, proof: Proof) {
+            engine().component_manager(other).call::<()>("increase", invoke_args![]);
+            proof.drop();
+        }
+
+        pub fn increase_via_proxy(proxy: ComponentAddress, other: ComponentAddress, proof: Proof) {
+            engine()
+                .component_manager(proxy)
+                .call::<()>("increase_other", invoke_args![other, proof]);
+        }
+
+        pub fn prove_without_returning(account: ComponentAddress, badge_resource: ResourceAddress) {
+            let _proof = engine()
+                .component_manager(account)
+                .call::<Proof>("create_proof_by_non_fungible_ids", invoke_args![badge_resource, vec![
+                    NonFungibleId::from_u32(1)
+                ]]);
+        }
+
+        pub fn counter(&self) -> u32 {
+            self.counter
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::{prelude::*, rand};
+
+#[template]
+mod random_template {
+    use super::*;
+
+    pub struct TestRandom {}
+
+    impl TestRandom {
+        pub fn epoch_beacon() -> [u8; 32] {
+            Consensus::current_epoch_beacon()
+        }
+
+        pub fn random_bytes(len: u32) -> Vec<u8> {
+            rand::random_bytes(len as usize)
+        }
+
+        pub fn two_random_u64s() -> Vec<u64> {
+            vec![rand::random_u64(), rand::random_u64()]
+        }
+
+        pub fn random_range(max: u64) -> u64 {
+            rand::random_range(max)
+        }
+    }
+}
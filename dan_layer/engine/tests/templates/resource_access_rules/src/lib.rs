@@ -0,0 +1,56 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::prelude::*;
+
+#[template]
+mod resource_access_rules_template {
+    use super::*;
+
+    pub struct RestrictedResources {
+        tokens: Vault,
+        badges: Vault,
+    }
+
+    impl RestrictedResources {
+        pub fn new(owner_badge: NonFungibleAddress) -> Self {
+            let rule = AccessRule::Restricted(Require(owner_badge));
+            let tokens = ResourceBuilder::fungible()
+                .with_token_symbol("RST")
+                .mintable(rule.clone())
+                .burnable(rule.clone())
+                .initial_supply(1000)
+                .build_bucket();
+
+            let badges = ResourceBuilder::non_fungible()
+                .with_token_symbol("RSB")
+                .updatable_non_fungible_data(rule)
+                .with_non_fungibles(Some((NonFungibleId::from_u32(1), (&(), &0u32))))
+                .build_bucket();
+
+            Self {
+                tokens: Vault::from_bucket(tokens),
+                badges: Vault::from_bucket(badges),
+            }
+        }
+
+        pub fn mint(&mut self, amount: Amount) {
+            let bucket = ResourceManager::get(self.tokens.resource_address()).mint_fungible(amount);
+            self.tokens.deposit(bucket);
+        }
+
+        pub fn burn(&mut self, amount: Amount) {
+            let mut bucket = self.tokens.withdraw(amount);
+            bucket.burn();
+        }
+
+        pub fn update_badge(&mut self, value: u32) {
+            ResourceManager::get(self.badges.resource_address())
+                .update_non_fungible_data(NonFungibleId::from_u32(1), &value);
+        }
+
+        pub fn total_supply(&self) -> Amount {
+            ResourceManager::get(self.tokens.resource_address()).total_supply()
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::prelude::*;
+
+#[template]
+mod time_lock_template {
+    use super::*;
+
+    pub struct TimeLock {
+        locked: Vault,
+        claimed: Vault,
+    }
+
+    impl TimeLock {
+        pub fn until_epoch(epoch: u64) -> Self {
+            Self::with_lock(VaultLock::UntilEpoch(epoch))
+        }
+
+        pub fn until_timestamp(timestamp: u64) -> Self {
+            Self::with_lock(VaultLock::UntilTimestamp(timestamp))
+        }
+
+        fn with_lock(lock: VaultLock) -> Self {
+            let coins = ResourceBuilder::fungible()
+                .with_token_symbol("LOCKED")
+                .initial_supply(Amount(1000))
+                .build_bucket();
+            let resource_address = coins.resource_address();
+
+            Self {
+                locked: Vault::from_bucket_locked(coins, lock),
+                claimed: Vault::new_empty(resource_address),
+            }
+        }
+
+        pub fn claim(&mut self, amount: Amount) {
+            let bucket = self.locked.withdraw(amount);
+            self.claimed.deposit(bucket);
+        }
+
+        pub fn lock(&self) -> Option<VaultLock> {
+            self.locked.get_lock()
+        }
+
+        pub fn claimed_balance(&self) -> Amount {
+            self.claimed.balance()
+        }
+    }
+}
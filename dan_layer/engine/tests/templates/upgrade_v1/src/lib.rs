@@ -0,0 +1,35 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::{models::TemplateAddress, prelude::*};
+
+#[template]
+mod upgrade_v1_template {
+    use super::*;
+
+    pub struct CounterV1 {
+        value: u32,
+    }
+
+    impl CounterV1 {
+        pub fn new(owner: NonFungibleAddress) -> CounterV1Component {
+            let rules = AccessRules::with_default_allow().add_upgrade_rule(AccessRule::Restricted(Require(owner)));
+            Self { value: 0 }.create_with_access_rules(rules)
+        }
+
+        pub fn increase(&mut self) {
+            self.value += 1;
+        }
+
+        pub fn value(&self) -> u32 {
+            self.value
+        }
+
+        /// Attempts to upgrade this component from within one of its own methods, which the engine does not permit
+        pub fn upgrade_self(&mut self, component_address: ComponentAddress, template_address: TemplateAddress) {
+            engine()
+                .component_manager(component_address)
+                .upgrade_template(template_address);
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_template_lib::{models::TemplateAddress, prelude::*};
+
+#[template]
+mod upgrade_v2_template {
+    use super::*;
+
+    pub struct CounterV2 {
+        value: u64,
+        step: u64,
+    }
+
+    impl CounterV2 {
+        /// Upgrades the given component to this template
+        pub fn upgrade(component_address: ComponentAddress, template_address: TemplateAddress) {
+            engine()
+                .component_manager(component_address)
+                .upgrade_template(template_address);
+        }
+
+        pub fn migrate(_from: TemplateAddress, state: Vec<u8>) -> Vec<u8> {
+            #[derive(Decode)]
+            struct CounterV1 {
+                value: u32,
+            }
+
+            let previous: CounterV1 = decode_exact(&state).expect("Invalid CounterV1 state");
+            encode(&Self {
+                value: u64::from(previous.value),
+                step: 10,
+            })
+            .expect("Failed to encode CounterV2 state")
+        }
+
+        pub fn increase(&mut self) {
+            self.value += self.step;
+        }
+
+        pub fn value(&self) -> u64 {
+            self.value
+        }
+    }
+}
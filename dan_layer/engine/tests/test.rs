@@ -30,7 +30,7 @@ use tari_dan_engine::{
 use tari_engine_types::{commit_result::FinalizeResult, instruction::Instruction, substate::SubstateAddress};
 use tari_template_lib::{
     args,
-    models::{Amount, ComponentAddress, NonFungibleAddress},
+    models::{Amount, ComponentAddress, KeyValueStoreId, NonFungibleAddress},
     prelude::{NonFungibleId, ResourceAddress},
 };
 use tari_template_test_tooling::{SubstateType, TemplateTest};
@@ -82,8 +82,8 @@ fn test_state() {
 fn test_composed() {
     let mut template_test = TemplateTest::new(vec!["tests/templates/state", "tests/templates/hello_world"]);
 
-    let functions = template_test
-        .get_module("HelloWorld")
+    let module = template_test.get_module("HelloWorld");
+    let functions = module
         .template_def()
         .functions
         .iter()
@@ -91,8 +91,8 @@ fn test_composed() {
         .collect::<Vec<_>>();
     assert_eq!(functions, vec!["greet", "new", "custom_greeting"]);
 
-    let functions = template_test
-        .get_module("State")
+    let module = template_test.get_module("State");
+    let functions = module
         .template_def()
         .functions
         .iter()
@@ -149,8 +149,8 @@ fn test_private_function() {
     let mut template_test = TemplateTest::new(vec!["tests/templates/private_function"]);
 
     // check that the private method and function are not exported
-    let functions = template_test
-        .get_module("PrivateCounter")
+    let module = template_test.get_module("PrivateCounter");
+    let functions = module
         .template_def()
         .functions
         .iter()
@@ -257,11 +257,339 @@ mod consensus {
 
         // set the value of current epoch to "1" and call the template function again to check that it reads the new
         // value
-        let new_consensus_context = ConsensusContext { current_epoch: 1 };
+        let new_consensus_context = ConsensusContext {
+            current_epoch: 1,
+            current_epoch_timestamp: 0,
+            current_epoch_beacon: [0; 32],
+        };
         template_test.set_consensus_context(new_consensus_context);
         let result: u64 = template_test.call_function("TestConsensus", "current_epoch", args![], vec![]);
         assert_eq!(result, 1);
     }
+
+    #[test]
+    fn current_epoch_timestamp() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/consensus"]);
+
+        template_test.set_consensus_context(ConsensusContext {
+            current_epoch: 1,
+            current_epoch_timestamp: 1_679_400_000,
+            current_epoch_beacon: [0; 32],
+        });
+        let result: u64 = template_test.call_function("TestConsensus", "current_epoch_timestamp", args![], vec![]);
+        assert_eq!(result, 1_679_400_000);
+    }
+}
+
+mod randomness {
+    use tari_dan_engine::runtime::ConsensusContext;
+
+    use super::*;
+
+    fn consensus_context_with_beacon(beacon: [u8; 32]) -> ConsensusContext {
+        ConsensusContext {
+            current_epoch: 1,
+            current_epoch_timestamp: 0,
+            current_epoch_beacon: beacon,
+        }
+    }
+
+    #[test]
+    fn it_returns_the_epoch_beacon() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/random"]);
+
+        template_test.set_consensus_context(consensus_context_with_beacon([7; 32]));
+        let result: [u8; 32] = template_test.call_function("TestRandom", "epoch_beacon", args![], vec![]);
+        assert_eq!(result, [7; 32]);
+    }
+
+    #[test]
+    fn it_returns_the_requested_number_of_bytes() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/random"]);
+
+        for len in [0u32, 1, 32, 33, 100] {
+            let result: Vec<u8> = template_test.call_function("TestRandom", "random_bytes", args![len], vec![]);
+            assert_eq!(result.len(), len as usize);
+        }
+    }
+
+    #[test]
+    fn it_returns_different_values_within_a_transaction() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/random"]);
+
+        let result: Vec<u64> = template_test.call_function("TestRandom", "two_random_u64s", args![], vec![]);
+        assert_ne!(result[0], result[1]);
+    }
+
+    #[test]
+    fn it_depends_on_the_epoch_beacon() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/random"]);
+
+        template_test.set_consensus_context(consensus_context_with_beacon([1; 32]));
+        let a: Vec<u8> = template_test.call_function("TestRandom", "random_bytes", args![32u32], vec![]);
+        template_test.set_consensus_context(consensus_context_with_beacon([2; 32]));
+        let b: Vec<u8> = template_test.call_function("TestRandom", "random_bytes", args![32u32], vec![]);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn it_returns_values_within_range() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/random"]);
+
+        let result: u64 = template_test.call_function("TestRandom", "random_range", args![10u64], vec![]);
+        assert!(result < 10);
+    }
+}
+
+mod key_value_store {
+    use super::*;
+
+    fn set(template_test: &mut TemplateTest, component: ComponentAddress, name: &str, value: u64) -> FinalizeResult {
+        template_test
+            .execute_and_commit(
+                vec![Instruction::CallMethod {
+                    component_address: component,
+                    method: "set".to_string(),
+                    args: args![name.to_string(), value],
+                }],
+                vec![],
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn it_inserts_gets_and_removes_entries() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/key_value_store"]);
+        let registry: ComponentAddress = template_test.call_function("Registry", "new", args![], vec![]);
+
+        let previous: Option<u64> = template_test.call_method(registry, "set", args!["a".to_string(), 1u64], vec![]);
+        assert_eq!(previous, None);
+        let previous: Option<u64> = template_test.call_method(registry, "set", args!["a".to_string(), 2u64], vec![]);
+        assert_eq!(previous, Some(1));
+
+        let value: Option<u64> = template_test.call_method(registry, "get", args!["a".to_string()], vec![]);
+        assert_eq!(value, Some(2));
+        let value: Option<u64> = template_test.call_method(registry, "get", args!["b".to_string()], vec![]);
+        assert_eq!(value, None);
+
+        let removed: Option<u64> = template_test.call_method(registry, "remove", args!["a".to_string()], vec![]);
+        assert_eq!(removed, Some(2));
+        let value: Option<u64> = template_test.call_method(registry, "get", args!["a".to_string()], vec![]);
+        assert_eq!(value, None);
+        let count: u32 = template_test.call_method(registry, "count", args![], vec![]);
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn it_only_includes_touched_entries_in_the_diff() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/key_value_store"]);
+        let registry: ComponentAddress = template_test.call_function("Registry", "new", args![], vec![]);
+
+        template_test
+            .execute_and_commit(
+                vec![Instruction::CallMethod {
+                    component_address: registry,
+                    method: "set_many".to_string(),
+                    args: args![100u32, 1u64],
+                }],
+                vec![],
+            )
+            .unwrap();
+        let count: u32 = template_test.call_method(registry, "count", args![], vec![]);
+        assert_eq!(count, 100);
+
+        // Updating an existing entry downs and ups only that entry
+        let result = set(&mut template_test, registry, "entry-42", 5);
+        let diff = result.result.expect("execution failed");
+        assert_eq!(
+            diff.down_iter().filter(|(addr, _)| addr.is_key_value_entry()).count(),
+            1
+        );
+        let (_, substate) = diff.up_iter().find(|(addr, _)| addr.is_key_value_entry()).unwrap();
+        assert_eq!(substate.version(), 1);
+        assert_eq!(diff.up_iter().filter(|(addr, _)| addr.is_key_value_entry()).count(), 1);
+
+        // A new entry is only upped
+        let result = set(&mut template_test, registry, "new-entry", 5);
+        let diff = result.result.expect("execution failed");
+        assert_eq!(
+            diff.down_iter().filter(|(addr, _)| addr.is_key_value_entry()).count(),
+            0
+        );
+        assert_eq!(diff.up_iter().filter(|(addr, _)| addr.is_key_value_entry()).count(), 1);
+
+        // A removed entry is replaced with a tombstone
+        let result = template_test
+            .execute_and_commit(
+                vec![Instruction::CallMethod {
+                    component_address: registry,
+                    method: "remove".to_string(),
+                    args: args!["entry-7".to_string()],
+                }],
+                vec![],
+            )
+            .unwrap();
+        let diff = result.result.expect("execution failed");
+        assert_eq!(
+            diff.down_iter().filter(|(addr, _)| addr.is_key_value_entry()).count(),
+            1
+        );
+        let (_, substate) = diff.up_iter().find(|(addr, _)| addr.is_key_value_entry()).unwrap();
+        assert_eq!(substate.version(), 1);
+        assert!(substate.substate_value().key_value_entry().unwrap().is_tombstone());
+
+        // Inserting a removed entry again continues from the version of the tombstone
+        let result = set(&mut template_test, registry, "entry-7", 3);
+        let diff = result.result.expect("execution failed");
+        assert_eq!(
+            diff.down_iter().filter(|(addr, _)| addr.is_key_value_entry()).count(),
+            1
+        );
+        let (_, substate) = diff.up_iter().find(|(addr, _)| addr.is_key_value_entry()).unwrap();
+        assert_eq!(substate.version(), 2);
+
+        let value: Option<u64> = template_test.call_method(registry, "get", args!["entry-42".to_string()], vec![]);
+        assert_eq!(value, Some(5));
+        let value: Option<u64> = template_test.call_method(registry, "get", args!["entry-7".to_string()], vec![]);
+        assert_eq!(value, Some(3));
+    }
+
+    #[test]
+    fn it_rejects_writes_from_other_components_and_functions() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/key_value_store"]);
+        let registry: ComponentAddress = template_test.call_function("Registry", "new", args![], vec![]);
+        let other: ComponentAddress = template_test.call_function("Registry", "new", args![], vec![]);
+        let store_id: KeyValueStoreId = template_test.call_method(registry, "store_id", args![], vec![]);
+
+        // The owner may write through the store id
+        let previous: Option<u64> =
+            template_test.call_method(registry, "set_in_store", args![store_id, "a".to_string(), 1u64], vec![]);
+        assert_eq!(previous, None);
+
+        let err = template_test
+            .execute_and_commit(
+                vec![Instruction::CallMethod {
+                    component_address: other,
+                    method: "set_in_store".to_string(),
+                    args: args![store_id, "a".to_string(), 2u64],
+                }],
+                vec![],
+            )
+            .unwrap_err();
+        assert!(format!("{:?}", err).contains("can only be written by the component that owns it"));
+
+        let err = template_test
+            .execute_and_commit(
+                vec![Instruction::CallFunction {
+                    template_address: template_test.get_template_address("Registry"),
+                    function: "set_in_store_from_function".to_string(),
+                    args: args![store_id, "a".to_string(), 2u64],
+                }],
+                vec![],
+            )
+            .unwrap_err();
+        assert!(format!("{:?}", err).contains("can only be written by the component that owns it"));
+
+        let value: Option<u64> = template_test.call_method(registry, "get", args!["a".to_string()], vec![]);
+        assert_eq!(value, Some(1));
+    }
+}
+
+mod composability {
+    use super::*;
+
+    fn setup() -> (TemplateTest, ComponentAddress) {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/state", "tests/templates/composability"]);
+        let state_template = template_test.get_template_address("State");
+        let component: ComponentAddress =
+            template_test.call_function("Composability", "new", args![state_template], vec![]);
+        (template_test, component)
+    }
+
+    #[test]
+    fn it_calls_a_function_on_another_template() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/state", "tests/templates/composability"]);
+        let state_template = template_test.get_template_address("State");
+
+        let result = template_test
+            .execute_and_commit(
+                vec![Instruction::CallFunction {
+                    template_address: template_test.get_template_address("Composability"),
+                    function: "new".to_string(),
+                    args: args![state_template],
+                }],
+                vec![],
+            )
+            .unwrap();
+        assert_eq!(result.nested_calls.len(), 1);
+        assert_eq!(result.nested_calls[0].template_address, state_template);
+        assert_eq!(result.nested_calls[0].component_address, None);
+
+        let state_component: ComponentAddress = result.nested_calls[0].result.decode().unwrap();
+        let state_component = template_test
+            .read_only_state_store()
+            .get_component(state_component)
+            .unwrap();
+        assert_eq!(state_component.module_name, "State");
+    }
+
+    #[test]
+    fn it_calls_methods_on_another_component() {
+        let (mut template_test, component) = setup();
+
+        let result = template_test
+            .execute_and_commit(
+                vec![Instruction::CallMethod {
+                    component_address: component,
+                    method: "set_inner_value".to_string(),
+                    args: args![123u32],
+                }],
+                vec![],
+            )
+            .unwrap();
+        assert_eq!(result.nested_calls.len(), 1);
+        assert_eq!(result.nested_calls[0].depth, 1);
+        assert_eq!(result.nested_calls[0].function, "set");
+
+        let value: u32 = template_test.call_method(component, "get_inner_value", args![], vec![]);
+        assert_eq!(value, 123);
+
+        let state_component: ComponentAddress =
+            template_test.call_method(component, "state_component", args![], vec![]);
+        let value: u32 = template_test.call_method(state_component, "get", args![], vec![]);
+        assert_eq!(value, 123);
+    }
+
+    #[test]
+    fn it_passes_buckets_between_components() {
+        let mut template_test = TemplateTest::new(vec![
+            "tests/templates/state",
+            "tests/templates/composability",
+            "tests/templates/faucet",
+        ]);
+        let state_template = template_test.get_template_address("State");
+        let component: ComponentAddress =
+            template_test.call_function("Composability", "new", args![state_template], vec![]);
+        let faucet: ComponentAddress = template_test.call_function("TestFaucet", "mint", args![Amount(10_000)], vec![]);
+
+        let amount: Amount = template_test.call_method(component, "borrow_coins", args![faucet], vec![]);
+        assert_eq!(amount, Amount(1000));
+    }
+
+    #[test]
+    fn it_does_not_allow_reentrant_calls() {
+        let (mut template_test, component) = setup();
+
+        let result = template_test.try_execute(
+            vec![Instruction::CallMethod {
+                component_address: component,
+                method: "call_self".to_string(),
+                args: args![component],
+            }],
+            vec![],
+        );
+        assert!(result.is_err());
+    }
 }
 
 mod fungible {
@@ -1147,3 +1475,872 @@ mod nft_indexes {
         assert_eq!(total_supply, Amount(1));
     }
 }
+
+mod events {
+    use super::*;
+
+    #[test]
+    fn it_emits_events_from_functions_and_methods() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/events"]);
+        let template_address = template_test.get_template_address("EventEmitter");
+
+        let result = template_test
+            .execute_and_commit(
+                vec![Instruction::CallFunction {
+                    template_address,
+                    function: "emit_from_function".to_string(),
+                    args: args!["hello".to_string()],
+                }],
+                vec![],
+            )
+            .unwrap();
+        assert_eq!(result.events.len(), 1);
+        let event = &result.events[0];
+        assert_eq!(event.topic, "Function");
+        assert_eq!(event.template_address, template_address);
+        assert_eq!(event.component_address, None);
+        assert_eq!(event.payload.get("message"), Some("hello"));
+
+        let component: ComponentAddress = template_test.call_function("EventEmitter", "new", args![], vec![]);
+        let result = template_test
+            .execute_and_commit(
+                vec![
+                    Instruction::CallMethod {
+                        component_address: component,
+                        method: "increase".to_string(),
+                        args: args![],
+                    },
+                    Instruction::CallMethod {
+                        component_address: component,
+                        method: "increase".to_string(),
+                        args: args![],
+                    },
+                ],
+                vec![],
+            )
+            .unwrap();
+        assert_eq!(result.events.len(), 2);
+        assert!(result.events.iter().all(|e| e.topic == "Increased"));
+        assert!(result.events.iter().all(|e| e.component_address == Some(component)));
+        assert_eq!(result.events[0].payload.get("count"), Some("1"));
+        assert_eq!(result.events[1].payload.get("count"), Some("2"));
+    }
+}
+
+mod fees {
+    use tari_dan_engine::fees::{FeeTable, RUNTIME_CALL_FUEL};
+    use tari_engine_types::{
+        commit_result::{RejectReason, TransactionResult},
+        fees::FeeSource,
+    };
+    use tari_template_lib::constants::CONFIDENTIAL_TARI_RESOURCE_ADDRESS;
+
+    use super::*;
+
+    const INITIAL_BALANCE: Amount = Amount(1_000_000);
+
+    fn fee_table() -> FeeTable {
+        FeeTable {
+            per_runtime_call_cost: 1,
+            per_wasm_point_cost: 1,
+            per_byte_storage_cost: 1,
+        }
+    }
+
+    fn setup() -> (TemplateTest, ComponentAddress, NonFungibleAddress) {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/fees"]);
+        let (account, owner_proof, _) = template_test.create_owned_account();
+
+        let faucet: ComponentAddress = template_test.call_function("FeeFaucet", "new", args![], vec![]);
+        let vault_id = template_test
+            .get_previous_output_address(SubstateType::Vault)
+            .as_vault_id()
+            .unwrap();
+        template_test.fund_vault(vault_id, INITIAL_BALANCE);
+
+        template_test
+            .execute_and_commit(
+                vec![
+                    Instruction::CallMethod {
+                        component_address: faucet,
+                        method: "take".to_string(),
+                        args: args![INITIAL_BALANCE],
+                    },
+                    Instruction::PutLastInstructionOutputOnWorkspace {
+                        key: b"bucket".to_vec(),
+                    },
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "deposit".to_string(),
+                        args: args![Variable("bucket")],
+                    },
+                ],
+                vec![],
+            )
+            .unwrap();
+
+        template_test.enable_fees(fee_table());
+
+        (template_test, account, owner_proof)
+    }
+
+    fn get_balance(template_test: &mut TemplateTest, account: ComponentAddress) -> Amount {
+        template_test.disable_fees();
+        let balance = template_test.call_method(account, "balance", args![CONFIDENTIAL_TARI_RESOURCE_ADDRESS], vec![]);
+        template_test.enable_fees(fee_table());
+        balance
+    }
+
+    #[test]
+    fn it_charges_fees_and_refunds_the_remainder() {
+        let (mut template_test, account, owner_proof) = setup();
+
+        let max_fee = Amount(100_000);
+        let result = template_test
+            .execute_and_commit(
+                vec![
+                    Instruction::PayFee {
+                        component_address: account,
+                        max_fee,
+                    },
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "balance".to_string(),
+                        args: args![CONFIDENTIAL_TARI_RESOURCE_ADDRESS],
+                    },
+                ],
+                vec![owner_proof],
+            )
+            .unwrap();
+
+        let receipt = result.fee_receipt.unwrap();
+        assert!(receipt.is_paid_in_full());
+        assert_eq!(receipt.total_fee_payment, max_fee);
+        assert!(receipt.total_fees_paid.is_positive());
+        assert_eq!(receipt.total_fees_paid, receipt.total_fees_charged());
+        assert_eq!(receipt.total_refunded(), max_fee - receipt.total_fees_paid);
+        let sources = receipt.cost_breakdown.iter().map(|b| b.source).collect::<Vec<_>>();
+        assert!(sources.contains(&FeeSource::WasmExecution));
+        assert!(sources.contains(&FeeSource::Storage));
+
+        let balance = get_balance(&mut template_test, account);
+        assert_eq!(balance, INITIAL_BALANCE - receipt.total_fees_paid);
+    }
+
+    #[test]
+    fn it_charges_fees_when_the_transaction_fails() {
+        let (mut template_test, account, owner_proof) = setup();
+
+        let result = template_test
+            .try_execute(
+                vec![
+                    Instruction::PayFee {
+                        component_address: account,
+                        max_fee: Amount(100_000),
+                    },
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "no_such_method".to_string(),
+                        args: args![],
+                    },
+                ],
+                vec![owner_proof.clone()],
+            )
+            .unwrap();
+        assert!(matches!(
+            result.result,
+            TransactionResult::AcceptFeeRejectRest(_, RejectReason::ExecutionFailure(_))
+        ));
+        assert!(result.fee_receipt.unwrap().total_fees_paid.is_positive());
+
+        let err = template_test
+            .execute_and_commit(
+                vec![
+                    Instruction::PayFee {
+                        component_address: account,
+                        max_fee: Amount(100_000),
+                    },
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "no_such_method".to_string(),
+                        args: args![],
+                    },
+                ],
+                vec![owner_proof],
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Transaction was rejected"));
+
+        let balance = get_balance(&mut template_test, account);
+        assert!(balance < INITIAL_BALANCE);
+    }
+
+    #[test]
+    fn it_charges_fees_when_the_fuel_limit_is_exceeded() {
+        let (mut template_test, account, owner_proof) = setup();
+        let pay_fee = Instruction::PayFee {
+            component_address: account,
+            max_fee: Amount(100_000),
+        };
+
+        // With a fee of 1 per unit, the receipt of a transaction that only pays the fee tells us the fuel it consumes
+        let result = template_test
+            .try_execute(vec![pay_fee.clone()], vec![owner_proof.clone()])
+            .unwrap();
+        let fuel = result
+            .fee_receipt
+            .unwrap()
+            .cost_breakdown
+            .iter()
+            .map(|breakdown| match breakdown.source {
+                FeeSource::RuntimeCall => breakdown.amount.value() as u64 * RUNTIME_CALL_FUEL,
+                FeeSource::WasmExecution => breakdown.amount.value() as u64,
+                FeeSource::Storage => 0,
+            })
+            .sum::<u64>();
+
+        // The fuel runs out in the second instruction and is still exceeded when the transaction is finalized
+        template_test.set_fuel_limit(Some(fuel));
+        let result = template_test
+            .try_execute(
+                vec![pay_fee, Instruction::CallMethod {
+                    component_address: account,
+                    method: "balance".to_string(),
+                    args: args![CONFIDENTIAL_TARI_RESOURCE_ADDRESS],
+                }],
+                vec![owner_proof],
+            )
+            .unwrap();
+        match result.result {
+            TransactionResult::AcceptFeeRejectRest(_, RejectReason::ExecutionFailure(reason)) => {
+                assert!(reason.contains("fuel limit"), "unexpected reason: {}", reason);
+            },
+            result => panic!("expected the fee to be charged, but got {:?}", result),
+        }
+        assert!(result.fee_receipt.unwrap().total_fees_paid.is_positive());
+    }
+
+    #[test]
+    fn it_rejects_transactions_that_do_not_pay_enough_fees() {
+        let (mut template_test, account, owner_proof) = setup();
+
+        let result = template_test
+            .try_execute(
+                vec![Instruction::CallMethod {
+                    component_address: account,
+                    method: "balance".to_string(),
+                    args: args![CONFIDENTIAL_TARI_RESOURCE_ADDRESS],
+                }],
+                vec![],
+            )
+            .unwrap();
+        assert!(matches!(
+            result.result,
+            TransactionResult::Reject(RejectReason::FeesNotPaid(_))
+        ));
+
+        let max_fee = Amount(1);
+        let result = template_test
+            .try_execute(
+                vec![
+                    Instruction::PayFee {
+                        component_address: account,
+                        max_fee,
+                    },
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "balance".to_string(),
+                        args: args![CONFIDENTIAL_TARI_RESOURCE_ADDRESS],
+                    },
+                ],
+                vec![owner_proof],
+            )
+            .unwrap();
+        assert!(matches!(
+            result.result,
+            TransactionResult::AcceptFeeRejectRest(_, RejectReason::FeesNotPaid(_))
+        ));
+        let receipt = result.fee_receipt.unwrap();
+        assert!(!receipt.is_paid_in_full());
+        assert_eq!(receipt.total_fees_paid, max_fee);
+    }
+}
+
+mod fuel_limit {
+    use tari_dan_engine::{
+        fees::RUNTIME_CALL_FUEL,
+        runtime::{RuntimeError, RuntimeModuleError},
+    };
+
+    use super::*;
+
+    fn greet(template_test: &mut TemplateTest) -> Result<FinalizeResult, TransactionError> {
+        let template_address = template_test.get_template_address("HelloWorld");
+        template_test.try_execute(
+            vec![Instruction::CallFunction {
+                template_address,
+                function: "greet".to_string(),
+                args: args![],
+            }],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn it_aborts_transactions_that_exceed_the_fuel_limit() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/hello_world"]);
+
+        template_test.set_fuel_limit(Some(RUNTIME_CALL_FUEL - 1));
+        let err = greet(&mut template_test).unwrap_err();
+        assert!(matches!(
+            err,
+            TransactionError::RuntimeError(RuntimeError::ModuleError(RuntimeModuleError::FuelExhausted { limit }))
+                if limit == RUNTIME_CALL_FUEL - 1
+        ));
+
+        template_test.set_fuel_limit(Some(100_000_000));
+        let result = greet(&mut template_test).unwrap();
+        assert!(result.result.is_accept());
+
+        template_test.set_fuel_limit(None);
+        let result = greet(&mut template_test).unwrap();
+        assert!(result.result.is_accept());
+    }
+}
+
+mod resource_access_rules {
+    use tari_template_lib::args::Arg;
+
+    use super::*;
+
+    fn setup() -> (TemplateTest, ComponentAddress, NonFungibleAddress) {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/resource_access_rules"]);
+        let (owner_proof, _) = template_test.create_owner_proof();
+        let component: ComponentAddress =
+            template_test.call_function("RestrictedResources", "new", args![owner_proof], vec![]);
+        (template_test, component, owner_proof)
+    }
+
+    fn call(
+        template_test: &mut TemplateTest,
+        component_address: ComponentAddress,
+        method: &str,
+        args: Vec<Arg>,
+        proofs: Vec<NonFungibleAddress>,
+    ) -> anyhow::Result<FinalizeResult> {
+        template_test.execute_and_commit(
+            vec![Instruction::CallMethod {
+                component_address,
+                method: method.to_string(),
+                args,
+            }],
+            proofs,
+        )
+    }
+
+    #[test]
+    fn it_denies_minting_and_burning_without_the_required_proof() {
+        let (mut template_test, component, _) = setup();
+        let (other_proof, _) = template_test.create_owner_proof();
+
+        call(&mut template_test, component, "mint", args![Amount(10)], vec![
+            other_proof.clone(),
+        ])
+        .unwrap_err();
+
+        call(&mut template_test, component, "burn", args![Amount(10)], vec![
+            other_proof,
+        ])
+        .unwrap_err();
+
+        let total_supply: Amount = template_test.call_method(component, "total_supply", args![], vec![]);
+        assert_eq!(total_supply, Amount(1000));
+    }
+
+    #[test]
+    fn it_allows_minting_and_burning_with_the_required_proof() {
+        let (mut template_test, component, owner_proof) = setup();
+
+        call(&mut template_test, component, "mint", args![Amount(10)], vec![
+            owner_proof.clone(),
+        ])
+        .unwrap();
+        call(&mut template_test, component, "burn", args![Amount(20)], vec![
+            owner_proof,
+        ])
+        .unwrap();
+
+        let total_supply: Amount = template_test.call_method(component, "total_supply", args![], vec![]);
+        assert_eq!(total_supply, Amount(990));
+    }
+
+    #[test]
+    fn it_restricts_non_fungible_data_updates() {
+        let (mut template_test, component, owner_proof) = setup();
+
+        call(&mut template_test, component, "update_badge", args![1u32], vec![]).unwrap_err();
+        call(&mut template_test, component, "update_badge", args![1u32], vec![
+            owner_proof,
+        ])
+        .unwrap();
+    }
+}
+
+mod proofs {
+    use tari_template_lib::args::Arg::Variable;
+
+    use super::*;
+
+    fn setup() -> (
+        TemplateTest,
+        ComponentAddress,
+        NonFungibleAddress,
+        ComponentAddress,
+        ResourceAddress,
+    ) {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/proofs"]);
+        let (account, owner_proof, _) = template_test.create_owned_account();
+        let template_address = template_test.get_template_address("BadgeGated");
+
+        template_test
+            .execute_and_commit(
+                vec![
+                    Instruction::CallFunction {
+                        template_address,
+                        function: "create_badges".to_string(),
+                        args: args![],
+                    },
+                    Instruction::PutLastInstructionOutputOnWorkspace {
+                        key: b"badges".to_vec(),
+                    },
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "deposit".to_string(),
+                        args: args![Variable("badges")],
+                    },
+                ],
+                vec![],
+            )
+            .unwrap();
+        let badge_resource = template_test
+            .get_previous_output_address(SubstateType::Resource)
+            .as_resource_address()
+            .unwrap();
+
+        let gated: ComponentAddress = template_test.call_function("BadgeGated", "new", args![badge_resource], vec![]);
+
+        (template_test, account, owner_proof, gated, badge_resource)
+    }
+
+    #[test]
+    fn it_denies_access_without_a_proof() {
+        let (mut template_test, _, owner_proof, gated, _) = setup();
+
+        template_test
+            .execute_and_commit(
+                vec![Instruction::CallMethod {
+                    component_address: gated,
+                    method: "increase".to_string(),
+                    args: args![],
+                }],
+                vec![owner_proof],
+            )
+            .unwrap_err();
+    }
+
+    #[test]
+    fn it_grants_access_with_a_proof_from_an_account_vault() {
+        let (mut template_test, account, owner_proof, gated, badge_resource) = setup();
+
+        template_test
+            .execute_and_commit(
+                vec![
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "create_proof_by_non_fungible_ids".to_string(),
+                        args: args![badge_resource, vec![NonFungibleId::from_u32(1)]],
+                    },
+                    Instruction::CallMethod {
+                        component_address: gated,
+                        method: "increase".to_string(),
+                        args: args![],
+                    },
+                ],
+                vec![owner_proof],
+            )
+            .unwrap();
+
+        let counter: u32 = template_test.call_method(gated, "counter", args![], vec![]);
+        assert_eq!(counter, 1);
+        // The badge was never withdrawn from the account
+        let ids: Vec<NonFungibleId> =
+            template_test.call_method(account, "get_non_fungible_ids", args![badge_resource], vec![]);
+        assert_eq!(ids.len(), 2);
+    }
+
+    #[test]
+    fn it_does_not_grant_access_with_a_proof_of_another_badge() {
+        let (mut template_test, account, owner_proof, gated, badge_resource) = setup();
+
+        template_test
+            .execute_and_commit(
+                vec![
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "create_proof_by_non_fungible_ids".to_string(),
+                        args: args![badge_resource, vec![NonFungibleId::from_u32(2)]],
+                    },
+                    Instruction::CallMethod {
+                        component_address: gated,
+                        method: "increase".to_string(),
+                        args: args![],
+                    },
+                ],
+                vec![owner_proof],
+            )
+            .unwrap_err();
+    }
+
+    #[test]
+    fn it_passes_proofs_as_arguments_and_drops_them() {
+        let (mut template_test, account, owner_proof, gated, badge_resource) = setup();
+
+        let create_proof = Instruction::CallMethod {
+            component_address: account,
+            method: "create_proof_by_amount".to_string(),
+            args: args![badge_resource, Amount(2)],
+        };
+
+        template_test
+            .execute_and_commit(
+                vec![
+                    create_proof.clone(),
+                    Instruction::PutLastInstructionOutputOnWorkspace { key: b"proof".to_vec() },
+                    Instruction::CallMethod {
+                        component_address: gated,
+                        method: "increase_with_proof".to_string(),
+                        args: args![Variable("proof")],
+                    },
+                ],
+                vec![owner_proof.clone()],
+            )
+            .unwrap();
+
+        let counter: u32 = template_test.call_method(gated, "counter", args![], vec![]);
+        assert_eq!(counter, 1);
+
+        // The proof is dropped by increase_with_proof so it no longer grants access
+        template_test
+            .execute_and_commit(
+                vec![
+                    create_proof,
+                    Instruction::PutLastInstructionOutputOnWorkspace { key: b"proof".to_vec() },
+                    Instruction::CallMethod {
+                        component_address: gated,
+                        method: "increase_with_proof".to_string(),
+                        args: args![Variable("proof")],
+                    },
+                    Instruction::CallMethod {
+                        component_address: gated,
+                        method: "increase".to_string(),
+                        args: args![],
+                    },
+                ],
+                vec![owner_proof],
+            )
+            .unwrap_err();
+    }
+
+    #[test]
+    fn it_satisfies_access_rules_in_a_callee_with_a_passed_proof() {
+        let (mut template_test, account, owner_proof, gated, badge_resource) = setup();
+        let proxy: ComponentAddress = template_test.call_function("BadgeGated", "new", args![badge_resource], vec![]);
+        let template_address = template_test.get_template_address("BadgeGated");
+
+        // The proof is passed to the proxy's method, which uses it to call the restricted method of the gated component
+        template_test
+            .execute_and_commit(
+                vec![
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "create_proof_by_non_fungible_ids".to_string(),
+                        args: args![badge_resource, vec![NonFungibleId::from_u32(1)]],
+                    },
+                    Instruction::PutLastInstructionOutputOnWorkspace { key: b"proof".to_vec() },
+                    Instruction::CallFunction {
+                        template_address,
+                        function: "increase_via_proxy".to_string(),
+                        args: args![proxy, gated, Variable("proof")],
+                    },
+                ],
+                vec![owner_proof],
+            )
+            .unwrap();
+
+        let counter: u32 = template_test.call_method(gated, "counter", args![], vec![]);
+        assert_eq!(counter, 1);
+    }
+
+    #[test]
+    fn it_drops_proofs_that_an_instruction_does_not_return() {
+        let (mut template_test, account, owner_proof, gated, badge_resource) = setup();
+        let template_address = template_test.get_template_address("BadgeGated");
+
+        template_test
+            .execute_and_commit(
+                vec![
+                    Instruction::CallFunction {
+                        template_address,
+                        function: "prove_without_returning".to_string(),
+                        args: args![account, badge_resource],
+                    },
+                    Instruction::CallMethod {
+                        component_address: gated,
+                        method: "increase".to_string(),
+                        args: args![],
+                    },
+                ],
+                vec![owner_proof],
+            )
+            .unwrap_err();
+    }
+
+    #[test]
+    fn it_locks_proven_resources_in_the_vault() {
+        let (mut template_test, account, owner_proof, _, badge_resource) = setup();
+
+        template_test
+            .execute_and_commit(
+                vec![
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "create_proof_by_non_fungible_ids".to_string(),
+                        args: args![badge_resource, vec![NonFungibleId::from_u32(1)]],
+                    },
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "withdraw_non_fungible".to_string(),
+                        args: args![badge_resource, NonFungibleId::from_u32(1)],
+                    },
+                    Instruction::PutLastInstructionOutputOnWorkspace { key: b"badge".to_vec() },
+                    Instruction::CallMethod {
+                        component_address: account,
+                        method: "deposit".to_string(),
+                        args: args![Variable("badge")],
+                    },
+                ],
+                vec![owner_proof],
+            )
+            .unwrap_err();
+    }
+
+    #[test]
+    fn it_does_not_prove_the_same_resources_twice() {
+        let (mut template_test, account, owner_proof, gated, badge_resource) = setup();
+
+        let create_proof_of_badge_1 = Instruction::CallMethod {
+            component_address: account,
+            method: "create_proof_by_non_fungible_ids".to_string(),
+            args: args![badge_resource, vec![NonFungibleId::from_u32(1)]],
+        };
+        template_test
+            .execute_and_commit(vec![create_proof_of_badge_1.clone(), create_proof_of_badge_1], vec![
+                owner_proof.clone(),
+            ])
+            .unwrap_err();
+
+        let create_proof_of_one_badge = Instruction::CallMethod {
+            component_address: account,
+            method: "create_proof_by_amount".to_string(),
+            args: args![badge_resource, Amount(1)],
+        };
+        let increase_with_two_badges = Instruction::CallMethod {
+            component_address: gated,
+            method: "increase_with_two_badges".to_string(),
+            args: args![],
+        };
+        // Each proof covers a different badge, so together they prove an amount of 2
+        template_test
+            .execute_and_commit(
+                vec![
+                    create_proof_of_one_badge.clone(),
+                    create_proof_of_one_badge.clone(),
+                    increase_with_two_badges.clone(),
+                ],
+                vec![owner_proof.clone()],
+            )
+            .unwrap();
+        let counter: u32 = template_test.call_method(gated, "counter", args![], vec![]);
+        assert_eq!(counter, 1);
+
+        // Both badges are locked by the first two proofs
+        template_test
+            .execute_and_commit(
+                vec![
+                    create_proof_of_one_badge.clone(),
+                    create_proof_of_one_badge.clone(),
+                    create_proof_of_one_badge,
+                ],
+                vec![owner_proof],
+            )
+            .unwrap_err();
+    }
+}
+
+mod time_locks {
+    use tari_template_lib::models::VaultLock;
+
+    use super::*;
+
+    fn claim(template_test: &mut TemplateTest, component_address: ComponentAddress) -> anyhow::Result<FinalizeResult> {
+        template_test.execute_and_commit(
+            vec![Instruction::CallMethod {
+                component_address,
+                method: "claim".to_string(),
+                args: args![Amount(100)],
+            }],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn it_denies_withdrawal_until_the_lock_epoch() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/time_lock"]);
+        let component: ComponentAddress = template_test.call_function("TimeLock", "until_epoch", args![10u64], vec![]);
+
+        let lock: Option<VaultLock> = template_test.call_method(component, "lock", args![], vec![]);
+        assert_eq!(lock, Some(VaultLock::UntilEpoch(10)));
+
+        template_test.set_consensus_context(ConsensusContext {
+            current_epoch: 9,
+            current_epoch_timestamp: 0,
+            current_epoch_beacon: [0; 32],
+        });
+        claim(&mut template_test, component).unwrap_err();
+
+        template_test.set_consensus_context(ConsensusContext {
+            current_epoch: 10,
+            current_epoch_timestamp: 0,
+            current_epoch_beacon: [0; 32],
+        });
+        claim(&mut template_test, component).unwrap();
+
+        let claimed: Amount = template_test.call_method(component, "claimed_balance", args![], vec![]);
+        assert_eq!(claimed, Amount(100));
+    }
+
+    #[test]
+    fn it_denies_withdrawal_until_the_lock_timestamp() {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/time_lock"]);
+        let component: ComponentAddress =
+            template_test.call_function("TimeLock", "until_timestamp", args![1_000u64], vec![]);
+
+        template_test.set_consensus_context(ConsensusContext {
+            current_epoch: 100,
+            current_epoch_timestamp: 999,
+            current_epoch_beacon: [0; 32],
+        });
+        claim(&mut template_test, component).unwrap_err();
+
+        template_test.set_consensus_context(ConsensusContext {
+            current_epoch: 100,
+            current_epoch_timestamp: 1_000,
+            current_epoch_beacon: [0; 32],
+        });
+        claim(&mut template_test, component).unwrap();
+
+        let claimed: Amount = template_test.call_method(component, "claimed_balance", args![], vec![]);
+        assert_eq!(claimed, Amount(100));
+    }
+}
+
+mod template_upgrades {
+    use tari_template_lib::models::TemplateAddress;
+
+    use super::*;
+
+    fn setup() -> (TemplateTest, ComponentAddress, NonFungibleAddress, TemplateAddress) {
+        let mut template_test = TemplateTest::new(vec!["tests/templates/upgrade_v1", "tests/templates/upgrade_v2"]);
+        let (owner_proof, _) = template_test.create_owner_proof();
+        let component: ComponentAddress = template_test.call_function("CounterV1", "new", args![owner_proof], vec![]);
+        template_test.call_method::<()>(component, "increase", args![], vec![]);
+        template_test.call_method::<()>(component, "increase", args![], vec![]);
+        let v2_template = template_test.get_template_address("CounterV2");
+        (template_test, component, owner_proof, v2_template)
+    }
+
+    fn upgrade(
+        template_test: &mut TemplateTest,
+        component: ComponentAddress,
+        template_address: TemplateAddress,
+        proofs: Vec<NonFungibleAddress>,
+    ) -> anyhow::Result<FinalizeResult> {
+        template_test.execute_and_commit(
+            vec![Instruction::CallFunction {
+                template_address,
+                function: "upgrade".to_string(),
+                args: args![component, template_address],
+            }],
+            proofs,
+        )
+    }
+
+    #[test]
+    fn it_upgrades_the_component_and_migrates_its_state() {
+        let (mut template_test, component, owner_proof, v2_template) = setup();
+        template_test.set_template_predecessor("CounterV2", "CounterV1");
+
+        upgrade(&mut template_test, component, v2_template, vec![owner_proof]).unwrap();
+
+        let header = template_test.read_only_state_store().get_component(component).unwrap();
+        assert_eq!(header.template_address, v2_template);
+
+        let value: u64 = template_test.call_method(component, "value", args![], vec![]);
+        assert_eq!(value, 2);
+        template_test.call_method::<()>(component, "increase", args![], vec![]);
+        let value: u64 = template_test.call_method(component, "value", args![], vec![]);
+        assert_eq!(value, 12);
+    }
+
+    #[test]
+    fn it_denies_an_upgrade_without_the_required_proof() {
+        let (mut template_test, component, _, v2_template) = setup();
+        template_test.set_template_predecessor("CounterV2", "CounterV1");
+        let (other_proof, _) = template_test.create_owner_proof();
+
+        upgrade(&mut template_test, component, v2_template, vec![other_proof]).unwrap_err();
+
+        let value: u32 = template_test.call_method(component, "value", args![], vec![]);
+        assert_eq!(value, 2);
+    }
+
+    #[test]
+    fn it_denies_an_upgrade_to_a_template_that_is_not_a_successor() {
+        let (mut template_test, component, owner_proof, v2_template) = setup();
+
+        upgrade(&mut template_test, component, v2_template, vec![owner_proof]).unwrap_err();
+
+        let value: u32 = template_test.call_method(component, "value", args![], vec![]);
+        assert_eq!(value, 2);
+    }
+
+    #[test]
+    fn it_denies_an_upgrade_from_within_the_component() {
+        let (mut template_test, component, owner_proof, v2_template) = setup();
+        template_test.set_template_predecessor("CounterV2", "CounterV1");
+
+        template_test
+            .execute_and_commit(
+                vec![Instruction::CallMethod {
+                    component_address: component,
+                    method: "upgrade_self".to_string(),
+                    args: args![component, v2_template],
+                }],
+                vec![owner_proof],
+            )
+            .unwrap_err();
+
+        let value: u32 = template_test.call_method(component, "value", args![], vec![]);
+        assert_eq!(value, 2);
+    }
+}
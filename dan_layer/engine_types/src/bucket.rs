@@ -28,7 +28,10 @@ use tari_template_lib::{
     prelude::ResourceType,
 };
 
-use crate::resource_container::{ResourceContainer, ResourceError};
+use crate::{
+    proof::{LockedResource, Proof},
+    resource_container::{ResourceContainer, ResourceError},
+};
 
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct Bucket {
@@ -52,6 +55,15 @@ impl Bucket {
         self.resource.resource_type()
     }
 
+    /// Creates a proof of all of the resources in the bucket that are not locked by another proof
+    pub fn create_proof(&self, locked: &LockedResource) -> Result<Proof, ResourceError> {
+        Ok(Proof::of_all(&locked.unlocked(&self.resource)?))
+    }
+
+    pub fn check_locked(&self, locked: &LockedResource) -> Result<(), ResourceError> {
+        locked.check_held_by(&self.resource)
+    }
+
     pub(crate) fn into_resource(self) -> ResourceContainer {
         self.resource
     }
@@ -23,28 +23,48 @@
 use serde::{Deserialize, Serialize};
 use tari_template_lib::Hash;
 
-use crate::{execution_result::ExecutionResult, logs::LogEntry, substate::SubstateDiff};
+use crate::{
+    events::Event,
+    execution_result::{ExecutionResult, NestedCall},
+    fees::FeeReceipt,
+    logs::LogEntry,
+    substate::SubstateDiff,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FinalizeResult {
     pub transaction_hash: Hash,
     pub logs: Vec<LogEntry>,
+    #[serde(default)]
+    pub events: Vec<Event>,
     pub execution_results: Vec<ExecutionResult>,
+    #[serde(default)]
+    pub nested_calls: Vec<NestedCall>,
     pub result: TransactionResult,
+    #[serde(default)]
+    pub fee_receipt: Option<FeeReceipt>,
 }
 
 impl FinalizeResult {
-    pub fn new(transaction_hash: Hash, logs: Vec<LogEntry>, result: TransactionResult) -> Self {
+    pub fn new(transaction_hash: Hash, logs: Vec<LogEntry>, events: Vec<Event>, result: TransactionResult) -> Self {
         Self {
             transaction_hash,
             logs,
+            events,
             execution_results: Vec::new(),
+            nested_calls: Vec::new(),
             result,
+            fee_receipt: None,
         }
     }
 
     pub fn reject(transaction_hash: Hash, reason: RejectReason) -> Self {
-        Self::new(transaction_hash, Vec::new(), TransactionResult::Reject(reason))
+        Self::new(
+            transaction_hash,
+            Vec::new(),
+            Vec::new(),
+            TransactionResult::Reject(reason),
+        )
     }
 
     pub fn is_accept(&self) -> bool {
@@ -55,6 +75,9 @@ impl FinalizeResult {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum TransactionResult {
     Accept(SubstateDiff),
+    /// The fee payment was committed but the rest of the transaction failed. The substate diff only contains the
+    /// changes made by the fee instructions.
+    AcceptFeeRejectRest(SubstateDiff, RejectReason),
     Reject(RejectReason),
 }
 
@@ -63,9 +86,12 @@ impl TransactionResult {
         matches!(self, Self::Accept(_))
     }
 
+    /// Returns the substate diff that should be committed, if any. For a transaction that failed after paying fees,
+    /// this is the diff of the fee payment only.
     pub fn accept(&self) -> Option<&SubstateDiff> {
         match self {
             Self::Accept(substate_diff) => Some(substate_diff),
+            Self::AcceptFeeRejectRest(substate_diff, _) => Some(substate_diff),
             Self::Reject(_) => None,
         }
     }
@@ -73,6 +99,7 @@ impl TransactionResult {
     pub fn reject(&self) -> Option<&RejectReason> {
         match self {
             Self::Accept(_) => None,
+            Self::AcceptFeeRejectRest(_, reject_result) => Some(reject_result),
             Self::Reject(reject_result) => Some(reject_result),
         }
     }
@@ -80,7 +107,7 @@ impl TransactionResult {
     pub fn expect(self, msg: &str) -> SubstateDiff {
         match self {
             Self::Accept(substate_diff) => substate_diff,
-            Self::Reject(reject_result) => {
+            Self::AcceptFeeRejectRest(_, reject_result) | Self::Reject(reject_result) => {
                 panic!("{}: {:?}", msg, reject_result);
             },
         }
@@ -94,6 +121,9 @@ pub enum RejectReason {
     PreviousQcRejection,
     ShardPledgedToAnotherPayload(String),
     ShardRejected(String),
+    FeesNotPaid(String),
+    InvalidEpoch(String),
+    InvalidSignature(String),
 }
 
 impl std::fmt::Display for RejectReason {
@@ -104,6 +134,9 @@ impl std::fmt::Display for RejectReason {
             RejectReason::PreviousQcRejection => write!(f, "Previous QC was a rejection"),
             RejectReason::ShardPledgedToAnotherPayload(msg) => write!(f, "Shard pledged to another payload: {}", msg),
             RejectReason::ShardRejected(msg) => write!(f, "Shard was rejected: {}", msg),
+            RejectReason::FeesNotPaid(msg) => write!(f, "Fees not paid: {}", msg),
+            RejectReason::InvalidEpoch(msg) => write!(f, "Invalid epoch: {}", msg),
+            RejectReason::InvalidSignature(msg) => write!(f, "Invalid signature: {}", msg),
         }
     }
 }
@@ -0,0 +1,46 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use tari_bor::{borsh, Decode, Encode};
+use tari_template_lib::models::{ComponentAddress, Metadata, TemplateAddress};
+
+/// A structured event emitted by a template. Unlike logs, events are intended to be consumed by indexers and
+/// wallets, so the payload is a set of key-value pairs rather than free text.
+#[derive(Debug, Clone, Encode, Decode, Serialize, Deserialize, PartialEq)]
+pub struct Event {
+    pub topic: String,
+    /// The template that emitted the event
+    pub template_address: TemplateAddress,
+    /// The component whose method emitted the event, or None if it was emitted by a template function
+    pub component_address: Option<ComponentAddress>,
+    pub payload: Metadata,
+}
+
+impl Event {
+    pub fn new(
+        topic: String,
+        template_address: TemplateAddress,
+        component_address: Option<ComponentAddress>,
+        payload: Metadata,
+    ) -> Self {
+        Self {
+            topic,
+            template_address,
+            component_address,
+            payload,
+        }
+    }
+}
+
+impl Display for Event {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} template: {}", self.topic, self.template_address)?;
+        if let Some(component_address) = self.component_address {
+            write!(f, " component: {}", component_address)?;
+        }
+        Ok(())
+    }
+}
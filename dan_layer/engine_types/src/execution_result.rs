@@ -24,6 +24,9 @@ use std::io;
 
 use serde::{Deserialize, Serialize};
 use tari_bor::Decode;
+use tari_template_lib::models::ComponentAddress;
+
+use crate::TemplateAddress;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ExecutionResult {
@@ -44,6 +47,17 @@ impl ExecutionResult {
     }
 }
 
+/// A function or method call made by a template to another template or component during execution
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct NestedCall {
+    /// The call depth, a call made directly from a transaction instruction has a depth of 1
+    pub depth: usize,
+    pub template_address: TemplateAddress,
+    pub component_address: Option<ComponentAddress>,
+    pub function: String,
+    pub result: ExecutionResult,
+}
+
 // TODO: This is to avoid adding serde to the abi crate - that probably isn't so bad if we use feature flags, but I'm
 //       still cautious
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -0,0 +1,48 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_template_lib::models::Amount;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FeeReceipt {
+    /// The total amount locked by the fee instructions of the transaction
+    pub total_fee_payment: Amount,
+    /// The amount taken from the fee payment, which is burnt. Any payment in excess of this is refunded to the paying
+    /// vault.
+    pub total_fees_paid: Amount,
+    /// The fees charged for executing the transaction, by source
+    pub cost_breakdown: Vec<FeeBreakdown>,
+}
+
+impl FeeReceipt {
+    pub fn total_fees_charged(&self) -> Amount {
+        self.cost_breakdown
+            .iter()
+            .fold(Amount::zero(), |total, breakdown| total + breakdown.amount)
+    }
+
+    pub fn total_refunded(&self) -> Amount {
+        self.total_fee_payment - self.total_fees_paid
+    }
+
+    pub fn is_paid_in_full(&self) -> bool {
+        self.total_fees_paid >= self.total_fees_charged()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FeeBreakdown {
+    pub source: FeeSource,
+    pub amount: Amount,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FeeSource {
+    /// Calls made from the template to the engine
+    RuntimeCall,
+    /// WASM instructions executed, as counted by the metering middleware
+    WasmExecution,
+    /// Bytes written to the state store by the substate diff
+    Storage,
+}
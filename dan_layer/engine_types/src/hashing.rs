@@ -122,7 +122,16 @@ pub enum EngineHashDomainLabel {
     NonFungibleIndex,
     UuidOutput,
     Output,
-    InstructionSignature,
+    TransactionSignature,
+    TransactionIdSeed,
+    RandomBytes,
+    EpochBeacon,
+    KeyValueStoreId,
+    KeyValueEntry,
+    StateTreeLeaf,
+    StateTreeBranch,
+    SubstateShardData,
+    MempoolBatch,
 }
 
 impl EngineHashDomainLabel {
@@ -139,7 +148,16 @@ impl EngineHashDomainLabel {
             Self::NonFungibleIndex => "NonFungibleIndex",
             Self::UuidOutput => "UuidOutput",
             Self::Output => "Output",
-            Self::InstructionSignature => "InstructionSignature",
+            Self::TransactionSignature => "TransactionSignature",
+            Self::TransactionIdSeed => "TransactionIdSeed",
+            Self::RandomBytes => "RandomBytes",
+            Self::EpochBeacon => "EpochBeacon",
+            Self::KeyValueStoreId => "KeyValueStoreId",
+            Self::KeyValueEntry => "KeyValueEntry",
+            Self::StateTreeLeaf => "StateTreeLeaf",
+            Self::StateTreeBranch => "StateTreeBranch",
+            Self::SubstateShardData => "SubstateShardData",
+            Self::MempoolBatch => "MempoolBatch",
         }
     }
 }
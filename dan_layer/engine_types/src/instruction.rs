@@ -7,7 +7,7 @@ use serde::{Deserialize, Serialize};
 use tari_bor::{borsh, Encode};
 use tari_template_lib::{
     args::{Arg, LogLevel},
-    models::{ComponentAddress, TemplateAddress},
+    models::{Amount, ComponentAddress, TemplateAddress},
 };
 
 use crate::confidential::ConfidentialClaim;
@@ -35,6 +35,13 @@ pub enum Instruction {
     ClaimBurn {
         claim: Box<ConfidentialClaim>,
     },
+    /// Calls `pay_fee(max_fee)` on the component (e.g. an account) to lock funds for the transaction fee. Fee
+    /// instructions are executed before any other instruction and are committed even if the rest of the transaction
+    /// fails.
+    PayFee {
+        component_address: ComponentAddress,
+        max_fee: Amount,
+    },
 }
 
 impl Display for Instruction {
@@ -71,6 +78,16 @@ impl Display for Instruction {
                     claim.output_address, claim.proof_of_knowledge
                 )
             },
+            Self::PayFee {
+                component_address,
+                max_fee,
+            } => {
+                write!(
+                    f,
+                    "PayFee {{ component_address: {}, max_fee: {} }}",
+                    component_address, max_fee
+                )
+            },
         }
     }
 }
@@ -0,0 +1,36 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_bor::{borsh, Decode, Encode};
+
+/// A single entry of a key-value store. The value is kept encoded, it is decoded by the template that owns the store.
+/// A removed entry is kept as a tombstone without a value, so that the version of the entry keeps increasing if the
+/// key is inserted again.
+#[derive(Debug, Clone, Encode, Decode, Serialize, Deserialize, PartialEq)]
+pub struct KeyValueEntry {
+    value: Option<Vec<u8>>,
+}
+
+impl KeyValueEntry {
+    pub fn new(value: Vec<u8>) -> Self {
+        Self { value: Some(value) }
+    }
+
+    pub fn tombstone() -> Self {
+        Self { value: None }
+    }
+
+    pub fn is_tombstone(&self) -> bool {
+        self.value.is_none()
+    }
+
+    /// Returns the value of the entry, or None if the entry was removed
+    pub fn value(&self) -> Option<&[u8]> {
+        self.value.as_deref()
+    }
+
+    pub fn into_value(self) -> Option<Vec<u8>> {
+        self.value
+    }
+}
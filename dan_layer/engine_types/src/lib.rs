@@ -4,12 +4,16 @@
 pub mod bucket;
 pub mod commit_result;
 pub mod confidential;
+pub mod events;
 pub mod execution_result;
+pub mod fees;
 pub mod hashing;
 pub mod instruction;
+pub mod key_value_store;
 pub mod logs;
 pub mod non_fungible;
 pub mod non_fungible_index;
+pub mod proof;
 pub mod resource;
 pub mod resource_container;
 pub mod substate;
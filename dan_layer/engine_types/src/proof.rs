@@ -0,0 +1,160 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::collections::BTreeSet;
+
+use tari_template_lib::{
+    models::{Amount, BucketId, NonFungibleId, ResourceAddress, VaultId},
+    prelude::ResourceType,
+};
+
+use crate::resource_container::{ResourceContainer, ResourceError};
+
+/// Proof that the creator had access to an amount of a resource held in a vault or bucket. A proof does not take
+/// custody of the resource and only exists for the duration of the transaction.
+#[derive(Debug, Clone)]
+pub struct Proof {
+    resource_address: ResourceAddress,
+    resource_type: ResourceType,
+    amount: Amount,
+    non_fungible_ids: BTreeSet<NonFungibleId>,
+}
+
+impl Proof {
+    /// Creates a proof of all the resource held in the container
+    pub fn of_all(container: &ResourceContainer) -> Self {
+        Self {
+            resource_address: *container.resource_address(),
+            resource_type: container.resource_type(),
+            amount: container.amount(),
+            non_fungible_ids: container.non_fungible_token_ids().cloned().unwrap_or_default(),
+        }
+    }
+
+    /// Creates a proof of the given amount of the resource held in the container. For non-fungible resources, the
+    /// proof covers the first `amount` tokens in the container.
+    pub fn of_amount(container: &ResourceContainer, amount: Amount) -> Result<Self, ResourceError> {
+        if !amount.is_positive() {
+            return Err(ResourceError::OperationNotAllowed(
+                "Proof amount must be positive".to_string(),
+            ));
+        }
+        if container.amount() < amount {
+            return Err(ResourceError::InsufficientBalance {
+                details: format!(
+                    "Proof of amount {} requested but container only holds {}",
+                    amount,
+                    container.amount()
+                ),
+            });
+        }
+        let non_fungible_ids = container
+            .non_fungible_token_ids()
+            .map(|ids| ids.iter().take(amount.value() as usize).cloned().collect())
+            .unwrap_or_default();
+
+        Ok(Self {
+            resource_address: *container.resource_address(),
+            resource_type: container.resource_type(),
+            amount,
+            non_fungible_ids,
+        })
+    }
+
+    /// Creates a proof of the given non-fungibles held in the container
+    pub fn of_non_fungibles(
+        container: &ResourceContainer,
+        ids: BTreeSet<NonFungibleId>,
+    ) -> Result<Self, ResourceError> {
+        let token_ids = container.non_fungible_token_ids().ok_or_else(|| {
+            ResourceError::OperationNotAllowed("Cannot create a proof of non-fungibles for this resource".to_string())
+        })?;
+        if let Some(id) = ids.iter().find(|id| !token_ids.contains(id)) {
+            return Err(ResourceError::NonFungibleTokenIdNotFound { token: id.clone() });
+        }
+
+        Ok(Self {
+            resource_address: *container.resource_address(),
+            resource_type: container.resource_type(),
+            amount: ids.len().into(),
+            non_fungible_ids: ids,
+        })
+    }
+
+    pub fn resource_address(&self) -> &ResourceAddress {
+        &self.resource_address
+    }
+
+    pub fn resource_type(&self) -> ResourceType {
+        self.resource_type
+    }
+
+    pub fn amount(&self) -> Amount {
+        self.amount
+    }
+
+    pub fn non_fungible_ids(&self) -> &BTreeSet<NonFungibleId> {
+        &self.non_fungible_ids
+    }
+}
+
+/// The vault or bucket that holds the resources covered by a proof
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofContainer {
+    Vault(VaultId),
+    Bucket(BucketId),
+}
+
+/// The resources of a vault or bucket that are covered by existing proofs. Locked resources cannot be moved out of the
+/// container and are not covered by new proofs, so that the same resources are never proven twice.
+#[derive(Debug, Clone)]
+pub struct LockedResource {
+    amount: Amount,
+    non_fungible_ids: BTreeSet<NonFungibleId>,
+}
+
+impl Default for LockedResource {
+    fn default() -> Self {
+        Self {
+            amount: Amount::zero(),
+            non_fungible_ids: BTreeSet::new(),
+        }
+    }
+}
+
+impl LockedResource {
+    pub fn add(&mut self, proof: &Proof) {
+        self.amount = self.amount.saturating_add(&proof.amount());
+        self.non_fungible_ids.extend(proof.non_fungible_ids().iter().cloned());
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.amount.is_zero() && self.non_fungible_ids.is_empty()
+    }
+
+    /// Returns the resources held in the container that are not locked
+    pub fn unlocked(&self, container: &ResourceContainer) -> Result<ResourceContainer, ResourceError> {
+        let mut unlocked = container.clone();
+        if !self.non_fungible_ids.is_empty() {
+            unlocked.withdraw_by_ids(&self.non_fungible_ids)?;
+        } else if self.amount.is_positive() {
+            unlocked.withdraw(self.amount)?;
+        }
+        Ok(unlocked)
+    }
+
+    /// Returns an error if the container no longer holds all of the locked resources
+    pub fn check_held_by(&self, container: &ResourceContainer) -> Result<(), ResourceError> {
+        let holds_ids = container
+            .non_fungible_token_ids()
+            .map_or(self.non_fungible_ids.is_empty(), |ids| {
+                self.non_fungible_ids.is_subset(ids)
+            });
+        if container.amount() < self.amount || !holds_ids {
+            return Err(ResourceError::OperationNotAllowed(
+                "Resources that are locked by a proof cannot be withdrawn".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -23,6 +23,7 @@
 use serde::{Deserialize, Serialize};
 use tari_bor::{borsh, Decode, Encode};
 use tari_template_lib::{
+    auth::ResourceAccessRules,
     models::{Amount, Metadata},
     resource::ResourceType,
 };
@@ -30,14 +31,16 @@ use tari_template_lib::{
 #[derive(Debug, Clone, Encode, Decode, Serialize, Deserialize, PartialEq)]
 pub struct Resource {
     resource_type: ResourceType,
+    access_rules: ResourceAccessRules,
     metadata: Metadata,
     total_supply: Amount,
 }
 
 impl Resource {
-    pub fn new(resource_type: ResourceType, metadata: Metadata) -> Self {
+    pub fn new(resource_type: ResourceType, access_rules: ResourceAccessRules, metadata: Metadata) -> Self {
         Self {
             resource_type,
+            access_rules,
             metadata,
             total_supply: 0.into(),
         }
@@ -78,4 +81,8 @@ impl Resource {
     pub fn metadata(&self) -> &Metadata {
         &self.metadata
     }
+
+    pub fn access_rules(&self) -> &ResourceAccessRules {
+        &self.access_rules
+    }
 }
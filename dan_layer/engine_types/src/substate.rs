@@ -31,6 +31,8 @@ use tari_template_lib::{
     models::{
         ComponentAddress,
         ComponentHeader,
+        KeyValueEntryAddress,
+        KeyValueStoreId,
         NonFungibleAddress,
         NonFungibleId,
         NonFungibleIndexAddress,
@@ -40,10 +42,12 @@ use tari_template_lib::{
     },
     Hash,
 };
+use tari_utilities::hex::from_hex;
 
 use crate::{
     confidential::UnclaimedConfidentialOutput,
     hashing::{hasher, EngineHashDomainLabel},
+    key_value_store::KeyValueEntry,
     non_fungible::NonFungibleContainer,
     non_fungible_index::NonFungibleIndex,
     resource::Resource,
@@ -94,6 +98,7 @@ pub enum SubstateAddress {
     UnclaimedConfidentialOutput(UnclaimedConfidentialOutputAddress),
     NonFungible(NonFungibleAddress),
     NonFungibleIndex(NonFungibleIndexAddress),
+    KeyValueEntry(KeyValueEntryAddress),
 }
 
 impl SubstateAddress {
@@ -132,6 +137,10 @@ impl SubstateAddress {
                 .chain(address.resource_address().hash())
                 .chain(&address.index())
                 .result(),
+            SubstateAddress::KeyValueEntry(address) => hasher(EngineHashDomainLabel::KeyValueEntry)
+                .chain(address.store_id().hash())
+                .chain(address.key())
+                .result(),
         }
     }
 
@@ -162,6 +171,13 @@ impl SubstateAddress {
         }
     }
 
+    pub fn as_key_value_entry_address(&self) -> Option<&KeyValueEntryAddress> {
+        match self {
+            SubstateAddress::KeyValueEntry(addr) => Some(addr),
+            _ => None,
+        }
+    }
+
     pub fn is_resource(&self) -> bool {
         matches!(self, Self::Resource(_))
     }
@@ -182,6 +198,10 @@ impl SubstateAddress {
         matches!(self, Self::NonFungibleIndex(_))
     }
 
+    pub fn is_key_value_entry(&self) -> bool {
+        matches!(self, Self::KeyValueEntry(_))
+    }
+
     pub fn is_layer1_commitment(&self) -> bool {
         matches!(self, Self::UnclaimedConfidentialOutput(_))
     }
@@ -217,6 +237,12 @@ impl From<NonFungibleIndexAddress> for SubstateAddress {
     }
 }
 
+impl From<KeyValueEntryAddress> for SubstateAddress {
+    fn from(address: KeyValueEntryAddress) -> Self {
+        Self::KeyValueEntry(address)
+    }
+}
+
 impl From<UnclaimedConfidentialOutputAddress> for SubstateAddress {
     fn from(address: UnclaimedConfidentialOutputAddress) -> Self {
         Self::UnclaimedConfidentialOutput(address)
@@ -231,6 +257,7 @@ impl Display for SubstateAddress {
             SubstateAddress::Vault(addr) => write!(f, "{}", addr),
             SubstateAddress::NonFungible(addr) => write!(f, "{}", addr),
             SubstateAddress::NonFungibleIndex(addr) => write!(f, "{}", addr),
+            SubstateAddress::KeyValueEntry(addr) => write!(f, "{}", addr),
             SubstateAddress::UnclaimedConfidentialOutput(commitment_address) => write!(f, "{}", commitment_address),
         }
     }
@@ -285,6 +312,19 @@ impl FromStr for SubstateAddress {
                 let id = VaultId::from_hex(addr).map_err(|_| InvalidSubstateAddressFormat(s.to_string()))?;
                 Ok(SubstateAddress::Vault(id))
             },
+            Some(("kvstore", addr)) => match addr.split_once(' ') {
+                // kvstore_xxxx key_xxxx
+                Some((store_id, key)) => {
+                    let store_id =
+                        KeyValueStoreId::from_hex(store_id).map_err(|_| InvalidSubstateAddressFormat(s.to_string()))?;
+                    let key = key
+                        .strip_prefix("key_")
+                        .and_then(|key| from_hex(key).ok())
+                        .ok_or_else(|| InvalidSubstateAddressFormat(s.to_string()))?;
+                    Ok(SubstateAddress::KeyValueEntry(KeyValueEntryAddress::new(store_id, key)))
+                },
+                None => Err(InvalidSubstateAddressFormat(s.to_string())),
+            },
             Some(("commitment", addr)) => {
                 let commitment_address = UnclaimedConfidentialOutputAddress::from_hex(addr)
                     .map_err(|_| InvalidSubstateAddressFormat(s.to_string()))?;
@@ -317,6 +357,7 @@ impl_partial_eq!(ResourceAddress, Resource);
 impl_partial_eq!(VaultId, Vault);
 impl_partial_eq!(UnclaimedConfidentialOutputAddress, UnclaimedConfidentialOutput);
 impl_partial_eq!(NonFungibleAddress, NonFungible);
+impl_partial_eq!(KeyValueEntryAddress, KeyValueEntry);
 
 #[derive(Debug, Clone, Encode, Decode, Serialize, Deserialize)]
 pub enum SubstateValue {
@@ -325,6 +366,7 @@ pub enum SubstateValue {
     Vault(Vault),
     NonFungible(NonFungibleContainer),
     NonFungibleIndex(NonFungibleIndex),
+    KeyValueEntry(KeyValueEntry),
     UnclaimedConfidentialOutput(UnclaimedConfidentialOutput),
 }
 
@@ -392,6 +434,20 @@ impl SubstateValue {
         }
     }
 
+    pub fn key_value_entry(&self) -> Option<&KeyValueEntry> {
+        match self {
+            SubstateValue::KeyValueEntry(entry) => Some(entry),
+            _ => None,
+        }
+    }
+
+    pub fn into_key_value_entry(self) -> Option<KeyValueEntry> {
+        match self {
+            SubstateValue::KeyValueEntry(entry) => Some(entry),
+            _ => None,
+        }
+    }
+
     pub fn into_unclaimed_confidential_output(self) -> Option<UnclaimedConfidentialOutput> {
         match self {
             SubstateValue::UnclaimedConfidentialOutput(output) => Some(output),
@@ -430,6 +486,12 @@ impl From<NonFungibleIndex> for SubstateValue {
     }
 }
 
+impl From<KeyValueEntry> for SubstateValue {
+    fn from(entry: KeyValueEntry) -> Self {
+        Self::KeyValueEntry(entry)
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SubstateDiff {
     up_substates: Vec<(SubstateAddress, Substate)>,
@@ -513,6 +575,12 @@ mod tests {
             .unwrap()
             .as_non_fungible_index_address()
             .unwrap();
+            SubstateAddress::from_str(
+                "kvstore_7cbfe29101c24924b1b6ccefbfff98986d648622272ae24f7585dab55ff1ff64 key_0a000000",
+            )
+            .unwrap()
+            .as_key_value_entry_address()
+            .unwrap();
         }
     }
 }
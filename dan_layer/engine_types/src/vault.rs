@@ -25,13 +25,14 @@ use std::collections::BTreeSet;
 use serde::{Deserialize, Serialize};
 use tari_bor::{borsh, Decode, Encode};
 use tari_template_lib::{
-    models::{Amount, ConfidentialWithdrawProof, NonFungibleId, ResourceAddress, VaultId},
+    models::{Amount, ConfidentialWithdrawProof, NonFungibleId, ResourceAddress, VaultId, VaultLock},
     prelude::ResourceType,
 };
 
 use crate::{
     bucket::Bucket,
     confidential::ConfidentialOutput,
+    proof::{LockedResource, Proof},
     resource_container::{ResourceContainer, ResourceError},
 };
 
@@ -39,6 +40,7 @@ use crate::{
 pub struct Vault {
     vault_id: VaultId,
     resource_container: ResourceContainer,
+    lock: Option<VaultLock>,
 }
 
 impl Vault {
@@ -46,9 +48,28 @@ impl Vault {
         Self {
             vault_id,
             resource_container: resource,
+            lock: None,
         }
     }
 
+    pub fn new_locked(vault_id: VaultId, resource: ResourceContainer, lock: VaultLock) -> Self {
+        Self {
+            vault_id,
+            resource_container: resource,
+            lock: Some(lock),
+        }
+    }
+
+    pub fn lock(&self) -> Option<&VaultLock> {
+        self.lock.as_ref()
+    }
+
+    /// Returns true if resources may be withdrawn from this vault at the given epoch and epoch timestamp
+    pub fn is_unlocked(&self, current_epoch: u64, current_epoch_timestamp: u64) -> bool {
+        self.lock
+            .map_or(true, |lock| lock.is_unlocked(current_epoch, current_epoch_timestamp))
+    }
+
     pub fn deposit(&mut self, bucket: Bucket) -> Result<(), ResourceError> {
         self.resource_container.deposit(bucket.into_resource())?;
         Ok(())
@@ -107,6 +128,27 @@ impl Vault {
         self.resource_container.reveal_confidential(proof)
     }
 
+    /// Creates a proof of all of the resources in the vault that are not locked by another proof
+    pub fn create_proof(&self, locked: &LockedResource) -> Result<Proof, ResourceError> {
+        Ok(Proof::of_all(&locked.unlocked(&self.resource_container)?))
+    }
+
+    pub fn create_proof_of_amount(&self, locked: &LockedResource, amount: Amount) -> Result<Proof, ResourceError> {
+        Proof::of_amount(&locked.unlocked(&self.resource_container)?, amount)
+    }
+
+    pub fn create_proof_of_non_fungibles(
+        &self,
+        locked: &LockedResource,
+        ids: BTreeSet<NonFungibleId>,
+    ) -> Result<Proof, ResourceError> {
+        Proof::of_non_fungibles(&locked.unlocked(&self.resource_container)?, ids)
+    }
+
+    pub fn check_locked(&self, locked: &LockedResource) -> Result<(), ResourceError> {
+        locked.check_held_by(&self.resource_container)
+    }
+
     pub fn vault_id(&self) -> &VaultId {
         &self.vault_id
     }
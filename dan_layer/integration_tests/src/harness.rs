@@ -25,7 +25,7 @@ use tari_dan_core::{
 use tari_dan_engine::runtime::ConsensusContext;
 use tari_engine_types::{
     commit_result::{FinalizeResult, RejectReason, TransactionResult},
-    substate::SubstateDiff,
+    substate::{Substate, SubstateAddress, SubstateDiff},
 };
 use tari_shutdown::Shutdown;
 use tari_template_lib::Hash;
@@ -63,6 +63,7 @@ impl PayloadProcessor<TariDanPayload> for PayloadProcessorListener {
         Ok(FinalizeResult::new(
             Hash::default(),
             vec![],
+            vec![],
             TransactionResult::Accept(SubstateDiff::new()),
         ))
     }
@@ -74,6 +75,40 @@ impl Default for PayloadProcessorListener {
     }
 }
 
+/// Accepts every payload and creates each of the given substates that the payload declares as an output
+pub struct SubstateCreatingPayloadProcessor {
+    substates: Vec<(SubstateAddress, Substate)>,
+}
+
+impl SubstateCreatingPayloadProcessor {
+    pub fn new(substates: Vec<(SubstateAddress, Substate)>) -> Self {
+        Self { substates }
+    }
+}
+
+impl PayloadProcessor<TariDanPayload> for SubstateCreatingPayloadProcessor {
+    fn process_payload(
+        &self,
+        payload: TariDanPayload,
+        _pledges: HashMap<ShardId, ObjectPledge>,
+        _consensus: ConsensusContext,
+    ) -> Result<FinalizeResult, PayloadProcessorError> {
+        let involved_shards = payload.involved_shards();
+        let mut diff = SubstateDiff::new();
+        for (address, substate) in &self.substates {
+            if involved_shards.contains(&ShardId::from_address(address, substate.version())) {
+                diff.up(address.clone(), substate.clone());
+            }
+        }
+        Ok(FinalizeResult::new(
+            payload.to_id().into_array().into(),
+            vec![],
+            vec![],
+            TransactionResult::Accept(diff),
+        ))
+    }
+}
+
 pub struct NullPayloadProcessor {}
 
 impl PayloadProcessor<TariDanPayload> for NullPayloadProcessor {
@@ -86,6 +121,7 @@ impl PayloadProcessor<TariDanPayload> for NullPayloadProcessor {
         Ok(FinalizeResult::new(
             payload.to_id().into_array().into(),
             vec![],
+            vec![],
             TransactionResult::Reject(RejectReason::ExecutionFailure("NullPayloadProcessor".to_string())),
         ))
     }
@@ -146,7 +182,10 @@ impl HsTestHarness {
         let rx_execute = payload_processor.receiver.resubscribe();
         let shutdown = Shutdown::new();
 
-        let consensus_constants = ConsensusConstants::devnet();
+        let consensus_constants = ConsensusConstants {
+            pacemaker_timeout: network_latency,
+            ..ConsensusConstants::devnet()
+        };
         let shard_store = TempShardStoreFactory::new();
 
         let public_address = Multiaddr::from_str("/ip4/127.0.0.1/tcp/48000").unwrap();
@@ -174,7 +213,6 @@ impl HsTestHarness {
             shard_store.clone(),
             shutdown.to_signal(),
             consensus_constants,
-            network_latency,
         );
 
         Self {
@@ -26,3 +26,7 @@ pub use temp_shard_store_factory::TempShardStoreFactory;
 pub mod harness;
 #[cfg(test)]
 mod test_consensus;
+#[cfg(test)]
+mod test_shard_store;
+#[cfg(test)]
+mod test_simulator;
@@ -43,6 +43,7 @@ use tari_dan_core::{
         epoch_manager::RangeEpochManager,
         leader_strategy::{AlwaysFirstLeader, RotatingLeader},
         NodeIdentitySigningService,
+        SigningService,
     },
     storage::shard_store::{ShardStore, ShardStoreWriteTransaction},
     workers::hotstuff_waiter::RecoveryMessage,
@@ -78,25 +79,22 @@ fn create_test_qc(
     }
     let vn_bmt = ValidatorNodeBMT::create(vn_bmt_vec);
 
-    let validators_metadata: Vec<_> = committee_keys
+    let signing_services: Vec<_> = committee_keys
         .into_iter()
-        .map(|(_, secret)| {
+        .map(|(_, secret)| create_signing_service(secret))
+        .collect();
+    let signatures = signing_services
+        .iter()
+        .map(|signing_service| {
             let mut node_vote = vote.clone();
-            let node_identity = Arc::new(NodeIdentity::new(
-                secret,
-                vec![Multiaddr::empty()],
-                PeerFeatures::COMMUNICATION_NODE,
-            ));
-            node_vote
-                .sign_vote(
-                    &NodeIdentitySigningService::new(node_identity),
-                    ShardId::zero(),
-                    &vn_bmt,
-                )
-                .unwrap();
-            node_vote.validator_metadata().clone()
+            node_vote.sign_vote(signing_service, ShardId::zero(), &vn_bmt).unwrap();
+            let md = node_vote.validator_metadata();
+            (md.merkle_leaf_index, md.public_key.clone(), md.signature.clone())
         })
         .collect();
+    let aggregated_signature = signing_services[0]
+        .aggregate(signatures, &*vote.construct_challenge())
+        .unwrap();
 
     QuorumCertificate::new(
         qc.payload_id(),
@@ -107,10 +105,19 @@ fn create_test_qc(
         qc.epoch(),
         *qc.decision(),
         qc.all_shard_pledges().clone(),
-        validators_metadata,
+        aggregated_signature,
     )
 }
 
+fn create_signing_service(secret: PrivateKey) -> NodeIdentitySigningService {
+    let node_identity = Arc::new(NodeIdentity::new(
+        secret,
+        vec![Multiaddr::empty()],
+        PeerFeatures::COMMUNICATION_NODE,
+    ));
+    NodeIdentitySigningService::new(node_identity)
+}
+
 lazy_static! {
     static ref SHARD0: ShardId = ShardId::zero();
     static ref SHARD1: ShardId = ShardId([1u8; 32]);
@@ -329,7 +336,7 @@ async fn test_hs_waiter_leader_sends_new_proposal_when_enough_votes_are_received
 
     // Send another vote
     let mut vote = VoteMessage::new(*vote_hash, QuorumDecision::Accept, Default::default());
-    vote.sign_vote(instance.signing_service(), ShardId::zero(), &vn_bmt)
+    vote.sign_vote(&create_signing_service(node2_pk), ShardId::zero(), &vn_bmt)
         .unwrap();
     instance.tx_votes.send((node2, vote)).await.unwrap();
 
@@ -341,6 +348,87 @@ async fn test_hs_waiter_leader_sends_new_proposal_when_enough_votes_are_received
     instance.assert_shuts_down_safely().await
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_hs_waiter_leader_counts_votes_per_decision() {
+    let keys = (0..4)
+        .map(|_| PublicKey::random_keypair(&mut OsRng))
+        .collect::<Vec<_>>();
+    let public_keys = keys.iter().map(|(_, pk)| pk.clone()).collect::<Vec<_>>();
+    let vn_bmt = ValidatorNodeBMT::create(
+        public_keys
+            .iter()
+            .map(|pk| vn_bmt_node_hash(pk, &ShardId::zero()).to_vec())
+            .collect(),
+    );
+
+    let epoch_manager = RangeEpochManager::new(public_keys.clone(), *SHARD0..*SHARD1, public_keys.clone());
+    let mut instance = HsTestHarness::new(
+        keys[0].0.clone(),
+        public_keys[0].clone(),
+        epoch_manager,
+        AlwaysFirstLeader {},
+        *NEVER,
+    );
+    let payload = TariDanPayload::new(
+        Transaction::builder()
+            .add_input(*SHARD0)
+            .add_output(*SHARD1)
+            .sign(&keys[0].0)
+            .clone()
+            .build(),
+    );
+
+    let qc = create_test_default_qc(keys.clone(), public_keys.clone(), &payload);
+    let new_view_message = HotStuffMessage::new_view(qc, *SHARD0, payload.clone());
+    // The leader starts the view once it has a quorum of new views
+    for pk in &public_keys[..3] {
+        instance
+            .tx_hs_messages
+            .send((pk.clone(), new_view_message.clone()))
+            .await
+            .unwrap();
+    }
+
+    let (proposal_message, _broadcast_group) = instance.recv_broadcast().await;
+    let vote_hash = *proposal_message.node().unwrap().hash();
+    instance
+        .state_store()
+        .with_write_tx(|tx| tx.save_node(proposal_message.node().unwrap().clone()))
+        .unwrap();
+    let pledges = new_view_message.high_qc().unwrap().all_shard_pledges().clone();
+
+    // The threshold for 4 members is 3. One member rejects, so the quorum is only reached by the fourth vote.
+    let decisions = [
+        QuorumDecision::Accept,
+        QuorumDecision::Reject,
+        QuorumDecision::Accept,
+        QuorumDecision::Accept,
+    ];
+    for (i, ((secret, public_key), decision)) in keys.iter().zip(decisions).enumerate() {
+        let mut vote = VoteMessage::new(vote_hash, decision, pledges.clone());
+        vote.sign_vote(&create_signing_service(secret.clone()), ShardId::zero(), &vn_bmt)
+            .unwrap();
+        instance.tx_votes.send((public_key.clone(), vote)).await.unwrap();
+
+        if i < 3 {
+            assert!(
+                timeout(Duration::from_secs(1), instance.rx_broadcast.recv())
+                    .await
+                    .is_err(),
+                "received a proposal before the accept votes reached the threshold"
+            );
+        }
+    }
+
+    let (proposal2, _broadcast_group) = instance.recv_broadcast().await;
+    let proposed_node = proposal2.node().expect("Should have a node attached");
+    assert_eq!(proposed_node.justify().node_hash(), vote_hash);
+    assert_eq!(*proposed_node.justify().decision(), QuorumDecision::Accept);
+    assert_eq!(proposed_node.justify().aggregated_signature().num_signers(), 3);
+
+    instance.assert_shuts_down_safely().await
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_hs_waiter_execute_called_at_prepare_phase_only() {
     let (node1_pk, node1) = PublicKey::random_keypair(&mut OsRng);
@@ -0,0 +1,907 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Runs the same scenarios against every shard store backend so that the backends stay interchangeable.
+
+use tari_common_types::types::{FixedHash, PrivateKey, PublicKey};
+use tari_crypto::keys::PublicKey as PublicKeyT;
+use tari_dan_common_types::{
+    Epoch,
+    NodeHeight,
+    PayloadId,
+    QuorumCertificate,
+    ShardId,
+    ShardPledgeCollection,
+    SubstateState,
+    TreeNodeHash,
+};
+use tari_dan_core::{
+    models::{
+        vote_message::VoteMessage,
+        Committee,
+        HotStuffTreeNode,
+        LeaderFailure,
+        MempoolTransaction,
+        Payload,
+        PayloadResult,
+        SlashingEvidence,
+        SlashingEvidenceKind,
+        TariDanPayload,
+    },
+    services::leader_strategy::{LeaderStrategy, PayloadSpecificLeaderStrategy, ReputationLeaderStrategy},
+    storage::{
+        shard_store::{ShardStore, ShardStoreReadTransaction, ShardStoreWriteTransaction},
+        StorageError,
+    },
+};
+use tari_engine_types::{
+    commit_result::{FinalizeResult, RejectReason},
+    events::Event,
+    instruction::Instruction,
+    key_value_store::KeyValueEntry,
+    substate::{Substate, SubstateAddress},
+};
+use tari_template_lib::{
+    models::{KeyValueEntryAddress, KeyValueStoreId, Metadata, TemplateAddress},
+    Hash,
+};
+use tari_transaction::TransactionBuilder;
+use tari_utilities::ByteArray;
+
+fn create_payload(seed: u8) -> TariDanPayload {
+    let instruction = Instruction::CallFunction {
+        template_address: TemplateAddress::from_array([seed; 32]),
+        function: "new".to_string(),
+        args: vec![],
+    };
+    let secret_key = PrivateKey::from_bytes(&[seed; 32]).unwrap();
+    let mut builder = TransactionBuilder::new();
+    builder.add_instruction(instruction);
+    builder.with_new_outputs(1).sign(&secret_key);
+    TariDanPayload::new(builder.build())
+}
+
+fn create_node(
+    payload_id: PayloadId,
+    shard: ShardId,
+    height: u64,
+    payload_height: u64,
+    epoch: u64,
+) -> HotStuffTreeNode<PublicKey, TariDanPayload> {
+    HotStuffTreeNode::new(
+        TreeNodeHash::zero(),
+        shard,
+        NodeHeight(height),
+        payload_id,
+        None,
+        NodeHeight(payload_height),
+        0,
+        None,
+        Epoch(epoch),
+        PublicKey::default(),
+        QuorumCertificate::genesis(Epoch(epoch), payload_id, shard),
+        FixedHash::zero(),
+    )
+}
+
+fn create_qc(payload_id: PayloadId, shard: ShardId, node_hash: TreeNodeHash, height: u64) -> QuorumCertificate {
+    let mut qc = QuorumCertificate::genesis(Epoch(0), payload_id, shard);
+    qc.set_node(node_hash, NodeHeight(height));
+    qc
+}
+
+fn create_substate(seed: u8) -> (SubstateAddress, Substate) {
+    let address = SubstateAddress::KeyValueEntry(KeyValueEntryAddress::new(
+        KeyValueStoreId::new(Hash::from_array([seed; 32])),
+        vec![seed],
+    ));
+    (address, Substate::new(0, KeyValueEntry::new(vec![seed])))
+}
+
+fn payloads_round_trip<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let payload = create_payload(1);
+    let payload_id = payload.to_id();
+
+    store
+        .with_write_tx(|tx| {
+            tx.save_payload(payload.clone())?;
+            // Saving the same payload again is a no-op
+            tx.save_payload(payload.clone())
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    assert_eq!(tx.get_payload(&payload_id).unwrap().to_id(), payload_id);
+    assert!(tx.get_payload_result(&payload_id).is_err());
+    let recent = tx.get_recent_transactions().unwrap();
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].payload_id, payload_id.as_bytes().to_vec());
+    drop(tx);
+
+    let result = PayloadResult {
+        finalize_result: FinalizeResult::reject(
+            Hash::from_array(payload_id.into_array()),
+            RejectReason::ExecutionFailure("test".to_string()),
+        ),
+        pledge_hash: FixedHash::from([1u8; 32]),
+    };
+    store
+        .with_write_tx(|tx| tx.update_payload_result(&payload_id, result))
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    let result = tx.get_payload_result(&payload_id).unwrap();
+    assert_eq!(result.pledge_hash, FixedHash::from([1u8; 32]));
+    assert!(!result.finalize_result.result.is_accept());
+}
+
+fn payload_batches_round_trip<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let transactions = (1..=3)
+        .map(|seed| create_payload(seed).transaction().clone())
+        .collect::<Vec<_>>();
+    // A batch of one transaction is the same payload as the transaction on its own
+    assert_eq!(
+        TariDanPayload::new_batch(transactions[..1].to_vec()).to_id(),
+        create_payload(1).to_id()
+    );
+
+    let batch = TariDanPayload::new_batch(transactions.clone());
+    let batch_id = batch.to_id();
+    assert_ne!(batch_id, create_payload(1).to_id());
+
+    store.with_write_tx(|tx| tx.save_payload(batch.clone())).unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    let loaded = tx.get_payload(&batch_id).unwrap();
+    assert_eq!(loaded.to_id(), batch_id);
+    assert!(loaded.is_batch());
+    let batched_ids = loaded
+        .batched_payloads()
+        .iter()
+        .map(|payload| payload.to_id())
+        .collect::<Vec<_>>();
+    let expected_ids = transactions
+        .iter()
+        .map(|transaction| PayloadId::new(*transaction.hash()))
+        .collect::<Vec<_>>();
+    assert_eq!(batched_ids, expected_ids);
+}
+
+fn rollback_discards_writes<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let payload = create_payload(1);
+    let payload_id = payload.to_id();
+
+    let mut tx = store.create_write_tx().unwrap();
+    tx.save_payload(payload).unwrap();
+    tx.rollback().unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    assert!(matches!(
+        tx.get_payload(&payload_id),
+        Err(StorageError::NotFound { .. })
+    ));
+}
+
+fn high_qcs<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let payload_id = create_payload(1).to_id();
+    let shard_a = ShardId([1u8; 32]);
+    let shard_b = ShardId([2u8; 32]);
+    let node_hash = TreeNodeHash::from([3u8; 32]);
+
+    store
+        .with_write_tx(|tx| {
+            for height in [1, 3, 2] {
+                tx.insert_high_qc(
+                    PublicKey::default(),
+                    shard_a,
+                    create_qc(payload_id, shard_a, node_hash, height),
+                )?;
+            }
+            tx.insert_high_qc(
+                PublicKey::default(),
+                shard_b,
+                create_qc(payload_id, shard_b, node_hash, 1),
+            )
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    assert_eq!(
+        tx.get_high_qc_for(payload_id, shard_a).unwrap().node_height(),
+        NodeHeight(3)
+    );
+    assert_eq!(
+        tx.get_high_qc_for(payload_id, shard_b).unwrap().node_height(),
+        NodeHeight(1)
+    );
+    let qcs = tx.get_high_qcs(payload_id).unwrap();
+    assert_eq!(qcs.len(), 4);
+    assert_eq!(qcs[0].node_height(), NodeHeight(3));
+    drop(tx);
+
+    // (shard, payload, height) is unique
+    let mut tx = store.create_write_tx().unwrap();
+    assert!(tx
+        .insert_high_qc(
+            PublicKey::default(),
+            shard_a,
+            create_qc(payload_id, shard_a, node_hash, 3)
+        )
+        .is_err());
+    tx.rollback().unwrap();
+}
+
+fn consensus_heights<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let payload_id = create_payload(1).to_id();
+    let shard = ShardId([1u8; 32]);
+    let node_hash = TreeNodeHash::from([3u8; 32]);
+
+    let mut tx = store.create_read_tx().unwrap();
+    assert!(matches!(
+        tx.get_leaf_node(&payload_id, &shard),
+        Err(StorageError::NotFound { .. })
+    ));
+    assert_eq!(
+        tx.get_locked_node_hash_and_height(payload_id, shard).unwrap(),
+        (TreeNodeHash::zero(), NodeHeight(0))
+    );
+    assert_eq!(tx.get_last_executed_height(shard, payload_id).unwrap(), NodeHeight(0));
+    assert_eq!(tx.get_last_voted_height(shard, payload_id).unwrap(), (NodeHeight(0), 0));
+    drop(tx);
+
+    store
+        .with_write_tx(|tx| {
+            tx.set_leaf_node(payload_id, shard, TreeNodeHash::zero(), NodeHeight(1), NodeHeight(1))?;
+            tx.set_leaf_node(payload_id, shard, node_hash, NodeHeight(2), NodeHeight(2))?;
+            tx.set_locked(payload_id, shard, TreeNodeHash::zero(), NodeHeight(2))?;
+            tx.set_locked(payload_id, shard, node_hash, NodeHeight(3))?;
+            tx.set_last_executed_height(shard, payload_id, NodeHeight(4))?;
+            tx.set_last_voted_height(shard, payload_id, NodeHeight(1), 0)?;
+            tx.set_last_voted_height(shard, payload_id, NodeHeight(2), 1)
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    let leaf = tx.get_leaf_node(&payload_id, &shard).unwrap();
+    assert_eq!(*leaf.hash(), node_hash);
+    assert_eq!(leaf.height(), NodeHeight(2));
+    assert_eq!(
+        tx.get_locked_node_hash_and_height(payload_id, shard).unwrap(),
+        (node_hash, NodeHeight(3))
+    );
+    assert_eq!(tx.get_last_executed_height(shard, payload_id).unwrap(), NodeHeight(4));
+    assert_eq!(tx.get_last_voted_height(shard, payload_id).unwrap(), (NodeHeight(2), 1));
+}
+
+fn nodes_and_votes<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let payload_id = create_payload(1).to_id();
+    let shard = ShardId([1u8; 32]);
+    let node = create_node(payload_id, shard, 1, 1, 1);
+    let voter = PublicKey::from_secret_key(&PrivateKey::from_bytes(&[2u8; 32]).unwrap());
+
+    store
+        .with_write_tx(|tx| {
+            tx.save_node(node.clone())?;
+            // Nodes are unique by hash
+            tx.save_node(node.clone())?;
+            tx.save_received_vote_for(
+                voter.clone(),
+                *node.hash(),
+                VoteMessage::accept(*node.hash(), ShardPledgeCollection::empty()),
+            )
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    assert_eq!(tx.get_node(node.hash()).unwrap().hash(), node.hash());
+    assert!(tx.has_vote_for(&voter, *node.hash()).unwrap());
+    assert!(!tx.has_vote_for(&PublicKey::default(), *node.hash()).unwrap());
+    assert_eq!(tx.get_received_votes_for(*node.hash()).unwrap().len(), 1);
+    let transaction = tx.get_transaction(payload_id.as_bytes().to_vec()).unwrap();
+    assert_eq!(transaction.len(), 1);
+    assert_eq!(transaction[0].node_hash, node.hash().as_bytes().to_vec());
+    assert_eq!(transaction[0].total_votes, 1);
+}
+
+fn leader_proposals<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let payload_id = create_payload(1).to_id();
+    let shard_1 = ShardId([1u8; 32]);
+    let shard_2 = ShardId([2u8; 32]);
+    let shard_3 = ShardId([3u8; 32]);
+
+    store
+        .with_write_tx(|tx| {
+            for (shard, payload_height) in [(shard_2, 1), (shard_1, 1), (shard_1, 2)] {
+                let node = create_node(payload_id, shard, payload_height, payload_height, 1);
+                tx.save_leader_proposals(shard, payload_id, NodeHeight(payload_height), 0, node.clone())?;
+                // Duplicate proposals are ignored
+                tx.save_leader_proposals(shard, payload_id, NodeHeight(payload_height), 0, node)?;
+            }
+            Ok::<_, StorageError>(())
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    let proposals = tx
+        .get_leader_proposals(payload_id, NodeHeight(1), &[shard_1, shard_2])
+        .unwrap();
+    assert_eq!(proposals.len(), 2);
+    assert_eq!(proposals[0].shard(), shard_1);
+    assert_eq!(proposals[1].shard(), shard_2);
+    let proposals = tx.get_leader_proposals(payload_id, NodeHeight(1), &[shard_2]).unwrap();
+    assert_eq!(proposals.len(), 1);
+    assert_eq!(
+        tx.get_last_payload_height_for_leader_proposal(payload_id, shard_1)
+            .unwrap(),
+        NodeHeight(2)
+    );
+    assert_eq!(
+        tx.get_last_payload_height_for_leader_proposal(payload_id, shard_3)
+            .unwrap(),
+        NodeHeight(0)
+    );
+}
+
+fn substates_and_pledges<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let created_by = create_payload(1).to_id();
+    let pledged_to = create_payload(2).to_id();
+    let deleted_by = create_payload(3).to_id();
+    let shard = ShardId([1u8; 32]);
+    let (address, substate) = create_substate(1);
+
+    store
+        .with_write_tx(|tx| {
+            tx.save_substate_changes(create_node(created_by, shard, 4, 4, 1), &[SubstateState::Up {
+                created_by,
+                address,
+                data: substate,
+            }])
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    let substates = tx.get_substate_states(&[shard]).unwrap();
+    assert_eq!(substates.len(), 1);
+    assert_eq!(substates[0].created_payload_id(), created_by);
+    assert_eq!(substates[0].destroyed_payload_id(), None);
+    assert_eq!(tx.get_state_inventory().unwrap(), vec![shard]);
+    assert_eq!(tx.get_substate_states_by_range(shard, shard, &[]).unwrap().len(), 1);
+    assert!(tx
+        .get_substate_states_by_range(shard, shard, &[shard])
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        tx.get_substates_for_payload(created_by.as_bytes().to_vec(), shard.as_bytes().to_vec())
+            .unwrap()
+            .len(),
+        1
+    );
+    drop(tx);
+
+    let node_hash = TreeNodeHash::from([5u8; 32]);
+    let mut tx = store.create_write_tx().unwrap();
+    let pledge = tx.pledge_object(shard, pledged_to, NodeHeight(1)).unwrap();
+    assert_eq!(pledge.pledged_to_payload, pledged_to);
+    assert!(matches!(pledge.current_state, SubstateState::Up { .. }));
+    // Pledging again to the same payload returns the existing pledge
+    let pledge = tx.pledge_object(shard, pledged_to, NodeHeight(2)).unwrap();
+    assert_eq!(pledge.pledged_to_payload, pledged_to);
+    tx.commit().unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    let active = tx.get_active_pledge(shard).unwrap().unwrap();
+    assert_eq!(active.pledged_to_payload_id, pledged_to);
+    assert!(active.is_active);
+    drop(tx);
+
+    let mut tx = store.create_write_tx().unwrap();
+    tx.complete_pledges(shard, pledged_to, &node_hash).unwrap();
+    // There are no active pledges left to abandon
+    assert!(tx.abandon_pledges(shard, pledged_to, &node_hash).is_err());
+    tx.commit().unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    let resolved = tx.get_resolved_pledges_for_payload(pledged_to).unwrap();
+    assert_eq!(resolved.len(), 1);
+    assert_eq!(resolved[0].completed_by_tree_node_hash, Some(node_hash));
+    assert!(!resolved[0].is_active);
+    assert!(tx.get_active_pledge(shard).unwrap().is_none());
+    drop(tx);
+
+    store
+        .with_write_tx(|tx| {
+            tx.save_substate_changes(create_node(deleted_by, shard, 4, 4, 1), &[SubstateState::Down {
+                deleted_by,
+            }])
+        })
+        .unwrap();
+
+    let mut tx = store.create_write_tx().unwrap();
+    let pledge = tx
+        .pledge_object(shard, create_payload(4).to_id(), NodeHeight(1))
+        .unwrap();
+    assert!(matches!(pledge.current_state, SubstateState::Down { deleted_by: id } if id == deleted_by));
+    tx.rollback().unwrap();
+}
+
+fn state_tree<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let created_by = create_payload(1).to_id();
+    let deleted_by = create_payload(2).to_id();
+    let shards = [ShardId([1u8; 32]), ShardId([2u8; 32])];
+
+    let mut tx = store.create_read_tx().unwrap();
+    assert_eq!(tx.get_state_root().unwrap(), FixedHash::zero());
+    assert!(tx.get_state_proof(shards[0]).is_err());
+    drop(tx);
+
+    for (seed, shard) in (1u8..).zip(shards) {
+        let (address, substate) = create_substate(seed);
+        store
+            .with_write_tx(|tx| {
+                tx.save_substate_changes(create_node(created_by, shard, 4, 4, 1), &[SubstateState::Up {
+                    created_by,
+                    address,
+                    data: substate,
+                }])
+            })
+            .unwrap();
+    }
+
+    let mut tx = store.create_read_tx().unwrap();
+    let root = tx.get_state_root().unwrap();
+    assert_ne!(root, FixedHash::zero());
+    let substates = tx.get_substate_states(&shards).unwrap();
+    assert_eq!(substates.len(), 2);
+    for substate in &substates {
+        let proof = tx.get_state_proof(substate.shard_id()).unwrap();
+        assert!(proof.verify_substate(&root, substate));
+    }
+
+    let end = ShardId([0xff; 32]);
+    let chunk = tx.get_substate_states_chunk(ShardId::zero(), end, 1).unwrap();
+    assert_eq!(chunk.len(), 1);
+    assert_eq!(chunk[0].shard_id(), shards[0]);
+    let chunk = tx.get_substate_states_chunk(shards[1], end, 10).unwrap();
+    assert_eq!(chunk.len(), 1);
+    assert_eq!(chunk[0].shard_id(), shards[1]);
+    drop(tx);
+
+    store
+        .with_write_tx(|tx| {
+            tx.save_substate_changes(create_node(deleted_by, shards[0], 5, 5, 1), &[SubstateState::Down {
+                deleted_by,
+            }])
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    let new_root = tx.get_state_root().unwrap();
+    assert_ne!(new_root, root);
+    let destroyed = tx.get_substate_states(&shards[..1]).unwrap().remove(0);
+    let proof = tx.get_state_proof(shards[0]).unwrap();
+    assert!(proof.verify_substate(&new_root, &destroyed));
+    // The substate before it was destroyed is no longer part of the state
+    let before = substates.iter().find(|s| s.shard_id() == shards[0]).unwrap();
+    assert!(!proof.verify_substate(&new_root, before));
+}
+
+fn events<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let payload_id = create_payload(1).to_id();
+    let other_payload_id = create_payload(2).to_id();
+    let template_address = TemplateAddress::from_array([1u8; 32]);
+    let event = |topic: &str| Event::new(topic.to_string(), template_address, None, Metadata::new());
+
+    store
+        .with_write_tx(|tx| {
+            tx.save_events(payload_id, &[event("a"), event("b"), event("a")])?;
+            tx.save_events(other_payload_id, &[event("ab")])
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    let topics = tx
+        .get_events_for_payload(payload_id)
+        .unwrap()
+        .into_iter()
+        .map(|e| e.topic)
+        .collect::<Vec<_>>();
+    assert_eq!(topics, vec!["a", "b", "a"]);
+    assert_eq!(tx.get_events_by_topic("a").unwrap().len(), 2);
+    assert_eq!(tx.get_events_by_topic("ab").unwrap().len(), 1);
+    assert!(tx.get_events_by_topic("c").unwrap().is_empty());
+}
+
+fn prune_committed_payload<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let payload = create_payload(1);
+    let payload_id = payload.to_id();
+    let uncommitted_payload_id = create_payload(2).to_id();
+    let shard = ShardId([1u8; 32]);
+    let other_shard = ShardId([2u8; 32]);
+    let mut nodes = (1..=3)
+        .map(|height| create_node(payload_id, shard, height, height, 1))
+        .collect::<Vec<_>>();
+    // The node at the last executed height, whose justify is the committing QC
+    let committing_qc = create_qc(payload_id, shard, *nodes[2].hash(), 3);
+    nodes.push(HotStuffTreeNode::new(
+        *nodes[2].hash(),
+        shard,
+        NodeHeight(4),
+        payload_id,
+        None,
+        NodeHeight(4),
+        0,
+        None,
+        Epoch(1),
+        PublicKey::default(),
+        committing_qc.clone(),
+        FixedHash::zero(),
+    ));
+    // A proposal in a later leader round that this node did not save as a node
+    let proposal = create_node(payload_id, shard, 5, 4, 1);
+    let created_by = create_payload(3).to_id();
+
+    store
+        .with_write_tx(|tx| {
+            tx.save_payload(payload.clone())?;
+            for node in &nodes {
+                tx.save_node(node.clone())?;
+            }
+            tx.save_node(create_node(uncommitted_payload_id, shard, 4, 4, 1))?;
+            tx.set_leaf_node(payload_id, shard, *nodes[3].hash(), NodeHeight(4), NodeHeight(4))?;
+            tx.set_locked(payload_id, shard, *nodes[2].hash(), NodeHeight(3))?;
+            tx.set_last_executed_height(shard, payload_id, NodeHeight(4))?;
+            tx.set_last_voted_height(shard, payload_id, NodeHeight(4), 0)?;
+            tx.save_current_leader_state(payload_id, shard, 0, PublicKey::default())?;
+            tx.save_received_vote_for(
+                PublicKey::default(),
+                *nodes[3].hash(),
+                VoteMessage::accept(*nodes[3].hash(), ShardPledgeCollection::empty()),
+            )?;
+            tx.save_leader_proposals(shard, payload_id, NodeHeight(4), 0, nodes[3].clone())?;
+            tx.save_leader_proposals(shard, payload_id, NodeHeight(4), 1, proposal.clone())?;
+            tx.save_received_vote_for(
+                PublicKey::default(),
+                *proposal.hash(),
+                VoteMessage::accept(*proposal.hash(), ShardPledgeCollection::empty()),
+            )?;
+            tx.insert_high_qc(PublicKey::default(), shard, committing_qc.clone())?;
+            tx.insert_high_qc(
+                PublicKey::default(),
+                shard,
+                create_qc(payload_id, shard, *nodes[3].hash(), 4),
+            )?;
+            tx.update_payload_result(&payload_id, PayloadResult {
+                finalize_result: FinalizeResult::reject(
+                    Hash::from_array(payload_id.into_array()),
+                    RejectReason::ExecutionFailure("test".to_string()),
+                ),
+                pledge_hash: FixedHash::zero(),
+            })?;
+            for (seed, pledged_shard) in [(1, shard), (2, other_shard)] {
+                let (address, data) = create_substate(seed);
+                tx.save_substate_changes(create_node(created_by, pledged_shard, 4, 4, 1), &[SubstateState::Up {
+                    created_by,
+                    address,
+                    data,
+                }])?;
+            }
+            tx.pledge_object(shard, payload_id, NodeHeight(1))?;
+            tx.complete_pledges(shard, payload_id, nodes[3].hash())?;
+            // Active pledges are kept
+            tx.pledge_object(other_shard, payload_id, NodeHeight(1))?;
+            Ok::<_, StorageError>(())
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    assert!(tx
+        .get_prunable_payloads(Epoch(1), NodeHeight(4), 10)
+        .unwrap()
+        .is_empty());
+    assert_eq!(tx.get_prunable_payloads(Epoch(2), NodeHeight(4), 10).unwrap(), vec![
+        payload_id
+    ]);
+    drop(tx);
+
+    let counts = store.with_write_tx(|tx| tx.prune_payload(payload_id)).unwrap();
+    assert_eq!(counts.nodes, 2);
+    assert_eq!(counts.received_votes, 2);
+    assert_eq!(counts.leader_proposals, 2);
+    assert_eq!(counts.high_qcs, 1);
+    assert_eq!(counts.pledges, 1);
+    assert_eq!(counts.consensus_states, 5);
+
+    let mut tx = store.create_read_tx().unwrap();
+    // The committing QC, the node that carries it and the node that it certifies are kept
+    let qcs = tx.get_high_qcs(payload_id).unwrap();
+    assert_eq!(qcs.len(), 1);
+    assert_eq!(qcs[0].node_hash(), committing_qc.node_hash());
+    assert_eq!(qcs[0].node_height(), NodeHeight(3));
+    assert_eq!(
+        tx.get_node(nodes[3].hash()).unwrap().justify().node_hash(),
+        *nodes[2].hash()
+    );
+    assert!(tx.get_node(nodes[2].hash()).is_ok());
+    assert_eq!(tx.get_transaction(payload_id.as_bytes().to_vec()).unwrap().len(), 2);
+    assert!(tx.get_node(nodes[0].hash()).is_err());
+    assert!(tx.get_node(nodes[1].hash()).is_err());
+    assert!(tx.get_received_votes_for(*proposal.hash()).unwrap().is_empty());
+    assert!(tx
+        .get_leader_proposals(payload_id, NodeHeight(4), &[shard])
+        .unwrap()
+        .is_empty());
+    assert!(tx
+        .get_payload_result(&payload_id)
+        .unwrap()
+        .finalize_result
+        .result
+        .reject()
+        .is_some());
+    assert_eq!(tx.get_payload(&payload_id).unwrap().to_id(), payload_id);
+    let pledges = tx.get_resolved_pledges_for_payload(payload_id).unwrap();
+    assert!(pledges.is_empty());
+    assert_eq!(
+        tx.get_active_pledge(other_shard)
+            .unwrap()
+            .unwrap()
+            .pledged_to_payload_id,
+        payload_id
+    );
+    assert!(matches!(
+        tx.get_leaf_node(&payload_id, &shard),
+        Err(StorageError::NotFound { .. })
+    ));
+    assert_eq!(tx.get_last_executed_height(shard, payload_id).unwrap(), NodeHeight(0));
+    assert!(tx
+        .get_prunable_payloads(Epoch(2), NodeHeight(4), 10)
+        .unwrap()
+        .is_empty());
+}
+
+fn prunable_payloads<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let shard_a = ShardId([1u8; 32]);
+    let shard_b = ShardId([2u8; 32]);
+    let payload_ids = (1..=3).map(|seed| create_payload(seed).to_id()).collect::<Vec<_>>();
+
+    store
+        .with_write_tx(|tx| {
+            // Decided at payload height 3 on shard a
+            for height in 1..=3 {
+                tx.save_node(create_node(payload_ids[0], shard_a, height, height, 1))?;
+            }
+            tx.set_last_executed_height(shard_a, payload_ids[0], NodeHeight(3))?;
+            // Decided on shard a, but not on shard b
+            for (shard, height) in [(shard_a, 3), (shard_b, 2)] {
+                for height in 1..=height {
+                    tx.save_node(create_node(payload_ids[1], shard, height, height, 1))?;
+                }
+                tx.set_last_executed_height(shard, payload_ids[1], NodeHeight(height))?;
+            }
+            // Decided, but not executed on shard a
+            for height in 1..=3 {
+                tx.save_node(create_node(payload_ids[2], shard_a, height, height, 1))?;
+            }
+            tx.set_last_executed_height(shard_a, payload_ids[2], NodeHeight(2))
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    // The payload is only committed at the decide height
+    assert!(tx
+        .get_prunable_payloads(Epoch(2), NodeHeight(4), 10)
+        .unwrap()
+        .is_empty());
+    assert_eq!(tx.get_prunable_payloads(Epoch(2), NodeHeight(3), 10).unwrap(), vec![
+        payload_ids[0]
+    ]);
+}
+
+fn leader_failures<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload> + Clone>(store: &S) {
+    let members = (1u8..=3)
+        .map(|seed| PublicKey::from_secret_key(&PrivateKey::from_bytes(&[seed; 32]).unwrap()))
+        .collect::<Vec<_>>();
+    let committee = Committee::new(members);
+    let payload_id = create_payload(1).to_id();
+    let shard = ShardId([1u8; 32]);
+    let payload_specific_order = (0..3)
+        .map(|round| {
+            PayloadSpecificLeaderStrategy {}
+                .get_leader(Epoch(2), &committee, payload_id, shard, round)
+                .unwrap()
+                .clone()
+        })
+        .collect::<Vec<_>>();
+
+    let failure = |epoch: u64, leader_round: u32| LeaderFailure {
+        epoch: Epoch(epoch),
+        committee_hash: committee.members_hash(),
+        shard_id: shard,
+        payload_id,
+        leader_round,
+        leader: payload_specific_order[leader_round as usize].clone(),
+    };
+    store
+        .with_write_tx(|tx| {
+            tx.save_leader_failure(failure(1, 0))?;
+            // Saving the same failure again is a no-op
+            tx.save_leader_failure(failure(1, 0))?;
+            tx.save_leader_failure(failure(3, 0))?;
+            tx.save_leader_failure(failure(3, 1))
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    assert_eq!(tx.get_leader_failures(Epoch(1), Epoch(2)).unwrap(), vec![failure(1, 0)]);
+    assert_eq!(tx.get_leader_failures(Epoch(0), Epoch(3)).unwrap().len(), 3);
+    assert!(tx.get_leader_failures(Epoch(4), Epoch(10)).unwrap().is_empty());
+    drop(tx);
+
+    let strategy = ReputationLeaderStrategy::new(store.clone());
+    let leaders = |epoch: u64| {
+        (0..3)
+            .map(|round| {
+                strategy
+                    .get_leader(Epoch(epoch), &committee, payload_id, shard, round)
+                    .unwrap()
+                    .clone()
+            })
+            .collect::<Vec<_>>()
+    };
+    // Failures in the current epoch are not counted
+    assert_eq!(leaders(1), payload_specific_order);
+    // The leader that failed in epoch 1 moves to the back of the order
+    assert_eq!(leaders(2), vec![
+        payload_specific_order[1].clone(),
+        payload_specific_order[2].clone(),
+        payload_specific_order[0].clone(),
+    ]);
+    // In epoch 4 the first leader has failed twice and the second leader once
+    assert_eq!(leaders(4), vec![
+        payload_specific_order[2].clone(),
+        payload_specific_order[1].clone(),
+        payload_specific_order[0].clone(),
+    ]);
+
+    // Failures recorded by a committee with different members are not counted
+    let other_committee = Committee::new(committee.members[..2].to_vec());
+    assert_eq!(
+        strategy
+            .get_leader(Epoch(2), &other_committee, payload_id, shard, 0)
+            .unwrap(),
+        PayloadSpecificLeaderStrategy {}
+            .get_leader(Epoch(2), &other_committee, payload_id, shard, 0)
+            .unwrap()
+    );
+}
+
+fn slashing_evidence<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let payload_id = create_payload(1).to_id();
+    let shard = ShardId([1u8; 32]);
+    let first = create_node(payload_id, shard, 1, 1, 1);
+    let second = create_node(payload_id, shard, 1, 2, 1);
+    let voter = PublicKey::from_secret_key(&PrivateKey::from_bytes(&[1u8; 32]).unwrap());
+    let reporter = PublicKey::from_secret_key(&PrivateKey::from_bytes(&[2u8; 32]).unwrap());
+
+    let double_proposal = SlashingEvidence::double_proposal(first.clone(), second.clone(), reporter.clone());
+    let double_vote = SlashingEvidence::double_vote(
+        voter.clone(),
+        (
+            first.clone(),
+            VoteMessage::accept(*first.hash(), ShardPledgeCollection::empty()),
+        ),
+        (
+            second.clone(),
+            VoteMessage::accept(*second.hash(), ShardPledgeCollection::empty()),
+        ),
+        reporter,
+    );
+    store
+        .with_write_tx(|tx| {
+            tx.save_slashing_evidence(double_proposal.clone())?;
+            // Saving the same evidence again is a no-op
+            tx.save_slashing_evidence(double_proposal.clone())?;
+            tx.save_slashing_evidence(double_vote.clone())
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    assert_eq!(tx.get_slashing_evidence(None).unwrap().len(), 2);
+
+    let against_voter = tx.get_slashing_evidence(Some(&voter)).unwrap();
+    assert_eq!(against_voter.len(), 1);
+    assert_eq!(against_voter[0].kind(), SlashingEvidenceKind::DoubleVote);
+    assert_eq!(against_voter[0].hash(), double_vote.hash());
+    assert_eq!(against_voter[0].height(), NodeHeight(1));
+    assert!(against_voter[0].votes().is_some());
+
+    let against_leader = tx.get_slashing_evidence(Some(&PublicKey::default())).unwrap();
+    assert_eq!(against_leader.len(), 1);
+    assert_eq!(against_leader[0].kind(), SlashingEvidenceKind::DoubleProposal);
+    assert_eq!(against_leader[0].hash(), double_proposal.hash());
+    assert!(against_leader[0].votes().is_none());
+}
+
+fn mempool_transactions<S: ShardStore<Addr = PublicKey, Payload = TariDanPayload>>(store: &S) {
+    let waiting = MempoolTransaction {
+        transaction: create_payload(1).transaction().clone(),
+        received_at: 100,
+        payload_id: None,
+    };
+    let payload = create_payload(2);
+    let dispatched = MempoolTransaction {
+        transaction: payload.transaction().clone(),
+        received_at: 200,
+        payload_id: Some(payload.to_id()),
+    };
+
+    store
+        .with_write_tx(|tx| {
+            tx.save_mempool_transaction(waiting.clone())?;
+            tx.save_mempool_transaction(dispatched.clone())
+        })
+        .unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    let mut transactions = tx.get_mempool_transactions().unwrap();
+    transactions.sort_by_key(|t| t.received_at);
+    assert_eq!(transactions, vec![waiting.clone(), dispatched.clone()]);
+    drop(tx);
+
+    // Saving the same transaction again replaces the entry
+    let batched = MempoolTransaction {
+        payload_id: Some(create_payload(3).to_id()),
+        ..waiting.clone()
+    };
+    let mut tx = store.create_write_tx().unwrap();
+    tx.save_mempool_transaction(batched.clone()).unwrap();
+    assert!(tx.remove_mempool_transaction(&dispatched.hash()).unwrap());
+    assert!(!tx.remove_mempool_transaction(&dispatched.hash()).unwrap());
+    tx.commit().unwrap();
+
+    let mut tx = store.create_read_tx().unwrap();
+    assert_eq!(tx.get_mempool_transactions().unwrap(), vec![batched]);
+}
+
+macro_rules! shard_store_conformance_tests {
+    ($($scenario:ident),+ $(,)?) => {
+        mod sqlite {
+            $(
+                #[test]
+                fn $scenario() {
+                    super::$scenario(&crate::TempShardStoreFactory::new());
+                }
+            )+
+        }
+
+        mod lmdb {
+            use tari_dan_storage_lmdb::shard_store::LmdbShardStore;
+            use tempdir::TempDir;
+
+            $(
+                #[test]
+                fn $scenario() {
+                    let path = TempDir::new("lmdbshardstore").unwrap();
+                    super::$scenario(&LmdbShardStore::try_create(path.path()).unwrap());
+                }
+            )+
+        }
+    };
+}
+
+shard_store_conformance_tests!(
+    payloads_round_trip,
+    payload_batches_round_trip,
+    rollback_discards_writes,
+    high_qcs,
+    consensus_heights,
+    nodes_and_votes,
+    leader_proposals,
+    substates_and_pledges,
+    state_tree,
+    events,
+    prune_committed_payload,
+    prunable_payloads,
+    leader_failures,
+    slashing_evidence,
+    mempool_transactions,
+);
@@ -0,0 +1,285 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use rand::rngs::OsRng;
+use tari_common_types::types::PublicKey;
+use tari_crypto::keys::PublicKey as PublicKeyT;
+use tari_dan_common_types::ShardId;
+use tari_dan_consensus_simulator::{
+    ByzantineBehaviour,
+    NetworkRule,
+    Outcome,
+    Simulator,
+    SimulatorConfig,
+    SimulatorError,
+};
+use tari_dan_core::{
+    models::{Payload, TariDanPayload},
+    services::{leader_strategy::RotatingLeader, PayloadProcessor},
+};
+use tari_engine_types::{
+    resource::Resource,
+    substate::{Substate, SubstateAddress},
+};
+use tari_template_lib::{models::ResourceAddress, prelude::ResourceType, Hash};
+use tari_transaction::Transaction;
+
+use crate::{
+    harness::{PayloadProcessorListener, SubstateCreatingPayloadProcessor},
+    TempShardStoreFactory,
+};
+
+const MAX_STEPS: u64 = 500;
+
+type TestSimulator<TPayloadProcessor = PayloadProcessorListener> =
+    Simulator<RotatingLeader, TPayloadProcessor, TempShardStoreFactory>;
+
+fn create_simulator(config: SimulatorConfig, committee_sizes: &[usize]) -> TestSimulator {
+    Simulator::new(config, committee_sizes, |_| {
+        (
+            RotatingLeader {},
+            PayloadProcessorListener::new(),
+            TempShardStoreFactory::new(),
+        )
+    })
+}
+
+/// Creates a payload with an input in each of the simulator's committees
+fn create_payload(simulator: &TestSimulator, num_committees: usize) -> TariDanPayload {
+    let (secret_key, _) = PublicKey::random_keypair(&mut OsRng);
+    let inputs = (0..num_committees)
+        .map(|committee| simulator.committee_shard(committee))
+        .collect::<Vec<_>>();
+    TariDanPayload::new(
+        Transaction::builder()
+            .with_inputs(inputs)
+            .sign(&secret_key)
+            .clone()
+            .build(),
+    )
+}
+
+/// Returns substates with shards in the first `num_committees` committees of the simulator
+fn create_substates(num_substates: usize, num_committees: u8) -> Vec<(SubstateAddress, Substate)> {
+    (0u64..)
+        .map(|n| {
+            let mut hash = [0u8; 32];
+            hash[..8].copy_from_slice(&n.to_le_bytes());
+            SubstateAddress::Resource(ResourceAddress::new(Hash::from(hash)))
+        })
+        .filter(|address| ShardId::from_address(address, 0).0[0] < num_committees)
+        .take(num_substates)
+        .map(|address| {
+            let resource = Resource::new(ResourceType::Fungible, Default::default(), Default::default());
+            (address, Substate::new(0, resource))
+        })
+        .collect()
+}
+
+fn assert_all_accepted<TPayloadProcessor>(
+    simulator: &TestSimulator<TPayloadProcessor>,
+    payload: &TariDanPayload,
+    nodes: &[usize],
+) where
+    TPayloadProcessor: PayloadProcessor<TariDanPayload> + Send + Sync + 'static,
+{
+    let outcomes = simulator.outcomes(&payload.to_id());
+    for node in nodes {
+        assert!(
+            outcomes
+                .iter()
+                .any(|(n, outcome)| n == node && matches!(outcome, Outcome::Finalized { accepted: true, .. })),
+            "node {} did not finalize the payload: {:?}",
+            node,
+            outcomes
+        );
+    }
+}
+
+/// Asserts that an honest node committed the payload. The invariants ensure that the other honest nodes agree.
+fn assert_committed_by_honest_node(simulator: &TestSimulator, payload: &TariDanPayload) {
+    let outcomes = simulator.outcomes(&payload.to_id());
+    assert!(
+        outcomes
+            .iter()
+            .any(|(node, outcome)| simulator.is_honest(*node) && matches!(outcome, Outcome::Finalized { .. })),
+        "no honest node committed the payload: {:?}",
+        outcomes
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn honest_committees_commit_payload() {
+    let mut simulator = create_simulator(SimulatorConfig::new(1), &[4, 4]);
+    let payload = create_payload(&simulator, 2);
+
+    simulator.submit(payload.clone()).await.unwrap();
+    simulator.run_until_decided(payload.to_id(), MAX_STEPS).await.unwrap();
+
+    assert_all_accepted(&simulator, &payload, &(0..simulator.num_nodes()).collect::<Vec<_>>());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn concurrent_payloads_are_committed_by_every_committee() {
+    let substates = create_substates(2, 2);
+    let mut simulator = {
+        let substates = substates.clone();
+        Simulator::new(SimulatorConfig::new(9), &[4, 4], move |_| {
+            (
+                RotatingLeader {},
+                SubstateCreatingPayloadProcessor::new(substates.clone()),
+                TempShardStoreFactory::new(),
+            )
+        })
+    };
+    // Each payload has its own input in both committees and creates a substate, so members commit the state changes
+    // of one payload while they are voting on the other
+    let (secret_key, _) = PublicKey::random_keypair(&mut OsRng);
+    let payloads = substates
+        .iter()
+        .enumerate()
+        .map(|(i, (address, substate))| {
+            let inputs = (0..2)
+                .map(|committee| {
+                    let mut shard = simulator.committee_shard(committee);
+                    shard.0[31] = i as u8 + 1;
+                    shard
+                })
+                .collect();
+            TariDanPayload::new(
+                Transaction::builder()
+                    .with_inputs(inputs)
+                    .add_output(ShardId::from_address(address, substate.version()))
+                    .sign(&secret_key)
+                    .clone()
+                    .build(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    for payload in &payloads {
+        simulator.submit(payload.clone()).await.unwrap();
+    }
+    simulator
+        .run_until(MAX_STEPS, |sim| {
+            payloads.iter().all(|payload| sim.is_decided(&payload.to_id()))
+        })
+        .await
+        .unwrap();
+
+    let all_nodes = (0..simulator.num_nodes()).collect::<Vec<_>>();
+    for payload in &payloads {
+        assert_all_accepted(&simulator, payload, &all_nodes);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn minority_partition_does_not_prevent_commit() {
+    let config = SimulatorConfig::new(2).with_network_rule(NetworkRule::Partition {
+        groups: vec![vec![0, 1, 2], vec![3]],
+        steps: 0..u64::MAX,
+    });
+    let mut simulator = create_simulator(config, &[4]);
+    let payload = create_payload(&simulator, 1);
+    let payload_id = payload.to_id();
+
+    simulator.submit(payload.clone()).await.unwrap();
+    simulator
+        .run_until(MAX_STEPS, |sim| {
+            let outcomes = sim.outcomes(&payload_id);
+            (0..3).all(|node| outcomes.iter().any(|(n, _)| *n == node))
+        })
+        .await
+        .unwrap();
+
+    assert_all_accepted(&simulator, &payload, &[0, 1, 2]);
+    assert!(simulator.outcomes(&payload_id).iter().all(|(node, _)| *node != 3));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn silent_leader_is_replaced() {
+    // With the rotating leader strategy, node 0 leads the first round
+    let config = SimulatorConfig::new(3).with_byzantine_node(0, ByzantineBehaviour::Silent);
+    let mut simulator = create_simulator(config, &[4]);
+    let payload = create_payload(&simulator, 1);
+
+    simulator.submit(payload.clone()).await.unwrap();
+    simulator.run_until_decided(payload.to_id(), MAX_STEPS).await.unwrap();
+
+    assert_all_accepted(&simulator, &payload, &[1, 2, 3]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn equivocating_leader_does_not_cause_conflicting_commits() {
+    let config = SimulatorConfig::new(4).with_byzantine_node(0, ByzantineBehaviour::Equivocate);
+    let mut simulator = create_simulator(config, &[4]);
+    let payload = create_payload(&simulator, 1);
+
+    simulator.submit(payload.clone()).await.unwrap();
+    // The invariants are checked after every step
+    simulator.run_until_decided(payload.to_id(), MAX_STEPS).await.unwrap();
+
+    assert_committed_by_honest_node(&simulator, &payload);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn invalid_pledges_do_not_cause_conflicting_commits() {
+    let config = SimulatorConfig::new(5).with_byzantine_node(1, ByzantineBehaviour::InvalidPledges);
+    let mut simulator = create_simulator(config, &[4, 4]);
+    let payload = create_payload(&simulator, 2);
+
+    simulator.submit(payload.clone()).await.unwrap();
+    simulator.run_until_decided(payload.to_id(), MAX_STEPS).await.unwrap();
+
+    assert_committed_by_honest_node(&simulator, &payload);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lossy_network_preserves_safety() {
+    let config = SimulatorConfig::new(6)
+        .with_network_rule(NetworkRule::Delay { min: 0, max: 3 })
+        .with_network_rule(NetworkRule::Drop { probability: 0.1 })
+        .with_network_rule(NetworkRule::Reorder);
+    let mut simulator = create_simulator(config, &[4, 4]);
+    let payload = create_payload(&simulator, 2);
+
+    simulator.submit(payload.clone()).await.unwrap();
+    simulator.run_until_decided(payload.to_id(), MAX_STEPS).await.unwrap();
+
+    assert_committed_by_honest_node(&simulator, &payload);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn same_seed_replays_identically() {
+    let config = SimulatorConfig::new(7)
+        .with_network_rule(NetworkRule::Delay { min: 0, max: 2 })
+        .with_network_rule(NetworkRule::Drop { probability: 0.05 })
+        .with_network_rule(NetworkRule::Reorder);
+
+    let mut first = create_simulator(config.clone(), &[4, 4]);
+    let payload = create_payload(&first, 2);
+    first.submit(payload.clone()).await.unwrap();
+    first.run_for(100).await.unwrap();
+
+    let mut second = create_simulator(config, &[4, 4]);
+    second.submit(payload).await.unwrap();
+    second.run_for(100).await.unwrap();
+
+    assert_eq!(first.trace(), second.trace());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn payload_outside_committees_is_rejected() {
+    let mut simulator = create_simulator(SimulatorConfig::new(8), &[4]);
+    let (secret_key, _) = PublicKey::random_keypair(&mut OsRng);
+    let payload = TariDanPayload::new(
+        Transaction::builder()
+            .add_input(ShardId([0xffu8; 32]))
+            .sign(&secret_key)
+            .clone()
+            .build(),
+    );
+
+    let err = simulator.submit(payload).await.unwrap_err();
+    assert!(matches!(err, SimulatorError::PayloadNotInvolved { .. }));
+}
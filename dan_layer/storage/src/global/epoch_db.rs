@@ -49,4 +49,8 @@ impl<'a, 'tx, TGlobalDbAdapter: GlobalDbAdapter> EpochDb<'a, 'tx, TGlobalDbAdapt
 pub struct DbEpoch {
     pub epoch: u64,
     pub validator_node_mr: Vec<u8>,
+    /// Timestamp of the base layer block header that started this epoch
+    pub timestamp: u64,
+    /// Hash of the base layer block header that started this epoch
+    pub block_hash: Vec<u8>,
 }
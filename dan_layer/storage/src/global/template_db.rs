@@ -55,6 +55,16 @@ impl<'a, 'tx, TGlobalDbAdapter: GlobalDbAdapter> TemplateDb<'a, 'tx, TGlobalDbAd
     pub fn template_exists(&mut self, key: &[u8]) -> Result<bool, TGlobalDbAdapter::Error> {
         self.backend.template_exists(self.tx, key)
     }
+
+    /// Returns the highest version of the named template registered by the given author
+    pub fn get_latest_template_version(
+        &mut self,
+        author_public_key: &[u8],
+        template_name: &str,
+    ) -> Result<Option<DbTemplate>, TGlobalDbAdapter::Error> {
+        self.backend
+            .get_latest_template_version(self.tx, author_public_key, template_name)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +77,10 @@ pub struct DbTemplate {
     pub compiled_code: Vec<u8>,
     pub status: TemplateStatus,
     pub added_at: time::OffsetDateTime,
+    pub author_public_key: Vec<u8>,
+    pub version: u16,
+    /// The address of the previous version of this template, if any
+    pub predecessor_address: Option<FixedHash>,
 }
 
 #[derive(Debug, Clone, Default)]
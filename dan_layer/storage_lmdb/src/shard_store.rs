@@ -0,0 +1,1637 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    collections::{HashMap, HashSet},
+    fs::create_dir_all,
+    ops::{Deref, DerefMut},
+    path::Path,
+    sync::Arc,
+};
+
+use chrono::{NaiveDateTime, Utc};
+use lmdb_zero::{db, put, ConstTransaction, LmdbResultExt, ReadTransaction, WriteTransaction};
+use log::{debug, warn};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use tari_common_types::types::{FixedHash, PublicKey};
+use tari_dan_common_types::{
+    Epoch,
+    NodeHeight,
+    ObjectPledge,
+    ObjectPledgeInfo,
+    PayloadId,
+    QuorumCertificate,
+    ShardId,
+    SubstateState,
+    TreeNodeHash,
+};
+use tari_dan_core::{
+    models::{
+        vote_message::VoteMessage,
+        CurrentLeaderStates,
+        HotStuffTreeNode,
+        LeaderFailure,
+        LeafNode,
+        MempoolTransaction,
+        Payload,
+        PayloadResult,
+        PrunedRecordCounts,
+        RecentTransaction,
+        SQLSubstate,
+        SQLTransaction,
+        SlashingEvidence,
+        StateTreeNode,
+        StateTreeProof,
+        SubstateShardData,
+        TariDanPayload,
+    },
+    storage::{
+        shard_store::{ShardStore, ShardStoreReadTransaction, ShardStoreWriteTransaction},
+        state_tree::{StateTree, StateTreeStoreReader, StateTreeStoreWriter},
+        StorageError,
+    },
+};
+use tari_engine_types::{
+    events::Event,
+    substate::{Substate, SubstateAddress},
+};
+use tari_storage::lmdb_store::{DatabaseRef, LMDBBuilder, LMDBConfig};
+use tari_utilities::{hex::Hex, ByteArray};
+
+const LOG_TARGET: &str = "tari::dan::storage::lmdb::shard_store";
+
+const PAYLOADS_DB: &str = "payloads";
+const NODES_DB: &str = "nodes";
+const NODES_BY_PAYLOAD_DB: &str = "nodes_by_payload";
+const HIGH_QCS_DB: &str = "high_qcs";
+const LEAF_NODES_DB: &str = "leaf_nodes";
+const CURRENT_LEADER_STATES_DB: &str = "current_leader_states";
+const LOCKED_NODES_DB: &str = "lock_node_and_heights";
+const LAST_EXECUTED_HEIGHTS_DB: &str = "last_executed_heights";
+const LAST_VOTED_HEIGHTS_DB: &str = "last_voted_heights";
+const LEADER_PROPOSALS_DB: &str = "leader_proposals";
+const RECEIVED_VOTES_DB: &str = "received_votes";
+const SUBSTATES_DB: &str = "substates";
+const SHARD_PLEDGES_DB: &str = "shard_pledges";
+const PLEDGES_BY_PAYLOAD_DB: &str = "pledges_by_payload";
+const EVENTS_DB: &str = "events";
+const EVENTS_BY_PAYLOAD_DB: &str = "events_by_payload";
+const EVENTS_BY_TOPIC_DB: &str = "events_by_topic";
+const STATE_TREE_NODES_DB: &str = "state_tree_nodes";
+const LEADER_FAILURES_DB: &str = "leader_failures";
+const SLASHING_EVIDENCE_DB: &str = "slashing_evidence";
+const MEMPOOL_TRANSACTIONS_DB: &str = "mempool_transactions";
+const METADATA_DB: &str = "metadata";
+
+const ALL_DATABASES: [&str; 22] = [
+    PAYLOADS_DB,
+    NODES_DB,
+    NODES_BY_PAYLOAD_DB,
+    HIGH_QCS_DB,
+    LEAF_NODES_DB,
+    CURRENT_LEADER_STATES_DB,
+    LOCKED_NODES_DB,
+    LAST_EXECUTED_HEIGHTS_DB,
+    LAST_VOTED_HEIGHTS_DB,
+    LEADER_PROPOSALS_DB,
+    RECEIVED_VOTES_DB,
+    SUBSTATES_DB,
+    SHARD_PLEDGES_DB,
+    PLEDGES_BY_PAYLOAD_DB,
+    EVENTS_DB,
+    EVENTS_BY_PAYLOAD_DB,
+    EVENTS_BY_TOPIC_DB,
+    STATE_TREE_NODES_DB,
+    LEADER_FAILURES_DB,
+    SLASHING_EVIDENCE_DB,
+    MEMPOOL_TRANSACTIONS_DB,
+    METADATA_DB,
+];
+
+/// The map is not resized while the store is open, as that is only safe when no transactions are active. The map is
+/// a sparse file, so this only bounds the size of the store.
+const MAP_SIZE_MB: usize = 64 * 1024;
+
+const NEXT_SEQ_KEY: &[u8] = b"next_seq";
+const STATE_TREE_ROOT_KEY: &[u8] = b"state_tree_root";
+
+/// The length of a payload id, shard id or tree node hash in a key
+const HASH_LEN: usize = 32;
+const HEIGHT_LEN: usize = 8;
+
+/// A [ShardStore] backed by LMDB.
+///
+/// Each table of the SQLite store maps to a named database. Keys are the concatenation of the fixed-size fields that
+/// are queried on, so that a prefix scan replaces the SQL filter. Heights and sequence numbers in keys are big-endian
+/// so that keys sort in numeric order. Values are JSON, as in the SQLite store.
+#[derive(Clone)]
+pub struct LmdbShardStore {
+    env: Arc<lmdb_zero::Environment>,
+    databases: Arc<HashMap<&'static str, DatabaseRef>>,
+}
+
+impl LmdbShardStore {
+    pub fn try_create<P: AsRef<Path>>(path: P) -> Result<Self, StorageError> {
+        create_dir_all(&path).map_err(|_| StorageError::FileSystemPathDoesNotExist)?;
+
+        let mut builder = LMDBBuilder::new()
+            .set_path(path.as_ref())
+            .set_env_config(LMDBConfig::new_from_mb(MAP_SIZE_MB, 0, 0))
+            .set_max_number_of_databases(ALL_DATABASES.len());
+        for name in ALL_DATABASES {
+            builder = builder.add_database(name, db::CREATE);
+        }
+        let store = builder.build()?;
+
+        let databases = ALL_DATABASES
+            .iter()
+            .map(|name| {
+                let handle = store.get_handle(name).ok_or_else(|| StorageError::ConnectionError {
+                    reason: format!("LMDB database {} was not created", name),
+                })?;
+                Ok((*name, handle.db()))
+            })
+            .collect::<Result<_, StorageError>>()?;
+
+        Ok(Self {
+            env: store.env(),
+            databases: Arc::new(databases),
+        })
+    }
+}
+
+impl ShardStore for LmdbShardStore {
+    type Addr = PublicKey;
+    type Payload = TariDanPayload;
+    type ReadTransaction<'a> = LmdbShardStoreReadTransaction<'a>;
+    type WriteTransaction<'a> = LmdbShardStoreWriteTransaction<'a>;
+
+    fn create_read_tx(&self) -> Result<Self::ReadTransaction<'_>, StorageError> {
+        let tx = ReadTransaction::new(&*self.env)?;
+        Ok(LmdbShardStoreReadTransaction::new(self, LmdbTx::Read(tx)))
+    }
+
+    fn create_write_tx(&self) -> Result<Self::WriteTransaction<'_>, StorageError> {
+        let tx = WriteTransaction::new(&*self.env)?;
+        Ok(LmdbShardStoreWriteTransaction::new(LmdbShardStoreReadTransaction::new(
+            self,
+            LmdbTx::Write(tx),
+        )))
+    }
+}
+
+enum LmdbTx<'a> {
+    Read(ReadTransaction<'a>),
+    Write(WriteTransaction<'a>),
+}
+
+impl<'a> Deref for LmdbTx<'a> {
+    type Target = ConstTransaction<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            LmdbTx::Read(tx) => tx,
+            LmdbTx::Write(tx) => tx,
+        }
+    }
+}
+
+// ---------------------------------- Records ---------------------------------- //
+
+#[derive(Serialize, Deserialize)]
+struct PayloadRecord {
+    payload: TariDanPayload,
+    result: Option<PayloadResult>,
+    timestamp: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NodeRecord {
+    node: HotStuffTreeNode<PublicKey, TariDanPayload>,
+    timestamp: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HighQcRecord {
+    qc: QuorumCertificate,
+    identity: PublicKey,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LeafNodeRecord {
+    tree_node_hash: TreeNodeHash,
+    node_height: NodeHeight,
+    payload_height: NodeHeight,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CurrentLeaderStateRecord {
+    shard_id: ShardId,
+    leader_round: u32,
+    leader: PublicKey,
+    timestamp: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LockedNodeRecord {
+    tree_node_hash: TreeNodeHash,
+    node_height: NodeHeight,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LastVotedHeightRecord {
+    node_height: NodeHeight,
+    leader_round: u32,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SubstateRecord {
+    shard_id: ShardId,
+    address: SubstateAddress,
+    version: u32,
+    substate: Substate,
+    created_by_payload_id: PayloadId,
+    created_justify: QuorumCertificate,
+    created_node_hash: TreeNodeHash,
+    created_height: NodeHeight,
+    destroyed_by_payload_id: Option<PayloadId>,
+    destroyed_justify: Option<QuorumCertificate>,
+    destroyed_node_hash: Option<TreeNodeHash>,
+    destroyed_height: Option<NodeHeight>,
+}
+
+impl SubstateRecord {
+    fn is_destroyed(&self) -> bool {
+        self.destroyed_by_payload_id.is_some()
+    }
+}
+
+impl From<SubstateRecord> for SubstateShardData {
+    fn from(record: SubstateRecord) -> Self {
+        SubstateShardData::new(
+            record.shard_id,
+            record.address,
+            record.version,
+            record.substate,
+            record.created_height,
+            record.destroyed_height,
+            record.created_node_hash,
+            record.destroyed_node_hash,
+            record.created_by_payload_id,
+            record.destroyed_by_payload_id,
+            record.created_justify,
+            record.destroyed_justify,
+        )
+    }
+}
+
+impl From<SubstateShardData> for SubstateRecord {
+    fn from(data: SubstateShardData) -> Self {
+        Self {
+            shard_id: data.shard_id(),
+            address: data.substate_address().clone(),
+            version: data.version(),
+            created_by_payload_id: data.created_payload_id(),
+            created_justify: data.created_justify().clone(),
+            created_node_hash: data.created_node_hash(),
+            created_height: data.created_height(),
+            destroyed_by_payload_id: data.destroyed_payload_id(),
+            destroyed_justify: data.destroyed_justify().clone(),
+            destroyed_node_hash: data.destroyed_node_hash(),
+            destroyed_height: data.destroyed_height(),
+            substate: data.into_substate(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct PledgeRecord {
+    shard_id: ShardId,
+    created_height: NodeHeight,
+    pledged_to_payload_id: PayloadId,
+    is_active: bool,
+    completed_by_tree_node_hash: Option<TreeNodeHash>,
+    abandoned_by_tree_node_hash: Option<TreeNodeHash>,
+}
+
+impl From<PledgeRecord> for ObjectPledgeInfo {
+    fn from(record: PledgeRecord) -> Self {
+        Self {
+            shard_id: record.shard_id,
+            pledged_to_payload_id: record.pledged_to_payload_id,
+            completed_by_tree_node_hash: record.completed_by_tree_node_hash,
+            abandoned_by_tree_node_hash: record.abandoned_by_tree_node_hash,
+            is_active: record.is_active,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct EventRecord {
+    payload_id: PayloadId,
+    event: Event,
+}
+
+// ------------------------------------ Keys ----------------------------------- //
+
+fn key(parts: &[&[u8]]) -> Vec<u8> {
+    parts.concat()
+}
+
+fn height_key(height: NodeHeight) -> [u8; HEIGHT_LEN] {
+    height.as_u64().to_be_bytes()
+}
+
+fn topic_prefix(topic: &str) -> Vec<u8> {
+    // Length-prefixed so that a topic is never a prefix of another topic's keys
+    key(&[&(topic.len() as u32).to_be_bytes(), topic.as_bytes()])
+}
+
+fn seq_from_key_suffix(key: &[u8]) -> Result<[u8; 8], StorageError> {
+    key.len()
+        .checked_sub(8)
+        .and_then(|start| key[start..].try_into().ok())
+        .ok_or(StorageError::DecodingError)
+}
+
+fn to_timestamp(secs: i64) -> Result<NaiveDateTime, StorageError> {
+    NaiveDateTime::from_timestamp_opt(secs, 0).ok_or(StorageError::DecodingError)
+}
+
+fn serialize<T: Serialize>(value: &T) -> Result<Vec<u8>, StorageError> {
+    serde_json::to_vec(value).map_err(|_| StorageError::EncodingError)
+}
+
+fn deserialize<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, StorageError> {
+    serde_json::from_slice(bytes).map_err(|_| StorageError::DecodingError)
+}
+
+pub struct LmdbShardStoreReadTransaction<'a> {
+    store: &'a LmdbShardStore,
+    tx: LmdbTx<'a>,
+}
+
+impl<'a> LmdbShardStoreReadTransaction<'a> {
+    fn new(store: &'a LmdbShardStore, tx: LmdbTx<'a>) -> Self {
+        Self { store, tx }
+    }
+
+    fn db(&self, name: &'static str) -> DatabaseRef {
+        self.store
+            .databases
+            .get(name)
+            .cloned()
+            .unwrap_or_else(|| panic!("LMDB database {} is not registered", name))
+    }
+
+    fn get_raw(&self, db_name: &'static str, key: &[u8]) -> Result<Option<Vec<u8>>, StorageError> {
+        let db = self.db(db_name);
+        let access = self.tx.access();
+        let value = access.get::<_, [u8]>(&db, key).to_opt()?;
+        Ok(value.map(|v| v.to_vec()))
+    }
+
+    fn get<T: DeserializeOwned>(&self, db_name: &'static str, key: &[u8]) -> Result<Option<T>, StorageError> {
+        self.get_raw(db_name, key)?.map(|v| deserialize(&v)).transpose()
+    }
+
+    fn exists(&self, db_name: &'static str, key: &[u8]) -> Result<bool, StorageError> {
+        Ok(self.get_raw(db_name, key)?.is_some())
+    }
+
+    /// Returns all entries whose key starts with `prefix`, in key order. An empty prefix returns every entry.
+    fn get_raw_with_prefix(
+        &self,
+        db_name: &'static str,
+        prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, StorageError> {
+        self.get_raw_in_range(db_name, prefix, |key| key.starts_with(prefix))
+    }
+
+    /// Returns the entries from the first key that is greater than or equal to `start` while `predicate` holds.
+    fn get_raw_in_range<F: FnMut(&[u8]) -> bool>(
+        &self,
+        db_name: &'static str,
+        start: &[u8],
+        mut predicate: F,
+    ) -> Result<Vec<(Vec<u8>, Vec<u8>)>, StorageError> {
+        let db = self.db(db_name);
+        let access = self.tx.access();
+        let mut cursor = self.tx.cursor(&*db)?;
+        let mut entry = if start.is_empty() {
+            cursor.first::<[u8], [u8]>(&access).to_opt()?
+        } else {
+            cursor.seek_range_k::<[u8], [u8]>(&access, start).to_opt()?
+        };
+
+        let mut entries = Vec::new();
+        while let Some((key, value)) = entry {
+            if !predicate(key) {
+                break;
+            }
+            entries.push((key.to_vec(), value.to_vec()));
+            entry = cursor.next::<[u8], [u8]>(&access).to_opt()?;
+        }
+        Ok(entries)
+    }
+
+    fn get_with_prefix<T: DeserializeOwned>(
+        &self,
+        db_name: &'static str,
+        prefix: &[u8],
+    ) -> Result<Vec<(Vec<u8>, T)>, StorageError> {
+        self.get_raw_with_prefix(db_name, prefix)?
+            .into_iter()
+            .map(|(k, v)| Ok((k, deserialize(&v)?)))
+            .collect()
+    }
+
+    fn keys_with_prefix(&self, db_name: &'static str, prefix: &[u8]) -> Result<Vec<Vec<u8>>, StorageError> {
+        Ok(self
+            .get_raw_with_prefix(db_name, prefix)?
+            .into_iter()
+            .map(|(k, _)| k)
+            .collect())
+    }
+
+    fn get_substate(&self, shard: &ShardId) -> Result<Option<SubstateRecord>, StorageError> {
+        self.get(SUBSTATES_DB, shard.as_bytes())
+    }
+
+    /// Returns the pledges for the shard with their keys
+    fn get_pledges_for_shard(&self, shard: &ShardId) -> Result<Vec<(Vec<u8>, PledgeRecord)>, StorageError> {
+        self.get_with_prefix(SHARD_PLEDGES_DB, shard.as_bytes())
+    }
+
+    fn get_node_hashes_for_payload(&self, payload_id: &[u8]) -> Result<Vec<Vec<u8>>, StorageError> {
+        Ok(self
+            .keys_with_prefix(NODES_BY_PAYLOAD_DB, payload_id)?
+            .into_iter()
+            .map(|k| k[HASH_LEN..].to_vec())
+            .collect())
+    }
+
+    fn get_events_by_index(&self, index_db: &'static str, prefix: &[u8]) -> Result<Vec<Event>, StorageError> {
+        self.keys_with_prefix(index_db, prefix)?
+            .into_iter()
+            .map(|k| {
+                let seq = seq_from_key_suffix(&k)?;
+                let record: EventRecord = self.get(EVENTS_DB, &seq)?.ok_or(StorageError::DecodingError)?;
+                Ok(record.event)
+            })
+            .collect()
+    }
+
+    fn create_pledge(&self, shard: ShardId, pledged_to_payload: PayloadId) -> Result<ObjectPledge, StorageError> {
+        let current_state = match self.get_substate(&shard)? {
+            Some(SubstateRecord {
+                destroyed_by_payload_id: Some(deleted_by),
+                ..
+            }) => SubstateState::Down { deleted_by },
+            Some(substate) => SubstateState::Up {
+                address: substate.address,
+                created_by: substate.created_by_payload_id,
+                data: substate.substate,
+            },
+            None => SubstateState::DoesNotExist,
+        };
+
+        Ok(ObjectPledge {
+            shard_id: shard,
+            pledged_to_payload,
+            current_state,
+        })
+    }
+}
+
+impl ShardStoreReadTransaction<PublicKey, TariDanPayload> for LmdbShardStoreReadTransaction<'_> {
+    fn get_high_qc_for(&mut self, payload_id: PayloadId, shard_id: ShardId) -> Result<QuorumCertificate, StorageError> {
+        let prefix = key(&[payload_id.as_bytes(), shard_id.as_bytes()]);
+        let highest = self.get_with_prefix::<HighQcRecord>(HIGH_QCS_DB, &prefix)?.pop();
+        highest
+            .map(|(_, record)| record.qc)
+            .ok_or_else(|| StorageError::NotFound {
+                item: "high_qc".to_string(),
+                key: shard_id.to_string(),
+            })
+    }
+
+    fn get_high_qcs(&mut self, payload_id: PayloadId) -> Result<Vec<QuorumCertificate>, StorageError> {
+        let mut qcs = self
+            .get_with_prefix::<HighQcRecord>(HIGH_QCS_DB, payload_id.as_bytes())?
+            .into_iter()
+            .map(|(_, record)| record.qc)
+            .collect::<Vec<_>>();
+        qcs.sort_by(|a, b| b.node_height().as_u64().cmp(&a.node_height().as_u64()));
+        Ok(qcs)
+    }
+
+    fn get_leaf_node(&mut self, payload_id: &PayloadId, shard: &ShardId) -> Result<LeafNode, StorageError> {
+        let leaf_node: Option<LeafNodeRecord> =
+            self.get(LEAF_NODES_DB, &key(&[payload_id.as_bytes(), shard.as_bytes()]))?;
+        match leaf_node {
+            Some(leaf_node) => Ok(LeafNode::new(
+                leaf_node.tree_node_hash,
+                leaf_node.node_height,
+                leaf_node.payload_height,
+            )),
+            None => Err(StorageError::NotFound {
+                item: "leaf_node".to_string(),
+                key: format!("shard_id={},payload_id={}", shard, payload_id),
+            }),
+        }
+    }
+
+    fn get_current_leaders_states(&mut self, payload_id: &PayloadId) -> Result<Vec<CurrentLeaderStates>, StorageError> {
+        self.get_with_prefix::<CurrentLeaderStateRecord>(CURRENT_LEADER_STATES_DB, payload_id.as_bytes())?
+            .into_iter()
+            .map(|(_, state)| {
+                Ok(CurrentLeaderStates {
+                    payload_id: payload_id.as_bytes().to_vec(),
+                    shard_id: state.shard_id.as_bytes().to_vec(),
+                    leader_round: i64::from(state.leader_round),
+                    leader: state.leader.as_bytes().to_vec(),
+                    timestamp: to_timestamp(state.timestamp)?,
+                })
+            })
+            .collect()
+    }
+
+    fn get_payload(&mut self, id: &PayloadId) -> Result<TariDanPayload, StorageError> {
+        let record: PayloadRecord = self
+            .get(PAYLOADS_DB, id.as_bytes())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "payload".to_string(),
+                key: id.to_string(),
+            })?;
+
+        let mut payload = record.payload;
+        if let Some(result) = record.result {
+            payload.set_result(result.finalize_result);
+        }
+        Ok(payload)
+    }
+
+    fn get_node(&mut self, hash: &TreeNodeHash) -> Result<HotStuffTreeNode<PublicKey, TariDanPayload>, StorageError> {
+        let record: NodeRecord = self
+            .get(NODES_DB, hash.as_bytes())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "node".to_string(),
+                key: hash.to_hex(),
+            })?;
+        Ok(record.node)
+    }
+
+    fn get_locked_node_hash_and_height(
+        &mut self,
+        payload_id: PayloadId,
+        shard: ShardId,
+    ) -> Result<(TreeNodeHash, NodeHeight), StorageError> {
+        let locked: Option<LockedNodeRecord> =
+            self.get(LOCKED_NODES_DB, &key(&[payload_id.as_bytes(), shard.as_bytes()]))?;
+        Ok(locked
+            .map(|l| (l.tree_node_hash, l.node_height))
+            .unwrap_or_else(|| (TreeNodeHash::zero(), NodeHeight(0))))
+    }
+
+    fn get_last_executed_height(&mut self, shard: ShardId, payload_id: PayloadId) -> Result<NodeHeight, StorageError> {
+        let height: Option<NodeHeight> = self.get(
+            LAST_EXECUTED_HEIGHTS_DB,
+            &key(&[payload_id.as_bytes(), shard.as_bytes()]),
+        )?;
+        Ok(height.unwrap_or(NodeHeight(0)))
+    }
+
+    fn get_state_inventory(&mut self) -> Result<Vec<ShardId>, StorageError> {
+        self.keys_with_prefix(SUBSTATES_DB, &[])?
+            .iter()
+            .map(|k| ShardId::from_bytes(k).map_err(|_| StorageError::DecodingError))
+            .collect()
+    }
+
+    fn get_substate_states(&mut self, shard_ids: &[ShardId]) -> Result<Vec<SubstateShardData>, StorageError> {
+        let mut substates = Vec::with_capacity(shard_ids.len());
+        for shard_id in shard_ids {
+            if let Some(substate) = self.get_substate(shard_id)? {
+                substates.push(substate.into());
+            }
+        }
+        Ok(substates)
+    }
+
+    fn get_substate_states_by_range(
+        &mut self,
+        start_shard_id: ShardId,
+        end_shard_id: ShardId,
+        excluded_shards: &[ShardId],
+    ) -> Result<Vec<SubstateShardData>, StorageError> {
+        self.get_raw_in_range(SUBSTATES_DB, start_shard_id.as_bytes(), |k| {
+            k <= end_shard_id.as_bytes()
+        })?
+        .into_iter()
+        .filter(|(k, _)| excluded_shards.iter().all(|s| s.as_bytes() != k.as_slice()))
+        .map(|(_, v)| deserialize::<SubstateRecord>(&v).map(Into::into))
+        .collect()
+    }
+
+    fn get_substate_states_chunk(
+        &mut self,
+        start_shard_id: ShardId,
+        end_shard_id: ShardId,
+        limit: usize,
+    ) -> Result<Vec<SubstateShardData>, StorageError> {
+        let mut remaining = limit;
+        self.get_raw_in_range(SUBSTATES_DB, start_shard_id.as_bytes(), |k| {
+            if remaining == 0 || k > end_shard_id.as_bytes() {
+                return false;
+            }
+            remaining -= 1;
+            true
+        })?
+        .into_iter()
+        .map(|(_, v)| deserialize::<SubstateRecord>(&v).map(Into::into))
+        .collect()
+    }
+
+    fn get_last_voted_height(
+        &mut self,
+        shard: ShardId,
+        payload_id: PayloadId,
+    ) -> Result<(NodeHeight, u32), StorageError> {
+        let last_vote: Option<LastVotedHeightRecord> =
+            self.get(LAST_VOTED_HEIGHTS_DB, &key(&[payload_id.as_bytes(), shard.as_bytes()]))?;
+        Ok(last_vote
+            .map(|v| (v.node_height, v.leader_round))
+            .unwrap_or((NodeHeight(0), 0)))
+    }
+
+    fn get_leader_proposals(
+        &mut self,
+        payload: PayloadId,
+        payload_height: NodeHeight,
+        shards: &[ShardId],
+    ) -> Result<Vec<HotStuffTreeNode<PublicKey, TariDanPayload>>, StorageError> {
+        let prefix = key(&[payload.as_bytes(), &height_key(payload_height)]);
+        let shard_start = prefix.len();
+        self.get_raw_with_prefix(LEADER_PROPOSALS_DB, &prefix)?
+            .into_iter()
+            .filter(|(k, _)| {
+                shards
+                    .iter()
+                    .any(|s| s.as_bytes() == &k[shard_start..shard_start + HASH_LEN])
+            })
+            .map(|(_, v)| deserialize(&v))
+            .collect()
+    }
+
+    fn get_last_payload_height_for_leader_proposal(
+        &mut self,
+        payload: PayloadId,
+        shard: ShardId,
+    ) -> Result<NodeHeight, StorageError> {
+        let shard_start = HASH_LEN + HEIGHT_LEN;
+        let height = self
+            .keys_with_prefix(LEADER_PROPOSALS_DB, payload.as_bytes())?
+            .iter()
+            .filter(|k| &k[shard_start..shard_start + HASH_LEN] == shard.as_bytes())
+            .filter_map(|k| k[HASH_LEN..shard_start].try_into().ok().map(u64::from_be_bytes))
+            .max()
+            .unwrap_or(0);
+        Ok(NodeHeight(height))
+    }
+
+    fn has_vote_for(&mut self, from: &PublicKey, node_hash: TreeNodeHash) -> Result<bool, StorageError> {
+        self.exists(RECEIVED_VOTES_DB, &key(&[node_hash.as_bytes(), from.as_bytes()]))
+    }
+
+    fn get_received_votes_for(&mut self, node_hash: TreeNodeHash) -> Result<Vec<VoteMessage>, StorageError> {
+        Ok(self
+            .get_with_prefix(RECEIVED_VOTES_DB, node_hash.as_bytes())?
+            .into_iter()
+            .map(|(_, v)| v)
+            .collect())
+    }
+
+    fn get_recent_transactions(&mut self) -> Result<Vec<RecentTransaction>, StorageError> {
+        let mut payloads = self.get_with_prefix::<PayloadRecord>(PAYLOADS_DB, &[])?;
+        payloads.sort_by(|(_, a), (_, b)| b.timestamp.cmp(&a.timestamp));
+        payloads
+            .into_iter()
+            .map(|(payload_id, record)| {
+                let transaction = record.payload.transaction();
+                Ok(RecentTransaction {
+                    payload_id,
+                    timestamp: to_timestamp(record.timestamp)?,
+                    meta: serde_json::to_string(transaction.meta()).map_err(|_| StorageError::EncodingError)?,
+                    instructions: serde_json::to_string(transaction.instructions())
+                        .map_err(|_| StorageError::EncodingError)?,
+                })
+            })
+            .collect()
+    }
+
+    fn get_transaction(&mut self, payload_id: Vec<u8>) -> Result<Vec<SQLTransaction>, StorageError> {
+        let mut nodes = Vec::new();
+        for node_hash in self.get_node_hashes_for_payload(&payload_id)? {
+            let record: NodeRecord = self.get(NODES_DB, &node_hash)?.ok_or(StorageError::DecodingError)?;
+            nodes.push(record);
+        }
+        nodes.sort_by(|a, b| a.node.shard().cmp(&b.node.shard()));
+
+        nodes
+            .into_iter()
+            .map(|NodeRecord { node, timestamp }| {
+                let total_votes = self.keys_with_prefix(RECEIVED_VOTES_DB, node.hash().as_bytes())?.len();
+                let proposal_prefix = key(&[&payload_id, &height_key(node.payload_height())]);
+                let total_leader_proposals = self
+                    .keys_with_prefix(LEADER_PROPOSALS_DB, &proposal_prefix)?
+                    .iter()
+                    .filter(|k| k.ends_with(node.hash().as_bytes()))
+                    .count();
+
+                Ok(SQLTransaction {
+                    node_hash: node.hash().as_bytes().to_vec(),
+                    parent_node_hash: node.parent().as_bytes().to_vec(),
+                    shard: node.shard().as_bytes().to_vec(),
+                    height: node.height().as_u64() as i64,
+                    payload_height: node.payload_height().as_u64() as i64,
+                    total_votes: total_votes as i64,
+                    total_leader_proposals: total_leader_proposals as i64,
+                    timestamp: to_timestamp(timestamp)?,
+                    justify: serde_json::to_string_pretty(node.justify()).map_err(|_| StorageError::EncodingError)?,
+                    proposed_by: node.proposed_by().as_bytes().to_vec(),
+                    leader_round: i64::from(node.leader_round()),
+                })
+            })
+            .collect()
+    }
+
+    fn get_substates_for_payload(
+        &mut self,
+        payload_id: Vec<u8>,
+        shard_id: Vec<u8>,
+    ) -> Result<Vec<SQLSubstate>, StorageError> {
+        let substate: Option<SubstateRecord> = self.get(SUBSTATES_DB, &shard_id)?;
+        let substate = match substate {
+            Some(s)
+                if s.created_by_payload_id.as_bytes() == payload_id.as_slice() ||
+                    s.destroyed_by_payload_id
+                        .map(|p| p.as_bytes() == payload_id.as_slice())
+                        .unwrap_or(false) =>
+            {
+                s
+            },
+            _ => return Ok(vec![]),
+        };
+
+        Ok(vec![SQLSubstate {
+            shard_id,
+            address: substate.address.to_string(),
+            version: i64::from(substate.version),
+            data: serde_json::to_string_pretty(&substate.substate).map_err(|_| StorageError::EncodingError)?,
+            created_justify: serde_json::to_string_pretty(&substate.created_justify)
+                .map_err(|_| StorageError::EncodingError)?,
+            destroyed_justify: substate
+                .destroyed_justify
+                .as_ref()
+                .map(serde_json::to_string_pretty)
+                .transpose()
+                .map_err(|_| StorageError::EncodingError)?,
+        }])
+    }
+
+    fn get_payload_result(&mut self, payload_id: &PayloadId) -> Result<PayloadResult, StorageError> {
+        let record: PayloadRecord =
+            self.get(PAYLOADS_DB, payload_id.as_bytes())?
+                .ok_or_else(|| StorageError::NotFound {
+                    item: "payload".to_string(),
+                    key: payload_id.to_string(),
+                })?;
+
+        record.result.ok_or_else(|| StorageError::NotFound {
+            item: "payload result".to_string(),
+            key: payload_id.to_string(),
+        })
+    }
+
+    fn get_resolved_pledges_for_payload(&mut self, payload: PayloadId) -> Result<Vec<ObjectPledgeInfo>, StorageError> {
+        let mut pledges = Vec::new();
+        for index_key in self.keys_with_prefix(PLEDGES_BY_PAYLOAD_DB, payload.as_bytes())? {
+            let pledge: PledgeRecord = self
+                .get(SHARD_PLEDGES_DB, &index_key[HASH_LEN..])?
+                .ok_or(StorageError::DecodingError)?;
+            let is_resolved =
+                pledge.completed_by_tree_node_hash.is_some() || pledge.abandoned_by_tree_node_hash.is_some();
+            if is_resolved && !pledge.is_active {
+                pledges.push(pledge);
+            }
+        }
+        pledges.sort_by(|a, b| b.created_height.as_u64().cmp(&a.created_height.as_u64()));
+
+        Ok(pledges.into_iter().map(Into::into).collect())
+    }
+
+    fn get_active_pledge(&mut self, shard: ShardId) -> Result<Option<ObjectPledgeInfo>, StorageError> {
+        let pledge = self
+            .get_pledges_for_shard(&shard)?
+            .into_iter()
+            .map(|(_, p)| p)
+            .filter(|p| p.is_active)
+            .max_by_key(|p| p.created_height.as_u64());
+        Ok(pledge.map(Into::into))
+    }
+
+    fn get_events_for_payload(&mut self, payload_id: PayloadId) -> Result<Vec<Event>, StorageError> {
+        self.get_events_by_index(EVENTS_BY_PAYLOAD_DB, payload_id.as_bytes())
+    }
+
+    fn get_events_by_topic(&mut self, topic: &str) -> Result<Vec<Event>, StorageError> {
+        self.get_events_by_index(EVENTS_BY_TOPIC_DB, &topic_prefix(topic))
+    }
+
+    fn get_prunable_payloads(
+        &mut self,
+        before_epoch: Epoch,
+        decide_height: NodeHeight,
+        limit: usize,
+    ) -> Result<Vec<PayloadId>, StorageError> {
+        let mut node_hashes_by_payload = Vec::<(Vec<u8>, Vec<Vec<u8>>)>::new();
+        for k in self.keys_with_prefix(NODES_BY_PAYLOAD_DB, &[])? {
+            let (payload_id, node_hash) = k.split_at(HASH_LEN);
+            match node_hashes_by_payload.last_mut() {
+                Some((last, hashes)) if last.as_slice() == payload_id => hashes.push(node_hash.to_vec()),
+                _ => node_hashes_by_payload.push((payload_id.to_vec(), vec![node_hash.to_vec()])),
+            }
+        }
+
+        let mut prunable = Vec::new();
+        for (payload_id, node_hashes) in node_hashes_by_payload {
+            if prunable.len() >= limit {
+                break;
+            }
+            let payload_id = PayloadId::try_from(payload_id)?;
+
+            let mut nodes = Vec::with_capacity(node_hashes.len());
+            for node_hash in node_hashes {
+                let record: NodeRecord = self.get(NODES_DB, &node_hash)?.ok_or(StorageError::DecodingError)?;
+                nodes.push(record.node);
+            }
+            if nodes.iter().any(|n| n.epoch().as_u64() >= before_epoch.as_u64()) {
+                continue;
+            }
+
+            let mut shards = nodes.iter().map(|n| n.shard()).collect::<Vec<_>>();
+            shards.sort();
+            shards.dedup();
+            let mut is_committed = true;
+            for shard in shards {
+                let last_executed = self.get_last_executed_height(shard, payload_id)?;
+                let is_decided = nodes.iter().any(|n| {
+                    n.shard() == shard &&
+                        n.payload_height() == decide_height &&
+                        last_executed.as_u64() >= n.height().as_u64()
+                });
+                if !is_decided {
+                    is_committed = false;
+                    break;
+                }
+            }
+            if is_committed {
+                prunable.push(payload_id);
+            }
+        }
+
+        Ok(prunable)
+    }
+
+    fn get_state_root(&mut self) -> Result<FixedHash, StorageError> {
+        StateTree::new(self).root()
+    }
+
+    fn get_state_proof(&mut self, shard: ShardId) -> Result<StateTreeProof, StorageError> {
+        StateTree::new(self).get_proof(&shard)
+    }
+
+    fn get_leader_failures(
+        &mut self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<LeaderFailure<PublicKey>>, StorageError> {
+        let end_epoch = end_epoch.as_u64().to_be_bytes();
+        self.get_raw_in_range(LEADER_FAILURES_DB, &start_epoch.as_u64().to_be_bytes(), |k| {
+            k[..HEIGHT_LEN] <= end_epoch[..]
+        })?
+        .into_iter()
+        .map(|(_, v)| deserialize(&v))
+        .collect()
+    }
+
+    fn get_slashing_evidence(
+        &mut self,
+        offender: Option<&PublicKey>,
+    ) -> Result<Vec<SlashingEvidence<PublicKey, TariDanPayload>>, StorageError> {
+        let prefix = offender.map(|offender| offender.as_bytes()).unwrap_or_default();
+        Ok(self
+            .get_with_prefix(SLASHING_EVIDENCE_DB, prefix)?
+            .into_iter()
+            .map(|(_, evidence)| evidence)
+            .collect())
+    }
+
+    fn get_mempool_transactions(&mut self) -> Result<Vec<MempoolTransaction>, StorageError> {
+        Ok(self
+            .get_with_prefix(MEMPOOL_TRANSACTIONS_DB, &[])?
+            .into_iter()
+            .map(|(_, transaction)| transaction)
+            .collect())
+    }
+}
+
+impl StateTreeStoreReader for LmdbShardStoreReadTransaction<'_> {
+    fn get_state_tree_root(&mut self) -> Result<FixedHash, StorageError> {
+        match self.get_raw(METADATA_DB, STATE_TREE_ROOT_KEY)? {
+            Some(root) => Ok(FixedHash::try_from(root.as_slice())?),
+            None => Ok(FixedHash::zero()),
+        }
+    }
+
+    fn get_state_tree_node(&mut self, hash: &FixedHash) -> Result<StateTreeNode, StorageError> {
+        self.get(STATE_TREE_NODES_DB, hash.as_slice())?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "state tree node".to_string(),
+                key: hash.to_string(),
+            })
+    }
+}
+
+pub struct LmdbShardStoreWriteTransaction<'a> {
+    /// None indicates if the transaction has been explicitly committed/rolled back
+    transaction: Option<LmdbShardStoreReadTransaction<'a>>,
+}
+
+impl<'a> LmdbShardStoreWriteTransaction<'a> {
+    fn new(transaction: LmdbShardStoreReadTransaction<'a>) -> Self {
+        Self {
+            transaction: Some(transaction),
+        }
+    }
+
+    fn take_write_tx(&mut self) -> WriteTransaction<'a> {
+        match self.transaction.take().map(|t| t.tx) {
+            Some(LmdbTx::Write(tx)) => tx,
+            _ => unreachable!("LmdbShardStoreWriteTransaction always holds a write transaction"),
+        }
+    }
+
+    fn put_raw(&mut self, db_name: &'static str, key: &[u8], value: &[u8]) -> Result<(), StorageError> {
+        let db = self.db(db_name);
+        match &self.tx {
+            LmdbTx::Write(tx) => {
+                let mut access = tx.access();
+                access.put(&db, key, value, put::Flags::empty())?;
+                Ok(())
+            },
+            LmdbTx::Read(_) => unreachable!("LmdbShardStoreWriteTransaction always holds a write transaction"),
+        }
+    }
+
+    fn put<T: Serialize>(&mut self, db_name: &'static str, key: &[u8], value: &T) -> Result<(), StorageError> {
+        let value = serialize(value)?;
+        self.put_raw(db_name, key, &value)
+    }
+
+    /// Deletes the key, returning true if it existed
+    fn delete(&mut self, db_name: &'static str, key: &[u8]) -> Result<bool, StorageError> {
+        let db = self.db(db_name);
+        match &self.tx {
+            LmdbTx::Write(tx) => {
+                let mut access = tx.access();
+                Ok(access.del_key(&db, key).to_opt()?.is_some())
+            },
+            LmdbTx::Read(_) => unreachable!("LmdbShardStoreWriteTransaction always holds a write transaction"),
+        }
+    }
+
+    fn delete_with_prefix(&mut self, db_name: &'static str, prefix: &[u8]) -> Result<u64, StorageError> {
+        let keys = self.keys_with_prefix(db_name, prefix)?;
+        for k in &keys {
+            self.delete(db_name, k)?;
+        }
+        Ok(keys.len() as u64)
+    }
+
+    fn next_seq(&mut self) -> Result<[u8; 8], StorageError> {
+        let seq = self
+            .get_raw(METADATA_DB, NEXT_SEQ_KEY)?
+            .and_then(|v| v.as_slice().try_into().ok())
+            .map(u64::from_be_bytes)
+            .unwrap_or(0);
+        self.put_raw(METADATA_DB, NEXT_SEQ_KEY, &(seq + 1).to_be_bytes())?;
+        Ok(seq.to_be_bytes())
+    }
+
+    fn insert_substate(&mut self, substate: SubstateRecord) -> Result<(), StorageError> {
+        if self.exists(SUBSTATES_DB, substate.shard_id.as_bytes())? {
+            return Err(StorageError::QueryError {
+                reason: format!(
+                    "Insert substate: substate for shard {} already exists",
+                    substate.shard_id
+                ),
+            });
+        }
+        self.put_substate(substate)
+    }
+
+    /// Writes the substate and sets its leaf in the state tree
+    fn put_substate(&mut self, substate: SubstateRecord) -> Result<(), StorageError> {
+        self.put(SUBSTATES_DB, substate.shard_id.as_bytes(), &substate)?;
+        let substate = SubstateShardData::from(substate);
+        StateTree::new(self).put(substate.shard_id(), substate.state_tree_value_hash())?;
+        Ok(())
+    }
+
+    /// Marks the active pledges for the shard and payload as resolved, returning the number of pledges updated
+    fn resolve_pledges(
+        &mut self,
+        shard: ShardId,
+        payload_id: PayloadId,
+        node_hash: &TreeNodeHash,
+        is_completed: bool,
+    ) -> Result<usize, StorageError> {
+        let pledges = self
+            .get_pledges_for_shard(&shard)?
+            .into_iter()
+            .filter(|(_, p)| p.is_active && p.pledged_to_payload_id == payload_id)
+            .collect::<Vec<_>>();
+        let num_resolved = pledges.len();
+        for (k, mut pledge) in pledges {
+            pledge.is_active = false;
+            if is_completed {
+                pledge.completed_by_tree_node_hash = Some(*node_hash);
+            } else {
+                pledge.abandoned_by_tree_node_hash = Some(*node_hash);
+            }
+            self.put(SHARD_PLEDGES_DB, &k, &pledge)?;
+        }
+        Ok(num_resolved)
+    }
+}
+
+impl ShardStoreWriteTransaction<PublicKey, TariDanPayload> for LmdbShardStoreWriteTransaction<'_> {
+    fn commit(mut self) -> Result<(), StorageError> {
+        self.take_write_tx().commit()?;
+        Ok(())
+    }
+
+    fn rollback(mut self) -> Result<(), StorageError> {
+        // Dropping an LMDB write transaction aborts it
+        drop(self.take_write_tx());
+        Ok(())
+    }
+
+    fn insert_high_qc(
+        &mut self,
+        identity: PublicKey,
+        shard: ShardId,
+        qc: QuorumCertificate,
+    ) -> Result<(), StorageError> {
+        let k = key(&[
+            qc.payload_id().as_bytes(),
+            shard.as_bytes(),
+            &height_key(qc.node_height()),
+        ]);
+        if self.exists(HIGH_QCS_DB, &k)? {
+            return Err(StorageError::QueryError {
+                reason: format!(
+                    "update high QC error: QC for shard {}, payload {}, height {} exists",
+                    shard,
+                    qc.payload_id(),
+                    qc.node_height()
+                ),
+            });
+        }
+        self.put(HIGH_QCS_DB, &k, &HighQcRecord { qc, identity })
+    }
+
+    fn save_payload(&mut self, payload: TariDanPayload) -> Result<(), StorageError> {
+        let payload_id = payload.to_id();
+        // It can happen that we get this payload from two shards that we are responsible
+        if self.exists(PAYLOADS_DB, payload_id.as_bytes())? {
+            debug!(target: LOG_TARGET, "Payload already exists");
+            return Ok(());
+        }
+
+        self.put(PAYLOADS_DB, payload_id.as_bytes(), &PayloadRecord {
+            payload,
+            result: None,
+            timestamp: Utc::now().timestamp(),
+        })
+    }
+
+    fn save_current_leader_state(
+        &mut self,
+        payload: PayloadId,
+        shard_id: ShardId,
+        leader_round: u32,
+        leader: PublicKey,
+    ) -> Result<(), StorageError> {
+        self.put(
+            CURRENT_LEADER_STATES_DB,
+            &key(&[payload.as_bytes(), shard_id.as_bytes()]),
+            &CurrentLeaderStateRecord {
+                shard_id,
+                leader_round,
+                leader,
+                timestamp: Utc::now().timestamp(),
+            },
+        )
+    }
+
+    fn set_leaf_node(
+        &mut self,
+        payload_id: PayloadId,
+        shard_id: ShardId,
+        node: TreeNodeHash,
+        payload_height: NodeHeight,
+        height: NodeHeight,
+    ) -> Result<(), StorageError> {
+        self.put(
+            LEAF_NODES_DB,
+            &key(&[payload_id.as_bytes(), shard_id.as_bytes()]),
+            &LeafNodeRecord {
+                tree_node_hash: node,
+                node_height: height,
+                payload_height,
+            },
+        )
+    }
+
+    fn save_node(&mut self, node: HotStuffTreeNode<PublicKey, TariDanPayload>) -> Result<(), StorageError> {
+        let node_hash = *node.hash();
+        if self.exists(NODES_DB, node_hash.as_bytes())? {
+            debug!(target: LOG_TARGET, "Node already exists");
+            return Ok(());
+        }
+
+        self.put_raw(
+            NODES_BY_PAYLOAD_DB,
+            &key(&[node.payload_id().as_bytes(), node_hash.as_bytes()]),
+            &[],
+        )?;
+        self.put(NODES_DB, node_hash.as_bytes(), &NodeRecord {
+            node,
+            timestamp: Utc::now().timestamp(),
+        })
+    }
+
+    fn set_locked(
+        &mut self,
+        payload_id: PayloadId,
+        shard: ShardId,
+        node_hash: TreeNodeHash,
+        node_height: NodeHeight,
+    ) -> Result<(), StorageError> {
+        let (_, current_height) = self.get_locked_node_hash_and_height(payload_id, shard)?;
+        if node_height.as_u64() < current_height.as_u64() {
+            return Ok(());
+        }
+        self.put(
+            LOCKED_NODES_DB,
+            &key(&[payload_id.as_bytes(), shard.as_bytes()]),
+            &LockedNodeRecord {
+                tree_node_hash: node_hash,
+                node_height,
+            },
+        )
+    }
+
+    fn set_last_executed_height(
+        &mut self,
+        shard: ShardId,
+        payload_id: PayloadId,
+        height: NodeHeight,
+    ) -> Result<(), StorageError> {
+        let current_height = self.get_last_executed_height(shard, payload_id)?;
+        if height.as_u64() < current_height.as_u64() {
+            return Ok(());
+        }
+        self.put(
+            LAST_EXECUTED_HEIGHTS_DB,
+            &key(&[payload_id.as_bytes(), shard.as_bytes()]),
+            &height,
+        )
+    }
+
+    fn save_substate_changes(
+        &mut self,
+        node: HotStuffTreeNode<PublicKey, TariDanPayload>,
+        changes: &[SubstateState],
+    ) -> Result<(), StorageError> {
+        let shard = node.shard();
+        for st_change in changes {
+            match st_change {
+                SubstateState::DoesNotExist => (),
+                SubstateState::Up { address, data, .. } => {
+                    if let Some(s) = self.get_substate(&shard)? {
+                        if !s.is_destroyed() {
+                            return Err(StorageError::QueryError {
+                                reason: "Save substate changes error. Cannot create a substate that has not been \
+                                         destroyed"
+                                    .to_string(),
+                            });
+                        }
+                    }
+
+                    self.insert_substate(SubstateRecord {
+                        shard_id: shard,
+                        address: address.clone(),
+                        version: data.version(),
+                        substate: data.clone(),
+                        created_by_payload_id: node.payload_id(),
+                        created_justify: node.justify().clone(),
+                        created_node_hash: *node.hash(),
+                        created_height: node.height(),
+                        destroyed_by_payload_id: None,
+                        destroyed_justify: None,
+                        destroyed_node_hash: None,
+                        destroyed_height: None,
+                    })?;
+                },
+                SubstateState::Down { .. } => {
+                    let mut substate = self.get_substate(&shard)?.ok_or_else(|| StorageError::QueryError {
+                        reason: "Save substate changes error. Cannot destroy a substate that does not exist"
+                            .to_string(),
+                    })?;
+                    if substate.is_destroyed() {
+                        return Err(StorageError::QueryError {
+                            reason: "Save substate changes error. Cannot destroy a substate that has already been \
+                                     destroyed"
+                                .to_string(),
+                        });
+                    }
+
+                    substate.destroyed_by_payload_id = Some(node.payload_id());
+                    substate.destroyed_justify = Some(node.justify().clone());
+                    substate.destroyed_node_hash = Some(*node.hash());
+                    substate.destroyed_height = Some(node.height());
+                    self.put_substate(substate)?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+
+    fn insert_substates(&mut self, substate_data: SubstateShardData) -> Result<(), StorageError> {
+        self.insert_substate(substate_data.into())
+    }
+
+    fn set_last_voted_height(
+        &mut self,
+        shard: ShardId,
+        payload_id: PayloadId,
+        height: NodeHeight,
+        leader_round: u32,
+    ) -> Result<(), StorageError> {
+        let (current_height, current_round) = self.get_last_voted_height(shard, payload_id)?;
+        if (height.as_u64(), leader_round) < (current_height.as_u64(), current_round) {
+            return Ok(());
+        }
+        self.put(
+            LAST_VOTED_HEIGHTS_DB,
+            &key(&[payload_id.as_bytes(), shard.as_bytes()]),
+            &LastVotedHeightRecord {
+                node_height: height,
+                leader_round,
+            },
+        )
+    }
+
+    fn save_leader_proposals(
+        &mut self,
+        shard: ShardId,
+        payload: PayloadId,
+        payload_height: NodeHeight,
+        _leader_round: u32,
+        node: HotStuffTreeNode<PublicKey, TariDanPayload>,
+    ) -> Result<(), StorageError> {
+        let k = key(&[
+            payload.as_bytes(),
+            &height_key(payload_height),
+            shard.as_bytes(),
+            node.hash().as_bytes(),
+        ]);
+        if self.exists(LEADER_PROPOSALS_DB, &k)? {
+            warn!(target: LOG_TARGET, "Leader proposal already exists");
+            return Ok(());
+        }
+        self.put(LEADER_PROPOSALS_DB, &k, &node)
+    }
+
+    fn save_received_vote_for(
+        &mut self,
+        from: PublicKey,
+        node_hash: TreeNodeHash,
+        vote_message: VoteMessage,
+    ) -> Result<(), StorageError> {
+        self.put(
+            RECEIVED_VOTES_DB,
+            &key(&[node_hash.as_bytes(), from.as_bytes()]),
+            &vote_message,
+        )
+    }
+
+    fn update_payload_result(
+        &mut self,
+        requested_payload_id: &PayloadId,
+        result: PayloadResult,
+    ) -> Result<(), StorageError> {
+        let record: Option<PayloadRecord> = self.get(PAYLOADS_DB, requested_payload_id.as_bytes())?;
+        if let Some(mut record) = record {
+            record.result = Some(result);
+            self.put(PAYLOADS_DB, requested_payload_id.as_bytes(), &record)?;
+        }
+        Ok(())
+    }
+
+    fn save_events(&mut self, payload_id: PayloadId, events: &[Event]) -> Result<(), StorageError> {
+        for event in events {
+            let seq = self.next_seq()?;
+            self.put(EVENTS_DB, &seq, &EventRecord {
+                payload_id,
+                event: event.clone(),
+            })?;
+            self.put_raw(EVENTS_BY_PAYLOAD_DB, &key(&[payload_id.as_bytes(), &seq]), &[])?;
+            self.put_raw(EVENTS_BY_TOPIC_DB, &key(&[&topic_prefix(&event.topic), &seq]), &[])?;
+        }
+        Ok(())
+    }
+
+    // -------------------------------- Pledges -------------------------------- //
+
+    fn pledge_object(
+        &mut self,
+        shard: ShardId,
+        payload: PayloadId,
+        current_height: NodeHeight,
+    ) -> Result<ObjectPledge, StorageError> {
+        let existing_pledge = self
+            .get_pledges_for_shard(&shard)?
+            .into_iter()
+            .map(|(_, p)| p)
+            .filter(|p| p.is_active)
+            .max_by_key(|p| p.created_height.as_u64());
+
+        if let Some(pledge) = existing_pledge {
+            // Emit a warning in this case, consensus should handle this correctly (by rejecting)
+            if pledge.pledged_to_payload_id != payload {
+                warn!(
+                    target: LOG_TARGET,
+                    "[pledge_object]: Attempted to create a pledge for shard {}/payload{} that already exists for \
+                     another payload {}",
+                    shard,
+                    payload,
+                    pledge.pledged_to_payload_id
+                );
+            }
+
+            return self.create_pledge(shard, pledge.pledged_to_payload_id);
+        }
+
+        // otherwise save pledge
+        let seq = self.next_seq()?;
+        let pledge_key = key(&[shard.as_bytes(), &seq]);
+        self.put(SHARD_PLEDGES_DB, &pledge_key, &PledgeRecord {
+            shard_id: shard,
+            created_height: current_height,
+            pledged_to_payload_id: payload,
+            is_active: true,
+            completed_by_tree_node_hash: None,
+            abandoned_by_tree_node_hash: None,
+        })?;
+        self.put_raw(PLEDGES_BY_PAYLOAD_DB, &key(&[payload.as_bytes(), &pledge_key]), &[])?;
+
+        self.create_pledge(shard, payload)
+    }
+
+    fn complete_pledges(
+        &mut self,
+        shard: ShardId,
+        payload: PayloadId,
+        node_hash: &TreeNodeHash,
+    ) -> Result<(), StorageError> {
+        let num_completed = self.resolve_pledges(shard, payload, node_hash, true)?;
+        if num_completed == 0 {
+            return Err(StorageError::QueryError {
+                reason: format!(
+                    "Complete pledges: No pledges found to complete for shard {}, payload_id: {}, is_active: true",
+                    shard, payload
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    fn abandon_pledges(
+        &mut self,
+        shard: ShardId,
+        payload_id: PayloadId,
+        node_hash: &TreeNodeHash,
+    ) -> Result<(), StorageError> {
+        let num_abandoned = self.resolve_pledges(shard, payload_id, node_hash, false)?;
+        if num_abandoned == 0 {
+            return Err(StorageError::NotFound {
+                item: "Abandon pledges".to_string(),
+                key: format!("payload={}, shard={}", payload_id, shard),
+            });
+        }
+        Ok(())
+    }
+
+    fn save_burnt_utxo(
+        &mut self,
+        substate: &Substate,
+        commitment_address: SubstateAddress,
+        shard_id: ShardId,
+    ) -> Result<(), StorageError> {
+        let created_by_payload_id = PayloadId::new(vec![0; 32]);
+        self.insert_substate(SubstateRecord {
+            shard_id,
+            address: commitment_address,
+            version: substate.version(),
+            substate: substate.clone(),
+            created_by_payload_id,
+            created_justify: QuorumCertificate::genesis(Epoch(0), created_by_payload_id, ShardId::zero()),
+            created_node_hash: TreeNodeHash::zero(),
+            created_height: NodeHeight(0),
+            destroyed_by_payload_id: None,
+            destroyed_justify: None,
+            destroyed_node_hash: None,
+            destroyed_height: None,
+        })
+    }
+
+    fn prune_payload(&mut self, payload_id: PayloadId) -> Result<PrunedRecordCounts, StorageError> {
+        let mut counts = PrunedRecordCounts::default();
+
+        // The node at the last executed height of each shard carries the committing QC. It is kept along with the node
+        // that the QC certifies.
+        let node_hashes = self.get_node_hashes_for_payload(payload_id.as_bytes())?;
+        let mut kept_nodes = HashSet::new();
+        let mut committing_qc_keys = HashSet::new();
+        for node_hash in &node_hashes {
+            let record: Option<NodeRecord> = self.get(NODES_DB, node_hash)?;
+            let Some(NodeRecord { node, .. }) = record else {
+                continue;
+            };
+            let last_executed_height: Option<NodeHeight> = self.get(
+                LAST_EXECUTED_HEIGHTS_DB,
+                &key(&[payload_id.as_bytes(), node.shard().as_bytes()]),
+            )?;
+            if last_executed_height == Some(node.height()) {
+                kept_nodes.insert(node.hash().as_bytes().to_vec());
+                kept_nodes.insert(node.justify().node_hash().as_bytes().to_vec());
+                committing_qc_keys.insert(key(&[
+                    payload_id.as_bytes(),
+                    node.shard().as_bytes(),
+                    &height_key(node.justify().node_height()),
+                ]));
+            }
+        }
+
+        for node_hash in node_hashes {
+            counts.received_votes += self.delete_with_prefix(RECEIVED_VOTES_DB, &node_hash)?;
+            if kept_nodes.contains(&node_hash) {
+                continue;
+            }
+            if self.delete(NODES_DB, &node_hash)? {
+                counts.nodes += 1;
+            }
+            self.delete(NODES_BY_PAYLOAD_DB, &key(&[payload_id.as_bytes(), &node_hash]))?;
+        }
+
+        // A leader receives votes for the nodes it proposed, which it may not have saved as nodes. Keys are (payload
+        // id, payload height, shard, node hash).
+        let proposal_keys = self.keys_with_prefix(LEADER_PROPOSALS_DB, payload_id.as_bytes())?;
+        for k in &proposal_keys {
+            counts.received_votes += self.delete_with_prefix(RECEIVED_VOTES_DB, &k[k.len() - HASH_LEN..])?;
+            self.delete(LEADER_PROPOSALS_DB, k)?;
+        }
+        counts.leader_proposals = proposal_keys.len() as u64;
+
+        // Keep the committing QC for each shard. Keys are (payload id, shard, QC node height).
+        for k in self.keys_with_prefix(HIGH_QCS_DB, payload_id.as_bytes())? {
+            if !committing_qc_keys.contains(&k) {
+                self.delete(HIGH_QCS_DB, &k)?;
+                counts.high_qcs += 1;
+            }
+        }
+
+        for index_key in self.keys_with_prefix(PLEDGES_BY_PAYLOAD_DB, payload_id.as_bytes())? {
+            let pledge_key = &index_key[HASH_LEN..];
+            let pledge: Option<PledgeRecord> = self.get(SHARD_PLEDGES_DB, pledge_key)?;
+            match pledge {
+                Some(pledge) if pledge.is_active => continue,
+                Some(_) => {
+                    self.delete(SHARD_PLEDGES_DB, pledge_key)?;
+                    counts.pledges += 1;
+                },
+                None => {},
+            }
+            self.delete(PLEDGES_BY_PAYLOAD_DB, &index_key)?;
+        }
+
+        for db_name in [
+            LEAF_NODES_DB,
+            LOCKED_NODES_DB,
+            LAST_EXECUTED_HEIGHTS_DB,
+            LAST_VOTED_HEIGHTS_DB,
+            CURRENT_LEADER_STATES_DB,
+        ] {
+            counts.consensus_states += self.delete_with_prefix(db_name, payload_id.as_bytes())?;
+        }
+
+        Ok(counts)
+    }
+
+    fn save_leader_failure(&mut self, failure: LeaderFailure<PublicKey>) -> Result<(), StorageError> {
+        // Keys are ordered by epoch so that the failures for a range of epochs can be scanned
+        let k = key(&[
+            &failure.epoch.as_u64().to_be_bytes(),
+            failure.payload_id.as_bytes(),
+            failure.shard_id.as_bytes(),
+            &failure.leader_round.to_be_bytes(),
+        ]);
+        self.put(LEADER_FAILURES_DB, &k, &failure)
+    }
+
+    fn save_slashing_evidence(
+        &mut self,
+        evidence: SlashingEvidence<PublicKey, TariDanPayload>,
+    ) -> Result<(), StorageError> {
+        // Keys are prefixed by the offender so that the evidence against a validator node can be scanned
+        let k = key(&[evidence.offender().as_bytes(), evidence.hash().as_slice()]);
+        if self.exists(SLASHING_EVIDENCE_DB, &k)? {
+            return Ok(());
+        }
+        self.put(SLASHING_EVIDENCE_DB, &k, &evidence)
+    }
+
+    fn save_mempool_transaction(&mut self, transaction: MempoolTransaction) -> Result<(), StorageError> {
+        self.put(MEMPOOL_TRANSACTIONS_DB, transaction.hash().as_slice(), &transaction)
+    }
+
+    fn remove_mempool_transaction(&mut self, transaction_hash: &FixedHash) -> Result<bool, StorageError> {
+        self.delete(MEMPOOL_TRANSACTIONS_DB, transaction_hash.as_slice())
+    }
+}
+
+impl StateTreeStoreReader for LmdbShardStoreWriteTransaction<'_> {
+    fn get_state_tree_root(&mut self) -> Result<FixedHash, StorageError> {
+        self.transaction.as_mut().unwrap().get_state_tree_root()
+    }
+
+    fn get_state_tree_node(&mut self, hash: &FixedHash) -> Result<StateTreeNode, StorageError> {
+        self.transaction.as_mut().unwrap().get_state_tree_node(hash)
+    }
+}
+
+impl StateTreeStoreWriter for LmdbShardStoreWriteTransaction<'_> {
+    fn set_state_tree_root(&mut self, root: FixedHash) -> Result<(), StorageError> {
+        self.put_raw(METADATA_DB, STATE_TREE_ROOT_KEY, root.as_slice())
+    }
+
+    fn insert_state_tree_node(&mut self, hash: FixedHash, node: StateTreeNode) -> Result<(), StorageError> {
+        self.put(STATE_TREE_NODES_DB, hash.as_slice(), &node)
+    }
+
+    fn delete_state_tree_node(&mut self, hash: &FixedHash) -> Result<(), StorageError> {
+        self.delete(STATE_TREE_NODES_DB, hash.as_slice())?;
+        Ok(())
+    }
+}
+
+impl<'a> Deref for LmdbShardStoreWriteTransaction<'a> {
+    type Target = LmdbShardStoreReadTransaction<'a>;
+
+    fn deref(&self) -> &Self::Target {
+        self.transaction.as_ref().unwrap()
+    }
+}
+
+impl<'a> DerefMut for LmdbShardStoreWriteTransaction<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.transaction.as_mut().unwrap()
+    }
+}
+
+impl Drop for LmdbShardStoreWriteTransaction<'_> {
+    fn drop(&mut self) {
+        if self.transaction.is_some() {
+            warn!(
+                target: LOG_TARGET,
+                "Shard store write transaction was not committed/rolled back"
+            );
+        }
+    }
+}
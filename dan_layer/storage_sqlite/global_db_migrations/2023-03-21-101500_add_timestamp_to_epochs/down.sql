@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE epochs
+    DROP COLUMN timestamp;
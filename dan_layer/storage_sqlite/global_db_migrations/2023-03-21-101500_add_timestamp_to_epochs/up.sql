@@ -0,0 +1,2 @@
+ALTER TABLE epochs
+    ADD COLUMN timestamp BIGINT NOT NULL DEFAULT 0;
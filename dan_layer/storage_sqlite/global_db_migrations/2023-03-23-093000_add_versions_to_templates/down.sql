@@ -0,0 +1,11 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX templates_author_public_key_template_name_index;
+
+ALTER TABLE templates
+    DROP COLUMN predecessor_address;
+
+ALTER TABLE templates
+    DROP COLUMN version;
+
+ALTER TABLE templates
+    DROP COLUMN author_public_key;
@@ -0,0 +1,11 @@
+ALTER TABLE templates
+    ADD COLUMN author_public_key BLOB NOT NULL DEFAULT x'';
+
+ALTER TABLE templates
+    ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
+
+-- the address of the previous version of this template, if any
+ALTER TABLE templates
+    ADD COLUMN predecessor_address BLOB NULL;
+
+CREATE INDEX templates_author_public_key_template_name_index ON templates (author_public_key, template_name);
@@ -0,0 +1,2 @@
+ALTER TABLE epochs
+    ADD COLUMN block_hash BLOB NOT NULL DEFAULT x'';
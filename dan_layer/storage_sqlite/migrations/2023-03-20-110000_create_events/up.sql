@@ -0,0 +1,13 @@
+CREATE TABLE events
+(
+    id                integer   NOT NULL PRIMARY KEY AUTOINCREMENT,
+    payload_id        blob      NOT NULL,
+    topic             text      NOT NULL,
+    template_address  text      NOT NULL,
+    component_address text      NULL,
+    data              text      NOT NULL,
+    timestamp         timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX events_index_payload_id ON events (payload_id);
+CREATE INDEX events_index_topic ON events (topic);
@@ -0,0 +1,2 @@
+drop index nodes_index_payload_id;
+drop index received_votes_index_tree_node_hash;
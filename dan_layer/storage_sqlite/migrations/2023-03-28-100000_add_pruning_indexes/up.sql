@@ -0,0 +1,2 @@
+create index nodes_index_payload_id on nodes (payload_id);
+create index received_votes_index_tree_node_hash on received_votes (tree_node_hash);
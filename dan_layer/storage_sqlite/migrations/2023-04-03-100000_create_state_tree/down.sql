@@ -0,0 +1,3 @@
+ALTER TABLE nodes DROP COLUMN state_root;
+DROP TABLE state_tree_root;
+DROP TABLE state_tree_nodes;
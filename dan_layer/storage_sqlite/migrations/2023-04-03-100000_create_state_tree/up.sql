@@ -0,0 +1,18 @@
+CREATE TABLE state_tree_nodes
+(
+    id        integer NOT NULL PRIMARY KEY AUTOINCREMENT,
+    node_hash blob    NOT NULL,
+    node      text    NOT NULL
+);
+
+CREATE UNIQUE INDEX state_tree_nodes_uniq_idx_node_hash ON state_tree_nodes (node_hash);
+
+-- Holds a single row with the current root of the state tree
+CREATE TABLE state_tree_root
+(
+    id        integer NOT NULL PRIMARY KEY,
+    root_hash blob    NOT NULL
+);
+
+ALTER TABLE nodes
+    ADD COLUMN state_root blob NOT NULL DEFAULT X'0000000000000000000000000000000000000000000000000000000000000000';
@@ -0,0 +1,14 @@
+CREATE TABLE leader_failures
+(
+    id             integer   NOT NULL PRIMARY KEY AUTOINCREMENT,
+    epoch          bigint    NOT NULL,
+    committee_hash blob      NOT NULL,
+    shard_id       blob      NOT NULL,
+    payload_id     blob      NOT NULL,
+    leader_round   bigint    NOT NULL,
+    leader         blob      NOT NULL,
+    timestamp      timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX leader_failures_uniq_idx_payload_id_shard_id_leader_round ON leader_failures (payload_id, shard_id, leader_round);
+CREATE INDEX leader_failures_index_epoch ON leader_failures (epoch);
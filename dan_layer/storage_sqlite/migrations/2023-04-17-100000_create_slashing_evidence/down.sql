@@ -0,0 +1 @@
+DROP TABLE slashing_evidence;
@@ -0,0 +1,17 @@
+CREATE TABLE slashing_evidence
+(
+    id            integer   NOT NULL PRIMARY KEY AUTOINCREMENT,
+    evidence_hash blob      NOT NULL,
+    kind          text      NOT NULL,
+    offender      blob      NOT NULL,
+    epoch         bigint    NOT NULL,
+    payload_id    blob      NOT NULL,
+    shard_id      blob      NOT NULL,
+    height        bigint    NOT NULL,
+    leader_round  bigint    NOT NULL,
+    evidence      text      NOT NULL,
+    timestamp     timestamp NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX slashing_evidence_uniq_idx_evidence_hash ON slashing_evidence (evidence_hash);
+CREATE INDEX slashing_evidence_index_offender ON slashing_evidence (offender);
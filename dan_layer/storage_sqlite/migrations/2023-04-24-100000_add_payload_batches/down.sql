@@ -0,0 +1 @@
+ALTER TABLE payloads DROP COLUMN batched_transactions;
@@ -0,0 +1,2 @@
+ALTER TABLE payloads
+    ADD COLUMN batched_transactions text NULL;
@@ -0,0 +1 @@
+DROP TABLE mempool_transactions;
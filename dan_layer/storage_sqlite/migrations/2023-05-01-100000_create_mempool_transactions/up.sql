@@ -0,0 +1,10 @@
+CREATE TABLE mempool_transactions
+(
+    id               integer NOT NULL PRIMARY KEY AUTOINCREMENT,
+    transaction_hash blob    NOT NULL,
+    transaction_data text    NOT NULL,
+    received_at      bigint  NOT NULL,
+    payload_id       blob    NULL
+);
+
+CREATE UNIQUE INDEX mempool_transactions_uniq_idx_transaction_hash ON mempool_transactions (transaction_hash);
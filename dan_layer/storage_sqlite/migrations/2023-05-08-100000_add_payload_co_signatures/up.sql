@@ -0,0 +1,2 @@
+ALTER TABLE payloads
+    ADD COLUMN co_signatures text NULL;
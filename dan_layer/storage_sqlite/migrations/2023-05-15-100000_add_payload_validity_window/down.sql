@@ -0,0 +1,2 @@
+ALTER TABLE payloads DROP COLUMN expires_at_epoch;
+ALTER TABLE payloads DROP COLUMN min_epoch;
@@ -0,0 +1,4 @@
+ALTER TABLE payloads
+    ADD COLUMN min_epoch bigint NULL;
+ALTER TABLE payloads
+    ADD COLUMN expires_at_epoch bigint NULL;
@@ -151,15 +151,7 @@ impl GlobalDbAdapter for SqliteGlobalDbAdapter {
             })?;
 
         match template {
-            Some(t) => Ok(Some(DbTemplate {
-                template_name: t.template_name,
-                template_address: t.template_address.try_into()?,
-                url: t.url,
-                height: t.height as u64,
-                compiled_code: t.compiled_code,
-                status: t.status.parse().expect("DB status corrupted"),
-                added_at: time::OffsetDateTime::from_unix_timestamp(t.added_at).expect("added_at timestamp corrupted"),
-            })),
+            Some(t) => Ok(Some(t.try_into()?)),
             None => Ok(None),
         }
     }
@@ -175,21 +167,28 @@ impl GlobalDbAdapter for SqliteGlobalDbAdapter {
                 operation: "get_template".to_string(),
             })?;
 
-        templates
-            .into_iter()
-            .map(|t| {
-                Ok(DbTemplate {
-                    template_name: t.template_name,
-                    template_address: t.template_address.try_into()?,
-                    url: t.url,
-                    height: t.height as u64,
-                    compiled_code: t.compiled_code,
-                    status: t.status.parse().expect("DB status corrupted"),
-                    added_at: time::OffsetDateTime::from_unix_timestamp(t.added_at)
-                        .expect("added_at timestamp corrupted"),
-                })
-            })
-            .collect()
+        templates.into_iter().map(TryInto::try_into).collect()
+    }
+
+    fn get_latest_template_version(
+        &self,
+        tx: &mut Self::DbTransaction<'_>,
+        author_public_key: &[u8],
+        template_name: &str,
+    ) -> Result<Option<DbTemplate>, Self::Error> {
+        use crate::global::schema::templates::dsl;
+        let template = dsl::templates
+            .filter(templates::author_public_key.eq(author_public_key))
+            .filter(templates::template_name.eq(template_name))
+            .order_by(templates::version.desc())
+            .first::<TemplateModel>(tx.connection())
+            .optional()
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "get_latest_template_version".to_string(),
+            })?;
+
+        template.map(TryInto::try_into).transpose()
     }
 
     fn insert_template(&self, tx: &mut Self::DbTransaction<'_>, item: DbTemplate) -> Result<(), Self::Error> {
@@ -203,6 +202,9 @@ impl GlobalDbAdapter for SqliteGlobalDbAdapter {
             // TODO
             wasm_path: None,
             added_at: item.added_at.unix_timestamp(),
+            author_public_key: item.author_public_key,
+            version: i32::from(item.version),
+            predecessor_address: item.predecessor_address.map(|addr| addr.to_vec()),
         };
         diesel::insert_into(templates::table)
             .values(new_template)
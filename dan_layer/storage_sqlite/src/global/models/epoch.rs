@@ -28,6 +28,8 @@ use crate::global::schema::*;
 pub struct Epoch {
     pub epoch: i64,
     pub validator_node_mr: Vec<u8>,
+    pub timestamp: i64,
+    pub block_hash: Vec<u8>,
 }
 
 impl From<Epoch> for DbEpoch {
@@ -35,6 +37,8 @@ impl From<Epoch> for DbEpoch {
         Self {
             epoch: e.epoch as u64,
             validator_node_mr: e.validator_node_mr,
+            timestamp: e.timestamp as u64,
+            block_hash: e.block_hash,
         }
     }
 }
@@ -44,6 +48,8 @@ impl From<Epoch> for DbEpoch {
 pub struct NewEpoch {
     pub epoch: i64,
     pub validator_node_mr: Vec<u8>,
+    pub timestamp: i64,
+    pub block_hash: Vec<u8>,
 }
 
 impl From<DbEpoch> for NewEpoch {
@@ -51,6 +57,8 @@ impl From<DbEpoch> for NewEpoch {
         Self {
             epoch: e.epoch as i64,
             validator_node_mr: e.validator_node_mr,
+            timestamp: e.timestamp as i64,
+            block_hash: e.block_hash,
         }
     }
 }
@@ -20,7 +20,9 @@
 //   WHETHER IN CONTRACT, STRICT LIABILITY, OR TORT (INCLUDING NEGLIGENCE OR OTHERWISE) ARISING IN ANY WAY OUT OF THE
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
-use crate::global::schema::*;
+use tari_dan_storage::global::DbTemplate;
+
+use crate::{error::SqliteStorageError, global::schema::*};
 
 #[derive(Debug, Identifiable, Queryable)]
 #[diesel(table_name = templates)]
@@ -34,6 +36,28 @@ pub struct TemplateModel {
     pub status: String,
     pub wasm_path: Option<String>,
     pub added_at: i64,
+    pub author_public_key: Vec<u8>,
+    pub version: i32,
+    pub predecessor_address: Option<Vec<u8>>,
+}
+
+impl TryFrom<TemplateModel> for DbTemplate {
+    type Error = SqliteStorageError;
+
+    fn try_from(t: TemplateModel) -> Result<Self, Self::Error> {
+        Ok(Self {
+            template_name: t.template_name,
+            template_address: t.template_address.try_into()?,
+            url: t.url,
+            height: t.height as u64,
+            compiled_code: t.compiled_code,
+            status: t.status.parse().expect("DB status corrupted"),
+            added_at: time::OffsetDateTime::from_unix_timestamp(t.added_at).expect("added_at timestamp corrupted"),
+            author_public_key: t.author_public_key,
+            version: u16::try_from(t.version).expect("DB template version corrupted"),
+            predecessor_address: t.predecessor_address.map(TryInto::try_into).transpose()?,
+        })
+    }
 }
 
 #[derive(Debug, Insertable)]
@@ -47,6 +71,9 @@ pub struct NewTemplateModel {
     pub status: String,
     pub wasm_path: Option<String>,
     pub added_at: i64,
+    pub author_public_key: Vec<u8>,
+    pub version: i32,
+    pub predecessor_address: Option<Vec<u8>>,
 }
 
 #[derive(Debug, AsChangeset)]
@@ -24,6 +24,8 @@ table! {
     epochs (epoch) {
         epoch -> BigInt,
         validator_node_mr -> Binary,
+        timestamp -> BigInt,
+        block_hash -> Binary,
     }
 }
 
@@ -54,6 +56,9 @@ table! {
         status -> Text,
         wasm_path -> Nullable<Text>,
         added_at -> BigInt,
+        author_public_key -> Binary,
+        version -> Integer,
+        predecessor_address -> Nullable<Binary>,
     }
 }
 
@@ -0,0 +1,27 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use chrono::NaiveDateTime;
+
+use crate::schema::*;
+
+#[derive(Debug, Identifiable, Queryable)]
+pub struct Event {
+    pub id: i32,
+    pub payload_id: Vec<u8>,
+    pub topic: String,
+    pub template_address: String,
+    pub component_address: Option<String>,
+    pub data: String,
+    pub timestamp: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = events)]
+pub struct NewEvent {
+    pub payload_id: Vec<u8>,
+    pub topic: String,
+    pub template_address: String,
+    pub component_address: Option<String>,
+    pub data: String,
+}
@@ -0,0 +1,29 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use chrono::NaiveDateTime;
+
+use crate::schema::*;
+
+#[derive(Debug, Identifiable, Queryable)]
+pub struct LeaderFailure {
+    pub id: i32,
+    pub epoch: i64,
+    pub committee_hash: Vec<u8>,
+    pub shard_id: Vec<u8>,
+    pub payload_id: Vec<u8>,
+    pub leader_round: i64,
+    pub leader: Vec<u8>,
+    pub timestamp: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = leader_failures)]
+pub struct NewLeaderFailure {
+    pub epoch: i64,
+    pub committee_hash: Vec<u8>,
+    pub shard_id: Vec<u8>,
+    pub payload_id: Vec<u8>,
+    pub leader_round: i64,
+    pub leader: Vec<u8>,
+}
@@ -0,0 +1,22 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use crate::schema::*;
+
+#[derive(Debug, Identifiable, Queryable)]
+pub struct MempoolTransaction {
+    pub id: i32,
+    pub transaction_hash: Vec<u8>,
+    pub transaction_data: String,
+    pub received_at: i64,
+    pub payload_id: Option<Vec<u8>>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = mempool_transactions)]
+pub struct NewMempoolTransaction {
+    pub transaction_hash: Vec<u8>,
+    pub transaction_data: String,
+    pub received_at: i64,
+    pub payload_id: Option<Vec<u8>>,
+}
@@ -21,16 +21,21 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 pub mod current_state;
+pub mod event;
 pub mod high_qc;
 pub mod last_executed_height;
 pub mod last_voted_height;
+pub mod leader_failure;
 pub mod leader_proposals;
 pub mod leaf_nodes;
 pub mod lock_node_and_height;
 pub mod locked_qc;
+pub mod mempool_transaction;
 pub mod node;
 pub mod payload;
 pub mod pledge;
 pub mod prepare_qc;
 pub mod received_votes;
+pub mod slashing_evidence;
+pub mod state_tree;
 pub mod substate;
@@ -39,6 +39,8 @@ pub struct Node {
     pub proposed_by: Vec<u8>,
     pub justify: String,
     pub timestamp: NaiveDateTime,
+    pub state_root: Vec<u8>,
+    pub proposer_signature: Option<String>,
 }
 
 #[derive(Debug, Insertable)]
@@ -55,4 +57,6 @@ pub struct NewNode {
     pub epoch: i64,
     pub proposed_by: Vec<u8>,
     pub justify: String,
+    pub state_root: Vec<u8>,
+    pub proposer_signature: Option<String>,
 }
@@ -36,6 +36,10 @@ pub struct Payload {
     pub meta: String,
     pub result: Option<String>,
     pub timestamp: NaiveDateTime,
+    pub batched_transactions: Option<String>,
+    pub co_signatures: Option<String>,
+    pub min_epoch: Option<i64>,
+    pub expires_at_epoch: Option<i64>,
 }
 
 #[derive(Debug, Insertable)]
@@ -49,4 +53,8 @@ pub struct NewPayload {
     pub sender_address: Vec<u8>,
     pub meta: String,
     pub result: Option<String>,
+    pub batched_transactions: Option<String>,
+    pub co_signatures: Option<String>,
+    pub min_epoch: Option<i64>,
+    pub expires_at_epoch: Option<i64>,
 }
@@ -0,0 +1,36 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use chrono::NaiveDateTime;
+
+use crate::schema::*;
+
+#[derive(Debug, Identifiable, Queryable)]
+#[diesel(table_name = slashing_evidence)]
+pub struct SlashingEvidence {
+    pub id: i32,
+    pub evidence_hash: Vec<u8>,
+    pub kind: String,
+    pub offender: Vec<u8>,
+    pub epoch: i64,
+    pub payload_id: Vec<u8>,
+    pub shard_id: Vec<u8>,
+    pub height: i64,
+    pub leader_round: i64,
+    pub evidence: String,
+    pub timestamp: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = slashing_evidence)]
+pub struct NewSlashingEvidence {
+    pub evidence_hash: Vec<u8>,
+    pub kind: String,
+    pub offender: Vec<u8>,
+    pub epoch: i64,
+    pub payload_id: Vec<u8>,
+    pub shard_id: Vec<u8>,
+    pub height: i64,
+    pub leader_round: i64,
+    pub evidence: String,
+}
@@ -0,0 +1,18 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use crate::schema::*;
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = state_tree_nodes)]
+pub struct NewStateTreeNode {
+    pub node_hash: Vec<u8>,
+    pub node: String,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = state_tree_root)]
+pub struct StateTreeRoot {
+    pub id: i32,
+    pub root_hash: Vec<u8>,
+}
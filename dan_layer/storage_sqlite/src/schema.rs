@@ -1,5 +1,17 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    events (id) {
+        id -> Integer,
+        payload_id -> Binary,
+        topic -> Text,
+        template_address -> Text,
+        component_address -> Nullable<Text>,
+        data -> Text,
+        timestamp -> Timestamp,
+    }
+}
+
 diesel::table! {
     high_qcs (id) {
         id -> Integer,
@@ -33,6 +45,19 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    leader_failures (id) {
+        id -> Integer,
+        epoch -> BigInt,
+        committee_hash -> Binary,
+        shard_id -> Binary,
+        payload_id -> Binary,
+        leader_round -> BigInt,
+        leader -> Binary,
+        timestamp -> Timestamp,
+    }
+}
+
 diesel::table! {
     leader_proposals (id) {
         id -> Integer,
@@ -69,6 +94,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    mempool_transactions (id) {
+        id -> Integer,
+        transaction_hash -> Binary,
+        transaction_data -> Text,
+        received_at -> BigInt,
+        payload_id -> Nullable<Binary>,
+    }
+}
+
 diesel::table! {
     nodes (id) {
         id -> Integer,
@@ -84,6 +119,8 @@ diesel::table! {
         proposed_by -> Binary,
         justify -> Text,
         timestamp -> Timestamp,
+        state_root -> Binary,
+        proposer_signature -> Nullable<Text>,
     }
 }
 
@@ -99,6 +136,10 @@ diesel::table! {
         meta -> Text,
         result -> Nullable<Text>,
         timestamp -> Timestamp,
+        batched_transactions -> Nullable<Text>,
+        co_signatures -> Nullable<Text>,
+        min_epoch -> Nullable<BigInt>,
+        expires_at_epoch -> Nullable<BigInt>,
     }
 }
 
@@ -137,6 +178,37 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    slashing_evidence (id) {
+        id -> Integer,
+        evidence_hash -> Binary,
+        kind -> Text,
+        offender -> Binary,
+        epoch -> BigInt,
+        payload_id -> Binary,
+        shard_id -> Binary,
+        height -> BigInt,
+        leader_round -> BigInt,
+        evidence -> Text,
+        timestamp -> Timestamp,
+    }
+}
+
+diesel::table! {
+    state_tree_nodes (id) {
+        id -> Integer,
+        node_hash -> Binary,
+        node -> Text,
+    }
+}
+
+diesel::table! {
+    state_tree_root (id) {
+        id -> Integer,
+        root_hash -> Binary,
+    }
+}
+
 diesel::table! {
     substates (id) {
         id -> Integer,
@@ -158,15 +230,21 @@ diesel::table! {
 }
 
 diesel::allow_tables_to_appear_in_same_query!(
+    events,
     high_qcs,
     last_executed_heights,
     last_voted_heights,
+    leader_failures,
     leader_proposals,
     leaf_nodes,
     lock_node_and_heights,
+    mempool_transactions,
     nodes,
     payloads,
     received_votes,
     shard_pledges,
+    slashing_evidence,
+    state_tree_nodes,
+    state_tree_root,
     substates,
 );
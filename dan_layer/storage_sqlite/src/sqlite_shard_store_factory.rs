@@ -21,6 +21,7 @@
 //  USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use std::{
+    collections::HashSet,
     convert::{TryFrom, TryInto},
     fs::create_dir_all,
     ops::{Deref, DerefMut},
@@ -40,7 +41,7 @@ use diesel::{
 use diesel_migrations::{EmbeddedMigrations, MigrationHarness};
 use log::{debug, warn};
 use serde_json::json;
-use tari_common_types::types::{PrivateKey, PublicKey, Signature};
+use tari_common_types::types::{FixedHash, PrivateKey, PublicKey, Signature};
 use tari_dan_common_types::{
     Epoch,
     NodeHeight,
@@ -57,22 +58,29 @@ use tari_dan_core::{
         vote_message::VoteMessage,
         CurrentLeaderStates,
         HotStuffTreeNode,
+        LeaderFailure,
         LeafNode,
+        MempoolTransaction,
         Payload,
         PayloadResult,
+        PrunedRecordCounts,
         RecentTransaction,
         SQLSubstate,
         SQLTransaction,
+        SlashingEvidence,
+        StateTreeNode,
+        StateTreeProof,
         SubstateShardData,
         TariDanPayload,
     },
     storage::{
         shard_store::{ShardStore, ShardStoreReadTransaction, ShardStoreWriteTransaction},
+        state_tree::{StateTree, StateTreeStoreReader, StateTreeStoreWriter},
         StorageError,
     },
 };
-use tari_engine_types::{instruction::Instruction, substate::SubstateAddress};
-use tari_transaction::{InstructionSignature, Transaction, TransactionMeta};
+use tari_engine_types::{events::Event, instruction::Instruction, substate::SubstateAddress};
+use tari_transaction::{InstructionSignature, Transaction, TransactionBody, TransactionMeta};
 use tari_utilities::{
     hex::{to_hex, Hex},
     ByteArray,
@@ -82,22 +90,29 @@ use crate::{
     error::SqliteStorageError,
     models::{
         current_state::{CurrentLeaderState, NewCurrentLeaderState},
+        event::{Event as DbEvent, NewEvent},
         high_qc::{HighQc, NewHighQc},
         last_executed_height::{LastExecutedHeight, NewLastExecutedHeight},
         last_voted_height::{LastVotedHeight, NewLastVotedHeight},
+        leader_failure::{LeaderFailure as DbLeaderFailure, NewLeaderFailure},
         leader_proposals::{LeaderProposal, NewLeaderProposal},
         leaf_nodes::{LeafNode as DbLeafNode, NewLeafNode},
         lock_node_and_height::{LockNodeAndHeight, NewLockNodeAndHeight},
+        mempool_transaction::{MempoolTransaction as DbMempoolTransaction, NewMempoolTransaction},
         node::{NewNode, Node},
         payload::{NewPayload, Payload as SqlPayload},
         pledge::{NewShardPledge, ShardPledge as DbShardPledge},
         received_votes::{NewReceivedVote, ReceivedVote},
+        slashing_evidence::{NewSlashingEvidence, SlashingEvidence as DbSlashingEvidence},
+        state_tree::{NewStateTreeNode, StateTreeRoot},
         substate::{ImportedSubstate, NewSubstate, Substate},
     },
     SqliteTransaction,
 };
 
 const LOG_TARGET: &str = "tari::dan::storage::sqlite::shard_store";
+/// The state_tree_root table only ever contains this row
+const STATE_TREE_ROOT_ID: i32 = 1;
 
 #[derive(Debug, QueryableByName)]
 pub struct QueryableRecentTransaction {
@@ -122,6 +137,12 @@ impl From<QueryableRecentTransaction> for RecentTransaction {
     }
 }
 
+#[derive(Debug, QueryableByName)]
+pub struct QueryablePayloadId {
+    #[diesel(sql_type = Binary)]
+    pub payload_id: Vec<u8>,
+}
+
 #[derive(Debug, QueryableByName)]
 pub struct QueryableTransaction {
     #[diesel(sql_type = Binary)]
@@ -404,10 +425,32 @@ impl ShardStoreReadTransaction<PublicKey, TariDanPayload> for SqliteShardStoreRe
 
         let sender_public_key =
             PublicKey::from_vec(&payload.sender_address).map_err(StorageError::InvalidByteArrayConversion)?;
+        let co_signatures = payload
+            .co_signatures
+            .map(|co_signatures| serde_json::from_str(&co_signatures))
+            .transpose()
+            .map_err(|_| StorageError::DecodingError)?
+            .unwrap_or_default();
         let meta: TransactionMeta = serde_json::from_str(&payload.meta).unwrap();
 
-        let transaction = Transaction::new(fee, instructions, signature, sender_public_key, meta);
-        let mut tari_dan_payload = TariDanPayload::new(transaction);
+        let min_epoch = payload.min_epoch.map(|epoch| Epoch(epoch as u64));
+        let expires_at_epoch = payload.expires_at_epoch.map(|epoch| Epoch(epoch as u64));
+
+        let body = TransactionBody {
+            fee,
+            instructions,
+            meta,
+            min_epoch,
+            expires_at_epoch,
+        };
+        let transaction = Transaction::new(body, signature, sender_public_key, co_signatures);
+        let mut transactions = vec![transaction];
+        if let Some(batched_transactions) = payload.batched_transactions {
+            let batched_transactions: Vec<Transaction> =
+                serde_json::from_str(&batched_transactions).map_err(|_| StorageError::DecodingError)?;
+            transactions.extend(batched_transactions);
+        }
+        let mut tari_dan_payload = TariDanPayload::new_batch(transactions);
 
         // deserialize the transaction result
         let result_field: Option<PayloadResult> = match payload.result {
@@ -459,8 +502,14 @@ impl ShardStoreReadTransaction<PublicKey, TariDanPayload> for SqliteShardStoreRe
         let proposed_by = PublicKey::from_vec(&node.proposed_by).map_err(StorageError::InvalidByteArrayConversion)?;
 
         let justify: QuorumCertificate = serde_json::from_str(&node.justify).unwrap();
-
-        Ok(HotStuffTreeNode::new(
+        let state_root = FixedHash::try_from(node.state_root.as_slice())?;
+        let proposer_signature = node
+            .proposer_signature
+            .map(|signature| serde_json::from_str(&signature))
+            .transpose()
+            .map_err(|_| StorageError::DecodingError)?;
+
+        let mut node = HotStuffTreeNode::new(
             parent,
             shard,
             NodeHeight(hgt),
@@ -472,7 +521,12 @@ impl ShardStoreReadTransaction<PublicKey, TariDanPayload> for SqliteShardStoreRe
             Epoch(epoch),
             proposed_by,
             justify,
-        ))
+            state_root,
+        );
+        if let Some(signature) = proposer_signature {
+            node.set_proposer_signature(signature);
+        }
+        Ok(node)
     }
 
     fn get_locked_node_hash_and_height(
@@ -604,6 +658,27 @@ impl ShardStoreReadTransaction<PublicKey, TariDanPayload> for SqliteShardStoreRe
         }
     }
 
+    fn get_substate_states_chunk(
+        &mut self,
+        start_shard_id: ShardId,
+        end_shard_id: ShardId,
+        limit: usize,
+    ) -> Result<Vec<SubstateShardData>, StorageError> {
+        use crate::schema::substates;
+
+        let substate_states: Vec<Substate> = substates::table
+            .filter(substates::shard_id.ge(start_shard_id.as_bytes()))
+            .filter(substates::shard_id.le(end_shard_id.as_bytes()))
+            .order_by(substates::shard_id.asc())
+            .limit(limit as i64)
+            .get_results(self.transaction.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("Get substate states chunk: {}", e),
+            })?;
+
+        substate_states.iter().map(Self::map_substate_to_shard_data).collect()
+    }
+
     fn get_last_voted_height(
         &mut self,
         shard: ShardId,
@@ -741,6 +816,31 @@ impl ShardStoreReadTransaction<PublicKey, TariDanPayload> for SqliteShardStoreRe
             .collect())
     }
 
+    fn get_prunable_payloads(
+        &mut self,
+        before_epoch: Epoch,
+        decide_height: NodeHeight,
+        limit: usize,
+    ) -> Result<Vec<PayloadId>, StorageError> {
+        let res = sql_query(
+            "select n.payload_id from nodes n group by n.payload_id having max(n.epoch) < ? and count(distinct \
+             n.shard) = (select count(distinct d.shard) from nodes d where d.payload_id = n.payload_id and \
+             d.payload_height = ? and exists (select 1 from last_executed_heights h where h.payload_id = d.payload_id \
+             and h.shard_id = d.shard and h.node_height >= d.height)) limit ?",
+        )
+        .bind::<BigInt, _>(before_epoch.as_u64() as i64)
+        .bind::<BigInt, _>(decide_height.as_u64() as i64)
+        .bind::<BigInt, _>(limit as i64)
+        .load::<QueryablePayloadId>(self.transaction.connection())
+        .map_err(|e| StorageError::QueryError {
+            reason: format!("Get prunable payloads: {}", e),
+        })?;
+
+        res.into_iter()
+            .map(|row| PayloadId::try_from(row.payload_id).map_err(Into::into))
+            .collect()
+    }
+
     fn get_payload_result(&mut self, payload_id: &PayloadId) -> Result<PayloadResult, StorageError> {
         use crate::schema::{payloads, payloads::dsl};
 
@@ -765,6 +865,38 @@ impl ShardStoreReadTransaction<PublicKey, TariDanPayload> for SqliteShardStoreRe
         Ok(serde_json::from_str(&payload_result_json).expect("payload result in database corrupt"))
     }
 
+    fn get_events_for_payload(&mut self, payload_id: PayloadId) -> Result<Vec<Event>, StorageError> {
+        use crate::schema::events;
+
+        let rows: Vec<DbEvent> = events::table
+            .filter(events::payload_id.eq(payload_id.as_bytes()))
+            .order_by(events::id.asc())
+            .get_results(self.transaction.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("Get events for payload: {}", e),
+            })?;
+
+        rows.iter()
+            .map(|row| serde_json::from_str(&row.data).map_err(|_| StorageError::DecodingError))
+            .collect()
+    }
+
+    fn get_events_by_topic(&mut self, topic: &str) -> Result<Vec<Event>, StorageError> {
+        use crate::schema::events;
+
+        let rows: Vec<DbEvent> = events::table
+            .filter(events::topic.eq(topic))
+            .order_by(events::id.asc())
+            .get_results(self.transaction.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("Get events by topic: {}", e),
+            })?;
+
+        rows.iter()
+            .map(|row| serde_json::from_str(&row.data).map_err(|_| StorageError::DecodingError))
+            .collect()
+    }
+
     fn get_transaction(&mut self, payload_id: Vec<u8>) -> Result<Vec<SQLTransaction>, StorageError> {
         let res = sql_query(
             "select node_hash, parent_node_hash, shard, height, payload_height, (select count(*) from received_votes \
@@ -825,6 +957,149 @@ impl ShardStoreReadTransaction<PublicKey, TariDanPayload> for SqliteShardStoreRe
             .collect::<Result<_, SqliteStorageError>>()
             .map_err(Into::into)
     }
+
+    fn get_active_pledge(&mut self, shard: ShardId) -> Result<Option<ObjectPledgeInfo>, StorageError> {
+        use crate::schema::shard_pledges;
+
+        let pledge: Option<DbShardPledge> = shard_pledges::table
+            .filter(shard_pledges::shard_id.eq(shard.as_bytes()))
+            .filter(shard_pledges::is_active.eq(true))
+            .order_by(shard_pledges::created_height.desc())
+            .first(self.transaction.connection())
+            .optional()
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("Get active pledge error: {}", e),
+            })?;
+
+        let pledge = pledge.map(ObjectPledgeInfo::try_from).transpose()?;
+        Ok(pledge)
+    }
+
+    fn get_state_root(&mut self) -> Result<FixedHash, StorageError> {
+        StateTree::new(self).root()
+    }
+
+    fn get_state_proof(&mut self, shard: ShardId) -> Result<StateTreeProof, StorageError> {
+        StateTree::new(self).get_proof(&shard)
+    }
+
+    fn get_leader_failures(
+        &mut self,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<LeaderFailure<PublicKey>>, StorageError> {
+        use crate::schema::leader_failures;
+
+        let failures: Vec<DbLeaderFailure> = leader_failures::table
+            .filter(leader_failures::epoch.ge(start_epoch.as_u64() as i64))
+            .filter(leader_failures::epoch.le(end_epoch.as_u64() as i64))
+            .order_by(leader_failures::id.asc())
+            .get_results(self.transaction.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("Get leader failures error: {}", e),
+            })?;
+
+        failures
+            .into_iter()
+            .map(|failure| {
+                Ok::<_, StorageError>(LeaderFailure {
+                    epoch: Epoch(failure.epoch as u64),
+                    committee_hash: FixedHash::try_from(failure.committee_hash.as_slice())?,
+                    shard_id: ShardId::from_bytes(&failure.shard_id)?,
+                    payload_id: PayloadId::try_from(failure.payload_id)?,
+                    leader_round: failure.leader_round as u32,
+                    leader: PublicKey::from_vec(&failure.leader).map_err(StorageError::InvalidByteArrayConversion)?,
+                })
+            })
+            .collect()
+    }
+
+    fn get_slashing_evidence(
+        &mut self,
+        offender: Option<&PublicKey>,
+    ) -> Result<Vec<SlashingEvidence<PublicKey, TariDanPayload>>, StorageError> {
+        use crate::schema::slashing_evidence;
+
+        let mut query = slashing_evidence::table.into_boxed();
+        if let Some(offender) = offender {
+            query = query.filter(slashing_evidence::offender.eq(offender.as_bytes().to_vec()));
+        }
+        let evidence: Vec<DbSlashingEvidence> = query
+            .order_by(slashing_evidence::id.asc())
+            .get_results(self.transaction.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("Get slashing evidence error: {}", e),
+            })?;
+
+        evidence
+            .into_iter()
+            .map(|evidence| serde_json::from_str(&evidence.evidence).map_err(|_| StorageError::DecodingError))
+            .collect()
+    }
+
+    fn get_mempool_transactions(&mut self) -> Result<Vec<MempoolTransaction>, StorageError> {
+        use crate::schema::mempool_transactions;
+
+        let transactions: Vec<DbMempoolTransaction> = mempool_transactions::table
+            .order_by(mempool_transactions::id.asc())
+            .get_results(self.transaction.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("Get mempool transactions error: {}", e),
+            })?;
+
+        transactions
+            .into_iter()
+            .map(|transaction| {
+                Ok::<_, StorageError>(MempoolTransaction {
+                    transaction: serde_json::from_str(&transaction.transaction_data)
+                        .map_err(|_| StorageError::DecodingError)?,
+                    received_at: transaction.received_at as u64,
+                    payload_id: transaction.payload_id.map(PayloadId::try_from).transpose()?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl StateTreeStoreReader for SqliteShardStoreReadTransaction<'_> {
+    fn get_state_tree_root(&mut self) -> Result<FixedHash, StorageError> {
+        use crate::schema::state_tree_root;
+
+        let root_hash = state_tree_root::table
+            .select(state_tree_root::root_hash)
+            .filter(state_tree_root::id.eq(STATE_TREE_ROOT_ID))
+            .first::<Vec<u8>>(self.connection())
+            .optional()
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "get_state_tree_root".to_string(),
+            })?;
+
+        match root_hash {
+            Some(root_hash) => Ok(FixedHash::try_from(root_hash.as_slice())?),
+            None => Ok(FixedHash::zero()),
+        }
+    }
+
+    fn get_state_tree_node(&mut self, hash: &FixedHash) -> Result<StateTreeNode, StorageError> {
+        use crate::schema::state_tree_nodes;
+
+        let node = state_tree_nodes::table
+            .select(state_tree_nodes::node)
+            .filter(state_tree_nodes::node_hash.eq(hash.as_slice()))
+            .first::<String>(self.connection())
+            .optional()
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "get_state_tree_node".to_string(),
+            })?
+            .ok_or_else(|| StorageError::NotFound {
+                item: "state tree node".to_string(),
+                key: hash.to_string(),
+            })?;
+
+        serde_json::from_str(&node).map_err(|_| StorageError::DecodingError)
+    }
 }
 
 pub struct SqliteShardStoreWriteTransaction<'a> {
@@ -878,6 +1153,16 @@ impl<'a> SqliteShardStoreWriteTransaction<'a> {
             })
         }
     }
+
+    /// Sets the state tree leaves of the given shards to their current substates
+    fn update_state_tree(&mut self, shards: &[ShardId]) -> Result<(), StorageError> {
+        let substates = self.get_substate_states(shards)?;
+        let mut state_tree = StateTree::new(self);
+        for substate in substates {
+            state_tree.put(substate.shard_id(), substate.state_tree_value_hash())?;
+        }
+        Ok(())
+    }
 }
 
 impl ShardStoreWriteTransaction<PublicKey, TariDanPayload> for SqliteShardStoreWriteTransaction<'_> {
@@ -959,6 +1244,18 @@ impl ShardStoreWriteTransaction<PublicKey, TariDanPayload> for SqliteShardStoreW
 
         let payload_id = Vec::from(payload.to_id().as_bytes());
 
+        let batched_transactions = if payload.is_batch() {
+            Some(serde_json::to_string(payload.batched_transactions()).map_err(|_| StorageError::EncodingError)?)
+        } else {
+            None
+        };
+
+        let co_signatures = if transaction.co_signatures().is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(transaction.co_signatures()).map_err(|_| StorageError::EncodingError)?)
+        };
+
         let new_row = NewPayload {
             payload_id,
             instructions,
@@ -968,6 +1265,10 @@ impl ShardStoreWriteTransaction<PublicKey, TariDanPayload> for SqliteShardStoreW
             sender_address: sender_public_key,
             meta,
             result: None,
+            batched_transactions,
+            co_signatures,
+            min_epoch: transaction.min_epoch().map(|epoch| epoch.as_u64() as i64),
+            expires_at_epoch: transaction.expires_at_epoch().map(|epoch| epoch.as_u64() as i64),
         };
 
         match diesel::insert_into(payloads::table)
@@ -1121,6 +1422,12 @@ impl ShardStoreWriteTransaction<PublicKey, TariDanPayload> for SqliteShardStoreW
         let proposed_by = Vec::from(node.proposed_by().as_bytes());
 
         let justify = serde_json::to_string_pretty(node.justify()).unwrap();
+        let state_root = node.state_root().as_slice().to_vec();
+        let proposer_signature = node
+            .proposer_signature()
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(|_| StorageError::EncodingError)?;
 
         let new_row = NewNode {
             node_hash,
@@ -1134,6 +1441,8 @@ impl ShardStoreWriteTransaction<PublicKey, TariDanPayload> for SqliteShardStoreW
             epoch,
             proposed_by,
             justify,
+            state_root,
+            proposer_signature,
         };
 
         match diesel::insert_into(nodes::dsl::nodes)
@@ -1291,6 +1600,8 @@ impl ShardStoreWriteTransaction<PublicKey, TariDanPayload> for SqliteShardStoreW
             }
         }
 
+        self.update_state_tree(&[node.shard()])?;
+
         Ok(())
     }
 
@@ -1322,6 +1633,8 @@ impl ShardStoreWriteTransaction<PublicKey, TariDanPayload> for SqliteShardStoreW
                 reason: format!("Insert substates: {}", e),
             })?;
 
+        StateTree::new(self).put(substate_data.shard_id(), substate_data.state_tree_value_hash())?;
+
         Ok(())
     }
 
@@ -1443,6 +1756,33 @@ impl ShardStoreWriteTransaction<PublicKey, TariDanPayload> for SqliteShardStoreW
         Ok(())
     }
 
+    fn save_events(&mut self, payload_id: PayloadId, events: &[Event]) -> Result<(), StorageError> {
+        use crate::schema::events;
+
+        let new_rows = events
+            .iter()
+            .map(|event| {
+                Ok(NewEvent {
+                    payload_id: payload_id.as_bytes().to_vec(),
+                    topic: event.topic.clone(),
+                    template_address: event.template_address.to_string(),
+                    component_address: event.component_address.map(|addr| addr.to_string()),
+                    data: serde_json::to_string_pretty(event).map_err(|_| StorageError::EncodingError)?,
+                })
+            })
+            .collect::<Result<Vec<_>, StorageError>>()?;
+
+        diesel::insert_into(events::table)
+            .values(&new_rows)
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "save_events".to_string(),
+            })?;
+
+        Ok(())
+    }
+
     // -------------------------------- Pledges -------------------------------- //
 
     fn pledge_object(
@@ -1604,6 +1944,316 @@ impl ShardStoreWriteTransaction<PublicKey, TariDanPayload> for SqliteShardStoreW
             .map_err(|e| StorageError::QueryError {
                 reason: format!("Burnt commitment insert error: {}", e),
             })?;
+        self.update_state_tree(&[shard_id])
+    }
+
+    fn prune_payload(&mut self, payload_id: PayloadId) -> Result<PrunedRecordCounts, StorageError> {
+        use crate::schema::{
+            current_leader_states,
+            high_qcs,
+            last_executed_heights,
+            last_voted_heights,
+            leader_proposals,
+            leaf_nodes,
+            lock_node_and_heights,
+            nodes,
+            received_votes,
+            shard_pledges,
+        };
+
+        let payload_nodes = nodes::table
+            .select((nodes::node_hash, nodes::shard, nodes::height, nodes::justify))
+            .filter(nodes::payload_id.eq(payload_id.as_bytes()))
+            .load::<(Vec<u8>, Vec<u8>, i64, String)>(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: get nodes".to_string(),
+            })?;
+        let last_executed_heights = last_executed_heights::table
+            .select((last_executed_heights::shard_id, last_executed_heights::node_height))
+            .filter(last_executed_heights::payload_id.eq(payload_id.as_bytes()))
+            .load::<(Vec<u8>, i64)>(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: get last executed heights".to_string(),
+            })?;
+
+        // The node at the last executed height of each shard carries the committing QC. It is kept along with the node
+        // that the QC certifies.
+        let mut kept_nodes = HashSet::new();
+        let mut committing_qcs = HashSet::new();
+        for (node_hash, shard, height, justify) in &payload_nodes {
+            if last_executed_heights.contains(&(shard.clone(), *height)) {
+                let justify: QuorumCertificate =
+                    serde_json::from_str(justify).map_err(SqliteStorageError::SerializationFailed)?;
+                kept_nodes.insert(node_hash.clone());
+                kept_nodes.insert(justify.node_hash().as_bytes().to_vec());
+                committing_qcs.insert((shard.clone(), justify.node_height().as_u64() as i64));
+            }
+        }
+
+        let mut node_hashes = payload_nodes
+            .into_iter()
+            .map(|(node_hash, _, _, _)| node_hash)
+            .collect::<Vec<_>>();
+        let pruned_node_hashes = node_hashes
+            .iter()
+            .filter(|node_hash| !kept_nodes.contains(*node_hash))
+            .cloned()
+            .collect::<Vec<_>>();
+        // A leader receives votes for the nodes it proposed, which it may not have saved as nodes
+        let proposal_hashes = leader_proposals::table
+            .select(leader_proposals::node_hash)
+            .filter(leader_proposals::payload_id.eq(payload_id.as_bytes()))
+            .load::<Vec<u8>>(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: get leader proposals".to_string(),
+            })?;
+        node_hashes.extend(proposal_hashes);
+
+        let received_votes = diesel::delete(received_votes::table)
+            .filter(received_votes::tree_node_hash.eq_any(&node_hashes))
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: delete received votes".to_string(),
+            })?;
+
+        let num_nodes = diesel::delete(nodes::table)
+            .filter(nodes::node_hash.eq_any(&pruned_node_hashes))
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: delete nodes".to_string(),
+            })?;
+
+        let leader_proposals = diesel::delete(leader_proposals::table)
+            .filter(leader_proposals::payload_id.eq(payload_id.as_bytes()))
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: delete leader proposals".to_string(),
+            })?;
+
+        // Keep the committing QC for each shard
+        let qcs = high_qcs::table
+            .select((high_qcs::id, high_qcs::shard_id, high_qcs::height))
+            .filter(high_qcs::payload_id.eq(payload_id.as_bytes()))
+            .load::<(i32, Vec<u8>, i64)>(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: get high qcs".to_string(),
+            })?;
+        let superseded_qcs = qcs
+            .into_iter()
+            .filter(|(_, shard_id, height)| !committing_qcs.contains(&(shard_id.clone(), *height)))
+            .map(|(id, _, _)| id)
+            .collect::<Vec<_>>();
+        let num_high_qcs = diesel::delete(high_qcs::table)
+            .filter(high_qcs::id.eq_any(&superseded_qcs))
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: delete high qcs".to_string(),
+            })?;
+
+        let pledges = diesel::delete(shard_pledges::table)
+            .filter(shard_pledges::pledged_to_payload_id.eq(payload_id.as_bytes()))
+            .filter(shard_pledges::is_active.eq(false))
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: delete pledges".to_string(),
+            })?;
+
+        let mut consensus_states = diesel::delete(leaf_nodes::table)
+            .filter(leaf_nodes::payload_id.eq(payload_id.as_bytes()))
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: delete leaf nodes".to_string(),
+            })?;
+        consensus_states += diesel::delete(lock_node_and_heights::table)
+            .filter(lock_node_and_heights::payload_id.eq(payload_id.as_bytes()))
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: delete locked nodes".to_string(),
+            })?;
+        consensus_states += diesel::delete(last_executed_heights::table)
+            .filter(last_executed_heights::payload_id.eq(payload_id.as_bytes()))
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: delete last executed heights".to_string(),
+            })?;
+        consensus_states += diesel::delete(last_voted_heights::table)
+            .filter(last_voted_heights::payload_id.eq(payload_id.as_bytes()))
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: delete last voted heights".to_string(),
+            })?;
+        consensus_states += diesel::delete(current_leader_states::table)
+            .filter(current_leader_states::payload_id.eq(payload_id.as_bytes()))
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "prune_payload: delete current leader states".to_string(),
+            })?;
+
+        Ok(PrunedRecordCounts {
+            nodes: num_nodes as u64,
+            received_votes: received_votes as u64,
+            leader_proposals: leader_proposals as u64,
+            high_qcs: num_high_qcs as u64,
+            pledges: pledges as u64,
+            consensus_states: consensus_states as u64,
+        })
+    }
+
+    fn save_leader_failure(&mut self, failure: LeaderFailure<PublicKey>) -> Result<(), StorageError> {
+        use crate::schema::leader_failures;
+
+        let new_row = NewLeaderFailure {
+            epoch: failure.epoch.as_u64() as i64,
+            committee_hash: failure.committee_hash.to_vec(),
+            shard_id: failure.shard_id.as_bytes().to_vec(),
+            payload_id: failure.payload_id.as_bytes().to_vec(),
+            leader_round: i64::from(failure.leader_round),
+            leader: failure.leader.as_bytes().to_vec(),
+        };
+
+        diesel::insert_or_ignore_into(leader_failures::table)
+            .values(&new_row)
+            .execute(self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("Save leader failure error: {}", e),
+            })?;
+
+        Ok(())
+    }
+
+    fn save_slashing_evidence(
+        &mut self,
+        evidence: SlashingEvidence<PublicKey, TariDanPayload>,
+    ) -> Result<(), StorageError> {
+        use crate::schema::slashing_evidence;
+
+        let new_row = NewSlashingEvidence {
+            evidence_hash: evidence.hash().to_vec(),
+            kind: evidence.kind().to_string(),
+            offender: evidence.offender().as_bytes().to_vec(),
+            epoch: evidence.epoch().as_u64() as i64,
+            payload_id: evidence.payload_id().as_bytes().to_vec(),
+            shard_id: evidence.shard().as_bytes().to_vec(),
+            height: evidence.height().as_u64() as i64,
+            leader_round: i64::from(evidence.leader_round()),
+            evidence: serde_json::to_string(&evidence).map_err(|_| StorageError::EncodingError)?,
+        };
+
+        diesel::insert_or_ignore_into(slashing_evidence::table)
+            .values(&new_row)
+            .execute(self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("Save slashing evidence error: {}", e),
+            })?;
+
+        Ok(())
+    }
+
+    fn save_mempool_transaction(&mut self, transaction: MempoolTransaction) -> Result<(), StorageError> {
+        use crate::schema::mempool_transactions;
+
+        let new_row = NewMempoolTransaction {
+            transaction_hash: transaction.hash().to_vec(),
+            transaction_data: serde_json::to_string(&transaction.transaction)
+                .map_err(|_| StorageError::EncodingError)?,
+            received_at: transaction.received_at as i64,
+            payload_id: transaction.payload_id.map(|id| id.as_bytes().to_vec()),
+        };
+
+        diesel::replace_into(mempool_transactions::table)
+            .values(&new_row)
+            .execute(self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("Save mempool transaction error: {}", e),
+            })?;
+
+        Ok(())
+    }
+
+    fn remove_mempool_transaction(&mut self, transaction_hash: &FixedHash) -> Result<bool, StorageError> {
+        use crate::schema::mempool_transactions;
+
+        let num_deleted = diesel::delete(mempool_transactions::table)
+            .filter(mempool_transactions::transaction_hash.eq(transaction_hash.as_slice()))
+            .execute(self.connection())
+            .map_err(|e| StorageError::QueryError {
+                reason: format!("Remove mempool transaction error: {}", e),
+            })?;
+
+        Ok(num_deleted > 0)
+    }
+}
+
+impl StateTreeStoreReader for SqliteShardStoreWriteTransaction<'_> {
+    fn get_state_tree_root(&mut self) -> Result<FixedHash, StorageError> {
+        self.transaction.as_mut().unwrap().get_state_tree_root()
+    }
+
+    fn get_state_tree_node(&mut self, hash: &FixedHash) -> Result<StateTreeNode, StorageError> {
+        self.transaction.as_mut().unwrap().get_state_tree_node(hash)
+    }
+}
+
+impl StateTreeStoreWriter for SqliteShardStoreWriteTransaction<'_> {
+    fn set_state_tree_root(&mut self, root: FixedHash) -> Result<(), StorageError> {
+        use crate::schema::state_tree_root;
+
+        diesel::replace_into(state_tree_root::table)
+            .values(&StateTreeRoot {
+                id: STATE_TREE_ROOT_ID,
+                root_hash: root.as_slice().to_vec(),
+            })
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "set_state_tree_root".to_string(),
+            })?;
+
+        Ok(())
+    }
+
+    fn insert_state_tree_node(&mut self, hash: FixedHash, node: StateTreeNode) -> Result<(), StorageError> {
+        use crate::schema::state_tree_nodes;
+
+        diesel::insert_into(state_tree_nodes::table)
+            .values(&NewStateTreeNode {
+                node_hash: hash.as_slice().to_vec(),
+                node: serde_json::to_string(&node).map_err(|_| StorageError::EncodingError)?,
+            })
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "insert_state_tree_node".to_string(),
+            })?;
+
+        Ok(())
+    }
+
+    fn delete_state_tree_node(&mut self, hash: &FixedHash) -> Result<(), StorageError> {
+        use crate::schema::state_tree_nodes;
+
+        diesel::delete(state_tree_nodes::table)
+            .filter(state_tree_nodes::node_hash.eq(hash.as_slice()))
+            .execute(self.connection())
+            .map_err(|source| SqliteStorageError::DieselError {
+                source,
+                operation: "delete_state_tree_node".to_string(),
+            })?;
+
         Ok(())
     }
 }
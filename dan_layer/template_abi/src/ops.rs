@@ -34,6 +34,10 @@ pub enum EngineOp {
     NonFungibleInvoke = 0x06,
     GenerateUniqueId = 0x07,
     ConsensusInvoke = 0x08,
+    CallInvoke = 0x09,
+    EmitEvent = 0x0A,
+    ProofInvoke = 0x0B,
+    KeyValueStoreInvoke = 0x0C,
 }
 
 impl EngineOp {
@@ -48,6 +52,10 @@ impl EngineOp {
             0x06 => Some(EngineOp::NonFungibleInvoke),
             0x07 => Some(EngineOp::GenerateUniqueId),
             0x08 => Some(EngineOp::ConsensusInvoke),
+            0x09 => Some(EngineOp::CallInvoke),
+            0x0A => Some(EngineOp::EmitEvent),
+            0x0B => Some(EngineOp::ProofInvoke),
+            0x0C => Some(EngineOp::KeyValueStoreInvoke),
             _ => None,
         }
     }
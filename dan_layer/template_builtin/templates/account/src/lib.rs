@@ -21,7 +21,7 @@
 //   USE OF THIS SOFTWARE, EVEN IF ADVISED OF THE POSSIBILITY OF SUCH DAMAGE.
 
 use tari_template_abi::rust::collections::HashMap;
-use tari_template_lib::prelude::*;
+use tari_template_lib::{constants::CONFIDENTIAL_TARI_RESOURCE_ADDRESS, prelude::*};
 
 #[template]
 mod account_template {
@@ -83,6 +83,12 @@ mod account_template {
             v.withdraw_confidential(withdraw_proof)
         }
 
+        // #[access_rules(requires(owner_badge))]
+        pub fn pay_fee(&mut self, max_fee: Amount) {
+            let v = self.get_vault_mut(CONFIDENTIAL_TARI_RESOURCE_ADDRESS);
+            v.pay_fee(max_fee);
+        }
+
         // #[access_rules(allow_all)]
         pub fn deposit(&mut self, bucket: Bucket) {
             let resource_address = bucket.resource_address();
@@ -105,6 +111,18 @@ mod account_template {
             v.get_non_fungible_ids()
         }
 
+        // #[access_rules(require(owner_badge))]
+        pub fn create_proof_by_amount(&self, resource: ResourceAddress, amount: Amount) -> Proof {
+            let v = self.get_vault(resource);
+            v.create_proof_of_amount(amount)
+        }
+
+        // #[access_rules(require(owner_badge))]
+        pub fn create_proof_by_non_fungible_ids(&self, resource: ResourceAddress, ids: Vec<NonFungibleId>) -> Proof {
+            let v = self.get_vault(resource);
+            v.create_proof_of_non_fungibles(ids)
+        }
+
         fn get_vault(&self, resource: ResourceAddress) -> &Vault {
             self.vaults
                 .get(&resource)
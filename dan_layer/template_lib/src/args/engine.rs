@@ -35,16 +35,26 @@ use crate::{
         BucketId,
         ComponentAddress,
         ConfidentialWithdrawProof,
+        KeyValueStoreId,
         Metadata,
         NonFungibleAddress,
         NonFungibleId,
+        ProofId,
         ResourceAddress,
+        TemplateAddress,
         VaultRef,
     },
-    prelude::{AccessRules, ConfidentialOutputProof},
+    prelude::{AccessRules, ConfidentialOutputProof, ResourceAccessRules},
     resource::ResourceType,
 };
 
+// -------------------------------- EVENTS -------------------------------- //
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct EmitEventArg {
+    pub topic: String,
+    pub payload: Metadata,
+}
+
 // -------------------------------- LOGS -------------------------------- //
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct EmitLogArg {
@@ -112,6 +122,7 @@ pub enum ComponentAction {
     Create,
     SetState,
     SetAccessRules,
+    UpgradeTemplate,
 }
 
 #[derive(Clone, Copy, Hash, Debug, Decode, Encode)]
@@ -192,6 +203,7 @@ pub enum MintArg {
 #[derive(Clone, Debug, Decode, Encode)]
 pub struct CreateResourceArg {
     pub resource_type: ResourceType,
+    pub access_rules: ResourceAccessRules,
     pub metadata: Metadata,
     pub mint_arg: Option<MintArg>,
 }
@@ -224,6 +236,7 @@ pub struct VaultInvokeArg {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VaultAction {
     Create,
+    CreateLocked,
     Deposit,
     Withdraw,
     WithdrawAll,
@@ -232,6 +245,11 @@ pub enum VaultAction {
     GetNonFungibleIds,
     GetCommitmentCount,
     ConfidentialReveal,
+    PayFee,
+    CreateProofByResource,
+    CreateProofByAmount,
+    CreateProofByNonFungibles,
+    GetLock,
 }
 
 #[derive(Clone, Debug, Decode, Encode)]
@@ -277,7 +295,8 @@ impl BucketRef {
     }
 }
 
-#[derive(Clone, Copy, Debug, Decode, Encode)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Decode, Encode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum BucketAction {
     Create,
     GetResourceAddress,
@@ -287,6 +306,7 @@ pub enum BucketAction {
     TakeConfidential,
     RevealConfidential,
     Burn,
+    CreateProof,
 }
 
 #[derive(Clone, Debug, Decode, Encode)]
@@ -294,6 +314,22 @@ pub struct BucketBurnArg {
     pub bucket_id: BucketId,
 }
 
+// -------------------------------- Proof -------------------------------- //
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct ProofInvokeArg {
+    pub proof_id: ProofId,
+    pub action: ProofAction,
+    pub args: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, Copy, Debug, Decode, Encode)]
+pub enum ProofAction {
+    GetResourceAddress,
+    GetAmount,
+    GetNonFungibles,
+    Drop,
+}
+
 // -------------------------------- Workspace -------------------------------- //
 #[derive(Clone, Copy, Debug, Decode, Encode)]
 pub enum WorkspaceAction {
@@ -323,6 +359,37 @@ pub enum NonFungibleAction {
     GetMutableData,
 }
 
+// -------------------------------- KeyValueStore -------------------------------- //
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct KeyValueStoreInvokeArg {
+    pub store_ref: KeyValueStoreRef,
+    pub action: KeyValueStoreAction,
+    pub args: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, Copy, Debug, Decode, Encode)]
+pub enum KeyValueStoreRef {
+    New,
+    Ref(KeyValueStoreId),
+}
+
+impl KeyValueStoreRef {
+    pub fn store_id(&self) -> Option<KeyValueStoreId> {
+        match self {
+            KeyValueStoreRef::New => None,
+            KeyValueStoreRef::Ref(id) => Some(*id),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Decode, Encode)]
+pub enum KeyValueStoreAction {
+    Create,
+    Get,
+    Insert,
+    Remove,
+}
+
 // -------------------------------- Consensus -------------------------------- //
 #[derive(Clone, Debug, Decode, Encode)]
 pub struct ConsensusInvokeArg {
@@ -332,4 +399,34 @@ pub struct ConsensusInvokeArg {
 #[derive(Clone, Debug, Decode, Encode)]
 pub enum ConsensusAction {
     GetCurrentEpoch,
+    GetEpochTimestamp,
+    GetEpochBeacon,
+    GenerateRandomBytes,
+}
+
+// -------------------------------- Call -------------------------------- //
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct CallInvokeArg {
+    pub action: CallAction,
+    pub args: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, Copy, Debug, Decode, Encode)]
+pub enum CallAction {
+    CallFunction,
+    CallMethod,
+}
+
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct CallFunctionArg {
+    pub template_address: TemplateAddress,
+    pub function: String,
+    pub args: Vec<Vec<u8>>,
+}
+
+#[derive(Clone, Debug, Decode, Encode)]
+pub struct CallMethodArg {
+    pub component_address: ComponentAddress,
+    pub method: String,
+    pub args: Vec<Vec<u8>>,
 }
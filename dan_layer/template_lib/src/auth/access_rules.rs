@@ -4,9 +4,15 @@
 use tari_bor::{borsh, Decode, Encode};
 use tari_template_abi::rust::collections::HashMap;
 
-use crate::{auth::NativeFunctionCall, models::NonFungibleAddress};
+use crate::{
+    args::ComponentAction,
+    auth::NativeFunctionCall,
+    models::{Amount, NonFungibleAddress, ResourceAddress},
+};
 
-#[derive(Debug, Clone, Decode, Encode)]
+const UPGRADE_TEMPLATE_CALL: NativeFunctionCall = NativeFunctionCall::Component(ComponentAction::UpgradeTemplate);
+
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AccessRule {
     AllowAll,
@@ -14,30 +20,21 @@ pub enum AccessRule {
     Restricted(RestrictedAccessRule),
 }
 
-impl AccessRule {
-    pub fn is_access_allowed(&self, proofs: &[NonFungibleAddress]) -> bool {
-        match self {
-            AccessRule::AllowAll => true,
-            AccessRule::DenyAll => false,
-            AccessRule::Restricted(rule) => rule.is_access_allowed(proofs),
-        }
-    }
-}
-
-#[derive(Debug, Clone, Decode, Encode)]
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RestrictedAccessRule {
+    /// Requires a proof of the specific non-fungible
     Require(NonFungibleAddress),
-    // TODO: Other requirements, for example, holder of a particular resource or particular balance of funds locked
-    //       for the transaction
-}
-
-impl RestrictedAccessRule {
-    pub fn is_access_allowed(&self, proofs: &[NonFungibleAddress]) -> bool {
-        match self {
-            RestrictedAccessRule::Require(proof) => proofs.contains(proof),
-        }
-    }
+    /// Requires a proof of any token of the resource
+    RequireResource(ResourceAddress),
+    /// Requires proofs of at least the given amount of the resource
+    RequireAmount(ResourceAddress, Amount),
+    /// Satisfied if any of the rules are satisfied
+    AnyOf(Vec<RestrictedAccessRule>),
+    /// Satisfied if all of the rules are satisfied
+    AllOf(Vec<RestrictedAccessRule>),
+    /// Satisfied if at least `n` of the rules are satisfied
+    MinOf(u32, Vec<RestrictedAccessRule>),
 }
 
 #[derive(Debug, Clone, Decode, Encode)]
@@ -80,6 +77,12 @@ impl AccessRules {
         self
     }
 
+    /// Sets the rule that must be satisfied to upgrade the component to a new version of its template. Components
+    /// without an upgrade rule cannot be upgraded, regardless of the default rule.
+    pub fn add_upgrade_rule(self, rule: AccessRule) -> Self {
+        self.add_native_rule(UPGRADE_TEMPLATE_CALL, rule)
+    }
+
     pub fn default(mut self, rule: AccessRule) -> Self {
         self.default = rule;
         self
@@ -93,6 +96,12 @@ impl AccessRules {
         self.native_method_access.get(call).unwrap_or(&self.default)
     }
 
+    pub fn get_upgrade_access_rule(&self) -> &AccessRule {
+        self.native_method_access
+            .get(&UPGRADE_TEMPLATE_CALL)
+            .unwrap_or(&AccessRule::DenyAll)
+    }
+
     pub fn method_access_rules_iter(&self) -> impl Iterator<Item = (&String, &AccessRule)> {
         self.method_access.iter()
     }
@@ -6,3 +6,6 @@ pub use access_rules::{AccessRule, AccessRules, RestrictedAccessRule};
 
 mod native;
 pub use native::NativeFunctionCall;
+
+mod resource_access_rules;
+pub use resource_access_rules::ResourceAccessRules;
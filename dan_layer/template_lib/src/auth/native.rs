@@ -4,7 +4,7 @@
 use tari_bor::{borsh, Decode, Encode};
 use tari_template_abi::rust::fmt::{Display, Formatter};
 
-use crate::args::{ComponentAction, ResourceAction, VaultAction};
+use crate::args::{BucketAction, ComponentAction, ResourceAction, VaultAction};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Decode, Encode)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -12,6 +12,7 @@ pub enum NativeFunctionCall {
     Component(ComponentAction),
     Resource(ResourceAction),
     Vault(VaultAction),
+    Bucket(BucketAction),
 }
 
 impl Display for NativeFunctionCall {
@@ -20,6 +21,7 @@ impl Display for NativeFunctionCall {
             NativeFunctionCall::Component(action) => write!(f, "component.{:?}", action),
             NativeFunctionCall::Resource(action) => write!(f, "resource.{:?}", action),
             NativeFunctionCall::Vault(action) => write!(f, "vault.{:?}", action),
+            NativeFunctionCall::Bucket(action) => write!(f, "bucket.{:?}", action),
         }
     }
 }
@@ -0,0 +1,69 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_bor::{borsh, Decode, Encode};
+
+use crate::auth::AccessRule;
+
+/// Access rules for the native operations that change the supply or data of a resource. These rules are stored in
+/// the resource and apply to every caller, regardless of the component that makes the call.
+#[derive(Debug, Clone, PartialEq, Eq, Decode, Encode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResourceAccessRules {
+    mintable: AccessRule,
+    burnable: AccessRule,
+    update_non_fungible_data: AccessRule,
+}
+
+impl ResourceAccessRules {
+    /// Creates access rules that allow anyone to mint, burn and update non-fungible data
+    pub fn new() -> Self {
+        Self {
+            mintable: AccessRule::AllowAll,
+            burnable: AccessRule::AllowAll,
+            update_non_fungible_data: AccessRule::AllowAll,
+        }
+    }
+
+    /// Creates access rules that deny all minting, burning and updating of non-fungible data
+    pub fn deny_all() -> Self {
+        Self {
+            mintable: AccessRule::DenyAll,
+            burnable: AccessRule::DenyAll,
+            update_non_fungible_data: AccessRule::DenyAll,
+        }
+    }
+
+    pub fn mintable(mut self, rule: AccessRule) -> Self {
+        self.mintable = rule;
+        self
+    }
+
+    pub fn burnable(mut self, rule: AccessRule) -> Self {
+        self.burnable = rule;
+        self
+    }
+
+    pub fn update_non_fungible_data(mut self, rule: AccessRule) -> Self {
+        self.update_non_fungible_data = rule;
+        self
+    }
+
+    pub fn get_mintable_rule(&self) -> &AccessRule {
+        &self.mintable
+    }
+
+    pub fn get_burnable_rule(&self) -> &AccessRule {
+        &self.burnable
+    }
+
+    pub fn get_update_non_fungible_data_rule(&self) -> &AccessRule {
+        &self.update_non_fungible_data
+    }
+}
+
+impl Default for ResourceAccessRules {
+    fn default() -> Self {
+        Self::new()
+    }
+}
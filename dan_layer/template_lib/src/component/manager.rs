@@ -24,9 +24,9 @@ use tari_bor::{decode_exact, encode, Decode, Encode};
 use tari_template_abi::{call_engine, EngineOp};
 
 use crate::{
-    args::{ComponentAction, ComponentInvokeArg, ComponentRef, InvokeResult},
+    args::{CallAction, CallInvokeArg, CallMethodArg, ComponentAction, ComponentInvokeArg, ComponentRef, InvokeResult},
     auth::AccessRules,
-    models::{ComponentAddress, ComponentHeader},
+    models::{ComponentAddress, ComponentHeader, TemplateAddress},
 };
 
 pub struct ComponentManager {
@@ -59,6 +59,23 @@ impl ComponentManager {
         });
     }
 
+    /// Calls a method on the component. The call is executed by the engine using the component's own access rules. Any
+    /// buckets or proofs passed as arguments are moved to the callee.
+    pub fn call<T: Decode>(&self, method: &str, args: Vec<Vec<u8>>) -> T {
+        let result = call_engine::<_, InvokeResult>(EngineOp::CallInvoke, &CallInvokeArg {
+            action: CallAction::CallMethod,
+            args: invoke_args![CallMethodArg {
+                component_address: self.address,
+                method: method.to_string(),
+                args,
+            }],
+        });
+
+        result
+            .decode()
+            .expect("failed to decode component method call result from engine")
+    }
+
     pub fn set_access_rules(&self, access_rules: AccessRules) {
         call_engine::<_, InvokeResult>(EngineOp::ComponentInvoke, &ComponentInvokeArg {
             component_ref: ComponentRef::Ref(self.address),
@@ -66,4 +83,17 @@ impl ComponentManager {
             args: invoke_args![access_rules],
         });
     }
+
+    /// Upgrades the component to a new version of its template. The new template must be registered as an upgrade of
+    /// the component's current template and the component's upgrade access rule must be satisfied. If the new template
+    /// defines a migration function (see [TEMPLATE_MIGRATION_FUNCTION](crate::constants::TEMPLATE_MIGRATION_FUNCTION)),
+    /// it is called to convert the component state. A component cannot be upgraded while one of its methods is
+    /// executing.
+    pub fn upgrade_template(&self, template_address: TemplateAddress) {
+        call_engine::<_, InvokeResult>(EngineOp::ComponentInvoke, &ComponentInvokeArg {
+            component_ref: ComponentRef::Ref(self.address),
+            action: ComponentAction::UpgradeTemplate,
+            args: invoke_args![template_address],
+        });
+    }
 }
@@ -34,4 +34,23 @@ impl Consensus {
         resp.decode()
             .expect("Consensus GetCurrentEpoch returned invalid resource type")
     }
+
+    /// Returns the timestamp (seconds since the unix epoch) of the base layer block that started the current epoch.
+    /// All validators agree on this value, so it can be used for coarse-grained time locks.
+    pub fn current_epoch_timestamp() -> u64 {
+        let resp: InvokeResult = call_engine(EngineOp::ConsensusInvoke, &ConsensusInvokeArg {
+            action: ConsensusAction::GetEpochTimestamp,
+        });
+        resp.decode()
+            .expect("Consensus GetEpochTimestamp returned invalid timestamp")
+    }
+
+    /// Returns the random beacon of the current epoch. Every validator computes the same value. It cannot be predicted
+    /// before the epoch starts, but is public during the epoch, so it is not a secret from the transaction sender.
+    pub fn current_epoch_beacon() -> [u8; 32] {
+        let resp: InvokeResult = call_engine(EngineOp::ConsensusInvoke, &ConsensusInvokeArg {
+            action: ConsensusAction::GetEpochBeacon,
+        });
+        resp.decode().expect("Consensus GetEpochBeacon returned invalid beacon")
+    }
 }
@@ -11,3 +11,8 @@ pub const PUBLIC_IDENTITY_RESOURCE_ADDRESS: ResourceAddress = ResourceAddress::n
 ]));
 
 pub const CONFIDENTIAL_TARI_RESOURCE_ADDRESS: ResourceAddress = ResourceAddress::new(Hash::from_array([1u8; 32]));
+
+/// The name of the optional function that the engine calls on the new template when a component's template is
+/// upgraded. The function is called with the previous template address and the encoded component state, and must
+/// return the encoded state for the new template i.e. `fn migrate(from: TemplateAddress, state: Vec<u8>) -> Vec<u8>`.
+pub const TEMPLATE_MIGRATION_FUNCTION: &str = "migrate";
@@ -24,11 +24,20 @@ use tari_bor::{encode, Encode};
 use tari_template_abi::{call_engine, EngineOp};
 
 use crate::{
-    args::{ComponentAction, ComponentInvokeArg, ComponentRef, CreateComponentArg, EmitLogArg, InvokeResult, LogLevel},
+    args::{
+        ComponentAction,
+        ComponentInvokeArg,
+        ComponentRef,
+        CreateComponentArg,
+        EmitEventArg,
+        EmitLogArg,
+        InvokeResult,
+        LogLevel,
+    },
     component::ComponentManager,
     context::Context,
     get_context,
-    models::ComponentAddress,
+    models::{ComponentAddress, Metadata},
     prelude::AccessRules,
 };
 
@@ -75,6 +84,15 @@ impl TariEngine {
         });
     }
 
+    /// Emits a structured event that is included in the transaction result. The event is tagged with the calling
+    /// template and component so that it can be queried by topic.
+    pub fn emit_event<T: Into<String>>(&self, topic: T, payload: Metadata) {
+        call_engine::<_, ()>(EngineOp::EmitEvent, &EmitEventArg {
+            topic: topic.into(),
+            payload,
+        });
+    }
+
     pub fn component_manager(&self, component_address: ComponentAddress) -> ComponentManager {
         ComponentManager::new(component_address)
     }
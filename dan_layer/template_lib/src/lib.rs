@@ -31,12 +31,15 @@ pub mod models;
 
 pub mod component;
 pub mod consensus;
+pub mod rand;
 
 mod context;
 pub use context::{get_context, init_context, AbiContext};
 
 pub mod resource;
 
+pub mod template;
+
 pub mod crypto;
 
 // ---------------------------------------- WASM target exports ------------------------------------------------
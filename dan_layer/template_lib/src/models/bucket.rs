@@ -25,7 +25,7 @@ use tari_template_abi::{call_engine, EngineOp};
 
 use crate::{
     args::{BucketAction, BucketInvokeArg, BucketRef, InvokeResult},
-    models::{Amount, ConfidentialWithdrawProof, ResourceAddress},
+    models::{Amount, ConfidentialWithdrawProof, Proof, ResourceAddress},
     prelude::ResourceType,
 };
 
@@ -118,6 +118,17 @@ impl Bucket {
         resp.decode().expect("Bucket GetAmount returned invalid amount")
     }
 
+    /// Creates a proof of all the tokens held in this bucket
+    pub fn create_proof(&self) -> Proof {
+        let resp: InvokeResult = call_engine(EngineOp::BucketInvoke, &BucketInvokeArg {
+            bucket_ref: BucketRef::Ref(self.id),
+            action: BucketAction::CreateProof,
+            args: invoke_args![],
+        });
+
+        resp.decode().expect("Bucket CreateProof returned invalid proof")
+    }
+
     pub fn reveal_confidential(&mut self, proof: ConfidentialWithdrawProof) -> Bucket {
         let resp: InvokeResult = call_engine(EngineOp::BucketInvoke, &BucketInvokeArg {
             bucket_ref: BucketRef::Ref(self.id),
@@ -27,7 +27,12 @@ use std::{
 
 use tari_bor::{borsh, Decode, Encode};
 
-use crate::{hash::HashParseError, models::TemplateAddress, prelude::AccessRules, Hash};
+use crate::{
+    hash::HashParseError,
+    models::{KeyValueStoreId, TemplateAddress},
+    prelude::AccessRules,
+    Hash,
+};
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -96,6 +101,10 @@ pub struct ComponentHeader {
     pub access_rules: AccessRules,
     // TODO: Split the state from the header
     pub state: ComponentBody,
+    /// The key-value stores owned by the component. Entries of these stores can only be written while a method of the
+    /// component is executing.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub key_value_stores: Vec<KeyValueStoreId>,
 }
 
 impl ComponentHeader {
@@ -0,0 +1,200 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use std::{
+    fmt,
+    fmt::{Display, Formatter},
+    io,
+    io::Write,
+    marker::PhantomData,
+};
+
+use tari_bor::{borsh, decode_exact, encode, Decode, Encode};
+use tari_template_abi::{call_engine, EngineOp};
+
+use crate::{
+    args::{InvokeResult, KeyValueStoreAction, KeyValueStoreInvokeArg, KeyValueStoreRef},
+    hash::HashParseError,
+    Hash,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyValueStoreId(Hash);
+
+impl KeyValueStoreId {
+    pub fn new(id: Hash) -> Self {
+        Self(id)
+    }
+
+    pub fn hash(&self) -> &Hash {
+        &self.0
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, HashParseError> {
+        let hash = Hash::from_hex(hex)?;
+        Ok(Self::new(hash))
+    }
+}
+
+impl From<Hash> for KeyValueStoreId {
+    fn from(id: Hash) -> Self {
+        Self::new(id)
+    }
+}
+
+impl Display for KeyValueStoreId {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "kvstore_{}", self.0)
+    }
+}
+
+/// The address of a single entry in a key-value store. Each entry is stored in its own substate, keyed by the encoded
+/// key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyValueEntryAddress {
+    store_id: KeyValueStoreId,
+    key: Vec<u8>,
+}
+
+impl KeyValueEntryAddress {
+    pub fn new(store_id: KeyValueStoreId, key: Vec<u8>) -> Self {
+        Self { store_id, key }
+    }
+
+    pub fn store_id(&self) -> &KeyValueStoreId {
+        &self.store_id
+    }
+
+    /// The borsh-encoded key of the entry
+    pub fn key(&self) -> &[u8] {
+        &self.key
+    }
+}
+
+impl Display for KeyValueEntryAddress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} key_", self.store_id)?;
+        for b in &self.key {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// A map whose entries are each stored in their own substate. Unlike a collection held in component state, only the
+/// entries that are read or written are loaded by the engine and only the changed entries are included in the
+/// transaction result, so large collections do not have to be rewritten on every call.
+///
+/// The store itself only holds the id, so it can be kept in component state.
+pub struct KeyValueStore<K, V> {
+    id: KeyValueStoreId,
+    _types: PhantomData<(K, V)>,
+}
+
+impl<K: Encode, V: Encode + Decode> KeyValueStore<K, V> {
+    pub fn new() -> Self {
+        let resp: InvokeResult = call_engine(EngineOp::KeyValueStoreInvoke, &KeyValueStoreInvokeArg {
+            store_ref: KeyValueStoreRef::New,
+            action: KeyValueStoreAction::Create,
+            args: invoke_args![],
+        });
+        let id = resp.decode().expect("KeyValueStore Create returned invalid id");
+        Self::from_id(id)
+    }
+
+    pub fn from_id(id: KeyValueStoreId) -> Self {
+        Self {
+            id,
+            _types: PhantomData,
+        }
+    }
+
+    pub fn id(&self) -> KeyValueStoreId {
+        self.id
+    }
+
+    pub fn get(&self, key: &K) -> Option<V> {
+        let resp: InvokeResult = call_engine(EngineOp::KeyValueStoreInvoke, &KeyValueStoreInvokeArg {
+            store_ref: KeyValueStoreRef::Ref(self.id),
+            action: KeyValueStoreAction::Get,
+            args: invoke_args![encode_key(key)],
+        });
+        decode_value(resp, "Get")
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Inserts the value, returning the previous value if the key was already present
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let value = encode(&value).expect("Failed to encode KeyValueStore value");
+        let resp: InvokeResult = call_engine(EngineOp::KeyValueStoreInvoke, &KeyValueStoreInvokeArg {
+            store_ref: KeyValueStoreRef::Ref(self.id),
+            action: KeyValueStoreAction::Insert,
+            args: invoke_args![encode_key(&key), value],
+        });
+        decode_value(resp, "Insert")
+    }
+
+    /// Removes the entry, returning its value if the key was present
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let resp: InvokeResult = call_engine(EngineOp::KeyValueStoreInvoke, &KeyValueStoreInvokeArg {
+            store_ref: KeyValueStoreRef::Ref(self.id),
+            action: KeyValueStoreAction::Remove,
+            args: invoke_args![encode_key(key)],
+        });
+        decode_value(resp, "Remove")
+    }
+}
+
+impl<K: Encode, V: Encode + Decode> Default for KeyValueStore<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn encode_key<K: Encode>(key: &K) -> Vec<u8> {
+    encode(key).expect("Failed to encode KeyValueStore key")
+}
+
+fn decode_value<V: Decode>(resp: InvokeResult, action: &str) -> Option<V> {
+    let value: Option<Vec<u8>> = resp
+        .decode()
+        .unwrap_or_else(|_| panic!("KeyValueStore {} returned invalid value", action));
+    value.map(|v| decode_exact(&v).unwrap_or_else(|_| panic!("KeyValueStore {} failed to decode value", action)))
+}
+
+// The store is a typed handle to its id, so it is encoded as the id only and can be cloned regardless of K and V
+impl<K, V> Encode for KeyValueStore<K, V> {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.id.serialize(writer)
+    }
+}
+
+impl<K, V> Decode for KeyValueStore<K, V> {
+    fn deserialize(buf: &mut &[u8]) -> io::Result<Self> {
+        let id = KeyValueStoreId::deserialize(buf)?;
+        Ok(Self {
+            id,
+            _types: PhantomData,
+        })
+    }
+}
+
+impl<K, V> Clone for KeyValueStore<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id,
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<K, V> fmt::Debug for KeyValueStore<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyValueStore").field("id", &self.id).finish()
+    }
+}
@@ -35,6 +35,9 @@ pub use component::*;
 mod confidential_proof;
 pub use confidential_proof::*;
 
+mod key_value_store;
+pub use key_value_store::{KeyValueEntryAddress, KeyValueStore, KeyValueStoreId};
+
 mod layer_one_commitment;
 pub use layer_one_commitment::UnclaimedConfidentialOutputAddress;
 
@@ -44,6 +47,9 @@ pub use metadata::Metadata;
 mod non_fungible;
 pub use non_fungible::{NonFungible, NonFungibleAddress, NonFungibleId};
 
+mod proof;
+pub use proof::{Proof, ProofId};
+
 mod resource;
 pub use resource::ResourceAddress;
 
@@ -54,4 +60,4 @@ mod template;
 pub use template::TemplateAddress;
 
 mod vault;
-pub use vault::{Vault, VaultId, VaultRef};
+pub use vault::{Vault, VaultId, VaultLock, VaultRef};
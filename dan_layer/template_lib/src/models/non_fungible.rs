@@ -29,6 +29,8 @@ pub enum NonFungibleId {
 }
 
 impl NonFungibleId {
+    /// Returns a new unique id. The id is derived from the transaction hash and is therefore predictable by the
+    /// transaction sender. Use [rand](crate::rand) for ids that must not be known in advance.
     pub fn random() -> Self {
         let uuid = call_engine(EngineOp::GenerateUniqueId, &());
         Self::U256(uuid)
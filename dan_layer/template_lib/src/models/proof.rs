@@ -0,0 +1,72 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_bor::{borsh, Decode, Encode};
+use tari_template_abi::{call_engine, EngineOp};
+
+use crate::{
+    args::{InvokeResult, ProofAction, ProofInvokeArg},
+    models::{Amount, NonFungibleId, ResourceAddress},
+};
+
+pub type ProofId = u32;
+
+/// Proof that the creator has access to some amount of a resource held in a vault or bucket, without taking custody of
+/// it. Proofs are used to satisfy access rules for the remainder of the transaction, or until they are dropped.
+#[derive(Debug, Clone, Decode, Encode)]
+pub struct Proof {
+    id: ProofId,
+}
+
+impl Proof {
+    pub fn from_id(id: ProofId) -> Self {
+        Self { id }
+    }
+
+    pub fn id(&self) -> ProofId {
+        self.id
+    }
+
+    pub fn resource_address(&self) -> ResourceAddress {
+        let resp: InvokeResult = call_engine(EngineOp::ProofInvoke, &ProofInvokeArg {
+            proof_id: self.id,
+            action: ProofAction::GetResourceAddress,
+            args: invoke_args![],
+        });
+
+        resp.decode()
+            .expect("Proof GetResourceAddress returned invalid resource address")
+    }
+
+    pub fn amount(&self) -> Amount {
+        let resp: InvokeResult = call_engine(EngineOp::ProofInvoke, &ProofInvokeArg {
+            proof_id: self.id,
+            action: ProofAction::GetAmount,
+            args: invoke_args![],
+        });
+
+        resp.decode().expect("Proof GetAmount returned invalid amount")
+    }
+
+    pub fn get_non_fungibles(&self) -> Vec<NonFungibleId> {
+        let resp: InvokeResult = call_engine(EngineOp::ProofInvoke, &ProofInvokeArg {
+            proof_id: self.id,
+            action: ProofAction::GetNonFungibles,
+            args: invoke_args![],
+        });
+
+        resp.decode()
+            .expect("Proof GetNonFungibles returned invalid non-fungibles")
+    }
+
+    /// Drops the proof so that it can no longer be used to satisfy access rules
+    pub fn drop(self) {
+        let resp: InvokeResult = call_engine(EngineOp::ProofInvoke, &ProofInvokeArg {
+            proof_id: self.id,
+            action: ProofAction::Drop,
+            args: invoke_args![],
+        });
+
+        resp.decode().expect("Proof Drop returned invalid result")
+    }
+}
@@ -24,6 +24,7 @@ use tari_bor::{borsh, Decode, Encode};
 use tari_template_abi::{
     call_engine,
     rust::{
+        collections::BTreeSet,
         fmt,
         fmt::{Display, Formatter},
     },
@@ -33,7 +34,7 @@ use tari_template_abi::{
 use crate::{
     args::{ConfidentialRevealArg, InvokeResult, VaultAction, VaultInvokeArg, VaultWithdrawArg},
     hash::HashParseError,
-    models::{Amount, Bucket, ConfidentialWithdrawProof, NonFungibleId, ResourceAddress},
+    models::{Amount, Bucket, ConfidentialWithdrawProof, NonFungibleId, Proof, ResourceAddress},
     Hash,
 };
 
@@ -90,6 +91,36 @@ impl VaultRef {
     }
 }
 
+/// A lock that prevents resources from being withdrawn from a vault until the condition is met. The lock is enforced
+/// by the engine, deposits are always allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VaultLock {
+    /// Locked until the current epoch is greater than or equal to the given epoch
+    UntilEpoch(u64),
+    /// Locked until the current epoch timestamp (seconds since the unix epoch) is greater than or equal to the given
+    /// timestamp
+    UntilTimestamp(u64),
+}
+
+impl VaultLock {
+    pub fn is_unlocked(&self, current_epoch: u64, current_epoch_timestamp: u64) -> bool {
+        match self {
+            VaultLock::UntilEpoch(epoch) => current_epoch >= *epoch,
+            VaultLock::UntilTimestamp(timestamp) => current_epoch_timestamp >= *timestamp,
+        }
+    }
+}
+
+impl Display for VaultLock {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            VaultLock::UntilEpoch(epoch) => write!(f, "locked until epoch {}", epoch),
+            VaultLock::UntilTimestamp(timestamp) => write!(f, "locked until timestamp {}", timestamp),
+        }
+    }
+}
+
 #[derive(Clone, Debug, Decode, Encode)]
 pub struct Vault {
     vault_id: VaultId,
@@ -110,6 +141,28 @@ impl Vault {
         }
     }
 
+    /// Creates an empty vault from which nothing can be withdrawn until the lock condition is met
+    pub fn new_locked(resource_address: ResourceAddress, lock: VaultLock) -> Self {
+        let resp: InvokeResult = call_engine(EngineOp::VaultInvoke, &VaultInvokeArg {
+            vault_ref: VaultRef::Vault {
+                address: resource_address,
+            },
+            action: VaultAction::CreateLocked,
+            args: invoke_args![lock],
+        });
+
+        Self {
+            vault_id: resp.decode().unwrap(),
+        }
+    }
+
+    pub fn from_bucket_locked(bucket: Bucket, lock: VaultLock) -> Self {
+        let resource_address = bucket.resource_address();
+        let mut vault = Self::new_locked(resource_address, lock);
+        vault.deposit(bucket);
+        vault
+    }
+
     pub fn from_bucket(bucket: Bucket) -> Self {
         let resource_address = bucket.resource_address();
         let mut vault = Self::new_empty(resource_address);
@@ -222,11 +275,67 @@ impl Vault {
             .expect("get_non_fungible_ids returned invalid non fungible ids")
     }
 
+    /// Locks `max_fee` of revealed Tari from this vault to pay the transaction fee. The amount charged is burnt, and
+    /// any amount that is not charged is returned to this vault when the transaction is finalized.
+    pub fn pay_fee(&mut self, max_fee: Amount) {
+        let resp: InvokeResult = call_engine(EngineOp::VaultInvoke, &VaultInvokeArg {
+            vault_ref: self.vault_ref(),
+            action: VaultAction::PayFee,
+            args: invoke_args![max_fee],
+        });
+
+        resp.decode::<()>().expect("pay_fee failed");
+    }
+
+    /// Creates a proof of all the tokens held in this vault
+    pub fn create_proof(&self) -> Proof {
+        let resp: InvokeResult = call_engine(EngineOp::VaultInvoke, &VaultInvokeArg {
+            vault_ref: self.vault_ref(),
+            action: VaultAction::CreateProofByResource,
+            args: invoke_args![],
+        });
+
+        resp.decode().expect("CreateProofByResource returned invalid proof")
+    }
+
+    /// Creates a proof of at least `amount` tokens held in this vault
+    pub fn create_proof_of_amount(&self, amount: Amount) -> Proof {
+        let resp: InvokeResult = call_engine(EngineOp::VaultInvoke, &VaultInvokeArg {
+            vault_ref: self.vault_ref(),
+            action: VaultAction::CreateProofByAmount,
+            args: invoke_args![amount],
+        });
+
+        resp.decode().expect("CreateProofByAmount returned invalid proof")
+    }
+
+    /// Creates a proof of the given non-fungibles held in this vault
+    pub fn create_proof_of_non_fungibles<I: IntoIterator<Item = NonFungibleId>>(&self, ids: I) -> Proof {
+        let ids: BTreeSet<NonFungibleId> = ids.into_iter().collect();
+        let resp: InvokeResult = call_engine(EngineOp::VaultInvoke, &VaultInvokeArg {
+            vault_ref: self.vault_ref(),
+            action: VaultAction::CreateProofByNonFungibles,
+            args: invoke_args![ids],
+        });
+
+        resp.decode().expect("CreateProofByNonFungibles returned invalid proof")
+    }
+
     pub fn join_confidential(&mut self, proof: ConfidentialWithdrawProof) {
         let bucket = self.withdraw_confidential(proof);
         self.deposit(bucket);
     }
 
+    pub fn get_lock(&self) -> Option<VaultLock> {
+        let resp: InvokeResult = call_engine(EngineOp::VaultInvoke, &VaultInvokeArg {
+            vault_ref: self.vault_ref(),
+            action: VaultAction::GetLock,
+            args: invoke_args![],
+        });
+
+        resp.decode().expect("GetLock returned invalid vault lock")
+    }
+
     pub fn vault_id(&self) -> VaultId {
         self.vault_id
     }
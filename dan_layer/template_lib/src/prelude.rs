@@ -25,7 +25,7 @@ pub use tari_bor::encode;
 pub use tari_template_macros::template;
 
 pub use crate::{
-    auth::{AccessRule, AccessRules, RestrictedAccessRule::*},
+    auth::{AccessRule, AccessRules, ResourceAccessRules, RestrictedAccessRule::*},
     component::{
         interface::{ComponentInstanceInterface, ComponentInterface},
         ComponentManager,
@@ -38,12 +38,17 @@ pub use crate::{
         ComponentAddress,
         ConfidentialOutputProof,
         ConfidentialWithdrawProof,
+        KeyValueStore,
+        KeyValueStoreId,
         Metadata,
         NonFungible,
         NonFungibleAddress,
         NonFungibleId,
+        Proof,
         ResourceAddress,
         Vault,
+        VaultLock,
     },
     resource::{ResourceBuilder, ResourceManager, ResourceType},
+    template::TemplateManager,
 };
@@ -0,0 +1,57 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+//! Randomness for templates.
+//!
+//! Random values are derived by the engine from the current epoch beacon, the transaction's ID seed and a counter, so
+//! every validator computes the same values for a transaction. The sender chooses the ID seed and the beacon of the
+//! current epoch is public, so the sender can compute the values before submitting the transaction. They must not be
+//! used where the sender benefits from choosing the outcome, e.g. to pick the winner of a lottery.
+
+use tari_template_abi::{call_engine, EngineOp};
+
+use crate::args::{ConsensusAction, ConsensusInvokeArg, InvokeResult};
+
+/// Returns `len` random bytes
+pub fn random_bytes(len: usize) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(len);
+    while bytes.len() < len {
+        let chunk = next_chunk();
+        let remaining = len - bytes.len();
+        bytes.extend_from_slice(&chunk[..remaining.min(chunk.len())]);
+    }
+    bytes
+}
+
+pub fn random_u32() -> u32 {
+    let chunk = next_chunk();
+    u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])
+}
+
+pub fn random_u64() -> u64 {
+    let chunk = next_chunk();
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&chunk[..8]);
+    u64::from_le_bytes(buf)
+}
+
+/// Returns a random value in the range `0..max` (exclusive). Panics if `max` is zero.
+pub fn random_range(max: u64) -> u64 {
+    assert!(max > 0, "random_range: max must be greater than zero");
+    // Reject values above the largest multiple of max to avoid modulo bias
+    let zone = u64::MAX - (u64::MAX % max);
+    loop {
+        let value = random_u64();
+        if value < zone {
+            return value % max;
+        }
+    }
+}
+
+fn next_chunk() -> [u8; 32] {
+    let resp: InvokeResult = call_engine(EngineOp::ConsensusInvoke, &ConsensusInvokeArg {
+        action: ConsensusAction::GenerateRandomBytes,
+    });
+    resp.decode()
+        .expect("Consensus GenerateRandomBytes returned invalid bytes")
+}
@@ -3,6 +3,7 @@
 
 use crate::{
     args::MintArg,
+    auth::{AccessRule, ResourceAccessRules},
     models::{Bucket, Metadata, ResourceAddress},
     prelude::ConfidentialOutputProof,
     resource::{builder::TOKEN_SYMBOL, ResourceManager, ResourceType},
@@ -11,6 +12,7 @@ use crate::{
 pub struct ConfidentialResourceBuilder {
     initial_supply_proof: Option<ConfidentialOutputProof>,
     metadata: Metadata,
+    access_rules: ResourceAccessRules,
 }
 
 impl ConfidentialResourceBuilder {
@@ -18,6 +20,7 @@ impl ConfidentialResourceBuilder {
         Self {
             initial_supply_proof: None,
             metadata: Metadata::new(),
+            access_rules: ResourceAccessRules::new(),
         }
     }
 
@@ -31,6 +34,18 @@ impl ConfidentialResourceBuilder {
         self
     }
 
+    /// Sets the access rule that must be satisfied to mint new tokens of this resource
+    pub fn mintable(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.mintable(rule);
+        self
+    }
+
+    /// Sets the access rule that must be satisfied to burn tokens of this resource
+    pub fn burnable(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.burnable(rule);
+        self
+    }
+
     pub fn initial_supply(mut self, initial_supply: ConfidentialOutputProof) -> Self {
         self.initial_supply_proof = Some(initial_supply);
         self
@@ -42,7 +57,7 @@ impl ConfidentialResourceBuilder {
             self.initial_supply_proof.is_some(),
             "call build_bucket when initial supply set"
         );
-        let (address, _) = Self::build_internal(self.metadata, None);
+        let (address, _) = Self::build_internal(self.access_rules, self.metadata, None);
         address
     }
 
@@ -53,11 +68,15 @@ impl ConfidentialResourceBuilder {
                 .expect("[build_bucket] initial supply not set"),
         };
 
-        let (_, bucket) = Self::build_internal(self.metadata, Some(mint_args));
+        let (_, bucket) = Self::build_internal(self.access_rules, self.metadata, Some(mint_args));
         bucket.expect("[build_bucket] Bucket not returned from system")
     }
 
-    fn build_internal(metadata: Metadata, mint_args: Option<MintArg>) -> (ResourceAddress, Option<Bucket>) {
-        ResourceManager::new().create(ResourceType::Confidential, metadata, mint_args)
+    fn build_internal(
+        access_rules: ResourceAccessRules,
+        metadata: Metadata,
+        mint_args: Option<MintArg>,
+    ) -> (ResourceAddress, Option<Bucket>) {
+        ResourceManager::new().create(ResourceType::Confidential, access_rules, metadata, mint_args)
     }
 }
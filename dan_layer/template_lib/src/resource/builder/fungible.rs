@@ -3,6 +3,7 @@
 
 use crate::{
     args::MintArg,
+    auth::{AccessRule, ResourceAccessRules},
     models::{Amount, Bucket, Metadata, ResourceAddress},
     resource::{builder::TOKEN_SYMBOL, ResourceManager, ResourceType},
 };
@@ -10,6 +11,7 @@ use crate::{
 pub struct FungibleResourceBuilder {
     initial_supply: Amount,
     metadata: Metadata,
+    access_rules: ResourceAccessRules,
 }
 
 impl FungibleResourceBuilder {
@@ -17,6 +19,7 @@ impl FungibleResourceBuilder {
         Self {
             initial_supply: Amount::zero(),
             metadata: Metadata::new(),
+            access_rules: ResourceAccessRules::new(),
         }
     }
 
@@ -30,6 +33,18 @@ impl FungibleResourceBuilder {
         self
     }
 
+    /// Sets the access rule that must be satisfied to mint new tokens of this resource
+    pub fn mintable(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.mintable(rule);
+        self
+    }
+
+    /// Sets the access rule that must be satisfied to burn tokens of this resource
+    pub fn burnable(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.burnable(rule);
+        self
+    }
+
     pub fn initial_supply<A: Into<Amount>>(mut self, initial_supply: A) -> Self {
         self.initial_supply = initial_supply.into();
         self
@@ -41,7 +56,7 @@ impl FungibleResourceBuilder {
             self.initial_supply.is_zero(),
             "call build_bucket when initial supply set"
         );
-        let (address, _) = Self::build_internal(self.metadata, None);
+        let (address, _) = Self::build_internal(self.access_rules, self.metadata, None);
         address
     }
 
@@ -50,11 +65,15 @@ impl FungibleResourceBuilder {
             amount: self.initial_supply,
         };
 
-        let (_, bucket) = Self::build_internal(self.metadata, Some(mint_args));
+        let (_, bucket) = Self::build_internal(self.access_rules, self.metadata, Some(mint_args));
         bucket.expect("[build_bucket] Bucket not returned from system")
     }
 
-    fn build_internal(metadata: Metadata, mint_args: Option<MintArg>) -> (ResourceAddress, Option<Bucket>) {
-        ResourceManager::new().create(ResourceType::Fungible, metadata, mint_args)
+    fn build_internal(
+        access_rules: ResourceAccessRules,
+        metadata: Metadata,
+        mint_args: Option<MintArg>,
+    ) -> (ResourceAddress, Option<Bucket>) {
+        ResourceManager::new().create(ResourceType::Fungible, access_rules, metadata, mint_args)
     }
 }
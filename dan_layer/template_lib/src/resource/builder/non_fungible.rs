@@ -6,12 +6,14 @@ use tari_template_abi::rust::{collections::HashMap, fmt, ops::RangeInclusive};
 
 use crate::{
     args::MintArg,
+    auth::{AccessRule, ResourceAccessRules},
     models::{Bucket, Metadata, NonFungibleId, ResourceAddress},
     resource::{builder::TOKEN_SYMBOL, ResourceManager, ResourceType},
 };
 
 pub struct NonFungibleResourceBuilder {
     metadata: Metadata,
+    access_rules: ResourceAccessRules,
     tokens_ids: HashMap<NonFungibleId, (Vec<u8>, Vec<u8>)>,
 }
 
@@ -19,6 +21,7 @@ impl NonFungibleResourceBuilder {
     pub(super) fn new() -> Self {
         Self {
             metadata: Metadata::new(),
+            access_rules: ResourceAccessRules::new(),
             tokens_ids: HashMap::new(),
         }
     }
@@ -33,6 +36,24 @@ impl NonFungibleResourceBuilder {
         self
     }
 
+    /// Sets the access rule that must be satisfied to mint new tokens of this resource
+    pub fn mintable(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.mintable(rule);
+        self
+    }
+
+    /// Sets the access rule that must be satisfied to burn tokens of this resource
+    pub fn burnable(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.burnable(rule);
+        self
+    }
+
+    /// Sets the access rule that must be satisfied to update the mutable data of a non-fungible in this resource
+    pub fn updatable_non_fungible_data(mut self, rule: AccessRule) -> Self {
+        self.access_rules = self.access_rules.update_non_fungible_data(rule);
+        self
+    }
+
     pub fn with_non_fungibles<'a, I, T, U>(mut self, tokens: I) -> Self
     where
         I: IntoIterator<Item = (NonFungibleId, (&'a T, &'a U))>,
@@ -60,7 +81,7 @@ impl NonFungibleResourceBuilder {
     pub fn build(self) -> ResourceAddress {
         // TODO: Improve API
         assert!(self.tokens_ids.is_empty(), "call build_bucket with initial tokens set");
-        let (address, _) = Self::build_internal(self.metadata, None);
+        let (address, _) = Self::build_internal(self.access_rules, self.metadata, None);
         address
     }
 
@@ -69,11 +90,15 @@ impl NonFungibleResourceBuilder {
             tokens: self.tokens_ids,
         };
 
-        let (_, bucket) = Self::build_internal(self.metadata, Some(mint_args));
+        let (_, bucket) = Self::build_internal(self.access_rules, self.metadata, Some(mint_args));
         bucket.expect("[build_bucket] Bucket not returned from system")
     }
 
-    fn build_internal(metadata: Metadata, mint_args: Option<MintArg>) -> (ResourceAddress, Option<Bucket>) {
-        ResourceManager::new().create(ResourceType::NonFungible, metadata, mint_args)
+    fn build_internal(
+        access_rules: ResourceAccessRules,
+        metadata: Metadata,
+        mint_args: Option<MintArg>,
+    ) -> (ResourceAddress, Option<Bucket>) {
+        ResourceManager::new().create(ResourceType::NonFungible, access_rules, metadata, mint_args)
     }
 }
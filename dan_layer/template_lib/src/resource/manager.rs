@@ -36,7 +36,7 @@ use crate::{
         ResourceUpdateNonFungibleDataArg,
     },
     models::{Amount, Bucket, Metadata, NonFungible, NonFungibleId, ResourceAddress},
-    prelude::ResourceType,
+    prelude::{ResourceAccessRules, ResourceType},
 };
 
 #[derive(Debug)]
@@ -77,6 +77,7 @@ impl ResourceManager {
     pub fn create(
         &mut self,
         resource_type: ResourceType,
+        access_rules: ResourceAccessRules,
         metadata: Metadata,
         mint_arg: Option<MintArg>,
     ) -> (ResourceAddress, Option<Bucket>) {
@@ -85,6 +86,7 @@ impl ResourceManager {
             action: ResourceAction::Create,
             args: invoke_args![CreateResourceArg {
                 resource_type,
+                access_rules,
                 metadata,
                 mint_arg
             }],
@@ -0,0 +1,36 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use tari_bor::Decode;
+use tari_template_abi::{call_engine, EngineOp};
+
+use crate::{
+    args::{CallAction, CallFunctionArg, CallInvokeArg, InvokeResult},
+    models::TemplateAddress,
+};
+
+pub struct TemplateManager {
+    template_address: TemplateAddress,
+}
+
+impl TemplateManager {
+    pub fn get(template_address: TemplateAddress) -> Self {
+        Self { template_address }
+    }
+
+    /// Calls a function on the template. Any buckets or proofs passed as arguments are moved to the callee.
+    pub fn call_function<T: Decode>(&self, function: &str, args: Vec<Vec<u8>>) -> T {
+        let result = call_engine::<_, InvokeResult>(EngineOp::CallInvoke, &CallInvokeArg {
+            action: CallAction::CallFunction,
+            args: invoke_args![CallFunctionArg {
+                template_address: self.template_address,
+                function: function.to_string(),
+                args,
+            }],
+        });
+
+        result
+            .decode()
+            .expect("failed to decode template function call result from engine")
+    }
+}
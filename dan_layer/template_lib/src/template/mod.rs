@@ -0,0 +1,5 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+mod manager;
+pub use manager::TemplateManager;
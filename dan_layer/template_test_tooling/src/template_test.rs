@@ -13,6 +13,7 @@ use tari_crypto::{ristretto::RistrettoSecretKey, tari_utilities::ByteArray};
 use tari_dan_common_types::crypto::create_key_pair;
 use tari_dan_engine::{
     bootstrap_state,
+    fees::{FeeModule, FeeTable, FuelLimitModule},
     packager::{LoadedTemplate, Package, TemplateModuleLoader},
     runtime::{AuthParams, ConsensusContext, RuntimeModule},
     state_store::{memory::MemoryStateStore, AtomicDb, StateReader, StateStoreError, StateWriter},
@@ -23,14 +24,16 @@ use tari_engine_types::{
     commit_result::FinalizeResult,
     hashing::{hasher, EngineHashDomainLabel},
     instruction::Instruction,
+    resource_container::ResourceContainer,
     substate::{Substate, SubstateAddress, SubstateDiff},
+    vault::Vault,
 };
 use tari_template_builtin::{get_template_builtin, ACCOUNT_TEMPLATE_ADDRESS};
 use tari_template_lib::{
     args,
     args::Arg,
     crypto::RistrettoPublicKeyBytes,
-    models::{ComponentAddress, ComponentHeader, NonFungibleAddress, TemplateAddress},
+    models::{Amount, ComponentAddress, ComponentHeader, NonFungibleAddress, TemplateAddress, VaultId},
 };
 use tari_transaction::Transaction;
 use tari_transaction_manifest::{parse_manifest, ManifestValue};
@@ -46,6 +49,8 @@ pub struct TemplateTest {
     state_store: MemoryStateStore,
     // TODO: cleanup
     consensus_context: ConsensusContext,
+    fee_table: Option<FeeTable>,
+    fuel_limit: Option<u64>,
 }
 
 impl TemplateTest {
@@ -83,14 +88,57 @@ impl TemplateTest {
             last_outputs: HashSet::new(),
             state_store,
             // TODO: cleanup
-            consensus_context: ConsensusContext { current_epoch: 0 },
+            consensus_context: ConsensusContext {
+                current_epoch: 0,
+                current_epoch_timestamp: 0,
+                current_epoch_beacon: [0; 32],
+            },
+            fee_table: None,
+            fuel_limit: None,
         }
     }
 
+    /// Charges fees for all subsequent transactions according to the given fee table
+    pub fn enable_fees(&mut self, fee_table: FeeTable) {
+        self.fee_table = Some(fee_table);
+    }
+
+    pub fn disable_fees(&mut self) {
+        self.fee_table = None;
+    }
+
+    /// Aborts all subsequent transactions that consume more than the given fuel, or lifts the limit if `None`
+    pub fn set_fuel_limit(&mut self, fuel_limit: Option<u64>) {
+        self.fuel_limit = fuel_limit;
+    }
+
+    /// Sets the revealed balance of a confidential vault. The funds are created out of thin air, so this is only
+    /// useful for funding fee payments in tests.
+    pub fn fund_vault(&mut self, vault_id: VaultId, amount: Amount) {
+        let address = SubstateAddress::Vault(vault_id);
+        let substate = self.read_only_state_store().get_substate(&address).unwrap();
+        let version = substate.version();
+        let vault = substate.into_substate_value().into_vault().unwrap();
+        let resource = ResourceContainer::confidential(*vault.resource_address(), None, amount);
+
+        let mut diff = SubstateDiff::new();
+        diff.down(address.clone(), version);
+        diff.up(address, Substate::new(version + 1, Vault::new(vault_id, resource)));
+        self.commit_diff(&diff);
+    }
+
     pub fn set_consensus_context(&mut self, consensus: ConsensusContext) {
         self.consensus_context = consensus;
     }
 
+    /// Registers the named template as an upgrade of the predecessor template
+    pub fn set_template_predecessor(&mut self, template_name: &str, predecessor_name: &str) {
+        let template_address = self.get_template_address(template_name);
+        let predecessor_address = self.get_template_address(predecessor_name);
+        self.package
+            .set_template_predecessor(template_address, predecessor_address);
+    }
+
     pub fn read_only_state_store(&self) -> ReadOnlyStateStore {
         ReadOnlyStateStore::new(self.state_store.clone())
     }
@@ -130,9 +178,9 @@ impl TemplateTest {
         tx.commit().unwrap();
     }
 
-    pub fn get_module(&self, module_name: &str) -> &LoadedWasmTemplate {
+    pub fn get_module(&self, module_name: &str) -> LoadedWasmTemplate {
         let addr = self.name_to_template.get(module_name).unwrap();
-        match self.package.get_template_by_address(addr).unwrap() {
+        match self.package.get_template_by_address(addr).unwrap().unwrap() {
             LoadedTemplate::Wasm(wasm) => wasm,
         }
     }
@@ -216,7 +264,13 @@ impl TemplateTest {
         builder.sign(&self.secret_key);
         let transaction = builder.build();
 
-        let modules: Vec<Box<dyn RuntimeModule>> = vec![Box::new(self.track_calls.clone())];
+        let mut modules: Vec<Box<dyn RuntimeModule>> = vec![Box::new(self.track_calls.clone())];
+        if let Some(fee_table) = self.fee_table.clone() {
+            modules.push(Box::new(FeeModule::new(fee_table)));
+        }
+        if let Some(fuel_limit) = self.fuel_limit {
+            modules.push(Box::new(FuelLimitModule::new(fuel_limit)));
+        }
         let auth_params = AuthParams {
             initial_ownership_proofs: proofs,
         };
@@ -248,6 +302,11 @@ impl TemplateTest {
         // It is convenient to commit the state back to the staged state store in tests.
         self.commit_diff(diff);
 
+        // Only the fee payment was committed
+        if let Some(reason) = result.result.reject() {
+            return Err(anyhow!("Transaction was rejected: {}", reason));
+        }
+
         Ok(result)
     }
 
@@ -302,6 +361,7 @@ pub enum SubstateType {
     Vault,
     NonFungible,
     NonFungibleIndex,
+    KeyValueEntry,
 }
 
 impl SubstateType {
@@ -313,6 +373,7 @@ impl SubstateType {
             (SubstateType::Vault, SubstateAddress::Vault(_)) => true,
             (SubstateType::NonFungible, SubstateAddress::NonFungible(_)) => true,
             (SubstateType::NonFungibleIndex, SubstateAddress::NonFungibleIndex(_)) => true,
+            (SubstateType::KeyValueEntry, SubstateAddress::KeyValueEntry(_)) => true,
             _ => false,
         }
     }
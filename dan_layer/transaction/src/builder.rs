@@ -3,11 +3,19 @@
 
 use std::convert::TryFrom;
 
+use rand::rngs::OsRng;
+use serde::{Deserialize, Serialize};
 use tari_common_types::types::{PrivateKey, PublicKey};
-use tari_crypto::{keys::PublicKey as PublicKeyTrait, ristretto::RistrettoPublicKey};
-use tari_dan_common_types::ShardId;
+use tari_crypto::{
+    keys::PublicKey as PublicKeyTrait,
+    ristretto::{RistrettoPublicKey, RistrettoSchnorr, RistrettoSecretKey},
+};
+use tari_dan_common_types::{Epoch, ShardId};
 use tari_engine_types::{instruction::Instruction, substate::SubstateAddress};
-use tari_template_lib::models::{NonFungibleAddress, NonFungibleId, NonFungibleIndexAddress, ResourceAddress};
+use tari_template_lib::{
+    models::{Amount, ComponentAddress, NonFungibleAddress, NonFungibleId, NonFungibleIndexAddress, ResourceAddress},
+    Hash,
+};
 
 use super::Transaction;
 use crate::{
@@ -16,15 +24,23 @@ use crate::{
     transaction::TransactionMeta,
     InstructionSignature,
     ObjectClaim,
+    SubstateRequirements,
+    TransactionBody,
+    TransactionSignature,
 };
 
-#[derive(Debug, Clone, Default)]
+/// Builds a [`Transaction`]. The builder is serializable so that a partially signed transaction can be passed between
+/// its signers, each of which adds their signature, before it is built.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TransactionBuilder {
     instructions: Vec<Instruction>,
     fee: u64,
     meta: TransactionMeta,
+    min_epoch: Option<Epoch>,
+    expires_at_epoch: Option<Epoch>,
     signature: Option<InstructionSignature>,
     sender_public_key: Option<RistrettoPublicKey>,
+    co_signatures: Vec<TransactionSignature>,
     new_non_fungible_outputs: Vec<(ResourceAddress, u8)>,
     new_non_fungible_index_outputs: Vec<(ResourceAddress, u64)>,
 }
@@ -35,8 +51,11 @@ impl TransactionBuilder {
             instructions: Vec::new(),
             signature: None,
             sender_public_key: None,
+            co_signatures: vec![],
             fee: 0,
             meta: TransactionMeta::default(),
+            min_epoch: None,
+            expires_at_epoch: None,
             new_non_fungible_outputs: vec![],
             new_non_fungible_index_outputs: vec![],
         }
@@ -44,20 +63,45 @@ impl TransactionBuilder {
 
     pub fn with_fee(&mut self, fee: u64) -> &mut Self {
         self.fee = fee;
+        self.reset_signatures();
         self
     }
 
+    /// Adds an instruction that locks up to `max_fee` from the account to pay the transaction fee. Fee instructions
+    /// are executed before all other instructions and the fee is charged even if the transaction fails.
+    pub fn with_fee_account(&mut self, account: ComponentAddress, max_fee: Amount) -> &mut Self {
+        self.fee = self
+            .fee
+            .saturating_add(u64::try_from(max_fee.value()).unwrap_or_default());
+        self.add_instruction(Instruction::PayFee {
+            component_address: account,
+            max_fee,
+        })
+    }
+
     pub fn add_instruction(&mut self, instruction: Instruction) -> &mut Self {
         self.instructions.push(instruction);
-        // Reset the signature as it is no longer valid
-        self.signature = None;
+        self.reset_signatures();
         self
     }
 
     pub fn with_instructions(&mut self, instructions: Vec<Instruction>) -> &mut Self {
         self.instructions.extend(instructions);
-        // Reset the signature as it is no longer valid
-        self.signature = None;
+        self.reset_signatures();
+        self
+    }
+
+    /// The transaction is not valid before this epoch
+    pub fn with_min_epoch(&mut self, min_epoch: Epoch) -> &mut Self {
+        self.min_epoch = Some(min_epoch);
+        self.reset_signatures();
+        self
+    }
+
+    /// The transaction is not valid in or after this epoch, so it cannot be held back and submitted later
+    pub fn with_expires_at_epoch(&mut self, expires_at_epoch: Epoch) -> &mut Self {
+        self.expires_at_epoch = Some(expires_at_epoch);
+        self.reset_signatures();
         self
     }
 
@@ -71,17 +115,57 @@ impl TransactionBuilder {
         self
     }
 
+    /// Signs the transaction as the sender. Any co-signatures are removed, because the output shards that the signers
+    /// sign over are derived from the sender's signature nonce.
     pub fn sign(&mut self, secret_key: &PrivateKey) -> &mut Self {
-        self.signature = Some(InstructionSignature::sign(secret_key, &self.instructions));
-        self.sender_public_key = Some(PublicKey::from_secret_key(secret_key));
+        let sender_public_key = PublicKey::from_secret_key(secret_key);
+        let (secret_nonce, public_nonce) = PublicKey::random_keypair(&mut OsRng);
+        let meta = self.derive_meta(Transaction::calculate_id_seed(&sender_public_key, &public_nonce));
+        let signature = InstructionSignature::sign_with_nonce(secret_key, secret_nonce, &self.body(meta));
+        self.signature = Some(signature);
+        self.sender_public_key = Some(sender_public_key);
+        self.co_signatures.clear();
         self
     }
 
+    /// Adds a signature by a signer other than the sender. Co-signers sign the same body as the sender, so the sender
+    /// signs first and the co-signers sign in any order after that.
+    ///
+    /// Panics if the transaction has not been signed by the sender.
+    pub fn co_sign(&mut self, secret_key: &PrivateKey) -> &mut Self {
+        let meta = self.signed_meta().expect("not signed by the sender");
+        let co_signature = TransactionSignature::sign(secret_key, &self.body(meta));
+        self.with_co_signature(co_signature)
+    }
+
+    pub fn with_co_signature(&mut self, co_signature: TransactionSignature) -> &mut Self {
+        // Replace any previous signature by the same signer
+        self.co_signatures
+            .retain(|s| s.public_key() != co_signature.public_key());
+        self.co_signatures.push(co_signature);
+        self
+    }
+
+    /// Returns true if the transaction has been signed by the sender and can be built
+    pub fn is_signed(&self) -> bool {
+        self.signature.is_some() && self.sender_public_key.is_some()
+    }
+
+    /// Returns the public keys of the signers that have signed the transaction so far
+    pub fn signer_public_keys(&self) -> impl Iterator<Item = &RistrettoPublicKey> + '_ {
+        self.signature
+            .as_ref()
+            .and(self.sender_public_key.as_ref())
+            .into_iter()
+            .chain(self.co_signatures.iter().map(|s| s.public_key()))
+    }
+
     /// Add an input to be consumed
     pub fn add_input(&mut self, input_object: ShardId) -> &mut Self {
         self.meta
             .involved_objects_mut()
             .insert(input_object, (SubstateChange::Destroy, ObjectClaim {}));
+        self.reset_signatures();
         self
     }
 
@@ -103,16 +187,19 @@ impl TransactionBuilder {
         self.meta
             .involved_objects_mut()
             .insert(output_object, (SubstateChange::Create, ObjectClaim {}));
+        self.reset_signatures();
         self
     }
 
     pub fn with_new_outputs(&mut self, num_outputs: u8) -> &mut Self {
         self.meta.set_max_outputs(num_outputs.into());
+        self.reset_signatures();
         self
     }
 
     pub fn with_new_non_fungible_outputs(&mut self, new_non_fungible_outputs: Vec<(ResourceAddress, u8)>) -> &mut Self {
         self.new_non_fungible_outputs = new_non_fungible_outputs;
+        self.reset_signatures();
         self
     }
 
@@ -121,46 +208,128 @@ impl TransactionBuilder {
         new_non_fungible_index_outputs: Vec<(ResourceAddress, u64)>,
     ) -> &mut Self {
         self.new_non_fungible_index_outputs = new_non_fungible_index_outputs;
+        self.reset_signatures();
         self
     }
 
-    pub fn build(mut self) -> Transaction {
-        let mut transaction = Transaction::new(
-            self.fee,
-            self.instructions.drain(..).collect(),
-            self.signature.take().expect("not signed"),
-            self.sender_public_key.take().expect("not signed"),
-            self.meta,
+    /// Replaces the inputs and outputs of the transaction with the substates that a dry run found it to require
+    pub fn with_substate_requirements(&mut self, requirements: &SubstateRequirements) -> &mut Self {
+        let involved_objects = self.meta.involved_objects_mut();
+        involved_objects.clear();
+        involved_objects.extend(
+            requirements
+                .read
+                .iter()
+                .map(|shard| (*shard, (SubstateChange::Exists, ObjectClaim {}))),
+        );
+        involved_objects.extend(
+            requirements
+                .written
+                .iter()
+                .map(|shard| (*shard, (SubstateChange::Destroy, ObjectClaim {}))),
         );
+        involved_objects.extend(
+            requirements
+                .outputs
+                .iter()
+                .map(|shard| (*shard, (SubstateChange::Create, ObjectClaim {}))),
+        );
+        self.meta.set_max_outputs(requirements.new_outputs);
+        self.new_non_fungible_outputs = requirements.new_non_fungible_outputs.clone();
+        // Non-fungible index addresses do not depend on the signature nonce, so they are included in the outputs
+        self.new_non_fungible_index_outputs.clear();
+        self.reset_signatures();
+        self
+    }
+
+    /// Builds the transaction without signatures so that it can be dry-run to discover the substates it requires. The
+    /// network rejects the transaction until it is signed.
+    ///
+    /// Panics if the sender public key has not been set.
+    pub fn build_unsigned(mut self) -> Transaction {
+        let sender_public_key = self.sender_public_key.take().expect("sender public key not set");
+        let signature = InstructionSignature::try_from(RistrettoSchnorr::new(
+            RistrettoPublicKey::default(),
+            RistrettoSecretKey::default(),
+        ))
+        .expect("infallible conversion");
+        let meta = self.derive_meta(Transaction::calculate_id_seed(
+            &sender_public_key,
+            signature.signature().get_public_nonce(),
+        ));
+        Transaction::new(self.into_body(meta), signature, sender_public_key, vec![])
+    }
+
+    pub fn build(mut self) -> Transaction {
+        let meta = self.signed_meta().expect("not signed");
+        let signature = self.signature.take().expect("not signed");
+        let sender_public_key = self.sender_public_key.take().expect("not signed");
+        let co_signatures = std::mem::take(&mut self.co_signatures);
+        Transaction::new(self.into_body(meta), signature, sender_public_key, co_signatures)
+    }
 
-        let max_outputs = transaction.meta().max_outputs();
+    /// Resets the signatures as they are no longer valid
+    fn reset_signatures(&mut self) {
+        self.signature = None;
+        self.co_signatures.clear();
+    }
+
+    fn body(&self, meta: TransactionMeta) -> TransactionBody {
+        TransactionBody {
+            fee: self.fee,
+            instructions: self.instructions.clone(),
+            meta,
+            min_epoch: self.min_epoch,
+            expires_at_epoch: self.expires_at_epoch,
+        }
+    }
+
+    fn into_body(self, meta: TransactionMeta) -> TransactionBody {
+        TransactionBody {
+            fee: self.fee,
+            instructions: self.instructions,
+            meta,
+            min_epoch: self.min_epoch,
+            expires_at_epoch: self.expires_at_epoch,
+        }
+    }
+
+    /// Returns the metadata that the signers sign over, if the sender has signed
+    fn signed_meta(&self) -> Option<TransactionMeta> {
+        let signature = self.signature.as_ref()?.signature();
+        let sender_public_key = self.sender_public_key.as_ref()?;
+        let id_seed = Transaction::calculate_id_seed(sender_public_key, signature.get_public_nonce());
+        Some(self.derive_meta(id_seed))
+    }
+
+    /// Returns the metadata including the output shards of the new substates, which are derived from the ID seed
+    fn derive_meta(&self, id_seed: Hash) -> TransactionMeta {
+        let mut meta = self.meta.clone();
+        let max_outputs = meta.max_outputs();
         let total_new_nft_outputs = self
             .new_non_fungible_outputs
             .iter()
             .map(|(_, count)| u32::from(*count))
             .sum::<u32>();
-        let id_provider = IdProvider::new(*transaction.hash(), max_outputs + total_new_nft_outputs);
+        let id_provider = IdProvider::new(id_seed, max_outputs + total_new_nft_outputs);
 
-        transaction
-            .meta_mut()
-            .involved_objects_mut()
-            .extend((0..max_outputs).map(|_| {
-                let new_hash = id_provider
-                    .new_address_hash()
-                    .expect("id provider provides num_outputs IDs");
-                (
-                    ShardId::from_hash(&new_hash, 0),
-                    (SubstateChange::Create, ObjectClaim {}),
-                )
-            }));
+        meta.involved_objects_mut().extend((0..max_outputs).map(|_| {
+            let new_hash = id_provider
+                .new_address_hash()
+                .expect("id provider provides num_outputs IDs");
+            (
+                ShardId::from_hash(&new_hash, 0),
+                (SubstateChange::Create, ObjectClaim {}),
+            )
+        }));
 
         let mut new_nft_outputs =
             Vec::with_capacity(usize::try_from(total_new_nft_outputs).expect("too many new NFT outputs"));
-        for (resource_addr, count) in self.new_non_fungible_outputs {
-            new_nft_outputs.extend((0..count).map({
+        for (resource_addr, count) in &self.new_non_fungible_outputs {
+            new_nft_outputs.extend((0..*count).map({
                 |_| {
                     let new_hash = id_provider.new_uuid().expect("id provider provides num_outputs IDs");
-                    let address = NonFungibleAddress::new(resource_addr, NonFungibleId::from_u256(new_hash));
+                    let address = NonFungibleAddress::new(*resource_addr, NonFungibleId::from_u256(new_hash));
                     let new_addr = SubstateAddress::NonFungible(address);
                     (
                         ShardId::from_hash(&new_addr.to_canonical_hash(), 0),
@@ -170,7 +339,7 @@ impl TransactionBuilder {
             }));
         }
 
-        transaction.meta_mut().involved_objects_mut().extend(new_nft_outputs);
+        meta.involved_objects_mut().extend(new_nft_outputs);
 
         // add the involved objects for NFT indexes
         let new_nft_index_outputs: Vec<(ShardId, (SubstateChange, ObjectClaim))> = self
@@ -184,11 +353,103 @@ impl TransactionBuilder {
                 (shard_id, (SubstateChange::Create, ObjectClaim {}))
             })
             .collect();
-        transaction
-            .meta_mut()
-            .involved_objects_mut()
-            .extend(new_nft_index_outputs);
+        meta.involved_objects_mut().extend(new_nft_index_outputs);
+
+        meta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tari_template_lib::models::TemplateAddress;
+
+    use super::*;
+
+    fn create_builder() -> TransactionBuilder {
+        let mut builder = TransactionBuilder::new();
+        builder.add_instruction(Instruction::CallFunction {
+            template_address: TemplateAddress::from_array([1u8; 32]),
+            function: "new".to_string(),
+            args: vec![],
+        });
+        builder
+    }
+
+    #[test]
+    fn it_builds_a_co_signed_transaction() {
+        let (sender_secret, sender_public) = PublicKey::random_keypair(&mut OsRng);
+        let (co_signer_secret, co_signer_public) = PublicKey::random_keypair(&mut OsRng);
+
+        let mut builder = create_builder();
+        builder
+            .with_new_outputs(1)
+            .sign(&sender_secret)
+            .co_sign(&co_signer_secret);
+        let transaction = builder.build();
+
+        assert!(transaction
+            .signature()
+            .verify(transaction.sender_public_key(), transaction.body()));
+        assert!(transaction.co_signatures()[0].verify(transaction.body()));
+        assert_eq!(transaction.signer_public_keys().collect::<Vec<_>>(), vec![
+            &sender_public,
+            &co_signer_public
+        ]);
+    }
+
+    #[test]
+    fn it_resets_signatures_when_the_body_changes() {
+        let (sender_secret, _) = PublicKey::random_keypair(&mut OsRng);
+        let (co_signer_secret, _) = PublicKey::random_keypair(&mut OsRng);
+        let mut builder = create_builder();
+        builder.sign(&sender_secret).co_sign(&co_signer_secret);
+        builder.with_fee(100);
+        assert!(!builder.is_signed());
+        assert_eq!(builder.signer_public_keys().count(), 0);
+
+        builder.sign(&sender_secret).co_sign(&co_signer_secret);
+        builder.with_expires_at_epoch(Epoch(10));
+        assert!(!builder.is_signed());
+        assert_eq!(builder.signer_public_keys().count(), 0);
+    }
 
-        transaction
+    #[test]
+    fn it_replaces_the_inputs_and_outputs_with_the_substate_requirements() {
+        let (sender_secret, sender_public) = PublicKey::random_keypair(&mut OsRng);
+        let requirements = SubstateRequirements {
+            read: vec![ShardId([1u8; 32])],
+            written: vec![ShardId([2u8; 32])],
+            outputs: vec![ShardId([3u8; 32])],
+            new_outputs: 2,
+            new_non_fungible_outputs: vec![],
+        };
+
+        let mut builder = create_builder();
+        builder
+            .add_input(ShardId([4u8; 32]))
+            .with_sender_public_key(sender_public)
+            .with_substate_requirements(&requirements);
+        let unsigned = builder.clone().build_unsigned();
+        builder.sign(&sender_secret);
+        let transaction = builder.build();
+
+        for transaction in [unsigned, transaction] {
+            let meta = transaction.meta();
+            assert_eq!(meta.involved_shards().len(), 5);
+            assert_eq!(meta.max_outputs(), 2);
+            assert!(meta.objects_for_shard(ShardId([4u8; 32])).is_none());
+            assert_eq!(
+                meta.objects_for_shard(ShardId([1u8; 32])).unwrap().0,
+                SubstateChange::Exists
+            );
+            assert_eq!(
+                meta.objects_for_shard(ShardId([2u8; 32])).unwrap().0,
+                SubstateChange::Destroy
+            );
+            assert_eq!(
+                meta.objects_for_shard(ShardId([3u8; 32])).unwrap().0,
+                SubstateChange::Create
+            );
+        }
     }
 }
@@ -5,17 +5,20 @@ use std::sync::{atomic::AtomicU32, Arc};
 
 use tari_engine_types::hashing::{hasher, EngineHashDomainLabel};
 use tari_template_lib::{
-    models::{BucketId, ComponentAddress, ResourceAddress, VaultId},
+    models::{BucketId, ComponentAddress, KeyValueStoreId, ProofId, ResourceAddress, VaultId},
     Hash,
 };
 
 #[derive(Debug, Clone)]
 pub struct IdProvider {
-    transaction_hash: Hash,
+    id_seed: Hash,
     max_ids: u32,
     current_id: Arc<AtomicU32>,
     bucket_id: Arc<AtomicU32>,
+    proof_id: Arc<AtomicU32>,
     uuid: Arc<AtomicU32>,
+    random: Arc<AtomicU32>,
+    key_value_store_id: Arc<AtomicU32>,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -25,14 +28,17 @@ pub struct MaxIdsExceeded {
 }
 
 impl IdProvider {
-    pub fn new(transaction_hash: Hash, max_ids: u32) -> Self {
+    pub fn new(id_seed: Hash, max_ids: u32) -> Self {
         Self {
-            transaction_hash,
+            id_seed,
             max_ids,
             // TODO: these should be ranges
             current_id: Arc::new(AtomicU32::new(0)),
             bucket_id: Arc::new(AtomicU32::new(1000)),
+            proof_id: Arc::new(AtomicU32::new(1000)),
             uuid: Arc::new(AtomicU32::new(0)),
+            random: Arc::new(AtomicU32::new(0)),
+            key_value_store_id: Arc::new(AtomicU32::new(0)),
         }
     }
 
@@ -44,14 +50,10 @@ impl IdProvider {
         Ok(id)
     }
 
-    pub fn transaction_hash(&self) -> Hash {
-        self.transaction_hash
-    }
-
-    /// Generates a new unique id H(tx_hash || n).
+    /// Generates a new unique id H(id_seed || n).
     /// NOTE: we rely on IDs being predictable for all outputs (components, resources, vaults).
     fn new_id(&self) -> Result<Hash, MaxIdsExceeded> {
-        let id = generate_output_id(&self.transaction_hash, self.next()?);
+        let id = generate_output_id(&self.id_seed, self.next()?);
         Ok(id)
     }
 
@@ -76,14 +78,45 @@ impl IdProvider {
         self.bucket_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
     }
 
+    pub fn new_proof_id(&self) -> ProofId {
+        // Proofs only exist for the duration of the transaction and are never saved to shards
+        self.proof_id.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    }
+
+    pub fn new_key_value_store_id(&self) -> KeyValueStoreId {
+        // Key-value stores are not substates themselves (only their entries are), so they do not use an output ID
+        let n = self
+            .key_value_store_id
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        hasher(EngineHashDomainLabel::KeyValueStoreId)
+            .chain(&self.id_seed)
+            .chain(&n)
+            .result()
+            .into()
+    }
+
     pub fn new_uuid(&self) -> Result<[u8; 32], MaxIdsExceeded> {
         let n = self.uuid.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
         let id = hasher(EngineHashDomainLabel::UuidOutput)
-            .chain(&self.transaction_hash)
+            .chain(&self.id_seed)
             .chain(&n)
             .result();
         Ok(id.into_array())
     }
+
+    /// Generates random bytes H(beacon || id_seed || n). The sender chooses the ID seed, and the beacon of the current
+    /// epoch is public, so the sender can compute the bytes before submitting a transaction that executes in the
+    /// current epoch, and only submit it if they are favourable. The bytes must not be relied on where the sender
+    /// benefits from choosing the outcome.
+    pub fn new_random_bytes(&self, beacon: &[u8; 32]) -> [u8; 32] {
+        let n = self.random.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        hasher(EngineHashDomainLabel::RandomBytes)
+            .chain(beacon)
+            .chain(&self.id_seed)
+            .chain(&n)
+            .result()
+            .into_array()
+    }
 }
 
 fn generate_output_id(hash: &Hash, n: u32) -> Hash {
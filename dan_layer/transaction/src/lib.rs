@@ -25,10 +25,12 @@ mod change;
 pub mod id_provider;
 mod object_claim;
 mod signature;
+mod substate_requirements;
 mod transaction;
 
 pub use builder::TransactionBuilder;
 pub use change::SubstateChange;
 pub use object_claim::ObjectClaim;
-pub use signature::InstructionSignature;
-pub use transaction::{Transaction, TransactionMeta};
+pub use signature::{InstructionSignature, TransactionSignature, TRANSACTION_SIGNATURE_VERSION};
+pub use substate_requirements::SubstateRequirements;
+pub use transaction::{Transaction, TransactionBody, TransactionMeta};
@@ -9,33 +9,104 @@ use tari_crypto::{
     keys::PublicKey as PublicKeyT,
     ristretto::{RistrettoPublicKey, RistrettoSchnorr, RistrettoSecretKey},
 };
-use tari_engine_types::{
-    hashing::{hasher, EngineHashDomainLabel},
-    instruction::Instruction,
-};
+use tari_engine_types::hashing::{hasher, EngineHashDomainLabel};
+use tari_template_lib::Hash;
+
+use crate::{SubstateChange, TransactionBody};
+
+/// The version of the signing challenge. This must be incremented whenever the fields covered by the challenge change.
+pub const TRANSACTION_SIGNATURE_VERSION: u8 = 1;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct InstructionSignature(RistrettoSchnorr);
 
 impl InstructionSignature {
-    pub fn sign(secret_key: &RistrettoSecretKey, instructions: &[Instruction]) -> Self {
+    pub fn sign(secret_key: &RistrettoSecretKey, body: &TransactionBody) -> Self {
         let (secret_nonce, _nonce_pk) = RistrettoPublicKey::random_keypair(&mut OsRng);
+        Self::sign_with_nonce(secret_key, secret_nonce, body)
+    }
+
+    /// Signs the body with the given secret nonce. The nonce must be random and must never be reused.
+    pub fn sign_with_nonce(
+        secret_key: &RistrettoSecretKey,
+        secret_nonce: RistrettoSecretKey,
+        body: &TransactionBody,
+    ) -> Self {
         let public_key = RistrettoPublicKey::from_secret_key(secret_key);
         let nonce_pk = RistrettoPublicKey::from_secret_key(&secret_nonce);
-        // TODO: implement dan encoding for (a wrapper of) PublicKey
-        let challenge = hasher(EngineHashDomainLabel::InstructionSignature)
-            .chain(&nonce_pk)
-            .chain(&public_key)
-            .chain(instructions)
-            .result();
+        let challenge = Self::challenge(&nonce_pk, &public_key, body);
         Self(RistrettoSchnorr::sign_raw(secret_key, secret_nonce, &challenge).unwrap())
     }
 
+    /// Returns true if this is a valid signature by `public_key` over the transaction body
+    pub fn verify(&self, public_key: &RistrettoPublicKey, body: &TransactionBody) -> bool {
+        let challenge = Self::challenge(self.0.get_public_nonce(), public_key, body);
+        self.0.verify_challenge(public_key, &challenge)
+    }
+
+    fn challenge(nonce_pk: &RistrettoPublicKey, public_key: &RistrettoPublicKey, body: &TransactionBody) -> Hash {
+        let mut hasher = hasher(EngineHashDomainLabel::TransactionSignature)
+            .chain(&TRANSACTION_SIGNATURE_VERSION)
+            .chain(nonce_pk)
+            .chain(public_key)
+            .chain(&body.fee)
+            .chain(&body.instructions)
+            .chain(&body.meta.max_outputs())
+            .chain(&body.min_epoch)
+            .chain(&body.expires_at_epoch);
+        // Involved objects are hashed in shard order so that the challenge does not depend on the map order
+        let mut involved_objects = body.meta.involved_objects_iter().collect::<Vec<_>>();
+        involved_objects.sort_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+        for (shard_id, (change, _)) in involved_objects {
+            let change: u8 = match change {
+                SubstateChange::Create => 0,
+                SubstateChange::Exists => 1,
+                SubstateChange::Destroy => 2,
+            };
+            hasher.update(shard_id);
+            hasher.update(&change);
+        }
+        hasher.result()
+    }
+
     pub fn signature(&self) -> RistrettoSchnorr {
         self.0.clone()
     }
 }
 
+/// A signature over the transaction body together with the public key of the signer
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TransactionSignature {
+    public_key: RistrettoPublicKey,
+    signature: InstructionSignature,
+}
+
+impl TransactionSignature {
+    pub fn new(public_key: RistrettoPublicKey, signature: InstructionSignature) -> Self {
+        Self { public_key, signature }
+    }
+
+    pub fn sign(secret_key: &RistrettoSecretKey, body: &TransactionBody) -> Self {
+        Self {
+            public_key: RistrettoPublicKey::from_secret_key(secret_key),
+            signature: InstructionSignature::sign(secret_key, body),
+        }
+    }
+
+    /// Returns true if this is a valid signature by the signer over the transaction body
+    pub fn verify(&self, body: &TransactionBody) -> bool {
+        self.signature.verify(&self.public_key, body)
+    }
+
+    pub fn public_key(&self) -> &RistrettoPublicKey {
+        &self.public_key
+    }
+
+    pub fn signature(&self) -> &InstructionSignature {
+        &self.signature
+    }
+}
+
 impl TryFrom<RistrettoSchnorr> for InstructionSignature {
     type Error = String;
 
@@ -43,3 +114,96 @@ impl TryFrom<RistrettoSchnorr> for InstructionSignature {
         Ok(InstructionSignature(sig))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tari_dan_common_types::{Epoch, ShardId};
+    use tari_engine_types::instruction::Instruction;
+    use tari_template_lib::models::TemplateAddress;
+
+    use super::*;
+    use crate::{TransactionBuilder, TransactionMeta};
+
+    fn create_body(function: &str) -> TransactionBody {
+        TransactionBody {
+            fee: 10,
+            instructions: vec![Instruction::CallFunction {
+                template_address: TemplateAddress::from_array([1u8; 32]),
+                function: function.to_string(),
+                args: vec![],
+            }],
+            meta: TransactionMeta::default(),
+            min_epoch: None,
+            expires_at_epoch: Some(Epoch(10)),
+        }
+    }
+
+    #[test]
+    fn it_verifies_a_valid_signature() {
+        let (secret_key, public_key) = RistrettoPublicKey::random_keypair(&mut OsRng);
+        let body = create_body("new");
+        let signature = InstructionSignature::sign(&secret_key, &body);
+        assert!(signature.verify(&public_key, &body));
+    }
+
+    #[test]
+    fn it_rejects_a_signature_over_other_instructions() {
+        let (secret_key, public_key) = RistrettoPublicKey::random_keypair(&mut OsRng);
+        let signature = InstructionSignature::sign(&secret_key, &create_body("new"));
+        assert!(!signature.verify(&public_key, &create_body("mint")));
+    }
+
+    #[test]
+    fn it_rejects_a_signature_over_another_fee_or_validity_window() {
+        let (secret_key, public_key) = RistrettoPublicKey::random_keypair(&mut OsRng);
+        let body = create_body("new");
+        let signature = InstructionSignature::sign(&secret_key, &body);
+
+        assert!(!signature.verify(&public_key, &TransactionBody { fee: 1, ..body.clone() }));
+        assert!(!signature.verify(&public_key, &TransactionBody {
+            expires_at_epoch: Some(Epoch(11)),
+            ..body.clone()
+        }));
+        assert!(!signature.verify(&public_key, &TransactionBody {
+            min_epoch: Some(Epoch(1)),
+            ..body
+        }));
+    }
+
+    #[test]
+    fn it_rejects_a_signature_over_other_metadata() {
+        let (secret_key, _) = RistrettoPublicKey::random_keypair(&mut OsRng);
+        let mut builder = TransactionBuilder::new();
+        builder
+            .with_instructions(create_body("new").instructions)
+            .add_input(ShardId::zero())
+            .sign(&secret_key);
+        let transaction = builder.build();
+        assert!(transaction
+            .signature()
+            .verify(transaction.sender_public_key(), transaction.body()));
+
+        let mut body = transaction.body().clone();
+        body.meta
+            .involved_objects_mut()
+            .insert(ShardId([1u8; 32]), (SubstateChange::Destroy, crate::ObjectClaim {}));
+        assert!(!transaction.signature().verify(transaction.sender_public_key(), &body));
+    }
+
+    #[test]
+    fn it_verifies_a_transaction_signature() {
+        let (secret_key, _) = RistrettoPublicKey::random_keypair(&mut OsRng);
+        let signature = TransactionSignature::sign(&secret_key, &create_body("new"));
+        assert!(signature.verify(&create_body("new")));
+        assert!(!signature.verify(&create_body("mint")));
+    }
+
+    #[test]
+    fn it_rejects_a_signature_by_another_key() {
+        let (secret_key, _) = RistrettoPublicKey::random_keypair(&mut OsRng);
+        let (_, other_public_key) = RistrettoPublicKey::random_keypair(&mut OsRng);
+        let body = create_body("new");
+        let signature = InstructionSignature::sign(&secret_key, &body);
+        assert!(!signature.verify(&other_public_key, &body));
+    }
+}
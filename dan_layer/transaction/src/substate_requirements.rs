@@ -0,0 +1,24 @@
+//   Copyright 2023 The Tari Project
+//   SPDX-License-Identifier: BSD-3-Clause
+
+use serde::{Deserialize, Serialize};
+use tari_dan_common_types::ShardId;
+use tari_template_lib::models::ResourceAddress;
+
+/// The substates that a transaction requires, as discovered by dry-running it before it is signed. The addresses of
+/// the new components, resources, vaults and randomly identified non-fungibles are derived from the sender's signature
+/// nonce, so only the number of these outputs is known before signing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubstateRequirements {
+    /// Existing substates that are read but not changed
+    pub read: Vec<ShardId>,
+    /// Existing substates that are changed or destroyed
+    pub written: Vec<ShardId>,
+    /// New substates whose addresses do not depend on the signature nonce, including the next version of each written
+    /// substate
+    pub outputs: Vec<ShardId>,
+    /// The number of new components, resources and vaults
+    pub new_outputs: u32,
+    /// The number of non-fungibles minted with a random ID for each resource, in the order that they are minted
+    pub new_non_fungible_outputs: Vec<(ResourceAddress, u8)>,
+}
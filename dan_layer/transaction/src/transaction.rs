@@ -5,17 +5,17 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 use tari_common_types::types::PublicKey;
-use tari_dan_common_types::ShardId;
+use tari_dan_common_types::{Epoch, ShardId};
 use tari_engine_types::{
     hashing::{hasher, EngineHashDomainLabel},
     instruction::Instruction,
 };
 use tari_template_lib::{
-    models::{ComponentAddress, TemplateAddress},
+    models::{Amount, ComponentAddress, TemplateAddress},
     Hash,
 };
 
-use crate::{change::SubstateChange, InstructionSignature, ObjectClaim, TransactionBuilder};
+use crate::{change::SubstateChange, InstructionSignature, ObjectClaim, TransactionBuilder, TransactionSignature};
 
 #[derive(Debug, Clone)]
 pub struct BalanceProof {}
@@ -23,12 +23,13 @@ pub struct BalanceProof {}
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct Transaction {
     hash: Hash,
-    instructions: Vec<Instruction>,
+    #[serde(flatten)]
+    body: TransactionBody,
     signature: InstructionSignature,
-    _fee: u64,
     sender_public_key: PublicKey,
-    // Not part of signature. TODO: Should it be?
-    meta: TransactionMeta,
+    /// Signatures by signers other than the sender, each of which adds the signer's public key badge to the auth scope
+    #[serde(default)]
+    co_signatures: Vec<TransactionSignature>,
 }
 
 impl Transaction {
@@ -37,19 +38,17 @@ impl Transaction {
     }
 
     pub fn new(
-        fee: u64,
-        instructions: Vec<Instruction>,
+        body: TransactionBody,
         signature: InstructionSignature,
         sender_public_key: PublicKey,
-        meta: TransactionMeta,
+        co_signatures: Vec<TransactionSignature>,
     ) -> Self {
         let mut s = Self {
             hash: Hash::default(),
-            instructions,
+            body,
             signature,
-            _fee: fee,
             sender_public_key,
-            meta,
+            co_signatures,
         };
         s.hash = s.calculate_hash();
         s
@@ -58,7 +57,8 @@ impl Transaction {
     /// Returns the template addresses that are statically known to be executed by this transaction.
     /// This does not include templates for component invocation as that data is not contained within the transaction.
     pub fn required_templates(&self) -> Vec<TemplateAddress> {
-        self.instructions
+        self.body
+            .instructions
             .iter()
             .filter_map(|instruction| match instruction {
                 Instruction::CallFunction {
@@ -71,10 +71,13 @@ impl Transaction {
     }
 
     pub fn required_components(&self) -> Vec<ComponentAddress> {
-        self.instructions
+        self.body
+            .instructions
             .iter()
             .filter_map(|instruction| match instruction {
-                Instruction::CallMethod { component_address, .. } => Some(*component_address),
+                Instruction::CallMethod { component_address, .. } | Instruction::PayFee { component_address, .. } => {
+                    Some(*component_address)
+                },
                 _ => None,
             })
             .collect()
@@ -84,33 +87,84 @@ impl Transaction {
         &self.hash
     }
 
+    /// The maximum fee that the sender is willing to pay for this transaction
     pub fn fee(&self) -> u64 {
-        self._fee
+        self.body.fee
+    }
+
+    /// The total fee locked by the fee instructions of this transaction. Unlike [Self::fee], which is only declared,
+    /// this is the amount that the fees charged for executing the transaction are paid from.
+    pub fn max_fee_payment(&self) -> Amount {
+        self.body
+            .instructions
+            .iter()
+            .filter_map(|instruction| match instruction {
+                Instruction::PayFee { max_fee, .. } if max_fee.is_positive() => Some(*max_fee),
+                _ => None,
+            })
+            .fold(Amount::zero(), |total, max_fee| total.saturating_add(&max_fee))
     }
 
     pub fn meta(&self) -> &TransactionMeta {
-        &self.meta
+        &self.body.meta
+    }
+
+    pub fn min_epoch(&self) -> Option<Epoch> {
+        self.body.min_epoch
+    }
+
+    pub fn expires_at_epoch(&self) -> Option<Epoch> {
+        self.body.expires_at_epoch
+    }
+
+    /// Returns true if the transaction may be executed in the given epoch
+    pub fn is_valid_in_epoch(&self, epoch: Epoch) -> bool {
+        self.body.min_epoch.map_or(true, |min_epoch| epoch >= min_epoch) &&
+            self.body
+                .expires_at_epoch
+                .map_or(true, |expires_at_epoch| epoch < expires_at_epoch)
+    }
+
+    /// Returns the fields of the transaction that are covered by the signatures
+    pub fn body(&self) -> &TransactionBody {
+        &self.body
     }
 
-    pub fn meta_mut(&mut self) -> &mut TransactionMeta {
-        &mut self.meta
+    /// Returns the seed for the IDs of the substates created by the transaction. The seed is derived from the sender's
+    /// signature nonce, which is chosen before the body is signed, so that the signed metadata can include the output
+    /// shards of the new substates.
+    pub fn id_seed(&self) -> Hash {
+        Self::calculate_id_seed(&self.sender_public_key, self.signature.signature().get_public_nonce())
+    }
+
+    pub(crate) fn calculate_id_seed(sender_public_key: &PublicKey, public_nonce: &PublicKey) -> Hash {
+        hasher(EngineHashDomainLabel::TransactionIdSeed)
+            .chain(sender_public_key)
+            .chain(public_nonce)
+            .result()
     }
 
     fn calculate_hash(&self) -> Hash {
-        hasher(EngineHashDomainLabel::Transaction)
+        let mut hasher = hasher(EngineHashDomainLabel::Transaction)
             .chain(&self.sender_public_key)
             .chain(self.signature.signature().get_public_nonce())
             .chain(self.signature.signature().get_signature())
-            .chain(&self.instructions)
-            .result()
+            .chain(&self.body.instructions);
+        for co_signature in &self.co_signatures {
+            let signature = co_signature.signature().signature();
+            hasher.update(co_signature.public_key());
+            hasher.update(signature.get_public_nonce());
+            hasher.update(signature.get_signature());
+        }
+        hasher.result()
     }
 
     pub fn instructions(&self) -> &[Instruction] {
-        &self.instructions
+        &self.body.instructions
     }
 
     pub fn into_instructions(self) -> Vec<Instruction> {
-        self.instructions
+        self.body.instructions
     }
 
     pub fn signature(&self) -> &InstructionSignature {
@@ -121,9 +175,48 @@ impl Transaction {
         &self.sender_public_key
     }
 
-    pub fn destruct(self) -> (Vec<Instruction>, InstructionSignature, PublicKey) {
-        (self.instructions, self.signature, self.sender_public_key)
+    pub fn co_signatures(&self) -> &[TransactionSignature] {
+        &self.co_signatures
+    }
+
+    /// Returns true if the sender and every co-signer signed the body of this transaction
+    pub fn verify_all_signatures(&self) -> bool {
+        self.signature.verify(&self.sender_public_key, &self.body) &&
+            self.co_signatures
+                .iter()
+                .all(|co_signature| co_signature.verify(&self.body))
     }
+
+    /// Returns the public keys of every signer of this transaction, starting with the sender
+    pub fn signer_public_keys(&self) -> impl Iterator<Item = &PublicKey> + '_ {
+        std::iter::once(&self.sender_public_key).chain(self.co_signatures.iter().map(|s| s.public_key()))
+    }
+
+    pub fn destruct(
+        self,
+    ) -> (
+        TransactionBody,
+        InstructionSignature,
+        PublicKey,
+        Vec<TransactionSignature>,
+    ) {
+        (self.body, self.signature, self.sender_public_key, self.co_signatures)
+    }
+}
+
+/// The fields of a transaction that are covered by the signatures of its signers
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct TransactionBody {
+    /// The maximum fee that the sender is willing to pay for the transaction
+    pub fee: u64,
+    pub instructions: Vec<Instruction>,
+    pub meta: TransactionMeta,
+    /// The transaction is not valid before this epoch
+    #[serde(default)]
+    pub min_epoch: Option<Epoch>,
+    /// The transaction is not valid in or after this epoch
+    #[serde(default)]
+    pub expires_at_epoch: Option<Epoch>,
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize, Eq, PartialEq)]
@@ -4,15 +4,20 @@
 use std::collections::HashMap;
 
 use log::*;
-use tari_common_types::types::FixedHash;
+use tari_common_types::types::{FixedHash, PublicKey};
 use tari_dan_common_types::optional::{IsNotFoundError, Optional};
 use tari_engine_types::{
     substate::{SubstateAddress, SubstateDiff},
     TemplateAddress,
 };
-use tari_transaction::Transaction;
+use tari_transaction::{SubstateRequirements, Transaction, TransactionBuilder};
 use tari_validator_node_client::{
-    types::{GetTransactionQcsRequest, GetTransactionResultRequest, SubmitTransactionRequest},
+    types::{
+        DiscoverTransactionSubstatesRequest,
+        GetTransactionQcsRequest,
+        GetTransactionResultRequest,
+        SubmitTransactionRequest,
+    },
     ValidatorNodeClient,
     ValidatorNodeClientError,
 };
@@ -43,6 +48,36 @@ impl<'a, TStore: WalletStore> TransactionApi<'a, TStore> {
         Ok(transaction)
     }
 
+    /// Dry-runs the unsigned transaction on the validator node and returns the substates that it requires. The inputs
+    /// of the builder must include every substate that the transaction could read.
+    pub async fn discover_substates(
+        &self,
+        builder: &TransactionBuilder,
+        sender_public_key: PublicKey,
+    ) -> Result<SubstateRequirements, TransactionApiError> {
+        let mut unsigned = builder.clone();
+        unsigned.with_sender_public_key(sender_public_key);
+        let mut client = self.get_validator_node_client()?;
+        let resp = client
+            .discover_transaction_substates(DiscoverTransactionSubstatesRequest {
+                transaction: unsigned.build_unsigned(),
+            })
+            .await?;
+        Ok(resp.requirements)
+    }
+
+    /// Replaces the inputs and outputs of the builder with the substates that the transaction requires, so that it
+    /// can be signed. See [`TransactionApi::discover_substates`].
+    pub async fn fill_substate_requirements(
+        &self,
+        builder: &mut TransactionBuilder,
+        sender_public_key: PublicKey,
+    ) -> Result<SubstateRequirements, TransactionApiError> {
+        let requirements = self.discover_substates(builder, sender_public_key).await?;
+        builder.with_substate_requirements(&requirements);
+        Ok(requirements)
+    }
+
     pub async fn submit_to_vn(&self, transaction: Transaction) -> Result<FixedHash, TransactionApiError> {
         self.submit_to_vn_internal(transaction, false).await
     }
@@ -219,7 +254,8 @@ impl<'a, TStore: WalletStore> TransactionApi<'a, TStore> {
                 addr @ SubstateAddress::Resource(_) |
                 addr @ SubstateAddress::Vault(_) |
                 addr @ SubstateAddress::NonFungible(_) |
-                addr @ SubstateAddress::NonFungibleIndex(_) => {
+                addr @ SubstateAddress::NonFungibleIndex(_) |
+                addr @ SubstateAddress::KeyValueEntry(_) => {
                     children.push(VersionedSubstateAddress {
                         address: addr.clone(),
                         version: substate.version(),
@@ -0,0 +1,2 @@
+ALTER TABLE transactions
+    ADD COLUMN co_signatures TEXT NOT NULL DEFAULT '[]';
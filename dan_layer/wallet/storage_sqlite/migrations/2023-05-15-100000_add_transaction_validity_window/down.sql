@@ -0,0 +1,2 @@
+ALTER TABLE transactions DROP COLUMN expires_at_epoch;
+ALTER TABLE transactions DROP COLUMN min_epoch;
@@ -0,0 +1,4 @@
+ALTER TABLE transactions
+    ADD COLUMN min_epoch BIGINT NULL;
+ALTER TABLE transactions
+    ADD COLUMN expires_at_epoch BIGINT NULL;
@@ -4,10 +4,12 @@
 use std::str::FromStr;
 
 use chrono::NaiveDateTime;
+use tari_dan_common_types::Epoch;
 use tari_dan_wallet_sdk::{
     models::{TransactionStatus, WalletTransaction},
     storage::WalletStorageError,
 };
+use tari_transaction::TransactionBody;
 use tari_utilities::hex::Hex;
 
 use crate::{schema::transactions, serialization::deserialize_json};
@@ -28,6 +30,9 @@ pub struct Transaction {
     pub is_dry_run: bool,
     pub updated_at: NaiveDateTime,
     pub created_at: NaiveDateTime,
+    pub co_signatures: String,
+    pub min_epoch: Option<i64>,
+    pub expires_at_epoch: Option<i64>,
 }
 
 impl Transaction {
@@ -41,11 +46,16 @@ impl Transaction {
 
         Ok(WalletTransaction {
             transaction: tari_transaction::Transaction::new(
-                self.fee as u64,
-                deserialize_json(&self.instructions)?,
+                TransactionBody {
+                    fee: self.fee as u64,
+                    instructions: deserialize_json(&self.instructions)?,
+                    meta: deserialize_json(&self.meta)?,
+                    min_epoch: self.min_epoch.map(|epoch| Epoch(epoch as u64)),
+                    expires_at_epoch: self.expires_at_epoch.map(|epoch| Epoch(epoch as u64)),
+                },
                 signature,
                 sender_address,
-                deserialize_json(&self.meta)?,
+                deserialize_json(&self.co_signatures)?,
             ),
             status: TransactionStatus::from_str(&self.status).map_err(|e| WalletStorageError::DecodingError {
                 operation: "transaction_get",
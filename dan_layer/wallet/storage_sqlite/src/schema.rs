@@ -90,6 +90,9 @@ diesel::table! {
         dry_run -> Bool,
         created_at -> Timestamp,
         updated_at -> Timestamp,
+        co_signatures -> Text,
+        min_epoch -> Nullable<BigInt>,
+        expires_at_epoch -> Nullable<BigInt>,
     }
 }
 
@@ -179,15 +179,18 @@ impl WalletStoreWriter for WriteTransaction<'_> {
         };
 
         sql_query(
-            "INSERT INTO transactions (hash, instructions, sender_address, fee, signature, meta, status, dry_run) \
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO transactions (hash, instructions, sender_address, fee, signature, co_signatures, meta, \
+             min_epoch, expires_at_epoch, status, dry_run) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind::<Text, _>(transaction.hash().to_string())
         .bind::<Text, _>(serialize_json(transaction.instructions())?)
         .bind::<Text, _>(transaction.sender_public_key().to_hex())
         .bind::<BigInt, _>(transaction.fee() as i64)
         .bind::<Text, _>(serialize_json(transaction.signature())?)
+        .bind::<Text, _>(serialize_json(transaction.co_signatures())?)
         .bind::<Text, _>(serialize_json(transaction.meta())?)
+        .bind::<Nullable<BigInt>, _>(transaction.min_epoch().map(|epoch| epoch.as_u64() as i64))
+        .bind::<Nullable<BigInt>, _>(transaction.expires_at_epoch().map(|epoch| epoch.as_u64() as i64))
         .bind::<Text, _>(status.as_key_str())
         .bind::<Bool, _>(is_dry_run)
         .execute(self.connection())